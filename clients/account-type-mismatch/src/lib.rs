@@ -0,0 +1,115 @@
+//! Client for the `account_type_mismatch` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`vulnerable_action`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableActionAccounts {
+        pub user_account: Pubkey,
+        pub caller: Pubkey,
+    }
+
+    pub const VULNERABLE_ACTION: [u8; 1] = [0];
+
+    pub fn vulnerable_action(
+        program_id: &Pubkey,
+        accounts: &VulnerableActionAccounts,
+    ) -> Instruction {
+        let data = VULNERABLE_ACTION.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user_account, false),
+                AccountMeta::new_readonly(accounts.caller, true),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_action`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureActionAccounts {
+        pub user_account: Pubkey,
+        pub caller: Pubkey,
+    }
+
+    pub const SECURE_ACTION: [u8; 1] = [1];
+
+    pub fn secure_action(program_id: &Pubkey, accounts: &SecureActionAccounts) -> Instruction {
+        let data = SECURE_ACTION.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user_account, false),
+                AccountMeta::new_readonly(accounts.caller, true),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`init_user`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitUserAccounts {
+        pub account: Pubkey,
+    }
+
+    /// Arguments for [`init_user`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct InitUserArgs {
+        pub pubkey: Pubkey,
+    }
+
+    pub const INIT_USER: [u8; 1] = [2];
+
+    pub fn init_user(
+        program_id: &Pubkey,
+        accounts: &InitUserAccounts,
+        args: &InitUserArgs,
+    ) -> Instruction {
+        let mut data = INIT_USER.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.account, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`init_admin`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitAdminAccounts {
+        pub account: Pubkey,
+    }
+
+    /// Arguments for [`init_admin`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct InitAdminArgs {
+        pub pubkey: Pubkey,
+    }
+
+    pub const INIT_ADMIN: [u8; 1] = [3];
+
+    pub fn init_admin(
+        program_id: &Pubkey,
+        accounts: &InitAdminAccounts,
+        args: &InitAdminArgs,
+    ) -> Instruction {
+        let mut data = INIT_ADMIN.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.account, false),
+            ],
+            data,
+        }
+    }
+}
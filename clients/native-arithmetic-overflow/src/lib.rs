@@ -0,0 +1,150 @@
+//! Client for the `native_arithmetic_overflow` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`initialize`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitializeAccounts {
+        pub authority: Pubkey,
+        pub pool: Pubkey,
+    }
+
+    /// Arguments for [`initialize`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct InitializeArgs {
+        pub initial_x: u64,
+        pub initial_y: u64,
+        pub fee_bps: u16,
+    }
+
+    pub const INITIALIZE: [u8; 1] = [0];
+
+    pub fn initialize(
+        program_id: &Pubkey,
+        accounts: &InitializeAccounts,
+        args: &InitializeArgs,
+    ) -> Instruction {
+        let mut data = INITIALIZE.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new(accounts.pool, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`vulnerable_swap`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableSwapAccounts {
+        pub user: Pubkey,
+        pub pool: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`vulnerable_swap`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct VulnerableSwapArgs {
+        pub amount_in: u64,
+        pub min_out: u64,
+    }
+
+    pub const VULNERABLE_SWAP: [u8; 1] = [1];
+
+    pub fn vulnerable_swap(
+        program_id: &Pubkey,
+        accounts: &VulnerableSwapAccounts,
+        args: &VulnerableSwapArgs,
+    ) -> Instruction {
+        let mut data = VULNERABLE_SWAP.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user, true),
+                AccountMeta::new(accounts.pool, false),
+                AccountMeta::new_readonly(accounts.cluster, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_swap`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureSwapAccounts {
+        pub user: Pubkey,
+        pub pool: Pubkey,
+    }
+
+    /// Arguments for [`secure_swap`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct SecureSwapArgs {
+        pub amount_in: u64,
+        pub min_out: u64,
+    }
+
+    pub const SECURE_SWAP: [u8; 1] = [2];
+
+    pub fn secure_swap(
+        program_id: &Pubkey,
+        accounts: &SecureSwapAccounts,
+        args: &SecureSwapArgs,
+    ) -> Instruction {
+        let mut data = SECURE_SWAP.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user, true),
+                AccountMeta::new(accounts.pool, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`record_cluster`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct RecordClusterAccounts {
+        pub authority: Pubkey,
+        pub program: Pubkey,
+        pub program_data: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`record_cluster`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct RecordClusterArgs {
+        pub genesis_hash: [u8; 32],
+    }
+
+    pub const RECORD_CLUSTER: [u8; 1] = [3];
+
+    pub fn record_cluster(
+        program_id: &Pubkey,
+        accounts: &RecordClusterAccounts,
+        args: &RecordClusterArgs,
+    ) -> Instruction {
+        let mut data = RECORD_CLUSTER.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new_readonly(accounts.program, false),
+                AccountMeta::new_readonly(accounts.program_data, false),
+                AccountMeta::new(accounts.cluster, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+}
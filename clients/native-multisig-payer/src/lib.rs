@@ -0,0 +1,151 @@
+//! Client for the `native_multisig_payer` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`initialize`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitializeAccounts {
+        pub creator: Pubkey,
+        pub config: Pubkey,
+        pub treasury: Pubkey,
+    }
+
+    pub const INITIALIZE: [u8; 1] = [0];
+
+    pub fn initialize(program_id: &Pubkey, accounts: &InitializeAccounts) -> Instruction {
+        let data = INITIALIZE.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.creator, true),
+                AccountMeta::new(accounts.config, false),
+                AccountMeta::new(accounts.treasury, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`vulnerable_create_proposal`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableCreateProposalAccounts {
+        pub treasury: Pubkey,
+        pub config: Pubkey,
+        pub proposal: Pubkey,
+        pub creator: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`vulnerable_create_proposal`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct VulnerableCreateProposalArgs {
+        pub proposal_id: u64,
+        pub title: String,
+    }
+
+    pub const VULNERABLE_CREATE_PROPOSAL: [u8; 1] = [1];
+
+    pub fn vulnerable_create_proposal(
+        program_id: &Pubkey,
+        accounts: &VulnerableCreateProposalAccounts,
+        args: &VulnerableCreateProposalArgs,
+    ) -> Instruction {
+        let mut data = VULNERABLE_CREATE_PROPOSAL.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.treasury, false),
+                AccountMeta::new_readonly(accounts.config, false),
+                AccountMeta::new(accounts.proposal, false),
+                AccountMeta::new_readonly(accounts.creator, true),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+                AccountMeta::new_readonly(accounts.cluster, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_create_proposal`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureCreateProposalAccounts {
+        pub rent_payer: Pubkey,
+        pub creator: Pubkey,
+        pub treasury: Pubkey,
+        pub config: Pubkey,
+        pub proposal: Pubkey,
+    }
+
+    /// Arguments for [`secure_create_proposal`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct SecureCreateProposalArgs {
+        pub proposal_id: u64,
+        pub title: String,
+    }
+
+    pub const SECURE_CREATE_PROPOSAL: [u8; 1] = [2];
+
+    pub fn secure_create_proposal(
+        program_id: &Pubkey,
+        accounts: &SecureCreateProposalAccounts,
+        args: &SecureCreateProposalArgs,
+    ) -> Instruction {
+        let mut data = SECURE_CREATE_PROPOSAL.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.rent_payer, true),
+                AccountMeta::new_readonly(accounts.creator, true),
+                AccountMeta::new_readonly(accounts.treasury, false),
+                AccountMeta::new_readonly(accounts.config, false),
+                AccountMeta::new(accounts.proposal, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`record_cluster`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct RecordClusterAccounts {
+        pub authority: Pubkey,
+        pub program: Pubkey,
+        pub program_data: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`record_cluster`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct RecordClusterArgs {
+        pub genesis_hash: [u8; 32],
+    }
+
+    pub const RECORD_CLUSTER: [u8; 1] = [3];
+
+    pub fn record_cluster(
+        program_id: &Pubkey,
+        accounts: &RecordClusterAccounts,
+        args: &RecordClusterArgs,
+    ) -> Instruction {
+        let mut data = RECORD_CLUSTER.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new_readonly(accounts.program, false),
+                AccountMeta::new_readonly(accounts.program_data, false),
+                AccountMeta::new(accounts.cluster, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Client for the `native_signer_authorization` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`initialize`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitializeAccounts {
+        pub authority: Pubkey,
+        pub vault: Pubkey,
+    }
+
+    pub const INITIALIZE: [u8; 1] = [0];
+
+    pub fn initialize(program_id: &Pubkey, accounts: &InitializeAccounts) -> Instruction {
+        let data = INITIALIZE.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`deposit`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct DepositAccounts {
+        pub authority: Pubkey,
+        pub vault: Pubkey,
+    }
+
+    /// Arguments for [`deposit`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct DepositArgs {
+        pub amount: u64,
+    }
+
+    pub const DEPOSIT: [u8; 1] = [1];
+
+    pub fn deposit(
+        program_id: &Pubkey,
+        accounts: &DepositAccounts,
+        args: &DepositArgs,
+    ) -> Instruction {
+        let mut data = DEPOSIT.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`vulnerable_withdraw`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableWithdrawAccounts {
+        pub vault: Pubkey,
+        pub authority: Pubkey,
+        pub destination: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`vulnerable_withdraw`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct VulnerableWithdrawArgs {
+        pub amount: u64,
+    }
+
+    pub const VULNERABLE_WITHDRAW: [u8; 1] = [2];
+
+    pub fn vulnerable_withdraw(
+        program_id: &Pubkey,
+        accounts: &VulnerableWithdrawAccounts,
+        args: &VulnerableWithdrawArgs,
+    ) -> Instruction {
+        let mut data = VULNERABLE_WITHDRAW.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new_readonly(accounts.authority, false),
+                AccountMeta::new(accounts.destination, false),
+                AccountMeta::new_readonly(accounts.cluster, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_withdraw`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureWithdrawAccounts {
+        pub vault: Pubkey,
+        pub authority: Pubkey,
+        pub destination: Pubkey,
+    }
+
+    /// Arguments for [`secure_withdraw`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct SecureWithdrawArgs {
+        pub amount: u64,
+    }
+
+    pub const SECURE_WITHDRAW: [u8; 1] = [3];
+
+    pub fn secure_withdraw(
+        program_id: &Pubkey,
+        accounts: &SecureWithdrawAccounts,
+        args: &SecureWithdrawArgs,
+    ) -> Instruction {
+        let mut data = SECURE_WITHDRAW.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new_readonly(accounts.authority, true),
+                AccountMeta::new(accounts.destination, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`record_cluster`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct RecordClusterAccounts {
+        pub authority: Pubkey,
+        pub program: Pubkey,
+        pub program_data: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`record_cluster`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct RecordClusterArgs {
+        pub genesis_hash: [u8; 32],
+    }
+
+    pub const RECORD_CLUSTER: [u8; 1] = [4];
+
+    pub fn record_cluster(
+        program_id: &Pubkey,
+        accounts: &RecordClusterAccounts,
+        args: &RecordClusterArgs,
+    ) -> Instruction {
+        let mut data = RECORD_CLUSTER.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new_readonly(accounts.program, false),
+                AccountMeta::new_readonly(accounts.program_data, false),
+                AccountMeta::new(accounts.cluster, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+}
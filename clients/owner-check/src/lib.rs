@@ -0,0 +1,88 @@
+//! Client for the `owner_check` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`vulnerable_read_config`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableReadConfigAccounts {
+        pub config: Pubkey,
+        pub caller: Pubkey,
+    }
+
+    pub const VULNERABLE_READ_CONFIG: [u8; 1] = [0];
+
+    pub fn vulnerable_read_config(
+        program_id: &Pubkey,
+        accounts: &VulnerableReadConfigAccounts,
+    ) -> Instruction {
+        let data = VULNERABLE_READ_CONFIG.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.config, false),
+                AccountMeta::new_readonly(accounts.caller, true),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_read_config`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureReadConfigAccounts {
+        pub config: Pubkey,
+        pub caller: Pubkey,
+    }
+
+    pub const SECURE_READ_CONFIG: [u8; 1] = [1];
+
+    pub fn secure_read_config(
+        program_id: &Pubkey,
+        accounts: &SecureReadConfigAccounts,
+    ) -> Instruction {
+        let data = SECURE_READ_CONFIG.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.config, false),
+                AccountMeta::new_readonly(accounts.caller, true),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`initialize_config`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitializeConfigAccounts {
+        pub config: Pubkey,
+    }
+
+    /// Arguments for [`initialize_config`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct InitializeConfigArgs {
+        pub admin: Pubkey,
+    }
+
+    pub const INITIALIZE_CONFIG: [u8; 1] = [2];
+
+    pub fn initialize_config(
+        program_id: &Pubkey,
+        accounts: &InitializeConfigAccounts,
+        args: &InitializeConfigArgs,
+    ) -> Instruction {
+        let mut data = INITIALIZE_CONFIG.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.config, false),
+            ],
+            data,
+        }
+    }
+}
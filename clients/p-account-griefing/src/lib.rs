@@ -0,0 +1,140 @@
+//! Client for the `p_account_griefing` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`vulnerable_create_stake`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableCreateStakeAccounts {
+        pub user: Pubkey,
+        pub stake_account: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    pub const VULNERABLE_CREATE_STAKE: [u8; 1] = [0];
+
+    pub fn vulnerable_create_stake(
+        program_id: &Pubkey,
+        accounts: &VulnerableCreateStakeAccounts,
+    ) -> Instruction {
+        let data = VULNERABLE_CREATE_STAKE.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user, true),
+                AccountMeta::new(accounts.stake_account, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+                AccountMeta::new_readonly(accounts.cluster, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_create_stake`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureCreateStakeAccounts {
+        pub user: Pubkey,
+        pub stake_account: Pubkey,
+    }
+
+    /// Arguments for [`secure_create_stake`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct SecureCreateStakeArgs {
+        pub nonce: u64,
+    }
+
+    pub const SECURE_CREATE_STAKE: [u8; 1] = [1];
+
+    pub fn secure_create_stake(
+        program_id: &Pubkey,
+        accounts: &SecureCreateStakeAccounts,
+        args: &SecureCreateStakeArgs,
+    ) -> Instruction {
+        let mut data = SECURE_CREATE_STAKE.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user, true),
+                AccountMeta::new(accounts.stake_account, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_deposit`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureDepositAccounts {
+        pub user: Pubkey,
+        pub stake_account: Pubkey,
+    }
+
+    /// Arguments for [`secure_deposit`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct SecureDepositArgs {
+        pub amount: u64,
+    }
+
+    pub const SECURE_DEPOSIT: [u8; 1] = [2];
+
+    pub fn secure_deposit(
+        program_id: &Pubkey,
+        accounts: &SecureDepositAccounts,
+        args: &SecureDepositArgs,
+    ) -> Instruction {
+        let mut data = SECURE_DEPOSIT.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.user, true),
+                AccountMeta::new(accounts.stake_account, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`record_cluster`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct RecordClusterAccounts {
+        pub authority: Pubkey,
+        pub program: Pubkey,
+        pub program_data: Pubkey,
+        pub cluster: Pubkey,
+    }
+
+    /// Arguments for [`record_cluster`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct RecordClusterArgs {
+        pub genesis_hash: [u8; 32],
+    }
+
+    pub const RECORD_CLUSTER: [u8; 1] = [3];
+
+    pub fn record_cluster(
+        program_id: &Pubkey,
+        accounts: &RecordClusterAccounts,
+        args: &RecordClusterArgs,
+    ) -> Instruction {
+        let mut data = RECORD_CLUSTER.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.authority, true),
+                AccountMeta::new_readonly(accounts.program, false),
+                AccountMeta::new_readonly(accounts.program_data, false),
+                AccountMeta::new(accounts.cluster, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+            ],
+            data,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Client for the `p_escrow` program.
+//!
+//! Generated by `cargo xtask clients` from the program's IDL. Do not edit.
+
+pub use solana_pubkey::Pubkey;
+
+pub mod instructions {
+    use super::*;
+    use solana_instruction::{AccountMeta, Instruction};
+
+    /// Accounts for [`make`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct MakeAccounts {
+        pub maker: Pubkey,
+        pub mint_a: Pubkey,
+        pub mint_b: Pubkey,
+        pub escrow: Pubkey,
+        pub maker_ata: Pubkey,
+        pub vault: Pubkey,
+        pub token_program: Pubkey,
+    }
+
+    /// Arguments for [`make`]
+    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+    pub struct MakeArgs {
+        pub bump: u8,
+        pub amount_to_receive: u64,
+        pub amount_to_give: u64,
+    }
+
+    pub const MAKE: [u8; 1] = [0];
+
+    pub fn make(program_id: &Pubkey, accounts: &MakeAccounts, args: &MakeArgs) -> Instruction {
+        let mut data = MAKE.to_vec();
+        borsh::BorshSerialize::serialize(args, &mut data).expect("writing to a Vec");
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(accounts.maker, true),
+                AccountMeta::new_readonly(accounts.mint_a, false),
+                AccountMeta::new_readonly(accounts.mint_b, false),
+                AccountMeta::new(accounts.escrow, false),
+                AccountMeta::new(accounts.maker_ata, false),
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new_readonly(Pubkey::from_str_const("11111111111111111111111111111111"), false),
+                AccountMeta::new_readonly(accounts.token_program, false),
+            ],
+            data,
+        }
+    }
+
+    pub const TAKE: [u8; 1] = [1];
+
+    pub fn take(program_id: &Pubkey) -> Instruction {
+        let data = TAKE.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    /// Accounts for [`vulnerable_refund`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VulnerableRefundAccounts {
+        pub caller: Pubkey,
+        pub escrow: Pubkey,
+        pub vault: Pubkey,
+        pub destination: Pubkey,
+        pub token_program: Pubkey,
+    }
+
+    pub const VULNERABLE_REFUND: [u8; 1] = [2];
+
+    pub fn vulnerable_refund(
+        program_id: &Pubkey,
+        accounts: &VulnerableRefundAccounts,
+    ) -> Instruction {
+        let data = VULNERABLE_REFUND.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(accounts.caller, true),
+                AccountMeta::new(accounts.escrow, false),
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new(accounts.destination, false),
+                AccountMeta::new_readonly(accounts.token_program, false),
+            ],
+            data,
+        }
+    }
+
+    /// Accounts for [`secure_refund`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SecureRefundAccounts {
+        pub caller: Pubkey,
+        pub escrow: Pubkey,
+        pub vault: Pubkey,
+        pub destination: Pubkey,
+        pub token_program: Pubkey,
+    }
+
+    pub const SECURE_REFUND: [u8; 1] = [3];
+
+    pub fn secure_refund(program_id: &Pubkey, accounts: &SecureRefundAccounts) -> Instruction {
+        let data = SECURE_REFUND.to_vec();
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(accounts.caller, true),
+                AccountMeta::new(accounts.escrow, false),
+                AccountMeta::new(accounts.vault, false),
+                AccountMeta::new(accounts.destination, false),
+                AccountMeta::new_readonly(accounts.token_program, false),
+            ],
+            data,
+        }
+    }
+}
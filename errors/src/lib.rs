@@ -0,0 +1,455 @@
+//! One taxonomy for the errors every program raises
+//!
+//! Each program declares its own `#[error_code]` enum, and the same failure
+//! goes by a different name in each: `Unauthorized`, `UnauthorizedAuthority`
+//! and `NotPendingAuthority` are all a caller without the right to act. An
+//! [`ErrorClass`] is that shared meaning. The program enums stay as they
+//! are, as extensions naming the failure precisely, and classify into it:
+//!
+//! ```ignore
+//! impl From<VaultError> for solsec_errors::ErrorClass {
+//!     fn from(error: VaultError) -> Self {
+//!         solsec_errors::SIGNER_AUTHORIZATION.class(error as usize)
+//!     }
+//! }
+//! ```
+//!
+//! The [`ErrorSet`] per program is the one copy of each enum's
+//! classification, in declaration order, so a test or tool holding only
+//! the variant name and code from a failed transaction's logs can classify
+//! it with [`program_class`], and Anchor's and the runtime's own errors with
+//! [`anchor_class`] and [`runtime_class`]. `tests/harness.rs` checks the sets
+//! against the enums in the tree.
+
+#![no_std]
+
+/// What kind of failure an error reports, whichever program raised it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorClass {
+    /// The caller didn't sign, or isn't the authority the state names
+    Unauthorized,
+    /// An account has the wrong owner, type, address or seeds
+    InvalidAccount,
+    /// An instruction argument is zero, too long or out of range
+    InvalidArgument,
+    AlreadyInitialized,
+    /// The state doesn't allow the call: locked, executed, closed, or
+    /// changed since it was read
+    InvalidState,
+    /// A calculation overflowed or divided by zero
+    Arithmetic,
+    /// A trade fell outside the caller's limits
+    Slippage,
+    /// An account or pool holds less than the call takes
+    InsufficientFunds,
+    /// The same account was passed where distinct ones were required
+    DuplicateAccounts,
+}
+
+impl ErrorClass {
+    pub const ALL: [ErrorClass; 9] = [
+        ErrorClass::Unauthorized,
+        ErrorClass::InvalidAccount,
+        ErrorClass::InvalidArgument,
+        ErrorClass::AlreadyInitialized,
+        ErrorClass::InvalidState,
+        ErrorClass::Arithmetic,
+        ErrorClass::Slippage,
+        ErrorClass::InsufficientFunds,
+        ErrorClass::DuplicateAccounts,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorClass::Unauthorized => "unauthorized",
+            ErrorClass::InvalidAccount => "invalid-account",
+            ErrorClass::InvalidArgument => "invalid-argument",
+            ErrorClass::AlreadyInitialized => "already-initialized",
+            ErrorClass::InvalidState => "invalid-state",
+            ErrorClass::Arithmetic => "arithmetic",
+            ErrorClass::Slippage => "slippage",
+            ErrorClass::InsufficientFunds => "insufficient-funds",
+            ErrorClass::DuplicateAccounts => "duplicate-accounts",
+        }
+    }
+}
+
+impl core::fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Where Anchor numbers a program's `#[error_code]` variants from.
+pub const ERROR_CODE_OFFSET: u32 = 6000;
+
+/// A program's `#[error_code]` enum and the class of each variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorSet {
+    /// Directory under `programs/`
+    pub program: &'static str,
+    /// The enum's name
+    pub name: &'static str,
+    /// In declaration order, so variant `i` has code
+    /// `ERROR_CODE_OFFSET + i`
+    pub variants: &'static [(&'static str, ErrorClass)],
+}
+
+impl ErrorSet {
+    /// The class of the variant with discriminant `index`.
+    pub const fn class(&self, index: usize) -> ErrorClass {
+        self.variants[index].1
+    }
+
+    /// The code and class of the variant called `name`.
+    pub fn find(&self, name: &str) -> Option<(u32, ErrorClass)> {
+        self.variants
+            .iter()
+            .position(|(variant, _)| *variant == name)
+            .map(|i| (ERROR_CODE_OFFSET + i as u32, self.variants[i].1))
+    }
+}
+
+pub const ACCOUNT_CLOSE: ErrorSet = ErrorSet {
+    program: "account-close",
+    name: "CloseError",
+    variants: &[
+        ("HasBalance", ErrorClass::InvalidState),
+        ("AlreadyClosed", ErrorClass::InvalidState),
+    ],
+};
+
+pub const ACCOUNT_GRIEFING: ErrorSet = ErrorSet {
+    program: "account-griefing",
+    name: "StakeError",
+    variants: &[
+        ("AlreadyInitialized", ErrorClass::AlreadyInitialized),
+        ("InvalidAmount", ErrorClass::InvalidArgument),
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("OutdatedVersion", ErrorClass::InvalidState),
+    ],
+};
+
+pub const ACCOUNT_RELOADING: ErrorSet = ErrorSet {
+    program: "account-reloading",
+    name: "ReloadError",
+    variants: &[("StaleData", ErrorClass::InvalidState)],
+};
+
+pub const ARITHMETIC_OVERFLOW: ErrorSet = ErrorSet {
+    program: "arithmetic-overflow",
+    name: "PoolError",
+    variants: &[
+        ("MathOverflow", ErrorClass::Arithmetic),
+        ("SlippageExceeded", ErrorClass::Slippage),
+        ("InvalidAmount", ErrorClass::InvalidArgument),
+        ("InsufficientReserves", ErrorClass::InsufficientFunds),
+    ],
+};
+
+pub const AUTHORITY_TRANSFER: ErrorSet = ErrorSet {
+    program: "authority-transfer",
+    name: "AuthError",
+    variants: &[
+        ("NoPendingAuthority", ErrorClass::InvalidState),
+        ("NotPendingAuthority", ErrorClass::Unauthorized),
+        ("ZeroAddress", ErrorClass::InvalidArgument),
+    ],
+};
+
+pub const DUPLICATE_ACCOUNTS: ErrorSet = ErrorSet {
+    program: "duplicate-accounts",
+    name: "TransferError",
+    variants: &[
+        ("InsufficientBalance", ErrorClass::InsufficientFunds),
+        ("DuplicateAccounts", ErrorClass::DuplicateAccounts),
+    ],
+};
+
+pub const INSECURE_INIT: ErrorSet = ErrorSet {
+    program: "insecure-init",
+    name: "InitError",
+    variants: &[
+        ("AlreadyInitialized", ErrorClass::AlreadyInitialized),
+        ("Unauthorized", ErrorClass::Unauthorized),
+    ],
+};
+
+pub const DURABLE_NONCE: ErrorSet = ErrorSet {
+    program: "durable-nonce",
+    name: "WithdrawError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        // An authorization the vault already executed or the owner revoked
+        ("StaleSequence", ErrorClass::InvalidState),
+        ("AuthorizationExpired", ErrorClass::InvalidState),
+    ],
+};
+
+pub const FEE_ROUNDING: ErrorSet = ErrorSet {
+    program: "fee-rounding",
+    name: "FeeError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("InvalidFee", ErrorClass::InvalidArgument),
+        ("InvalidAmount", ErrorClass::InvalidArgument),
+        ("InsufficientBalance", ErrorClass::InsufficientFunds),
+        ("MathOverflow", ErrorClass::Arithmetic),
+    ],
+};
+
+pub const ORDER_BOOK: ErrorSet = ErrorSet {
+    program: "order-book",
+    name: "BookError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("InvalidPrice", ErrorClass::InvalidArgument),
+        ("InvalidQuantity", ErrorClass::InvalidArgument),
+        ("BookFull", ErrorClass::InvalidState),
+        ("WrongSide", ErrorClass::InvalidAccount),
+        ("OrderFilled", ErrorClass::InvalidState),
+        ("PricesDontCross", ErrorClass::Slippage),
+        // A real order, but another is ahead of it in price-time priority
+        ("NotBestOrder", ErrorClass::InvalidState),
+        ("MathOverflow", ErrorClass::Arithmetic),
+    ],
+};
+
+pub const SEED_LIQUIDITY: ErrorSet = ErrorSet {
+    program: "seed-liquidity",
+    name: "LaunchError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("InvalidAmount", ErrorClass::InvalidArgument),
+        ("SeedTooSmall", ErrorClass::InvalidArgument),
+        // The creator's shares, before the launch's unlock slot
+        ("LiquidityLocked", ErrorClass::InvalidState),
+        ("InsufficientShares", ErrorClass::InsufficientFunds),
+        ("InsufficientTokens", ErrorClass::InsufficientFunds),
+        ("InsufficientReserve", ErrorClass::InsufficientFunds),
+        ("SlippageExceeded", ErrorClass::Slippage),
+        ("MathOverflow", ErrorClass::Arithmetic),
+    ],
+};
+
+pub const INSURANCE_FUND: ErrorSet = ErrorSet {
+    program: "insurance-fund",
+    name: "InsuranceError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("InvalidAmount", ErrorClass::InvalidArgument),
+        ("NotApproved", ErrorClass::InvalidState),
+        ("AlreadyPaid", ErrorClass::InvalidState),
+        // A payout recipient other than the claimant the claim names
+        ("WrongRecipient", ErrorClass::InvalidAccount),
+        ("MathOverflow", ErrorClass::Arithmetic),
+    ],
+};
+
+pub const PARTNER_MINT: ErrorSet = ErrorSet {
+    program: "partner-mint",
+    name: "MintError",
+    variants: &[
+        ("InvalidAmount", ErrorClass::InvalidArgument),
+        // A caller program account other than the partner's
+        ("NotPartner", ErrorClass::Unauthorized),
+        ("MathOverflow", ErrorClass::Arithmetic),
+    ],
+};
+
+pub const LOOKUP_TABLE_PITFALLS: ErrorSet = ErrorSet {
+    program: "lookup-table-pitfalls",
+    name: "PayoutError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("NotApproved", ErrorClass::InvalidState),
+        ("AlreadyExecuted", ErrorClass::InvalidState),
+        ("TooManyRecipients", ErrorClass::InvalidArgument),
+        ("InvalidLookupTable", ErrorClass::InvalidAccount),
+        // A table whose authority could still append to it
+        ("LookupTableNotFrozen", ErrorClass::InvalidAccount),
+        ("UnresolvedIndex", ErrorClass::InvalidArgument),
+        // A recipient other than the one resolved at approval
+        ("RecipientMismatch", ErrorClass::InvalidAccount),
+    ],
+};
+
+pub const MULTISIG_PAYER: ErrorSet = ErrorSet {
+    program: "multisig-payer",
+    name: "DaoError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("AlreadyInitialized", ErrorClass::AlreadyInitialized),
+        ("TitleTooLong", ErrorClass::InvalidArgument),
+        ("AlreadyExecuted", ErrorClass::InvalidState),
+        ("LogFull", ErrorClass::InvalidState),
+        ("ProposalNotApproved", ErrorClass::Unauthorized),
+    ],
+};
+
+pub const PDA_SECURITY: ErrorSet = ErrorSet {
+    program: "pda-security",
+    name: "PdaError",
+    variants: &[
+        ("Unauthorized", ErrorClass::Unauthorized),
+        ("InvalidPda", ErrorClass::InvalidAccount),
+    ],
+};
+
+pub const REMAINING_ACCOUNTS: ErrorSet = ErrorSet {
+    program: "remaining-accounts",
+    name: "BatchError",
+    variants: &[
+        ("InvalidAccount", ErrorClass::InvalidAccount),
+        // A recipient the config doesn't entitle to a reward
+        ("NotEligible", ErrorClass::Unauthorized),
+        ("CountMismatch", ErrorClass::InvalidArgument),
+        ("InvalidOwner", ErrorClass::InvalidAccount),
+    ],
+};
+
+pub const SIGNER_AUTHORIZATION: ErrorSet = ErrorSet {
+    program: "signer-authorization",
+    name: "VaultError",
+    variants: &[("UnauthorizedAuthority", ErrorClass::Unauthorized)],
+};
+
+/// Every program's set. The Pinocchio programs return the runtime's
+/// errors, and the AMMs keep an enum per instruction in their own
+/// workspace.
+pub const ERROR_SETS: &[&ErrorSet] = &[
+    &ACCOUNT_CLOSE,
+    &ACCOUNT_GRIEFING,
+    &ACCOUNT_RELOADING,
+    &ARITHMETIC_OVERFLOW,
+    &AUTHORITY_TRANSFER,
+    &DUPLICATE_ACCOUNTS,
+    &DURABLE_NONCE,
+    &FEE_ROUNDING,
+    &INSECURE_INIT,
+    &INSURANCE_FUND,
+    &LOOKUP_TABLE_PITFALLS,
+    &MULTISIG_PAYER,
+    &ORDER_BOOK,
+    &PARTNER_MINT,
+    &PDA_SECURITY,
+    &REMAINING_ACCOUNTS,
+    &SEED_LIQUIDITY,
+    &SIGNER_AUTHORIZATION,
+];
+
+/// The class of a program error logged as `name` with `code`, when every
+/// program declaring it that way agrees on one.
+pub fn program_class(name: &str, code: u32) -> Option<ErrorClass> {
+    let mut classes = ERROR_SETS
+        .iter()
+        .filter_map(|set| set.find(name))
+        .filter(|(found, _)| *found == code)
+        .map(|(_, class)| class);
+    let first = classes.next()?;
+    classes.all(|class| class == first).then_some(first)
+}
+
+/// The class of an Anchor framework error, by its `ErrorCode` variant name.
+pub fn anchor_class(name: &str) -> Option<ErrorClass> {
+    Some(match name {
+        "AccountNotSigner" | "ConstraintSigner" | "ConstraintHasOne" => ErrorClass::Unauthorized,
+        "AccountOwnedByWrongProgram"
+        | "AccountDiscriminatorMismatch"
+        | "AccountDiscriminatorNotFound"
+        | "AccountDidNotDeserialize"
+        | "AccountNotInitialized"
+        | "AccountNotMutable"
+        | "AccountNotProgramData"
+        | "InvalidProgramId"
+        | "InvalidProgramExecutable"
+        | "ConstraintOwner"
+        | "ConstraintSeeds"
+        | "ConstraintAddress"
+        | "ConstraintExecutable"
+        | "ConstraintMut"
+        | "ConstraintTokenMint"
+        | "ConstraintTokenOwner"
+        | "ConstraintAssociated" => ErrorClass::InvalidAccount,
+        "AccountNotEnoughKeys" | "InstructionDidNotDeserialize" | "InstructionMissing" => {
+            ErrorClass::InvalidArgument
+        }
+        "ConstraintZero" => ErrorClass::AlreadyInitialized,
+        "ConstraintClose" => ErrorClass::InvalidState,
+        "ConstraintRentExempt" => ErrorClass::InsufficientFunds,
+        "ConstraintDuplicateMutableAccount" => ErrorClass::DuplicateAccounts,
+        _ => return None,
+    })
+}
+
+/// The class of a runtime `InstructionError`, by its variant name, as the
+/// Pinocchio programs and the system program return them.
+pub fn runtime_class(name: &str) -> Option<ErrorClass> {
+    Some(match name {
+        "MissingRequiredSignature" => ErrorClass::Unauthorized,
+        "IllegalOwner"
+        | "InvalidAccountOwner"
+        | "InvalidAccountData"
+        | "IncorrectProgramId"
+        | "InvalidSeeds"
+        | "AccountDataTooSmall"
+        | "UninitializedAccount" => ErrorClass::InvalidAccount,
+        "InvalidArgument" | "InvalidInstructionData" | "NotEnoughAccountKeys" => {
+            ErrorClass::InvalidArgument
+        }
+        "AccountAlreadyInitialized" => ErrorClass::AlreadyInitialized,
+        "ArithmeticOverflow" => ErrorClass::Arithmetic,
+        "InsufficientFunds" | "AccountNotRentExempt" => ErrorClass::InsufficientFunds,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_same_meaning_classifies_the_same() {
+        let unauthorized = [
+            program_class("Unauthorized", 6002),
+            program_class("UnauthorizedAuthority", 6000),
+            program_class("NotPendingAuthority", 6001),
+            anchor_class("AccountNotSigner"),
+            runtime_class("MissingRequiredSignature"),
+        ];
+        assert!(unauthorized
+            .iter()
+            .all(|class| *class == Some(ErrorClass::Unauthorized)));
+
+        // `InvalidAmount` is 6001 in one program and 6002 in another
+        assert_eq!(
+            program_class("InvalidAmount", 6001),
+            Some(ErrorClass::InvalidArgument)
+        );
+        assert_eq!(
+            program_class("InvalidAmount", 6002),
+            Some(ErrorClass::InvalidArgument)
+        );
+        assert_eq!(program_class("InvalidAmount", 6003), None);
+        assert_eq!(program_class("Nope", 6000), None);
+        assert_eq!(anchor_class("ConstraintRaw"), None);
+    }
+
+    #[test]
+    fn test_sets_index_in_declaration_order() {
+        assert_eq!(ARITHMETIC_OVERFLOW.class(1), ErrorClass::Slippage);
+        assert_eq!(
+            ARITHMETIC_OVERFLOW.find("InsufficientReserves"),
+            Some((6003, ErrorClass::InsufficientFunds))
+        );
+        assert_eq!(ARITHMETIC_OVERFLOW.find("StaleData"), None);
+
+        for (i, set) in ERROR_SETS.iter().enumerate() {
+            assert!(!set.variants.is_empty());
+            // Sorted by program, and each program once
+            assert!(ERROR_SETS[..i]
+                .iter()
+                .all(|other| other.program < set.program));
+        }
+    }
+}
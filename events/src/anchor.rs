@@ -0,0 +1,36 @@
+//! `SecurityEvent` as an Anchor event
+
+use alloc::vec::Vec;
+
+use anchor_lang::{
+    prelude::borsh::maybestd::io, AnchorDeserialize, AnchorSerialize, Discriminator, Event,
+};
+
+use crate::{SecurityEvent, DISCRIMINATOR};
+
+impl AnchorSerialize for SecurityEvent {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_vec())
+    }
+}
+
+impl AnchorDeserialize for SecurityEvent {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        SecurityEvent::decode(&data)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "malformed security event"))
+    }
+}
+
+impl Discriminator for SecurityEvent {
+    const DISCRIMINATOR: &'static [u8] = &DISCRIMINATOR;
+}
+
+impl Event for SecurityEvent {
+    fn data(&self) -> Vec<u8> {
+        let mut data = Vec::from(DISCRIMINATOR);
+        data.extend_from_slice(&self.to_vec());
+        data
+    }
+}
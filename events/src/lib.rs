@@ -0,0 +1,345 @@
+//! The event envelope the programs emit on security-relevant changes
+//!
+//! An authority handed over or a vault drained is what an off-chain monitor
+//! needs to see, and what a suite asserts happened. The programs making such
+//! changes all emit the same [`SecurityEvent`] through Anchor's `emit_cpi!`,
+//! so one decoder reads them all: the event travels as the data of a
+//! self-CPI, which survives the log truncation `emit!`'s events are lost to.
+//!
+//! ```ignore
+//! // In the handler, with `#[event_cpi]` on the accounts struct
+//! emit_cpi!(SecurityEvent::new(crate::ID.to_bytes(), EventKind::Withdrawal { .. }));
+//!
+//! // Off-chain, over the transaction's inner instructions
+//! let events = solsec_events::decode_inner(inner.iter().map(|ix| (program_id, &ix.data[..])));
+//! ```
+//!
+//! The `anchor` feature makes [`SecurityEvent`] an `anchor_lang::Event`. The
+//! envelope is written by hand rather than with `#[event]`, so it doesn't
+//! appear in the IDLs; this crate is its schema. The layout is Borsh's:
+//! `program`, `version`, then the [`EventKind`] variant index and its fields.
+
+#![no_std]
+
+// `anchor-031` swaps in Anchor 0.31 under the same name
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+extern crate alloc;
+
+#[cfg(feature = "anchor")]
+mod anchor;
+
+use alloc::vec::Vec;
+
+/// The envelope layout this crate writes and reads.
+pub const VERSION: u8 = 1;
+
+/// What Anchor's event CPI data starts with: `sha256("anchor:event")[..8]`,
+/// little-endian.
+pub const EVENT_IX_TAG: [u8; 8] = 0x1d9acb512ea545e4u64.to_le_bytes();
+
+/// `sha256("event:SecurityEvent")[..8]`, after [`EVENT_IX_TAG`].
+pub const DISCRIMINATOR: [u8; 8] = [16, 175, 241, 170, 85, 9, 201, 100];
+
+/// Why bytes aren't a [`SecurityEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// Not an event CPI, or another event type's
+    Discriminator,
+    /// Written by a layout this crate doesn't know
+    Version(u8),
+    /// An unknown [`EventKind`] variant
+    Kind(u8),
+    /// Too short, or bytes left over
+    Layout,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::Discriminator => f.write_str("not a security event"),
+            DecodeError::Version(version) => write!(f, "unknown event version {}", version),
+            DecodeError::Kind(kind) => write!(f, "unknown event kind {}", kind),
+            DecodeError::Layout => f.write_str("malformed security event"),
+        }
+    }
+}
+
+/// What happened. Addresses are raw bytes, for either framework's key type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// `proposed` may now accept authority over `config`
+    AuthorityProposed {
+        config: [u8; 32],
+        authority: [u8; 32],
+        proposed: [u8; 32],
+    },
+    /// `config` answers to `current` instead of `previous`, the zero key
+    /// when it had no authority yet
+    AuthorityChanged {
+        config: [u8; 32],
+        previous: [u8; 32],
+        current: [u8; 32],
+    },
+    /// `amount` lamports left `vault` for `destination`, in the name of
+    /// `authority`
+    Withdrawal {
+        vault: [u8; 32],
+        authority: [u8; 32],
+        destination: [u8; 32],
+        amount: u64,
+    },
+}
+
+/// The envelope: which program emitted the event, in which layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SecurityEvent {
+    pub program: [u8; 32],
+    pub version: u8,
+    pub kind: EventKind,
+}
+
+impl SecurityEvent {
+    /// An event of the current [`VERSION`].
+    pub const fn new(program: [u8; 32], kind: EventKind) -> Self {
+        SecurityEvent {
+            program,
+            version: VERSION,
+            kind,
+        }
+    }
+
+    /// The Borsh encoding, without [`DISCRIMINATOR`].
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + 2 + 3 * 32 + 8);
+        out.extend_from_slice(&self.program);
+        out.push(self.version);
+        match &self.kind {
+            EventKind::AuthorityProposed {
+                config,
+                authority,
+                proposed,
+            } => {
+                out.push(0);
+                for key in [config, authority, proposed] {
+                    out.extend_from_slice(key);
+                }
+            }
+            EventKind::AuthorityChanged {
+                config,
+                previous,
+                current,
+            } => {
+                out.push(1);
+                for key in [config, previous, current] {
+                    out.extend_from_slice(key);
+                }
+            }
+            EventKind::Withdrawal {
+                vault,
+                authority,
+                destination,
+                amount,
+            } => {
+                out.push(2);
+                for key in [vault, authority, destination] {
+                    out.extend_from_slice(key);
+                }
+                out.extend_from_slice(&amount.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Read [`to_vec`](Self::to_vec)'s encoding back, all of `data`.
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader(data);
+        let program = reader.key()?;
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(DecodeError::Version(version));
+        }
+        let kind = match reader.byte()? {
+            0 => EventKind::AuthorityProposed {
+                config: reader.key()?,
+                authority: reader.key()?,
+                proposed: reader.key()?,
+            },
+            1 => EventKind::AuthorityChanged {
+                config: reader.key()?,
+                previous: reader.key()?,
+                current: reader.key()?,
+            },
+            2 => EventKind::Withdrawal {
+                vault: reader.key()?,
+                authority: reader.key()?,
+                destination: reader.key()?,
+                amount: u64::from_le_bytes(reader.take()?),
+            },
+            kind => return Err(DecodeError::Kind(kind)),
+        };
+        if !reader.0.is_empty() {
+            return Err(DecodeError::Layout);
+        }
+        Ok(SecurityEvent {
+            program,
+            version,
+            kind,
+        })
+    }
+
+    /// The data of the self-CPI `emit_cpi!` makes for this event.
+    pub fn to_ix_data(&self) -> Vec<u8> {
+        let mut data = Vec::from(EVENT_IX_TAG);
+        data.extend_from_slice(&DISCRIMINATOR);
+        data.extend_from_slice(&self.to_vec());
+        data
+    }
+
+    /// Read an instruction's data as an event CPI's.
+    pub fn from_ix_data(data: &[u8]) -> Result<Self, DecodeError> {
+        data.strip_prefix(&EVENT_IX_TAG[..])
+            .and_then(|event| event.strip_prefix(&DISCRIMINATOR[..]))
+            .ok_or(DecodeError::Discriminator)
+            .and_then(Self::decode)
+    }
+}
+
+/// The security events among a transaction's inner instructions, given as
+/// `(program id, data)` in execution order.
+///
+/// An event counts only when the instruction carrying it went to the program
+/// named in its envelope. Anchor rejects an event CPI not signed by the
+/// program's own event authority, so in a successful transaction that
+/// program emitted it; an envelope naming another program is a forgery and
+/// is skipped, as are other event types.
+pub fn decode_inner<'a>(
+    instructions: impl IntoIterator<Item = ([u8; 32], &'a [u8])>,
+) -> Vec<SecurityEvent> {
+    instructions
+        .into_iter()
+        .filter_map(|(program, data)| {
+            SecurityEvent::from_ix_data(data)
+                .ok()
+                .filter(|event| event.program == program)
+        })
+        .collect()
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let (head, rest) = self.0.split_first_chunk().ok_or(DecodeError::Layout)?;
+        self.0 = rest;
+        Ok(*head)
+    }
+
+    fn key(&mut self) -> Result<[u8; 32], DecodeError> {
+        self.take()
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const PROGRAM: [u8; 32] = [7; 32];
+
+    fn withdrawal(amount: u64) -> SecurityEvent {
+        SecurityEvent::new(
+            PROGRAM,
+            EventKind::Withdrawal {
+                vault: [1; 32],
+                authority: [2; 32],
+                destination: [3; 32],
+                amount,
+            },
+        )
+    }
+
+    #[test]
+    fn test_round_trips_every_kind() {
+        let events = [
+            SecurityEvent::new(
+                PROGRAM,
+                EventKind::AuthorityProposed {
+                    config: [1; 32],
+                    authority: [2; 32],
+                    proposed: [3; 32],
+                },
+            ),
+            SecurityEvent::new(
+                PROGRAM,
+                EventKind::AuthorityChanged {
+                    config: [1; 32],
+                    previous: [0; 32],
+                    current: [3; 32],
+                },
+            ),
+            withdrawal(u64::MAX),
+        ];
+        for event in events {
+            assert_eq!(SecurityEvent::decode(&event.to_vec()), Ok(event));
+            assert_eq!(SecurityEvent::from_ix_data(&event.to_ix_data()), Ok(event));
+        }
+
+        let data = withdrawal(5).to_vec();
+        assert_eq!((data.len(), data[32], data[33]), (138, VERSION, 2));
+        assert_eq!(data[130..], 5u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_rejects_what_it_cannot_read() {
+        let data = withdrawal(5).to_vec();
+        assert_eq!(
+            SecurityEvent::decode(&data[..137]),
+            Err(DecodeError::Layout)
+        );
+        let mut longer = data.clone();
+        longer.push(0);
+        assert_eq!(SecurityEvent::decode(&longer), Err(DecodeError::Layout));
+
+        let mut future = data.clone();
+        future[32] = VERSION + 1;
+        assert_eq!(
+            SecurityEvent::decode(&future),
+            Err(DecodeError::Version(VERSION + 1))
+        );
+        let mut unknown = data;
+        unknown[33] = 9;
+        assert_eq!(SecurityEvent::decode(&unknown), Err(DecodeError::Kind(9)));
+
+        // A plain instruction, and another `#[event]` type's CPI
+        let mut other = Vec::from(EVENT_IX_TAG);
+        other.extend_from_slice(&[0; 8]);
+        for data in [vec![1, 2, 3], other] {
+            assert_eq!(
+                SecurityEvent::from_ix_data(&data),
+                Err(DecodeError::Discriminator)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_inner_skips_forged_envelopes() {
+        let real = withdrawal(5);
+        let forged = withdrawal(1_000_000);
+        let (real_data, forged_data) = (real.to_ix_data(), forged.to_ix_data());
+        let transfer = [2, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0];
+
+        let events = decode_inner([
+            ([0; 32], &transfer[..]),
+            (PROGRAM, &real_data[..]),
+            // Another program emitting in this program's name
+            ([9; 32], &forged_data[..]),
+        ]);
+        assert_eq!(events, [real]);
+    }
+}
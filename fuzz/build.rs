@@ -0,0 +1,21 @@
+//! One `#[test]` per recorded finding of the targets here; see
+//! `src/stubs.rs`.
+
+use std::path::{Path, PathBuf};
+
+#[path = "src/stubs.rs"]
+mod stubs;
+
+const TARGETS: [&str; 3] = ["swap_math", "fee_math", "withdraw_fee"];
+
+fn main() {
+    let findings = Path::new(env!("CARGO_MANIFEST_DIR")).join("findings");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/stubs.rs");
+    println!("cargo:rerun-if-changed={}", findings.display());
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("cargo sets OUT_DIR"));
+    for target in TARGETS {
+        stubs::write(&findings, target, &out_dir);
+    }
+}
@@ -0,0 +1,12 @@
+//! honggfuzz entry point for `fee_math`; the check is the one
+//! `fuzz_targets/fee_math.rs` runs under libFuzzer.
+
+use security_fuzz::{fee, fuzz_input};
+
+fn main() {
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            fuzz_input("fee_math", data, fee::check);
+        });
+    }
+}
@@ -0,0 +1,12 @@
+//! honggfuzz entry point for `swap_math`; the check is the one
+//! `fuzz_targets/swap_math.rs` runs under libFuzzer.
+
+use security_fuzz::{fuzz_input, swap};
+
+fn main() {
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            fuzz_input("swap_math", data, swap::check);
+        });
+    }
+}
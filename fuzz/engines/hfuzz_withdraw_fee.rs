@@ -0,0 +1,12 @@
+//! honggfuzz entry point for `withdraw_fee`; the check is the one
+//! `fuzz_targets/withdraw_fee.rs` runs under libFuzzer.
+
+use security_fuzz::{fuzz_input, withdraw_fee};
+
+fn main() {
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            fuzz_input("withdraw_fee", data, withdraw_fee::check);
+        });
+    }
+}
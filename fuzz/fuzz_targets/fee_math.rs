@@ -0,0 +1,15 @@
+//! Fee-adjusted swap of the AMM programs
+//!
+//! For every fee `initialize` accepts, the secure formula must not fail,
+//! must not pay out more than the destination reserve, and must pay less
+//! (or the same) as the fee goes up. Where the buggy formula's unchecked
+//! math stays in range, the two must agree.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use security_fuzz::{fee, fuzz_input};
+
+fuzz_target!(|data: &[u8]| {
+    fuzz_input("fee_math", data, fee::check);
+});
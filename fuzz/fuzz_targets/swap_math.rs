@@ -0,0 +1,14 @@
+//! Constant-product swap of the arithmetic-overflow program
+//!
+//! The secure swap must return the exact (u128) output or reject the input
+//! for a reason the exact math agrees with: zero input, slippage, or a
+//! reserve that would overflow.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use security_fuzz::{fuzz_input, swap};
+
+fuzz_target!(|data: &[u8]| {
+    fuzz_input("swap_math", data, swap::check);
+});
@@ -0,0 +1,16 @@
+//! Withdrawal fee of the fee-rounding program
+//!
+//! For every amount and every fee `initialize` accepts, the secure fee must
+//! be the exact share rounded up, never more than the amount, and never
+//! lower for a larger amount, a higher rate, or the same amount split into
+//! pieces. Where the vulnerable formula's product doesn't overflow, it must
+//! be the exact share rounded down.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use security_fuzz::{fuzz_input, withdraw_fee};
+
+fuzz_target!(|data: &[u8]| {
+    fuzz_input("withdraw_fee", data, withdraw_fee::check);
+});
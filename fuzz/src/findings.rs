@@ -0,0 +1,218 @@
+//! Crash artifacts of every fuzz target
+//!
+//! A target runs each input through [`capture`]. When the check panics,
+//! whichever engine or test is driving it, the input is written to
+//! `fuzz/findings/<target>/<hash>.bin` before the panic goes on, with
+//! `<hash>.json` next to it: the panic message, the decoded input, and for
+//! targets that run programs the LiteSVM case that reproduces it (see
+//! [`attach_scenario`]).
+//!
+//! Every `.bin` there becomes a `#[test]` of its target on the next build
+//! (see [`crate::stubs`]), so a finding is a permanent regression test as
+//! soon as it is committed. Delete the pair once the finding is understood
+//! and covered elsewhere; a finding that still fails keeps failing `cargo
+//! test` until it is fixed.
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Directory holding the findings of every target.
+pub fn findings_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("findings")
+}
+
+/// Directory holding the findings of `target`.
+pub fn findings_dir(target: &str) -> PathBuf {
+    findings_root().join(target)
+}
+
+/// What `<hash>.json` records about a finding.
+#[derive(Debug, Serialize)]
+pub struct Finding<'a> {
+    pub target: &'a str,
+    pub message: &'a str,
+    /// The raw input, as hex; the same bytes are in `<hash>.bin`
+    pub input: String,
+    pub decoded: Option<&'a str>,
+    pub scenario: Option<&'a Value>,
+    /// Test that replays it
+    pub test: String,
+}
+
+// The input being checked on this thread
+struct Pending {
+    target: &'static str,
+    data: Vec<u8>,
+    decoded: Option<String>,
+    scenario: Option<Value>,
+}
+
+thread_local! {
+    static PENDING: RefCell<Option<Pending>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// Run `check` on `data` for `target`, recording the input as a finding if
+/// it panics.
+///
+/// The recording happens in a panic hook rather than by catching the
+/// panic, since libFuzzer and AFL++ abort the process from their own hooks;
+/// ours runs first and then hands over to theirs.
+pub fn capture<R>(target: &'static str, data: &[u8], check: impl FnOnce() -> R) -> R {
+    HOOK.call_once(|| {
+        let next = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let pending = PENDING.with(|p| p.try_borrow_mut().ok().and_then(|mut p| p.take()));
+            if let Some(pending) = pending {
+                let message = info.to_string();
+                match record(
+                    pending.target,
+                    &pending.data,
+                    &message,
+                    pending.decoded.as_deref(),
+                    pending.scenario.as_ref(),
+                ) {
+                    Ok(path) => eprintln!("finding written to {}", path.display()),
+                    Err(e) => eprintln!("failed to record the finding: {}", e),
+                }
+            }
+            next(info);
+        }));
+    });
+
+    PENDING.with(|p| {
+        *p.borrow_mut() = Some(Pending {
+            target,
+            data: data.to_vec(),
+            decoded: None,
+            scenario: None,
+        })
+    });
+    let result = check();
+    PENDING.with(|p| p.borrow_mut().take());
+    result
+}
+
+/// Note the decoded form of the input being captured, for the finding's
+/// `decoded` field. Does nothing outside [`capture`].
+pub fn attach_decoded(decoded: &impl Debug) {
+    PENDING.with(|p| {
+        if let Some(pending) = p.borrow_mut().as_mut() {
+            pending.decoded = Some(format!("{:#?}", decoded));
+        }
+    });
+}
+
+/// Note the case a program target runs in LiteSVM for the input being
+/// captured, for the finding's `scenario` field. Does nothing outside
+/// [`capture`].
+pub fn attach_scenario(scenario: &impl Serialize) {
+    PENDING.with(|p| {
+        if let Some(pending) = p.borrow_mut().as_mut() {
+            pending.scenario = serde_json::to_value(scenario).ok();
+        }
+    });
+}
+
+/// Write `data` to `findings/<target>/<hash>.bin` with its `<hash>.json`
+/// and return the `.bin` path. The hash is of the input alone, so the same
+/// input always lands in the same files.
+pub fn record(
+    target: &str,
+    data: &[u8],
+    message: &str,
+    decoded: Option<&str>,
+    scenario: Option<&Value>,
+) -> io::Result<PathBuf> {
+    record_in(
+        &findings_dir(target),
+        target,
+        data,
+        message,
+        decoded,
+        scenario,
+    )
+}
+
+fn record_in(
+    dir: &Path,
+    target: &str,
+    data: &[u8],
+    message: &str,
+    decoded: Option<&str>,
+    scenario: Option<&Value>,
+) -> io::Result<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    let hash = format!("{:016x}", hasher.finish());
+
+    let bin = dir.join(format!("{}.bin", hash));
+    let finding = Finding {
+        target,
+        message,
+        input: data.iter().map(|b| format!("{:02x}", b)).collect(),
+        decoded,
+        scenario,
+        test: format!("finding_{}", hash),
+    };
+    let json = serde_json::to_string_pretty(&finding).expect("finding serializes");
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&bin, data)?;
+    std::fs::write(bin.with_extension("json"), json + "\n")?;
+    Ok(bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_writes_input_and_metadata() {
+        let root = std::env::temp_dir().join(format!("findings-{}", std::process::id()));
+        let dir = root.join("t");
+        let scenario = serde_json::json!({ "steps": [1, 2] });
+        let bin = record_in(&dir, "t", &[0xab, 0x01], "boom", Some("X"), Some(&scenario)).unwrap();
+        assert_eq!(std::fs::read(&bin).unwrap(), [0xab, 0x01]);
+
+        let json: Value =
+            serde_json::from_slice(&std::fs::read(bin.with_extension("json")).unwrap()).unwrap();
+        assert_eq!(json["input"], "ab01");
+        assert_eq!(json["message"], "boom");
+        assert_eq!(json["scenario"], scenario);
+        let stem = bin.file_stem().unwrap().to_string_lossy();
+        assert_eq!(json["test"], format!("finding_{}", stem));
+
+        // Same input, same files
+        let again = record_in(&dir, "t", &[0xab, 0x01], "other", None, None).unwrap();
+        assert_eq!(again, bin);
+
+        crate::stubs::write(&root, "t", &root);
+        let stubs = std::fs::read_to_string(root.join("findings_t.rs")).unwrap();
+        assert_eq!(stubs.matches("#[test]").count(), 1);
+        assert!(stubs.contains(&format!("fn finding_{}()", stem)));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_attach_does_nothing_outside_capture() {
+        attach_decoded(&1u8);
+        attach_scenario(&"case");
+        assert!(PENDING.with(|p| p.borrow().is_none()));
+
+        capture("t", &[1], || {
+            attach_decoded(&7u8);
+            PENDING
+                .with(|p| assert_eq!(p.borrow().as_ref().unwrap().decoded.as_deref(), Some("7")));
+        });
+        assert!(PENDING.with(|p| p.borrow().is_none()));
+    }
+}
@@ -0,0 +1,545 @@
+//! libFuzzer targets for the programs' pure math
+//!
+//! Each target's input derives [`arbitrary::Arbitrary`], so libFuzzer's
+//! mutations and the saved corpus under `corpus/<target>/` map onto
+//! structured values instead of being rehashed into fresh random numbers.
+//!
+//! The math is mirrored from the on-chain code, the same way
+//! `trident-tests/fuzz_targets/fuzz_arithmetic.rs` does it, so the targets
+//! build without the Anchor toolchain. Keep both sides in step when a
+//! program's formula changes.
+//!
+//! The same checks also build for honggfuzz and AFL++ (`engines/`, behind
+//! the `honggfuzz` and `afl` features). Those entry points decode raw bytes
+//! with [`run_input`], so all three engines share one corpus format.
+//!
+//! Every engine runs its inputs through [`fuzz_input`], which records a
+//! crashing one under `findings/` (see [`findings`]); the crate's tests
+//! replay each recorded finding.
+
+use std::fmt::Debug;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+pub mod findings;
+pub mod stubs;
+
+/// Decode `data` the way libFuzzer's `fuzz_target!` does and `check` it.
+/// Inputs that don't decode are skipped, as `fuzz_target!` skips them.
+pub fn run_input<T: for<'a> Arbitrary<'a>>(data: &[u8], check: impl Fn(&T)) {
+    if let Ok(input) = T::arbitrary_take_rest(Unstructured::new(data)) {
+        check(&input);
+    }
+}
+
+/// [`run_input`] for a fuzzing engine: a panic records `data` as a finding
+/// of `target`, with the decoded input.
+pub fn fuzz_input<T: for<'a> Arbitrary<'a> + Debug>(
+    target: &'static str,
+    data: &[u8],
+    check: fn(&T),
+) {
+    findings::capture(target, data, || {
+        run_input(data, |input: &T| {
+            findings::attach_decoded(input);
+            check(input);
+        })
+    });
+}
+
+/// Swap math of `programs/arithmetic-overflow`
+pub mod swap {
+    use super::*;
+
+    #[derive(Debug, Clone, Arbitrary)]
+    pub struct SwapInput {
+        pub amount_in: u64,
+        pub min_out: u64,
+        pub reserve_x: u64,
+        pub reserve_y: u64,
+    }
+
+    /// Mirrors `vulnerable.rs`: wrapping math and no slippage check.
+    pub fn vulnerable_swap(
+        amount_in: u64,
+        reserve_x: u64,
+        reserve_y: u64,
+    ) -> Option<(u64, u64, u64)> {
+        let numerator = amount_in.wrapping_mul(reserve_y);
+        let denominator = reserve_x.wrapping_add(amount_in);
+        if denominator == 0 {
+            return None;
+        }
+        let amount_out = numerator / denominator;
+        Some((
+            amount_out,
+            reserve_x.wrapping_add(amount_in),
+            reserve_y.wrapping_sub(amount_out),
+        ))
+    }
+
+    /// Mirrors `secure.rs`: u128 intermediates and checked math.
+    pub fn secure_swap(
+        amount_in: u64,
+        min_out: u64,
+        reserve_x: u64,
+        reserve_y: u64,
+    ) -> Result<(u64, u64, u64), &'static str> {
+        if amount_in == 0 {
+            return Err("InvalidAmount");
+        }
+        let numerator = (amount_in as u128)
+            .checked_mul(reserve_y as u128)
+            .ok_or("MathOverflow")?;
+        let denominator = (reserve_x as u128)
+            .checked_add(amount_in as u128)
+            .ok_or("MathOverflow")?;
+        let amount_out = numerator.checked_div(denominator).ok_or("MathOverflow")?;
+        let amount_out = u64::try_from(amount_out).map_err(|_| "MathOverflow")?;
+
+        if amount_out < min_out {
+            return Err("SlippageExceeded");
+        }
+        if amount_out > reserve_y {
+            return Err("InsufficientReserves");
+        }
+        let new_reserve_x = reserve_x.checked_add(amount_in).ok_or("MathOverflow")?;
+        let new_reserve_y = reserve_y.checked_sub(amount_out).ok_or("MathOverflow")?;
+        Ok((amount_out, new_reserve_x, new_reserve_y))
+    }
+
+    /// The properties `swap_math` checks. Panics on the first one broken.
+    pub fn check(input: &SwapInput) {
+        let SwapInput {
+            amount_in,
+            min_out,
+            reserve_x,
+            reserve_y,
+        } = *input;
+        if reserve_x == 0 || reserve_y == 0 {
+            return;
+        }
+        let exact = amount_in as u128 * reserve_y as u128 / (reserve_x as u128 + amount_in as u128);
+
+        match secure_swap(amount_in, min_out, reserve_x, reserve_y) {
+            Ok((out, new_x, new_y)) => {
+                assert_eq!(out as u128, exact, "secure swap is off: {:?}", input);
+                assert!(out >= min_out, "slippage check skipped: {:?}", input);
+                assert_eq!(new_x, reserve_x + amount_in, "{:?}", input);
+                assert_eq!(new_y, reserve_y - out, "{:?}", input);
+            }
+            Err(e) => {
+                // Every rejection must have a reason the exact math agrees with
+                let legitimate = amount_in == 0
+                    || exact < min_out as u128
+                    || reserve_x.checked_add(amount_in).is_none();
+                assert!(
+                    legitimate,
+                    "secure swap rejected a valid swap ({}): {:?}",
+                    e, input
+                );
+            }
+        }
+    }
+}
+
+/// Fee math of `programs/amm`
+pub mod fee {
+    use super::*;
+
+    /// Basis points in 100%
+    pub const BPS: u16 = 10_000;
+
+    #[derive(Debug, Clone)]
+    pub struct FeeSwapInput {
+        pub amount_in: u64,
+        pub source_reserve: u64,
+        pub dest_reserve: u64,
+        /// Never above [`BPS`]; `initialize` rejects higher fees
+        pub fee: u16,
+    }
+
+    impl<'a> Arbitrary<'a> for FeeSwapInput {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            Ok(Self {
+                amount_in: u.arbitrary()?,
+                source_reserve: u.arbitrary()?,
+                dest_reserve: u.arbitrary()?,
+                fee: u.int_in_range(0..=BPS)?,
+            })
+        }
+    }
+
+    /// Mirrors buggy-amm's `swap::handler`, wrapping the way a release
+    /// build without overflow checks does.
+    pub fn buggy_amount_out(amount_in: u64, source: u64, dest: u64, fee: u16) -> Option<u64> {
+        let fee_adjusted = amount_in.wrapping_mul((BPS - fee) as u64) / BPS as u64;
+        let k = source.wrapping_mul(dest);
+        let new_source = source.wrapping_add(fee_adjusted);
+        if new_source == 0 {
+            return None;
+        }
+        Some(dest.wrapping_sub(k / new_source))
+    }
+
+    /// Mirrors secure-amm's `swap::handler`, up to the slippage check.
+    pub fn secure_amount_out(
+        amount_in: u64,
+        source: u64,
+        dest: u64,
+        fee: u16,
+    ) -> Result<u64, &'static str> {
+        let (source, dest) = (source as u128, dest as u128);
+        let fee_adjusted = (amount_in as u128)
+            .checked_mul((BPS - fee) as u128)
+            .ok_or("MathOverflow")?
+            .checked_div(BPS as u128)
+            .ok_or("MathOverflow")?;
+        let k = source.checked_mul(dest).ok_or("MathOverflow")?;
+        let new_source = source.checked_add(fee_adjusted).ok_or("MathOverflow")?;
+        let new_dest = k.checked_div(new_source).ok_or("MathOverflow")?;
+        let amount_out = dest.checked_sub(new_dest).ok_or("MathOverflow")?;
+        Ok(amount_out as u64)
+    }
+
+    /// The properties `fee_math` checks. Panics on the first one broken.
+    pub fn check(input: &FeeSwapInput) {
+        let FeeSwapInput {
+            amount_in,
+            source_reserve,
+            dest_reserve,
+            fee,
+        } = *input;
+        if source_reserve == 0 || dest_reserve == 0 {
+            return;
+        }
+
+        let out = secure_amount_out(amount_in, source_reserve, dest_reserve, fee)
+            .unwrap_or_else(|e| panic!("secure fee math failed ({}): {:?}", e, input));
+        assert!(
+            out <= dest_reserve,
+            "swap pays out more than the pool holds: {:?}",
+            input
+        );
+
+        // A higher fee never pays the trader more
+        if fee < BPS {
+            let pricier =
+                secure_amount_out(amount_in, source_reserve, dest_reserve, fee + 1).unwrap();
+            assert!(
+                pricier <= out,
+                "raising the fee raised the output: {:?}",
+                input
+            );
+        }
+        if fee == BPS {
+            assert_eq!(out, 0, "a 100% fee still paid out: {:?}", input);
+        }
+
+        if buggy_in_range(input) {
+            assert_eq!(
+                buggy_amount_out(amount_in, source_reserve, dest_reserve, fee),
+                Some(out),
+                "formulas diverge without an overflow: {:?}",
+                input
+            );
+        }
+    }
+
+    /// Whether the buggy formula's unchecked operations stay in range for
+    /// `input`, in which case it must agree with the secure one.
+    pub fn buggy_in_range(input: &FeeSwapInput) -> bool {
+        let scaled = input.amount_in.checked_mul((BPS - input.fee) as u64);
+        let k = input.source_reserve.checked_mul(input.dest_reserve);
+        let new_source = scaled.and_then(|s| input.source_reserve.checked_add(s / BPS as u64));
+        scaled.is_some() && k.is_some() && new_source.is_some()
+    }
+}
+
+/// Withdrawal fee of `programs/fee-rounding`
+pub mod withdraw_fee {
+    use super::*;
+
+    /// Basis points in 100%
+    pub const BPS: u16 = 10_000;
+
+    /// Most pieces a withdrawal is split into
+    pub const MAX_PIECES: u8 = 100;
+
+    #[derive(Debug, Clone)]
+    pub struct WithdrawFeeInput {
+        pub amount: u64,
+        /// From 1 to [`MAX_PIECES`]
+        pub pieces: u8,
+        /// Never above [`BPS`]; `initialize` rejects higher fees
+        pub fee_bps: u16,
+    }
+
+    impl<'a> Arbitrary<'a> for WithdrawFeeInput {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            Ok(Self {
+                amount: u.arbitrary()?,
+                pieces: u.int_in_range(1..=MAX_PIECES)?,
+                fee_bps: u.int_in_range(0..=BPS)?,
+            })
+        }
+    }
+
+    /// Mirrors `vulnerable.rs`. `None` is the overflow the program panics
+    /// on, since the release profile keeps overflow checks.
+    pub fn vulnerable_fee(amount: u64, fee_bps: u16) -> Option<u64> {
+        amount
+            .checked_mul(fee_bps as u64)
+            .map(|product| product / BPS as u64)
+    }
+
+    /// Mirrors `secure.rs`: `solsec_math::bps_ceil` through a u128 product.
+    pub fn secure_fee(amount: u64, fee_bps: u16) -> Result<u64, &'static str> {
+        let product = amount as u128 * fee_bps as u128;
+        let fee = product.div_ceil(BPS as u128);
+        u64::try_from(fee).map_err(|_| "MathOverflow")
+    }
+
+    /// `amount` in `pieces` withdrawals as equal as they can be, the
+    /// first ones a lamport larger.
+    pub fn split(amount: u64, pieces: u8) -> impl Iterator<Item = u64> {
+        let (share, extra) = (amount / pieces as u64, amount % pieces as u64);
+        (0..pieces as u64).map(move |i| share + (i < extra) as u64)
+    }
+
+    /// Total fee of withdrawing `amount` in `pieces` with `fee`.
+    pub fn split_fee(amount: u64, pieces: u8, fee: impl Fn(u64) -> u64) -> u64 {
+        split(amount, pieces).map(fee).sum()
+    }
+
+    /// The properties `withdraw_fee` checks. Panics on the first one broken.
+    pub fn check(input: &WithdrawFeeInput) {
+        let WithdrawFeeInput {
+            amount,
+            pieces,
+            fee_bps,
+        } = *input;
+        let fee = |amount| {
+            secure_fee(amount, fee_bps)
+                .unwrap_or_else(|e| panic!("secure fee failed ({}): {:?}", e, input))
+        };
+        let charged = fee(amount);
+
+        // Rounded up from the exact share, and never more than the amount
+        let exact = amount as u128 * fee_bps as u128;
+        assert!(
+            charged as u128 * BPS as u128 >= exact,
+            "fee rounded down: {:?}",
+            input
+        );
+        assert!(
+            charged == 0 || (charged as u128 - 1) * (BPS as u128) < exact,
+            "fee rounded up by more than a lamport: {:?}",
+            input
+        );
+        assert!(charged <= amount, "fee above the amount: {:?}", input);
+        if amount > 0 && fee_bps > 0 {
+            assert!(charged > 0, "a withdrawal paid no fee: {:?}", input);
+        }
+
+        // More withdrawn, or a higher rate, never charges less
+        if amount < u64::MAX {
+            assert!(
+                fee(amount + 1) >= charged,
+                "fee fell with the amount: {:?}",
+                input
+            );
+        }
+        if fee_bps < BPS {
+            let pricier = secure_fee(amount, fee_bps + 1).unwrap();
+            assert!(pricier >= charged, "fee fell with the rate: {:?}", input);
+        }
+
+        // Splitting never pays less than withdrawing at once
+        assert!(
+            split_fee(amount, pieces, fee) >= charged,
+            "splitting the withdrawal lowered the fee: {:?}",
+            input
+        );
+
+        if vulnerable_in_range(input) {
+            assert_eq!(
+                vulnerable_fee(amount, fee_bps).map(u128::from),
+                Some(exact / BPS as u128),
+                "vulnerable fee isn't the floor without an overflow: {:?}",
+                input
+            );
+        }
+    }
+
+    /// Whether the vulnerable formula's unchecked product stays in range
+    /// for `input`, in which case it is the exact share rounded down.
+    pub fn vulnerable_in_range(input: &WithdrawFeeInput) -> bool {
+        input.amount.checked_mul(input.fee_bps as u64).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fee::{self, FeeSwapInput};
+    use super::swap::{self, SwapInput};
+    use super::withdraw_fee::{self, WithdrawFeeInput};
+    use super::*;
+
+    #[test]
+    fn test_swap_overflow_case_holds() {
+        let input = SwapInput {
+            amount_in: u64::MAX / 4,
+            min_out: 0,
+            reserve_x: u64::MAX / 2,
+            reserve_y: 1000,
+        };
+        swap::check(&input);
+        // The wrapping version gets it wrong where the secure one doesn't
+        let (wrapped, _, _) =
+            swap::vulnerable_swap(input.amount_in, input.reserve_x, input.reserve_y).unwrap();
+        let (exact, _, _) =
+            swap::secure_swap(input.amount_in, 0, input.reserve_x, input.reserve_y).unwrap();
+        assert_ne!(wrapped, exact);
+    }
+
+    #[test]
+    fn test_fee_input_stays_in_bps_range() {
+        let data = [0xffu8; 64];
+        let input = FeeSwapInput::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!(input.fee <= fee::BPS);
+        fee::check(&input);
+    }
+
+    #[test]
+    fn test_buggy_fee_math_agrees_in_range() {
+        let input = FeeSwapInput {
+            amount_in: 1_000,
+            source_reserve: 1_000_000,
+            dest_reserve: 1_000_000,
+            fee: 30,
+        };
+        assert!(fee::buggy_in_range(&input));
+        assert_eq!(
+            fee::buggy_amount_out(
+                input.amount_in,
+                input.source_reserve,
+                input.dest_reserve,
+                input.fee
+            ),
+            fee::secure_amount_out(
+                input.amount_in,
+                input.source_reserve,
+                input.dest_reserve,
+                input.fee
+            )
+            .ok()
+        );
+        fee::check(&input);
+    }
+
+    #[test]
+    fn test_withdraw_fee_split_case_holds() {
+        let input = WithdrawFeeInput {
+            amount: 33_300,
+            pieces: 100,
+            fee_bps: 30,
+        };
+        withdraw_fee::check(&input);
+        assert_eq!(
+            withdraw_fee::secure_fee(input.amount, input.fee_bps),
+            Ok(100)
+        );
+        // Rounding each piece down loses the whole fee
+        let vulnerable = |amount| withdraw_fee::vulnerable_fee(amount, input.fee_bps).unwrap();
+        assert_eq!(vulnerable(input.amount), 99);
+        assert_eq!(
+            withdraw_fee::split_fee(input.amount, input.pieces, vulnerable),
+            0
+        );
+    }
+
+    #[test]
+    fn test_withdraw_fee_input_stays_in_range() {
+        let data = [0xffu8; 16];
+        let input = WithdrawFeeInput::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!((1..=withdraw_fee::MAX_PIECES).contains(&input.pieces));
+        assert!(input.fee_bps <= withdraw_fee::BPS);
+        withdraw_fee::check(&input);
+    }
+
+    #[test]
+    fn test_corpus_seeds_decode() {
+        let seed = std::fs::read("corpus/swap_math/overflow").unwrap();
+        let input = SwapInput::arbitrary(&mut Unstructured::new(&seed)).unwrap();
+        assert_eq!((input.amount_in, input.reserve_y), (u64::MAX / 4, 1000));
+
+        let seed = std::fs::read("corpus/fee_math/balanced_30bps").unwrap();
+        let input = FeeSwapInput::arbitrary(&mut Unstructured::new(&seed)).unwrap();
+        assert_eq!((input.amount_in, input.fee), (1_000, 30));
+
+        let seed = std::fs::read("corpus/withdraw_fee/split_333_30bps").unwrap();
+        let input = WithdrawFeeInput::arbitrary(&mut Unstructured::new(&seed)).unwrap();
+        assert_eq!(
+            (input.amount, input.pieces, input.fee_bps),
+            (33_300, 100, 30)
+        );
+    }
+
+    fn replay<T: for<'a> Arbitrary<'a>>(target: &str, check: fn(&T)) {
+        let mut paths: Vec<_> = std::fs::read_dir(format!("corpus/{}", target))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "no corpus for {}", target);
+        for path in paths {
+            run_input(&std::fs::read(&path).unwrap(), check);
+        }
+    }
+
+    #[test]
+    fn test_swap_math_corpus_replays() {
+        replay("swap_math", swap::check);
+    }
+
+    #[test]
+    fn test_fee_math_corpus_replays() {
+        replay("fee_math", fee::check);
+    }
+
+    #[test]
+    fn test_withdraw_fee_corpus_replays() {
+        replay("withdraw_fee", withdraw_fee::check);
+    }
+
+    mod swap_math_findings {
+        use super::*;
+
+        fn replay_finding(data: &[u8]) {
+            run_input(data, swap::check);
+        }
+
+        include!(concat!(env!("OUT_DIR"), "/findings_swap_math.rs"));
+    }
+
+    mod fee_math_findings {
+        use super::*;
+
+        fn replay_finding(data: &[u8]) {
+            run_input(data, fee::check);
+        }
+
+        include!(concat!(env!("OUT_DIR"), "/findings_fee_math.rs"));
+    }
+
+    mod withdraw_fee_findings {
+        use super::*;
+
+        fn replay_finding(data: &[u8]) {
+            run_input(data, withdraw_fee::check);
+        }
+
+        include!(concat!(env!("OUT_DIR"), "/findings_withdraw_fee.rs"));
+    }
+}
@@ -0,0 +1,60 @@
+//! `#[test]` stubs for recorded findings
+//!
+//! Build scripts call [`write`] for each target they replay findings of.
+//! It lists `findings/<target>/*.bin` and writes one test per file to
+//! `OUT_DIR/findings_<target>.rs`, which the target's tests pull in with
+//! `include!` next to a `replay_finding(&[u8])` that runs the check:
+//!
+//! ```ignore
+//! fn replay_finding(data: &[u8]) {
+//!     check(&harness, data);
+//! }
+//!
+//! include!(concat!(env!("OUT_DIR"), "/findings_account_meta.rs"));
+//! ```
+//!
+//! Build scripts can't depend on the crate they build, so this file uses
+//! nothing but `std`; `build.rs` includes it by path. The generated file
+//! names `replay_finding` even when it has no tests, so an empty one
+//! doesn't leave the function unused.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Write the stubs for every finding of `target` under `findings` to
+/// `out_dir/findings_<target>.rs`. With no findings the file has no tests,
+/// but it is always written, so the `include!` always finds it.
+pub fn write(findings: &Path, target: &str, out_dir: &Path) {
+    let mut inputs: Vec<_> = std::fs::read_dir(findings.join(target))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "bin"))
+                .collect()
+        })
+        .unwrap_or_default();
+    inputs.sort();
+
+    let mut out = format!(
+        "// Generated from {} by build.rs, one test per finding\n\nconst _: fn(&[u8]) = replay_finding;\n",
+        findings.join(target).display()
+    );
+    for input in &inputs {
+        let stem = input.file_stem().unwrap().to_string_lossy();
+        let name: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        writeln!(
+            out,
+            "\n#[test]\nfn finding_{}() {{\n    replay_finding(include_bytes!({:?}));\n}}",
+            name,
+            input.display().to_string()
+        )
+        .unwrap();
+    }
+
+    let path = out_dir.join(format!("findings_{}.rs", target));
+    std::fs::write(&path, out)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+}
@@ -0,0 +1,185 @@
+//! Property tests for the shared swap, fee and withdrawal fee math
+//!
+//! The fuzz targets search the same functions for crashes; these pin
+//! down algebraic properties at `cargo test` speed, and proptest shrinks a
+//! failure to the smallest input that still breaks it. Reserves below
+//! 2^32 keep the checked math clear of `MathOverflow`, so a property that
+//! holds for valid swaps isn't skipped by a rejection.
+
+use proptest::prelude::*;
+use security_fuzz::fee::{self, FeeSwapInput, BPS};
+use security_fuzz::swap::{self, SwapInput};
+use security_fuzz::withdraw_fee::{self, WithdrawFeeInput, MAX_PIECES};
+
+/// A reserve the pool can hold without overflow in its math
+fn reserve() -> impl Strategy<Value = u64> {
+    1..=u32::MAX as u64
+}
+
+fn fee_bps() -> impl Strategy<Value = u16> {
+    0..=BPS
+}
+
+proptest! {
+    #[test]
+    fn test_swap_never_panics(amount_in: u64, min_out: u64, reserve_x: u64, reserve_y: u64) {
+        let _ = swap::secure_swap(amount_in, min_out, reserve_x, reserve_y);
+        let _ = swap::vulnerable_swap(amount_in, reserve_x, reserve_y);
+        swap::check(&SwapInput { amount_in, min_out, reserve_x, reserve_y });
+    }
+
+    #[test]
+    fn test_swap_rounds_down_from_the_exact_output(
+        amount_in in 1..=u32::MAX as u64,
+        reserve_x in reserve(),
+        reserve_y in reserve(),
+    ) {
+        let (out, new_x, new_y) = swap::secure_swap(amount_in, 0, reserve_x, reserve_y).unwrap();
+        let num = amount_in as u128 * reserve_y as u128;
+        let den = reserve_x as u128 + amount_in as u128;
+        prop_assert!(out as u128 * den <= num);
+        prop_assert!(num < (out as u128 + 1) * den);
+        // So the product of the reserves never shrinks
+        prop_assert!(new_x as u128 * new_y as u128 >= reserve_x as u128 * reserve_y as u128);
+    }
+
+    #[test]
+    fn test_swap_output_grows_with_input(
+        a in 1..=u32::MAX as u64,
+        b in 1..=u32::MAX as u64,
+        reserve_x in reserve(),
+        reserve_y in reserve(),
+    ) {
+        let (small, large) = (a.min(b), a.max(b));
+        let out = |amount| swap::secure_swap(amount, 0, reserve_x, reserve_y).unwrap().0;
+        prop_assert!(out(small) <= out(large));
+        prop_assert!(out(large) < reserve_y);
+    }
+
+    #[test]
+    fn test_swap_back_returns_at_most_the_input(
+        amount_in in 1..=u32::MAX as u64,
+        reserve_x in reserve(),
+        reserve_y in reserve(),
+    ) {
+        let (out, new_x, new_y) = swap::secure_swap(amount_in, 0, reserve_x, reserve_y).unwrap();
+        // Selling the output straight back can't make a profit
+        match swap::secure_swap(out, 0, new_y, new_x) {
+            Ok((back, _, _)) => prop_assert!(back <= amount_in),
+            Err(e) => prop_assert_eq!(e, "InvalidAmount"),
+        }
+    }
+
+    #[test]
+    fn test_swap_honours_min_out(
+        amount_in in 1..=u32::MAX as u64,
+        min_out: u64,
+        reserve_x in reserve(),
+        reserve_y in reserve(),
+    ) {
+        let (exact, _, _) = swap::secure_swap(amount_in, 0, reserve_x, reserve_y).unwrap();
+        match swap::secure_swap(amount_in, min_out, reserve_x, reserve_y) {
+            Ok((out, _, _)) => prop_assert!(out >= min_out && out == exact),
+            Err(e) => {
+                prop_assert_eq!(e, "SlippageExceeded");
+                prop_assert!(exact < min_out);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fee_never_panics(amount_in: u64, source_reserve: u64, dest_reserve: u64, fee in fee_bps()) {
+        let _ = fee::secure_amount_out(amount_in, source_reserve, dest_reserve, fee);
+        let _ = fee::buggy_amount_out(amount_in, source_reserve, dest_reserve, fee);
+        fee::check(&FeeSwapInput { amount_in, source_reserve, dest_reserve, fee });
+    }
+
+    #[test]
+    fn test_fee_output_is_monotonic(
+        a: u32,
+        b: u32,
+        source in reserve(),
+        dest in reserve(),
+        fee in fee_bps(),
+    ) {
+        let out = |amount: u32, fee| {
+            fee::secure_amount_out(amount as u64, source, dest, fee).unwrap()
+        };
+        let (small, large) = (a.min(b), a.max(b));
+        // More in never pays less, a higher fee never pays more
+        prop_assert!(out(small, fee) <= out(large, fee));
+        prop_assert!(out(large, fee) <= dest);
+        if fee < BPS {
+            prop_assert!(out(large, fee + 1) <= out(large, fee));
+        }
+        prop_assert_eq!(out(large, BPS), 0);
+    }
+
+    #[test]
+    fn test_fee_formulas_agree_in_range(
+        amount_in: u32,
+        source in 1..=u16::MAX as u64,
+        dest in 1..=u16::MAX as u64,
+        fee in fee_bps(),
+    ) {
+        let input = FeeSwapInput {
+            amount_in: amount_in as u64,
+            source_reserve: source,
+            dest_reserve: dest,
+            fee,
+        };
+        prop_assert!(fee::buggy_in_range(&input));
+        prop_assert_eq!(
+            fee::buggy_amount_out(input.amount_in, source, dest, fee),
+            fee::secure_amount_out(input.amount_in, source, dest, fee).ok()
+        );
+    }
+
+    #[test]
+    fn test_withdraw_fee_never_panics(amount: u64, pieces in 1..=MAX_PIECES, fee_bps in fee_bps()) {
+        let _ = withdraw_fee::secure_fee(amount, fee_bps);
+        let _ = withdraw_fee::vulnerable_fee(amount, fee_bps);
+        withdraw_fee::check(&WithdrawFeeInput { amount, pieces, fee_bps });
+    }
+
+    #[test]
+    fn test_withdraw_fee_is_monotonic(a: u64, b: u64, fee_bps in fee_bps()) {
+        let fee = |amount, fee_bps| withdraw_fee::secure_fee(amount, fee_bps).unwrap();
+        let (small, large) = (a.min(b), a.max(b));
+        // More withdrawn never charges less, a higher rate never charges less
+        prop_assert!(fee(small, fee_bps) <= fee(large, fee_bps));
+        prop_assert!(fee(large, fee_bps) <= large);
+        if fee_bps < BPS {
+            prop_assert!(fee(large, fee_bps) <= fee(large, fee_bps + 1));
+        }
+        prop_assert_eq!(fee(large, 0), 0);
+        prop_assert_eq!(fee(large, BPS), large);
+    }
+
+    #[test]
+    fn test_withdraw_fee_split_never_pays_less(
+        amount: u64,
+        pieces in 1..=MAX_PIECES,
+        fee_bps in fee_bps(),
+    ) {
+        let fee = |amount| withdraw_fee::secure_fee(amount, fee_bps).unwrap();
+        prop_assert_eq!(withdraw_fee::split(amount, pieces).sum::<u64>(), amount);
+        prop_assert!(withdraw_fee::split_fee(amount, pieces, fee) >= fee(amount));
+    }
+
+    #[test]
+    fn test_vulnerable_withdraw_fee_split_pays_no_fee(
+        piece in 1..=u16::MAX as u64,
+        pieces in 2..=MAX_PIECES,
+        fee_bps in 1..BPS,
+    ) {
+        // Pieces small enough to round down to nothing, adding up to an
+        // amount that does pay
+        let piece = piece.min((BPS as u64 - 1) / fee_bps as u64);
+        prop_assume!(piece > 0);
+        let amount = piece * pieces as u64;
+        let vulnerable = |amount| withdraw_fee::vulnerable_fee(amount, fee_bps).unwrap();
+        prop_assert_eq!(withdraw_fee::split_fee(amount, pieces, vulnerable), 0);
+        prop_assert!(withdraw_fee::secure_fee(amount, fee_bps).unwrap() > 0);
+    }
+}
@@ -0,0 +1,68 @@
+//! Checks over solana-program's `AccountInfo`, which Anchor re-exports, so
+//! the `anchor` and `native` features share these
+
+#[cfg(not(feature = "native"))]
+use anchor_lang::{
+    prelude::{AccountInfo, Rent},
+    solana_program::sysvar::Sysvar,
+};
+#[cfg(feature = "native")]
+use solana_program::{account_info::AccountInfo, rent::Rent, sysvar::Sysvar};
+
+use crate::{AccountValidatable, ClosableAccount, GuardError, GuardedAccount, Result};
+
+impl GuardedAccount for AccountInfo<'_> {
+    fn owner(&self) -> [u8; 32] {
+        self.owner.to_bytes()
+    }
+
+    fn is_signer(&self) -> bool {
+        self.is_signer
+    }
+
+    fn lamports(&self) -> u64 {
+        AccountInfo::lamports(self)
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+        let data = self
+            .try_borrow_data()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&data))
+    }
+
+    fn rent_exempt_minimum(&self) -> Result<u64> {
+        let rent = Rent::get().map_err(|_| GuardError::RentUnavailable)?;
+        Ok(rent.minimum_balance(self.data_len()))
+    }
+}
+
+impl ClosableAccount for AccountInfo<'_> {
+    fn address(&self) -> [u8; 32] {
+        self.key.to_bytes()
+    }
+
+    fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+        let mut data = self
+            .try_borrow_mut_data()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&mut data))
+    }
+
+    fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+        let mut lamports = self
+            .try_borrow_mut_lamports()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&mut lamports))
+    }
+}
+
+impl AccountValidatable for AccountInfo<'_> {
+    fn address(&self) -> [u8; 32] {
+        self.key.to_bytes()
+    }
+
+    fn is_executable(&self) -> bool {
+        self.executable
+    }
+}
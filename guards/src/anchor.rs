@@ -0,0 +1,161 @@
+//! Anchor's errors for the checks, account migration, and Borsh for the
+//! state types
+
+use alloc::vec::Vec;
+use anchor_lang::{
+    context::CpiContext,
+    error::{Error, ErrorCode},
+    prelude::{borsh::maybestd::io, AccountInfo, ProgramError, Rent},
+    solana_program::sysvar::Sysvar,
+    system_program, AccountSerialize, AnchorDeserialize, AnchorSerialize, Discriminator, Owner,
+    Space,
+};
+
+use crate::{
+    check_owner, upgrade, Cursor, GuardError, RateLimiter, ReentrancyGuard, Versioned,
+    MAINNET_CLUSTER_ERROR,
+};
+
+/// Rewrite `account` in `T`'s current layout if it holds an older one, for
+/// a migrate instruction over an `UncheckedAccount`: check it is a `T` this
+/// program owns, [`upgrade`] it, resize it and top up its rent from
+/// `payer`. Returns the upgraded value, or `None` for an account already
+/// current, which is left alone so the instruction can be retried.
+///
+/// Check the account's seeds against the upgraded value before trusting
+/// it as the PDA it claims to be.
+pub fn migrate_account<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> anchor_lang::Result<Option<T>>
+where
+    T: Versioned + AccountSerialize + Discriminator + Owner,
+{
+    check_owner(account, &T::owner())?;
+    let upgraded = {
+        let data = account
+            .try_borrow_data()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        if !data.starts_with(T::DISCRIMINATOR) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        upgrade::<T>(&data)?
+    };
+    let Some(upgraded) = upgraded else {
+        return Ok(None);
+    };
+
+    let mut bytes = Vec::new();
+    upgraded.try_serialize(&mut bytes)?;
+    let rent = Rent::get()?.minimum_balance(bytes.len());
+    let lacking = rent.saturating_sub(AccountInfo::lamports(account));
+    if lacking > 0 {
+        let transfer = system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.clone(), transfer), lacking)?;
+    }
+    account.resize(bytes.len())?;
+    account
+        .try_borrow_mut_data()
+        .map_err(|_| GuardError::AccountBorrowFailed)?
+        .copy_from_slice(&bytes);
+    Ok(Some(upgraded))
+}
+
+// So `#[account]` structs can hold one
+impl AnchorSerialize for ReentrancyGuard {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl AnchorDeserialize for ReentrancyGuard {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        u8::deserialize_reader(reader).map(ReentrancyGuard)
+    }
+}
+
+impl Space for ReentrancyGuard {
+    const INIT_SPACE: usize = ReentrancyGuard::LEN;
+}
+
+impl AnchorSerialize for RateLimiter {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl AnchorDeserialize for RateLimiter {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; RateLimiter::LEN];
+        reader.read_exact(&mut bytes)?;
+        Ok(RateLimiter::from_bytes(&bytes))
+    }
+}
+
+impl Space for RateLimiter {
+    const INIT_SPACE: usize = RateLimiter::LEN;
+}
+
+impl AnchorSerialize for Cursor {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl AnchorDeserialize for Cursor {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; Cursor::LEN];
+        reader.read_exact(&mut bytes)?;
+        Ok(Cursor::from_bytes(&bytes))
+    }
+}
+
+impl Space for Cursor {
+    const INIT_SPACE: usize = Cursor::LEN;
+}
+
+// The errors Anchor's own constraints raise for the same checks
+impl From<GuardError> for Error {
+    fn from(error: GuardError) -> Self {
+        match error {
+            GuardError::MissingSignature => ErrorCode::AccountNotSigner.into(),
+            GuardError::IllegalOwner => ErrorCode::ConstraintOwner.into(),
+            GuardError::InsufficientFunds => ProgramError::InsufficientFunds.into(),
+            GuardError::NotRentExempt => ErrorCode::ConstraintRentExempt.into(),
+            GuardError::NotTokenAccount => ErrorCode::AccountDidNotDeserialize.into(),
+            GuardError::TokenMintMismatch => ErrorCode::ConstraintTokenMint.into(),
+            GuardError::TokenOwnerMismatch => ErrorCode::ConstraintTokenOwner.into(),
+            GuardError::TokenDelegated | GuardError::TokenCloseAuthority => {
+                ProgramError::InvalidAccountData.into()
+            }
+            GuardError::DiscriminatorMismatch => ErrorCode::AccountDiscriminatorMismatch.into(),
+            GuardError::UnexpectedKey => ErrorCode::ConstraintAddress.into(),
+            GuardError::Executable => ErrorCode::ConstraintExecutable.into(),
+            GuardError::OutdatedVersion | GuardError::UnknownVersion => {
+                ErrorCode::AccountDidNotDeserialize.into()
+            }
+            GuardError::AccountBorrowFailed => ProgramError::AccountBorrowFailed.into(),
+            GuardError::RentUnavailable => ProgramError::UnsupportedSysvar.into(),
+            GuardError::SameAccount => ErrorCode::ConstraintClose.into(),
+            GuardError::LamportOverflow => ProgramError::ArithmeticOverflow.into(),
+            // What the runtime would reject the instruction as, if it could
+            GuardError::LamportsNotConserved => ProgramError::InvalidAccountData.into(),
+            // The guard's state is wrong for the call
+            GuardError::Reentered | GuardError::NotEntered => {
+                ProgramError::InvalidAccountData.into()
+            }
+            GuardError::RateLimited => ProgramError::InsufficientFunds.into(),
+            GuardError::CursorComplete => ProgramError::InvalidAccountData.into(),
+            GuardError::EmptyBatch => ProgramError::InvalidArgument.into(),
+            GuardError::UnknownCluster => ErrorCode::AccountNotInitialized.into(),
+            GuardError::MainnetCluster => ProgramError::Custom(MAINNET_CLUSTER_ERROR).into(),
+            GuardError::NotUpgradeAuthority => ProgramError::IncorrectAuthority.into(),
+            GuardError::DataTooShort => ProgramError::AccountDataTooSmall.into(),
+            GuardError::InvalidField => ErrorCode::AccountDidNotDeserialize.into(),
+        }
+    }
+}
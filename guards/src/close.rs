@@ -0,0 +1,209 @@
+//! Closing an account so it can't be revived
+
+use crate::{GuardError, GuardedAccount, Result};
+
+/// What a closed account's data starts with: the marker Anchor used before
+/// 0.30, which no `#[account]` type's discriminator equals.
+pub const CLOSED_ACCOUNT_DISCRIMINATOR: [u8; 8] = [255; 8];
+
+/// An account the executing program may write, for [`close_account`].
+pub trait ClosableAccount: GuardedAccount {
+    fn address(&self) -> [u8; 32];
+    /// Run `f` over the account's data, failing if it's borrowed.
+    fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R>;
+    /// Run `f` over the account's lamports, failing if they're borrowed.
+    fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R>;
+}
+
+impl<T: ClosableAccount + ?Sized> ClosableAccount for &T {
+    fn address(&self) -> [u8; 32] {
+        (**self).address()
+    }
+
+    fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+        (**self).with_data_mut(f)
+    }
+
+    fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+        (**self).with_lamports_mut(f)
+    }
+}
+
+/// Close `target` into `refund_to`: zero its data behind
+/// [`CLOSED_ACCOUNT_DISCRIMINATOR`], then move all of its lamports.
+///
+/// An account left with no lamports is only deleted once the transaction
+/// ends, so a later instruction can fund it again and find whatever data it
+/// had. Zeroed data with the marker deserializes as nothing and can't be
+/// initialized again, because the program still owns it. The data goes
+/// first so no failure leaves the lamports moved and the record readable;
+/// a failure after the debit leaves the lamports unbalanced, which the
+/// runtime rejects.
+pub fn close_account(
+    target: &impl ClosableAccount,
+    refund_to: &impl ClosableAccount,
+) -> Result<()> {
+    // Crediting the target's lamports back to itself, then zeroing them,
+    // would burn them
+    if target.address() == refund_to.address() {
+        return Err(GuardError::SameAccount);
+    }
+    let lamports = target.lamports();
+    let refunded = refund_to
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(GuardError::LamportOverflow)?;
+
+    target.with_data_mut(|data| {
+        data.fill(0);
+        let marker = data.len().min(CLOSED_ACCOUNT_DISCRIMINATOR.len());
+        data[..marker].copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR[..marker]);
+    })?;
+    target.with_lamports_mut(|balance| *balance = 0)?;
+    refund_to.with_lamports_mut(|balance| *balance = refunded)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::cell::{Cell, RefCell};
+    use std::{vec, vec::Vec};
+
+    struct Live {
+        address: [u8; 32],
+        lamports: Cell<u64>,
+        data: RefCell<Vec<u8>>,
+        data_locked: bool,
+        lamports_locked: bool,
+    }
+
+    impl Live {
+        fn new(address: u8, lamports: u64, data: &[u8]) -> Self {
+            Live {
+                address: [address; 32],
+                lamports: Cell::new(lamports),
+                data: RefCell::new(data.to_vec()),
+                data_locked: false,
+                lamports_locked: false,
+            }
+        }
+
+        fn closed(&self) -> bool {
+            let data = self.data.borrow();
+            let marker = data.len().min(8);
+            data[..marker] == CLOSED_ACCOUNT_DISCRIMINATOR[..marker]
+                && data[marker..].iter().all(|byte| *byte == 0)
+        }
+    }
+
+    impl GuardedAccount for Live {
+        fn owner(&self) -> [u8; 32] {
+            [0; 32]
+        }
+
+        fn is_signer(&self) -> bool {
+            false
+        }
+
+        fn lamports(&self) -> u64 {
+            self.lamports.get()
+        }
+
+        fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+            Ok(f(&self.data.borrow()))
+        }
+
+        fn rent_exempt_minimum(&self) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    impl ClosableAccount for Live {
+        fn address(&self) -> [u8; 32] {
+            self.address
+        }
+
+        fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+            if self.data_locked {
+                return Err(GuardError::AccountBorrowFailed);
+            }
+            Ok(f(&mut self.data.borrow_mut()))
+        }
+
+        fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+            if self.lamports_locked {
+                return Err(GuardError::AccountBorrowFailed);
+            }
+            let mut lamports = self.lamports.get();
+            let result = f(&mut lamports);
+            self.lamports.set(lamports);
+            Ok(result)
+        }
+    }
+
+    #[test]
+    fn test_close_tombstones_then_refunds() {
+        let target = Live::new(1, 500, &[7; 50]);
+        let refund_to = Live::new(2, 100, &[]);
+        assert_eq!(close_account(&target, &refund_to), Ok(()));
+        assert!(target.closed());
+        assert_eq!(target.data.borrow().len(), 50);
+        assert_eq!((target.lamports(), refund_to.lamports()), (0, 600));
+
+        // Data shorter than the marker gets as much of it as fits
+        let short = Live::new(3, 5, &[7; 3]);
+        assert_eq!(close_account(&short, &refund_to), Ok(()));
+        assert_eq!(*short.data.borrow(), vec![255; 3]);
+    }
+
+    #[test]
+    fn test_failures_never_leave_lamports_moved_from_a_live_record() {
+        let fresh = || Live::new(1, 500, &[7; 50]);
+        let untouched = |target: &Live, refund_to: &Live| {
+            !target.closed() && target.lamports() == 500 && refund_to.lamports() == 100
+        };
+
+        let target = fresh();
+        let same = fresh();
+        same.lamports.set(100);
+        assert_eq!(close_account(&target, &same), Err(GuardError::SameAccount));
+        assert!(untouched(&target, &same));
+
+        let refund_to = Live::new(2, u64::MAX, &[]);
+        assert_eq!(
+            close_account(&target, &refund_to),
+            Err(GuardError::LamportOverflow)
+        );
+        assert!(!target.closed() && target.lamports() == 500);
+
+        let mut target = fresh();
+        target.data_locked = true;
+        let refund_to = Live::new(2, 100, &[]);
+        assert_eq!(
+            close_account(&target, &refund_to),
+            Err(GuardError::AccountBorrowFailed)
+        );
+        assert!(untouched(&target, &refund_to));
+
+        // Past the data, a failure leaves the record already gone
+        let mut target = fresh();
+        target.lamports_locked = true;
+        assert_eq!(
+            close_account(&target, &refund_to),
+            Err(GuardError::AccountBorrowFailed)
+        );
+        assert!(target.closed() && target.lamports() == 500);
+
+        let target = fresh();
+        let mut refund_to = Live::new(2, 100, &[]);
+        refund_to.lamports_locked = true;
+        assert_eq!(
+            close_account(&target, &refund_to),
+            Err(GuardError::AccountBorrowFailed)
+        );
+        assert!(target.closed() && target.lamports() == 0);
+        assert_eq!(refund_to.lamports(), 100);
+    }
+}
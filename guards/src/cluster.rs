@@ -0,0 +1,324 @@
+//! Which cluster a program is deployed on
+//!
+//! A program can't read the genesis hash, so its upgrade authority records
+//! it at deploy time, from `solana genesis-hash` on the cluster, in an
+//! account only the program writes. Instructions that must never run on
+//! mainnet-beta take that record and [`check_cluster`] it; without one they
+//! refuse to run anywhere, so a deploy that skips the step is safe.
+
+use crate::{AccountValidatable, ClosableAccount, GuardError, GuardedAccount, Result};
+
+/// `5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d`
+pub const MAINNET_BETA_GENESIS_HASH: [u8; 32] = [
+    69, 41, 105, 152, 166, 248, 226, 167, 132, 219, 93, 159, 149, 225, 143, 194, 63, 112, 68, 26,
+    16, 57, 68, 104, 1, 8, 152, 121, 176, 140, 126, 240,
+];
+
+/// `BPFLoaderUpgradeab1e11111111111111111111111`
+pub const BPF_LOADER_UPGRADEABLE_ID: [u8; 32] = [
+    2, 168, 246, 145, 78, 136, 161, 176, 226, 16, 21, 62, 247, 99, 174, 43, 0, 194, 185, 61, 22,
+    193, 36, 210, 192, 83, 122, 16, 4, 128, 0, 0,
+];
+
+/// Seed of the record's PDA under the program
+pub const CLUSTER_SEED: &[u8] = b"cluster";
+
+/// The `Custom` code a refused instruction fails with, in either
+/// framework: past Anchor's own codes and a program's 6000s
+pub const MAINNET_CLUSTER_ERROR: u32 = 7000;
+
+// `UpgradeableLoaderState` in bincode: a u32 tag, then for a program its
+// programdata address, and for programdata the deploy slot and an optional
+// upgrade authority
+const PROGRAM_TAG: u32 = 2;
+const PROGRAMDATA_ADDRESS: core::ops::Range<usize> = 4..36;
+const PROGRAMDATA_TAG: u32 = 3;
+const AUTHORITY_TAG: usize = 12;
+const AUTHORITY: core::ops::Range<usize> = 13..45;
+
+/// The genesis hash of the cluster a program was deployed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClusterRecord {
+    pub genesis_hash: [u8; 32],
+}
+
+impl ClusterRecord {
+    pub const DISCRIMINATOR: [u8; 8] = *b"cluster\0";
+    pub const LEN: usize = 40;
+
+    pub const fn is_mainnet_beta(&self) -> bool {
+        let mut i = 0;
+        while i < 32 {
+            if self.genesis_hash[i] != MAINNET_BETA_GENESIS_HASH[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// The discriminator, then the hash.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        bytes[8..].copy_from_slice(&self.genesis_hash);
+        bytes
+    }
+
+    /// `None` for data that isn't a record.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        let mut genesis_hash = [0; 32];
+        genesis_hash.copy_from_slice(&data[8..]);
+        Some(ClusterRecord { genesis_hash })
+    }
+}
+
+/// `record` is `program_id`'s record of a cluster other than mainnet-beta.
+/// Check its address too: under Anchor with `seeds = [CLUSTER_SEED]`.
+pub fn check_cluster(record: &impl GuardedAccount, program_id: &impl AsRef<[u8]>) -> Result<()> {
+    if record.owner()[..] != *program_id.as_ref() {
+        return Err(GuardError::UnknownCluster);
+    }
+    let record = record
+        .with_data(ClusterRecord::from_bytes)?
+        .ok_or(GuardError::UnknownCluster)?;
+    if record.is_mainnet_beta() {
+        return Err(GuardError::MainnetCluster);
+    }
+    Ok(())
+}
+
+/// `authority` signed and is the upgrade authority of `program`, the
+/// upgradeable program at `program_id` whose programdata is `program_data`.
+pub fn check_upgrade_authority(
+    program: &impl AccountValidatable,
+    program_data: &impl AccountValidatable,
+    authority: &impl AccountValidatable,
+    program_id: &impl AsRef<[u8]>,
+) -> Result<()> {
+    if !authority.is_signer() {
+        return Err(GuardError::MissingSignature);
+    }
+    if program.address()[..] != *program_id.as_ref() {
+        return Err(GuardError::UnexpectedKey);
+    }
+    if program.owner() != BPF_LOADER_UPGRADEABLE_ID
+        || program_data.owner() != BPF_LOADER_UPGRADEABLE_ID
+    {
+        return Err(GuardError::IllegalOwner);
+    }
+    let programdata_address = program_data.address();
+    let linked = program.with_data(|data| {
+        data.len() >= PROGRAMDATA_ADDRESS.end
+            && data[..4] == PROGRAM_TAG.to_le_bytes()
+            && data[PROGRAMDATA_ADDRESS] == programdata_address
+    })?;
+    if !linked {
+        return Err(GuardError::UnexpectedKey);
+    }
+    // An immutable program has no authority to record anything
+    let authority = authority.address();
+    let authorized = program_data.with_data(|data| {
+        data.len() >= AUTHORITY.end
+            && data[..4] == PROGRAMDATA_TAG.to_le_bytes()
+            && data[AUTHORITY_TAG] == 1
+            && data[AUTHORITY] == authority
+    })?;
+    if !authorized {
+        return Err(GuardError::NotUpgradeAuthority);
+    }
+    Ok(())
+}
+
+/// Write `genesis_hash` into `record`, an account of
+/// [`ClusterRecord::LEN`] bytes owned by `program_id`, created in the same
+/// instruction. Check the upgrade authority first.
+pub fn write_cluster_record(
+    record: &impl ClosableAccount,
+    program_id: &impl AsRef<[u8]>,
+    genesis_hash: [u8; 32],
+) -> Result<()> {
+    crate::check_owner(record, program_id)?;
+    let bytes = ClusterRecord { genesis_hash }.to_bytes();
+    record.with_data_mut(|data| {
+        if data.len() != ClusterRecord::LEN {
+            return Err(GuardError::UnknownCluster);
+        }
+        data.copy_from_slice(&bytes);
+        Ok(())
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+    use core::str::FromStr;
+    use solana_pubkey::Pubkey;
+
+    struct Account {
+        address: [u8; 32],
+        owner: [u8; 32],
+        is_signer: bool,
+        lamports: Cell<u64>,
+        data: RefCell<[u8; 64]>,
+        len: usize,
+    }
+
+    impl Account {
+        fn new(address: u8, owner: [u8; 32], data: &[u8]) -> Self {
+            let mut bytes = [0; 64];
+            bytes[..data.len()].copy_from_slice(data);
+            Account {
+                address: [address; 32],
+                owner,
+                is_signer: false,
+                lamports: Cell::new(0),
+                data: RefCell::new(bytes),
+                len: data.len(),
+            }
+        }
+    }
+
+    impl GuardedAccount for Account {
+        fn owner(&self) -> [u8; 32] {
+            self.owner
+        }
+
+        fn is_signer(&self) -> bool {
+            self.is_signer
+        }
+
+        fn lamports(&self) -> u64 {
+            self.lamports.get()
+        }
+
+        fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+            Ok(f(&self.data.borrow()[..self.len]))
+        }
+
+        fn rent_exempt_minimum(&self) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    impl AccountValidatable for Account {
+        fn address(&self) -> [u8; 32] {
+            self.address
+        }
+
+        fn is_executable(&self) -> bool {
+            false
+        }
+    }
+
+    impl ClosableAccount for Account {
+        fn address(&self) -> [u8; 32] {
+            self.address
+        }
+
+        fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+            Ok(f(&mut self.data.borrow_mut()[..self.len]))
+        }
+
+        fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+            let mut lamports = self.lamports.get();
+            let result = f(&mut lamports);
+            self.lamports.set(lamports);
+            Ok(result)
+        }
+    }
+
+    const PROGRAM: [u8; 32] = [1; 32];
+
+    #[test]
+    fn test_constants_match_their_addresses() {
+        let mainnet = Pubkey::from_str("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d").unwrap();
+        assert_eq!(mainnet.to_bytes(), MAINNET_BETA_GENESIS_HASH);
+        let loader = Pubkey::from_str("BPFLoaderUpgradeab1e11111111111111111111111").unwrap();
+        assert_eq!(loader.to_bytes(), BPF_LOADER_UPGRADEABLE_ID);
+    }
+
+    #[test]
+    fn test_refuses_mainnet_and_missing_records() {
+        let devnet = ClusterRecord {
+            genesis_hash: [7; 32],
+        };
+        let mainnet = ClusterRecord {
+            genesis_hash: MAINNET_BETA_GENESIS_HASH,
+        };
+        assert!(!devnet.is_mainnet_beta() && mainnet.is_mainnet_beta());
+        assert_eq!(ClusterRecord::from_bytes(&devnet.to_bytes()), Some(devnet));
+
+        let record = Account::new(2, PROGRAM, &devnet.to_bytes());
+        assert_eq!(check_cluster(&record, &PROGRAM), Ok(()));
+        let record = Account::new(2, PROGRAM, &mainnet.to_bytes());
+        assert_eq!(
+            check_cluster(&record, &PROGRAM),
+            Err(GuardError::MainnetCluster)
+        );
+
+        // Not written yet, someone else's, or not a record at all
+        for record in [
+            Account::new(2, PROGRAM, &[0; ClusterRecord::LEN]),
+            Account::new(2, [3; 32], &devnet.to_bytes()),
+            Account::new(2, PROGRAM, &devnet.to_bytes()[..39]),
+        ] {
+            assert_eq!(
+                check_cluster(&record, &PROGRAM),
+                Err(GuardError::UnknownCluster)
+            );
+        }
+    }
+
+    #[test]
+    fn test_only_the_upgrade_authority_records() {
+        let mut program_bytes = [0; 36];
+        program_bytes[..4].copy_from_slice(&PROGRAM_TAG.to_le_bytes());
+        program_bytes[4..].copy_from_slice(&[4; 32]);
+        let program = Account::new(1, BPF_LOADER_UPGRADEABLE_ID, &program_bytes);
+        let mut programdata_bytes = [0; 45];
+        programdata_bytes[..4].copy_from_slice(&PROGRAMDATA_TAG.to_le_bytes());
+        programdata_bytes[AUTHORITY_TAG] = 1;
+        programdata_bytes[AUTHORITY].copy_from_slice(&[5; 32]);
+        let program_data = Account::new(4, BPF_LOADER_UPGRADEABLE_ID, &programdata_bytes);
+        let mut authority = Account::new(5, [0; 32], &[]);
+        authority.is_signer = true;
+
+        assert_eq!(
+            check_upgrade_authority(&program, &program_data, &authority, &PROGRAM),
+            Ok(())
+        );
+
+        let mut impostor = Account::new(6, [0; 32], &[]);
+        impostor.is_signer = true;
+        assert_eq!(
+            check_upgrade_authority(&program, &program_data, &impostor, &PROGRAM),
+            Err(GuardError::NotUpgradeAuthority)
+        );
+        // Another program's programdata
+        let other = Account::new(6, BPF_LOADER_UPGRADEABLE_ID, &programdata_bytes);
+        assert_eq!(
+            check_upgrade_authority(&program, &other, &authority, &PROGRAM),
+            Err(GuardError::UnexpectedKey)
+        );
+        authority.is_signer = false;
+        assert_eq!(
+            check_upgrade_authority(&program, &program_data, &authority, &PROGRAM),
+            Err(GuardError::MissingSignature)
+        );
+
+        // Written into an account of the program's
+        let record = Account::new(2, PROGRAM, &[0; ClusterRecord::LEN]);
+        assert_eq!(write_cluster_record(&record, &PROGRAM, [7; 32]), Ok(()));
+        assert_eq!(check_cluster(&record, &PROGRAM), Ok(()));
+        let foreign = Account::new(2, [3; 32], &[0; ClusterRecord::LEN]);
+        assert_eq!(
+            write_cluster_record(&foreign, &PROGRAM, [7; 32]),
+            Err(GuardError::IllegalOwner)
+        );
+    }
+}
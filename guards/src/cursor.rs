@@ -0,0 +1,219 @@
+//! Resumable iteration over a collection too large for one transaction
+
+use crate::{GuardError, Result};
+use core::ops::Range;
+
+/// A stored position in a pass over `total` items, handed out at most
+/// `max_per_call` at a time, kept in account state so a crank or batch
+/// payout can walk a list no single transaction could finish.
+///
+/// [`Cursor::batch`] advances the offset as it hands out the range. A
+/// transaction that then runs out of compute units is rolled back with the
+/// account, so the next call starts over at the same offset; callers can
+/// ask for fewer items than `max_per_call` to fit the budget, never more.
+/// The total is fixed when the pass starts, so items appended meanwhile
+/// wait for the next pass instead of keeping this one from finishing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    pub(crate) offset: u64,
+    pub(crate) total: u64,
+    pub(crate) max_per_call: u32,
+    pub(crate) complete: bool,
+}
+
+impl Cursor {
+    pub const LEN: usize = 21;
+
+    /// A pass from the start over `total` items. A `max_per_call` of zero
+    /// is taken as one, so every call makes progress.
+    pub const fn new(total: u64, max_per_call: u32) -> Self {
+        Cursor {
+            offset: 0,
+            total,
+            max_per_call: if max_per_call == 0 { 1 } else { max_per_call },
+            complete: total == 0,
+        }
+    }
+
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub const fn max_per_call(&self) -> u32 {
+        self.max_per_call
+    }
+
+    /// Every item has been handed out.
+    pub const fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub const fn remaining(&self) -> u64 {
+        self.total - self.offset
+    }
+
+    /// The next `limit` items, or `max_per_call` if that is smaller, and
+    /// move past them. Fails once the pass is complete, and for a `limit`
+    /// of zero, without changing anything.
+    pub fn batch(&mut self, limit: u32) -> Result<Range<u64>> {
+        if self.complete {
+            return Err(GuardError::CursorComplete);
+        }
+        let len = u64::from(limit.min(self.max_per_call)).min(self.remaining());
+        if len == 0 {
+            return Err(GuardError::EmptyBatch);
+        }
+        let start = self.offset;
+        self.offset += len;
+        self.complete = self.offset == self.total;
+        Ok(start..self.offset)
+    }
+
+    /// Start a new pass over `total` items, keeping `max_per_call`.
+    pub fn restart(&mut self, total: u64) {
+        *self = Cursor::new(total, self.max_per_call);
+    }
+
+    /// The little-endian layout, for zero-copy state.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[..8].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.total.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.max_per_call.to_le_bytes());
+        bytes[20] = u8::from(self.complete);
+        bytes
+    }
+
+    /// Read a stored cursor. Bytes no [`Cursor`] would write, an offset
+    /// past the total or a completion flag that disagrees with it, come
+    /// back as one that is complete, so a corrupt account stops the pass
+    /// rather than restarting or overrunning it.
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Self {
+        let mut le = [0; 8];
+        le.copy_from_slice(&bytes[..8]);
+        let offset = u64::from_le_bytes(le);
+        le.copy_from_slice(&bytes[8..16]);
+        let total = u64::from_le_bytes(le);
+        let mut le = [0; 4];
+        le.copy_from_slice(&bytes[16..20]);
+        let max_per_call = u32::from_le_bytes(le).max(1);
+        let consistent = offset <= total && bytes[20] == u8::from(offset == total);
+        Cursor {
+            offset: if consistent { offset } else { total },
+            total,
+            max_per_call,
+            complete: !consistent || bytes[20] == 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pay out items until the compute budget runs out, the way a batch
+    // instruction would: an error means the whole call is rolled back
+    fn pay(cursor: &mut Cursor, limit: u32, budget: u64, paid: &mut [u8; 10]) -> Result<()> {
+        // The account as it was before the call
+        let stored = cursor.to_bytes();
+        let mut working = Cursor::from_bytes(&stored);
+        let mut copy = *paid;
+        let mut spent = 0;
+        for i in working.batch(limit)? {
+            spent += 1;
+            if spent > budget {
+                return Err(GuardError::RateLimited);
+            }
+            copy[i as usize] += 1;
+        }
+        *cursor = working;
+        *paid = copy;
+        Ok(())
+    }
+
+    #[test]
+    fn test_batches_cover_the_list_once() {
+        let mut cursor = Cursor::new(10, 4);
+        assert_eq!(cursor.batch(u32::MAX), Ok(0..4));
+        assert_eq!(cursor.batch(2), Ok(4..6));
+        assert_eq!(cursor.batch(u32::MAX), Ok(6..10));
+        assert!(cursor.is_complete());
+        assert_eq!(cursor.remaining(), 0);
+        assert_eq!(cursor.batch(1), Err(GuardError::CursorComplete));
+        assert_eq!(cursor.offset(), 10);
+
+        cursor.restart(3);
+        assert_eq!((cursor.offset(), cursor.max_per_call()), (0, 4));
+        assert_eq!(cursor.batch(u32::MAX), Ok(0..3));
+
+        // An empty list is done before it starts, and a zero limit never
+        // spends a call
+        assert!(Cursor::new(0, 4).is_complete());
+        let mut cursor = Cursor::new(5, 0);
+        assert_eq!(cursor.max_per_call(), 1);
+        assert_eq!(cursor.batch(0), Err(GuardError::EmptyBatch));
+        assert_eq!(cursor.offset(), 0);
+    }
+
+    #[test]
+    fn test_resumes_after_running_out_of_compute() {
+        let mut cursor = Cursor::new(10, 5);
+        let mut paid = [0u8; 10];
+        assert_eq!(pay(&mut cursor, 5, 5, &mut paid), Ok(()));
+
+        // A full batch of five no longer fits: the call fails and neither
+        // the cursor nor any payout moves
+        assert_eq!(
+            pay(&mut cursor, 5, 3, &mut paid),
+            Err(GuardError::RateLimited)
+        );
+        assert_eq!(cursor.offset(), 5);
+        assert_eq!(paid, [1, 1, 1, 1, 1, 0, 0, 0, 0, 0]);
+
+        // Retried with a smaller batch, it picks up at the same item
+        assert_eq!(pay(&mut cursor, 3, 3, &mut paid), Ok(()));
+        assert_eq!(pay(&mut cursor, 3, 3, &mut paid), Ok(()));
+        assert!(cursor.is_complete());
+        assert_eq!(paid, [1; 10]);
+        assert_eq!(
+            pay(&mut cursor, 3, 3, &mut paid),
+            Err(GuardError::CursorComplete)
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut cursor = Cursor::new(1_000, 7);
+        cursor.batch(7).unwrap();
+        assert_eq!(Cursor::from_bytes(&cursor.to_bytes()), cursor);
+        assert_eq!(cursor.to_bytes()[..8], 7u64.to_le_bytes());
+        let done = Cursor::new(0, 7);
+        assert_eq!(Cursor::from_bytes(&done.to_bytes()), done);
+    }
+
+    #[test]
+    fn test_corrupt_bytes_stop_the_pass() {
+        // Offset past the total
+        let mut bytes = Cursor::new(10, 2).to_bytes();
+        bytes[..8].copy_from_slice(&11u64.to_le_bytes());
+        let cursor = Cursor::from_bytes(&bytes);
+        assert!(cursor.is_complete());
+        assert_eq!(cursor.offset(), 10);
+
+        // Marked complete partway, or not complete at the end
+        let mut bytes = Cursor::new(10, 2).to_bytes();
+        bytes[20] = 1;
+        assert!(Cursor::from_bytes(&bytes).is_complete());
+        let mut bytes = Cursor::new(10, 2).to_bytes();
+        bytes[..8].copy_from_slice(&10u64.to_le_bytes());
+        let mut cursor = Cursor::from_bytes(&bytes);
+        assert_eq!(cursor.batch(2), Err(GuardError::CursorComplete));
+        let mut bytes = Cursor::new(10, 2).to_bytes();
+        bytes[20] = 2;
+        assert!(Cursor::from_bytes(&bytes).is_complete());
+    }
+}
@@ -0,0 +1,123 @@
+//! Fields read in place from an account's data
+//!
+//! An Anchor `Account<T>` decodes every field of `T` before the handler
+//! reads one, which across a batch of `remaining_accounts` can cost more
+//! than the checks around it. These read only the bytes at a field's
+//! offset, with the bounds and values Borsh would have checked, so a
+//! handler that validates with them accepts what deserializing would.
+
+use crate::{GuardError, GuardedAccount, Result, Validator};
+
+/// The `N` bytes at `offset` in the account's data.
+pub fn read_field<const N: usize>(account: &impl GuardedAccount, offset: usize) -> Result<[u8; N]> {
+    account.with_data(|data| {
+        offset
+            .checked_add(N)
+            .and_then(|end| data.get(offset..end))
+            .map(|bytes| {
+                let mut field = [0; N];
+                field.copy_from_slice(bytes);
+                field
+            })
+            .ok_or(GuardError::DataTooShort)
+    })?
+}
+
+/// The little-endian `u64` at `offset`.
+pub fn read_u64(account: &impl GuardedAccount, offset: usize) -> Result<u64> {
+    read_field(account, offset).map(u64::from_le_bytes)
+}
+
+/// The `bool` at `offset`; any byte but 0 or 1 fails, as it does in Borsh.
+pub fn read_bool(account: &impl GuardedAccount, offset: usize) -> Result<bool> {
+    match read_field::<1>(account, offset)? {
+        [0] => Ok(false),
+        [1] => Ok(true),
+        _ => Err(GuardError::InvalidField),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MinLen(usize);
+
+/// The account's data holds at least `len` bytes, such as `8 + INIT_SPACE`
+/// for an Anchor account, which Borsh needs to decode its last field.
+pub fn min_len(len: usize) -> MinLen {
+    MinLen(len)
+}
+
+impl Validator for MinLen {
+    fn validate(&self, account: &impl crate::AccountValidatable) -> Result<()> {
+        if account.with_data(|data| data.len())? < self.0 {
+            return Err(GuardError::DataTooShort);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AccountValidatable;
+
+    struct Account(&'static [u8]);
+
+    impl GuardedAccount for Account {
+        fn owner(&self) -> [u8; 32] {
+            [0; 32]
+        }
+
+        fn is_signer(&self) -> bool {
+            false
+        }
+
+        fn lamports(&self) -> u64 {
+            0
+        }
+
+        fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+            Ok(f(self.0))
+        }
+
+        fn rent_exempt_minimum(&self) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    impl AccountValidatable for Account {
+        fn address(&self) -> [u8; 32] {
+            [0; 32]
+        }
+
+        fn is_executable(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_reads_fields_within_the_data() {
+        let account = Account(&[9, 1, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(read_bool(&account, 1), Ok(true));
+        assert_eq!(read_bool(&account, 2), Ok(false));
+        assert_eq!(read_u64(&account, 3), Ok(2));
+        assert_eq!(read_field(&account, 0), Ok([9, 1]));
+
+        assert_eq!(read_bool(&account, 0), Err(GuardError::InvalidField));
+        assert_eq!(read_u64(&account, 4), Err(GuardError::DataTooShort));
+        assert_eq!(read_bool(&account, 11), Err(GuardError::DataTooShort));
+        assert_eq!(
+            read_field::<2>(&account, usize::MAX),
+            Err(GuardError::DataTooShort)
+        );
+    }
+
+    #[test]
+    fn test_min_len() {
+        let account = Account(&[0; 42]);
+        assert_eq!(min_len(42).validate(&account), Ok(()));
+        assert_eq!(
+            min_len(43).validate(&account),
+            Err(GuardError::DataTooShort)
+        );
+    }
+}
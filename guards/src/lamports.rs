@@ -0,0 +1,208 @@
+//! Moving lamports by hand between accounts the program owns
+
+use crate::{check_rent_exempt_after, ClosableAccount, GuardError, GuardedAccount, Result};
+
+/// Take `amount` lamports from `account`, leaving it rent exempt. Close the
+/// account with [`close_account`](crate::close_account) to empty it.
+pub fn debit_lamports(account: &impl ClosableAccount, amount: u64) -> Result<()> {
+    check_rent_exempt_after(account, amount)?;
+    account.with_lamports_mut(|balance| {
+        *balance = balance
+            .checked_sub(amount)
+            .ok_or(GuardError::InsufficientFunds)?;
+        Ok(())
+    })?
+}
+
+/// Give `account` `amount` more lamports.
+pub fn credit_lamports(account: &impl ClosableAccount, amount: u64) -> Result<()> {
+    account.with_lamports_mut(|balance| {
+        *balance = balance
+            .checked_add(amount)
+            .ok_or(GuardError::LamportOverflow)?;
+        Ok(())
+    })?
+}
+
+/// Move `amount` lamports from `from` to `to`, leaving `from` rent exempt.
+///
+/// The credit is checked before anything moves, so a refused transfer
+/// changes neither balance, and the two balances are checked to sum to
+/// what they did before.
+pub fn transfer_lamports(
+    from: &impl ClosableAccount,
+    to: &impl ClosableAccount,
+    amount: u64,
+) -> Result<()> {
+    to.lamports()
+        .checked_add(amount)
+        .ok_or(GuardError::LamportOverflow)?;
+    let before = from.lamports() as u128 + to.lamports() as u128;
+    debit_lamports(from, amount)?;
+    credit_lamports(to, amount)?;
+    if from.lamports() as u128 + to.lamports() as u128 != before {
+        return Err(GuardError::LamportsNotConserved);
+    }
+    Ok(())
+}
+
+/// The lamports held across a set of accounts, taken before an instruction
+/// moves any so it can [`check`](Self::check) that it minted or burned none.
+///
+/// The runtime rejects an unbalanced instruction too, but only once it
+/// returns, across every account, and without saying which transfer was
+/// wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LamportSnapshot(u128);
+
+impl LamportSnapshot {
+    pub fn take<A: GuardedAccount>(accounts: impl IntoIterator<Item = A>) -> Self {
+        LamportSnapshot(total(accounts))
+    }
+
+    /// The same accounts still hold the same lamports between them.
+    pub fn check<A: GuardedAccount>(&self, accounts: impl IntoIterator<Item = A>) -> Result<()> {
+        if total(accounts) != self.0 {
+            return Err(GuardError::LamportsNotConserved);
+        }
+        Ok(())
+    }
+}
+
+fn total<A: GuardedAccount>(accounts: impl IntoIterator<Item = A>) -> u128 {
+    // There are fewer than 2^64 accounts, so the sum fits
+    accounts
+        .into_iter()
+        .map(|account| account.lamports() as u128)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    struct Live {
+        address: [u8; 32],
+        lamports: Cell<u64>,
+        minimum: u64,
+        locked: bool,
+    }
+
+    impl Live {
+        fn new(address: u8, lamports: u64, minimum: u64) -> Self {
+            Live {
+                address: [address; 32],
+                lamports: Cell::new(lamports),
+                minimum,
+                locked: false,
+            }
+        }
+    }
+
+    impl GuardedAccount for Live {
+        fn owner(&self) -> [u8; 32] {
+            [0; 32]
+        }
+
+        fn is_signer(&self) -> bool {
+            false
+        }
+
+        fn lamports(&self) -> u64 {
+            self.lamports.get()
+        }
+
+        fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+            Ok(f(&[]))
+        }
+
+        fn rent_exempt_minimum(&self) -> Result<u64> {
+            Ok(self.minimum)
+        }
+    }
+
+    impl ClosableAccount for Live {
+        fn address(&self) -> [u8; 32] {
+            self.address
+        }
+
+        fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+            Ok(f(&mut []))
+        }
+
+        fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+            if self.locked {
+                return Err(GuardError::AccountBorrowFailed);
+            }
+            let mut lamports = self.lamports.get();
+            let result = f(&mut lamports);
+            self.lamports.set(lamports);
+            Ok(result)
+        }
+    }
+
+    #[test]
+    fn test_debit_and_credit_are_checked() {
+        let vault = Live::new(1, 1_000, 900);
+        assert_eq!(debit_lamports(&vault, 100), Ok(()));
+        assert_eq!(vault.lamports(), 900);
+        assert_eq!(debit_lamports(&vault, 1), Err(GuardError::NotRentExempt));
+        assert_eq!(
+            debit_lamports(&vault, u64::MAX),
+            Err(GuardError::InsufficientFunds)
+        );
+        assert_eq!(vault.lamports(), 900);
+
+        assert_eq!(credit_lamports(&vault, 50), Ok(()));
+        assert_eq!(
+            credit_lamports(&vault, u64::MAX),
+            Err(GuardError::LamportOverflow)
+        );
+        assert_eq!(vault.lamports(), 950);
+    }
+
+    #[test]
+    fn test_refused_transfers_move_nothing() {
+        let vault = Live::new(1, 1_000, 900);
+        let user = Live::new(2, 5, 0);
+        assert_eq!(transfer_lamports(&vault, &user, 100), Ok(()));
+        assert_eq!((vault.lamports(), user.lamports()), (900, 105));
+
+        assert_eq!(
+            transfer_lamports(&vault, &user, 1),
+            Err(GuardError::NotRentExempt)
+        );
+        let full = Live::new(3, u64::MAX, 0);
+        assert_eq!(
+            transfer_lamports(&user, &full, 1),
+            Err(GuardError::LamportOverflow)
+        );
+        let mut locked = Live::new(4, 1_000, 0);
+        locked.locked = true;
+        assert_eq!(
+            transfer_lamports(&locked, &user, 1),
+            Err(GuardError::AccountBorrowFailed)
+        );
+        assert_eq!((vault.lamports(), user.lamports()), (900, 105));
+        assert_eq!((full.lamports(), locked.lamports()), (u64::MAX, 1_000));
+
+        // Paying an account into itself is a no-op, not a mint
+        assert_eq!(transfer_lamports(&user, &user, 100), Ok(()));
+        assert_eq!(user.lamports(), 105);
+    }
+
+    #[test]
+    fn test_snapshot_catches_minted_lamports() {
+        let (vault, user) = (Live::new(1, 1_000, 0), Live::new(2, u64::MAX, 0));
+        let snapshot = LamportSnapshot::take([&vault, &user]);
+        assert_eq!(transfer_lamports(&user, &vault, 500), Ok(()));
+        assert_eq!(snapshot.check([&vault, &user]), Ok(()));
+
+        vault.lamports.set(vault.lamports() + 1);
+        assert_eq!(
+            snapshot.check([&vault, &user]),
+            Err(GuardError::LamportsNotConserved)
+        );
+    }
+}
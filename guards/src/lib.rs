@@ -0,0 +1,420 @@
+//! Runtime account checks for the secure programs
+//!
+//! Anchor's account types check signers, owners and token accounts before
+//! the handler runs; a Pinocchio program, or an Anchor handler reading
+//! `remaining_accounts`, has to write each check by hand, and a forgotten
+//! one is the bug in `owner-check`, `signer-authorization` and
+//! `remaining-accounts`. The macros here are those checks, each returning
+//! early with a [`GuardError`] the way Anchor's `require!` does:
+//!
+//! ```ignore
+//! require_owner!(config, program_id);
+//! require_signer!(caller);
+//! require_rent_exempt_after!(vault, amount);
+//! require_token_account!(vault, &mint, &config_pda, MyError::WrongVault);
+//! ```
+//!
+//! Each takes an optional last argument, the program's own error, to return
+//! instead. For an account that has to pass several checks, such as each of
+//! `remaining_accounts`, compose a [`Validator`] from [`owned_by`],
+//! [`discriminator_is`], [`key_in`], [`not_executable`] and [`min_len`] and
+//! check it with `require_valid!`, then read the fields it needs in place
+//! with [`read_field`] rather than deserializing the whole account. They run against any [`GuardedAccount`]: the `pinocchio`
+//! feature implements it for `pinocchio::AccountView`, the `anchor`
+//! feature for `anchor_lang::prelude::AccountInfo` and the `native` feature
+//! for the same `AccountInfo` from `solana-program`, with `pinocchio-09`
+//! covering Pinocchio 0.9's `AccountInfo`, and each converts
+//! [`GuardError`] into that framework's error. The `check_*` functions behind the macros
+//! return a `Result` for callers that want to handle the failure.
+//!
+//! [`close_account`] closes an account by hand in the order that keeps it
+//! from being revived, for programs that can't leave it to Anchor's
+//! `close` constraint, and [`transfer_lamports`] moves lamports between a
+//! program's own accounts with checked math, keeping the payer rent exempt
+//! and the total unchanged. [`ReentrancyGuard`] is a state field that
+//! refuses a call back in while a CPI to untrusted code is running, and
+//! [`RateLimiter`] a per-slot budget for withdrawals or rewards. A
+//! [`Cursor`] walks a list in bounded batches across transactions.
+//! [`Versioned`] state records its layout version, so a layout change can
+//! migrate old accounts rather than misread them. [`check_cluster`] keeps
+//! an instruction off mainnet-beta, from a [`ClusterRecord`] the upgrade
+//! authority writes at deploy time.
+
+#![no_std]
+
+// `anchor-031` swaps in Anchor 0.31 under the same name
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+#[cfg(feature = "anchor")]
+extern crate alloc;
+
+#[cfg(any(feature = "anchor", feature = "native"))]
+mod account_info;
+#[cfg(feature = "anchor")]
+mod anchor;
+mod close;
+mod cluster;
+mod cursor;
+mod field;
+mod lamports;
+#[cfg(feature = "native")]
+mod native;
+#[cfg(feature = "pinocchio")]
+mod pinocchio;
+#[cfg(feature = "pinocchio-09")]
+pub mod pinocchio_09;
+mod rate_limit;
+mod reentrancy;
+mod token;
+mod validate;
+mod version;
+
+#[cfg(feature = "anchor")]
+pub use anchor::migrate_account;
+pub use close::{close_account, ClosableAccount, CLOSED_ACCOUNT_DISCRIMINATOR};
+pub use cluster::{
+    check_cluster, check_upgrade_authority, write_cluster_record, ClusterRecord,
+    BPF_LOADER_UPGRADEABLE_ID, CLUSTER_SEED, MAINNET_BETA_GENESIS_HASH, MAINNET_CLUSTER_ERROR,
+};
+pub use cursor::Cursor;
+pub use field::{min_len, read_bool, read_field, read_u64, MinLen};
+pub use lamports::{credit_lamports, debit_lamports, transfer_lamports, LamportSnapshot};
+#[cfg(feature = "native")]
+pub use native::{check_cluster_account, record_cluster};
+pub use rate_limit::RateLimiter;
+pub use reentrancy::ReentrancyGuard;
+pub use token::{
+    check_close_authority, check_no_delegate, check_token_account, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+};
+pub use validate::{
+    discriminator_is, key_in, not_executable, owned_by, AccountValidatable, And, DiscriminatorIs,
+    KeyIn, NotExecutable, OwnedBy, Validator,
+};
+pub use version::{account_version, check_version, upgrade, Versioned, VERSION_OFFSET};
+
+/// Which check an account failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GuardError {
+    /// The account didn't sign the transaction
+    MissingSignature,
+    /// The account is owned by a program other than the expected one
+    IllegalOwner,
+    /// The account holds fewer lamports than are being taken from it
+    InsufficientFunds,
+    /// The account would be left below the rent-exempt minimum
+    NotRentExempt,
+    /// The account isn't an initialized SPL Token or Token-2022 account
+    NotTokenAccount,
+    /// The token account holds a different mint
+    TokenMintMismatch,
+    /// The token account belongs to a different owner
+    TokenOwnerMismatch,
+    /// The token account has a delegate who can move its tokens
+    TokenDelegated,
+    /// The token account's close authority isn't the expected one
+    TokenCloseAuthority,
+    /// The account's data doesn't start with the expected discriminator
+    DiscriminatorMismatch,
+    /// The account's address isn't one of those expected
+    UnexpectedKey,
+    /// The account is a program where data was expected
+    Executable,
+    /// The account holds an older layout than the program reads, and has
+    /// to be migrated first
+    OutdatedVersion,
+    /// The account's layout version is one the program can't read
+    UnknownVersion,
+    /// The account's data is already mutably borrowed
+    AccountBorrowFailed,
+    /// The rent sysvar couldn't be read
+    RentUnavailable,
+    /// An account was closed into itself
+    SameAccount,
+    /// The refund would overflow the receiving account's lamports
+    LamportOverflow,
+    /// Accounts hold a different total of lamports than before a transfer
+    LamportsNotConserved,
+    /// A [`ReentrancyGuard`] was entered while already entered
+    Reentered,
+    /// A [`ReentrancyGuard`] was exited without being entered
+    NotEntered,
+    /// A [`RateLimiter`] has less left than was asked for
+    RateLimited,
+    /// A [`Cursor`] has handed out every item in its pass
+    CursorComplete,
+    /// A [`Cursor`] was asked for a batch of no items
+    EmptyBatch,
+    /// The program has no [`ClusterRecord`] of where it is deployed
+    UnknownCluster,
+    /// The program is deployed on mainnet-beta
+    MainnetCluster,
+    /// The signer isn't the program's upgrade authority
+    NotUpgradeAuthority,
+    /// The account's data ends before the field being read
+    DataTooShort,
+    /// A field holds bytes that aren't a value of its type
+    InvalidField,
+}
+
+impl core::fmt::Display for GuardError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            GuardError::MissingSignature => "account did not sign",
+            GuardError::IllegalOwner => "account has the wrong owner",
+            GuardError::InsufficientFunds => "account has too few lamports",
+            GuardError::NotRentExempt => "account would not be rent exempt",
+            GuardError::NotTokenAccount => "account is not a token account",
+            GuardError::TokenMintMismatch => "token account has the wrong mint",
+            GuardError::TokenOwnerMismatch => "token account has the wrong owner",
+            GuardError::TokenDelegated => "token account has a delegate",
+            GuardError::TokenCloseAuthority => "token account has the wrong close authority",
+            GuardError::DiscriminatorMismatch => "account has the wrong discriminator",
+            GuardError::UnexpectedKey => "account is not an expected address",
+            GuardError::Executable => "account is executable",
+            GuardError::OutdatedVersion => "account layout needs migrating",
+            GuardError::UnknownVersion => "unknown account layout version",
+            GuardError::AccountBorrowFailed => "account data is already borrowed",
+            GuardError::RentUnavailable => "rent sysvar unavailable",
+            GuardError::SameAccount => "account closed into itself",
+            GuardError::LamportOverflow => "lamport balance overflow",
+            GuardError::LamportsNotConserved => "lamports minted or burned",
+            GuardError::Reentered => "reentrant call",
+            GuardError::NotEntered => "reentrancy guard exited without entering",
+            GuardError::RateLimited => "rate limit exceeded",
+            GuardError::CursorComplete => "cursor pass already complete",
+            GuardError::EmptyBatch => "cursor batch is empty",
+            GuardError::UnknownCluster => "no record of the program's cluster",
+            GuardError::MainnetCluster => "refused on mainnet-beta",
+            GuardError::NotUpgradeAuthority => "signer is not the upgrade authority",
+            GuardError::DataTooShort => "account data too short",
+            GuardError::InvalidField => "invalid field value",
+        })
+    }
+}
+
+pub type Result<T> = core::result::Result<T, GuardError>;
+
+/// The parts of an account the checks read, over each framework's type.
+pub trait GuardedAccount {
+    fn owner(&self) -> [u8; 32];
+    fn is_signer(&self) -> bool;
+    fn lamports(&self) -> u64;
+    /// Run `f` over the account's data, failing if it's mutably borrowed.
+    fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R>;
+    /// The rent-exempt minimum for an account of this one's size.
+    fn rent_exempt_minimum(&self) -> Result<u64>;
+}
+
+impl<T: GuardedAccount + ?Sized> GuardedAccount for &T {
+    fn owner(&self) -> [u8; 32] {
+        (**self).owner()
+    }
+
+    fn is_signer(&self) -> bool {
+        (**self).is_signer()
+    }
+
+    fn lamports(&self) -> u64 {
+        (**self).lamports()
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+        (**self).with_data(f)
+    }
+
+    fn rent_exempt_minimum(&self) -> Result<u64> {
+        (**self).rent_exempt_minimum()
+    }
+}
+
+/// The account signed the transaction.
+pub fn check_signer(account: &impl GuardedAccount) -> Result<()> {
+    if !account.is_signer() {
+        return Err(GuardError::MissingSignature);
+    }
+    Ok(())
+}
+
+/// The account is owned by `owner`, usually the executing program. Check
+/// this before reading the account's data.
+pub fn check_owner(account: &impl GuardedAccount, owner: &impl AsRef<[u8]>) -> Result<()> {
+    if account.owner()[..] != *owner.as_ref() {
+        return Err(GuardError::IllegalOwner);
+    }
+    Ok(())
+}
+
+/// Taking `delta` lamports from the account leaves it rent exempt. Close
+/// an account to empty it instead.
+pub fn check_rent_exempt_after(account: &impl GuardedAccount, delta: u64) -> Result<()> {
+    let remaining = account
+        .lamports()
+        .checked_sub(delta)
+        .ok_or(GuardError::InsufficientFunds)?;
+    if remaining < account.rent_exempt_minimum()? {
+        return Err(GuardError::NotRentExempt);
+    }
+    Ok(())
+}
+
+/// Returns early unless the account signed the transaction.
+#[macro_export]
+macro_rules! require_signer {
+    ($account:expr $(, $error:expr)? $(,)?) => {
+        $crate::__require!($crate::check_signer(&$account) $(, $error)?)
+    };
+}
+
+/// Returns early unless the account is owned by the given program.
+#[macro_export]
+macro_rules! require_owner {
+    ($account:expr, $owner:expr $(, $error:expr)? $(,)?) => {
+        $crate::__require!($crate::check_owner(&$account, $owner) $(, $error)?)
+    };
+}
+
+/// Returns early unless the account stays rent exempt after giving up
+/// the given lamports.
+#[macro_export]
+macro_rules! require_rent_exempt_after {
+    ($account:expr, $delta:expr $(, $error:expr)? $(,)?) => {
+        $crate::__require!($crate::check_rent_exempt_after(&$account, $delta) $(, $error)?)
+    };
+}
+
+/// Returns early unless the account is a token account for the given mint
+/// and owner.
+#[macro_export]
+macro_rules! require_token_account {
+    ($account:expr, $mint:expr, $owner:expr $(, $error:expr)? $(,)?) => {
+        $crate::__require!($crate::check_token_account(&$account, $mint, $owner) $(, $error)?)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require {
+    ($check:expr) => {
+        if let ::core::result::Result::Err(error) = $check {
+            return ::core::result::Result::Err(error.into());
+        }
+    };
+    ($check:expr, $error:expr) => {
+        if $check.is_err() {
+            return ::core::result::Result::Err($error.into());
+        }
+    };
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// An account with fixed fields, rent at the mainnet default
+    pub struct Mock {
+        pub owner: [u8; 32],
+        pub is_signer: bool,
+        pub lamports: u64,
+        pub data: &'static [u8],
+        pub executable: bool,
+    }
+
+    impl Mock {
+        pub fn new(owner: [u8; 32]) -> Self {
+            Mock {
+                owner,
+                is_signer: false,
+                lamports: 0,
+                data: &[],
+                executable: false,
+            }
+        }
+    }
+
+    impl GuardedAccount for Mock {
+        fn owner(&self) -> [u8; 32] {
+            self.owner
+        }
+
+        fn is_signer(&self) -> bool {
+            self.is_signer
+        }
+
+        fn lamports(&self) -> u64 {
+            self.lamports
+        }
+
+        fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+            Ok(f(self.data))
+        }
+
+        fn rent_exempt_minimum(&self) -> Result<u64> {
+            Ok((128 + self.data.len() as u64) * 3480 * 2)
+        }
+    }
+
+    impl AccountValidatable for Mock {
+        fn address(&self) -> [u8; 32] {
+            [0; 32]
+        }
+
+        fn is_executable(&self) -> bool {
+            self.executable
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum ProgramError {
+        Guard(GuardError),
+        Custom,
+    }
+
+    impl From<GuardError> for ProgramError {
+        fn from(error: GuardError) -> Self {
+            ProgramError::Guard(error)
+        }
+    }
+
+    fn guarded(account: &Mock, owner: [u8; 32]) -> core::result::Result<(), ProgramError> {
+        require_owner!(account, &owner);
+        require_signer!(account, ProgramError::Custom);
+        Ok(())
+    }
+
+    #[test]
+    fn test_macros_return_the_guard_or_given_error() {
+        let mut account = Mock::new([1; 32]);
+        assert_eq!(
+            guarded(&account, [2; 32]),
+            Err(ProgramError::Guard(GuardError::IllegalOwner))
+        );
+        assert_eq!(guarded(&account, [1; 32]), Err(ProgramError::Custom));
+        account.is_signer = true;
+        assert_eq!(guarded(&account, [1; 32]), Ok(()));
+        assert_eq!(check_signer(&&account), Ok(()));
+    }
+
+    #[test]
+    fn test_rent_exempt_after_keeps_the_minimum() {
+        let mut account = Mock::new([1; 32]);
+        account.data = &[0; 41];
+        let minimum = account.rent_exempt_minimum().unwrap();
+        account.lamports = minimum + 10;
+        assert_eq!(check_rent_exempt_after(&account, 10), Ok(()));
+        assert_eq!(
+            check_rent_exempt_after(&account, 11),
+            Err(GuardError::NotRentExempt)
+        );
+        // Emptying the account is a close, not a withdrawal
+        assert_eq!(
+            check_rent_exempt_after(&account, minimum + 10),
+            Err(GuardError::NotRentExempt)
+        );
+        assert_eq!(
+            check_rent_exempt_after(&account, u64::MAX),
+            Err(GuardError::InsufficientFunds)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! solana-program's errors for the checks, and the cluster record, for the
+//! native programs
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::{
+    check_cluster, check_upgrade_authority, write_cluster_record, ClusterRecord, GuardError,
+    CLUSTER_SEED, MAINNET_CLUSTER_ERROR,
+};
+
+/// A native program's `record_cluster` instruction, what
+/// `solsec_prelude::record_cluster!` gives an Anchor one: the upgrade
+/// authority creates the program's [`ClusterRecord`] for the cluster with
+/// `genesis_hash`, once.
+///
+/// Accounts: authority (signer, writable), program, program data, cluster
+/// (writable), system program
+pub fn record_cluster(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    genesis_hash: [u8; 32],
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let authority = next_account_info(accounts)?;
+    let program = next_account_info(accounts)?;
+    let program_data = next_account_info(accounts)?;
+    let cluster = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    check_upgrade_authority(program, program_data, authority, program_id)?;
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (address, bump) = Pubkey::find_program_address(&[CLUSTER_SEED], program_id);
+    if address != *cluster.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    create_record(authority, cluster, &[CLUSTER_SEED, &[bump]], program_id)?;
+    write_cluster_record(cluster, program_id, genesis_hash)?;
+    Ok(())
+}
+
+/// [`check_cluster`] for a native program's `cluster` account, which must
+/// also sit at the `seeds = [CLUSTER_SEED]` address.
+pub fn check_cluster_account(record: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    let (address, _) = Pubkey::find_program_address(&[CLUSTER_SEED], program_id);
+    if address != *record.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    check_cluster(record, program_id)?;
+    Ok(())
+}
+
+// `init` on the record's PDA: anyone can fund its address first, which
+// `create_account` would refuse, so a funded address is topped up,
+// allocated and assigned instead
+fn create_record<'info>(
+    payer: &AccountInfo<'info>,
+    record: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(ClusterRecord::LEN);
+    let lamports = record.lamports();
+    if lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                record.key,
+                rent,
+                ClusterRecord::LEN as u64,
+                program_id,
+            ),
+            &[payer.clone(), record.clone()],
+            &[seeds],
+        );
+    }
+
+    // Recorded already
+    if *record.owner != system_program::ID || !record.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if lamports < rent {
+        invoke(
+            &system_instruction::transfer(payer.key, record.key, rent - lamports),
+            &[payer.clone(), record.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(record.key, ClusterRecord::LEN as u64),
+        core::slice::from_ref(record),
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(record.key, program_id),
+        core::slice::from_ref(record),
+        &[seeds],
+    )
+}
+
+// The same codes as under Pinocchio, so a native and a Pinocchio port fail
+// the same way
+impl From<GuardError> for ProgramError {
+    fn from(error: GuardError) -> Self {
+        match error {
+            GuardError::MissingSignature => ProgramError::MissingRequiredSignature,
+            GuardError::IllegalOwner => ProgramError::IllegalOwner,
+            GuardError::InsufficientFunds => ProgramError::InsufficientFunds,
+            GuardError::NotRentExempt => ProgramError::AccountNotRentExempt,
+            GuardError::NotTokenAccount
+            | GuardError::TokenMintMismatch
+            | GuardError::TokenOwnerMismatch
+            | GuardError::TokenDelegated
+            | GuardError::TokenCloseAuthority
+            | GuardError::DiscriminatorMismatch
+            | GuardError::Executable
+            | GuardError::OutdatedVersion
+            | GuardError::UnknownVersion => ProgramError::InvalidAccountData,
+            GuardError::UnexpectedKey => ProgramError::InvalidArgument,
+            GuardError::AccountBorrowFailed => ProgramError::AccountBorrowFailed,
+            GuardError::RentUnavailable => ProgramError::UnsupportedSysvar,
+            GuardError::SameAccount => ProgramError::InvalidArgument,
+            GuardError::LamportOverflow => ProgramError::ArithmeticOverflow,
+            GuardError::LamportsNotConserved => ProgramError::InvalidAccountData,
+            // The guard's state is wrong for the call
+            GuardError::Reentered | GuardError::NotEntered => ProgramError::InvalidAccountData,
+            GuardError::RateLimited => ProgramError::InsufficientFunds,
+            GuardError::CursorComplete => ProgramError::InvalidAccountData,
+            GuardError::EmptyBatch => ProgramError::InvalidArgument,
+            GuardError::UnknownCluster => ProgramError::UninitializedAccount,
+            GuardError::MainnetCluster => ProgramError::Custom(MAINNET_CLUSTER_ERROR),
+            GuardError::NotUpgradeAuthority => ProgramError::IncorrectAuthority,
+            GuardError::DataTooShort => ProgramError::AccountDataTooSmall,
+            GuardError::InvalidField => ProgramError::InvalidAccountData,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Checks over Pinocchio's `AccountView`
+
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView,
+};
+
+use crate::{
+    AccountValidatable, ClosableAccount, GuardError, GuardedAccount, Result, MAINNET_CLUSTER_ERROR,
+};
+
+impl GuardedAccount for AccountView {
+    fn owner(&self) -> [u8; 32] {
+        // SAFETY: the owner is copied out before anything can reassign it
+        unsafe { AccountView::owner(self) }.to_bytes()
+    }
+
+    fn is_signer(&self) -> bool {
+        AccountView::is_signer(self)
+    }
+
+    fn lamports(&self) -> u64 {
+        AccountView::lamports(self)
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+        let data = self
+            .try_borrow()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&data))
+    }
+
+    fn rent_exempt_minimum(&self) -> Result<u64> {
+        let rent = Rent::get().map_err(|_| GuardError::RentUnavailable)?;
+        rent.try_minimum_balance(self.data_len())
+            .map_err(|_| GuardError::RentUnavailable)
+    }
+}
+
+impl ClosableAccount for AccountView {
+    fn address(&self) -> [u8; 32] {
+        AccountView::address(self).to_bytes()
+    }
+
+    fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+        let mut data = self
+            .try_borrow_mut()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&mut data))
+    }
+
+    fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+        let mut lamports = AccountView::lamports(self);
+        let result = f(&mut lamports);
+        self.set_lamports(lamports);
+        Ok(result)
+    }
+}
+
+impl AccountValidatable for AccountView {
+    fn address(&self) -> [u8; 32] {
+        AccountView::address(self).to_bytes()
+    }
+
+    fn is_executable(&self) -> bool {
+        AccountView::executable(self)
+    }
+}
+
+impl From<GuardError> for ProgramError {
+    fn from(error: GuardError) -> Self {
+        match error {
+            GuardError::MissingSignature => ProgramError::MissingRequiredSignature,
+            GuardError::IllegalOwner => ProgramError::IllegalOwner,
+            GuardError::InsufficientFunds => ProgramError::InsufficientFunds,
+            GuardError::NotRentExempt => ProgramError::AccountNotRentExempt,
+            GuardError::NotTokenAccount
+            | GuardError::TokenMintMismatch
+            | GuardError::TokenOwnerMismatch
+            | GuardError::TokenDelegated
+            | GuardError::TokenCloseAuthority
+            | GuardError::DiscriminatorMismatch
+            | GuardError::Executable
+            | GuardError::OutdatedVersion
+            | GuardError::UnknownVersion => ProgramError::InvalidAccountData,
+            GuardError::UnexpectedKey => ProgramError::InvalidArgument,
+            GuardError::AccountBorrowFailed => ProgramError::AccountBorrowFailed,
+            GuardError::RentUnavailable => ProgramError::UnsupportedSysvar,
+            GuardError::SameAccount => ProgramError::InvalidArgument,
+            GuardError::LamportOverflow => ProgramError::ArithmeticOverflow,
+            GuardError::LamportsNotConserved => ProgramError::InvalidAccountData,
+            // The guard's state is wrong for the call
+            GuardError::Reentered | GuardError::NotEntered => ProgramError::InvalidAccountData,
+            GuardError::RateLimited => ProgramError::InsufficientFunds,
+            GuardError::CursorComplete => ProgramError::InvalidAccountData,
+            GuardError::EmptyBatch => ProgramError::InvalidArgument,
+            GuardError::UnknownCluster => ProgramError::UninitializedAccount,
+            GuardError::MainnetCluster => ProgramError::Custom(MAINNET_CLUSTER_ERROR),
+            GuardError::NotUpgradeAuthority => ProgramError::IncorrectAuthority,
+            GuardError::DataTooShort => ProgramError::AccountDataTooSmall,
+            GuardError::InvalidField => ProgramError::InvalidAccountData,
+        }
+    }
+}
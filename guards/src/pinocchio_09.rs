@@ -0,0 +1,202 @@
+//! Checks over Pinocchio 0.9's `AccountInfo`, and the cluster record, for the
+//! `programs-pinocchio` ports
+//!
+//! The ports stay on the 0.9 line, which pinocchio-system builds against.
+//! [`record_cluster`] and [`check_cluster_account`] are the `native`
+//! feature's, under their own path, since a workspace build turns on both.
+
+use pinocchio_09::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+use crate::{
+    check_cluster, check_upgrade_authority, write_cluster_record, AccountValidatable,
+    ClosableAccount, ClusterRecord, GuardError, GuardedAccount, Result, CLUSTER_SEED,
+    MAINNET_CLUSTER_ERROR,
+};
+
+impl GuardedAccount for AccountInfo {
+    fn owner(&self) -> [u8; 32] {
+        *AccountInfo::owner(self)
+    }
+
+    fn is_signer(&self) -> bool {
+        AccountInfo::is_signer(self)
+    }
+
+    fn lamports(&self) -> u64 {
+        AccountInfo::lamports(self)
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+        let data = self
+            .try_borrow_data()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&data))
+    }
+
+    fn rent_exempt_minimum(&self) -> Result<u64> {
+        let rent = Rent::get().map_err(|_| GuardError::RentUnavailable)?;
+        Ok(rent.minimum_balance(self.data_len()))
+    }
+}
+
+impl ClosableAccount for AccountInfo {
+    fn address(&self) -> [u8; 32] {
+        *self.key()
+    }
+
+    fn with_data_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Result<R> {
+        let mut data = self
+            .try_borrow_mut_data()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&mut data))
+    }
+
+    fn with_lamports_mut<R>(&self, f: impl FnOnce(&mut u64) -> R) -> Result<R> {
+        let mut lamports = self
+            .try_borrow_mut_lamports()
+            .map_err(|_| GuardError::AccountBorrowFailed)?;
+        Ok(f(&mut lamports))
+    }
+}
+
+impl AccountValidatable for AccountInfo {
+    fn address(&self) -> [u8; 32] {
+        *self.key()
+    }
+
+    fn is_executable(&self) -> bool {
+        self.executable()
+    }
+}
+
+/// A Pinocchio port's `record_cluster` instruction: the upgrade authority
+/// creates the program's [`ClusterRecord`] for the cluster with
+/// `genesis_hash`, once.
+///
+/// Accounts: authority (signer, writable), program, program data, cluster
+/// (writable), system program
+pub fn record_cluster(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    genesis_hash: [u8; 32],
+) -> ProgramResult {
+    let [authority, program, program_data, cluster, system, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_upgrade_authority(program, program_data, authority, program_id)?;
+    if *system.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (address, bump) = pubkey::find_program_address(&[CLUSTER_SEED], program_id);
+    if address != *cluster.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let bump = [bump];
+    let seeds = [Seed::from(CLUSTER_SEED), Seed::from(&bump)];
+    create_record(authority, cluster, Signer::from(&seeds), program_id)?;
+    write_cluster_record(cluster, program_id, genesis_hash)?;
+    Ok(())
+}
+
+/// [`check_cluster`] for a Pinocchio port's `cluster` account, which must
+/// also sit at the `seeds = [CLUSTER_SEED]` address.
+pub fn check_cluster_account(record: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    let (address, _) = pubkey::find_program_address(&[CLUSTER_SEED], program_id);
+    if address != *record.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    check_cluster(record, program_id)?;
+    Ok(())
+}
+
+// `init` on the record's PDA, as under `native`: a funded address is topped
+// up, allocated and assigned rather than refused
+fn create_record(
+    payer: &AccountInfo,
+    record: &AccountInfo,
+    signer: Signer,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(ClusterRecord::LEN);
+    let lamports = record.lamports();
+    if lamports == 0 {
+        return CreateAccount {
+            from: payer,
+            to: record,
+            lamports: rent,
+            space: ClusterRecord::LEN as u64,
+            owner: program_id,
+        }
+        .invoke_signed(&[signer]);
+    }
+
+    // Recorded already
+    if !record.is_owned_by(&pinocchio_system::ID) || !record.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if lamports < rent {
+        Transfer {
+            from: payer,
+            to: record,
+            lamports: rent - lamports,
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account: record,
+        space: ClusterRecord::LEN as u64,
+    }
+    .invoke_signed(core::slice::from_ref(&signer))?;
+    Assign {
+        account: record,
+        owner: program_id,
+    }
+    .invoke_signed(&[signer])
+}
+
+// The same codes as under Pinocchio 0.10 and `native`
+impl From<GuardError> for ProgramError {
+    fn from(error: GuardError) -> Self {
+        match error {
+            GuardError::MissingSignature => ProgramError::MissingRequiredSignature,
+            GuardError::IllegalOwner => ProgramError::IllegalOwner,
+            GuardError::InsufficientFunds => ProgramError::InsufficientFunds,
+            GuardError::NotRentExempt => ProgramError::AccountNotRentExempt,
+            GuardError::NotTokenAccount
+            | GuardError::TokenMintMismatch
+            | GuardError::TokenOwnerMismatch
+            | GuardError::TokenDelegated
+            | GuardError::TokenCloseAuthority
+            | GuardError::DiscriminatorMismatch
+            | GuardError::Executable
+            | GuardError::OutdatedVersion
+            | GuardError::UnknownVersion => ProgramError::InvalidAccountData,
+            GuardError::UnexpectedKey => ProgramError::InvalidArgument,
+            GuardError::AccountBorrowFailed => ProgramError::AccountBorrowFailed,
+            GuardError::RentUnavailable => ProgramError::UnsupportedSysvar,
+            GuardError::SameAccount => ProgramError::InvalidArgument,
+            GuardError::LamportOverflow => ProgramError::ArithmeticOverflow,
+            GuardError::LamportsNotConserved => ProgramError::InvalidAccountData,
+            // The guard's state is wrong for the call
+            GuardError::Reentered | GuardError::NotEntered => ProgramError::InvalidAccountData,
+            GuardError::RateLimited => ProgramError::InsufficientFunds,
+            GuardError::CursorComplete => ProgramError::InvalidAccountData,
+            GuardError::EmptyBatch => ProgramError::InvalidArgument,
+            GuardError::UnknownCluster => ProgramError::UninitializedAccount,
+            GuardError::MainnetCluster => ProgramError::Custom(MAINNET_CLUSTER_ERROR),
+            GuardError::NotUpgradeAuthority => ProgramError::IncorrectAuthority,
+            GuardError::DataTooShort => ProgramError::AccountDataTooSmall,
+            GuardError::InvalidField => ProgramError::InvalidAccountData,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! A token bucket refilled per slot
+
+use crate::{GuardError, Result};
+
+/// A budget of up to `capacity` units that refills by `refill_per_slot`
+/// each slot, kept in account state to cap withdrawals or crank rewards
+/// over time. Pass the current slot from the `Clock` sysvar.
+///
+/// A slot earlier than the last one seen refills nothing, and a refill
+/// that would overflow stops at `capacity`, so a warped or hostile clock
+/// can at most fill the bucket.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RateLimiter {
+    pub(crate) capacity: u64,
+    pub(crate) refill_per_slot: u64,
+    pub(crate) tokens: u64,
+    pub(crate) last_slot: u64,
+}
+
+impl RateLimiter {
+    pub const LEN: usize = 32;
+
+    /// A full bucket as of `slot`.
+    pub const fn new(capacity: u64, refill_per_slot: u64, slot: u64) -> Self {
+        RateLimiter {
+            capacity,
+            refill_per_slot,
+            tokens: capacity,
+            last_slot: slot,
+        }
+    }
+
+    pub const fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    pub const fn refill_per_slot(&self) -> u64 {
+        self.refill_per_slot
+    }
+
+    /// What could be consumed at `slot`.
+    pub fn available(&self, slot: u64) -> u64 {
+        let elapsed = slot.saturating_sub(self.last_slot);
+        // Saturating is exact here: anything past `capacity` is cut anyway
+        let refill = elapsed.saturating_mul(self.refill_per_slot);
+        self.tokens.saturating_add(refill).min(self.capacity)
+    }
+
+    /// Take `amount` at `slot`, or fail and change nothing.
+    pub fn consume(&mut self, amount: u64, slot: u64) -> Result<()> {
+        let available = self.available(slot);
+        if amount > available {
+            return Err(GuardError::RateLimited);
+        }
+        self.tokens = available - amount;
+        self.last_slot = self.last_slot.max(slot);
+        Ok(())
+    }
+
+    /// The little-endian layout, for zero-copy state.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        for (chunk, field) in bytes.chunks_exact_mut(8).zip([
+            self.capacity,
+            self.refill_per_slot,
+            self.tokens,
+            self.last_slot,
+        ]) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Self {
+        let field = |i: usize| {
+            let mut le = [0; 8];
+            le.copy_from_slice(&bytes[i * 8..(i + 1) * 8]);
+            u64::from_le_bytes(le)
+        };
+        RateLimiter {
+            capacity: field(0),
+            refill_per_slot: field(1),
+            tokens: field(2),
+            last_slot: field(3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refills_per_slot_up_to_capacity() {
+        let mut limiter = RateLimiter::new(100, 10, 1_000);
+        assert_eq!(limiter.consume(70, 1_000), Ok(()));
+        assert_eq!(limiter.available(1_000), 30);
+        assert_eq!(limiter.consume(31, 1_000), Err(GuardError::RateLimited));
+        assert_eq!(limiter.available(1_000), 30);
+
+        // Two slots later 20 more, and a long wait only fills it
+        assert_eq!(limiter.available(1_002), 50);
+        assert_eq!(limiter.consume(50, 1_002), Ok(()));
+        assert_eq!(limiter.available(1_002), 0);
+        assert_eq!(limiter.available(5_000), 100);
+    }
+
+    #[test]
+    fn test_clock_warps_never_mint_tokens() {
+        let mut limiter = RateLimiter::new(100, 10, 1_000);
+        assert_eq!(limiter.consume(100, 1_000), Ok(()));
+
+        // Backwards: nothing refills, and the last slot stays put
+        assert_eq!(limiter.available(0), 0);
+        assert_eq!(limiter.consume(1, 500), Err(GuardError::RateLimited));
+        assert_eq!(limiter.consume(0, 500), Ok(()));
+        assert_eq!(limiter.last_slot, 1_000);
+        assert_eq!(limiter.available(1_001), 10);
+
+        // To the end of time with a refill that overflows u64
+        let mut huge = RateLimiter::new(u64::MAX, u64::MAX, 0);
+        assert_eq!(huge.consume(u64::MAX, 0), Ok(()));
+        assert_eq!(huge.available(1), u64::MAX);
+        assert_eq!(huge.available(u64::MAX), u64::MAX);
+        assert_eq!(huge.consume(u64::MAX, u64::MAX), Ok(()));
+        assert_eq!(huge.available(u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_zero_refill_is_a_fixed_allowance() {
+        let mut limiter = RateLimiter::new(5, 0, 0);
+        assert_eq!(limiter.consume(5, 0), Ok(()));
+        assert_eq!(limiter.available(u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut limiter = RateLimiter::new(100, 3, 42);
+        limiter.consume(17, 43).unwrap();
+        assert_eq!(RateLimiter::from_bytes(&limiter.to_bytes()), limiter);
+        assert_eq!(limiter.to_bytes()[..8], 100u64.to_le_bytes());
+    }
+}
@@ -0,0 +1,94 @@
+//! A flag that refuses a second entry while a call is in flight
+
+use crate::{GuardError, Result};
+
+/// One byte of account state marking an instruction in progress, for
+/// programs that CPI into code they don't control (transfer hooks,
+/// adapters) which could call back in before the state is consistent.
+///
+/// [`enter`](Self::enter) before the CPI and [`exit`](Self::exit) after.
+/// The flag has to be in the account's data while the CPI runs: with an
+/// Anchor `Account`, call its `exit` to persist the struct before invoking,
+/// since Anchor only writes it back when the instruction returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ReentrancyGuard(pub(crate) u8);
+
+impl ReentrancyGuard {
+    pub const LEN: usize = 1;
+
+    pub fn is_entered(&self) -> bool {
+        self.0 != 0
+    }
+
+    /// Mark the call in progress, failing if one already is.
+    pub fn enter(&mut self) -> Result<()> {
+        if self.is_entered() {
+            return Err(GuardError::Reentered);
+        }
+        self.0 = 1;
+        Ok(())
+    }
+
+    /// Mark the call finished, failing if none was in progress.
+    pub fn exit(&mut self) -> Result<()> {
+        if !self.is_entered() {
+            return Err(GuardError::NotEntered);
+        }
+        self.0 = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+
+    /// A vault that pays out through a hook it doesn't trust, and only
+    /// updates its balance once the hook returns
+    struct Vault {
+        guard: RefCell<ReentrancyGuard>,
+        balance: Cell<u64>,
+    }
+
+    impl Vault {
+        fn withdraw(&self, amount: u64, hook: &dyn Fn(&Vault) -> Result<()>) -> Result<()> {
+            self.guard.borrow_mut().enter()?;
+            if self.balance.get() < amount {
+                return Err(GuardError::InsufficientFunds);
+            }
+            hook(self)?;
+            self.balance.set(self.balance.get() - amount);
+            self.guard.borrow_mut().exit()
+        }
+    }
+
+    #[test]
+    fn test_hook_cannot_reenter_mid_withdrawal() {
+        let vault = Vault {
+            guard: RefCell::default(),
+            balance: Cell::new(100),
+        };
+        let honest = |_: &Vault| Ok(());
+        assert_eq!(vault.withdraw(60, &honest), Ok(()));
+        assert_eq!(vault.balance.get(), 40);
+        assert!(!vault.guard.borrow().is_entered());
+
+        // The malicious hook withdraws again before the balance is debited;
+        // without the guard both would pass the balance check
+        let reentrant = |vault: &Vault| vault.withdraw(40, &honest);
+        assert_eq!(vault.withdraw(40, &reentrant), Err(GuardError::Reentered));
+        assert_eq!(vault.balance.get(), 40);
+    }
+
+    #[test]
+    fn test_exit_requires_enter() {
+        let mut guard = ReentrancyGuard::default();
+        assert_eq!(guard.exit(), Err(GuardError::NotEntered));
+        assert_eq!(guard.enter(), Ok(()));
+        assert_eq!(guard.enter(), Err(GuardError::Reentered));
+        assert_eq!(guard.exit(), Ok(()));
+        assert_eq!(guard.enter(), Ok(()));
+    }
+}
@@ -0,0 +1,199 @@
+//! SPL Token account layout
+
+use crate::{GuardError, GuardedAccount, Result};
+
+/// `TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA`
+pub const TOKEN_PROGRAM_ID: [u8; 32] = [
+    6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180, 133, 237,
+    95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+];
+
+/// `TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb`
+pub const TOKEN_2022_PROGRAM_ID: [u8; 32] = [
+    6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252, 77,
+    131, 185, 13, 39, 254, 189, 249, 40, 216, 161, 139, 252,
+];
+
+// The base account: mint, owner, amount, delegate, then the state byte,
+// and after it the close authority. Optional keys are a u32 tag and a key.
+const ACCOUNT_LEN: usize = 165;
+const MINT: core::ops::Range<usize> = 0..32;
+const OWNER: core::ops::Range<usize> = 32..64;
+const DELEGATE_TAG: core::ops::Range<usize> = 72..76;
+const STATE: usize = 108;
+const CLOSE_AUTHORITY_TAG: core::ops::Range<usize> = 129..133;
+const CLOSE_AUTHORITY: core::ops::Range<usize> = 133..165;
+// Token-2022 pads mints to the account length and tags everything past it
+// with an account type; multisigs are never extended
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+const MULTISIG_LEN: usize = 355;
+
+/// The account is an initialized SPL Token or Token-2022 account holding
+/// `mint` for `owner`. A frozen account passes; it is still the owner's.
+pub fn check_token_account(
+    account: &impl GuardedAccount,
+    mint: &impl AsRef<[u8]>,
+    owner: &impl AsRef<[u8]>,
+) -> Result<()> {
+    let program = account.owner();
+    if program != TOKEN_PROGRAM_ID && program != TOKEN_2022_PROGRAM_ID {
+        return Err(GuardError::NotTokenAccount);
+    }
+    account.with_data(|data| {
+        let is_account = match data.len() {
+            ACCOUNT_LEN => true,
+            MULTISIG_LEN => false,
+            len => len > ACCOUNT_LEN && data[ACCOUNT_LEN] == ACCOUNT_TYPE_ACCOUNT,
+        };
+        if !is_account || data[STATE] == 0 {
+            return Err(GuardError::NotTokenAccount);
+        }
+        if data[MINT] != *mint.as_ref() {
+            return Err(GuardError::TokenMintMismatch);
+        }
+        if data[OWNER] != *owner.as_ref() {
+            return Err(GuardError::TokenOwnerMismatch);
+        }
+        Ok(())
+    })?
+}
+
+/// The token account has no delegate, so only its owner can move what it
+/// holds. Check it is a token account with [`check_token_account`] first.
+pub fn check_no_delegate(account: &impl GuardedAccount) -> Result<()> {
+    account.with_data(|data| {
+        if data.len() < ACCOUNT_LEN {
+            return Err(GuardError::NotTokenAccount);
+        }
+        if data[DELEGATE_TAG] != [0; 4] {
+            return Err(GuardError::TokenDelegated);
+        }
+        Ok(())
+    })?
+}
+
+/// The token account's close authority is `expected`, or it has none when
+/// `expected` is `None`. Whoever it names can close the account once it's
+/// empty, and its owner can set one at any time.
+pub fn check_close_authority(account: &impl GuardedAccount, expected: Option<&[u8]>) -> Result<()> {
+    account.with_data(|data| {
+        if data.len() < ACCOUNT_LEN {
+            return Err(GuardError::NotTokenAccount);
+        }
+        let authority = (data[CLOSE_AUTHORITY_TAG] != [0; 4]).then_some(&data[CLOSE_AUTHORITY]);
+        if authority != expected {
+            return Err(GuardError::TokenCloseAuthority);
+        }
+        Ok(())
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::tests::Mock;
+    use core::str::FromStr;
+    use solana_pubkey::Pubkey;
+    use std::{boxed::Box, vec};
+
+    fn token_account(len: usize, state: u8) -> &'static [u8] {
+        let data = Box::leak(vec![0u8; len].into_boxed_slice());
+        data[MINT].fill(1);
+        data[OWNER].fill(2);
+        data[STATE] = state;
+        if len > ACCOUNT_LEN {
+            data[ACCOUNT_LEN] = ACCOUNT_TYPE_ACCOUNT;
+        }
+        data
+    }
+
+    #[test]
+    fn test_program_ids_match_spl() {
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let token_2022 = Pubkey::from_str("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb").unwrap();
+        assert_eq!(token.to_bytes(), TOKEN_PROGRAM_ID);
+        assert_eq!(token_2022.to_bytes(), TOKEN_2022_PROGRAM_ID);
+    }
+
+    #[test]
+    fn test_token_account_checks_layout_mint_and_owner() {
+        let (mint, owner) = ([1u8; 32], [2u8; 32]);
+        let mut account = Mock::new(TOKEN_PROGRAM_ID);
+        account.data = token_account(ACCOUNT_LEN, 1);
+        assert_eq!(check_token_account(&account, &mint, &owner), Ok(()));
+        assert_eq!(
+            check_token_account(&account, &[3u8; 32], &owner),
+            Err(GuardError::TokenMintMismatch)
+        );
+        assert_eq!(
+            check_token_account(&account, &mint, &[3u8; 32]),
+            Err(GuardError::TokenOwnerMismatch)
+        );
+
+        // Frozen and extended accounts pass; uninitialized ones, mints and
+        // look-alikes owned elsewhere don't
+        account.data = token_account(ACCOUNT_LEN, 2);
+        assert_eq!(check_token_account(&account, &mint, &owner), Ok(()));
+        account = Mock::new(TOKEN_2022_PROGRAM_ID);
+        account.data = token_account(ACCOUNT_LEN + 30, 1);
+        assert_eq!(check_token_account(&account, &mint, &owner), Ok(()));
+        for data in [
+            token_account(ACCOUNT_LEN, 0),
+            token_account(MULTISIG_LEN, 1),
+            &[0; 82],
+        ] {
+            account.data = data;
+            assert_eq!(
+                check_token_account(&account, &mint, &owner),
+                Err(GuardError::NotTokenAccount)
+            );
+        }
+        let mut fake = Mock::new([9; 32]);
+        fake.data = token_account(ACCOUNT_LEN, 1);
+        assert_eq!(
+            check_token_account(&fake, &mint, &owner),
+            Err(GuardError::NotTokenAccount)
+        );
+    }
+
+    #[test]
+    fn test_delegate_and_close_authority() {
+        let mut account = Mock::new(TOKEN_PROGRAM_ID);
+        account.data = token_account(ACCOUNT_LEN, 1);
+        assert_eq!(check_no_delegate(&account), Ok(()));
+        assert_eq!(check_close_authority(&account, None), Ok(()));
+        assert_eq!(
+            check_close_authority(&account, Some(&[2; 32])),
+            Err(GuardError::TokenCloseAuthority)
+        );
+
+        let data = Box::leak(token_account(ACCOUNT_LEN, 1).to_vec().into_boxed_slice());
+        data[DELEGATE_TAG][0] = 1;
+        data[CLOSE_AUTHORITY_TAG][0] = 1;
+        data[CLOSE_AUTHORITY].fill(2);
+        account.data = data;
+        assert_eq!(check_no_delegate(&account), Err(GuardError::TokenDelegated));
+        assert_eq!(check_close_authority(&account, Some(&[2; 32])), Ok(()));
+        assert_eq!(
+            check_close_authority(&account, None),
+            Err(GuardError::TokenCloseAuthority)
+        );
+        assert_eq!(
+            check_close_authority(&account, Some(&[3; 32])),
+            Err(GuardError::TokenCloseAuthority)
+        );
+
+        // A mint is too short to hold either
+        account.data = &[0; 82];
+        assert_eq!(
+            check_no_delegate(&account),
+            Err(GuardError::NotTokenAccount)
+        );
+        assert_eq!(
+            check_close_authority(&account, None),
+            Err(GuardError::NotTokenAccount)
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Composable checks over a raw account
+
+use crate::{check_owner, GuardError, GuardedAccount, Result};
+
+/// An account a [`Validator`] can check: what [`GuardedAccount`] reads,
+/// plus its address and whether it's a program.
+pub trait AccountValidatable: GuardedAccount {
+    fn address(&self) -> [u8; 32];
+    fn is_executable(&self) -> bool;
+}
+
+impl<T: AccountValidatable + ?Sized> AccountValidatable for &T {
+    fn address(&self) -> [u8; 32] {
+        (**self).address()
+    }
+
+    fn is_executable(&self) -> bool {
+        (**self).is_executable()
+    }
+}
+
+/// A check over one account, for accounts that arrive unchecked: Anchor's
+/// `remaining_accounts`, `UncheckedAccount` fields, or any Pinocchio
+/// account. Chain checks with [`and`](Self::and); they run in order and
+/// stop at the first failure, so put [`owned_by`] before anything that
+/// reads the data.
+///
+/// ```ignore
+/// let recipient = owned_by(&crate::ID)
+///     .and(discriminator_is(RewardRecipient::DISCRIMINATOR))
+///     .and(not_executable());
+/// for account in remaining {
+///     require_valid!(account, recipient);
+/// }
+/// ```
+pub trait Validator {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()>;
+
+    /// This check, then `next`.
+    fn and<V: Validator>(self, next: V) -> And<Self, V>
+    where
+        Self: Sized,
+    {
+        And(self, next)
+    }
+}
+
+impl<T: Validator + ?Sized> Validator for &T {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()> {
+        (**self).validate(account)
+    }
+}
+
+/// Two checks in order, from [`Validator::and`].
+#[derive(Debug, Clone, Copy)]
+pub struct And<A, B>(A, B);
+
+impl<A: Validator, B: Validator> Validator for And<A, B> {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()> {
+        self.0.validate(account)?;
+        self.1.validate(account)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OwnedBy<O>(O);
+
+/// The account is owned by `owner`, as [`check_owner`].
+pub fn owned_by<O: AsRef<[u8]>>(owner: O) -> OwnedBy<O> {
+    OwnedBy(owner)
+}
+
+impl<O: AsRef<[u8]>> Validator for OwnedBy<O> {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()> {
+        check_owner(account, &self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiscriminatorIs<'a>(&'a [u8]);
+
+/// The account's data starts with `discriminator`, such as an Anchor
+/// account type's `DISCRIMINATOR`. Only meaningful once the owner is known:
+/// any program can write any bytes into its own accounts.
+pub fn discriminator_is(discriminator: &[u8]) -> DiscriminatorIs<'_> {
+    DiscriminatorIs(discriminator)
+}
+
+impl Validator for DiscriminatorIs<'_> {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()> {
+        if !account.with_data(|data| data.starts_with(self.0))? {
+            return Err(GuardError::DiscriminatorMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeyIn<'a, K>(&'a [K]);
+
+/// The account's address is one of `keys`, such as the token programs an
+/// instruction accepts.
+pub fn key_in<K: AsRef<[u8]>>(keys: &[K]) -> KeyIn<'_, K> {
+    KeyIn(keys)
+}
+
+impl<K: AsRef<[u8]>> Validator for KeyIn<'_, K> {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()> {
+        let address = account.address();
+        if !self.0.iter().any(|key| key.as_ref() == address) {
+            return Err(GuardError::UnexpectedKey);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NotExecutable;
+
+/// The account isn't a program, which a data account posing as one could
+/// otherwise be.
+pub fn not_executable() -> NotExecutable {
+    NotExecutable
+}
+
+impl Validator for NotExecutable {
+    fn validate(&self, account: &impl AccountValidatable) -> Result<()> {
+        if account.is_executable() {
+            return Err(GuardError::Executable);
+        }
+        Ok(())
+    }
+}
+
+/// Returns early unless the account passes the given [`Validator`].
+#[macro_export]
+macro_rules! require_valid {
+    ($account:expr, $validator:expr $(, $error:expr)? $(,)?) => {
+        $crate::__require!($crate::Validator::validate(&$validator, &$account) $(, $error)?)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Mock;
+
+    const PROGRAM: [u8; 32] = [1; 32];
+    const DISCRIMINATOR: [u8; 8] = [9, 8, 7, 6, 5, 4, 3, 2];
+
+    fn record(data: &'static [u8]) -> Mock {
+        let mut account = Mock::new(PROGRAM);
+        account.data = data;
+        account
+    }
+
+    #[test]
+    fn test_each_validator() {
+        let account = record(&[9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(owned_by(PROGRAM).validate(&account), Ok(()));
+        assert_eq!(
+            owned_by([2; 32]).validate(&account),
+            Err(GuardError::IllegalOwner)
+        );
+
+        assert_eq!(discriminator_is(&DISCRIMINATOR).validate(&account), Ok(()));
+        assert_eq!(
+            discriminator_is(&[9, 8, 7, 6, 5, 4, 3, 3]).validate(&account),
+            Err(GuardError::DiscriminatorMismatch)
+        );
+        // Data shorter than the discriminator
+        assert_eq!(
+            discriminator_is(&DISCRIMINATOR).validate(&record(&[9, 8])),
+            Err(GuardError::DiscriminatorMismatch)
+        );
+
+        assert_eq!(key_in(&[[3; 32], [0; 32]]).validate(&account), Ok(()));
+        assert_eq!(
+            key_in(&[[3; 32]]).validate(&account),
+            Err(GuardError::UnexpectedKey)
+        );
+        assert_eq!(
+            key_in::<[u8; 32]>(&[]).validate(&account),
+            Err(GuardError::UnexpectedKey)
+        );
+
+        let mut program = record(&[]);
+        assert_eq!(not_executable().validate(&program), Ok(()));
+        program.executable = true;
+        assert_eq!(
+            not_executable().validate(&program),
+            Err(GuardError::Executable)
+        );
+    }
+
+    #[test]
+    fn test_chain_stops_at_the_first_failure() {
+        let recipient = owned_by(PROGRAM)
+            .and(discriminator_is(&DISCRIMINATOR))
+            .and(not_executable());
+        assert_eq!(recipient.validate(&record(&DISCRIMINATOR)), Ok(()));
+
+        // A foreign account with the right bytes fails on its owner first
+        let mut forged = record(&DISCRIMINATOR);
+        forged.owner = [2; 32];
+        forged.executable = true;
+        assert_eq!(recipient.validate(&forged), Err(GuardError::IllegalOwner));
+        forged.owner = PROGRAM;
+        assert_eq!(recipient.validate(&forged), Err(GuardError::Executable));
+        assert_eq!(
+            recipient.validate(&record(&[0; 8])),
+            Err(GuardError::DiscriminatorMismatch)
+        );
+    }
+
+    fn guarded(account: &Mock) -> core::result::Result<(), &'static str> {
+        require_valid!(account, owned_by(PROGRAM), "wrong owner");
+        require_valid!(account, discriminator_is(&DISCRIMINATOR), "wrong type");
+        Ok(())
+    }
+
+    #[test]
+    fn test_require_valid_returns_the_given_error() {
+        assert_eq!(guarded(&record(&DISCRIMINATOR)), Ok(()));
+        assert_eq!(guarded(&record(&[])), Err("wrong type"));
+        assert_eq!(guarded(&Mock::new([2; 32])), Err("wrong owner"));
+    }
+}
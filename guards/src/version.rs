@@ -0,0 +1,128 @@
+//! Account layouts that record which version wrote them
+
+use crate::{GuardError, GuardedAccount, Result};
+
+/// Where the version byte sits: right after Anchor's discriminator.
+pub const VERSION_OFFSET: usize = 8;
+
+/// Account state whose first field is its layout version, so a program
+/// that changes the layout can tell old accounts from new ones instead of
+/// reading one as the other.
+///
+/// Bump [`VERSION`](Self::VERSION) with every layout change and teach
+/// [`upgrade`](Self::upgrade) to read the layout being replaced. Accounts
+/// are then rewritten by a migrate instruction, with
+/// `solsec_guards::migrate_account` under Anchor, and every other
+/// instruction refuses them until they are.
+pub trait Versioned: Sized {
+    /// The layout this build writes
+    const VERSION: u8;
+
+    /// Read `data`, the whole account as written at the older `version`,
+    /// as the current layout. `None` for a version there is no migration
+    /// from, or data that doesn't parse as it.
+    fn upgrade(version: u8, data: &[u8]) -> Option<Self>;
+}
+
+/// The layout version an account's data claims.
+pub fn account_version(data: &[u8]) -> Result<u8> {
+    data.get(VERSION_OFFSET)
+        .copied()
+        .ok_or(GuardError::UnknownVersion)
+}
+
+/// The account holds `T`'s current layout: an older one needs migrating
+/// first, and a newer one was written by a later build.
+pub fn check_version<T: Versioned>(account: &impl GuardedAccount) -> Result<()> {
+    let version = account.with_data(account_version)??;
+    match version.cmp(&T::VERSION) {
+        core::cmp::Ordering::Equal => Ok(()),
+        core::cmp::Ordering::Less => Err(GuardError::OutdatedVersion),
+        core::cmp::Ordering::Greater => Err(GuardError::UnknownVersion),
+    }
+}
+
+/// `data` as `T`'s current layout when it holds an older one, or `None`
+/// when it is already current, so migrating twice changes nothing.
+pub fn upgrade<T: Versioned>(data: &[u8]) -> Result<Option<T>> {
+    let version = account_version(data)?;
+    match version.cmp(&T::VERSION) {
+        core::cmp::Ordering::Equal => Ok(None),
+        core::cmp::Ordering::Less => T::upgrade(version, data)
+            .map(Some)
+            .ok_or(GuardError::UnknownVersion),
+        core::cmp::Ordering::Greater => Err(GuardError::UnknownVersion),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Mock;
+
+    /// Version 1 was `[amount: u32]`, version 2 is `[amount: u64, flags: u8]`
+    #[derive(Debug, PartialEq)]
+    struct Stake {
+        amount: u64,
+        flags: u8,
+    }
+
+    impl Versioned for Stake {
+        const VERSION: u8 = 2;
+
+        fn upgrade(version: u8, data: &[u8]) -> Option<Self> {
+            match version {
+                1 => {
+                    let amount = data.get(9..13)?.try_into().ok()?;
+                    Some(Stake {
+                        amount: u32::from_le_bytes(amount).into(),
+                        flags: 0,
+                    })
+                }
+                _ => None,
+            }
+        }
+    }
+
+    const V1: [u8; 13] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 7, 0, 0, 0];
+    const V2: [u8; 18] = [0, 0, 0, 0, 0, 0, 0, 0, 2, 7, 0, 0, 0, 0, 0, 0, 0, 1];
+
+    #[test]
+    fn test_upgrades_only_older_layouts() {
+        assert_eq!(
+            upgrade::<Stake>(&V1),
+            Ok(Some(Stake {
+                amount: 7,
+                flags: 0
+            }))
+        );
+        assert_eq!(upgrade::<Stake>(&V2), Ok(None));
+
+        let mut future = V2;
+        future[VERSION_OFFSET] = 3;
+        assert_eq!(upgrade::<Stake>(&future), Err(GuardError::UnknownVersion));
+        // No migration from version 0, and a version 1 cut short
+        let mut unknown = V1;
+        unknown[VERSION_OFFSET] = 0;
+        assert_eq!(upgrade::<Stake>(&unknown), Err(GuardError::UnknownVersion));
+        assert_eq!(upgrade::<Stake>(&V1[..12]), Err(GuardError::UnknownVersion));
+        assert_eq!(upgrade::<Stake>(&[0; 8]), Err(GuardError::UnknownVersion));
+    }
+
+    #[test]
+    fn test_check_version_refuses_other_layouts() {
+        let mut account = Mock::new([1; 32]);
+        account.data = &V2;
+        assert_eq!(check_version::<Stake>(&account), Ok(()));
+        account.data = &V1;
+        assert_eq!(
+            check_version::<Stake>(&account),
+            Err(GuardError::OutdatedVersion)
+        );
+        account.data = &[0; 8];
+        assert_eq!(
+            check_version::<Stake>(&account),
+            Err(GuardError::UnknownVersion)
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! What each instruction of an Anchor program takes, read from its source
+//!
+//! The IDL Anchor generates records an instruction's arguments and its
+//! accounts' signer and writable flags, but not the `#[account(...)]`
+//! constraints, which is where most fixes in this tree live. [`instructions`]
+//! reads both from the handlers of the `#[program]` module and the
+//! `#[derive(Accounts)]` structs in their `Context`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{FnArg, GenericArgument, Item, ItemStruct, Pat, PathArguments, Type};
+
+use crate::{derives_accounts, is_cfg_test, rust_files};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub name: String,
+    /// `(name, type)` after the `Context`
+    pub args: Vec<(String, String)>,
+    pub accounts: Vec<InstructionAccount>,
+}
+
+/// A field of the instruction's accounts struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionAccount {
+    pub name: String,
+    /// The field type without lifetimes, e.g. `Account<Vault>`
+    pub ty: String,
+    /// Each item of its `#[account(...)]` attributes, e.g. `has_one = authority`
+    pub constraints: Vec<String>,
+}
+
+/// Every instruction of the Anchor program whose sources are under `dir`.
+pub fn instructions(dir: &Path) -> Result<Vec<Instruction>, String> {
+    let mut files = Vec::new();
+    rust_files(dir, &mut files);
+    files.sort();
+    let mut sources = Vec::new();
+    for file in &files {
+        sources.push(
+            std::fs::read_to_string(file)
+                .map_err(|e| format!("failed to read {}: {}", file.display(), e))?,
+        );
+    }
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    instructions_in(&sources).map_err(|e| format!("failed to parse {}: {}", dir.display(), e))
+}
+
+/// [`instructions`] over sources already read, as one crate.
+pub fn instructions_in(sources: &[&str]) -> syn::Result<Vec<Instruction>> {
+    let mut structs = BTreeMap::new();
+    let mut handlers = Vec::new();
+    for source in sources {
+        collect(syn::parse_file(source)?.items, &mut structs, &mut handlers);
+    }
+
+    Ok(handlers
+        .into_iter()
+        .map(|handler| {
+            let mut accounts = Vec::new();
+            if let Some(name) = context_accounts(&handler) {
+                flatten(&name, &structs, &mut accounts);
+            }
+            let args = handler
+                .sig
+                .inputs
+                .iter()
+                .skip(1)
+                .filter_map(|input| match input {
+                    FnArg::Typed(arg) => Some((
+                        match &*arg.pat {
+                            Pat::Ident(ident) => ident.ident.to_string(),
+                            other => render(other.to_token_stream()),
+                        },
+                        render_type(&arg.ty),
+                    )),
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+            Instruction {
+                name: handler.sig.ident.to_string(),
+                args,
+                accounts,
+            }
+        })
+        .collect())
+}
+
+fn collect(
+    items: Vec<Item>,
+    structs: &mut BTreeMap<String, ItemStruct>,
+    handlers: &mut Vec<syn::ItemFn>,
+) {
+    for item in items {
+        match item {
+            Item::Struct(item) if derives_accounts(&item.attrs) => {
+                structs.insert(item.ident.to_string(), item);
+            }
+            Item::Mod(module) if !is_cfg_test(&module.attrs) => {
+                let Some((_, items)) = module.content else {
+                    continue;
+                };
+                if module.attrs.iter().any(|a| a.path().is_ident("program")) {
+                    handlers.extend(items.into_iter().filter_map(|item| match item {
+                        Item::Fn(handler) => Some(handler),
+                        _ => None,
+                    }));
+                } else {
+                    collect(items, structs, handlers);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// `T` of the handler's `Context<.., T>`
+fn context_accounts(handler: &syn::ItemFn) -> Option<String> {
+    let FnArg::Typed(ctx) = handler.sig.inputs.first()? else {
+        return None;
+    };
+    let Type::Path(path) = &*ctx.ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().rev().find_map(|arg| match arg {
+        GenericArgument::Type(Type::Path(accounts)) => {
+            Some(accounts.path.segments.last()?.ident.to_string())
+        }
+        _ => None,
+    })
+}
+
+// The fields of `name`, with nested accounts structs inlined
+fn flatten(name: &str, structs: &BTreeMap<String, ItemStruct>, out: &mut Vec<InstructionAccount>) {
+    let Some(item) = structs.get(name) else {
+        return;
+    };
+    for field in &item.fields {
+        let nested = crate::type_name(&field.ty).filter(|ty| structs.contains_key(ty));
+        if let Some(nested) = nested {
+            flatten(&nested, structs, out);
+            continue;
+        }
+        let constraints = field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("account"))
+            .filter_map(|a| match &a.meta {
+                syn::Meta::List(list) => Some(split_commas(list.tokens.clone())),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        out.push(InstructionAccount {
+            name: field
+                .ident
+                .as_ref()
+                .map_or_else(String::new, ToString::to_string),
+            ty: render_type(&field.ty),
+            constraints,
+        });
+    }
+}
+
+// Top-level items of a comma-separated list
+fn split_commas(tokens: TokenStream) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = TokenStream::new();
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                items.push(render(std::mem::take(&mut current)));
+            }
+            _ => current.extend([token]),
+        }
+    }
+    items.push(render(current));
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+// Tokens as they'd be written, rather than spaced out one by one
+fn render(tokens: TokenStream) -> String {
+    // `&&` keeps its spaces, a borrow loses them
+    let mut text = tokens.to_string().replace(" && ", "\0");
+    for (spaced, tight) in [
+        (" . ", "."),
+        (" :: ", "::"),
+        (" (", "("),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ,", ","),
+        ("& ", "&"),
+        ("! ", "!"),
+    ] {
+        text = text.replace(spaced, tight);
+    }
+    text.replace('\0', " && ")
+}
+
+fn render_type(ty: &Type) -> String {
+    let mut text = render(ty.to_token_stream());
+    for (spaced, tight) in [(" < ", "<"), (" <", "<"), ("< ", "<"), (" >", ">")] {
+        text = text.replace(spaced, tight);
+    }
+    for lifetime in ["'info, ", "'_, ", "<'info>"] {
+        text = text.replace(lifetime, "");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_constraints_args_and_nested_accounts() {
+        let source = r#"
+            #[program]
+            pub mod vault {
+                use super::*;
+                pub fn withdraw(ctx: Context<Withdraw>, amount: u64, memo: Vec<u8>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Withdraw<'info> {
+                #[account(
+                    mut,
+                    seeds = [b"vault", authority.key().as_ref()],
+                    bump = vault.bump,
+                    // a comment doesn't count
+                    constraint = vault.authority == authority.key() @ VaultError::Unauthorized,
+                    constraint = amount > 0 && !vault.frozen
+                )]
+                pub vault: Account<'info, Vault>,
+                pub authority: Signer<'info>,
+                pub common: Common<'info>,
+            }
+
+            #[derive(Accounts)]
+            pub struct Common<'info> {
+                pub system_program: Program<'info, System>,
+            }
+        "#;
+        let instructions = instructions_in(&[source]).unwrap();
+        assert_eq!(instructions.len(), 1);
+        let withdraw = &instructions[0];
+        assert_eq!(withdraw.name, "withdraw");
+        assert_eq!(
+            withdraw.args,
+            [
+                ("amount".to_string(), "u64".to_string()),
+                ("memo".to_string(), "Vec<u8>".to_string())
+            ]
+        );
+
+        let accounts: Vec<(&str, &str)> = withdraw
+            .accounts
+            .iter()
+            .map(|a| (a.name.as_str(), a.ty.as_str()))
+            .collect();
+        assert_eq!(
+            accounts,
+            [
+                ("vault", "Account<Vault>"),
+                ("authority", "Signer"),
+                ("system_program", "Program<System>")
+            ]
+        );
+        assert_eq!(
+            withdraw.accounts[0].constraints,
+            [
+                "mut",
+                "seeds = [b\"vault\", authority.key().as_ref()]",
+                "bump = vault.bump",
+                "constraint = vault.authority == authority.key() @ VaultError::Unauthorized",
+                "constraint = amount > 0 && !vault.frozen",
+            ]
+        );
+    }
+}
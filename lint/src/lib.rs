@@ -0,0 +1,490 @@
+//! Static checks for Anchor anti-patterns
+//!
+//! Parses the sources of every Anchor program in the registry with `syn`
+//! and flags:
+//! - `unchecked-account`: an `AccountInfo` or `UncheckedAccount` field of an
+//!   `#[derive(Accounts)]` struct that no `address`, `owner`, `seeds` or
+//!   `constraint` pins down
+//! - `missing-signer`: an authority-shaped field (`authority`, `admin`,
+//!   `owner`, `*_authority`) that isn't a `Signer`
+//! - `init-if-needed`: an account that may be initialized again
+//! - `unchecked-arithmetic`: `+`, `-` or `*` (or their assignments) in a
+//!   function body, unless both operands are literals or constants
+//!
+//! The programs demonstrate these mistakes on purpose, so findings are
+//! expected, but only in the vulnerable modules: the vulnerable half of
+//! each entry's `sources`. [`Findings::misplaced`] are the ones anywhere
+//! else, and `cargo xtask lint` fails on them.
+//!
+//! A reviewed false positive is silenced with a comment on the flagged
+//! line or the one above it, giving the reason:
+//!
+//! ```text
+//! // solsec-lint: allow(unchecked-account) any account may receive lamports
+//! ```
+//!
+//! [`interface`] reads the same structs for `cargo xtask idl-diff`.
+
+pub mod interface;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use solsec_registry::{Framework, CATALOG};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, BinOp, Expr, Field, ItemStruct, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    UncheckedAccount,
+    MissingSigner,
+    InitIfNeeded,
+    UncheckedArithmetic,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UncheckedAccount,
+        Lint::MissingSigner,
+        Lint::InitIfNeeded,
+        Lint::UncheckedArithmetic,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UncheckedAccount => "unchecked-account",
+            Lint::MissingSigner => "missing-signer",
+            Lint::InitIfNeeded => "init-if-needed",
+            Lint::UncheckedArithmetic => "unchecked-arithmetic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub lint: Lint,
+    /// From the repository root
+    pub path: PathBuf,
+    pub line: usize,
+    /// `Struct.field` or the enclosing function
+    pub item: String,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: [{}] {}: {}",
+            self.path.display(),
+            self.line,
+            self.lint.name(),
+            self.item,
+            self.message
+        )
+    }
+}
+
+/// Everything one run flagged.
+#[derive(Debug, Default)]
+pub struct Findings {
+    pub findings: Vec<Finding>,
+    /// Files the registry lists as vulnerable, from the repository root
+    pub vulnerable_modules: Vec<PathBuf>,
+}
+
+impl Findings {
+    /// Findings outside the vulnerable modules.
+    pub fn misplaced(&self) -> Vec<&Finding> {
+        self.findings
+            .iter()
+            .filter(|f| !self.vulnerable_modules.contains(&f.path))
+            .collect()
+    }
+
+    /// Vulnerable modules with nothing flagged, i.e. mistakes these lints
+    /// don't catch.
+    pub fn unflagged_modules(&self) -> Vec<&Path> {
+        self.vulnerable_modules
+            .iter()
+            .filter(|m| !self.findings.iter().any(|f| f.path == **m))
+            .map(PathBuf::as_path)
+            .collect()
+    }
+}
+
+/// Lint every Anchor program under `root`.
+pub fn lint_workspace(root: &Path) -> Result<Findings, String> {
+    let mut out = Findings::default();
+    for vulnerability in CATALOG {
+        if vulnerability.framework != Framework::Anchor {
+            continue;
+        }
+        let sources = vulnerability.sources;
+        if sources.len() > 1 {
+            out.vulnerable_modules
+                .extend(sources[..sources.len() / 2].iter().map(PathBuf::from));
+        }
+
+        let mut files = Vec::new();
+        rust_files(
+            &root.join("programs").join(vulnerability.program),
+            &mut files,
+        );
+        files.sort();
+        for file in files {
+            let source = std::fs::read_to_string(&file)
+                .map_err(|e| format!("failed to read {}: {}", file.display(), e))?;
+            let relative = file.strip_prefix(root).unwrap_or(&file);
+            out.findings.extend(
+                lint_source(relative, &source)
+                    .map_err(|e| format!("failed to parse {}: {}", file.display(), e))?,
+            );
+        }
+    }
+    Ok(out)
+}
+
+fn rust_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            if path.file_name().is_some_and(|n| n != "target") {
+                rust_files(&path, out);
+            }
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// Lint one file; `path` is only used to label the findings.
+pub fn lint_source(path: &Path, source: &str) -> syn::Result<Vec<Finding>> {
+    let file = syn::parse_file(source)?;
+    let mut visitor = Visitor {
+        path,
+        item: String::new(),
+        findings: Vec::new(),
+    };
+    visitor.visit_file(&file);
+
+    let lines: Vec<&str> = source.lines().collect();
+    let allowed = |f: &Finding| {
+        let marker = format!("solsec-lint: allow({})", f.lint.name());
+        (f.line.saturating_sub(2)..f.line)
+            .filter_map(|i| lines.get(i))
+            .any(|line| line.contains(&marker))
+    };
+    Ok(visitor
+        .findings
+        .into_iter()
+        .filter(|f| !allowed(f))
+        .collect())
+}
+
+struct Visitor<'a> {
+    path: &'a Path,
+    /// The function whose body is being visited
+    item: String,
+    findings: Vec<Finding>,
+}
+
+impl Visitor<'_> {
+    fn flag(&mut self, lint: Lint, span: proc_macro2::Span, item: String, message: String) {
+        self.findings.push(Finding {
+            lint,
+            path: self.path.to_path_buf(),
+            line: span.start().line,
+            item,
+            message,
+        });
+    }
+
+    fn accounts_field(&mut self, strukt: &ItemStruct, field: &Field) {
+        let Some(name) = &field.ident else {
+            return;
+        };
+        let item = format!("{}.{}", strukt.ident, name);
+        let ty = type_name(&field.ty);
+        let constraints: Vec<String> = field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("account"))
+            .flat_map(account_idents)
+            .collect();
+        let has = |key: &str| constraints.iter().any(|c| c == key);
+
+        if matches!(ty.as_deref(), Some("AccountInfo" | "UncheckedAccount"))
+            && !["address", "owner", "seeds", "constraint"]
+                .iter()
+                .any(|key| has(key))
+        {
+            self.flag(
+                Lint::UncheckedAccount,
+                field.span(),
+                item.clone(),
+                format!(
+                    "{} with no address, owner, seeds or constraint",
+                    ty.as_deref().unwrap()
+                ),
+            );
+        }
+        if authority_shaped(&name.to_string()) && ty.as_deref() != Some("Signer") {
+            self.flag(
+                Lint::MissingSigner,
+                field.span(),
+                item.clone(),
+                format!(
+                    "authority-shaped field is {}, not Signer",
+                    ty.as_deref().unwrap_or("not a path type")
+                ),
+            );
+        }
+        if has("init_if_needed") {
+            self.flag(
+                Lint::InitIfNeeded,
+                field.span(),
+                item,
+                "init_if_needed lets the account be initialized again".to_string(),
+            );
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Visitor<'_> {
+    fn visit_item_mod(&mut self, module: &'ast syn::ItemMod) {
+        if !is_cfg_test(&module.attrs) {
+            visit::visit_item_mod(self, module);
+        }
+    }
+
+    fn visit_item_struct(&mut self, strukt: &'ast ItemStruct) {
+        if derives_accounts(&strukt.attrs) {
+            for field in &strukt.fields {
+                self.accounts_field(strukt, field);
+            }
+        }
+    }
+
+    fn visit_item_fn(&mut self, function: &'ast syn::ItemFn) {
+        let outer = std::mem::replace(&mut self.item, function.sig.ident.to_string());
+        visit::visit_item_fn(self, function);
+        self.item = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, function: &'ast syn::ImplItemFn) {
+        let outer = std::mem::replace(&mut self.item, function.sig.ident.to_string());
+        visit::visit_impl_item_fn(self, function);
+        self.item = outer;
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        let op = match expr.op {
+            BinOp::Add(_) | BinOp::AddAssign(_) => Some("+"),
+            BinOp::Sub(_) | BinOp::SubAssign(_) => Some("-"),
+            BinOp::Mul(_) | BinOp::MulAssign(_) => Some("*"),
+            _ => None,
+        };
+        let in_body = !self.item.is_empty();
+        let folded = constant(&expr.left) && constant(&expr.right);
+        if let Some(op) = op.filter(|_| in_body && !folded) {
+            self.flag(
+                Lint::UncheckedArithmetic,
+                expr.span(),
+                self.item.clone(),
+                format!("unchecked `{}`; use checked_* or saturating_*", op),
+            );
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+}
+
+fn is_cfg_test(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|a| {
+        a.path().is_ident("cfg")
+            && a.parse_args::<syn::Ident>()
+                .is_ok_and(|ident| ident == "test")
+    })
+}
+
+fn derives_accounts(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|a| a.path().is_ident("derive"))
+        .any(|a| {
+            let mut found = false;
+            let _ = a.parse_nested_meta(|meta| {
+                found |= meta
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|s| s.ident == "Accounts");
+                Ok(())
+            });
+            found
+        })
+}
+
+// The identifiers of `#[account(...)]`, which holds the constraint keys;
+// their values don't matter here
+fn account_idents(attr: &Attribute) -> Vec<String> {
+    let syn::Meta::List(list) = &attr.meta else {
+        return Vec::new();
+    };
+    list.tokens
+        .clone()
+        .into_iter()
+        .filter_map(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Last segment of a path type, through references and `Box`
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Reference(reference) => type_name(&reference.elem),
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            if segment.ident == "Box" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                        return type_name(inner);
+                    }
+                }
+            }
+            Some(segment.ident.to_string())
+        }
+        _ => None,
+    }
+}
+
+// A key that is being handed authority (`new_authority`,
+// `pending_admin`) doesn't sign for it yet
+fn authority_shaped(name: &str) -> bool {
+    let named = matches!(name, "authority" | "admin" | "owner")
+        || name.ends_with("_authority")
+        || name.ends_with("_admin");
+    named
+        && !["new_", "pending_", "proposed_"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Literals, `SCREAMING_CASE` constants and `Type::CONST` paths
+fn constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Paren(paren) => constant(&paren.expr),
+        Expr::Binary(binary) => constant(&binary.left) && constant(&binary.right),
+        Expr::Path(path) => path.path.segments.last().is_some_and(|s| {
+            let name = s.ident.to_string();
+            name.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }),
+        Expr::Call(call) => {
+            // `size_of::<T>()` and friends
+            matches!(&*call.func, Expr::Path(path)
+                if path.path.segments.last().is_some_and(|s| s.ident == "size_of"))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lints(source: &str) -> Vec<(Lint, String)> {
+        lint_source(Path::new("test.rs"), source)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.lint, f.item))
+            .collect()
+    }
+
+    #[test]
+    fn test_flags_unconstrained_accounts_and_unsigned_authorities() {
+        let found = lints(
+            r#"
+            #[derive(Accounts)]
+            pub struct Withdraw<'info> {
+                #[account(mut, has_one = authority)]
+                pub vault: Account<'info, Vault>,
+                /// CHECK: not checked
+                pub authority: UncheckedAccount<'info>,
+                /// CHECK: pinned
+                #[account(address = vault.destination)]
+                pub destination: AccountInfo<'info>,
+                pub new_authority: UncheckedAccount<'info>,
+                #[account(init_if_needed, payer = payer, space = 8 + 32)]
+                pub config: Account<'info, Config>,
+                pub payer: Signer<'info>,
+            }
+            "#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                (Lint::UncheckedAccount, "Withdraw.authority".to_string()),
+                (Lint::MissingSigner, "Withdraw.authority".to_string()),
+                (Lint::UncheckedAccount, "Withdraw.new_authority".to_string()),
+                (Lint::InitIfNeeded, "Withdraw.config".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flags_arithmetic_on_values_only() {
+        let found = lints(
+            r#"
+            const SIZE: usize = 8 + 32;
+            impl Pool {
+                pub fn swap(&mut self, amount: u64) -> Result<()> {
+                    let space = 8 + Pool::LEN;
+                    self.reserve += amount;
+                    let out = amount.checked_mul(self.other).unwrap();
+                    let fee = amount * FEE_BPS / 10_000;
+                    Ok(())
+                }
+            }
+            fn bump(count: u64) -> u64 {
+                // solsec-lint: allow(unchecked-arithmetic) overflow-checks
+                count + 1
+            }
+            #[cfg(test)]
+            mod tests {
+                fn helper(a: u64) -> u64 { a + 1 }
+            }
+            "#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                (Lint::UncheckedArithmetic, "swap".to_string()),
+                (Lint::UncheckedArithmetic, "swap".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_vulnerable_modules_are_flagged() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let findings = lint_workspace(root).unwrap();
+        let misplaced = findings.misplaced();
+        assert!(
+            misplaced.is_empty(),
+            "findings outside the vulnerable modules:\n  {}",
+            misplaced
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join("\n  ")
+        );
+        assert!(!findings.findings.is_empty());
+    }
+}
@@ -0,0 +1,387 @@
+//! Reference model of the arithmetic-overflow pool
+//!
+//! A swap of `amount_in` X pays out the exact constant-product amount
+//! `amount_in * reserve_y / (reserve_x + amount_in)` of Y. The model keeps
+//! that value as a fraction of two `u128`s: with `u64` amounts and reserves
+//! the numerator stays below 2^128 and the denominator below 2^65, so none
+//! of its arithmetic can overflow.
+//!
+//! A correct program may only round the way the pool's own math does:
+//!
+//! - the output is the exact value rounded down, so it is never above the
+//!   exact value and less than one unit below it
+//! - hence the product of the reserves never decreases
+//!
+//! [`judge`] holds an on-chain swap to those bounds. Neither program
+//! charges the pool's `fee_bps`, so the model doesn't either.
+//!
+//! The differential fuzz target judges LiteSVM swaps with it; it has no
+//! dependencies of its own, so a wasm32 playground can run the same
+//! swaps. The `serde` feature derives `Serialize` for the fuzz target's
+//! regression files.
+
+use core::fmt;
+
+/// Reserves of a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Pool {
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+}
+
+/// An exact non-negative fraction; `den` is never zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Ratio {
+    pub num: u128,
+    pub den: u128,
+}
+
+impl Ratio {
+    pub fn floor(&self) -> u128 {
+        self.num / self.den
+    }
+
+    /// Whether `value` is at most `self` and less than one unit below it,
+    /// the output a swap that rounds down pays.
+    pub fn rounds_down_to(&self, value: u64) -> bool {
+        // value * den can pass u128 only when value > num / den
+        match (value as u128).checked_mul(self.den) {
+            Some(low) if low <= self.num => self.num - low < self.den,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+/// Why the pool refuses a swap; each is a `PoolError` variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Rejection {
+    InvalidAmount,
+    SlippageExceeded,
+    MathOverflow,
+    InsufficientReserves,
+}
+
+impl Rejection {
+    pub const ALL: [Rejection; 4] = [
+        Rejection::InvalidAmount,
+        Rejection::SlippageExceeded,
+        Rejection::MathOverflow,
+        Rejection::InsufficientReserves,
+    ];
+
+    /// The `PoolError` variant, as the IDL names it
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rejection::InvalidAmount => "InvalidAmount",
+            Rejection::SlippageExceeded => "SlippageExceeded",
+            Rejection::MathOverflow => "MathOverflow",
+            Rejection::InsufficientReserves => "InsufficientReserves",
+        }
+    }
+}
+
+/// A swap the model accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Quote {
+    pub before: Pool,
+    pub exact: Ratio,
+    pub amount_out: u64,
+    pub after: Pool,
+}
+
+impl Pool {
+    /// Product of the reserves
+    pub fn k(&self) -> u128 {
+        self.reserve_x as u128 * self.reserve_y as u128
+    }
+
+    /// Swap `amount_in` X for Y, refusing it for the reasons, and in the
+    /// order, the secure swap does.
+    pub fn swap(&self, amount_in: u64, min_out: u64) -> Result<Quote, Rejection> {
+        if amount_in == 0 {
+            return Err(Rejection::InvalidAmount);
+        }
+        let exact = Ratio {
+            num: amount_in as u128 * self.reserve_y as u128,
+            den: self.reserve_x as u128 + amount_in as u128,
+        };
+        // Below reserve_y, so it fits
+        let amount_out = exact.floor() as u64;
+        if amount_out < min_out {
+            return Err(Rejection::SlippageExceeded);
+        }
+        if amount_out > self.reserve_y {
+            return Err(Rejection::InsufficientReserves);
+        }
+        let reserve_x = self
+            .reserve_x
+            .checked_add(amount_in)
+            .ok_or(Rejection::MathOverflow)?;
+        Ok(Quote {
+            before: *self,
+            exact,
+            amount_out,
+            after: Pool {
+                reserve_x,
+                reserve_y: self.reserve_y - amount_out,
+            },
+        })
+    }
+}
+
+impl Quote {
+    /// Whether `after` is a result within the rounding bounds: X grew by
+    /// exactly the input, Y paid out the exact output rounded down, and
+    /// the product of the reserves didn't shrink.
+    pub fn admits(&self, after: Pool) -> bool {
+        let Some(paid) = self.before.reserve_y.checked_sub(after.reserve_y) else {
+            return false;
+        };
+        after.reserve_x == self.after.reserve_x
+            && self.exact.rounds_down_to(paid)
+            && after.k() >= self.before.k()
+    }
+}
+
+/// What a swap did on-chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Observed {
+    Landed(Pool),
+    /// Failed with this `PoolError`, or with another error for `None`
+    Failed(Option<Rejection>),
+}
+
+/// How an on-chain swap compares to the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Verdict {
+    Agrees,
+    /// Landed where the model refuses the swap
+    Landed {
+        expected: Rejection,
+        after: Pool,
+    },
+    /// Failed where the model pays `amount_out`
+    Failed {
+        error: Option<Rejection>,
+        amount_out: u64,
+    },
+    /// Failed, but not for the model's reason
+    WrongRejection {
+        expected: Rejection,
+        error: Option<Rejection>,
+    },
+    /// Landed outside the rounding bounds
+    OutOfBounds {
+        quote: Quote,
+        after: Pool,
+    },
+}
+
+/// Compare what a swap of `amount_in` with `min_out` did from `before`
+/// with what the model says it should have done.
+pub fn judge(before: Pool, amount_in: u64, min_out: u64, observed: &Observed) -> Verdict {
+    match (before.swap(amount_in, min_out), observed) {
+        (Ok(quote), Observed::Landed(after)) if quote.admits(*after) => Verdict::Agrees,
+        (Ok(quote), Observed::Landed(after)) => Verdict::OutOfBounds {
+            quote,
+            after: *after,
+        },
+        (Ok(quote), Observed::Failed(error)) => Verdict::Failed {
+            error: *error,
+            amount_out: quote.amount_out,
+        },
+        (Err(expected), Observed::Landed(after)) => Verdict::Landed {
+            expected,
+            after: *after,
+        },
+        (Err(expected), Observed::Failed(error)) if *error == Some(expected) => Verdict::Agrees,
+        (Err(expected), Observed::Failed(error)) => Verdict::WrongRejection {
+            expected,
+            error: *error,
+        },
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let error = |e: &Option<Rejection>| e.map_or("a non-pool error", |e| e.name());
+        match self {
+            Verdict::Agrees => write!(f, "agrees with the model"),
+            Verdict::Landed { expected, after } => write!(
+                f,
+                "landed with reserves {}/{}; the model refuses it: {}",
+                after.reserve_x,
+                after.reserve_y,
+                expected.name()
+            ),
+            Verdict::Failed {
+                error: e,
+                amount_out,
+            } => write!(
+                f,
+                "failed with {}; the model pays out {}",
+                error(e),
+                amount_out
+            ),
+            Verdict::WrongRejection { expected, error: e } => write!(
+                f,
+                "failed with {}; the model refuses it with {}",
+                error(e),
+                expected.name()
+            ),
+            Verdict::OutOfBounds { quote, after } => {
+                let paid = quote.before.reserve_y as i128 - after.reserve_y as i128;
+                write!(
+                    f,
+                    "paid out {} where the model pays {} (exactly {}), {:+} off; reserve_x {} against {}",
+                    paid,
+                    quote.amount_out,
+                    quote.exact,
+                    paid - quote.amount_out as i128,
+                    after.reserve_x,
+                    quote.after.reserve_x
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use proptest::prelude::*;
+    use std::string::ToString;
+
+    const POOL: Pool = Pool {
+        reserve_x: 1_000_000,
+        reserve_y: 1_000_000,
+    };
+
+    #[test]
+    fn test_rounds_down_to_only_the_floor() {
+        // 7/2 = 3.5
+        let half = Ratio { num: 7, den: 2 };
+        assert!(half.rounds_down_to(3));
+        assert!(!half.rounds_down_to(2));
+        assert!(!half.rounds_down_to(4));
+
+        let whole = Ratio { num: 8, den: 2 };
+        assert!(whole.rounds_down_to(4));
+        assert!(!whole.rounds_down_to(3));
+
+        // value * den past u128
+        let tiny = Ratio {
+            num: 1,
+            den: u64::MAX as u128 * 2,
+        };
+        assert!(tiny.rounds_down_to(0));
+        assert!(!tiny.rounds_down_to(u64::MAX));
+    }
+
+    #[test]
+    fn test_swap_pays_the_exact_output_rounded_down() {
+        let quote = POOL.swap(1_000, 0).unwrap();
+        // 1e9 / 1_001_000 = 999.000999...
+        assert_eq!(quote.amount_out, 999);
+        assert_eq!(quote.after.reserve_x, 1_001_000);
+        assert_eq!(quote.after.reserve_y, 999_001);
+        assert!(quote.after.k() >= POOL.k());
+        assert!(quote.admits(quote.after));
+    }
+
+    #[test]
+    fn test_swap_refuses_in_the_secure_order() {
+        assert_eq!(POOL.swap(0, 0), Err(Rejection::InvalidAmount));
+        assert_eq!(POOL.swap(1_000, 1_000), Err(Rejection::SlippageExceeded));
+
+        let full = Pool {
+            reserve_x: u64::MAX,
+            reserve_y: 1_000,
+        };
+        assert_eq!(full.swap(1, 0), Err(Rejection::MathOverflow));
+        // The slippage check comes first
+        assert_eq!(full.swap(1, 1), Err(Rejection::SlippageExceeded));
+    }
+
+    #[test]
+    fn test_extreme_values_do_not_overflow() {
+        for (x, y, amount_in) in [
+            (1, u64::MAX, u64::MAX - 1),
+            (u64::MAX, u64::MAX, u64::MAX),
+            (1, 1, 1),
+        ] {
+            let pool = Pool {
+                reserve_x: x,
+                reserve_y: y,
+            };
+            if let Ok(quote) = pool.swap(amount_in, 0) {
+                assert!(quote.amount_out < y);
+                assert!(quote.after.k() >= pool.k());
+            }
+        }
+    }
+
+    #[test]
+    fn test_judge_names_the_divergence() {
+        let quote = POOL.swap(1_000, 0).unwrap();
+        assert_eq!(
+            judge(POOL, 1_000, 0, &Observed::Landed(quote.after)),
+            Verdict::Agrees
+        );
+
+        // Paying one unit too much breaks the bound
+        let mut greedy = quote.after;
+        greedy.reserve_y -= 1;
+        let verdict = judge(POOL, 1_000, 0, &Observed::Landed(greedy));
+        assert!(matches!(verdict, Verdict::OutOfBounds { .. }));
+        assert!(verdict.to_string().contains("+1 off"));
+
+        // A zero-input swap that lands, as the vulnerable one does
+        assert_eq!(
+            judge(POOL, 0, 0, &Observed::Landed(POOL)),
+            Verdict::Landed {
+                expected: Rejection::InvalidAmount,
+                after: POOL
+            }
+        );
+        assert_eq!(
+            judge(POOL, 0, 0, &Observed::Failed(Some(Rejection::MathOverflow))),
+            Verdict::WrongRejection {
+                expected: Rejection::InvalidAmount,
+                error: Some(Rejection::MathOverflow)
+            }
+        );
+        assert_eq!(
+            judge(POOL, 1_000, 0, &Observed::Failed(None)),
+            Verdict::Failed {
+                error: None,
+                amount_out: 999
+            }
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_rounds_down_to_exactly_one_value(num: u64, den in 1..=u64::MAX) {
+            let ratio = Ratio { num: num as u128, den: den as u128 };
+            let floor = ratio.floor() as u64;
+            prop_assert!(ratio.rounds_down_to(floor));
+            prop_assert!(floor == u64::MAX || !ratio.rounds_down_to(floor + 1));
+            prop_assert!(floor == 0 || !ratio.rounds_down_to(floor - 1));
+        }
+    }
+}
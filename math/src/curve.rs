@@ -0,0 +1,342 @@
+//! Swap curves: constant product and StableSwap
+//!
+//! A [`Curve`] prices a swap from the pool's reserves alone, so a program
+//! stores which one a pool uses and takes its fee before calling it. Both
+//! round the output down, so the curve's invariant never shrinks across a
+//! swap.
+//!
+//! [`StableSwap`] solves for its invariant by Newton iteration. Every step
+//! is checked: the products that outgrow `u128` are held at 256 bits and
+//! divided back down, so the iteration either settles within a unit of the
+//! exact answer, reports [`MathError::Overflow`] for a quotient that
+//! doesn't fit, or gives up with [`MathError::NoConvergence`] after
+//! [`StableSwap::MAX_ITERATIONS`] steps. None of these is a wrong price.
+
+use crate::{cast, mul_div_floor, MathError, Result};
+
+/// How a pool prices a swap from its reserves.
+pub trait Curve {
+    /// Output of `amount_in`, already net of fees, into a pool holding
+    /// `reserve_in` and `reserve_out`, rounded down.
+    fn swap_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64>;
+}
+
+/// `x * y = k`, for assets whose prices move independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ConstantProduct;
+
+impl Curve for ConstantProduct {
+    fn swap_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        let new_reserve_in = reserve_in
+            .checked_add(amount_in)
+            .ok_or(MathError::Overflow)?;
+        // out = y * dx / (x + dx), which is below `reserve_out`
+        mul_div_floor(reserve_out, amount_in, new_reserve_in)
+    }
+}
+
+/// Curve Finance's StableSwap over two assets meant to trade one for one.
+///
+/// The invariant `D` satisfies `4A(x + y) + D = 4AD + D^3 / 4xy`: the
+/// amplification `A` flattens the curve near balance towards `x + y = D`,
+/// and it bends back to constant product as the pool empties on one side.
+///
+/// Overflow: reserves are `u64`, so `x + y` and `D`, which never exceeds
+/// it, stay below 2^65, and `4A(x + y)` below 2^87 with `A` at most
+/// [`MAX_AMP`](Self::MAX_AMP). The terms that grow past that, `D^3 / 4xy`
+/// for a lopsided pool and the `y^2` of the output's solve, are formed as
+/// 256-bit products and divided back into `u128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StableSwap {
+    amp: u64,
+}
+
+impl StableSwap {
+    /// Curve's own cap on the amplification
+    pub const MAX_AMP: u64 = 1_000_000;
+
+    /// Newton steps before giving up. Starting from `x + y`, the invariant
+    /// falls monotonically onto its root and converges in a handful of
+    /// steps for a balanced pool; the lopsided extremes the tests measure
+    /// take a few dozen.
+    pub const MAX_ITERATIONS: usize = 255;
+
+    /// A curve with amplification `amp`, from 1 to [`MAX_AMP`](Self::MAX_AMP).
+    pub fn new(amp: u64) -> Result<Self> {
+        if amp == 0 || amp > Self::MAX_AMP {
+            return Err(MathError::InvalidAmplification);
+        }
+        Ok(StableSwap { amp })
+    }
+
+    pub fn amp(&self) -> u64 {
+        self.amp
+    }
+
+    /// `4A`, the amplification scaled by `n^n` for two assets
+    fn ann(&self) -> u128 {
+        self.amp as u128 * 4
+    }
+
+    /// The invariant `D` of a pool holding `x` and `y`, within one unit of
+    /// the exact root. Both reserves must be non-zero once either is.
+    pub fn invariant(&self, x: u64, y: u64) -> Result<u128> {
+        self.invariant_steps(x, y).map(|(d, _)| d)
+    }
+
+    /// The invariant and the Newton steps it took.
+    fn invariant_steps(&self, x: u64, y: u64) -> Result<(u128, usize)> {
+        let (x, y) = (x as u128, y as u128);
+        let sum = x + y;
+        if sum == 0 {
+            return Ok((0, 0));
+        }
+        if x == 0 || y == 0 {
+            return Err(MathError::DivisionByZero);
+        }
+        let ann = self.ann();
+        let mut d = sum;
+        for step in 1..=Self::MAX_ITERATIONS {
+            // d_p = D^3 / 4xy, the smaller reserve divided out first so
+            // the truncation is at most a unit or two
+            let d_p = mul_add_div(d, d, 0, x.min(y) * 2)?;
+            let d_p = mul_add_div(d_p, d, 0, x.max(y) * 2)?;
+            let numerator = (ann * sum)
+                .checked_add(d_p.checked_mul(2).ok_or(MathError::Overflow)?)
+                .ok_or(MathError::Overflow)?;
+            let denominator = ((ann - 1) * d)
+                .checked_add(d_p.checked_mul(3).ok_or(MathError::Overflow)?)
+                .ok_or(MathError::Overflow)?;
+            let previous = d;
+            d = mul_add_div(numerator, d, 0, denominator)?;
+            if d.abs_diff(previous) <= 1 {
+                return Ok((d, step));
+            }
+        }
+        Err(MathError::NoConvergence)
+    }
+
+    /// The other reserve that keeps the invariant at `d` once one side
+    /// holds `x`.
+    fn reserve_for(&self, x: u128, d: u128) -> Result<u128> {
+        let ann = self.ann();
+        // y^2 + (x + D/4A - D) y = D^3 / 16Ax, solved for y
+        let c = mul_add_div(d, d, 0, x.min(ann) * 2)?;
+        let c = mul_add_div(c, d, 0, x.max(ann) * 2)?;
+        let b = x + d / ann;
+        let mut y = d;
+        for _ in 0..Self::MAX_ITERATIONS {
+            let denominator = (y * 2 + b).checked_sub(d).ok_or(MathError::Underflow)?;
+            let previous = y;
+            y = mul_add_div(y, y, c, denominator)?;
+            if y.abs_diff(previous) <= 1 {
+                return Ok(y);
+            }
+        }
+        Err(MathError::NoConvergence)
+    }
+}
+
+impl Curve for StableSwap {
+    fn swap_out(&self, amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+        let d = self.invariant(reserve_in, reserve_out)?;
+        let new_reserve_in = reserve_in as u128 + amount_in as u128;
+        let new_reserve_out = self.reserve_for(new_reserve_in, d)?;
+        // The solve is only good to one unit, so keep one more in the pool
+        let out = (reserve_out as u128)
+            .saturating_sub(new_reserve_out)
+            .saturating_sub(1);
+        cast(out)
+    }
+}
+
+/// `(a * b + c) / d`, rounded down, through a 256-bit intermediate.
+fn mul_add_div(a: u128, b: u128, c: u128, d: u128) -> Result<u128> {
+    if d == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    if let Some(sum) = a.checked_mul(b).and_then(|p| p.checked_add(c)) {
+        return Ok(sum / d);
+    }
+
+    // Schoolbook product of the 64-bit halves into (high, low)
+    const HALF: u32 = 64;
+    let low_mask = u64::MAX as u128;
+    let (a1, a0) = (a >> HALF, a & low_mask);
+    let (b1, b0) = (b >> HALF, b & low_mask);
+    let low_low = a0 * b0;
+    let high_low = a1 * b0;
+    let low_high = a0 * b1;
+    let middle = (low_low >> HALF) + (high_low & low_mask) + (low_high & low_mask);
+    let low = (low_low & low_mask) | (middle << HALF);
+    let high = a1 * b1 + (high_low >> HALF) + (low_high >> HALF) + (middle >> HALF);
+    let (low, carry) = low.overflowing_add(c);
+    let high = high + carry as u128;
+
+    // The quotient fits in u128 only if the high half is below `d`
+    if high >= d {
+        return Err(MathError::Overflow);
+    }
+    let (mut remainder, mut quotient) = (high, 0u128);
+    for bit in (0..128).rev() {
+        let overflowed = remainder >> 127 == 1;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if overflowed || remainder >= d {
+            remainder = remainder.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+    Ok(quotient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+    use proptest::prelude::*;
+
+    fn big(value: impl Into<BigInt>) -> BigInt {
+        value.into()
+    }
+
+    /// The invariant equation with its fraction cleared, exactly:
+    /// `4xy(4A(x + y) - (4A - 1)D) - D^3`. It falls as `D` grows and is zero
+    /// at the exact invariant, so it is non-negative for any `D` at or below
+    /// the invariant of `x` and `y`.
+    fn residual(amp: u64, x: u128, y: u128, d: u128) -> BigInt {
+        let ann = big(amp) * 4;
+        let (x, y, d) = (big(x), big(y), big(d));
+        big(4) * &x * &y * (&ann * (&x + &y) - (&ann - 1) * &d) - d.pow(3)
+    }
+
+    #[test]
+    fn test_mul_add_div_matches_big_integers() {
+        let edges = [0, 1, 2, u64::MAX as u128, 1 << 64, u128::MAX / 3, u128::MAX];
+        for a in edges {
+            for b in edges {
+                for c in edges {
+                    for d in edges.iter().filter(|&&d| d != 0) {
+                        let exact = (big(a) * big(b) + big(c)) / big(*d);
+                        let expected = u128::try_from(exact).map_err(|_| MathError::Overflow);
+                        assert_eq!(mul_add_div(a, b, c, *d), expected, "{a} * {b} + {c} / {d}");
+                    }
+                }
+            }
+        }
+        assert_eq!(mul_add_div(1, 1, 0, 0), Err(MathError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_constant_product_matches_the_exact_quotient() {
+        assert_eq!(ConstantProduct.swap_out(997, 1_000_000, 1_000_000), Ok(996));
+        assert_eq!(
+            ConstantProduct.swap_out(0, 0, 10),
+            Err(MathError::DivisionByZero)
+        );
+        assert_eq!(
+            ConstantProduct.swap_out(1, u64::MAX, 10),
+            Err(MathError::Overflow)
+        );
+        for (dx, x, y) in [(u64::MAX / 2, u64::MAX / 2, u64::MAX), (3, 7, 11)] {
+            let exact = big(y) * big(dx) / (big(x) + big(dx));
+            assert_eq!(big(ConstantProduct.swap_out(dx, x, y).unwrap()), exact);
+        }
+    }
+
+    #[test]
+    fn test_stable_swap_validates_amplification() {
+        assert_eq!(StableSwap::new(0), Err(MathError::InvalidAmplification));
+        assert_eq!(
+            StableSwap::new(StableSwap::MAX_AMP + 1),
+            Err(MathError::InvalidAmplification)
+        );
+        assert_eq!(StableSwap::new(100).map(|c| c.amp()), Ok(100));
+    }
+
+    #[test]
+    fn test_stable_swap_prices_near_parity() {
+        let curve = StableSwap::new(100).unwrap();
+        assert_eq!(curve.invariant(0, 0), Ok(0));
+        assert_eq!(curve.invariant(1_000, 1_000), Ok(2_000));
+        assert_eq!(curve.invariant(5, 0), Err(MathError::DivisionByZero));
+
+        // A balanced pool trades close to one for one, and far closer than
+        // constant product does
+        let reserve = 1_000_000_000;
+        let stable = curve.swap_out(1_000_000, reserve, reserve).unwrap();
+        let product = ConstantProduct
+            .swap_out(1_000_000, reserve, reserve)
+            .unwrap();
+        assert!(stable > product, "{stable} vs {product}");
+        assert!(stable > 999_900 && stable < 1_000_000, "{stable}");
+        assert_eq!(curve.swap_out(0, reserve, reserve), Ok(0));
+        // More than the pool holds can't come out
+        let drained = curve
+            .swap_out(u64::MAX - reserve, reserve, reserve)
+            .unwrap();
+        assert!(drained < reserve);
+    }
+
+    #[test]
+    fn test_stable_swap_converges_at_the_extremes() {
+        let mut worst = 0;
+        for amp in [1, 100, StableSwap::MAX_AMP] {
+            let curve = StableSwap::new(amp).unwrap();
+            for (x, y) in [
+                (1, 1),
+                (1, u64::MAX),
+                (u64::MAX, u64::MAX),
+                (1_000, u64::MAX / 3),
+                (u64::MAX / 2, 7),
+            ] {
+                let (d, steps) = curve.invariant_steps(x, y).unwrap();
+                worst = worst.max(steps);
+                let (x, y) = (x as u128, y as u128);
+                assert!(
+                    residual(amp, x, y, d - 1) >= big(0),
+                    "amp {amp}: D {d} too high"
+                );
+                assert!(
+                    residual(amp, x, y, d + 1) < big(0),
+                    "amp {amp}: D {d} too low"
+                );
+            }
+        }
+        assert!(worst < 64, "took {worst} steps");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_stable_invariant_is_within_one_of_the_root(
+            amp in 1..=StableSwap::MAX_AMP,
+            x in 1..=u64::MAX,
+            y in 1..=u64::MAX,
+        ) {
+            let d = StableSwap::new(amp).unwrap().invariant(x, y).unwrap();
+            let (x, y) = (x as u128, y as u128);
+            prop_assert!(residual(amp, x, y, d - 1) >= big(0));
+            prop_assert!(residual(amp, x, y, d + 1) < big(0));
+        }
+
+        #[test]
+        fn prop_stable_swap_never_shrinks_the_invariant(
+            amp in 1..=StableSwap::MAX_AMP,
+            reserve_in in 1..=u64::MAX / 2,
+            reserve_out in 1..=u64::MAX / 2,
+            amount_in in 0..=u64::MAX / 2,
+        ) {
+            let curve = StableSwap::new(amp).unwrap();
+            let out = curve.swap_out(amount_in, reserve_in, reserve_out).unwrap();
+            prop_assert!(out < reserve_out);
+            // The exact invariant after the swap is at least the exact one
+            // before: the earlier one's real root, bracketed by `d`
+            let d = curve.invariant(reserve_in, reserve_out).unwrap();
+            let after_in = reserve_in as u128 + amount_in as u128;
+            let after_out = (reserve_out - out) as u128;
+            prop_assert!(residual(amp, after_in, after_out, d) >= big(0));
+            // and the pool keeps no more than a few units beyond that
+            prop_assert!(residual(amp, after_in, after_out.saturating_sub(2), d + 1) < big(0));
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Checked integer and fixed-point math for the secure programs
+//!
+//! The arithmetic fixes in this template come down to a handful of
+//! operations: multiply then divide through a `u128` intermediate, take
+//! basis points of an amount, price a constant-product swap, and narrow the
+//! result back to `u64` without truncating. Each returns a [`MathError`]
+//! instead of wrapping or panicking, and rounds in the direction that keeps
+//! the program solvent: amounts paid out round down, amounts charged round
+//! up.
+//!
+//! [`Q64`] adds unsigned Q64.64 fixed point for rates and prices that
+//! aren't whole numbers, [`curve`] the constant-product and StableSwap
+//! curves a pool prices swaps on, and [`slippage`] the checks a trade makes
+//! against the caller's limits before it moves funds. [`amm_model`] is the
+//! exact reference the fuzzers hold the arithmetic-overflow pool to.
+//!
+//! The crate is `no_std` and, without the optional `serde` feature, has no
+//! dependencies, so Anchor and Pinocchio programs can both use it and it
+//! builds for `wasm32-unknown-unknown`; map a [`MathError`] to the
+//! program's own error at the call site.
+
+#![no_std]
+
+pub mod amm_model;
+pub mod curve;
+mod q64;
+pub mod slippage;
+
+pub use curve::{ConstantProduct, Curve, StableSwap};
+pub use q64::Q64;
+
+/// Why a calculation has no answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MathError {
+    /// The result is larger than the type holds
+    Overflow,
+    /// The result is below zero, or below the type's minimum
+    Underflow,
+    DivisionByZero,
+    /// A basis point rate above 100% where a share of the amount is meant
+    InvalidBps,
+    /// A StableSwap amplification of zero or above `StableSwap::MAX_AMP`
+    InvalidAmplification,
+    /// An iterative solve ran out of steps before settling
+    NoConvergence,
+}
+
+impl core::fmt::Display for MathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            MathError::Overflow => "math overflow",
+            MathError::Underflow => "math underflow",
+            MathError::DivisionByZero => "division by zero",
+            MathError::InvalidBps => "basis points above 10000",
+            MathError::InvalidAmplification => "amplification out of range",
+            MathError::NoConvergence => "did not converge",
+        })
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// Basis points in a whole: 10000 bps is 100%.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Convert between integer types, failing instead of truncating.
+///
+/// ```
+/// use solsec_math::{cast, MathError};
+///
+/// assert_eq!(cast::<u128, u64>(7), Ok(7));
+/// assert_eq!(cast::<u128, u64>(u128::MAX), Err(MathError::Overflow));
+/// assert_eq!(cast::<i64, u64>(-1), Err(MathError::Underflow));
+/// ```
+pub fn cast<T, U>(value: T) -> Result<U>
+where
+    T: Copy + Default + PartialOrd,
+    U: TryFrom<T>,
+{
+    U::try_from(value).map_err(|_| {
+        if value < T::default() {
+            MathError::Underflow
+        } else {
+            MathError::Overflow
+        }
+    })
+}
+
+/// `a * b / denominator`, rounded down, through a `u128` product.
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    // Two u64s always multiply within u128
+    let product = a as u128 * b as u128;
+    cast(product / denominator as u128)
+}
+
+/// `a * b / denominator`, rounded up, through a `u128` product.
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    let product = a as u128 * b as u128;
+    let denominator = denominator as u128;
+    let quotient = product / denominator;
+    let rounded = quotient + (product % denominator != 0) as u128;
+    cast(rounded)
+}
+
+/// `bps` basis points of `amount`, rounded down: a share paid out.
+pub fn bps_floor(amount: u64, bps: u64) -> Result<u64> {
+    mul_div_floor(amount, bps, BPS_DENOMINATOR)
+}
+
+/// `bps` basis points of `amount`, rounded up: a fee charged.
+pub fn bps_ceil(amount: u64, bps: u64) -> Result<u64> {
+    mul_div_ceil(amount, bps, BPS_DENOMINATOR)
+}
+
+/// What's left of `amount` after a `fee_bps` fee, the fee rounded up.
+pub fn after_fee(amount: u64, fee_bps: u64) -> Result<u64> {
+    if fee_bps > BPS_DENOMINATOR {
+        return Err(MathError::InvalidBps);
+    }
+    // The fee is at most `amount`
+    Ok(amount - bps_ceil(amount, fee_bps)?)
+}
+
+/// Output of a constant-product (`x * y = k`) swap of `amount_in` into a
+/// pool holding `reserve_in` and `reserve_out`, after a `fee_bps` fee on
+/// the input. Rounds down, so `k` never shrinks.
+pub fn swap_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> Result<u64> {
+    ConstantProduct.swap_out(after_fee(amount_in, fee_bps)?, reserve_in, reserve_out)
+}
+
+/// `part / whole` of `amount`, rounded down: a pro-rata share such as the
+/// reserves an LP token redeems.
+pub fn pro_rata(amount: u64, part: u64, whole: u64) -> Result<u64> {
+    mul_div_floor(amount, part, whole)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn reference(a: u64, b: u64, d: u64) -> (u128, u128) {
+        let product = a as u128 * b as u128;
+        let d = d as u128;
+        (product / d, product.div_ceil(d))
+    }
+
+    fn expect(value: u128) -> Result<u64> {
+        u64::try_from(value).map_err(|_| MathError::Overflow)
+    }
+
+    #[test]
+    fn test_mul_div_matches_reference_on_small_operands() {
+        for a in 0..=24u64 {
+            for b in 0..=24u64 {
+                assert_eq!(mul_div_floor(a, b, 0), Err(MathError::DivisionByZero));
+                assert_eq!(mul_div_ceil(a, b, 0), Err(MathError::DivisionByZero));
+                for d in 1..=24u64 {
+                    let (floor, ceil) = reference(a, b, d);
+                    assert_eq!(mul_div_floor(a, b, d), expect(floor));
+                    assert_eq!(mul_div_ceil(a, b, d), expect(ceil));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_at_the_edges() {
+        assert_eq!(mul_div_floor(u64::MAX, u64::MAX, u64::MAX), Ok(u64::MAX));
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX), Ok(u64::MAX));
+        assert_eq!(mul_div_floor(u64::MAX, 2, 1), Err(MathError::Overflow));
+        assert_eq!(
+            mul_div_ceil(u64::MAX, u64::MAX, u64::MAX - 1),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            mul_div_floor(u64::MAX, 9_900, 10_000),
+            Ok(18_262_276_632_972_456_098)
+        );
+    }
+
+    #[test]
+    fn test_bps_round_each_way() {
+        for bps in 0..=BPS_DENOMINATOR {
+            for amount in [0, 1, 3, 9_999, 10_000, 10_001, u64::MAX] {
+                let floor = bps_floor(amount, bps).unwrap();
+                let ceil = bps_ceil(amount, bps).unwrap();
+                assert!(floor <= ceil && ceil - floor <= 1);
+                assert_eq!(after_fee(amount, bps), Ok(amount - ceil));
+            }
+        }
+        assert_eq!(bps_ceil(1, 1), Ok(1));
+        assert_eq!(bps_floor(1, 1), Ok(0));
+        // A rate may exceed 100%, a fee may not
+        assert_eq!(bps_floor(100, 15_000), Ok(150));
+        assert_eq!(after_fee(100, 10_001), Err(MathError::InvalidBps));
+    }
+
+    #[test]
+    fn test_swap_out_matches_the_constant_product() {
+        // 1000 in against 1M:1M with a 0.3% fee: 997 after the fee
+        assert_eq!(swap_out(1_000, 1_000_000, 1_000_000, 30), Ok(996));
+        assert_eq!(swap_out(0, 10, 10, 0), Ok(0));
+        assert_eq!(swap_out(5, 0, 0, 0), Ok(0));
+        assert_eq!(swap_out(0, 0, 10, 0), Err(MathError::DivisionByZero));
+        assert_eq!(swap_out(1, u64::MAX, 10, 0), Err(MathError::Overflow));
+        assert_eq!(swap_out(1, 1, 1, 10_001), Err(MathError::InvalidBps));
+    }
+
+    #[test]
+    fn test_cast_reports_the_direction() {
+        assert_eq!(cast::<u128, u64>(u64::MAX as u128), Ok(u64::MAX));
+        assert_eq!(
+            cast::<u128, u64>(u64::MAX as u128 + 1),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(cast::<i128, u64>(-1), Err(MathError::Underflow));
+        assert_eq!(cast::<u64, i64>(u64::MAX), Err(MathError::Overflow));
+        assert_eq!(cast::<i64, i32>(i64::MIN), Err(MathError::Underflow));
+        assert_eq!(cast::<u8, u64>(255), Ok(255));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_mul_div_brackets_the_exact_quotient(a: u64, b: u64, d in 1..=u64::MAX) {
+            let (floor, ceil) = reference(a, b, d);
+            prop_assert_eq!(mul_div_floor(a, b, d), expect(floor));
+            prop_assert_eq!(mul_div_ceil(a, b, d), expect(ceil));
+            if let (Ok(floor), Ok(ceil)) = (mul_div_floor(a, b, d), mul_div_ceil(a, b, d)) {
+                let product = a as u128 * b as u128;
+                prop_assert!(floor as u128 * d as u128 <= product);
+                prop_assert!(ceil as u128 * d as u128 >= product);
+            }
+        }
+
+        #[test]
+        fn prop_fee_is_never_undercharged(amount: u64, fee in 0..=BPS_DENOMINATOR) {
+            let kept = after_fee(amount, fee).unwrap();
+            let charged = (amount - kept) as u128;
+            prop_assert!(charged * BPS_DENOMINATOR as u128 >= amount as u128 * fee as u128);
+        }
+
+        #[test]
+        fn prop_swap_never_shrinks_k(
+            amount_in in 0..=u32::MAX as u64,
+            reserve_in in 1..=u64::MAX / 2,
+            reserve_out: u64,
+            fee in 0..=BPS_DENOMINATOR,
+        ) {
+            let out = swap_out(amount_in, reserve_in, reserve_out, fee).unwrap();
+            prop_assert!(out < reserve_out || reserve_out == 0);
+            let before = reserve_in as u128 * reserve_out as u128;
+            let after = (reserve_in as u128 + amount_in as u128) * (reserve_out - out) as u128;
+            prop_assert!(after >= before);
+        }
+
+        #[test]
+        fn prop_pro_rata_shares_never_exceed_the_whole(
+            amount: u64,
+            whole in 1..=u64::MAX,
+            split: u64,
+        ) {
+            let part = split % whole;
+            let first = pro_rata(amount, part, whole).unwrap();
+            let rest = pro_rata(amount, whole - part, whole).unwrap();
+            prop_assert!(first as u128 + rest as u128 <= amount as u128);
+        }
+    }
+}
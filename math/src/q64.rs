@@ -0,0 +1,246 @@
+//! Unsigned Q64.64 fixed point
+
+use crate::{cast, MathError, Result};
+
+/// An unsigned fixed-point number with 64 integer and 64 fractional bits,
+/// stored as its `u128` bits: `value = bits / 2^64`.
+///
+/// Multiplying two `Q64`s or scaling a `u64` by one needs up to 192 bits,
+/// so those go through the halves rather than a single `u128` product.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Q64(u128);
+
+const FRACTION_BITS: u32 = 64;
+const LOW: u128 = u64::MAX as u128;
+
+impl Q64 {
+    pub const ZERO: Q64 = Q64(0);
+    pub const ONE: Q64 = Q64(1 << FRACTION_BITS);
+    pub const MAX: Q64 = Q64(u128::MAX);
+
+    pub const fn from_bits(bits: u128) -> Self {
+        Q64(bits)
+    }
+
+    pub const fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    pub const fn from_int(value: u64) -> Self {
+        Q64((value as u128) << FRACTION_BITS)
+    }
+
+    /// `numerator / denominator`, rounded down to the nearest 2^-64.
+    pub fn from_ratio(numerator: u64, denominator: u64) -> Result<Self> {
+        if denominator == 0 {
+            return Err(MathError::DivisionByZero);
+        }
+        // `numerator << 64` fits in u128
+        Ok(Q64(
+            ((numerator as u128) << FRACTION_BITS) / denominator as u128
+        ))
+    }
+
+    /// The integer part.
+    pub const fn floor(self) -> u64 {
+        (self.0 >> FRACTION_BITS) as u64
+    }
+
+    /// The smallest integer at least `self`.
+    pub fn ceil(self) -> Result<u64> {
+        let rounded = self.floor() as u128 + (self.0 & LOW != 0) as u128;
+        cast(rounded)
+    }
+
+    pub fn checked_add(self, rhs: Q64) -> Result<Q64> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Q64)
+            .ok_or(MathError::Overflow)
+    }
+
+    pub fn checked_sub(self, rhs: Q64) -> Result<Q64> {
+        self.0
+            .checked_sub(rhs.0)
+            .map(Q64)
+            .ok_or(MathError::Underflow)
+    }
+
+    /// `self * rhs`, rounded down.
+    pub fn checked_mul(self, rhs: Q64) -> Result<Q64> {
+        let (a_hi, a_lo) = (self.0 >> FRACTION_BITS, self.0 & LOW);
+        let (b_hi, b_lo) = (rhs.0 >> FRACTION_BITS, rhs.0 & LOW);
+        // (a_hi 2^64 + a_lo)(b_hi 2^64 + b_lo) / 2^64, each product within u128
+        let whole = a_hi * b_hi;
+        if whole > LOW {
+            return Err(MathError::Overflow);
+        }
+        let high = whole << FRACTION_BITS;
+        [a_hi * b_lo, a_lo * b_hi, (a_lo * b_lo) >> FRACTION_BITS]
+            .into_iter()
+            .try_fold(high, |sum, term| sum.checked_add(term))
+            .map(Q64)
+            .ok_or(MathError::Overflow)
+    }
+
+    /// `self / rhs`, rounded down.
+    pub fn checked_div(self, rhs: Q64) -> Result<Q64> {
+        if rhs.0 == 0 {
+            return Err(MathError::DivisionByZero);
+        }
+        // (self << 64) / rhs: the integer part first, then the fraction one
+        // bit at a time by long division
+        let integer = self.0 / rhs.0;
+        if integer > LOW {
+            return Err(MathError::Overflow);
+        }
+        let mut remainder = self.0 % rhs.0;
+        let mut fraction = 0u128;
+        for _ in 0..FRACTION_BITS {
+            // `remainder < rhs`, so twice it exceeds u128 only if it's at
+            // least `rhs`, and subtracting brings it back in range
+            let carry = remainder >> 127 == 1;
+            remainder <<= 1;
+            fraction <<= 1;
+            if carry || remainder >= rhs.0 {
+                remainder = remainder.wrapping_sub(rhs.0);
+                fraction |= 1;
+            }
+        }
+        Ok(Q64((integer << FRACTION_BITS) | fraction))
+    }
+
+    /// `self * value`, rounded down to an integer: an amount paid out.
+    pub fn mul_floor(self, value: u64) -> Result<u64> {
+        let (whole, _) = self.scale(value);
+        cast(whole)
+    }
+
+    /// `self * value`, rounded up to an integer: an amount charged.
+    pub fn mul_ceil(self, value: u64) -> Result<u64> {
+        let (whole, rest) = self.scale(value);
+        let rounded = whole.checked_add((rest != 0) as u128);
+        cast(rounded.ok_or(MathError::Overflow)?)
+    }
+
+    // `self * value` as its integer part (up to 128 bits) and fraction
+    fn scale(self, value: u64) -> (u128, u128) {
+        let value = value as u128;
+        let high = (self.0 >> FRACTION_BITS) * value;
+        let low = (self.0 & LOW) * value;
+        // `high` is below 2^128 - 2^65 and `low >> 64` below 2^64
+        (high + (low >> FRACTION_BITS), low & LOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_integers_round_trip() {
+        for value in [0, 1, 2, 1_000_000, u64::MAX] {
+            let q = Q64::from_int(value);
+            assert_eq!(q.floor(), value);
+            assert_eq!(q.ceil(), Ok(value));
+            assert_eq!(q.mul_floor(1), Ok(value));
+            assert_eq!(Q64::ONE.mul_floor(value), Ok(value));
+        }
+        assert_eq!(Q64::from_ratio(1, 0), Err(MathError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_ratios_round_each_way() {
+        let third = Q64::from_ratio(1, 3).unwrap();
+        assert_eq!(third.floor(), 0);
+        assert_eq!(third.ceil(), Ok(1));
+        assert_eq!(third.mul_floor(3), Ok(0));
+        assert_eq!(third.mul_ceil(3), Ok(1));
+        assert_eq!(third.mul_floor(300), Ok(99));
+        assert_eq!(third.mul_ceil(300), Ok(100));
+
+        let half = Q64::from_ratio(1, 2).unwrap();
+        assert_eq!(half.mul_floor(7), Ok(3));
+        assert_eq!(half.mul_ceil(7), Ok(4));
+        assert_eq!(half.checked_mul(half), Q64::from_ratio(1, 4));
+        assert_eq!(
+            Q64::ONE.checked_div(Q64::from_int(4)),
+            Q64::from_ratio(1, 4)
+        );
+        assert_eq!(Q64::MAX.ceil(), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_overflow_and_underflow_are_errors() {
+        let big = Q64::from_int(u64::MAX);
+        assert_eq!(big.checked_mul(Q64::from_int(2)), Err(MathError::Overflow));
+        assert_eq!(big.checked_mul(Q64::ONE), Ok(big));
+        assert_eq!(
+            Q64::MAX.checked_add(Q64::from_bits(1)),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            Q64::ZERO.checked_sub(Q64::from_bits(1)),
+            Err(MathError::Underflow)
+        );
+        assert_eq!(
+            big.checked_div(Q64::from_ratio(1, 2).unwrap()),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            Q64::ONE.checked_div(Q64::ZERO),
+            Err(MathError::DivisionByZero)
+        );
+        assert_eq!(
+            Q64::from_int(3).mul_floor(u64::MAX),
+            Err(MathError::Overflow)
+        );
+        // A remainder above 2^127 doubles past u128 mid-division
+        let near = Q64::from_bits(u128::MAX - 1);
+        assert_eq!(
+            near.checked_div(Q64::MAX),
+            Ok(Q64::from_bits(0xFFFF_FFFF_FFFF_FFFF))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_scaling_matches_the_ratio(numerator: u64, denominator in 1..=u64::MAX, value: u64) {
+            let q = Q64::from_ratio(numerator, denominator).unwrap();
+            let exact = (numerator as u128 * value as u128) / denominator as u128;
+            // `q` is below the ratio by under 2^-64, so `q * value` by under one
+            if let Ok(floor) = q.mul_floor(value) {
+                prop_assert!(floor as u128 <= exact && exact - floor as u128 <= 1);
+            }
+            if let (Ok(floor), Ok(ceil)) = (q.mul_floor(value), q.mul_ceil(value)) {
+                prop_assert!(ceil == floor || ceil == floor + 1);
+            }
+        }
+
+        #[test]
+        fn prop_mul_then_div_stays_at_or_below(a: u128, b in 1u128..=u128::MAX) {
+            let (a, b) = (Q64::from_bits(a), Q64::from_bits(b));
+            if let Ok(product) = a.checked_mul(b) {
+                let back = product.checked_div(b).unwrap();
+                prop_assert!(back <= a);
+            }
+        }
+
+        #[test]
+        fn prop_mul_matches_wide_reference(a: u64, b: u64, fa: u64, fb: u64) {
+            let (x, y) = (Q64::from_int(a), Q64::from_bits(fb as u128));
+            // An integer times a fraction is exact to a 2^-64
+            let product = x.checked_mul(y).unwrap();
+            prop_assert_eq!(product.to_bits(), a as u128 * fb as u128);
+            let (x, y) = (Q64::from_bits(fa as u128), Q64::from_int(b));
+            prop_assert_eq!(x.checked_mul(y).unwrap().to_bits(), fa as u128 * b as u128);
+        }
+
+        #[test]
+        fn prop_div_inverts_from_ratio(numerator: u64, denominator in 1..=u64::MAX) {
+            let q = Q64::from_int(numerator).checked_div(Q64::from_int(denominator));
+            prop_assert_eq!(q, Q64::from_ratio(numerator, denominator));
+        }
+    }
+}
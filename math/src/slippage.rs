@@ -0,0 +1,186 @@
+//! Slippage, deadline and price-impact checks for trades
+
+use crate::{mul_div_ceil, MathError, BPS_DENOMINATOR};
+
+/// Why a trade is refused at execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlippageError {
+    /// The trade would pay out less than the caller's minimum
+    BelowMinOut,
+    /// The trade would charge more than the caller's maximum
+    AboveMaxIn,
+    /// The trade landed after the caller's deadline
+    Expired,
+    /// The trade would move the price further than allowed
+    PriceImpact,
+    Math(MathError),
+}
+
+impl core::fmt::Display for SlippageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SlippageError::BelowMinOut => f.write_str("output below minimum"),
+            SlippageError::AboveMaxIn => f.write_str("input above maximum"),
+            SlippageError::Expired => f.write_str("deadline passed"),
+            SlippageError::PriceImpact => f.write_str("price impact above limit"),
+            SlippageError::Math(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<MathError> for SlippageError {
+    fn from(error: MathError) -> Self {
+        SlippageError::Math(error)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, SlippageError>;
+
+/// Refuse an output below what the caller signed for.
+pub fn check_min_out(amount_out: u64, min_out: u64) -> Result<()> {
+    if amount_out < min_out {
+        return Err(SlippageError::BelowMinOut);
+    }
+    Ok(())
+}
+
+/// Refuse an input above what the caller signed for.
+pub fn check_max_in(amount_in: u64, max_in: u64) -> Result<()> {
+    if amount_in > max_in {
+        return Err(SlippageError::AboveMaxIn);
+    }
+    Ok(())
+}
+
+/// Refuse a trade landing after `deadline`, both Unix timestamps as the
+/// `Clock` sysvar gives them. The deadline itself still counts.
+///
+/// A minimum output only bounds the price the caller accepts; a
+/// transaction held back by a validator can still fill at that price
+/// long after the market moved in the caller's favor.
+pub fn check_deadline(now: i64, deadline: i64) -> Result<()> {
+    if now > deadline {
+        return Err(SlippageError::Expired);
+    }
+    Ok(())
+}
+
+/// How far a constant-product swap of `amount_in` into `reserve_in` moves
+/// the price, in basis points, before fees: the execution price is
+/// `reserve_in / (reserve_in + amount_in)` of the spot price. Rounds up,
+/// so a limit is never exceeded by rounding.
+pub fn price_impact_bps(amount_in: u64, reserve_in: u64) -> crate::Result<u64> {
+    let new_reserve_in = reserve_in
+        .checked_add(amount_in)
+        .ok_or(MathError::Overflow)?;
+    mul_div_ceil(amount_in, BPS_DENOMINATOR, new_reserve_in)
+}
+
+/// Refuse a swap whose [`price_impact_bps`] is above `max_bps`.
+pub fn check_price_impact(amount_in: u64, reserve_in: u64, max_bps: u64) -> Result<()> {
+    if price_impact_bps(amount_in, reserve_in)? > max_bps {
+        return Err(SlippageError::PriceImpact);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_bounds_are_inclusive() {
+        assert_eq!(check_min_out(100, 100), Ok(()));
+        assert_eq!(check_min_out(99, 100), Err(SlippageError::BelowMinOut));
+        assert_eq!(check_max_in(100, 100), Ok(()));
+        assert_eq!(check_max_in(101, 100), Err(SlippageError::AboveMaxIn));
+        assert_eq!(check_deadline(1_700_000_000, 1_700_000_000), Ok(()));
+        assert_eq!(
+            check_deadline(1_700_000_001, 1_700_000_000),
+            Err(SlippageError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_price_impact() {
+        // Swapping in a pool's whole reserve halves the price
+        assert_eq!(price_impact_bps(1_000, 1_000), Ok(5_000));
+        assert_eq!(price_impact_bps(0, 1_000), Ok(0));
+        // 1 / 10001 of the way is still a basis point
+        assert_eq!(price_impact_bps(1, 10_000), Ok(1));
+        assert_eq!(price_impact_bps(1, 0), Ok(BPS_DENOMINATOR));
+        assert_eq!(price_impact_bps(0, 0), Err(MathError::DivisionByZero));
+        assert_eq!(price_impact_bps(1, u64::MAX), Err(MathError::Overflow));
+
+        assert_eq!(check_price_impact(1_000, 1_000, 5_000), Ok(()));
+        assert_eq!(
+            check_price_impact(1_000, 1_000, 4_999),
+            Err(SlippageError::PriceImpact)
+        );
+        assert_eq!(
+            check_price_impact(0, 0, BPS_DENOMINATOR),
+            Err(SlippageError::Math(MathError::DivisionByZero))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_min_out_holds_for_more_out_or_a_lower_minimum(
+            out: u64,
+            min: u64,
+            more: u64,
+            less: u64,
+        ) {
+            if check_min_out(out, min).is_ok() {
+                prop_assert!(check_min_out(out.saturating_add(more), min.saturating_sub(less)).is_ok());
+            }
+        }
+
+        #[test]
+        fn prop_max_in_holds_for_less_in_or_a_higher_maximum(
+            amount: u64,
+            max: u64,
+            less: u64,
+            more: u64,
+        ) {
+            if check_max_in(amount, max).is_ok() {
+                prop_assert!(check_max_in(amount.saturating_sub(less), max.saturating_add(more)).is_ok());
+            }
+        }
+
+        #[test]
+        fn prop_deadline_holds_earlier_or_later(now: i64, deadline: i64, earlier: u32, later: u32) {
+            if check_deadline(now, deadline).is_ok() {
+                let now = now.saturating_sub(earlier.into());
+                prop_assert!(check_deadline(now, deadline.saturating_add(later.into())).is_ok());
+            }
+        }
+
+        #[test]
+        fn prop_price_impact_grows_with_size_and_shrinks_with_depth(
+            amount in 0..=u64::MAX / 4,
+            reserve in 1..=u64::MAX / 4,
+            more in 0..=u64::MAX / 4,
+        ) {
+            let impact = price_impact_bps(amount, reserve).unwrap();
+            prop_assert!(impact <= BPS_DENOMINATOR);
+            prop_assert!(price_impact_bps(amount + more, reserve).unwrap() >= impact);
+            prop_assert!(price_impact_bps(amount, reserve + more).unwrap() <= impact);
+        }
+
+        #[test]
+        fn prop_price_impact_limit_holds_for_smaller_trades_or_looser_limits(
+            amount in 0..=u64::MAX / 2,
+            reserve in 1..=u64::MAX / 2,
+            max in 0..=BPS_DENOMINATOR,
+            less: u64,
+            looser: u64,
+        ) {
+            if check_price_impact(amount, reserve, max).is_ok() {
+                let amount = amount.saturating_sub(less);
+                prop_assert!(check_price_impact(amount, reserve, max.saturating_add(looser)).is_ok());
+            }
+        }
+    }
+}
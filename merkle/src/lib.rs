@@ -0,0 +1,252 @@
+//! Merkle proofs for the secure programs
+//!
+//! An airdrop or allow list stores one root and lets each claimant prove
+//! their entry. The proof is only as strong as its encoding, and the usual
+//! bugs are in the encoding rather than the hash:
+//!
+//! - A leaf hashed the same way as an inner node lets an attacker claim an
+//!   inner node's two children as a leaf (a second preimage). Leaves here
+//!   are hashed behind a `0x00` byte and nodes behind `0x01`.
+//! - Sorting each pair before hashing makes a proof valid for any position,
+//!   so a claim's index is never bound to it. Here the index's bits pick
+//!   which side each sibling goes on, from the leaf up, and [`verify`]
+//!   rejects an index with bits above the proof's depth.
+//! - A leaf of only the claimant, or only the amount, can be replayed
+//!   against another entry. [`Leaf`] commits to the index, the claimant and
+//!   the amount together.
+//!
+//! ```
+//! use solsec_merkle::{verify, Leaf, Sha256};
+//!
+//! let leaf = Leaf { index: 0, claimant: [7; 32], amount: 100 }.hash::<Sha256>();
+//! // A tree of one leaf is its own root, with an empty proof
+//! assert_eq!(verify::<Sha256>(&leaf, &leaf, 0, &[]), Ok(()));
+//! ```
+//!
+//! Pick [`Sha256`] or [`Keccak256`] to match whoever builds the tree; both
+//! use the runtime's syscall on-chain. The `builder` feature adds
+//! [`MerkleTree`] to build roots and proofs in tests and off-chain tools.
+//! The crate is `no_std`.
+
+#![no_std]
+
+#[cfg(feature = "builder")]
+extern crate alloc;
+
+#[cfg(feature = "builder")]
+mod tree;
+
+#[cfg(feature = "builder")]
+pub use tree::MerkleTree;
+
+/// A 32-byte hash: a leaf, a node or a root.
+pub type Hash = [u8; 32];
+
+/// Prefix of every leaf hash
+pub const LEAF_PREFIX: u8 = 0;
+/// Prefix of every inner node hash
+pub const NODE_PREFIX: u8 = 1;
+/// The deepest proof [`verify`] accepts: one sibling per bit of a `u64`
+/// index.
+pub const MAX_DEPTH: usize = 64;
+
+/// Why a proof was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MerkleError {
+    /// More siblings than [`MAX_DEPTH`]
+    ProofTooLong,
+    /// The index has bits above the proof's depth, so it names a leaf the
+    /// tree can't hold
+    IndexOutOfRange,
+    /// The proof leads to a different root
+    RootMismatch,
+}
+
+impl core::fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            MerkleError::ProofTooLong => "merkle proof too long",
+            MerkleError::IndexOutOfRange => "leaf index beyond the proof's depth",
+            MerkleError::RootMismatch => "merkle proof does not match the root",
+        })
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MerkleError>;
+
+/// A 32-byte hash over several slices, as if concatenated.
+pub trait Hasher {
+    fn hashv(parts: &[&[u8]]) -> Hash;
+}
+
+/// SHA-256, Solana's usual choice
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Sha256;
+
+impl Hasher for Sha256 {
+    fn hashv(parts: &[&[u8]]) -> Hash {
+        solana_sha256_hasher::hashv(parts).to_bytes()
+    }
+}
+
+/// Keccak-256, for trees built by Ethereum tooling
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Keccak256;
+
+impl Hasher for Keccak256 {
+    fn hashv(parts: &[&[u8]]) -> Hash {
+        solana_keccak_hasher::hashv(parts).to_bytes()
+    }
+}
+
+/// The leaf hash of `data`.
+pub fn hash_leaf<H: Hasher>(data: &[u8]) -> Hash {
+    H::hashv(&[&[LEAF_PREFIX], data])
+}
+
+/// The parent of `left` and `right`, in that order.
+pub fn hash_node<H: Hasher>(left: &Hash, right: &Hash) -> Hash {
+    H::hashv(&[&[NODE_PREFIX], left, right])
+}
+
+/// A claim in a distribution: `amount` to `claimant`, at `index`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Leaf {
+    pub index: u64,
+    pub claimant: [u8; 32],
+    pub amount: u64,
+}
+
+impl Leaf {
+    /// The encoding hashed into the tree: the index, the claimant and the
+    /// amount, the integers little-endian.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        let mut bytes = [0; 48];
+        bytes[..8].copy_from_slice(&self.index.to_le_bytes());
+        bytes[8..40].copy_from_slice(&self.claimant);
+        bytes[40..].copy_from_slice(&self.amount.to_le_bytes());
+        bytes
+    }
+
+    pub fn hash<H: Hasher>(&self) -> Hash {
+        hash_leaf::<H>(&self.to_bytes())
+    }
+}
+
+/// The root `proof` leads to from `leaf` at `index`, with the siblings
+/// ordered from the leaf up.
+pub fn compute_root<H: Hasher>(leaf: &Hash, index: u64, proof: &[Hash]) -> Result<Hash> {
+    if proof.len() > MAX_DEPTH {
+        return Err(MerkleError::ProofTooLong);
+    }
+    // Bits past the depth would be ignored, letting one proof claim many
+    // indices
+    if proof.len() < MAX_DEPTH && index >> proof.len() != 0 {
+        return Err(MerkleError::IndexOutOfRange);
+    }
+    let mut node = *leaf;
+    for (level, sibling) in proof.iter().enumerate() {
+        node = if index >> level & 1 == 0 {
+            hash_node::<H>(&node, sibling)
+        } else {
+            hash_node::<H>(sibling, &node)
+        };
+    }
+    Ok(node)
+}
+
+/// `proof` shows `leaf` is at `index` in the tree with `root`.
+pub fn verify<H: Hasher>(root: &Hash, leaf: &Hash, index: u64, proof: &[Hash]) -> Result<()> {
+    if compute_root::<H>(leaf, index, proof)? != *root {
+        return Err(MerkleError::RootMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashers_match_known_digests() {
+        // SHA-256 and Keccak-256 of the empty string
+        assert_eq!(
+            Sha256::hashv(&[]),
+            [
+                0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f,
+                0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b,
+                0x78, 0x52, 0xb8, 0x55,
+            ]
+        );
+        assert_eq!(
+            Keccak256::hashv(&[]),
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70,
+            ]
+        );
+        // Slices hash as their concatenation
+        assert_eq!(Sha256::hashv(&[b"ab", b"c"]), Sha256::hashv(&[b"abc"]));
+    }
+
+    #[test]
+    fn test_leaf_encoding() {
+        let leaf = Leaf {
+            index: 1,
+            claimant: [2; 32],
+            amount: 3,
+        };
+        let bytes = leaf.to_bytes();
+        assert_eq!(bytes[..8], 1u64.to_le_bytes());
+        assert_eq!(bytes[8..40], [2; 32]);
+        assert_eq!(bytes[40..], 3u64.to_le_bytes());
+        assert_eq!(leaf.hash::<Sha256>(), hash_leaf::<Sha256>(&bytes));
+        assert_ne!(leaf.hash::<Sha256>(), leaf.hash::<Keccak256>());
+
+        // Each field is committed to
+        for other in [
+            Leaf { index: 2, ..leaf },
+            Leaf {
+                claimant: [3; 32],
+                ..leaf
+            },
+            Leaf { amount: 4, ..leaf },
+        ] {
+            assert_ne!(other.hash::<Sha256>(), leaf.hash::<Sha256>());
+        }
+    }
+
+    #[test]
+    fn test_two_leaves_by_hand() {
+        let (a, b) = (hash_leaf::<Sha256>(b"a"), hash_leaf::<Sha256>(b"b"));
+        let root = hash_node::<Sha256>(&a, &b);
+        assert_eq!(verify::<Sha256>(&root, &a, 0, &[b]), Ok(()));
+        assert_eq!(verify::<Sha256>(&root, &b, 1, &[a]), Ok(()));
+        // Swapping sides, or claiming the other slot, fails
+        assert_eq!(
+            verify::<Sha256>(&root, &a, 1, &[b]),
+            Err(MerkleError::RootMismatch)
+        );
+        assert_eq!(
+            verify::<Sha256>(&root, &a, 2, &[b]),
+            Err(MerkleError::IndexOutOfRange)
+        );
+        assert_eq!(
+            verify::<Sha256>(&root, &a, 0, &[b; MAX_DEPTH + 1]),
+            Err(MerkleError::ProofTooLong)
+        );
+    }
+
+    #[test]
+    fn test_full_depth_index_is_accepted() {
+        let leaf = hash_leaf::<Sha256>(b"deep");
+        let proof = [[9; 32]; MAX_DEPTH];
+        let root = compute_root::<Sha256>(&leaf, u64::MAX, &proof).unwrap();
+        assert_eq!(verify::<Sha256>(&root, &leaf, u64::MAX, &proof), Ok(()));
+        assert_eq!(
+            verify::<Sha256>(&root, &leaf, u64::MAX - 1, &proof),
+            Err(MerkleError::RootMismatch)
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Building trees and proofs off-chain
+
+use crate::{hash_node, Hash, Hasher, Leaf};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// The hash padding a tree out to a power of two. No leaf or node hashes
+/// to it, so a proof of a padding slot can't be built.
+pub const EMPTY: Hash = [0; 32];
+
+/// Every level of a tree, from its leaves up to its root, for building
+/// the root a program stores and the proof each claimant sends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree<H> {
+    levels: Vec<Vec<Hash>>,
+    len: usize,
+    hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> MerkleTree<H> {
+    /// A tree over leaf hashes, in index order. An empty tree's root is
+    /// [`EMPTY`].
+    pub fn new(leaves: &[Hash]) -> Self {
+        let mut level = leaves.to_vec();
+        level.resize(leaves.len().next_power_of_two(), EMPTY);
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            let parent = level
+                .chunks_exact(2)
+                .map(|pair| hash_node::<H>(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+            level = parent;
+        }
+        levels.push(level);
+        MerkleTree {
+            levels,
+            len: leaves.len(),
+            hasher: PhantomData,
+        }
+    }
+
+    /// A tree over claims, each at the position its `index` names.
+    ///
+    /// # Panics
+    ///
+    /// If a claim's index isn't its position in `claims`.
+    pub fn from_claims(claims: &[Leaf]) -> Self {
+        let leaves: Vec<Hash> = claims
+            .iter()
+            .enumerate()
+            .map(|(position, claim)| {
+                assert_eq!(claim.index, position as u64, "claim out of order");
+                claim.hash::<H>()
+            })
+            .collect();
+        Self::new(&leaves)
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels.last().map_or(EMPTY, |root| root[0])
+    }
+
+    /// The number of leaves, not counting padding.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The length of every proof in the tree.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// The siblings from leaf `index` up to the root, or `None` past the
+    /// last leaf.
+    pub fn proof(&self, index: u64) -> Option<Vec<Hash>> {
+        let index = usize::try_from(index).ok().filter(|&i| i < self.len)?;
+        Some(
+            self.levels[..self.depth()]
+                .iter()
+                .enumerate()
+                .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hash_leaf, verify, Keccak256, MerkleError, Sha256};
+    use proptest::prelude::*;
+
+    fn claims(amounts: &[u64]) -> Vec<Leaf> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(index, &amount)| Leaf {
+                index: index as u64,
+                claimant: [index as u8; 32],
+                amount,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_small_trees() {
+        let empty = MerkleTree::<Sha256>::new(&[]);
+        assert_eq!((empty.root(), empty.len(), empty.depth()), (EMPTY, 0, 0));
+        assert_eq!(empty.proof(0), None);
+
+        let one = MerkleTree::<Sha256>::new(&[hash_leaf::<Sha256>(b"a")]);
+        assert_eq!(one.root(), hash_leaf::<Sha256>(b"a"));
+        assert_eq!(one.proof(0), Some(Vec::new()));
+
+        // Three leaves pad to four, and the padding has no proof
+        let leaves = [b"a", b"b", b"c"].map(|data| hash_leaf::<Sha256>(data));
+        let tree = MerkleTree::<Sha256>::new(&leaves);
+        let expected = hash_node::<Sha256>(
+            &hash_node::<Sha256>(&leaves[0], &leaves[1]),
+            &hash_node::<Sha256>(&leaves[2], &EMPTY),
+        );
+        assert_eq!(tree.root(), expected);
+        assert_eq!(tree.depth(), 2);
+        assert_eq!(tree.proof(3), None);
+        assert_eq!(
+            verify::<Sha256>(&tree.root(), &EMPTY, 3, &[leaves[2], tree.levels[1][0]]),
+            Ok(()),
+            "the padding proves only the zero hash, which no leaf hashes to"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "claim out of order")]
+    fn test_claims_must_be_in_index_order() {
+        let mut claims = claims(&[1, 2]);
+        claims.swap(0, 1);
+        MerkleTree::<Sha256>::from_claims(&claims);
+    }
+
+    #[test]
+    fn test_hashers_build_different_trees() {
+        let claims = claims(&[1, 2, 3]);
+        let sha = MerkleTree::<Sha256>::from_claims(&claims);
+        let keccak = MerkleTree::<Keccak256>::from_claims(&claims);
+        assert_ne!(sha.root(), keccak.root());
+        let proof = keccak.proof(1).unwrap();
+        let leaf = claims[1].hash::<Keccak256>();
+        assert_eq!(
+            verify::<Keccak256>(&keccak.root(), &leaf, 1, &proof),
+            Ok(())
+        );
+        assert_eq!(
+            verify::<Sha256>(&keccak.root(), &leaf, 1, &proof),
+            Err(MerkleError::RootMismatch)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_every_claim_verifies(amounts in prop::collection::vec(any::<u64>(), 1..70)) {
+            let claims = claims(&amounts);
+            let tree = MerkleTree::<Sha256>::from_claims(&claims);
+            for claim in &claims {
+                let proof = tree.proof(claim.index).unwrap();
+                prop_assert_eq!(proof.len(), tree.depth());
+                prop_assert_eq!(
+                    verify::<Sha256>(&tree.root(), &claim.hash::<Sha256>(), claim.index, &proof),
+                    Ok(())
+                );
+            }
+        }
+
+        #[test]
+        fn prop_claims_bind_their_index_and_amount(
+            amounts in prop::collection::vec(any::<u64>(), 2..70),
+            pick: prop::sample::Index,
+            other: prop::sample::Index,
+            bump in 1..=u64::MAX,
+        ) {
+            let claims = claims(&amounts);
+            let tree = MerkleTree::<Sha256>::from_claims(&claims);
+            let claim = claims[pick.index(claims.len())];
+            let proof = tree.proof(claim.index).unwrap();
+
+            // The right proof under another index, whether re-encoded in
+            // the leaf or only passed to `verify`
+            let wrong = other.index(claims.len()) as u64;
+            prop_assume!(wrong != claim.index);
+            let moved = Leaf { index: wrong, ..claim };
+            prop_assert!(verify::<Sha256>(&tree.root(), &moved.hash::<Sha256>(), wrong, &proof).is_err());
+            prop_assert!(verify::<Sha256>(&tree.root(), &claim.hash::<Sha256>(), wrong, &proof).is_err());
+
+            // Or at its own index with a different amount
+            let inflated = Leaf { amount: claim.amount.wrapping_add(bump), ..claim };
+            prop_assert!(verify::<Sha256>(&tree.root(), &inflated.hash::<Sha256>(), claim.index, &proof).is_err());
+
+            // Or with an index past the tree that agrees in the low bits
+            let aliased = claim.index | 1 << tree.depth();
+            prop_assert_eq!(
+                verify::<Sha256>(&tree.root(), &claim.hash::<Sha256>(), aliased, &proof),
+                Err(MerkleError::IndexOutOfRange)
+            );
+        }
+
+        #[test]
+        fn prop_swapped_siblings_fail(
+            amounts in prop::collection::vec(any::<u64>(), 3..70),
+            pick: prop::sample::Index,
+            a: prop::sample::Index,
+            b: prop::sample::Index,
+        ) {
+            let claims = claims(&amounts);
+            let tree = MerkleTree::<Sha256>::from_claims(&claims);
+            let claim = claims[pick.index(claims.len())];
+            let leaf = claim.hash::<Sha256>();
+            let mut proof = tree.proof(claim.index).unwrap();
+            let (a, b) = (a.index(proof.len()), b.index(proof.len()));
+            prop_assume!(proof[a] != proof[b]);
+            proof.swap(a, b);
+            prop_assert_eq!(
+                verify::<Sha256>(&tree.root(), &leaf, claim.index, &proof),
+                Err(MerkleError::RootMismatch)
+            );
+
+            // Flipping which side the leaf sits on at one level is the
+            // same proof under the neighbouring index
+            proof.swap(a, b);
+            let flipped = claim.index ^ 1 << a;
+            prop_assert!(verify::<Sha256>(&tree.root(), &leaf, flipped, &proof).is_err());
+        }
+
+        #[test]
+        fn prop_inner_nodes_are_not_leaves(
+            amounts in prop::collection::vec(any::<u64>(), 2..70),
+            pick: prop::sample::Index,
+            height in 1usize..7,
+        ) {
+            // The second preimage: present an inner node's children as a
+            // leaf's data, with the proof above that node
+            let claims = claims(&amounts);
+            let tree = MerkleTree::<Sha256>::from_claims(&claims);
+            let height = height.min(tree.depth());
+            let position = pick.index(claims.len()) >> height;
+            let children = &tree.levels[height - 1][position * 2..position * 2 + 2];
+            let mut data = [0; 64];
+            data[..32].copy_from_slice(&children[0]);
+            data[32..].copy_from_slice(&children[1]);
+            let proof = &tree.proof((position << height) as u64).unwrap()[height..];
+
+            // Hashed as a node it is the tree's own, so the proof is right;
+            // hashed as a leaf it leads nowhere
+            let node = hash_node::<Sha256>(&children[0], &children[1]);
+            prop_assert_eq!(node, tree.levels[height][position]);
+            prop_assert_eq!(verify::<Sha256>(&tree.root(), &node, position as u64, proof), Ok(()));
+            prop_assert_eq!(
+                verify::<Sha256>(&tree.root(), &hash_leaf::<Sha256>(&data), position as u64, proof),
+                Err(MerkleError::RootMismatch)
+            );
+        }
+    }
+}
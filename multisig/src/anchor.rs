@@ -0,0 +1,92 @@
+//! Borsh and errors for Anchor programs
+
+use crate::{Multisig, MultisigError, Proposal};
+use anchor_lang::{
+    error::Error,
+    prelude::{borsh::maybestd::io, ProgramError},
+    AnchorDeserialize, AnchorSerialize, Space,
+};
+
+// So `#[account]` structs can hold them
+impl AnchorSerialize for Multisig {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl AnchorDeserialize for Multisig {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; Multisig::LEN];
+        reader.read_exact(&mut bytes)?;
+        Multisig::from_bytes(&bytes).map_err(|_| io::ErrorKind::InvalidData.into())
+    }
+}
+
+impl Space for Multisig {
+    const INIT_SPACE: usize = Multisig::LEN;
+}
+
+impl AnchorSerialize for Proposal {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+impl AnchorDeserialize for Proposal {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; Proposal::LEN];
+        reader.read_exact(&mut bytes)?;
+        Proposal::from_bytes(&bytes).map_err(|_| io::ErrorKind::InvalidData.into())
+    }
+}
+
+impl Space for Proposal {
+    const INIT_SPACE: usize = Proposal::LEN;
+}
+
+impl From<MultisigError> for Error {
+    fn from(error: MultisigError) -> Self {
+        match error {
+            MultisigError::NoMembers
+            | MultisigError::TooManyMembers
+            | MultisigError::DuplicateMember
+            | MultisigError::InvalidThreshold => ProgramError::InvalidArgument.into(),
+            // Too few of the signatures the action needs
+            MultisigError::NotMember | MultisigError::BelowThreshold => {
+                ProgramError::MissingRequiredSignature.into()
+            }
+            MultisigError::AlreadyApproved
+            | MultisigError::NotApproved
+            | MultisigError::UnknownProposal
+            | MultisigError::Stale
+            | MultisigError::AlreadyExecuted
+            | MultisigError::DigestMismatch
+            | MultisigError::InvalidLayout => ProgramError::InvalidAccountData.into(),
+            MultisigError::IndexExhausted => ProgramError::ArithmeticOverflow.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borsh_matches_the_byte_layout() {
+        let members = [[1; 32], [2; 32]];
+        let mut multisig = Multisig::new(&members, 2).unwrap();
+        let mut proposal = multisig.propose(&members[0], [3; 32]).unwrap();
+        multisig.approve(&mut proposal, &members[1]).unwrap();
+
+        let bytes = multisig.try_to_vec().unwrap();
+        assert_eq!(bytes, multisig.to_bytes());
+        assert_eq!(Multisig::try_from_slice(&bytes).unwrap(), multisig);
+        let bytes = proposal.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), Proposal::INIT_SPACE);
+        assert_eq!(Proposal::try_from_slice(&bytes).unwrap(), proposal);
+
+        let mut corrupt = proposal.to_bytes();
+        corrupt[42] = 7;
+        assert!(Proposal::try_from_slice(&corrupt).is_err());
+    }
+}
@@ -0,0 +1,585 @@
+//! M-of-N approval bookkeeping for the secure programs
+//!
+//! A multisig is a member set, a threshold and a counter of proposals. Each
+//! proposal commits to a digest of what it will do, collects approvals as a
+//! bitmap over the member set, and executes once. The replay bugs live in
+//! the bookkeeping rather than the signatures:
+//!
+//! - Approvals counted by signature alone carry over to any transaction
+//!   the same members sign. A [`Proposal`] holds its own index and digest,
+//!   and [`Multisig::execute`] checks the digest of what is about to run.
+//! - Approvals kept after the member set changes count removed members, or
+//!   shift onto other members when the bitmap positions move.
+//!   [`Multisig::change_members`] marks every proposal up to the current
+//!   index stale, and stale proposals can't be approved or executed.
+//! - A proposal executed once and left open runs again. It's marked
+//!   executed, and an executed proposal takes no more approvals or
+//!   executions.
+//!
+//! ```
+//! use solsec_multisig::{Multisig, MultisigError};
+//!
+//! let (alice, bob, carol) = ([1; 32], [2; 32], [3; 32]);
+//! let mut multisig = Multisig::new(&[alice, bob, carol], 2).unwrap();
+//! let digest = [9; 32];
+//! let mut proposal = multisig.propose(&alice, digest).unwrap();
+//! multisig.approve(&mut proposal, &alice).unwrap();
+//! assert_eq!(
+//!     multisig.execute(&mut proposal, &digest),
+//!     Err(MultisigError::BelowThreshold)
+//! );
+//! multisig.approve(&mut proposal, &bob).unwrap();
+//! assert_eq!(multisig.execute(&mut proposal, &digest), Ok(()));
+//! ```
+//!
+//! The program stores both in accounts, a proposal at a PDA from the
+//! multisig's key and the proposal's index, checks each member is a
+//! signer before passing their key, and computes the digest from the
+//! instruction it is about to run. A member-set change should itself go
+//! through an executed proposal. The `anchor` feature makes both types
+//! Borsh fields for `#[account]` structs. The crate is `no_std`.
+
+#![no_std]
+
+#[cfg(feature = "anchor")]
+mod anchor;
+
+/// An account address.
+pub type Key = [u8; 32];
+
+/// The most members a [`Multisig`] holds, one bit of a [`Proposal`]'s
+/// approvals each.
+pub const MAX_MEMBERS: usize = 16;
+
+/// Why a multisig operation was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MultisigError {
+    /// A member set with nobody in it
+    NoMembers,
+    /// More than [`MAX_MEMBERS`] members
+    TooManyMembers,
+    /// The same key twice in a member set
+    DuplicateMember,
+    /// A threshold of zero, or above the number of members
+    InvalidThreshold,
+    /// The key isn't in the member set
+    NotMember,
+    /// The member has already approved the proposal
+    AlreadyApproved,
+    /// The member hasn't approved the proposal
+    NotApproved,
+    /// The proposal's index was never handed out by this multisig
+    UnknownProposal,
+    /// The member set changed after the proposal was made
+    Stale,
+    /// The proposal has already been executed
+    AlreadyExecuted,
+    /// Fewer approvals than the threshold
+    BelowThreshold,
+    /// What is about to run isn't what the proposal committed to
+    DigestMismatch,
+    /// Every proposal index has been used
+    IndexExhausted,
+    /// Stored bytes no multisig or proposal would write
+    InvalidLayout,
+}
+
+impl core::fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            MultisigError::NoMembers => "multisig has no members",
+            MultisigError::TooManyMembers => "multisig has too many members",
+            MultisigError::DuplicateMember => "multisig member listed twice",
+            MultisigError::InvalidThreshold => "threshold out of range",
+            MultisigError::NotMember => "not a multisig member",
+            MultisigError::AlreadyApproved => "proposal already approved by member",
+            MultisigError::NotApproved => "proposal not approved by member",
+            MultisigError::UnknownProposal => "proposal not made by this multisig",
+            MultisigError::Stale => "proposal predates the member set",
+            MultisigError::AlreadyExecuted => "proposal already executed",
+            MultisigError::BelowThreshold => "not enough approvals",
+            MultisigError::DigestMismatch => "proposal digest mismatch",
+            MultisigError::IndexExhausted => "proposal index exhausted",
+            MultisigError::InvalidLayout => "invalid multisig layout",
+        })
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MultisigError>;
+
+/// A member set, its threshold and the proposals made against it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Multisig {
+    members: [Key; MAX_MEMBERS],
+    len: u8,
+    threshold: u8,
+    transaction_index: u64,
+    stale_index: u64,
+}
+
+impl Multisig {
+    pub const LEN: usize = 32 * MAX_MEMBERS + 18;
+
+    /// `threshold` of `members` must approve each proposal.
+    pub fn new(members: &[Key], threshold: u8) -> Result<Self> {
+        let mut multisig = Multisig {
+            members: [[0; 32]; MAX_MEMBERS],
+            len: 0,
+            threshold: 0,
+            transaction_index: 0,
+            stale_index: 0,
+        };
+        multisig.set_members(members, threshold)?;
+        Ok(multisig)
+    }
+
+    pub fn members(&self) -> &[Key] {
+        &self.members[..usize::from(self.len)]
+    }
+
+    pub const fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// The index of the last proposal made; the first is 1.
+    pub const fn transaction_index(&self) -> u64 {
+        self.transaction_index
+    }
+
+    /// Proposals at or below this index were made before the member set
+    /// last changed.
+    pub const fn stale_index(&self) -> u64 {
+        self.stale_index
+    }
+
+    /// `key`'s position in the member set, its bit in a proposal's
+    /// approvals.
+    pub fn position(&self, key: &Key) -> Option<usize> {
+        self.members().iter().position(|member| member == key)
+    }
+
+    /// A new proposal by a member to run what `digest` commits to, with no
+    /// approvals yet.
+    pub fn propose(&mut self, proposer: &Key, digest: Key) -> Result<Proposal> {
+        self.position(proposer).ok_or(MultisigError::NotMember)?;
+        let index = self
+            .transaction_index
+            .checked_add(1)
+            .ok_or(MultisigError::IndexExhausted)?;
+        self.transaction_index = index;
+        Ok(Proposal {
+            index,
+            digest,
+            approvals: 0,
+            executed: false,
+        })
+    }
+
+    /// Record `member`'s approval.
+    pub fn approve(&self, proposal: &mut Proposal, member: &Key) -> Result<()> {
+        self.check_open(proposal)?;
+        let bit = self.bit(member)?;
+        if proposal.approvals & bit != 0 {
+            return Err(MultisigError::AlreadyApproved);
+        }
+        proposal.approvals |= bit;
+        Ok(())
+    }
+
+    /// Withdraw `member`'s approval before the proposal executes.
+    pub fn revoke(&self, proposal: &mut Proposal, member: &Key) -> Result<()> {
+        self.check_open(proposal)?;
+        let bit = self.bit(member)?;
+        if proposal.approvals & bit == 0 {
+            return Err(MultisigError::NotApproved);
+        }
+        proposal.approvals &= !bit;
+        Ok(())
+    }
+
+    /// Mark the proposal executed, if it has the threshold's approvals and
+    /// `digest` is what it committed to. Run the action only after this.
+    pub fn execute(&self, proposal: &mut Proposal, digest: &Key) -> Result<()> {
+        self.check_open(proposal)?;
+        if proposal.digest != *digest {
+            return Err(MultisigError::DigestMismatch);
+        }
+        if proposal.approval_count() < u32::from(self.threshold) {
+            return Err(MultisigError::BelowThreshold);
+        }
+        proposal.executed = true;
+        Ok(())
+    }
+
+    /// Replace the member set and threshold, and make every proposal so
+    /// far stale. Fails without changing anything.
+    pub fn change_members(&mut self, members: &[Key], threshold: u8) -> Result<()> {
+        self.set_members(members, threshold)?;
+        self.stale_index = self.transaction_index;
+        Ok(())
+    }
+
+    fn set_members(&mut self, members: &[Key], threshold: u8) -> Result<()> {
+        check_members(members, threshold)?;
+        self.members = [[0; 32]; MAX_MEMBERS];
+        self.members[..members.len()].copy_from_slice(members);
+        self.len = members.len() as u8;
+        self.threshold = threshold;
+        Ok(())
+    }
+
+    fn bit(&self, member: &Key) -> Result<u16> {
+        let position = self.position(member).ok_or(MultisigError::NotMember)?;
+        Ok(1 << position)
+    }
+
+    fn check_open(&self, proposal: &Proposal) -> Result<()> {
+        if proposal.index == 0 || proposal.index > self.transaction_index {
+            return Err(MultisigError::UnknownProposal);
+        }
+        if proposal.executed {
+            return Err(MultisigError::AlreadyExecuted);
+        }
+        if proposal.index <= self.stale_index {
+            return Err(MultisigError::Stale);
+        }
+        Ok(())
+    }
+
+    /// The little-endian layout: the member slots, the member count, the
+    /// threshold and the two indices.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        for (slot, member) in bytes.chunks_exact_mut(32).zip(&self.members) {
+            slot.copy_from_slice(member);
+        }
+        let tail = 32 * MAX_MEMBERS;
+        bytes[tail] = self.len;
+        bytes[tail + 1] = self.threshold;
+        bytes[tail + 2..tail + 10].copy_from_slice(&self.transaction_index.to_le_bytes());
+        bytes[tail + 10..].copy_from_slice(&self.stale_index.to_le_bytes());
+        bytes
+    }
+
+    /// Read a stored multisig, rejecting a member set [`Multisig::new`]
+    /// would refuse or a stale index past the last proposal.
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Result<Self> {
+        let tail = 32 * MAX_MEMBERS;
+        let len = usize::from(bytes[tail]);
+        if len > MAX_MEMBERS {
+            return Err(MultisigError::InvalidLayout);
+        }
+        let mut members = [[0; 32]; MAX_MEMBERS];
+        for (member, slot) in members.iter_mut().zip(bytes.chunks_exact(32)) {
+            member.copy_from_slice(slot);
+        }
+        let mut multisig = Multisig::new(&members[..len], bytes[tail + 1])
+            .map_err(|_| MultisigError::InvalidLayout)?;
+        let mut le = [0; 8];
+        le.copy_from_slice(&bytes[tail + 2..tail + 10]);
+        multisig.transaction_index = u64::from_le_bytes(le);
+        le.copy_from_slice(&bytes[tail + 10..]);
+        multisig.stale_index = u64::from_le_bytes(le);
+        if multisig.stale_index > multisig.transaction_index {
+            return Err(MultisigError::InvalidLayout);
+        }
+        Ok(multisig)
+    }
+}
+
+/// `members` and `threshold` would make a valid [`Multisig`].
+pub fn check_members(members: &[Key], threshold: u8) -> Result<()> {
+    if members.is_empty() {
+        return Err(MultisigError::NoMembers);
+    }
+    if members.len() > MAX_MEMBERS {
+        return Err(MultisigError::TooManyMembers);
+    }
+    for (i, member) in members.iter().enumerate() {
+        if members[..i].contains(member) {
+            return Err(MultisigError::DuplicateMember);
+        }
+    }
+    if threshold == 0 || usize::from(threshold) > members.len() {
+        return Err(MultisigError::InvalidThreshold);
+    }
+    Ok(())
+}
+
+/// A proposal: its index, the digest of what it runs, the members who
+/// approved it and whether it has run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Proposal {
+    index: u64,
+    digest: Key,
+    approvals: u16,
+    executed: bool,
+}
+
+impl Proposal {
+    pub const LEN: usize = 43;
+
+    pub const fn index(&self) -> u64 {
+        self.index
+    }
+
+    pub const fn digest(&self) -> &Key {
+        &self.digest
+    }
+
+    /// The approvals as a bitmap over member positions.
+    pub const fn approvals(&self) -> u16 {
+        self.approvals
+    }
+
+    pub const fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
+
+    pub const fn is_executed(&self) -> bool {
+        self.executed
+    }
+
+    /// The little-endian layout.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[..8].copy_from_slice(&self.index.to_le_bytes());
+        bytes[8..40].copy_from_slice(&self.digest);
+        bytes[40..42].copy_from_slice(&self.approvals.to_le_bytes());
+        bytes[42] = u8::from(self.executed);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Result<Self> {
+        let mut index = [0; 8];
+        index.copy_from_slice(&bytes[..8]);
+        let mut digest = [0; 32];
+        digest.copy_from_slice(&bytes[8..40]);
+        let executed = match bytes[42] {
+            0 => false,
+            1 => true,
+            _ => return Err(MultisigError::InvalidLayout),
+        };
+        Ok(Proposal {
+            index: u64::from_le_bytes(index),
+            digest,
+            approvals: u16::from_le_bytes([bytes[40], bytes[41]]),
+            executed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn keys() -> [Key; MAX_MEMBERS] {
+        core::array::from_fn(|i| [i as u8 + 1; 32])
+    }
+
+    #[test]
+    fn test_member_sets() {
+        let members = &keys()[..3];
+        assert!(Multisig::new(members, 3).is_ok());
+        assert_eq!(Multisig::new(&[], 1), Err(MultisigError::NoMembers));
+        assert_eq!(
+            Multisig::new(members, 0),
+            Err(MultisigError::InvalidThreshold)
+        );
+        assert_eq!(
+            Multisig::new(members, 4),
+            Err(MultisigError::InvalidThreshold)
+        );
+        assert_eq!(
+            Multisig::new(&[[1; 32], [2; 32], [1; 32]], 1),
+            Err(MultisigError::DuplicateMember)
+        );
+        let crowd: [Key; MAX_MEMBERS + 1] = core::array::from_fn(|i| [i as u8; 32]);
+        assert_eq!(Multisig::new(&crowd, 1), Err(MultisigError::TooManyMembers));
+        assert!(Multisig::new(&crowd[..MAX_MEMBERS], MAX_MEMBERS as u8).is_ok());
+    }
+
+    #[test]
+    fn test_approvals_count_once_per_member() {
+        let members = keys();
+        let mut multisig = Multisig::new(&members[..3], 2).unwrap();
+        let digest = [7; 32];
+        assert_eq!(
+            multisig.propose(&[9; 32], digest),
+            Err(MultisigError::NotMember)
+        );
+        let mut proposal = multisig.propose(&members[0], digest).unwrap();
+        assert_eq!(proposal.index(), 1);
+        multisig.approve(&mut proposal, &members[0]).unwrap();
+        assert_eq!(
+            multisig.approve(&mut proposal, &members[0]),
+            Err(MultisigError::AlreadyApproved)
+        );
+        assert_eq!(
+            multisig.approve(&mut proposal, &[9; 32]),
+            Err(MultisigError::NotMember)
+        );
+        assert_eq!(
+            multisig.execute(&mut proposal, &digest),
+            Err(MultisigError::BelowThreshold)
+        );
+
+        // A revoked approval stops counting
+        multisig.approve(&mut proposal, &members[2]).unwrap();
+        multisig.revoke(&mut proposal, &members[2]).unwrap();
+        assert_eq!(
+            multisig.revoke(&mut proposal, &members[2]),
+            Err(MultisigError::NotApproved)
+        );
+        assert_eq!(
+            multisig.execute(&mut proposal, &digest),
+            Err(MultisigError::BelowThreshold)
+        );
+
+        multisig.approve(&mut proposal, &members[1]).unwrap();
+        assert_eq!(
+            multisig.execute(&mut proposal, &[8; 32]),
+            Err(MultisigError::DigestMismatch)
+        );
+        assert_eq!(multisig.execute(&mut proposal, &digest), Ok(()));
+        assert_eq!(
+            multisig.execute(&mut proposal, &digest),
+            Err(MultisigError::AlreadyExecuted)
+        );
+        assert_eq!(
+            multisig.approve(&mut proposal, &members[2]),
+            Err(MultisigError::AlreadyExecuted)
+        );
+    }
+
+    #[test]
+    fn test_proposals_from_elsewhere_are_unknown() {
+        let members = keys();
+        let mut multisig = Multisig::new(&members[..2], 1).unwrap();
+        let mut forged = Proposal::default();
+        assert_eq!(
+            multisig.approve(&mut forged, &members[0]),
+            Err(MultisigError::UnknownProposal)
+        );
+        let mut other = Multisig::new(&members[..2], 1).unwrap();
+        other.propose(&members[0], [0; 32]).unwrap();
+        let mut ahead = other.propose(&members[0], [0; 32]).unwrap();
+        multisig.propose(&members[0], [0; 32]).unwrap();
+        assert_eq!(
+            multisig.approve(&mut ahead, &members[0]),
+            Err(MultisigError::UnknownProposal)
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let members = keys();
+        let mut multisig = Multisig::new(&members[..4], 3).unwrap();
+        let mut proposal = multisig.propose(&members[1], [5; 32]).unwrap();
+        multisig.approve(&mut proposal, &members[3]).unwrap();
+        multisig.change_members(&members[..3], 2).unwrap();
+        assert_eq!(Multisig::from_bytes(&multisig.to_bytes()), Ok(multisig));
+        assert_eq!(Proposal::from_bytes(&proposal.to_bytes()), Ok(proposal));
+
+        // A threshold above the count, a stale index past the last
+        // proposal, or an executed flag that isn't a bool
+        let mut bytes = multisig.to_bytes();
+        bytes[32 * MAX_MEMBERS + 1] = 4;
+        assert_eq!(
+            Multisig::from_bytes(&bytes),
+            Err(MultisigError::InvalidLayout)
+        );
+        let mut bytes = multisig.to_bytes();
+        bytes[32 * MAX_MEMBERS + 10] = 2;
+        assert_eq!(
+            Multisig::from_bytes(&bytes),
+            Err(MultisigError::InvalidLayout)
+        );
+        let mut bytes = proposal.to_bytes();
+        bytes[42] = 2;
+        assert_eq!(
+            Proposal::from_bytes(&bytes),
+            Err(MultisigError::InvalidLayout)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_approvals_never_carry_to_another_proposal(
+            n in 1usize..=MAX_MEMBERS,
+            threshold in 1u8..=MAX_MEMBERS as u8,
+            approvers in prop::collection::vec(0usize..MAX_MEMBERS, 0..40),
+            digests in prop::array::uniform2(any::<[u8; 32]>()),
+        ) {
+            let threshold = threshold.min(n as u8);
+            let members = keys();
+            let mut multisig = Multisig::new(&members[..n], threshold).unwrap();
+            let mut first = multisig.propose(&members[0], digests[0]).unwrap();
+            let mut second = multisig.propose(&members[0], digests[1]).unwrap();
+            let mut distinct = 0u16;
+            for &i in &approvers {
+                let result = multisig.approve(&mut first, &members[i]);
+                if i >= n {
+                    prop_assert_eq!(result, Err(MultisigError::NotMember));
+                } else if distinct & 1 << i != 0 {
+                    prop_assert_eq!(result, Err(MultisigError::AlreadyApproved));
+                } else {
+                    prop_assert_eq!(result, Ok(()));
+                    distinct |= 1 << i;
+                }
+            }
+            prop_assert_eq!(first.approvals(), distinct);
+
+            // The other proposal, even committing to the same digest, has
+            // none of them
+            prop_assert_eq!(second.approval_count(), 0);
+            prop_assert_eq!(multisig.execute(&mut second, &digests[1]), Err(MultisigError::BelowThreshold));
+
+            // The approved one runs for its own digest only, and once
+            if digests[0] != digests[1] {
+                prop_assert_eq!(multisig.execute(&mut first, &digests[1]), Err(MultisigError::DigestMismatch));
+            }
+            if distinct.count_ones() >= u32::from(threshold) {
+                prop_assert_eq!(multisig.execute(&mut first, &digests[0]), Ok(()));
+                prop_assert_eq!(multisig.execute(&mut first, &digests[0]), Err(MultisigError::AlreadyExecuted));
+            } else {
+                prop_assert_eq!(multisig.execute(&mut first, &digests[0]), Err(MultisigError::BelowThreshold));
+            }
+        }
+
+        #[test]
+        fn prop_member_changes_make_proposals_stale(
+            before in 1usize..=MAX_MEMBERS,
+            after in 1usize..=MAX_MEMBERS,
+            pending in 1u64..5,
+            digest: [u8; 32],
+        ) {
+            // Everyone approves every pending proposal, then the set
+            // changes, even to the same members
+            let members = keys();
+            let mut multisig = Multisig::new(&members[..before], 1).unwrap();
+            let mut proposals: [Proposal; 4] = Default::default();
+            for proposal in proposals.iter_mut().take(pending as usize) {
+                *proposal = multisig.propose(&members[0], digest).unwrap();
+                for member in &members[..before] {
+                    multisig.approve(proposal, member).unwrap();
+                }
+            }
+            multisig.change_members(&members[..after], 1).unwrap();
+            for proposal in proposals.iter_mut().take(pending as usize) {
+                prop_assert_eq!(multisig.execute(proposal, &digest), Err(MultisigError::Stale));
+                for member in &members[..after] {
+                    prop_assert_eq!(multisig.approve(proposal, member), Err(MultisigError::Stale));
+                }
+            }
+
+            // Proposals made after the change start clean
+            let mut fresh = multisig.propose(&members[0], digest).unwrap();
+            prop_assert_eq!(fresh.index(), pending + 1);
+            prop_assert_eq!(multisig.execute(&mut fresh, &digest), Err(MultisigError::BelowThreshold));
+            multisig.approve(&mut fresh, &members[after - 1]).unwrap();
+            prop_assert_eq!(multisig.execute(&mut fresh, &digest), Ok(()));
+        }
+    }
+}
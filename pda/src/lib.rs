@@ -0,0 +1,412 @@
+//! Domain-separated PDA seeds and canonical bumps for the secure programs
+//!
+//! Most PDA bugs in this template are a seed array written out by hand:
+//! seeds too predictable to stop griefing (`pda-security`,
+//! `account-griefing`), a bump taken from the caller instead of the
+//! canonical one, or two kinds of account whose seeds can collide.
+//! [`Seeds`] builds them in one order everywhere, a domain tag first:
+//!
+//! ```
+//! use solsec_pda::Seeds;
+//!
+//! let owner = [7u8; 32];
+//! let seeds = Seeds::new("stake").key(&owner).nonce(42);
+//! let slices = seeds.slices().unwrap();
+//! assert_eq!(slices.len(), 3);
+//! assert_eq!(slices[0], b"stake");
+//! ```
+//!
+//! With the `pubkey` feature, [`Seeds::find`] derives the address and
+//! canonical bump and [`Seeds::verify`] checks a stored pair against them.
+//! Store the bump [`Seeds::find`] returns (Anchor's `ctx.bumps`) in the
+//! account and sign with [`Seeds::bump`] afterwards, rather than searching
+//! again or accepting one from instruction data.
+//!
+//! # Choosing a nonce
+//!
+//! A nonce only stops an attacker from funding or creating the address
+//! first if they can't guess it before the user's transaction lands. Pick
+//! it client-side from a random source and pass it as an instruction
+//! argument, store it in the account so later instructions can re-derive
+//! the address, and never derive it on-chain from the clock, the slot or a
+//! counter anyone can read.
+//!
+//! # Domains
+//!
+//! Solana hashes the seeds concatenated, with no lengths between them, so
+//! `["ab", "c"]` and `["a", "bc"]` are the same address. Keys and nonces
+//! have fixed widths, so collisions come down to the domains: keep each
+//! program's domains from being prefixes of one another, which
+//! [`check_domains`] tests.
+
+#![no_std]
+
+#[cfg(feature = "pubkey")]
+use solana_pubkey::Pubkey;
+
+/// Seeds in one derivation, the bump included.
+pub const MAX_SEEDS: usize = 16;
+/// Bytes in one seed.
+pub const MAX_SEED_LEN: usize = 32;
+
+/// Why a set of seeds can't derive or sign for an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeedError {
+    EmptyDomain,
+    /// More than [`MAX_SEEDS`], the bump included
+    TooManySeeds,
+    /// A seed longer than [`MAX_SEED_LEN`]
+    SeedTooLong,
+    /// A key that isn't 32 bytes
+    KeyLength,
+    /// A seed added after the bump
+    AfterBump,
+    /// Signing or [`Seeds::verify`] without a bump
+    MissingBump,
+    /// A bump that derives the address, but isn't the canonical one
+    NonCanonicalBump,
+    /// The seeds derive a different address
+    AddressMismatch,
+    /// No bump puts the address off the curve
+    NoViableBump,
+    /// One domain is a prefix of another
+    OverlappingDomains,
+}
+
+impl core::fmt::Display for SeedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            SeedError::EmptyDomain => "empty seed domain",
+            SeedError::TooManySeeds => "more than 16 seeds",
+            SeedError::SeedTooLong => "seed longer than 32 bytes",
+            SeedError::KeyLength => "key seed is not 32 bytes",
+            SeedError::AfterBump => "seed added after the bump",
+            SeedError::MissingBump => "no bump in the seeds",
+            SeedError::NonCanonicalBump => "bump is not the canonical bump",
+            SeedError::AddressMismatch => "seeds derive a different address",
+            SeedError::NoViableBump => "no bump derives an off-curve address",
+            SeedError::OverlappingDomains => "one seed domain is a prefix of another",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Part<'a> {
+    Borrowed(&'a [u8]),
+    /// Little-endian integer bytes the builder owns
+    Owned([u8; 8], u8),
+}
+
+impl Part<'_> {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Part::Borrowed(bytes) => bytes,
+            Part::Owned(bytes, len) => &bytes[..*len as usize],
+        }
+    }
+}
+
+/// A PDA's seeds: a domain tag, then keys, nonces and other parts, then
+/// optionally the bump.
+///
+/// The builder records the first mistake instead of panicking; it comes
+/// back from [`Seeds::slices`], [`Seeds::find`] or [`Seeds::verify`].
+#[derive(Debug, Clone, Copy)]
+pub struct Seeds<'a> {
+    parts: [Part<'a>; MAX_SEEDS],
+    len: usize,
+    bump: Option<u8>,
+    error: Option<SeedError>,
+}
+
+impl<'a> Seeds<'a> {
+    /// Seeds starting with `domain`, e.g. `"stake"` or `"vault"`.
+    pub fn new(domain: &'a str) -> Self {
+        let seeds = Seeds {
+            parts: [Part::Borrowed(&[]); MAX_SEEDS],
+            len: 0,
+            bump: None,
+            error: domain.is_empty().then_some(SeedError::EmptyDomain),
+        };
+        seeds.push(Part::Borrowed(domain.as_bytes()))
+    }
+
+    /// A 32-byte key: an owner, a mint, another PDA.
+    pub fn key(self, key: &'a impl AsRef<[u8]>) -> Self {
+        let key = key.as_ref();
+        if key.len() != 32 {
+            return self.fail(SeedError::KeyLength);
+        }
+        self.push(Part::Borrowed(key))
+    }
+
+    /// A `u64` nonce or index, little-endian as `to_le_bytes` writes it.
+    pub fn nonce(self, nonce: u64) -> Self {
+        self.push(Part::Owned(nonce.to_le_bytes(), 8))
+    }
+
+    /// Any other seed, up to [`MAX_SEED_LEN`] bytes.
+    pub fn bytes(self, bytes: &'a [u8]) -> Self {
+        self.push(Part::Borrowed(bytes))
+    }
+
+    /// The stored canonical bump, for signing.
+    pub fn bump(mut self, bump: u8) -> Self {
+        if self.bump.is_some() {
+            return self.fail(SeedError::AfterBump);
+        }
+        let len = self.len;
+        self = self.push(Part::Owned([bump, 0, 0, 0, 0, 0, 0, 0], 1));
+        if self.len > len {
+            self.bump = Some(bump);
+        }
+        self
+    }
+
+    fn push(mut self, part: Part<'a>) -> Self {
+        if self.bump.is_some() {
+            return self.fail(SeedError::AfterBump);
+        }
+        if part.bytes().len() > MAX_SEED_LEN {
+            return self.fail(SeedError::SeedTooLong);
+        }
+        if self.len == MAX_SEEDS {
+            return self.fail(SeedError::TooManySeeds);
+        }
+        self.parts[self.len] = part;
+        self.len += 1;
+        self
+    }
+
+    fn fail(mut self, error: SeedError) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+
+    /// The bump, if added.
+    pub fn stored_bump(&self) -> Option<u8> {
+        self.bump
+    }
+
+    /// The seeds as `invoke_signed` and `find_program_address` take them.
+    pub fn slices(&self) -> Result<SeedSlices<'_>, SeedError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let mut slices: [&[u8]; MAX_SEEDS] = [&[]; MAX_SEEDS];
+        for (slice, part) in slices.iter_mut().zip(&self.parts[..self.len]) {
+            *slice = part.bytes();
+        }
+        Ok(SeedSlices {
+            slices,
+            len: self.len,
+        })
+    }
+
+    /// The seeds for signing: [`Seeds::slices`], requiring the bump.
+    pub fn signer(&self) -> Result<SeedSlices<'_>, SeedError> {
+        if self.bump.is_none() && self.error.is_none() {
+            return Err(SeedError::MissingBump);
+        }
+        self.slices()
+    }
+}
+
+/// Borrowed seed slices; derefs to `[&[u8]]`.
+#[derive(Debug, Clone, Copy)]
+pub struct SeedSlices<'s> {
+    slices: [&'s [u8]; MAX_SEEDS],
+    len: usize,
+}
+
+impl<'s> core::ops::Deref for SeedSlices<'s> {
+    type Target = [&'s [u8]];
+
+    fn deref(&self) -> &Self::Target {
+        &self.slices[..self.len]
+    }
+}
+
+/// Check that no domain in `domains` is a prefix of another, so their
+/// seeds can't collide.
+pub fn check_domains(domains: &[&str]) -> Result<(), SeedError> {
+    if domains.iter().any(|d| d.is_empty()) {
+        return Err(SeedError::EmptyDomain);
+    }
+    for (i, a) in domains.iter().enumerate() {
+        for b in &domains[i + 1..] {
+            if a.starts_with(b) || b.starts_with(a) {
+                return Err(SeedError::OverlappingDomains);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Derivation against a program id.
+#[cfg(feature = "pubkey")]
+impl Seeds<'_> {
+    /// The address and canonical bump of seeds without a bump.
+    pub fn find(&self, program_id: &Pubkey) -> Result<(Pubkey, u8), SeedError> {
+        if self.bump.is_some() {
+            return Err(SeedError::AfterBump);
+        }
+        let slices = self.slices()?;
+        // A full set of seeds leaves no room for the bump
+        if slices.len() == MAX_SEEDS {
+            return Err(SeedError::TooManySeeds);
+        }
+        Pubkey::try_find_program_address(&slices, program_id).ok_or(SeedError::NoViableBump)
+    }
+
+    /// Check that these seeds, with their bump, are `address`'s and the
+    /// bump is the canonical one.
+    pub fn verify(&self, address: &Pubkey, program_id: &Pubkey) -> Result<(), SeedError> {
+        let bump = self.bump.ok_or(SeedError::MissingBump)?;
+        let mut unbumped = *self;
+        unbumped.len -= 1;
+        unbumped.bump = None;
+        let (canonical, canonical_bump) = unbumped.find(program_id)?;
+        if canonical == *address {
+            if bump != canonical_bump {
+                return Err(SeedError::NonCanonicalBump);
+            }
+            return Ok(());
+        }
+        match Pubkey::create_program_address(&self.slices()?, program_id) {
+            Ok(derived) if derived == *address => Err(SeedError::NonCanonicalBump),
+            _ => Err(SeedError::AddressMismatch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_in_order() {
+        let owner = [1u8; 32];
+        let seeds = Seeds::new("stake")
+            .key(&owner)
+            .nonce(258)
+            .bytes(b"x")
+            .bump(254);
+        let slices = seeds.slices().unwrap();
+        assert_eq!(
+            &*slices,
+            &[
+                b"stake".as_ref(),
+                owner.as_ref(),
+                258u64.to_le_bytes().as_ref(),
+                b"x",
+                &[254]
+            ]
+        );
+        assert_eq!(seeds.stored_bump(), Some(254));
+        assert_eq!(seeds.signer().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_first_mistake_is_reported() {
+        let short = [0u8; 31];
+        assert_eq!(Seeds::new("").slices().unwrap_err(), SeedError::EmptyDomain);
+        assert_eq!(
+            Seeds::new("a").key(&short).slices().unwrap_err(),
+            SeedError::KeyLength
+        );
+        assert_eq!(
+            Seeds::new("a")
+                .bytes(&[0; 33])
+                .nonce(1)
+                .slices()
+                .unwrap_err(),
+            SeedError::SeedTooLong
+        );
+        assert_eq!(
+            Seeds::new("a").bump(1).nonce(1).slices().unwrap_err(),
+            SeedError::AfterBump
+        );
+        assert_eq!(
+            Seeds::new("a").bump(1).bump(2).slices().unwrap_err(),
+            SeedError::AfterBump
+        );
+        assert_eq!(
+            Seeds::new("a").signer().unwrap_err(),
+            SeedError::MissingBump
+        );
+
+        let mut seeds = Seeds::new("a");
+        for i in 0..MAX_SEEDS as u64 - 1 {
+            seeds = seeds.nonce(i);
+        }
+        assert_eq!(seeds.slices().unwrap().len(), MAX_SEEDS);
+        assert_eq!(
+            seeds.nonce(0).slices().unwrap_err(),
+            SeedError::TooManySeeds
+        );
+    }
+
+    #[test]
+    fn test_domains_must_not_prefix_each_other() {
+        assert_eq!(check_domains(&["config", "lp", "vault"]), Ok(()));
+        assert_eq!(
+            check_domains(&["user", "user_vault"]),
+            Err(SeedError::OverlappingDomains)
+        );
+        assert_eq!(
+            check_domains(&["vault", "vault"]),
+            Err(SeedError::OverlappingDomains)
+        );
+        assert_eq!(check_domains(&["a", ""]), Err(SeedError::EmptyDomain));
+    }
+
+    #[cfg(feature = "pubkey")]
+    #[test]
+    fn test_find_and_verify_the_canonical_bump() {
+        let program_id = Pubkey::new_from_array([9; 32]);
+        let owner = Pubkey::new_from_array([3; 32]);
+        let seeds = Seeds::new("stake").key(&owner).nonce(7);
+        let (address, bump) = seeds.find(&program_id).unwrap();
+        assert_eq!(
+            (address, bump),
+            Pubkey::find_program_address(
+                &[b"stake", owner.as_ref(), &7u64.to_le_bytes()],
+                &program_id
+            )
+        );
+        assert_eq!(seeds.bump(bump).verify(&address, &program_id), Ok(()));
+        assert_eq!(
+            seeds.verify(&address, &program_id),
+            Err(SeedError::MissingBump)
+        );
+        assert_eq!(
+            Seeds::new("stake")
+                .key(&owner)
+                .nonce(8)
+                .bump(bump)
+                .verify(&address, &program_id),
+            Err(SeedError::AddressMismatch)
+        );
+
+        // Another bump below the canonical one that also lands off the curve
+        let other = (0..bump)
+            .rev()
+            .find(|&b| {
+                Pubkey::create_program_address(&seeds.bump(b).slices().unwrap(), &program_id)
+                    .is_ok()
+            })
+            .unwrap();
+        let derived =
+            Pubkey::create_program_address(&seeds.bump(other).slices().unwrap(), &program_id)
+                .unwrap();
+        assert_eq!(
+            seeds.bump(other).verify(&derived, &program_id),
+            Err(SeedError::NonCanonicalBump)
+        );
+        assert_eq!(
+            seeds.bump(bump).find(&program_id),
+            Err(SeedError::AfterBump)
+        );
+    }
+}
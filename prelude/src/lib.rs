@@ -0,0 +1,135 @@
+//! What every Anchor program in the workspace starts from
+//!
+//! `use solsec_prelude::*;` stands in for `use anchor_lang::prelude::*;`:
+//! it brings in Anchor's prelude, the [`SecurityEvent`] envelope and its
+//! [`EventKind`], the guards the handlers call most, and the shared crates
+//! themselves, so `solsec_guards::require_owner!` or
+//! `solsec_errors::ErrorClass` resolve without their own dependency. Two
+//! macros write the rest of a program's boilerplate:
+//!
+//! ```ignore
+//! #[cfg(feature = "vulnerable")]
+//! solsec_prelude::record_cluster!();
+//! #[cfg(feature = "vulnerable")]
+//! use cluster::*;
+//!
+//! solsec_prelude::catalogued!(program::SignerAuthorization, SIGNER_AUTHORIZATION);
+//! ```
+//!
+//! [`record_cluster!`] expands to the `cluster` module, the `RecordCluster`
+//! accounts behind each program's `record_cluster` instruction, and
+//! [`catalogued!`] points the program at its entry in the registry. The
+//! instruction itself stays in the `#[program]` module, which Anchor reads
+//! before any macro expands. A program still depends on `anchor-lang`, whose
+//! macros name it by path, and still allows `unexpected_cfgs`, through
+//! `[lints] workspace = true`.
+//!
+//! A program's `anchor-031` feature builds it against Anchor 0.31 instead of
+//! the workspace's 0.32, turning on the same feature here. The program
+//! renames the release at its crate root, where Anchor's macros look it up:
+//!
+//! ```ignore
+//! #[cfg(feature = "anchor-031")]
+//! extern crate anchor_lang_031 as anchor_lang;
+//! ```
+//!
+//! It has to be written out rather than expanded from a macro, since a
+//! macro's `extern crate` can't shadow a dependency. Anchor 0.30 isn't
+//! offered: its `solana-program` 1.18 caps `zeroize` below 1.4, which
+//! `litesvm` 0.7, a dependency of `p-escrow`, can't resolve alongside, and
+//! Cargo resolves every optional dependency for the whole workspace.
+
+#[cfg(feature = "anchor-031")]
+pub extern crate anchor_lang_031 as anchor_lang;
+
+#[cfg(not(feature = "anchor-031"))]
+pub use anchor_lang;
+pub use anchor_lang::prelude::*;
+pub use solsec_errors;
+pub use solsec_events::{self, EventKind, SecurityEvent};
+pub use solsec_guards::{self, check_cluster, transfer_lamports};
+pub use solsec_registry::{self, Catalogued};
+
+/// The `cluster` module: where the program was deployed, for the
+/// vulnerable instructions to refuse mainnet-beta.
+///
+/// The upgrade authority sends the genesis hash of the cluster right after
+/// deploying; see [`check_cluster`]. Until then the vulnerable instructions
+/// refuse to run anywhere. Expand it at the crate root, next to
+/// `declare_id!`, since the accounts check `crate::ID`.
+// `crate` is meant to be the calling program
+#[allow(clippy::crate_in_macro_def)]
+#[macro_export]
+macro_rules! record_cluster {
+    () => {
+        /// Record cluster - where the program was deployed, for the
+        /// vulnerable instructions to refuse mainnet-beta
+        pub mod cluster {
+            use anchor_lang::prelude::*;
+            use anchor_lang::solana_program::bpf_loader_upgradeable;
+            use $crate::solsec_guards::{
+                check_upgrade_authority, write_cluster_record, ClusterRecord,
+            };
+
+            #[derive(Accounts)]
+            pub struct RecordCluster<'info> {
+                #[account(mut)]
+                pub authority: Signer<'info>,
+
+                /// CHECK: Its programdata address is checked in the handler
+                #[account(address = crate::ID)]
+                pub program: UncheckedAccount<'info>,
+
+                /// CHECK: Names the upgrade authority; checked in the handler
+                #[account(
+                            seeds = [crate::ID.as_ref()],
+                            bump,
+                            seeds::program = bpf_loader_upgradeable::ID
+                        )]
+                pub program_data: UncheckedAccount<'info>,
+
+                /// CHECK: Created here and written in the handler
+                #[account(
+                            init,
+                            payer = authority,
+                            space = ClusterRecord::LEN,
+                            seeds = [b"cluster"],
+                            bump,
+                            owner = crate::ID,
+                        )]
+                pub cluster: UncheckedAccount<'info>,
+
+                pub system_program: Program<'info, System>,
+            }
+
+            impl<'info> RecordCluster<'info> {
+                pub fn record(&mut self, genesis_hash: [u8; 32]) -> Result<()> {
+                    check_upgrade_authority(
+                        &self.program.to_account_info(),
+                        &self.program_data.to_account_info(),
+                        &self.authority.to_account_info(),
+                        &crate::ID,
+                    )?;
+                    write_cluster_record(
+                        &self.cluster.to_account_info(),
+                        &crate::ID,
+                        genesis_hash,
+                    )?;
+                    Ok(())
+                }
+            }
+        }
+    };
+}
+
+/// Implement [`Catalogued`] for a program type, pointing at the registry's
+/// constant of that name.
+#[macro_export]
+macro_rules! catalogued {
+    ($program:ty, $vulnerability:ident) => {
+        impl $crate::solsec_registry::Catalogued for $program {
+            const VULNERABILITY: &'static $crate::solsec_registry::Vulnerability =
+                &$crate::solsec_registry::$vulnerability;
+        }
+    };
+}
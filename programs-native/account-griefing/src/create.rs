@@ -0,0 +1,65 @@
+//! Creating a PDA the way Anchor's `init` does
+//!
+//! `create_account` fails with `AccountAlreadyInUse` once the address holds
+//! any lamports, and anyone can send lamports to any address. An account
+//! the system program still owns, with no data, can instead be topped up
+//! to rent exemption, allocated and assigned, which only its seeds can
+//! sign for.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+/// Create `account`, at the PDA `seeds` sign for, with `space` bytes owned
+/// by `program_id`, `payer` funding its rent.
+pub fn create_pda<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                rent,
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), account.clone()],
+            &[seeds],
+        );
+    }
+
+    // Already created, not just funded: `init` refuses it too
+    if *account.owner != system_program::ID || !account.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if lamports < rent {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, rent - lamports),
+            &[payer.clone(), account.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        core::slice::from_ref(account),
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        core::slice::from_ref(account),
+        &[seeds],
+    )
+}
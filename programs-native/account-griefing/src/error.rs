@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+/// The Anchor program's `StakeError`, under the same codes: Anchor numbers
+/// a program's errors from 6000
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakeError {
+    AlreadyInitialized,
+    InvalidAmount,
+    Unauthorized,
+    OutdatedVersion,
+}
+
+impl From<StakeError> for ProgramError {
+    fn from(error: StakeError) -> Self {
+        ProgramError::Custom(6000 + error as u32)
+    }
+}
+
+impl From<StakeError> for solsec_errors::ErrorClass {
+    fn from(error: StakeError) -> Self {
+        solsec_errors::ACCOUNT_GRIEFING.class(error as usize)
+    }
+}
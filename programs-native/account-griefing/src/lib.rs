@@ -0,0 +1,67 @@
+//! Account Creation Griefing - native program
+//!
+//! The `account-griefing` stake accounts with a raw `solana-program`
+//! entrypoint. The Anchor program's fix is half a nonce in the seeds and
+//! half its `init` constraint, which creates an account that already holds
+//! lamports by topping it up, allocating and assigning it instead of
+//! calling `create_account`. A native program that only adds the nonce
+//! still calls `create_account`, and anyone who sees the nonce in a failed
+//! transaction can block it again; [`create::create_pda`] is the rest.
+
+#![allow(unexpected_cfgs)]
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub mod create;
+pub mod error;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Dispatch on the first byte; an instruction's arguments follow it.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        #[cfg(feature = "vulnerable")]
+        0 => vulnerable::process_create_stake(program_id, accounts),
+        1 => secure::process_create_stake(program_id, accounts, read_u64(args)?),
+        2 => secure::process_deposit(program_id, accounts, read_u64(args)?),
+        3 => solsec_guards::record_cluster(program_id, accounts, read_hash(args)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Borsh's `u64`, which Anchor would decode for the handler
+fn read_u64(args: &[u8]) -> Result<u64, ProgramError> {
+    args.try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+// Borsh's `[u8; 32]`: the bytes alone
+fn read_hash(args: &[u8]) -> Result<[u8; 32], ProgramError> {
+    args.try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// The program, for its registry entry
+pub struct NativeAccountGriefing;
+
+impl solsec_registry::Catalogued for NativeAccountGriefing {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::ACCOUNT_GRIEFING;
+}
@@ -0,0 +1,111 @@
+//! SECURE implementation - nonce in the seeds, prefund-tolerant creation
+//!
+//! The nonce keeps the address from being computed ahead of time, and
+//! [`create_pda`] keeps it from being blocked once it is known.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    log::sol_log_data,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::create::create_pda;
+use crate::error::StakeError;
+use crate::state::SecureStakeAccount;
+
+/// Accounts: user (signer, writable), stake account (writable), system
+/// program
+pub fn process_create_stake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let user = next_account_info(accounts)?;
+    let stake = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // SECURE: Nonce makes the PDA unpredictable
+    let nonce_bytes = nonce.to_le_bytes();
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"stake", user.key.as_ref(), &nonce_bytes], program_id);
+    if address != *stake.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // SECURE: What `init` does, including for a pre-funded address
+    create_pda(
+        user,
+        stake,
+        &[b"stake", user.key.as_ref(), &nonce_bytes, &[bump]],
+        SecureStakeAccount::LEN,
+        program_id,
+    )?;
+
+    SecureStakeAccount {
+        owner: *user.key,
+        amount: 0,
+        nonce,
+        last_deposit_slot: 0,
+        bump,
+    }
+    .pack(&mut stake.try_borrow_mut_data()?)?;
+
+    msg!("Created secure stake account");
+    sol_log_data(&[&nonce_bytes]);
+    Ok(())
+}
+
+/// Accounts: user (signer, writable), stake account (writable), system
+/// program
+pub fn process_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let user = next_account_info(accounts)?;
+    let stake = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut state = SecureStakeAccount::load(program_id, stake, user.key)?;
+    if amount == 0 {
+        return Err(StakeError::InvalidAmount.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(user.key, stake.key, amount),
+        &[user.clone(), stake.clone()],
+    )?;
+
+    state.amount = state
+        .amount
+        .checked_add(amount)
+        .ok_or(StakeError::InvalidAmount)?;
+    state.last_deposit_slot = Clock::get()?.slot;
+    // Anchor's `Account` writes the struct back when the instruction exits
+    state.pack(&mut stake.try_borrow_mut_data()?)?;
+
+    msg!("Deposited");
+    sol_log_data(&[&amount.to_le_bytes()]);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Stake accounts, laid out by hand
+//!
+//! Anchor's layouts with a one-byte type tag in place of the
+//! discriminator, and without the version byte: these programs have only
+//! ever written one layout.
+
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+pub const STAKE_TAG: u8 = 3;
+pub const SECURE_STAKE_TAG: u8 = 4;
+
+/// Stake account at the predictable `[b"stake", owner]`
+pub struct StakeAccount {
+    pub owner: Pubkey, // bytes 1..33
+    pub amount: u64,   // bytes 33..41
+    pub bump: u8,      // byte 41
+}
+
+impl StakeAccount {
+    pub const LEN: usize = 42;
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = STAKE_TAG;
+        data[1..33].copy_from_slice(self.owner.as_ref());
+        data[33..41].copy_from_slice(&self.amount.to_le_bytes());
+        data[41] = self.bump;
+        Ok(())
+    }
+}
+
+/// Stake account at `[b"stake", owner, nonce]`
+pub struct SecureStakeAccount {
+    pub owner: Pubkey,          // bytes 1..33
+    pub amount: u64,            // bytes 33..41
+    pub nonce: u64,             // bytes 41..49
+    pub last_deposit_slot: u64, // bytes 49..57
+    pub bump: u8,               // byte 57
+}
+
+impl SecureStakeAccount {
+    pub const LEN: usize = 58;
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = SECURE_STAKE_TAG;
+        data[1..33].copy_from_slice(self.owner.as_ref());
+        data[33..41].copy_from_slice(&self.amount.to_le_bytes());
+        data[41..49].copy_from_slice(&self.nonce.to_le_bytes());
+        data[49..57].copy_from_slice(&self.last_deposit_slot.to_le_bytes());
+        data[57] = self.bump;
+        Ok(())
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN || data[0] != SECURE_STAKE_TAG {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let u64_at = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        Ok(SecureStakeAccount {
+            owner: Pubkey::try_from(&data[1..33]).unwrap(),
+            amount: u64_at(33),
+            nonce: u64_at(41),
+            last_deposit_slot: u64_at(49),
+            bump: data[57],
+        })
+    }
+
+    /// What `Account<'info, SecureStakeAccount>` with its seeds, stored
+    /// bump and `constraint = stake_account.owner == user.key()` check.
+    pub fn load(
+        program_id: &Pubkey,
+        stake: &AccountInfo,
+        user: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        if stake.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let state = Self::unpack(&stake.try_borrow_data()?)?;
+        let address = Pubkey::create_program_address(
+            &[
+                b"stake",
+                user.as_ref(),
+                &state.nonce.to_le_bytes(),
+                &[state.bump],
+            ],
+            program_id,
+        )?;
+        if address != *stake.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if state.owner != *user {
+            return Err(crate::error::StakeError::Unauthorized.into());
+        }
+        Ok(state)
+    }
+}
@@ -0,0 +1,65 @@
+//! VULNERABLE implementation - predictable seeds, plain `create_account`
+//!
+//! The stake account sits at `[b"stake", user]`, which anyone can compute.
+//! Sending it lamports first makes `create_account` fail, so the user can
+//! never create their stake account.
+
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+use solsec_guards::check_cluster_account;
+
+use crate::state::StakeAccount;
+
+/// Accounts: user (signer, writable), stake account (writable), system
+/// program, cluster
+pub fn process_create_stake(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let user = next_account_info(accounts)?;
+    let stake = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+    let cluster = next_account_info(accounts)?;
+    check_cluster_account(cluster, program_id)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // VULNERABLE: Only the user's key in the seeds
+    let (address, bump) = Pubkey::find_program_address(&[b"stake", user.key.as_ref()], program_id);
+    if address != *stake.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // VULNERABLE: Fails if the address already has lamports
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            stake.key,
+            Rent::get()?.minimum_balance(StakeAccount::LEN),
+            StakeAccount::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), stake.clone()],
+        &[&[b"stake", user.key.as_ref(), &[bump]]],
+    )?;
+
+    StakeAccount {
+        owner: *user.key,
+        amount: 0,
+        bump,
+    }
+    .pack(&mut stake.try_borrow_mut_data()?)
+}
@@ -0,0 +1,23 @@
+use solana_program::program_error::ProgramError;
+
+/// The Anchor program's `PoolError`, under the same codes: Anchor numbers
+/// a program's errors from 6000
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolError {
+    MathOverflow,
+    SlippageExceeded,
+    InvalidAmount,
+    InsufficientReserves,
+}
+
+impl From<PoolError> for ProgramError {
+    fn from(error: PoolError) -> Self {
+        ProgramError::Custom(6000 + error as u32)
+    }
+}
+
+impl From<PoolError> for solsec_errors::ErrorClass {
+    fn from(error: PoolError) -> Self {
+        solsec_errors::ARITHMETIC_OVERFLOW.class(error as usize)
+    }
+}
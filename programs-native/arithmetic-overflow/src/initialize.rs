@@ -0,0 +1,64 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::state::Pool;
+
+// ---------------------------------------------------------------------------
+// Initialize Pool
+// ---------------------------------------------------------------------------
+// Accounts: authority (signer, writable), pool (writable), system program
+// ---------------------------------------------------------------------------
+
+pub fn process_initialize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    initial_x: u64,
+    initial_y: u64,
+    fee_bps: u16,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let authority = next_account_info(accounts)?;
+    let pool = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"pool", authority.key.as_ref()], program_id);
+    if address != *pool.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            pool.key,
+            Rent::get()?.minimum_balance(Pool::LEN),
+            Pool::LEN as u64,
+            program_id,
+        ),
+        &[authority.clone(), pool.clone()],
+        &[&[b"pool", authority.key.as_ref(), &[bump]]],
+    )?;
+
+    Pool {
+        authority: *authority.key,
+        reserve_x: initial_x,
+        reserve_y: initial_y,
+        fee_bps,
+        bump,
+    }
+    .pack(&mut pool.try_borrow_mut_data()?)
+}
@@ -0,0 +1,91 @@
+//! Arithmetic Overflow - native program
+//!
+//! The `arithmetic-overflow` pool with a raw `solana-program` entrypoint.
+//! The swap's arithmetic is the same as in the Anchor program, which adds
+//! nothing there; what the native program has to write out itself is the
+//! pool's validation (owner, type tag, seeds) and the argument decoding.
+//!
+//! The vulnerable swap wraps explicitly. A crate's own release profile can
+//! drop `overflow-checks`, and then `*` and `+` compile to exactly this.
+
+#![allow(unexpected_cfgs)]
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Dispatch on the first byte; an instruction's arguments follow it.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        0 => {
+            let [x, y, fee] = read_args(args, [8, 8, 2])?;
+            initialize::process_initialize(
+                program_id,
+                accounts,
+                u64::from_le_bytes(x.try_into().unwrap()),
+                u64::from_le_bytes(y.try_into().unwrap()),
+                u16::from_le_bytes(fee.try_into().unwrap()),
+            )
+        }
+        #[cfg(feature = "vulnerable")]
+        1 => {
+            let [amount_in, min_out] = read_u64s(args)?;
+            vulnerable::process_swap(program_id, accounts, amount_in, min_out)
+        }
+        2 => {
+            let [amount_in, min_out] = read_u64s(args)?;
+            secure::process_swap(program_id, accounts, amount_in, min_out)
+        }
+        3 => {
+            let [genesis_hash] = read_args(args, [32])?;
+            solsec_guards::record_cluster(program_id, accounts, genesis_hash.try_into().unwrap())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Borsh's encoding of fixed-size arguments: their bytes in order, nothing
+// else after them
+fn read_args<const N: usize>(args: &[u8], sizes: [usize; N]) -> Result<[&[u8]; N], ProgramError> {
+    if args.len() != sizes.iter().sum::<usize>() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let mut rest = args;
+    Ok(sizes.map(|size| {
+        let (field, tail) = rest.split_at(size);
+        rest = tail;
+        field
+    }))
+}
+
+fn read_u64s(args: &[u8]) -> Result<[u64; 2], ProgramError> {
+    let fields = read_args(args, [8, 8])?;
+    Ok(fields.map(|field| u64::from_le_bytes(field.try_into().unwrap())))
+}
+
+/// The program, for its registry entry
+pub struct NativeArithmeticOverflow;
+
+impl solsec_registry::Catalogued for NativeArithmeticOverflow {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::ARITHMETIC_OVERFLOW;
+}
@@ -0,0 +1,72 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::error::PoolError;
+use crate::state::Pool;
+
+// ---------------------------------------------------------------------------
+// SECURE: Checked Arithmetic with u128 Intermediates
+// ---------------------------------------------------------------------------
+// FIX: Use checked_* methods and u128 for intermediate calculations, the
+// same code as the Anchor program's.
+//
+// Accounts: user (signer, writable), pool (writable)
+// ---------------------------------------------------------------------------
+
+pub fn process_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_out: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let user = next_account_info(accounts)?;
+    let pool = next_account_info(accounts)?;
+
+    // `Signer<'info>`
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut state = Pool::load(program_id, pool)?;
+    if amount_in == 0 {
+        return Err(PoolError::InvalidAmount.into());
+    }
+    let reserve_x = state.reserve_x;
+    let reserve_y = state.reserve_y;
+
+    // SECURE: u128 intermediates can't overflow u64 * u64
+    let numerator = (amount_in as u128)
+        .checked_mul(reserve_y as u128)
+        .ok_or(PoolError::MathOverflow)?;
+    let denominator = (reserve_x as u128)
+        .checked_add(amount_in as u128)
+        .ok_or(PoolError::MathOverflow)?;
+    let amount_out = numerator
+        .checked_div(denominator)
+        .ok_or(PoolError::MathOverflow)?;
+    // SECURE: Ensure result fits in u64
+    let amount_out = u64::try_from(amount_out).map_err(|_| PoolError::MathOverflow)?;
+
+    // SECURE: Slippage protection
+    solsec_math::slippage::check_min_out(amount_out, min_out)
+        .map_err(|_| PoolError::SlippageExceeded)?;
+    if amount_out > reserve_y {
+        return Err(PoolError::InsufficientReserves.into());
+    }
+
+    state.reserve_x = reserve_x
+        .checked_add(amount_in)
+        .ok_or(PoolError::MathOverflow)?;
+    state.reserve_y = reserve_y
+        .checked_sub(amount_out)
+        .ok_or(PoolError::MathOverflow)?;
+    state.pack(&mut pool.try_borrow_mut_data()?)?;
+
+    set_return_data(&amount_out.to_le_bytes());
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+// ---------------------------------------------------------------------------
+// Pool State
+// ---------------------------------------------------------------------------
+// Anchor's layout with a one-byte type tag in place of the discriminator.
+// ---------------------------------------------------------------------------
+
+pub const POOL_TAG: u8 = 2;
+
+pub struct Pool {
+    pub authority: Pubkey, // bytes 1..33
+    pub reserve_x: u64,    // bytes 33..41
+    pub reserve_y: u64,    // bytes 41..49
+    pub fee_bps: u16,      // bytes 49..51, fee in basis points (100 = 1%)
+    pub bump: u8,          // byte 51
+}
+
+impl Pool {
+    pub const LEN: usize = 52;
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = POOL_TAG;
+        data[1..33].copy_from_slice(self.authority.as_ref());
+        data[33..41].copy_from_slice(&self.reserve_x.to_le_bytes());
+        data[41..49].copy_from_slice(&self.reserve_y.to_le_bytes());
+        data[49..51].copy_from_slice(&self.fee_bps.to_le_bytes());
+        data[51] = self.bump;
+        Ok(())
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN || data[0] != POOL_TAG {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let u64_at = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        Ok(Pool {
+            authority: Pubkey::try_from(&data[1..33]).unwrap(),
+            reserve_x: u64_at(33),
+            reserve_y: u64_at(41),
+            fee_bps: u16::from_le_bytes([data[49], data[50]]),
+            bump: data[51],
+        })
+    }
+
+    /// What `Account<'info, Pool>` with `seeds = [b"pool", pool.authority]`
+    /// and `bump = pool.bump` check.
+    pub fn load(program_id: &Pubkey, pool: &AccountInfo) -> Result<Self, ProgramError> {
+        if pool.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let state = Self::unpack(&pool.try_borrow_data()?)?;
+        let address = Pubkey::create_program_address(
+            &[b"pool", state.authority.as_ref(), &[state.bump]],
+            program_id,
+        )?;
+        if address != *pool.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(state)
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solsec_guards::check_cluster_account;
+
+use crate::state::Pool;
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Unchecked Arithmetic
+// ---------------------------------------------------------------------------
+// Raw u64 multiplication and division, wrapping as they do without
+// `overflow-checks`. Large inputs cause:
+// 1. Overflow: amount_in * reserve_y wraps around
+// 2. Precision loss: integer division truncates to zero for small values
+//
+// Accounts: user (signer, writable), pool (writable), cluster
+// ---------------------------------------------------------------------------
+
+pub fn process_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    _min_out: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let user = next_account_info(accounts)?;
+    let pool = next_account_info(accounts)?;
+    let cluster = next_account_info(accounts)?;
+    check_cluster_account(cluster, program_id)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut state = Pool::load(program_id, pool)?;
+    let reserve_x = state.reserve_x;
+    let reserve_y = state.reserve_y;
+
+    // VULNERABLE: If amount_in = u64::MAX / 2 and reserve_y = 3, this wraps
+    let numerator = amount_in.wrapping_mul(reserve_y);
+    // VULNERABLE: Small amounts result in 0 output, and a wrapped
+    // denominator of 0 panics
+    let amount_out = numerator / reserve_x.wrapping_add(amount_in);
+
+    // No slippage check against min_out!
+    state.reserve_x = reserve_x.wrapping_add(amount_in);
+    state.reserve_y = reserve_y.wrapping_sub(amount_out);
+    state.pack(&mut pool.try_borrow_mut_data()?)?;
+
+    set_return_data(&amount_out.to_le_bytes());
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use solana_program::program_error::ProgramError;
+
+/// The Anchor program's `DaoError`, under the same codes: Anchor numbers a
+/// program's errors from 6000
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaoError {
+    Unauthorized,
+    AlreadyInitialized,
+    TitleTooLong,
+    AlreadyExecuted,
+    LogFull,
+    ProposalNotApproved,
+}
+
+impl From<DaoError> for ProgramError {
+    fn from(error: DaoError) -> Self {
+        ProgramError::Custom(6000 + error as u32)
+    }
+}
+
+impl From<DaoError> for solsec_errors::ErrorClass {
+    fn from(error: DaoError) -> Self {
+        solsec_errors::MULTISIG_PAYER.class(error as usize)
+    }
+}
@@ -0,0 +1,75 @@
+//! Initialize the DAO config and its treasury PDA for both proposal flows.
+//! Not part of the vulnerability.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::state::{DaoConfig, MultisigTreasury};
+
+/// Accounts: creator (signer, writable), config (writable), treasury
+/// (writable), system program
+pub fn process_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let creator = next_account_info(accounts)?;
+    let config = next_account_info(accounts)?;
+    let treasury = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (config_address, config_bump) = Pubkey::find_program_address(&[b"dao_config"], program_id);
+    let (treasury_address, treasury_bump) =
+        Pubkey::find_program_address(&[b"treasury", config.key.as_ref()], program_id);
+    if config_address != *config.key || treasury_address != *treasury.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            config.key,
+            rent.minimum_balance(DaoConfig::LEN),
+            DaoConfig::LEN as u64,
+            program_id,
+        ),
+        &[creator.clone(), config.clone()],
+        &[&[b"dao_config", &[config_bump]]],
+    )?;
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            treasury.key,
+            rent.minimum_balance(MultisigTreasury::LEN),
+            MultisigTreasury::LEN as u64,
+            program_id,
+        ),
+        &[creator.clone(), treasury.clone()],
+        &[&[b"treasury", config.key.as_ref(), &[treasury_bump]]],
+    )?;
+
+    DaoConfig {
+        authority: *treasury.key,
+        proposal_count: 0,
+        bump: config_bump,
+        is_initialized: true,
+    }
+    .pack(&mut config.try_borrow_mut_data()?)?;
+    MultisigTreasury {
+        dao: *config.key,
+        bump: treasury_bump,
+    }
+    .pack(&mut treasury.try_borrow_mut_data()?)
+}
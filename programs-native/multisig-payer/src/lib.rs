@@ -0,0 +1,76 @@
+//! Multisig as Payer - native program
+//!
+//! The `multisig-payer` DAO with a raw `solana-program` entrypoint. In
+//! Anchor, `init, payer = treasury` hides a `create_account` CPI funded by
+//! the treasury PDA; here the CPI is written out, and so is why it can't
+//! work: the system program only moves lamports out of an account it owns
+//! that holds no data, and the treasury is neither, whoever signs for it.
+
+#![allow(unexpected_cfgs)]
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Dispatch on the first byte; an instruction's arguments follow it.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        0 => initialize::process_initialize(program_id, accounts),
+        #[cfg(feature = "vulnerable")]
+        1 => {
+            let (proposal_id, title) = read_proposal(args)?;
+            vulnerable::process_create_proposal(program_id, accounts, proposal_id, title)
+        }
+        2 => {
+            let (proposal_id, title) = read_proposal(args)?;
+            secure::process_create_proposal(program_id, accounts, proposal_id, title)
+        }
+        3 => solsec_guards::record_cluster(program_id, accounts, read_hash(args)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Borsh's `(u64, String)`: the id, then the title's length as a `u32` and
+// its UTF-8 bytes
+fn read_proposal(args: &[u8]) -> Result<(u64, &str), ProgramError> {
+    let invalid = ProgramError::InvalidInstructionData;
+    let (id, rest) = args.split_at_checked(8).ok_or(invalid.clone())?;
+    let (len, title) = rest.split_at_checked(4).ok_or(invalid.clone())?;
+    if title.len() != u32::from_le_bytes(len.try_into().unwrap()) as usize {
+        return Err(invalid);
+    }
+    let title = core::str::from_utf8(title).map_err(|_| invalid)?;
+    Ok((u64::from_le_bytes(id.try_into().unwrap()), title))
+}
+
+// Borsh's `[u8; 32]`: the bytes alone
+fn read_hash(args: &[u8]) -> Result<[u8; 32], ProgramError> {
+    args.try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// The program, for its registry entry
+pub struct NativeMultisigPayer;
+
+impl solsec_registry::Catalogued for NativeMultisigPayer {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability = &solsec_registry::MULTISIG_PAYER;
+}
@@ -0,0 +1,75 @@
+//! SECURE implementation - separate rent payer
+//!
+//! A regular signer funds the proposal account and has no authority over
+//! the DAO; the treasury PDA is still checked as the config's authority.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    log::sol_log_data,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::error::DaoError;
+use crate::state::{DaoConfig, MultisigTreasury, Proposal};
+
+/// Accounts: rent payer (signer, writable), creator (signer), treasury,
+/// config, proposal (writable), system program
+pub fn process_create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proposal_id: u64,
+    title: &str,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let rent_payer = next_account_info(accounts)?;
+    let creator = next_account_info(accounts)?;
+    let treasury = next_account_info(accounts)?;
+    let config = next_account_info(accounts)?;
+    let proposal = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    // SECURE: Both are `Signer<'info>`; the runtime only checks the payer's
+    // signature when the CPI moves its lamports, the creator's never
+    if !rent_payer.is_signer || !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    MultisigTreasury::load(program_id, treasury, config.key)?;
+    if DaoConfig::load(program_id, config)?.authority != *treasury.key {
+        return Err(DaoError::Unauthorized.into());
+    }
+    let id_bytes = proposal_id.to_le_bytes();
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"proposal", config.key.as_ref(), &id_bytes], program_id);
+    if address != *proposal.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let state = Proposal::new(proposal_id, title, *creator.key, bump)?;
+
+    // SECURE: A regular signer pays rent
+    invoke_signed(
+        &system_instruction::create_account(
+            rent_payer.key,
+            proposal.key,
+            Rent::get()?.minimum_balance(Proposal::LEN),
+            Proposal::LEN as u64,
+            program_id,
+        ),
+        &[rent_payer.clone(), proposal.clone()],
+        &[&[b"proposal", config.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+    state.pack(&mut proposal.try_borrow_mut_data()?)?;
+
+    msg!("Proposal created");
+    sol_log_data(&[&id_bytes, creator.key.as_ref(), rent_payer.key.as_ref()]);
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! DAO state, laid out by hand
+//!
+//! Anchor's layouts with a one-byte type tag in place of the
+//! discriminator, which is what tells a config, a treasury and a proposal
+//! apart: the owner check passes for all three.
+
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::DaoError;
+
+pub const CONFIG_TAG: u8 = 5;
+pub const TREASURY_TAG: u8 = 6;
+pub const PROPOSAL_TAG: u8 = 7;
+
+/// Maximum title length for proposals
+pub const MAX_TITLE_LEN: usize = 64;
+
+/// DAO configuration with multisig authority, at `[b"dao_config"]`
+pub struct DaoConfig {
+    pub authority: Pubkey,    // bytes 1..33
+    pub proposal_count: u64,  // bytes 33..41
+    pub bump: u8,             // byte 41
+    pub is_initialized: bool, // byte 42
+}
+
+impl DaoConfig {
+    pub const LEN: usize = 43;
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = CONFIG_TAG;
+        data[1..33].copy_from_slice(self.authority.as_ref());
+        data[33..41].copy_from_slice(&self.proposal_count.to_le_bytes());
+        data[41] = self.bump;
+        data[42] = self.is_initialized as u8;
+        Ok(())
+    }
+
+    /// `Account<'info, DaoConfig>` with `seeds = [b"dao_config"]` and
+    /// `bump = config.bump`.
+    pub fn load(program_id: &Pubkey, config: &AccountInfo) -> Result<Self, ProgramError> {
+        if config.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let data = config.try_borrow_data()?;
+        if data.len() != Self::LEN || data[0] != CONFIG_TAG || data[42] > 1 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let state = DaoConfig {
+            authority: Pubkey::try_from(&data[1..33]).unwrap(),
+            proposal_count: u64::from_le_bytes(data[33..41].try_into().unwrap()),
+            bump: data[41],
+            is_initialized: data[42] == 1,
+        };
+        let address = Pubkey::create_program_address(&[b"dao_config", &[state.bump]], program_id)?;
+        if address != *config.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(state)
+    }
+}
+
+/// Multisig treasury PDA, at `[b"treasury", config]`
+pub struct MultisigTreasury {
+    pub dao: Pubkey, // bytes 1..33
+    pub bump: u8,    // byte 33
+}
+
+impl MultisigTreasury {
+    pub const LEN: usize = 34;
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = TREASURY_TAG;
+        data[1..33].copy_from_slice(self.dao.as_ref());
+        data[33] = self.bump;
+        Ok(())
+    }
+
+    /// `Account<'info, MultisigTreasury>` with `seeds = [b"treasury",
+    /// config]` and `bump = treasury.bump`.
+    pub fn load(
+        program_id: &Pubkey,
+        treasury: &AccountInfo,
+        config: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        if treasury.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let data = treasury.try_borrow_data()?;
+        if data.len() != Self::LEN || data[0] != TREASURY_TAG {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let state = MultisigTreasury {
+            dao: Pubkey::try_from(&data[1..33]).unwrap(),
+            bump: data[33],
+        };
+        let address = Pubkey::create_program_address(
+            &[b"treasury", config.as_ref(), &[state.bump]],
+            program_id,
+        )?;
+        if address != *treasury.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(state)
+    }
+}
+
+/// A governance proposal, at `[b"proposal", config, id]`
+pub struct Proposal {
+    pub id: u64,                    // bytes 1..9
+    pub title: [u8; MAX_TITLE_LEN], // bytes 9..73, the first `title_len` meaningful
+    pub title_len: u8,              // byte 73
+    pub creator: Pubkey,            // bytes 74..106
+    pub yes_votes: u64,             // bytes 106..114
+    pub no_votes: u64,              // bytes 114..122
+    pub executed: bool,             // byte 122
+    pub bump: u8,                   // byte 123
+}
+
+impl Proposal {
+    pub const LEN: usize = 124;
+
+    /// A new proposal; `title` must fit in [`MAX_TITLE_LEN`] bytes.
+    pub fn new(id: u64, title: &str, creator: Pubkey, bump: u8) -> Result<Self, ProgramError> {
+        let bytes = title.as_bytes();
+        if bytes.len() > MAX_TITLE_LEN {
+            return Err(DaoError::TitleTooLong.into());
+        }
+        let mut stored = [0; MAX_TITLE_LEN];
+        stored[..bytes.len()].copy_from_slice(bytes);
+        Ok(Proposal {
+            id,
+            title: stored,
+            title_len: bytes.len() as u8,
+            creator,
+            yes_votes: 0,
+            no_votes: 0,
+            executed: false,
+            bump,
+        })
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = PROPOSAL_TAG;
+        data[1..9].copy_from_slice(&self.id.to_le_bytes());
+        data[9..73].copy_from_slice(&self.title);
+        data[73] = self.title_len;
+        data[74..106].copy_from_slice(self.creator.as_ref());
+        data[106..114].copy_from_slice(&self.yes_votes.to_le_bytes());
+        data[114..122].copy_from_slice(&self.no_votes.to_le_bytes());
+        data[122] = self.executed as u8;
+        data[123] = self.bump;
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! VULNERABLE implementation - PDA as payer
+//!
+//! `create_account` is funded by the treasury PDA, as `init, payer =
+//! treasury` would have it. The program signs only for the proposal's
+//! seeds, as Anchor's generated CPI does, so the runtime refuses the
+//! treasury as an unsigned payer. Signing for the treasury too only moves
+//! the failure into the system program, which won't take lamports from an
+//! account it doesn't own.
+
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+use solsec_guards::check_cluster_account;
+
+use crate::error::DaoError;
+use crate::state::{DaoConfig, MultisigTreasury, Proposal};
+
+/// Accounts: treasury (writable), config, proposal (writable), creator
+/// (signer), system program, cluster
+pub fn process_create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proposal_id: u64,
+    title: &str,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let treasury = next_account_info(accounts)?;
+    let config = next_account_info(accounts)?;
+    let proposal = next_account_info(accounts)?;
+    let creator = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+    let cluster = next_account_info(accounts)?;
+    check_cluster_account(cluster, program_id)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    MultisigTreasury::load(program_id, treasury, config.key)?;
+    if DaoConfig::load(program_id, config)?.authority != *treasury.key {
+        return Err(DaoError::Unauthorized.into());
+    }
+    let id_bytes = proposal_id.to_le_bytes();
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"proposal", config.key.as_ref(), &id_bytes], program_id);
+    if address != *proposal.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let state = Proposal::new(proposal_id, title, *creator.key, bump)?;
+
+    // BUG: The treasury is a PDA and can't fund a system transfer
+    invoke_signed(
+        &system_instruction::create_account(
+            treasury.key,
+            proposal.key,
+            Rent::get()?.minimum_balance(Proposal::LEN),
+            Proposal::LEN as u64,
+            program_id,
+        ),
+        &[treasury.clone(), proposal.clone()],
+        &[&[b"proposal", config.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+
+    state.pack(&mut proposal.try_borrow_mut_data()?)
+}
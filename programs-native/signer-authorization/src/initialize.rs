@@ -0,0 +1,84 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// Initialize Vault
+// ---------------------------------------------------------------------------
+// Accounts: authority (signer, writable), vault (writable), system program
+// ---------------------------------------------------------------------------
+
+pub fn process_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let authority = next_account_info(accounts)?;
+    let vault = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    // `#[account(mut)] authority: Signer`: it pays for the vault
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // `Program<'info, System>`: otherwise the CPI goes wherever the caller
+    // points it
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // `seeds` and `bump`: Anchor finds the canonical bump and stores it
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"vault", authority.key.as_ref()], program_id);
+    if address != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // `init, payer = authority, space = ...`
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            vault.key,
+            Rent::get()?.minimum_balance(Vault::LEN),
+            Vault::LEN as u64,
+            program_id,
+        ),
+        &[authority.clone(), vault.clone()],
+        &[&[b"vault", authority.key.as_ref(), &[bump]]],
+    )?;
+
+    Vault {
+        authority: *authority.key,
+        bump,
+    }
+    .pack(&mut vault.try_borrow_mut_data()?)
+}
+
+/// Deposit lamports into the authority's vault.
+/// Accounts: authority (signer, writable), vault (writable), system program
+pub fn process_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let authority = next_account_info(accounts)?;
+    let vault = next_account_info(accounts)?;
+    let system = next_account_info(accounts)?;
+
+    if *system.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Vault::load(program_id, vault, authority.key)?;
+
+    // The system program checks the signature on the lamports it moves
+    invoke(
+        &system_instruction::transfer(authority.key, vault.key, amount),
+        &[authority.clone(), vault.clone()],
+    )
+}
@@ -0,0 +1,74 @@
+//! Signer Authorization - native program
+//!
+//! The `signer-authorization` vault with a raw `solana-program`
+//! entrypoint. Each constraint the Anchor program declares is a check
+//! written out here:
+//!
+//! - `Signer<'info>` is `is_signer` on the account
+//! - `Account<'info, Vault>` is an owner check and the type tag that
+//!   stands in for the discriminator
+//! - `seeds` and `bump = vault.bump` re-derive the address from the
+//!   stored bump
+//! - `init` is a system program CPI signed with the vault's seeds
+//!
+//! Leaving any one out still compiles; the vulnerable withdraw leaves out
+//! only the first, as the Anchor one does.
+
+#![allow(unexpected_cfgs)]
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Dispatch on the first byte; an instruction's arguments follow it.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        0 => initialize::process_initialize(program_id, accounts),
+        1 => initialize::process_deposit(program_id, accounts, read_u64(args)?),
+        #[cfg(feature = "vulnerable")]
+        2 => vulnerable::process_withdraw(program_id, accounts, read_u64(args)?),
+        3 => secure::process_withdraw(program_id, accounts, read_u64(args)?),
+        4 => solsec_guards::record_cluster(program_id, accounts, read_hash(args)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Borsh's `u64`, which Anchor would decode for the handler
+fn read_u64(args: &[u8]) -> Result<u64, ProgramError> {
+    args.try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+// Borsh's `[u8; 32]`: the bytes alone
+fn read_hash(args: &[u8]) -> Result<[u8; 32], ProgramError> {
+    args.try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// The program, for its registry entry
+pub struct NativeSignerAuthorization;
+
+impl solsec_registry::Catalogued for NativeSignerAuthorization {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::SIGNER_AUTHORIZATION;
+}
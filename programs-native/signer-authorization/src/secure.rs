@@ -0,0 +1,57 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use crate::error::VaultError;
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// SECURE: Proper Signer Validation
+// ---------------------------------------------------------------------------
+// FIX: Check `is_signer` on the authority and compare it with the stored one,
+// what `Signer<'info>` and the `constraint` on the vault do in Anchor.
+//
+// Accounts: vault (writable), authority (signer), destination (writable)
+// ---------------------------------------------------------------------------
+
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let vault = next_account_info(accounts)?;
+    let authority = next_account_info(accounts)?;
+    let destination = next_account_info(accounts)?;
+
+    let state = Vault::load(program_id, vault, authority.key)?;
+    // SECURE: `Signer<'info>`
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // SECURE: `constraint = vault.authority == authority.key()`. The seeds
+    // already tie the two together; this keeps the check if they change
+    if state.authority != *authority.key {
+        return Err(VaultError::UnauthorizedAuthority.into());
+    }
+
+    // The vault record must survive the withdrawal
+    let rent = Rent::get()?.minimum_balance(Vault::LEN);
+    let remaining = vault
+        .lamports()
+        .checked_sub(amount)
+        .filter(|&left| left >= rent)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let received = destination
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **vault.try_borrow_mut_lamports()? = remaining;
+    **destination.try_borrow_mut_lamports()? = received;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+// ---------------------------------------------------------------------------
+// Vault State
+// ---------------------------------------------------------------------------
+// Anchor's layout without the discriminator. A one-byte tag takes its
+// place: without it any 34-byte account of the program reads as a vault.
+// ---------------------------------------------------------------------------
+
+pub const VAULT_TAG: u8 = 1;
+
+pub struct Vault {
+    pub authority: Pubkey, // bytes 1..33
+    pub bump: u8,          // byte 33
+}
+
+impl Vault {
+    pub const LEN: usize = 34;
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = VAULT_TAG;
+        data[1..33].copy_from_slice(self.authority.as_ref());
+        data[33] = self.bump;
+        Ok(())
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        match data {
+            [VAULT_TAG, authority @ .., bump] if data.len() == Self::LEN => Ok(Vault {
+                authority: Pubkey::try_from(authority)
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+                bump: *bump,
+            }),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// What `Account<'info, Vault>` with `seeds = [b"vault", authority]`
+    /// and `bump = vault.bump` check: the program owns `vault`, it holds a
+    /// vault, and it sits at the address its stored bump gives.
+    pub fn load(
+        program_id: &Pubkey,
+        vault: &AccountInfo,
+        authority: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        if vault.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let state = Self::unpack(&vault.try_borrow_data()?)?;
+        let address = Pubkey::create_program_address(
+            &[b"vault", authority.as_ref(), &[state.bump]],
+            program_id,
+        )?;
+        if address != *vault.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(state)
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solsec_guards::check_cluster_account;
+
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Missing Signer Authorization
+// ---------------------------------------------------------------------------
+// The vault is loaded with every check `Account<'info, Vault>` and its seeds
+// make, but nothing reads `authority.is_signer`. Anyone can pass the vault's
+// authority without its signature and withdraw.
+//
+// Accounts: vault (writable), authority, destination (writable), cluster
+// ---------------------------------------------------------------------------
+
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let vault = next_account_info(accounts)?;
+    let authority = next_account_info(accounts)?;
+    let destination = next_account_info(accounts)?;
+    let cluster = next_account_info(accounts)?;
+    check_cluster_account(cluster, program_id)?;
+
+    Vault::load(program_id, vault, authority.key)?;
+
+    // DANGER: No signature verification - anyone can drain!
+    let remaining = vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let received = destination
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **vault.try_borrow_mut_lamports()? = remaining;
+    **destination.try_borrow_mut_lamports()? = received;
+    Ok(())
+}
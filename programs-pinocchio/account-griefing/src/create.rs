@@ -0,0 +1,63 @@
+//! Creating a PDA the way Anchor's `init` does
+//!
+//! `CreateAccount` fails with `AccountAlreadyInUse` once the address holds
+//! any lamports, and anyone can send lamports to any address. An account
+//! the system program still owns, with no data, can instead be topped up
+//! to rent exemption, allocated and assigned, which only its seeds can
+//! sign for.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+/// Create `account`, at the PDA `signer` signs for, with `space` bytes
+/// owned by `program_id`, `payer` funding its rent.
+pub fn create_pda(
+    payer: &AccountInfo,
+    account: &AccountInfo,
+    signer: Signer,
+    space: usize,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return CreateAccount {
+            from: payer,
+            to: account,
+            lamports: rent,
+            space: space as u64,
+            owner: program_id,
+        }
+        .invoke_signed(&[signer]);
+    }
+
+    // Already created, not just funded: `init` refuses it too
+    if !account.is_owned_by(&pinocchio_system::ID) || !account.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if lamports < rent {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: rent - lamports,
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account,
+        space: space as u64,
+    }
+    .invoke_signed(core::slice::from_ref(&signer))?;
+    Assign {
+        account,
+        owner: program_id,
+    }
+    .invoke_signed(&[signer])
+}
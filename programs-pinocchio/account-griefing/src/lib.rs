@@ -0,0 +1,69 @@
+//! Account Creation Griefing - Pinocchio program
+//!
+//! The `account-griefing` stake accounts in Pinocchio, `no_std` and with no
+//! allocator. Pinocchio has no `init`: the fix is the native program's, a
+//! nonce in the seeds and [`create::create_pda`] for an address that
+//! already holds lamports, written against `pinocchio-system`'s CPI
+//! structs instead of `solana-program`'s instruction builders.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{no_allocator, nostd_panic_handler, program_entrypoint, ProgramResult};
+use solsec_guards::pinocchio_09;
+
+pub mod create;
+pub mod error;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(not(feature = "no-entrypoint"))]
+program_entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+no_allocator!();
+#[cfg(not(feature = "no-entrypoint"))]
+nostd_panic_handler!();
+
+/// Dispatch on the first byte; an instruction's arguments follow it.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        #[cfg(feature = "vulnerable")]
+        0 => vulnerable::process_create_stake(program_id, accounts),
+        1 => secure::process_create_stake(program_id, accounts, read_u64(args)?),
+        2 => secure::process_deposit(program_id, accounts, read_u64(args)?),
+        3 => pinocchio_09::record_cluster(program_id, accounts, read_hash(args)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Borsh's `u64`, which Anchor would decode for the handler
+fn read_u64(args: &[u8]) -> Result<u64, ProgramError> {
+    args.try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+// Borsh's `[u8; 32]`: the bytes alone
+fn read_hash(args: &[u8]) -> Result<[u8; 32], ProgramError> {
+    args.try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// The program, for its registry entry
+pub struct PAccountGriefing;
+
+impl solsec_registry::Catalogued for PAccountGriefing {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::ACCOUNT_GRIEFING;
+}
@@ -0,0 +1,109 @@
+//! SECURE implementation - nonce in the seeds, prefund-tolerant creation
+//!
+//! The nonce keeps the address from being computed ahead of time, and
+//! [`create_pda`] keeps it from being blocked once it is known.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    log::sol_log_data,
+    msg,
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::create::create_pda;
+use crate::error::StakeError;
+use crate::state::SecureStakeAccount;
+
+/// Accounts: user (signer, writable), stake account (writable), system
+/// program
+pub fn process_create_stake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+) -> ProgramResult {
+    let [user, stake, system, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // SECURE: Nonce makes the PDA unpredictable
+    let nonce_bytes = nonce.to_le_bytes();
+    let (address, bump) =
+        pubkey::find_program_address(&[b"stake", user.key(), &nonce_bytes], program_id);
+    if address != *stake.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // SECURE: What `init` does, including for a pre-funded address
+    let bump_bytes = [bump];
+    let seeds = [
+        Seed::from(b"stake"),
+        Seed::from(user.key()),
+        Seed::from(&nonce_bytes),
+        Seed::from(&bump_bytes),
+    ];
+    create_pda(
+        user,
+        stake,
+        Signer::from(&seeds),
+        SecureStakeAccount::LEN,
+        program_id,
+    )?;
+
+    SecureStakeAccount::init(&mut stake.try_borrow_mut_data()?, user.key(), nonce, bump)?;
+
+    msg!("Created secure stake account");
+    sol_log_data(&[&nonce_bytes]);
+    Ok(())
+}
+
+/// Accounts: user (signer, writable), stake account (writable), system
+/// program
+pub fn process_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let [user, stake, system, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    SecureStakeAccount::load(program_id, stake, user.key())?;
+    if amount == 0 {
+        return Err(StakeError::InvalidAmount.into());
+    }
+
+    Transfer {
+        from: user,
+        to: stake,
+        lamports: amount,
+    }
+    .invoke()?;
+
+    // Written in place, where Anchor's `Account` writes the struct back
+    SecureStakeAccount::deposit(
+        &mut stake.try_borrow_mut_data()?,
+        amount,
+        Clock::get()?.slot,
+    )?;
+
+    msg!("Deposited");
+    sol_log_data(&[&amount.to_le_bytes()]);
+    Ok(())
+}
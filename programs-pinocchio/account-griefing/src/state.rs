@@ -0,0 +1,90 @@
+//! Stake accounts, laid out by hand
+//!
+//! The native program's layouts, with a one-byte type tag in place of the
+//! discriminator, read and written in place.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    ProgramResult,
+};
+
+use crate::error::StakeError;
+
+pub const STAKE_TAG: u8 = 3;
+pub const SECURE_STAKE_TAG: u8 = 4;
+
+/// Stake account at the predictable `[b"stake", owner]`: owner (bytes
+/// 1..33), amount (33..41), bump (41)
+pub struct StakeAccount;
+
+impl StakeAccount {
+    pub const LEN: usize = 42;
+
+    pub fn init(data: &mut [u8], owner: &Pubkey, bump: u8) -> ProgramResult {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = STAKE_TAG;
+        data[1..33].copy_from_slice(owner);
+        data[33..41].fill(0);
+        data[41] = bump;
+        Ok(())
+    }
+}
+
+/// Stake account at `[b"stake", owner, nonce]`: owner (bytes 1..33),
+/// amount (33..41), nonce (41..49), last deposit slot (49..57), bump (57)
+pub struct SecureStakeAccount;
+
+impl SecureStakeAccount {
+    pub const LEN: usize = 58;
+
+    pub fn init(data: &mut [u8], owner: &Pubkey, nonce: u64, bump: u8) -> ProgramResult {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = SECURE_STAKE_TAG;
+        data[1..33].copy_from_slice(owner);
+        data[33..41].fill(0);
+        data[41..49].copy_from_slice(&nonce.to_le_bytes());
+        data[49..57].fill(0);
+        data[57] = bump;
+        Ok(())
+    }
+
+    /// What `Account<'info, SecureStakeAccount>` with its seeds, stored
+    /// bump and `constraint = stake_account.owner == user.key()` check.
+    pub fn load(program_id: &Pubkey, stake: &AccountInfo, user: &Pubkey) -> ProgramResult {
+        if !stake.is_owned_by(program_id) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let data = stake.try_borrow_data()?;
+        if data.len() != Self::LEN || data[0] != SECURE_STAKE_TAG {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let address = pubkey::create_program_address(
+            &[b"stake", user, &data[41..49], &data[57..]],
+            program_id,
+        )?;
+        if address != *stake.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if data[1..33] != *user {
+            return Err(StakeError::Unauthorized.into());
+        }
+        Ok(())
+    }
+
+    /// Add `amount` to the stake and record the deposit's slot.
+    pub fn deposit(data: &mut [u8], amount: u64, slot: u64) -> ProgramResult {
+        let staked = u64::from_le_bytes(data[33..41].try_into().unwrap());
+        let staked = staked
+            .checked_add(amount)
+            .ok_or(StakeError::InvalidAmount)?;
+        data[33..41].copy_from_slice(&staked.to_le_bytes());
+        data[49..57].copy_from_slice(&slot.to_le_bytes());
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+//! VULNERABLE implementation - predictable seeds, plain `CreateAccount`
+//!
+//! The stake account sits at `[b"stake", user]`, which anyone can compute.
+//! Sending it lamports first makes `CreateAccount` fail, so the user can
+//! never create their stake account.
+
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use solsec_guards::pinocchio_09::check_cluster_account;
+
+use crate::state::StakeAccount;
+
+/// Accounts: user (signer, writable), stake account (writable), system
+/// program, cluster
+pub fn process_create_stake(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [user, stake, system, cluster, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_cluster_account(cluster, program_id)?;
+
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // VULNERABLE: Only the user's key in the seeds
+    let (address, bump) = pubkey::find_program_address(&[b"stake", user.key()], program_id);
+    if address != *stake.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // VULNERABLE: Fails if the address already has lamports
+    let bump_bytes = [bump];
+    let seeds = [
+        Seed::from(b"stake"),
+        Seed::from(user.key()),
+        Seed::from(&bump_bytes),
+    ];
+    CreateAccount {
+        from: user,
+        to: stake,
+        lamports: Rent::get()?.minimum_balance(StakeAccount::LEN),
+        space: StakeAccount::LEN as u64,
+        owner: program_id,
+    }
+    .invoke_signed(&[Signer::from(&seeds)])?;
+
+    StakeAccount::init(&mut stake.try_borrow_mut_data()?, user.key(), bump)
+}
@@ -0,0 +1,20 @@
+use pinocchio::program_error::ProgramError;
+
+/// The Anchor program's `VaultError`, under the same codes: Anchor
+/// numbers a program's errors from 6000
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultError {
+    UnauthorizedAuthority,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(error: VaultError) -> Self {
+        ProgramError::Custom(6000 + error as u32)
+    }
+}
+
+impl From<VaultError> for solsec_errors::ErrorClass {
+    fn from(error: VaultError) -> Self {
+        solsec_errors::SIGNER_AUTHORIZATION.class(error as usize)
+    }
+}
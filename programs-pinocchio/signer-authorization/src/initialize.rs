@@ -0,0 +1,81 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{CreateAccount, Transfer};
+
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// Initialize Vault
+// ---------------------------------------------------------------------------
+// Accounts: authority (signer, writable), vault (writable), system program
+// ---------------------------------------------------------------------------
+
+pub fn process_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [authority, vault, system, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // `#[account(mut)] authority: Signer`: it pays for the vault
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // `Program<'info, System>`: otherwise the CPI goes wherever the caller
+    // points it
+    if *system.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // `seeds` and `bump`: Anchor finds the canonical bump and stores it
+    let (address, bump) = pubkey::find_program_address(&[b"vault", authority.key()], program_id);
+    if address != *vault.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // `init, payer = authority, space = ...`
+    let bump_bytes = [bump];
+    let seeds = [
+        Seed::from(b"vault"),
+        Seed::from(authority.key()),
+        Seed::from(&bump_bytes),
+    ];
+    CreateAccount {
+        from: authority,
+        to: vault,
+        lamports: Rent::get()?.minimum_balance(Vault::LEN),
+        space: Vault::LEN as u64,
+        owner: program_id,
+    }
+    .invoke_signed(&[Signer::from(&seeds)])?;
+
+    Vault::init(&mut vault.try_borrow_mut_data()?, authority.key(), bump)
+}
+
+/// Deposit lamports into the authority's vault.
+/// Accounts: authority (signer, writable), vault (writable), system program
+pub fn process_deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let [authority, vault, system, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if *system.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Vault::load(program_id, vault, authority.key())?;
+
+    // The system program checks the signature on the lamports it moves
+    Transfer {
+        from: authority,
+        to: vault,
+        lamports: amount,
+    }
+    .invoke()
+}
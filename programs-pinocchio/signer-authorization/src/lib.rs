@@ -0,0 +1,74 @@
+//! Signer Authorization - Pinocchio program
+//!
+//! The `signer-authorization` vault in Pinocchio, `no_std` and with no
+//! allocator: accounts are views into the input buffer and the state is
+//! read in place. The checks are the native program's, in the same order,
+//! and nothing in Pinocchio makes any of them for the program either. What
+//! it drops is the copying: no `AccountInfo` clones or `Vec`s for a CPI,
+//! and the vault is read where it lies in the account's data.
+//!
+//! As in the native program the vulnerable withdraw loads the vault with
+//! every check but `is_signer`.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{no_allocator, nostd_panic_handler, program_entrypoint, ProgramResult};
+use solsec_guards::pinocchio_09;
+
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(not(feature = "no-entrypoint"))]
+program_entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+no_allocator!();
+#[cfg(not(feature = "no-entrypoint"))]
+nostd_panic_handler!();
+
+/// Dispatch on the first byte; an instruction's arguments follow it.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (tag, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        0 => initialize::process_initialize(program_id, accounts),
+        1 => initialize::process_deposit(program_id, accounts, read_u64(args)?),
+        #[cfg(feature = "vulnerable")]
+        2 => vulnerable::process_withdraw(program_id, accounts, read_u64(args)?),
+        3 => secure::process_withdraw(program_id, accounts, read_u64(args)?),
+        4 => pinocchio_09::record_cluster(program_id, accounts, read_hash(args)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// Borsh's `u64`, which Anchor would decode for the handler
+fn read_u64(args: &[u8]) -> Result<u64, ProgramError> {
+    args.try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+// Borsh's `[u8; 32]`: the bytes alone
+fn read_hash(args: &[u8]) -> Result<[u8; 32], ProgramError> {
+    args.try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// The program, for its registry entry
+pub struct PSignerAuthorization;
+
+impl solsec_registry::Catalogued for PSignerAuthorization {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::SIGNER_AUTHORIZATION;
+}
@@ -0,0 +1,53 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::error::VaultError;
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// SECURE: Proper Signer Validation
+// ---------------------------------------------------------------------------
+// FIX: Check `is_signer()` on the authority and compare it with the stored
+// one, what `Signer<'info>` and the `constraint` on the vault do in Anchor.
+//
+// Accounts: vault (writable), authority (signer), destination (writable)
+// ---------------------------------------------------------------------------
+
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let [vault, authority, destination, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let stored = Vault::load(program_id, vault, authority.key())?;
+    // SECURE: `Signer<'info>`
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // SECURE: `constraint = vault.authority == authority.key()`. The seeds
+    // already tie the two together; this keeps the check if they change
+    if stored != *authority.key() {
+        return Err(VaultError::UnauthorizedAuthority.into());
+    }
+
+    // The vault record must survive the withdrawal
+    let rent = Rent::get()?.minimum_balance(Vault::LEN);
+    let mut from = vault.try_borrow_mut_lamports()?;
+    let mut to = destination.try_borrow_mut_lamports()?;
+    *from = from
+        .checked_sub(amount)
+        .filter(|&left| left >= rent)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    *to = to
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+};
+
+// ---------------------------------------------------------------------------
+// Vault State
+// ---------------------------------------------------------------------------
+// The native program's layout: Anchor's without the discriminator, and a
+// one-byte tag in its place. Read in place rather than unpacked.
+// ---------------------------------------------------------------------------
+
+pub const VAULT_TAG: u8 = 1;
+
+pub struct Vault;
+
+impl Vault {
+    pub const LEN: usize = 34;
+
+    /// Write a vault for `authority` into `data`.
+    pub fn init(data: &mut [u8], authority: &Pubkey, bump: u8) -> Result<(), ProgramError> {
+        let data = data
+            .get_mut(..Self::LEN)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        data[0] = VAULT_TAG;
+        data[1..33].copy_from_slice(authority);
+        data[33] = bump;
+        Ok(())
+    }
+
+    /// What `Account<'info, Vault>` with `seeds = [b"vault", authority]`
+    /// and `bump = vault.bump` check, returning the stored authority: the
+    /// program owns `vault`, it holds a vault, and it sits at the address
+    /// its stored bump gives.
+    pub fn load(
+        program_id: &Pubkey,
+        vault: &AccountInfo,
+        authority: &Pubkey,
+    ) -> Result<Pubkey, ProgramError> {
+        if !vault.is_owned_by(program_id) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let data = vault.try_borrow_data()?;
+        let (stored, bump) = match &data[..] {
+            [VAULT_TAG, stored @ .., bump] if data.len() == Self::LEN => (stored, *bump),
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let address = pubkey::create_program_address(&[b"vault", authority, &[bump]], program_id)?;
+        if address != *vault.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        stored
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+use solsec_guards::pinocchio_09::check_cluster_account;
+
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Missing Signer Authorization
+// ---------------------------------------------------------------------------
+// The vault is loaded with every check `Account<'info, Vault>` and its seeds
+// make, but nothing reads `authority.is_signer()`. Anyone can pass the
+// vault's authority without its signature and withdraw.
+//
+// Accounts: vault (writable), authority, destination (writable), cluster
+// ---------------------------------------------------------------------------
+
+pub fn process_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let [vault, authority, destination, cluster, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_cluster_account(cluster, program_id)?;
+
+    Vault::load(program_id, vault, authority.key())?;
+
+    // DANGER: No signature verification - anyone can drain!
+    let mut from = vault.try_borrow_mut_lamports()?;
+    let mut to = destination.try_borrow_mut_lamports()?;
+    *from = from
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    *to = to
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
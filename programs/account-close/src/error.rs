@@ -1,6 +1,6 @@
 //! Error definitions for Account Close program
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum CloseError {
@@ -9,3 +9,9 @@ pub enum CloseError {
     #[msg("Account already closed")]
     AlreadyClosed,
 }
+
+impl From<CloseError> for solsec_errors::ErrorClass {
+    fn from(error: CloseError) -> Self {
+        solsec_errors::ACCOUNT_CLOSE.class(error as usize)
+    }
+}
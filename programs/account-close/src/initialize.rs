@@ -4,7 +4,7 @@
 //! vulnerable and secure versions. Not part of the vulnerability.
 
 use crate::state::UserAccount;
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
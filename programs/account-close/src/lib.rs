@@ -6,18 +6,29 @@
 //! by sending lamports to it within the same transaction. The program
 //! may then re-read the stale data or allow re-initialization.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("Cnji8fAoqzDyJaR1D2MXyk2hgyixZBoSf5UmN6SegpFf");
@@ -26,6 +37,13 @@ declare_id!("Cnji8fAoqzDyJaR1D2MXyk2hgyixZBoSf5UmN6SegpFf");
 pub mod account_close {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize a user data account
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps)
@@ -33,7 +51,9 @@ pub mod account_close {
 
     /// VULNERABLE: Close account without zeroing data
     /// Vulnerable to revival attack
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_close(ctx: Context<VulnerableClose>) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.close()
     }
 
@@ -47,3 +67,5 @@ pub mod account_close {
         Ok(ctx.accounts.user_account.balance)
     }
 }
+
+solsec_prelude::catalogued!(program::AccountClose, ACCOUNT_CLOSE);
@@ -1,51 +1,48 @@
 //! SECURE: Proper Account Closure
 //!
 //! THE FIX:
-//! 1. Zero out all account data before closing
-//! 2. Mark account as closed (discriminator = 0)
-//! 3. Use force_defund to handle edge cases
-//! 4. Check is_initialized flag on subsequent reads
+//! 1. Check the record is live (is_initialized) before closing it
+//! 2. Zero out all account data and write the CLOSED discriminator
+//! 3. Only then move the lamports out, with solsec-guards' `close_account`
+//!
+//! A revived account then holds nothing to read and can't be deserialized
+//! or initialized again.
 
 use crate::error::CloseError;
 use crate::state::UserAccount;
-use anchor_lang::prelude::*;
+use solsec_guards::close_account;
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 pub struct SecureClose<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    // Not an `Account`: it would serialize the record back over the
+    // tombstone on exit, so the handler deserializes it instead
     #[account(
         mut,
+        owner = crate::ID,
         seeds = [b"user", owner.key().as_ref()],
-        bump = user_account.bump,
-        constraint = user_account.is_initialized @ CloseError::AlreadyClosed,
-        close = owner
+        bump
     )]
-    pub user_account: Account<'info, UserAccount>,
+    /// CHECK: Owner and seeds pinned here; discriminator checked in `close`
+    pub user_account: UncheckedAccount<'info>,
 }
 
 impl<'info> SecureClose<'info> {
     pub fn close(&mut self) -> Result<()> {
-        // ---------------------------------------------------------------------------
-        // FIX 1: Zero out all sensitive data before closing
-        // This prevents data leakage if account is revived
-        // ---------------------------------------------------------------------------
-        self.user_account.owner = Pubkey::default();
-        self.user_account.balance = 0;
-        self.user_account.is_initialized = false;
+        let user = UserAccount::try_deserialize(&mut &self.user_account.try_borrow_data()?[..])?;
+        require!(user.is_initialized, CloseError::AlreadyClosed);
 
         // ---------------------------------------------------------------------------
-        // FIX 2: The account data is then serialized back (zeroed)
-        // Combined with Anchor's close = owner, this properly closes the account
-        //
-        // Note: Anchor's close constraint handles:
-        // - Transferring all lamports to destination
-        // - Setting account owner to system program
+        // FIX: Zero the data behind the CLOSED discriminator, then drain it
+        // Sending lamports back later revives an account with nothing in it
         // ---------------------------------------------------------------------------
-
-        // Additional protection: In production, consider using
-        // AccountInfo::realloc to shrink account to 0 bytes
+        close_account(
+            &self.user_account.to_account_info(),
+            &self.owner.to_account_info(),
+        )?;
 
         Ok(())
     }
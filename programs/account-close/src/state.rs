@@ -1,13 +1,21 @@
 //! State definitions for Account Close program
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 /// User account that stores balance data
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace)]
 pub struct UserAccount {
+    /// Bytes 8..40
     pub owner: Pubkey,
+    /// Bytes 40..48
     pub balance: u64,
+    /// Byte 48
     pub is_initialized: bool,
+    /// Byte 49
     pub bump: u8,
 }
+
+const _: () = assert!(8 + UserAccount::INIT_SPACE == 50);
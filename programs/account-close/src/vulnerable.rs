@@ -10,8 +10,11 @@
 //! 2. In same tx, send lamports back to the account address
 //! 3. Account is "alive" again with old data
 
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
 use crate::state::UserAccount;
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 pub struct VulnerableClose<'info> {
@@ -30,6 +33,10 @@ pub struct VulnerableClose<'info> {
         close = owner  // VULNERABLE: data not zeroed
     )]
     pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableClose<'info> {
@@ -1,6 +1,6 @@
 //! Error definitions
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum StakeError {
@@ -10,4 +10,12 @@ pub enum StakeError {
     InvalidAmount,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Stake account layout needs migrating")]
+    OutdatedVersion,
+}
+
+impl From<StakeError> for solsec_errors::ErrorClass {
+    fn from(error: StakeError) -> Self {
+        solsec_errors::ACCOUNT_GRIEFING.class(error as usize)
+    }
 }
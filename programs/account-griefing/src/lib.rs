@@ -8,16 +8,27 @@
 //!
 //! Source: J4X_Security (2026)
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("GWRZSqQP37DEg6R7hEmBHVBDyWJNVwRAPtDduwGhtQqp");
@@ -26,17 +37,36 @@ declare_id!("GWRZSqQP37DEg6R7hEmBHVBDyWJNVwRAPtDduwGhtQqp");
 pub mod account_griefing {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// VULNERABLE: Create stake account with predictable, deterministic seeds.
     /// Attacker can pre-fund PDA to block account creation permanently.
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_create_stake(ctx: Context<VulnerableCreateStake>) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.create_stake(&ctx.bumps)
     }
 
     /// VULNERABLE: Deposit to stake account
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_deposit(ctx: Context<VulnerableDeposit>, amount: u64) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.deposit(amount)
     }
 
+    /// VULNERABLE: Rewrite a stake account in the current layout, assuming
+    /// it is in the old one. Migrating twice corrupts it.
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_migrate_stake(ctx: Context<VulnerableMigrateStake>) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.migrate()
+    }
+
     /// SECURE: Create stake account with random nonce in seeds.
     /// Attacker cannot predict the address without knowing the nonce.
     pub fn secure_create_stake(ctx: Context<SecureCreateStake>, nonce: u64) -> Result<()> {
@@ -47,4 +77,22 @@ pub mod account_griefing {
     pub fn secure_deposit(ctx: Context<SecureDeposit>, amount: u64) -> Result<()> {
         ctx.accounts.deposit(amount)
     }
+
+    /// SECURE: `secure_create_stake` and `secure_deposit` in one
+    /// instruction, all or nothing.
+    pub fn create_and_deposit_stake(
+        ctx: Context<CreateAndDepositStake>,
+        nonce: u64,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.create_and_deposit(&ctx.bumps, nonce, amount)
+    }
+
+    /// SECURE: Rewrite a stake account in the current layout from the
+    /// version it records. Migrating twice changes nothing.
+    pub fn secure_migrate_stake(ctx: Context<SecureMigrateStake>) -> Result<()> {
+        ctx.accounts.migrate()
+    }
 }
+
+solsec_prelude::catalogued!(program::AccountGriefing, ACCOUNT_GRIEFING);
@@ -4,9 +4,11 @@
 //! the address unpredictable to attackers. They cannot fund the PDA in
 //! advance because they don't know which nonce the user will choose.
 
-use anchor_lang::prelude::*;
+use anchor_lang::solana_program::log::sol_log_data;
 use crate::state::SecureStakeAccount;
 use crate::error::StakeError;
+use solsec_guards::{migrate_account, Versioned};
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 #[instruction(nonce: u64)]
@@ -30,17 +32,30 @@ pub struct SecureCreateStake<'info> {
 
 impl<'info> SecureCreateStake<'info> {
     pub fn create_stake(&mut self, bumps: &SecureCreateStakeBumps, nonce: u64) -> Result<()> {
-        self.stake_account.owner = self.user.key();
-        self.stake_account.amount = 0;
-        self.stake_account.nonce = nonce;
-        self.stake_account.bump = bumps.stake_account;
-        self.stake_account.is_initialized = true;
-        
-        msg!("Created secure stake account with nonce: {}", nonce);
-        Ok(())
+        init_stake(&mut self.stake_account, &self.user, nonce, bumps.stake_account)
     }
 }
 
+fn init_stake(
+    stake_account: &mut SecureStakeAccount,
+    user: &Signer,
+    nonce: u64,
+    bump: u8,
+) -> Result<()> {
+    stake_account.version = SecureStakeAccount::VERSION;
+    stake_account.owner = user.key();
+    stake_account.amount = 0;
+    stake_account.nonce = nonce;
+    stake_account.bump = bump;
+    stake_account.is_initialized = true;
+
+    // Static text and the raw value: `msg!` with arguments formats on the
+    // heap
+    msg!("Created secure stake account");
+    sol_log_data(&[&nonce.to_le_bytes()]);
+    Ok(())
+}
+
 #[derive(Accounts)]
 #[instruction(amount: u64)]
 pub struct SecureDeposit<'info> {
@@ -51,7 +66,9 @@ pub struct SecureDeposit<'info> {
         mut,
         seeds = [b"stake", user.key().as_ref(), &stake_account.nonce.to_le_bytes()],
         bump = stake_account.bump,
-        constraint = stake_account.owner == user.key() @ StakeError::Unauthorized
+        constraint = stake_account.owner == user.key() @ StakeError::Unauthorized,
+        // SECURE: Only the current layout is read as the current layout
+        constraint = stake_account.version == SecureStakeAccount::VERSION @ StakeError::OutdatedVersion
     )]
     pub stake_account: Account<'info, SecureStakeAccount>,
 
@@ -60,23 +77,118 @@ pub struct SecureDeposit<'info> {
 
 impl<'info> SecureDeposit<'info> {
     pub fn deposit(&mut self, amount: u64) -> Result<()> {
-        require!(amount > 0, StakeError::InvalidAmount);
-
-        let cpi_context = CpiContext::new(
-            self.system_program.to_account_info(),
-            anchor_lang::system_program::Transfer {
-                from: self.user.to_account_info(),
-                to: self.stake_account.to_account_info(),
-            },
-        );
-        anchor_lang::system_program::transfer(cpi_context, amount)?;
-
-        self.stake_account.amount = self.stake_account
-            .amount
-            .checked_add(amount)
-            .ok_or(StakeError::InvalidAmount)?;
-            
-        msg!("Deposited {} lamports", amount);
+        deposit_into(&mut self.stake_account, &self.user, &self.system_program, amount)
+    }
+}
+
+fn deposit_into<'info>(
+    stake_account: &mut Account<'info, SecureStakeAccount>,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, StakeError::InvalidAmount);
+
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: user.to_account_info(),
+            to: stake_account.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+    stake_account.amount = stake_account
+        .amount
+        .checked_add(amount)
+        .ok_or(StakeError::InvalidAmount)?;
+    stake_account.last_deposit_slot = Clock::get()?.slot;
+
+    msg!("Deposited");
+    sol_log_data(&[&amount.to_le_bytes()]);
+    Ok(())
+}
+
+/// SECURE: Create and fund a stake account in one instruction, with the
+/// same seeds and checks as `secure_create_stake` then `secure_deposit`.
+/// Either both happen or, if the deposit fails, the account is never
+/// created.
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CreateAndDepositStake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + SecureStakeAccount::INIT_SPACE,
+        seeds = [b"stake", user.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub stake_account: Account<'info, SecureStakeAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateAndDepositStake<'info> {
+    pub fn create_and_deposit(
+        &mut self,
+        bumps: &CreateAndDepositStakeBumps,
+        nonce: u64,
+        amount: u64,
+    ) -> Result<()> {
+        init_stake(&mut self.stake_account, &self.user, nonce, bumps.stake_account)?;
+        deposit_into(&mut self.stake_account, &self.user, &self.system_program, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct SecureMigrateStake<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // solsec-lint: allow(unchecked-account) an older layout doesn't deserialize as the current one
+    /// CHECK: migrate_account checks the owner and discriminator, and the
+    /// upgraded record's seeds are checked against this address
+    #[account(mut)]
+    pub stake_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SecureMigrateStake<'info> {
+    pub fn migrate(&mut self) -> Result<()> {
+        let stake_account = self.stake_account.to_account_info();
+        // SECURE: The layout is read from the version byte, and an account
+        // already at the current version is left alone
+        let Some(migrated) = migrate_account::<SecureStakeAccount>(
+            &stake_account,
+            &self.payer.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?
+        else {
+            msg!("Stake account already at the current version");
+            sol_log_data(&[&[SecureStakeAccount::VERSION]]);
+            return Ok(());
+        };
+
+        // The record must be the one its own seeds derive; failing reverts
+        // the rewrite
+        let address = Pubkey::create_program_address(
+            &[
+                b"stake",
+                migrated.owner.as_ref(),
+                &migrated.nonce.to_le_bytes(),
+                &[migrated.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(address, stake_account.key(), ErrorCode::ConstraintSeeds);
+
+        msg!("Migrated stake account");
+        sol_log_data(&[&[SecureStakeAccount::VERSION]]);
         Ok(())
     }
 }
@@ -1,33 +1,93 @@
 //! State definitions for staking accounts
 
-use anchor_lang::prelude::*;
+use solsec_guards::Versioned;
+use solsec_prelude::*;
 
 /// Stake account storing user's staked amount
+///
+/// The current layout inserted `last_deposit_slot` after `amount`. Nothing
+/// in the data says which layout wrote an account.
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace, Default)]
 pub struct StakeAccount {
-    /// Owner of the stake
+    /// Owner of the stake, bytes 8..40
+    pub owner: Pubkey,
+    /// Amount staked, bytes 40..48
+    pub amount: u64,
+    /// Slot of the last deposit, bytes 48..56
+    pub last_deposit_slot: u64,
+    /// Bump seed for PDA derivation, byte 56
+    pub bump: u8,
+    /// Whether this account is initialized, byte 57
+    pub is_initialized: bool,
+}
+
+const _: () = assert!(8 + StakeAccount::INIT_SPACE == 58);
+
+/// `StakeAccount` as the first layout wrote it
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct StakeAccountV1 {
     pub owner: Pubkey,
-    /// Amount staked
     pub amount: u64,
-    /// Bump seed for PDA derivation
     pub bump: u8,
-    /// Whether this account is initialized
     pub is_initialized: bool,
 }
 
 /// Stake account with nonce for secure PDA derivation
+///
+/// Version 2 of the layout, which added `last_deposit_slot`.
 #[account]
 #[derive(InitSpace)]
 pub struct SecureStakeAccount {
-    /// Owner of the stake
+    /// Layout version, always first, byte 8
+    pub version: u8,
+    /// Owner of the stake, bytes 9..41
+    pub owner: Pubkey,
+    /// Amount staked, bytes 41..49
+    pub amount: u64,
+    /// Nonce used in PDA derivation, bytes 49..57
+    pub nonce: u64,
+    /// Slot of the last deposit, bytes 57..65
+    pub last_deposit_slot: u64,
+    /// Bump seed for PDA derivation, byte 65
+    pub bump: u8,
+    /// Whether this account is initialized, byte 66
+    pub is_initialized: bool,
+}
+
+const _: () = assert!(8 + SecureStakeAccount::INIT_SPACE == 67);
+
+/// `SecureStakeAccount` as version 1 wrote it
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SecureStakeAccountV1 {
+    pub version: u8,
     pub owner: Pubkey,
-    /// Amount staked
     pub amount: u64,
-    /// Nonce used in PDA derivation
     pub nonce: u64,
-    /// Bump seed for PDA derivation
     pub bump: u8,
-    /// Whether this account is initialized
     pub is_initialized: bool,
 }
+
+impl Versioned for SecureStakeAccount {
+    const VERSION: u8 = 2;
+
+    fn upgrade(version: u8, data: &[u8]) -> Option<Self> {
+        match version {
+            1 => {
+                let old = SecureStakeAccountV1::deserialize(&mut data.get(8..)?).ok()?;
+                Some(SecureStakeAccount {
+                    version: Self::VERSION,
+                    owner: old.owner,
+                    amount: old.amount,
+                    nonce: old.nonce,
+                    last_deposit_slot: 0,
+                    bump: old.bump,
+                    is_initialized: old.is_initialized,
+                })
+            }
+            _ => None,
+        }
+    }
+}
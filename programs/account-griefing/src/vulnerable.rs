@@ -9,21 +9,28 @@
 //! The create_account instruction will fail because the address already
 //! has lamports (Solana assumes it's "in use").
 
-use anchor_lang::prelude::*;
-use crate::state::StakeAccount;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use crate::state::{StakeAccount, StakeAccountV1};
 use crate::error::StakeError;
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 pub struct VulnerableCreateStake<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// VULNERABLE: Manual account creation without checking pre-funding
+    /// CHECK: VULNERABLE: Manual account creation without checking pre-funding
     /// We use UncheckedAccount because we're creating it manually
     #[account(mut)]
     pub stake_account: AccountInfo<'info>,
 
     pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableCreateStake<'info> {
@@ -51,9 +58,10 @@ impl<'info> VulnerableCreateStake<'info> {
         );
         require_keys_eq!(pda, self.stake_account.key(), StakeError::Unauthorized);
 
+        let user_key = self.user.key();
         let seeds = &[
             b"stake", 
-            self.user.key().as_ref(),
+            user_key.as_ref(),
             &[bump]
         ];
         let signer = &[&seeds[..]];
@@ -95,6 +103,10 @@ pub struct VulnerableDeposit<'info> {
     pub stake_account: Account<'info, StakeAccount>,
 
     pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableDeposit<'info> {
@@ -110,7 +122,64 @@ impl<'info> VulnerableDeposit<'info> {
         anchor_lang::system_program::transfer(cpi_context, amount)?;
 
         self.stake_account.amount = self.stake_account.amount.checked_add(amount).unwrap();
+        self.stake_account.last_deposit_slot = Clock::get()?.slot;
         msg!("Deposited {} lamports", amount);
         Ok(())
     }
 }
+
+#[derive(Accounts)]
+pub struct VulnerableMigrateStake<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: VULNERABLE: An account in the old layout no longer
+    /// deserializes as StakeAccount, so it is taken raw - and nothing in
+    /// the data says which layout it is in
+    #[account(mut, owner = crate::ID)]
+    pub stake_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableMigrateStake<'info> {
+    pub fn migrate(&mut self) -> Result<()> {
+        // VULNERABLE: Every account is assumed to still be in the old
+        // layout. Run against one already migrated, the old bump and
+        // is_initialized are read out of last_deposit_slot.
+        let old = {
+            let data = self.stake_account.try_borrow_data()?;
+            StakeAccountV1::deserialize(&mut &data[8..])?
+        };
+        let migrated = StakeAccount {
+            owner: old.owner,
+            amount: old.amount,
+            last_deposit_slot: 0,
+            bump: old.bump,
+            is_initialized: old.is_initialized,
+        };
+
+        let space = 8 + StakeAccount::INIT_SPACE;
+        let rent = Rent::get()?.minimum_balance(space);
+        let lacking = rent.saturating_sub(self.stake_account.lamports());
+        if lacking > 0 {
+            let cpi_context = CpiContext::new(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.stake_account.to_account_info(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, lacking)?;
+        }
+        self.stake_account.resize(space)?;
+        migrated.try_serialize(&mut &mut self.stake_account.try_borrow_mut_data()?[..])?;
+
+        msg!("Migrated stake account for: {}", migrated.owner);
+        Ok(())
+    }
+}
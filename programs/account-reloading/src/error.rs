@@ -1,7 +1,13 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum ReloadError {
     #[msg("Counter value mismatch - stale data detected")]
     StaleData,
 }
+
+impl From<ReloadError> for solsec_errors::ErrorClass {
+    fn from(error: ReloadError) -> Self {
+        solsec_errors::ACCOUNT_RELOADING.class(error as usize)
+    }
+}
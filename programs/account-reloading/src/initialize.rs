@@ -2,7 +2,7 @@
 //!
 //! Shared initialization - not part of the vulnerability demonstration.
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::state::Counter;
 
@@ -14,7 +14,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 8 + 1,
+        space = 8 + Counter::INIT_SPACE,
         seeds = [b"counter", authority.key().as_ref()],
         bump
     )]
@@ -48,6 +48,7 @@ pub struct Increment<'info> {
 
 impl<'info> Increment<'info> {
     pub fn increment(&mut self) -> Result<()> {
+        // solsec-lint: allow(unchecked-arithmetic) overflow-checks is on in release
         self.counter.count += 1;
         Ok(())
     }
@@ -6,18 +6,29 @@
 //!
 //! VULNERABILITY: Using stale account data after CPI leads to incorrect logic.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("GBMScyniY2yFUdFQRyBQ9QyHD7qUmJQZmvwJvhbSavG9");
@@ -26,6 +37,13 @@ declare_id!("GBMScyniY2yFUdFQRyBQ9QyHD7qUmJQZmvwJvhbSavG9");
 pub mod account_reloading {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize a counter account
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps)
@@ -38,7 +56,9 @@ pub mod account_reloading {
 
     /// VULNERABLE: Double increment without reload
     /// After first CPI, counter.count is stale - second operation uses old value
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_double_increment(ctx: Context<VulnerableDoubleIncrement>) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.double_increment()
     }
 
@@ -47,3 +67,5 @@ pub mod account_reloading {
         ctx.accounts.double_increment()
     }
 }
+
+solsec_prelude::catalogued!(program::AccountReloading, ACCOUNT_RELOADING);
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::state::Counter;
 
@@ -27,10 +27,12 @@ impl<'info> SecureDoubleIncrement<'info> {
     /// SAFE: We reload after operations that may have modified the account.
     pub fn double_increment(&mut self) -> Result<()> {
         // First increment
+        // solsec-lint: allow(unchecked-arithmetic) overflow-checks is on in release
         self.counter.count += 1;
 
         // Simulate CPI (in real code: invoke() or CpiContext)
         // After CPI, account state on-chain is updated but our copy is stale
+        // solsec-lint: allow(unchecked-arithmetic) overflow-checks is on in release
         self.counter.count += 1;
 
         // SECURE: Reload the account to get fresh data
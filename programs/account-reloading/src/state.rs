@@ -1,8 +1,12 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
+// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
+#[derive(InitSpace)]
 pub struct Counter {
-    pub authority: Pubkey,
-    pub count: u64,
-    pub bump: u8,
+    pub authority: Pubkey, // bytes 8..40
+    pub count: u64,        // bytes 40..48
+    pub bump: u8,          // byte 48
 }
+
+const _: () = assert!(8 + Counter::INIT_SPACE == 49);
@@ -19,6 +19,7 @@ use pinocchio::{
 
 mod secure;
 mod state;
+#[cfg(feature = "vulnerable")]
 mod vulnerable;
 
 use state::{Admin, User, ADMIN_DISCRIMINATOR, USER_DISCRIMINATOR};
@@ -38,6 +39,7 @@ pub fn process_instruction(
 
     match instruction {
         // 0 = Vulnerable: Read user without discriminator check
+        #[cfg(feature = "vulnerable")]
         0 => vulnerable::process_action(program_id, accounts),
         // 1 = Secure: Read user WITH discriminator verification
         1 => secure::process_action(program_id, accounts),
@@ -130,3 +132,11 @@ fn init_admin(program_id: &Address, accounts: &[AccountView], data: &[u8]) -> Pr
 
     Ok(())
 }
+
+/// The program, for its registry entry
+pub struct AccountTypeMismatch;
+
+impl solsec_registry::Catalogued for AccountTypeMismatch {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::ACCOUNT_TYPE_MISMATCH;
+}
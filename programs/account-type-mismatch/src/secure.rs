@@ -11,6 +11,7 @@ use pinocchio::{
     error::{ProgramError, ProgramResult},
     AccountView, Address,
 };
+use solsec_guards::{require_owner, require_signer};
 
 /// SECURE: Read user data WITH type verification
 pub fn process_action(program_id: &Address, accounts: &[AccountView]) -> ProgramResult {
@@ -19,9 +20,7 @@ pub fn process_action(program_id: &Address, accounts: &[AccountView]) -> Program
     let caller = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
 
     // Verify owner
-    if unsafe { user_account.owner() } != program_id {
-        return Err(ProgramError::IllegalOwner);
-    }
+    require_owner!(user_account, program_id);
 
     let data = user_account.try_borrow()?;
     if data.len() < 41 {
@@ -50,6 +49,9 @@ pub fn process_action(program_id: &Address, accounts: &[AccountView]) -> Program
         return Err(ProgramError::InvalidAccountData);
     }
 
+    // The user this account belongs to, not just their key
+    require_signer!(caller);
+
     // SECURE: We verified this is actually a User account
     // balance is definitely User.balance, not Admin.permissions
 
@@ -4,7 +4,9 @@ use crate::state::Config;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
+    token_interface::{
+        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
 #[derive(Accounts)]
@@ -12,15 +14,15 @@ pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         seeds = [b"lp", config.key().as_ref()],
         bump = config.lp_bump
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     #[account(
         has_one = mint_x,
@@ -31,28 +33,29 @@ pub struct Deposit<'info> {
     pub config: Account<'info, Config>,
 
     #[account(mut)]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
 
     // VULN: No constraint checking these belong to user!
     // Attacker can pass victim's token accounts
     #[account(mut)]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_lp,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_lp: Account<'info, TokenAccount>,
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -72,28 +75,32 @@ pub fn handler(ctx: Context<Deposit>, amount: u64, max_x: u64, max_y: u64) -> Re
     // VULN: Transfers from user_x/user_y without verifying ownership
     // If attacker passes victim's accounts, this CPI will fail
     // But shows the pattern of missing validation
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_x.to_account_info(),
+                mint: ctx.accounts.mint_x.to_account_info(),
                 to: ctx.accounts.vault_x.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         x,
+        ctx.accounts.mint_x.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_y.to_account_info(),
+                mint: ctx.accounts.mint_y.to_account_info(),
                 to: ctx.accounts.vault_y.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         y,
+        ctx.accounts.mint_y.decimals,
     )?;
 
     let seeds = &[b"config".as_ref(), &[ctx.accounts.config.config_bump]];
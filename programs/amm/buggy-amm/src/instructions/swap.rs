@@ -2,7 +2,9 @@
 
 use crate::state::Config;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -10,19 +12,19 @@ pub struct Swap<'info> {
     pub user: Signer<'info>,
 
     #[account(mut)]
-    pub user_source: Account<'info, TokenAccount>,
+    pub user_source: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub user_destination: Account<'info, TokenAccount>,
+    pub user_destination: InterfaceAccount<'info, TokenAccount>,
 
-    pub source_mint: Account<'info, Mint>,
-    pub destination_mint: Account<'info, Mint>,
+    pub source_mint: InterfaceAccount<'info, Mint>,
+    pub destination_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
-    pub vault_source: Account<'info, TokenAccount>,
+    pub vault_source: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub vault_destination: Account<'info, TokenAccount>,
+    pub vault_destination: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         seeds = [b"config"],
@@ -30,7 +32,7 @@ pub struct Swap<'info> {
     )]
     pub config: Account<'info, Config>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
@@ -50,32 +52,36 @@ pub fn handler(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
     // Attacker can sandwich this transaction
 
     // Transfer in
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_source.to_account_info(),
+                mint: ctx.accounts.source_mint.to_account_info(),
                 to: ctx.accounts.vault_source.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         amount_in,
+        ctx.accounts.source_mint.decimals,
     )?;
 
     // Transfer out
     let seeds = &[b"config".as_ref(), &[ctx.accounts.config.config_bump]];
     let signer = &[&seeds[..]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_destination.to_account_info(),
+                mint: ctx.accounts.destination_mint.to_account_info(),
                 to: ctx.accounts.user_destination.to_account_info(),
                 authority: ctx.accounts.config.to_account_info(),
             },
             signer,
         ),
         amount_out,
+        ctx.accounts.destination_mint.decimals,
     )
 }
@@ -2,7 +2,9 @@
 
 use crate::state::Config;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -10,27 +12,27 @@ pub struct Withdraw<'info> {
     pub user: Signer<'info>,
 
     #[account(mut)]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub user_lp: Account<'info, TokenAccount>,
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
 
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     // VULN: No constraint checking vault ownership
     // Attacker can pass fake vaults
     #[account(mut)]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
 
     // VULN: No close constraint - account can be revived
     #[account(
@@ -40,7 +42,7 @@ pub struct Withdraw<'info> {
     )]
     pub config: Account<'info, Config>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<Withdraw>, lp_amount: u64) -> Result<()> {
@@ -67,31 +69,35 @@ pub fn handler(ctx: Context<Withdraw>, lp_amount: u64) -> Result<()> {
     let signer = &[&seeds[..]];
 
     // Transfer X
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_x.to_account_info(),
+                mint: ctx.accounts.mint_x.to_account_info(),
                 to: ctx.accounts.user_x.to_account_info(),
                 authority: ctx.accounts.config.to_account_info(),
             },
             signer,
         ),
         x_amount,
+        ctx.accounts.mint_x.decimals,
     )?;
 
     // Transfer Y
     // VULN: No data zeroing if this fully drains - revival possible
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_y.to_account_info(),
+                mint: ctx.accounts.mint_y.to_account_info(),
                 to: ctx.accounts.user_y.to_account_info(),
                 authority: ctx.accounts.config.to_account_info(),
             },
             signer,
         ),
         y_amount,
+        ctx.accounts.mint_y.decimals,
     )
 }
@@ -3,6 +3,9 @@
 //! This program intentionally contains security flaws to demonstrate
 //! common Solana vulnerabilities. DO NOT USE IN PRODUCTION.
 
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("buggy-amm has no secure instructions; it only builds with the `vulnerable` feature");
+
 use anchor_lang::prelude::*;
 
 pub mod instructions;
@@ -32,3 +35,8 @@ pub mod buggy_amm {
         instructions::withdraw::handler(ctx, lp_amount)
     }
 }
+
+impl solsec_registry::Catalogued for program::BuggyAmm {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::AMM;
+}
@@ -4,14 +4,18 @@ use anchor_lang::prelude::*;
 
 /// Pool configuration account
 /// VULN: No discriminator byte - can be confused with other account types
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
-    pub seed: u64,
-    pub mint_x: Pubkey,
-    pub mint_y: Pubkey,
-    pub fee: u16, // basis points
-    pub locked: bool,
-    pub config_bump: u8,
-    pub lp_bump: u8,
+    pub seed: u64,       // bytes 8..16
+    pub mint_x: Pubkey,  // bytes 16..48
+    pub mint_y: Pubkey,  // bytes 48..80
+    pub fee: u16,        // bytes 80..82, basis points
+    pub locked: bool,    // byte 82
+    pub config_bump: u8, // byte 83
+    pub lp_bump: u8,     // byte 84
 }
+
+const _: () = assert!(8 + Config::INIT_SPACE == 85);
@@ -4,7 +4,7 @@ use crate::state::Config;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -13,15 +13,15 @@ pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         seeds = [b"lp", config.key().as_ref()],
         bump = config.lp_bump
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     #[account(
         has_one = mint_x,
@@ -34,16 +34,18 @@ pub struct Deposit<'info> {
     #[account(
         mut,
         associated_token::mint = mint_x,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint_y,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
 
     // FIX: Verify token accounts belong to user
     #[account(
@@ -51,24 +53,26 @@ pub struct Deposit<'info> {
         constraint = user_x.owner == user.key(),
         constraint = user_x.mint == mint_x.key()
     )]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         constraint = user_y.owner == user.key(),
         constraint = user_y.mint == mint_y.key()
     )]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
 
+    // solsec-lint: allow(init-if-needed) the ATA address is fixed, re-creating it is a no-op
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_lp,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_lp: Account<'info, TokenAccount>,
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -80,63 +84,90 @@ pub fn handler(ctx: Context<Deposit>, amount: u64, max_x: u64, max_y: u64) -> Re
     let (x, y) = if ctx.accounts.mint_lp.supply == 0 {
         (max_x, max_y)
     } else {
-        // FIX: Checked math prevents overflow
-        let ratio = ctx
-            .accounts
-            .vault_x
-            .amount
-            .checked_div(ctx.accounts.vault_y.amount)
-            .ok_or(ErrorCode::MathError)?;
-        let y_needed = max_x.checked_div(ratio).ok_or(ErrorCode::MathError)?;
+        // FIX: Checked math prevents overflow; the depositor's share of y
+        // rounds up
+        let y_needed = solsec_math::mul_div_ceil(
+            max_x,
+            ctx.accounts.vault_y.amount,
+            ctx.accounts.vault_x.amount,
+        )
+        .map_err(|_| ErrorCode::MathError)?;
         (max_x, y_needed.min(max_y))
     };
 
-    require!(x <= max_x && y <= max_y, ErrorCode::SlippageExceeded);
-
-    // Transfer with verified signer
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_x.to_account_info(),
-                to: ctx.accounts.vault_x.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
-        x,
-    )?;
-
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_y.to_account_info(),
-                to: ctx.accounts.vault_y.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
-        y,
-    )?;
-
-    let seeds = &[
-        b"config".as_ref(),
-        &ctx.accounts.config.seed.to_le_bytes(),
-        &[ctx.accounts.config.config_bump],
-    ];
-    let signer = &[&seeds[..]];
-
-    mint_to(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.mint_lp.to_account_info(),
-                to: ctx.accounts.user_lp.to_account_info(),
-                authority: ctx.accounts.config.to_account_info(),
-            },
-            signer,
-        ),
-        amount,
-    )
+    solsec_math::slippage::check_max_in(x, max_x)
+        .and(solsec_math::slippage::check_max_in(y, max_y))
+        .map_err(|_| ErrorCode::SlippageExceeded)?;
+
+    AddLiquidity {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        user: ctx.accounts.user.to_account_info(),
+        mint_x: ctx.accounts.mint_x.to_account_info(),
+        mint_y: ctx.accounts.mint_y.to_account_info(),
+        vault_x: ctx.accounts.vault_x.to_account_info(),
+        vault_y: ctx.accounts.vault_y.to_account_info(),
+        user_x: ctx.accounts.user_x.to_account_info(),
+        user_y: ctx.accounts.user_y.to_account_info(),
+        mint_lp: ctx.accounts.mint_lp.to_account_info(),
+        user_lp: ctx.accounts.user_lp.to_account_info(),
+    }
+    .add(&ctx.accounts.config, x, y, amount)
+}
+
+/// The token movements of a deposit, shared with `initialize_and_deposit`
+pub(crate) struct AddLiquidity<'info> {
+    pub token_program: AccountInfo<'info>,
+    pub user: AccountInfo<'info>,
+    pub mint_x: AccountInfo<'info>,
+    pub mint_y: AccountInfo<'info>,
+    pub vault_x: AccountInfo<'info>,
+    pub vault_y: AccountInfo<'info>,
+    pub user_x: AccountInfo<'info>,
+    pub user_y: AccountInfo<'info>,
+    pub mint_lp: AccountInfo<'info>,
+    pub user_lp: AccountInfo<'info>,
+}
+
+impl<'info> AddLiquidity<'info> {
+    /// Move `x` and `y` from the user into the vaults and mint them `amount`
+    /// LP tokens, signed by `config`. Takes the accounts by value so the
+    /// mint CPI can move them rather than clone them.
+    pub fn add(self, config: &Account<'info, Config>, x: u64, y: u64, amount: u64) -> Result<()> {
+        // FIX: Checked transfers from the signer's own accounts into the vaults
+        solsec_token::transfer_checked_from_user(
+            &self.token_program,
+            &self.user_x,
+            &self.mint_x,
+            &self.vault_x,
+            &self.user,
+            x,
+        )?;
+        solsec_token::transfer_checked_from_user(
+            &self.token_program,
+            &self.user_y,
+            &self.mint_y,
+            &self.vault_y,
+            &self.user,
+            y,
+        )?;
+
+        let seeds = config.signer_seeds();
+        let seeds = seeds.signer().map_err(|_| ProgramError::InvalidSeeds)?;
+        let signer = &[&seeds[..]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program,
+                MintTo {
+                    mint: self.mint_lp,
+                    to: self.user_lp,
+                    authority: config.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )
+    }
 }
 
 #[error_code]
@@ -1,10 +1,10 @@
 //! Initialize - FIX: Strong PDA seeds with initializer + seed
 
-use crate::state::Config;
+use crate::state::{Config, CurveType};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -13,8 +13,11 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub initializer: Signer<'info>,
 
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+    // Either token program, but the same one for the whole pool
+    #[account(mint::token_program = token_program)]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
@@ -33,32 +36,35 @@ pub struct Initialize<'info> {
         bump,
         mint::decimals = 6,
         mint::authority = config,
+        mint::token_program = token_program,
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_x,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_y,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Initialize>, seed: u64, fee: u16) -> Result<()> {
-    require!(fee <= 10000, ErrorCode::InvalidFee);
+pub fn handler(ctx: Context<Initialize>, seed: u64, fee: u16, curve: CurveType) -> Result<()> {
+    check_pool(&ctx.accounts.mint_x, &ctx.accounts.mint_y, fee, curve)?;
 
     ctx.accounts.config.set_inner(Config {
         seed,
@@ -66,6 +72,7 @@ pub fn handler(ctx: Context<Initialize>, seed: u64, fee: u16) -> Result<()> {
         mint_x: ctx.accounts.mint_x.key(),
         mint_y: ctx.accounts.mint_y.key(),
         fee,
+        curve,
         locked: false,
         config_bump: ctx.bumps.config,
         lp_bump: ctx.bumps.mint_lp,
@@ -73,8 +80,28 @@ pub fn handler(ctx: Context<Initialize>, seed: u64, fee: u16) -> Result<()> {
     Ok(())
 }
 
+/// The checks on a new pool's parameters, shared with
+/// `initialize_and_deposit`.
+pub(crate) fn check_pool(
+    mint_x: &InterfaceAccount<Mint>,
+    mint_y: &InterfaceAccount<Mint>,
+    fee: u16,
+    curve: CurveType,
+) -> Result<()> {
+    require!(fee <= 10000, ErrorCode::InvalidFee);
+    if let CurveType::StableSwap { amp } = curve {
+        solsec_math::StableSwap::new(amp).map_err(|_| ErrorCode::InvalidCurve)?;
+    }
+    // FIX: No mint extension can change what a transfer into the vaults does
+    solsec_token::check_mint_extensions(&mint_x.to_account_info(), &[])?;
+    solsec_token::check_mint_extensions(&mint_y.to_account_info(), &[])?;
+    Ok(())
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Fee exceeds 100%")]
     InvalidFee,
+    #[msg("Amplification out of range")]
+    InvalidCurve,
 }
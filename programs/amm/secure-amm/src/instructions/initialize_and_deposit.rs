@@ -0,0 +1,135 @@
+//! Initialize and deposit - a new pool and its first liquidity in one
+//! instruction
+//!
+//! The same checks as `initialize` followed by `deposit` into the empty
+//! pool, so one transaction either opens a funded pool or changes nothing:
+//! a failed transfer also undoes the config, the vaults and the LP mint.
+
+use crate::instructions::deposit::{self, AddLiquidity};
+use crate::instructions::initialize::check_pool;
+use crate::state::{Config, CurveType};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct InitializeAndDeposit<'info> {
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    // Boxed: both instructions' accounts don't fit the stack frame together
+    #[account(mint::token_program = token_program)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"config", seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Config::INIT_SPACE,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"lp", config.key().as_ref()],
+        bump,
+        mint::decimals = 6,
+        mint::authority = config,
+        mint::token_program = token_program,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_x.owner == initializer.key(),
+        constraint = user_x.mint == mint_x.key()
+    )]
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_y.owner == initializer.key(),
+        constraint = user_y.mint == mint_y.key()
+    )]
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // The LP mint is new, so its ATA can't exist yet
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_lp,
+        associated_token::authority = initializer,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<InitializeAndDeposit>,
+    seed: u64,
+    fee: u16,
+    curve: CurveType,
+    amount: u64,
+    x: u64,
+    y: u64,
+) -> Result<()> {
+    check_pool(&ctx.accounts.mint_x, &ctx.accounts.mint_y, fee, curve)?;
+    require!(amount > 0, deposit::ErrorCode::ZeroAmount);
+
+    ctx.accounts.config.set_inner(Config {
+        seed,
+        authority: Some(ctx.accounts.initializer.key()),
+        mint_x: ctx.accounts.mint_x.key(),
+        mint_y: ctx.accounts.mint_y.key(),
+        fee,
+        curve,
+        locked: false,
+        config_bump: ctx.bumps.config,
+        lp_bump: ctx.bumps.mint_lp,
+    });
+
+    // The pool is empty, so the first deposit sets its price: all of `x`
+    // and `y` go in
+    AddLiquidity {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        user: ctx.accounts.initializer.to_account_info(),
+        mint_x: ctx.accounts.mint_x.to_account_info(),
+        mint_y: ctx.accounts.mint_y.to_account_info(),
+        vault_x: ctx.accounts.vault_x.to_account_info(),
+        vault_y: ctx.accounts.vault_y.to_account_info(),
+        user_x: ctx.accounts.user_x.to_account_info(),
+        user_y: ctx.accounts.user_y.to_account_info(),
+        mint_lp: ctx.accounts.mint_lp.to_account_info(),
+        user_lp: ctx.accounts.user_lp.to_account_info(),
+    }
+    .add(&ctx.accounts.config, x, y, amount)
+}
@@ -1,9 +1,11 @@
 pub mod deposit;
 pub mod initialize;
+pub mod initialize_and_deposit;
 pub mod swap;
 pub mod withdraw;
 
 pub use deposit::*;
 pub use initialize::*;
+pub use initialize_and_deposit::*;
 pub use swap::*;
 pub use withdraw::*;
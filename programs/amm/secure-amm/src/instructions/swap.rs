@@ -2,7 +2,9 @@
 
 use crate::state::Config;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -13,29 +15,29 @@ pub struct Swap<'info> {
         mut,
         constraint = user_source.owner == user.key()
     )]
-    pub user_source: Account<'info, TokenAccount>,
+    pub user_source: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         constraint = user_destination.owner == user.key()
     )]
-    pub user_destination: Account<'info, TokenAccount>,
+    pub user_destination: InterfaceAccount<'info, TokenAccount>,
 
-    pub source_mint: Account<'info, Mint>,
-    pub destination_mint: Account<'info, Mint>,
+    pub source_mint: InterfaceAccount<'info, Mint>,
+    pub destination_mint: InterfaceAccount<'info, Mint>,
 
     // FIX: Verify vault authority matches config PDA
     #[account(
         mut,
         constraint = vault_source.owner == config.key()
     )]
-    pub vault_source: Account<'info, TokenAccount>,
+    pub vault_source: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         constraint = vault_destination.owner == config.key()
     )]
-    pub vault_destination: Account<'info, TokenAccount>,
+    pub vault_destination: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         seeds = [b"config", config.seed.to_le_bytes().as_ref()],
@@ -43,7 +45,7 @@ pub struct Swap<'info> {
     )]
     pub config: Account<'info, Config>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
@@ -51,66 +53,72 @@ pub fn handler(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
     require!(min_out > 0, ErrorCode::ZeroAmount);
     require!(!ctx.accounts.config.locked, ErrorCode::PoolLocked);
 
-    let source_reserve = ctx.accounts.vault_source.amount as u128;
-    let dest_reserve = ctx.accounts.vault_destination.amount as u128;
-    let fee = ctx.accounts.config.fee as u128;
-
-    // FIX: All math uses checked operations
-    let fee_adjusted = (amount_in as u128)
-        .checked_mul(10000 - fee)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(ErrorCode::MathOverflow)?;
-
-    // Constant product: k = x * y
-    let k = source_reserve
-        .checked_mul(dest_reserve)
-        .ok_or(ErrorCode::MathOverflow)?;
-
-    let new_source = source_reserve
-        .checked_add(fee_adjusted)
-        .ok_or(ErrorCode::MathOverflow)?;
-
-    let new_dest = k.checked_div(new_source).ok_or(ErrorCode::MathOverflow)?;
+    // FIX: The mints are the pool's pair and the vaults are its own ATAs
+    let config = &ctx.accounts.config;
+    let (source, destination) = (
+        ctx.accounts.source_mint.key(),
+        ctx.accounts.destination_mint.key(),
+    );
+    require!(
+        (source, destination) == (config.mint_x, config.mint_y)
+            || (source, destination) == (config.mint_y, config.mint_x),
+        ErrorCode::InvalidMint
+    );
+    let seeds = config.signer_seeds();
+    solsec_token::check_vault(
+        &ctx.accounts.vault_source.to_account_info(),
+        &seeds,
+        &crate::ID,
+        &source,
+    )?;
+    solsec_token::check_vault(
+        &ctx.accounts.vault_destination.to_account_info(),
+        &seeds,
+        &crate::ID,
+        &destination,
+    )?;
 
-    let amount_out = dest_reserve
-        .checked_sub(new_dest)
-        .ok_or(ErrorCode::MathOverflow)? as u64;
+    // FIX: Checked math on the pool's curve, rounded in the pool's favor
+    let amount_out = solsec_math::after_fee(amount_in, u64::from(ctx.accounts.config.fee))
+        .and_then(|amount_in| {
+            ctx.accounts.config.curve.swap_out(
+                amount_in,
+                ctx.accounts.vault_source.amount,
+                ctx.accounts.vault_destination.amount,
+            )
+        })
+        .map_err(|_| ErrorCode::MathOverflow)?;
 
     // FIX: Slippage protection - revert if output too low
-    require!(amount_out >= min_out, ErrorCode::SlippageExceeded);
-
-    // Transfer tokens
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_source.to_account_info(),
-                to: ctx.accounts.vault_source.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
+    solsec_math::slippage::check_min_out(amount_out, min_out)
+        .map_err(|_| ErrorCode::SlippageExceeded)?;
+
+    // FIX: Checked transfer from the signer's own account of the input mint
+    solsec_token::transfer_checked_from_user(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.user_source.to_account_info(),
+        &ctx.accounts.source_mint.to_account_info(),
+        &ctx.accounts.vault_source.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
         amount_in,
     )?;
 
-    let seeds = &[
-        b"config".as_ref(),
-        &ctx.accounts.config.seed.to_le_bytes(),
-        &[ctx.accounts.config.config_bump],
-    ];
+    let seeds = seeds.signer().map_err(|_| ProgramError::InvalidSeeds)?;
     let signer = &[&seeds[..]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_destination.to_account_info(),
+                mint: ctx.accounts.destination_mint.to_account_info(),
                 to: ctx.accounts.user_destination.to_account_info(),
                 authority: ctx.accounts.config.to_account_info(),
             },
             signer,
         ),
         amount_out,
+        ctx.accounts.destination_mint.decimals,
     )
 }
 
@@ -124,4 +132,6 @@ pub enum ErrorCode {
     MathOverflow,
     #[msg("Slippage tolerance exceeded")]
     SlippageExceeded,
+    #[msg("Mints are not the pool's pair")]
+    InvalidMint,
 }
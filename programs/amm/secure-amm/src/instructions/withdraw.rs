@@ -2,7 +2,9 @@
 
 use crate::state::Config;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -14,49 +16,51 @@ pub struct Withdraw<'info> {
         constraint = user_x.owner == user.key(),
         constraint = user_x.mint == config.mint_x
     )]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         constraint = user_y.owner == user.key(),
         constraint = user_y.mint == config.mint_y
     )]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         constraint = user_lp.owner == user.key(),
         constraint = user_lp.mint == mint_lp.key()
     )]
-    pub user_lp: Account<'info, TokenAccount>,
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
 
     #[account(address = config.mint_x)]
-    pub mint_x: Account<'info, Mint>,
+    pub mint_x: InterfaceAccount<'info, Mint>,
 
     #[account(address = config.mint_y)]
-    pub mint_y: Account<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         seeds = [b"lp", config.key().as_ref()],
         bump = config.lp_bump,
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     // FIX: Verify vaults belong to this config
     #[account(
         mut,
         associated_token::mint = mint_x,
         associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint_y,
         associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         seeds = [b"config", config.seed.to_le_bytes().as_ref()],
@@ -64,31 +68,25 @@ pub struct Withdraw<'info> {
     )]
     pub config: Account<'info, Config>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<Withdraw>, lp_amount: u64, min_x: u64, min_y: u64) -> Result<()> {
     require!(lp_amount > 0, ErrorCode::ZeroAmount);
 
-    let lp_supply = ctx.accounts.mint_lp.supply as u128;
+    let lp_supply = ctx.accounts.mint_lp.supply;
     require!(lp_supply > 0, ErrorCode::NoLiquidity);
 
     // FIX: Checked math for proportional calculation
-    let x_amount = (ctx.accounts.vault_x.amount as u128)
-        .checked_mul(lp_amount as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(lp_supply)
-        .ok_or(ErrorCode::MathOverflow)? as u64;
-
-    let y_amount = (ctx.accounts.vault_y.amount as u128)
-        .checked_mul(lp_amount as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(lp_supply)
-        .ok_or(ErrorCode::MathOverflow)? as u64;
+    let x_amount = solsec_math::pro_rata(ctx.accounts.vault_x.amount, lp_amount, lp_supply)
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let y_amount = solsec_math::pro_rata(ctx.accounts.vault_y.amount, lp_amount, lp_supply)
+        .map_err(|_| ErrorCode::MathOverflow)?;
 
     // FIX: Slippage protection
-    require!(x_amount >= min_x, ErrorCode::SlippageExceeded);
-    require!(y_amount >= min_y, ErrorCode::SlippageExceeded);
+    solsec_math::slippage::check_min_out(x_amount, min_x)
+        .and(solsec_math::slippage::check_min_out(y_amount, min_y))
+        .map_err(|_| ErrorCode::SlippageExceeded)?;
 
     // Burn LP tokens first
     burn(
@@ -103,37 +101,38 @@ pub fn handler(ctx: Context<Withdraw>, lp_amount: u64, min_x: u64, min_y: u64) -
         lp_amount,
     )?;
 
-    let seeds = &[
-        b"config".as_ref(),
-        &ctx.accounts.config.seed.to_le_bytes(),
-        &[ctx.accounts.config.config_bump],
-    ];
+    let seeds = ctx.accounts.config.signer_seeds();
+    let seeds = seeds.signer().map_err(|_| ProgramError::InvalidSeeds)?;
     let signer = &[&seeds[..]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_x.to_account_info(),
+                mint: ctx.accounts.mint_x.to_account_info(),
                 to: ctx.accounts.user_x.to_account_info(),
                 authority: ctx.accounts.config.to_account_info(),
             },
             signer,
         ),
         x_amount,
+        ctx.accounts.mint_x.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_y.to_account_info(),
+                mint: ctx.accounts.mint_y.to_account_info(),
                 to: ctx.accounts.user_y.to_account_info(),
                 authority: ctx.accounts.config.to_account_info(),
             },
             signer,
         ),
         y_amount,
+        ctx.accounts.mint_y.decimals,
     )
 }
 
@@ -8,6 +8,7 @@ pub mod instructions;
 pub mod state;
 
 use instructions::*;
+use state::CurveType;
 
 declare_id!("SecureAMM1111111111111111111111111111111111");
 
@@ -15,14 +16,33 @@ declare_id!("SecureAMM1111111111111111111111111111111111");
 pub mod secure_amm {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, seed: u64, fee: u16) -> Result<()> {
-        instructions::initialize::handler(ctx, seed, fee)
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        seed: u64,
+        fee: u16,
+        curve: CurveType,
+    ) -> Result<()> {
+        instructions::initialize::handler(ctx, seed, fee, curve)
     }
 
     pub fn deposit(ctx: Context<Deposit>, amount: u64, max_x: u64, max_y: u64) -> Result<()> {
         instructions::deposit::handler(ctx, amount, max_x, max_y)
     }
 
+    /// `initialize` and a first `deposit` of `x` and `y` for `amount` LP
+    /// tokens, in one instruction
+    pub fn initialize_and_deposit(
+        ctx: Context<InitializeAndDeposit>,
+        seed: u64,
+        fee: u16,
+        curve: CurveType,
+        amount: u64,
+        x: u64,
+        y: u64,
+    ) -> Result<()> {
+        instructions::initialize_and_deposit::handler(ctx, seed, fee, curve, amount, x, y)
+    }
+
     pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
         instructions::swap::handler(ctx, amount_in, min_out)
     }
@@ -31,3 +51,8 @@ pub mod secure_amm {
         instructions::withdraw::handler(ctx, lp_amount, min_x, min_y)
     }
 }
+
+impl solsec_registry::Catalogued for program::SecureAmm {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::AMM;
+}
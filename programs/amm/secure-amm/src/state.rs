@@ -1,17 +1,64 @@
 //! State with proper discriminator (Anchor handles this automatically)
 
 use anchor_lang::prelude::*;
+use solsec_math::{ConstantProduct, Curve, StableSwap};
+use solsec_pda::Seeds;
 
 /// Pool configuration - Anchor's #[account] macro adds 8-byte discriminator
+///
+/// Offsets are into the account data, after the discriminator, for a pool
+/// with an authority on the StableSwap curve. Borsh writes `None` and
+/// `ConstantProduct` as a single byte, moving every later field back 32 and
+/// 8 bytes; INIT_SPACE counts the largest case.
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
-    pub seed: u64,
-    pub authority: Option<Pubkey>, // FIX: Optional admin for updates
-    pub mint_x: Pubkey,
-    pub mint_y: Pubkey,
-    pub fee: u16,
-    pub locked: bool,
-    pub config_bump: u8,
-    pub lp_bump: u8,
+    pub seed: u64,                 // bytes 8..16
+    pub authority: Option<Pubkey>, // bytes 16..49 - FIX: Optional admin for updates
+    pub mint_x: Pubkey,            // bytes 49..81
+    pub mint_y: Pubkey,            // bytes 81..113
+    pub fee: u16,                  // bytes 113..115
+    pub curve: CurveType,          // bytes 115..124 - FIX: Curve chosen once, at initialization
+    pub locked: bool,              // byte 124
+    pub config_bump: u8,           // byte 125
+    pub lp_bump: u8,               // byte 126
+}
+
+const _: () = assert!(8 + Config::INIT_SPACE == 127);
+
+impl Config {
+    /// The seeds the config PDA signs vault transfers and LP mints with:
+    /// `[b"config", seed, config_bump]`, the stored canonical bump.
+    pub fn signer_seeds(&self) -> Seeds<'_> {
+        Seeds::new("config").nonce(self.seed).bump(self.config_bump)
+    }
+}
+
+/// Which curve a pool prices its swaps on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    /// `x * y = k`, for pairs whose prices move independently
+    ConstantProduct,
+    /// StableSwap with amplification `amp`, for pairs that trade near one
+    /// for one
+    StableSwap { amp: u64 },
+}
+
+impl CurveType {
+    /// Output of `amount_in`, already net of the fee, against the vaults
+    pub fn swap_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> solsec_math::Result<u64> {
+        match *self {
+            CurveType::ConstantProduct => {
+                ConstantProduct.swap_out(amount_in, reserve_in, reserve_out)
+            }
+            CurveType::StableSwap { amp } => {
+                StableSwap::new(amp)?.swap_out(amount_in, reserve_in, reserve_out)
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum PoolError {
@@ -14,3 +14,9 @@ pub enum PoolError {
     #[msg("Insufficient reserves in pool")]
     InsufficientReserves,
 }
+
+impl From<PoolError> for solsec_errors::ErrorClass {
+    fn from(error: PoolError) -> Self {
+        solsec_errors::ARITHMETIC_OVERFLOW.class(error as usize)
+    }
+}
@@ -1,15 +1,26 @@
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("Ar1thM3t1c111111111111111111111111111111111");
@@ -18,6 +29,13 @@ declare_id!("Ar1thM3t1c111111111111111111111111111111111");
 pub mod arithmetic_overflow {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize a new liquidity pool.
     pub fn initialize(
         ctx: Context<Initialize>,
@@ -31,11 +49,13 @@ pub mod arithmetic_overflow {
 
     /// VULNERABLE: Swap X for Y with vulnerable arithmetic.
     /// Demonstrates overflow and precision loss.
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_swap(
         ctx: Context<VulnerableSwap>,
         amount_in: u64,
         min_out: u64,
     ) -> Result<u64> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.swap_x_for_y(amount_in, min_out)
     }
 
@@ -45,3 +65,5 @@ pub mod arithmetic_overflow {
         ctx.accounts.swap_x_for_y(amount_in, min_out)
     }
 }
+
+solsec_prelude::catalogued!(program::ArithmeticOverflow, ARITHMETIC_OVERFLOW);
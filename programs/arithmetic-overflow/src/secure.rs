@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::error::PoolError;
 use crate::state::Pool;
@@ -54,7 +54,8 @@ impl<'info> SecureSwap<'info> {
         let amount_out = u64::try_from(amount_out_u128).map_err(|_| PoolError::MathOverflow)?;
 
         // SECURE: Slippage protection
-        require!(amount_out >= min_out, PoolError::SlippageExceeded);
+        solsec_math::slippage::check_min_out(amount_out, min_out)
+            .map_err(|_| PoolError::SlippageExceeded)?;
         require!(amount_out <= reserve_y, PoolError::InsufficientReserves);
 
         // Update reserves with checked math
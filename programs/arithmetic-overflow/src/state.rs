@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 // ---------------------------------------------------------------------------
 // Pool State
@@ -6,12 +6,15 @@ use anchor_lang::prelude::*;
 // Simple AMM pool with two token reserves.
 // ---------------------------------------------------------------------------
 
+// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace)]
 pub struct Pool {
-    pub authority: Pubkey,
-    pub reserve_x: u64,
-    pub reserve_y: u64,
-    pub fee_bps: u16, // Fee in basis points (100 = 1%)
-    pub bump: u8,
+    pub authority: Pubkey, // bytes 8..40
+    pub reserve_x: u64,    // bytes 40..48
+    pub reserve_y: u64,    // bytes 48..56
+    pub fee_bps: u16,      // bytes 56..58, fee in basis points (100 = 1%)
+    pub bump: u8,          // byte 58
 }
+
+const _: () = assert!(8 + Pool::INIT_SPACE == 59);
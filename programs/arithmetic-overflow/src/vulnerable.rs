@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
 
 use crate::state::Pool;
 
@@ -21,6 +24,10 @@ pub struct VulnerableSwap<'info> {
         bump = pool.bump,
     )]
     pub pool: Account<'info, Pool>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableSwap<'info> {
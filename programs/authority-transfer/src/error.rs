@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum AuthError {
@@ -9,3 +9,9 @@ pub enum AuthError {
     #[msg("Cannot transfer to zero address")]
     ZeroAddress,
 }
+
+impl From<AuthError> for solsec_errors::ErrorClass {
+    fn from(error: AuthError) -> Self {
+        solsec_errors::AUTHORITY_TRANSFER.class(error as usize)
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Shared initialization - not part of the vulnerability demonstration.
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::state::AuthConfig;
 
@@ -14,7 +14,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 33 + 1, // discriminator + authority + Option<Pubkey> + bump
+        space = 8 + AuthConfig::INIT_SPACE,
         seeds = [b"config"],
         bump
     )]
@@ -6,18 +6,29 @@
 //! VULNERABILITY: Missing validation in authority transfer allows attackers
 //! to take control of protocol.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("3APkTen4wwfvKAyjrwxCaCWVKEkwagJd5cXwJeFkefVS");
@@ -26,6 +37,13 @@ declare_id!("3APkTen4wwfvKAyjrwxCaCWVKEkwagJd5cXwJeFkefVS");
 pub mod authority_transfer {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize config with initial authority
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps)
@@ -33,22 +51,32 @@ pub mod authority_transfer {
 
     /// VULNERABLE: Direct authority transfer - no confirmation required
     /// Attacker can set authority to their address if they exploit another bug
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_transfer(
         ctx: Context<VulnerableTransfer>,
         new_authority: Pubkey,
     ) -> Result<()> {
-        ctx.accounts.transfer(new_authority)
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        let event = ctx.accounts.transfer(new_authority)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     /// SECURE: Two-step authority transfer
     /// Step 1: Current authority proposes new authority
     pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
-        ctx.accounts.propose(new_authority)
+        let event = ctx.accounts.propose(new_authority)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     /// SECURE: Two-step authority transfer
     /// Step 2: New authority must accept (proves they control the key)
     pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
-        ctx.accounts.accept()
+        let event = ctx.accounts.accept()?;
+        emit_cpi!(event);
+        Ok(())
     }
 }
+
+solsec_prelude::catalogued!(program::AuthorityTransfer, AUTHORITY_TRANSFER);
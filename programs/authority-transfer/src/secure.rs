@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::error::AuthError;
 use crate::state::AuthConfig;
@@ -12,6 +12,7 @@ use crate::state::AuthConfig;
 // 3. Typos or attacks don't result in permanent lockout
 // ---------------------------------------------------------------------------
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ProposeAuthority<'info> {
     pub authority: Signer<'info>,
@@ -28,20 +29,30 @@ pub struct ProposeAuthority<'info> {
 impl<'info> ProposeAuthority<'info> {
     /// Step 1: Propose a new authority.
     /// SAFE: This doesn't actually transfer - just marks pending.
-    pub fn propose(&mut self, new_authority: Pubkey) -> Result<()> {
+    pub fn propose(&mut self, new_authority: Pubkey) -> Result<SecurityEvent> {
         // SECURE: Check for zero address
         require!(new_authority != Pubkey::default(), AuthError::ZeroAddress);
 
         // Set pending, don't transfer yet
         self.config.pending_authority = Some(new_authority);
 
-        msg!("Authority transfer proposed to: {}", new_authority);
+        // The event carries the keys; formatting them here would cost a
+        // base58 encoding each
+        msg!("Authority transfer proposed");
         msg!("New authority must call accept_authority to complete transfer");
 
-        Ok(())
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::AuthorityProposed {
+                config: self.config.key().to_bytes(),
+                authority: self.authority.key().to_bytes(),
+                proposed: new_authority.to_bytes(),
+            },
+        ))
     }
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AcceptAuthority<'info> {
     /// The pending authority must sign to accept
@@ -58,7 +69,7 @@ pub struct AcceptAuthority<'info> {
 impl<'info> AcceptAuthority<'info> {
     /// Step 2: Accept authority transfer.
     /// SAFE: Only the pending authority can complete the transfer.
-    pub fn accept(&mut self) -> Result<()> {
+    pub fn accept(&mut self) -> Result<SecurityEvent> {
         // SECURE: Verify there's a pending transfer
         let pending = self
             .config
@@ -72,12 +83,19 @@ impl<'info> AcceptAuthority<'info> {
         );
 
         // Now safe to transfer
+        let previous = self.config.authority;
         self.config.authority = pending;
         self.config.pending_authority = None;
 
         msg!("Authority transfer accepted and completed");
-        msg!("New authority: {}", self.config.authority);
 
-        Ok(())
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::AuthorityChanged {
+                config: self.config.key().to_bytes(),
+                previous: previous.to_bytes(),
+                current: pending.to_bytes(),
+            },
+        ))
     }
 }
@@ -1,8 +1,14 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
+// Offsets are into the account data, after the 8-byte discriminator.
+// Borsh writes `None` as a single byte, so `bump` sits at 41 while no
+// transfer is pending; INIT_SPACE counts the `Some` case.
 #[account]
+#[derive(InitSpace)]
 pub struct AuthConfig {
-    pub authority: Pubkey,
-    pub pending_authority: Option<Pubkey>,
-    pub bump: u8,
+    pub authority: Pubkey,                 // bytes 8..40
+    pub pending_authority: Option<Pubkey>, // tag at 40, key at 41..73 if `Some`
+    pub bump: u8,                          // byte 73, or 41 if `None`
 }
+
+const _: () = assert!(8 + AuthConfig::INIT_SPACE == 74);
@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
 
 use crate::state::AuthConfig;
 
@@ -10,6 +13,7 @@ use crate::state::AuthConfig;
 // Also vulnerable to typos - wrong address = lost forever.
 // ---------------------------------------------------------------------------
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct VulnerableTransfer<'info> {
     pub authority: Signer<'info>,
@@ -21,12 +25,16 @@ pub struct VulnerableTransfer<'info> {
         has_one = authority
     )]
     pub config: Account<'info, AuthConfig>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableTransfer<'info> {
     /// Transfer authority in one step.
     /// DANGER: No confirmation, no recovery, irreversible!
-    pub fn transfer(&mut self, new_authority: Pubkey) -> Result<()> {
+    pub fn transfer(&mut self, new_authority: Pubkey) -> Result<SecurityEvent> {
         // VULNERABLE: Immediate transfer - no verification that new_authority
         // is a valid address or that recipient can accept
 
@@ -35,6 +43,7 @@ impl<'info> VulnerableTransfer<'info> {
         // No timelock
         // No multi-sig requirement
 
+        let previous = self.config.authority;
         self.config.authority = new_authority;
 
         msg!(
@@ -43,6 +52,14 @@ impl<'info> VulnerableTransfer<'info> {
         );
         msg!("If this was a mistake or attack, there's NO WAY TO RECOVER!");
 
-        Ok(())
+        // At least a monitor sees it happen
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::AuthorityChanged {
+                config: self.config.key().to_bytes(),
+                previous: previous.to_bytes(),
+                current: new_authority.to_bytes(),
+            },
+        ))
     }
 }
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum TransferError {
@@ -7,3 +7,9 @@ pub enum TransferError {
     #[msg("Cannot transfer to same account")]
     DuplicateAccounts,
 }
+
+impl From<TransferError> for solsec_errors::ErrorClass {
+    fn from(error: TransferError) -> Self {
+        solsec_errors::DUPLICATE_ACCOUNTS.class(error as usize)
+    }
+}
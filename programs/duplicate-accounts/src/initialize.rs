@@ -2,7 +2,7 @@
 //!
 //! Shared initialization - not part of the vulnerability demonstration.
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::state::UserBalance;
 
@@ -14,7 +14,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + 32 + 8 + 1,
+        space = 8 + UserBalance::INIT_SPACE,
         seeds = [b"balance", user.key().as_ref()],
         bump
     )]
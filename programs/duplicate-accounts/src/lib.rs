@@ -6,18 +6,29 @@
 //! VULNERABILITY: If from_account and to_account are the same,
 //! credits and debits cancel out unexpectedly.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("BgxHghQVFFavSL6VBpJ6eoKec1yBoAYVS24EfGXHXxTz");
@@ -26,6 +37,13 @@ declare_id!("BgxHghQVFFavSL6VBpJ6eoKec1yBoAYVS24EfGXHXxTz");
 pub mod duplicate_accounts {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize a user balance account
     pub fn initialize(ctx: Context<Initialize>, initial_balance: u64) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps, initial_balance)
@@ -33,7 +51,9 @@ pub mod duplicate_accounts {
 
     /// VULNERABLE: Transfer between accounts without duplicate check
     /// If from == to, balance stays the same but event shows transfer happened
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_transfer(ctx: Context<VulnerableTransfer>, amount: u64) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.transfer(amount)
     }
 
@@ -42,3 +62,5 @@ pub mod duplicate_accounts {
         ctx.accounts.transfer(amount)
     }
 }
+
+solsec_prelude::catalogued!(program::DuplicateAccounts, DUPLICATE_ACCOUNTS);
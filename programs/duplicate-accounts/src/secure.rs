@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::error::TransferError;
 use crate::state::UserBalance;
@@ -1,8 +1,12 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
+// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
+#[derive(InitSpace)]
 pub struct UserBalance {
-    pub owner: Pubkey,
-    pub balance: u64,
-    pub bump: u8,
+    pub owner: Pubkey, // bytes 8..40
+    pub balance: u64,  // bytes 40..48
+    pub bump: u8,      // byte 48
 }
+
+const _: () = assert!(8 + UserBalance::INIT_SPACE == 49);
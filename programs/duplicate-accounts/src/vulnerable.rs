@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
 
 use crate::error::TransferError;
 use crate::state::UserBalance;
@@ -27,6 +30,10 @@ pub struct VulnerableTransfer<'info> {
     #[account(mut)]
     /// CHECK: VULNERABLE - No constraint preventing this from being same as from_account
     pub to_account: Account<'info, UserBalance>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableTransfer<'info> {
@@ -0,0 +1,17 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum WithdrawError {
+    #[msg("Unauthorized - not the vault owner")]
+    Unauthorized,
+    #[msg("Authorization was for another sequence")]
+    StaleSequence,
+    #[msg("Authorization has expired")]
+    AuthorizationExpired,
+}
+
+impl From<WithdrawError> for solsec_errors::ErrorClass {
+    fn from(error: WithdrawError) -> Self {
+        solsec_errors::DURABLE_NONCE.class(error as usize)
+    }
+}
@@ -0,0 +1,33 @@
+//! Initialize instruction for durable-nonce program
+//!
+//! Shared initialization - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::state::Vault;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        self.vault.owner = self.owner.key();
+        self.vault.sequence = 0;
+        self.vault.bump = bumps.vault;
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Durable Nonce Replay - Anchor Program
+//!
+//! Demonstrates how a withdrawal authorized by nothing but the owner's
+//! signature can be held and sent long after the owner believes it expired.
+//! A transaction that advances a durable nonce carries the nonce in place of
+//! a recent blockhash, so it never expires on its own: whoever holds it can
+//! land it until the nonce is advanced.
+//!
+//! VULNERABILITY: the program trusts the signature alone, with no expiry and
+//! no record of which authorizations it already executed.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use initialize::*;
+use secure::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("HgpHtAcgneUhGHfRywnqRvgUaQ6aQfr3QJQnAK7fnNuT");
+
+#[program]
+pub mod durable_nonce {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// Initialize the signer's vault, at sequence 0
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps)
+    }
+
+    /// VULNERABLE: Withdraw on the owner's signature, whenever it arrives
+    /// and however often
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_withdraw(ctx: Context<VulnerableWithdraw>, amount: u64) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        let event = ctx.accounts.withdraw(amount)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// SECURE: Withdraw only as the vault's next `sequence`, and no later than
+    /// slot `expires_at`
+    pub fn secure_withdraw(
+        ctx: Context<SecureWithdraw>,
+        amount: u64,
+        sequence: u64,
+        expires_at: u64,
+    ) -> Result<()> {
+        let event = ctx.accounts.withdraw(amount, sequence, expires_at)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// SECURE: Invalidate every authorization signed for the current
+    /// `sequence`, without withdrawing
+    pub fn secure_revoke(ctx: Context<SecureRevoke>, sequence: u64) -> Result<()> {
+        ctx.accounts.revoke(sequence)
+    }
+}
+
+solsec_prelude::catalogued!(program::DurableNonce, DURABLE_NONCE);
@@ -0,0 +1,102 @@
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::WithdrawError;
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// SECURE: Sequence and Expiry Tracked in Program State
+// ---------------------------------------------------------------------------
+// FIX: Each withdrawal names the vault's next sequence and the last slot it
+// may land in. Executing it moves the sequence on, so the first of two
+// authorizations for the same sequence to land is the only one that pays,
+// and one held past its slot pays nothing. The owner can move the sequence
+// on without paying, to cancel an authorization they no longer trust.
+// ---------------------------------------------------------------------------
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SecureWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ WithdrawError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub owner: Signer<'info>,
+
+    // solsec-lint: allow(unchecked-account) any account may receive lamports
+    /// CHECK: Destination for lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+impl<'info> SecureWithdraw<'info> {
+    /// Pay `amount` to the destination, as the vault's `sequence`, before
+    /// slot `expires_at` has passed.
+    pub fn withdraw(
+        &mut self,
+        amount: u64,
+        sequence: u64,
+        expires_at: u64,
+    ) -> Result<SecurityEvent> {
+        // SECURE: A durable nonce keeps the transaction alive; the slot
+        // the owner signed for doesn't
+        require!(
+            Clock::get()?.slot <= expires_at,
+            WithdrawError::AuthorizationExpired
+        );
+        // SECURE: One payment per sequence, whichever copy lands first
+        consume(&mut self.vault, sequence)?;
+
+        transfer_lamports(
+            &self.vault.to_account_info(),
+            &self.destination.to_account_info(),
+            amount,
+        )?;
+
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::Withdrawal {
+                vault: self.vault.key().to_bytes(),
+                authority: self.owner.key().to_bytes(),
+                destination: self.destination.key().to_bytes(),
+                amount,
+            },
+        ))
+    }
+}
+
+/// Cancel what the owner signed for the vault's current sequence
+#[derive(Accounts)]
+pub struct SecureRevoke<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ WithdrawError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub owner: Signer<'info>,
+}
+
+impl<'info> SecureRevoke<'info> {
+    pub fn revoke(&mut self, sequence: u64) -> Result<()> {
+        consume(&mut self.vault, sequence)?;
+        msg!("Revoked sequence");
+        sol_log_data(&[&sequence.to_le_bytes()]);
+        Ok(())
+    }
+}
+
+// Move the vault past `sequence`, if that is the one it expects
+fn consume(vault: &mut Vault, sequence: u64) -> Result<()> {
+    require!(vault.sequence == sequence, WithdrawError::StaleSequence);
+    vault.sequence = sequence
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
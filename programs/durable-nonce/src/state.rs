@@ -0,0 +1,18 @@
+use solsec_prelude::*;
+
+/// A vault only its owner withdraws from
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    /// Signs every withdrawal, bytes 8..40
+    pub owner: Pubkey,
+    /// The sequence the next secure withdrawal must name; each one the
+    /// vault executes, or the owner revokes, moves it on, bytes 40..48
+    pub sequence: u64,
+    /// Bump seed for the vault PDA, byte 48
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Vault::INIT_SPACE == 49);
@@ -0,0 +1,62 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::error::WithdrawError;
+use crate::state::Vault;
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Authorization Without Expiry or Replay Protection
+// ---------------------------------------------------------------------------
+// The owner's signature is the whole authorization. A transaction signed
+// with a durable nonce doesn't expire with its blockhash, so a relayer that
+// holds it can land it any time until the nonce is advanced - after the
+// owner, believing it expired, has signed and sent the same payment again.
+// ---------------------------------------------------------------------------
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VulnerableWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = vault.bump,
+        constraint = vault.owner == owner.key() @ WithdrawError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub owner: Signer<'info>,
+
+    // solsec-lint: allow(unchecked-account) any account may receive lamports
+    /// CHECK: Destination for lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableWithdraw<'info> {
+    /// Pay `amount` to the destination.
+    /// DANGER: The same signed withdrawal pays every time it lands, however
+    /// late
+    pub fn withdraw(&mut self, amount: u64) -> Result<SecurityEvent> {
+        transfer_lamports(
+            &self.vault.to_account_info(),
+            &self.destination.to_account_info(),
+            amount,
+        )?;
+
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::Withdrawal {
+                vault: self.vault.key().to_bytes(),
+                authority: self.owner.key().to_bytes(),
+                destination: self.destination.key().to_bytes(),
+                amount,
+            },
+        ))
+    }
+}
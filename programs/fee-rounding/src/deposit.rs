@@ -0,0 +1,49 @@
+//! Deposit instruction for fee-rounding program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::error::FeeError;
+use crate::state::{Pool, Position};
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Deposit<'info> {
+    /// Move `amount` lamports into the pool and credit them to the position.
+    pub fn deposit(&mut self, bumps: &DepositBumps, amount: u64) -> Result<()> {
+        require!(amount > 0, FeeError::InvalidAmount);
+
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: self.owner.to_account_info(),
+                to: self.pool.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+        self.position.owner = self.owner.key();
+        self.position.balance = amount;
+        self.position.bump = bumps.position;
+        Ok(())
+    }
+}
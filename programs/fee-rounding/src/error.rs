@@ -0,0 +1,21 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum FeeError {
+    #[msg("Unauthorized - not the position owner")]
+    Unauthorized,
+    #[msg("Fee above 10000 basis points")]
+    InvalidFee,
+    #[msg("Invalid amount: cannot be zero")]
+    InvalidAmount,
+    #[msg("Withdrawal exceeds the position's balance")]
+    InsufficientBalance,
+    #[msg("Math overflow detected")]
+    MathOverflow,
+}
+
+impl From<FeeError> for solsec_errors::ErrorClass {
+    fn from(error: FeeError) -> Self {
+        solsec_errors::FEE_ROUNDING.class(error as usize)
+    }
+}
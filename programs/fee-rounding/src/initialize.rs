@@ -0,0 +1,39 @@
+//! Initialize instruction for fee-rounding program
+//!
+//! Shared initialization - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::error::FeeError;
+use crate::state::Pool;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool"],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps, fee_bps: u16) -> Result<()> {
+        require!(
+            fee_bps as u64 <= solsec_math::BPS_DENOMINATOR,
+            FeeError::InvalidFee
+        );
+        self.pool.authority = self.authority.key();
+        self.pool.fee_bps = fee_bps;
+        self.pool.fees_collected = 0;
+        self.pool.bump = bumps.pool;
+        Ok(())
+    }
+}
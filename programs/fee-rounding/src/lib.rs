@@ -0,0 +1,74 @@
+//! Fee Rounding Dust - Anchor Program
+//!
+//! Demonstrates a withdrawal fee taken in basis points and rounded down.
+//! Every withdrawal small enough that `amount * fee_bps` is below 10000
+//! pays no fee at all, so a user who splits one large withdrawal into many
+//! small ones, even inside a single transaction, withdraws everything fee
+//! free.
+//!
+//! VULNERABILITY: the fee is floored, in the user's favour, per withdrawal.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod deposit;
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use deposit::*;
+use initialize::*;
+use secure::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("7pcpgeWcVc73NYPkRS6bhZfFH75vWHkTxDXAxHwJESjF");
+
+#[program]
+pub mod fee_rounding {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// Initialize the pool, charging `fee_bps` on every withdrawal
+    pub fn initialize(ctx: Context<Initialize>, fee_bps: u16) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps, fee_bps)
+    }
+
+    /// Open the signer's position with `amount` lamports
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit(&ctx.bumps, amount)
+    }
+
+    /// VULNERABLE: Withdraw with the fee rounded down
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_withdraw(ctx: Context<VulnerableWithdraw>, amount: u64) -> Result<u64> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.withdraw(amount)
+    }
+
+    /// SECURE: Withdraw with the fee rounded up
+    pub fn secure_withdraw(ctx: Context<SecureWithdraw>, amount: u64) -> Result<u64> {
+        ctx.accounts.withdraw(amount)
+    }
+}
+
+solsec_prelude::catalogued!(program::FeeRounding, FEE_ROUNDING);
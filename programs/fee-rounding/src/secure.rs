@@ -0,0 +1,66 @@
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::FeeError;
+use crate::state::{Pool, Position};
+
+// ---------------------------------------------------------------------------
+// SECURE: Fee Rounded Up
+// ---------------------------------------------------------------------------
+// FIX: Round the fee up, in the pool's favour. Any non-zero withdrawal at a
+// non-zero rate then pays at least one lamport, and since rounding up never
+// charges less than the exact share, the fees on the pieces of a split
+// withdrawal add up to at least the fee on the whole.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SecureWithdraw<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        constraint = position.owner == owner.key() @ FeeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+}
+
+impl<'info> SecureWithdraw<'info> {
+    /// Take `amount` from the position, paying the owner all of it but the
+    /// fee. Returns the fee.
+    pub fn withdraw(&mut self, amount: u64) -> Result<u64> {
+        require!(amount > 0, FeeError::InvalidAmount);
+        self.position.balance = self
+            .position
+            .balance
+            .checked_sub(amount)
+            .ok_or(FeeError::InsufficientBalance)?;
+
+        // SECURE: Rounds up through a u128 product, so splitting never
+        // pays less than withdrawing at once
+        let fee = solsec_math::bps_ceil(amount, self.pool.fee_bps as u64)
+            .map_err(|_| FeeError::MathOverflow)?;
+
+        self.pool.fees_collected = self
+            .pool
+            .fees_collected
+            .checked_add(fee)
+            .ok_or(FeeError::MathOverflow)?;
+        // Never short while `fee_bps` is at most 10000, checked regardless
+        let payout = amount.checked_sub(fee).ok_or(FeeError::MathOverflow)?;
+        transfer_lamports(
+            &self.pool.to_account_info(),
+            &self.owner.to_account_info(),
+            payout,
+        )?;
+
+        msg!("Withdrew");
+        sol_log_data(&[&amount.to_le_bytes(), &fee.to_le_bytes()]);
+        Ok(fee)
+    }
+}
@@ -0,0 +1,33 @@
+use solsec_prelude::*;
+
+/// The pool every position withdraws from, and the fees it kept
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    /// Set the fee, bytes 8..40
+    pub authority: Pubkey,
+    /// Fee on every withdrawal, in basis points, bytes 40..42
+    pub fee_bps: u16,
+    /// Lamports kept as fees so far, bytes 42..50
+    pub fees_collected: u64,
+    /// Bump seed for the pool PDA, byte 50
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Pool::INIT_SPACE == 51);
+
+/// A user's claim on the pool's lamports
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    /// Withdraws from the position, bytes 8..40
+    pub owner: Pubkey,
+    /// Lamports left to withdraw, fee included, bytes 40..48
+    pub balance: u64,
+    /// Bump seed for the position PDA, byte 48
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Position::INIT_SPACE == 49);
@@ -0,0 +1,65 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::error::FeeError;
+use crate::state::{Pool, Position};
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Fee Rounded Down per Withdrawal
+// ---------------------------------------------------------------------------
+// `amount * fee_bps / 10_000` truncates. At 30 bps any withdrawal of 333
+// lamports or less pays no fee, and the truncated fraction of every larger
+// one is dust the pool never collects. The fee is computed per call, so
+// splitting a withdrawal into pieces just under the threshold, many to a
+// transaction, withdraws the whole position without paying anything.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct VulnerableWithdraw<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"position", owner.key().as_ref()],
+        bump = position.bump,
+        constraint = position.owner == owner.key() @ FeeError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableWithdraw<'info> {
+    /// Take `amount` from the position, paying the owner all of it but the
+    /// fee. Returns the fee.
+    /// DANGER: The fee rounds down to zero for small amounts
+    pub fn withdraw(&mut self, amount: u64) -> Result<u64> {
+        require!(amount > 0, FeeError::InvalidAmount);
+        self.position.balance = self
+            .position
+            .balance
+            .checked_sub(amount)
+            .ok_or(FeeError::InsufficientBalance)?;
+
+        // VULNERABLE: Floors in the user's favour, once per withdrawal
+        let fee = amount * self.pool.fee_bps as u64 / 10_000;
+
+        self.pool.fees_collected += fee;
+        transfer_lamports(
+            &self.pool.to_account_info(),
+            &self.owner.to_account_info(),
+            amount - fee,
+        )?;
+
+        msg!("Withdrew {}, fee {}", amount, fee);
+        Ok(fee)
+    }
+}
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum InitError {
@@ -7,3 +7,9 @@ pub enum InitError {
     #[msg("Unauthorized - not admin")]
     Unauthorized,
 }
+
+impl From<InitError> for solsec_errors::ErrorClass {
+    fn from(error: InitError) -> Self {
+        solsec_errors::INSECURE_INIT.class(error as usize)
+    }
+}
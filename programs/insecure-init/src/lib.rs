@@ -5,16 +5,27 @@
 //!
 //! VULNERABILITY: Missing is_initialized check allows re-initialization.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("5fCvzD3bcuA7dbp3afvnRFe68i2m4Tk6448JQLEgKgtY");
@@ -23,15 +34,28 @@ declare_id!("5fCvzD3bcuA7dbp3afvnRFe68i2m4Tk6448JQLEgKgtY");
 pub mod insecure_init {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// VULNERABLE: Initialize config without checking if already initialized
     /// Attacker can reinitialize and become admin
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_initialize(ctx: Context<VulnerableInitialize>, admin: Pubkey) -> Result<()> {
-        ctx.accounts.initialize(admin)
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        let event = ctx.accounts.initialize(admin)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     /// SECURE: Initialize with proper is_initialized guard
     pub fn secure_initialize(ctx: Context<SecureInitialize>, admin: Pubkey) -> Result<()> {
-        ctx.accounts.initialize(admin)
+        let event = ctx.accounts.initialize(&ctx.bumps, admin)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     /// Admin action that requires authorization
@@ -50,3 +74,5 @@ pub struct AdminAction<'info> {
 
     pub admin: Signer<'info>,
 }
+
+solsec_prelude::catalogued!(program::InsecureInit, INSECURE_INIT);
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::error::InitError;
 use crate::state::Config;
@@ -10,6 +10,7 @@ use crate::state::Config;
 // This prevents re-initialization attacks.
 // ---------------------------------------------------------------------------
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SecureInitialize<'info> {
     #[account(mut)]
@@ -30,13 +31,24 @@ pub struct SecureInitialize<'info> {
 impl<'info> SecureInitialize<'info> {
     /// Initialize config - only works once due to `init` constraint.
     /// SAFE: Anchor's `init` fails if account has non-zero lamports.
-    pub fn initialize(&mut self, admin: Pubkey) -> Result<()> {
+    pub fn initialize(
+        &mut self,
+        bumps: &SecureInitializeBumps,
+        admin: Pubkey,
+    ) -> Result<SecurityEvent> {
         // With `init`, this can only be called once
         self.config.admin = admin;
         self.config.is_initialized = true;
-        self.config.bump = 0; // Will be set by Anchor
+        self.config.bump = bumps.config; // Anchor only finds the bump, storing it is on us
 
-        Ok(())
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::AuthorityChanged {
+                config: self.config.key().to_bytes(),
+                previous: [0; 32],
+                current: admin.to_bytes(),
+            },
+        ))
     }
 }
 
@@ -46,6 +58,7 @@ pub struct SecureInitializeAlt<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // solsec-lint: allow(init-if-needed) the handler rejects an initialized config
     #[account(
         init_if_needed,
         payer = payer,
@@ -1,12 +1,16 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
+// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
+#[derive(InitSpace)]
 pub struct Config {
-    pub admin: Pubkey,
-    pub is_initialized: bool,
-    pub bump: u8,
+    pub admin: Pubkey,        // bytes 8..40
+    pub is_initialized: bool, // byte 40
+    pub bump: u8,             // byte 41
 }
 
 impl Config {
-    pub const SIZE: usize = 8 + 32 + 1 + 1; // discriminator + pubkey + bool + bump
+    pub const SIZE: usize = 8 + Config::INIT_SPACE;
 }
+
+const _: () = assert!(Config::SIZE == 42);
@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
 
 use crate::state::Config;
 
@@ -10,6 +13,7 @@ use crate::state::Config;
 // and overwrite the admin pubkey.
 // ---------------------------------------------------------------------------
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct VulnerableInitialize<'info> {
     #[account(mut)]
@@ -25,17 +29,29 @@ pub struct VulnerableInitialize<'info> {
     pub config: Account<'info, Config>,
 
     pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableInitialize<'info> {
     /// Initialize or reinitialize the config.
     /// DANGER: No is_initialized check - anyone can overwrite admin!
-    pub fn initialize(&mut self, admin: Pubkey) -> Result<()> {
+    pub fn initialize(&mut self, admin: Pubkey) -> Result<SecurityEvent> {
         // VULNERABLE: We just overwrite, no check if already initialized
+        let previous = self.config.admin;
         self.config.admin = admin;
         self.config.is_initialized = true;
         // Note: if account exists, bump won't be set correctly either
 
-        Ok(())
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::AuthorityChanged {
+                config: self.config.key().to_bytes(),
+                previous: previous.to_bytes(),
+                current: admin.to_bytes(),
+            },
+        ))
     }
 }
@@ -0,0 +1,68 @@
+//! File and approve instructions for insurance-fund program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::error::InsuranceError;
+use crate::state::{Claim, Fund};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct FileClaim<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + Claim::INIT_SPACE,
+        seeds = [b"claim", claimant.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub claim: Account<'info, Claim>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FileClaim<'info> {
+    pub fn file(&mut self, bumps: &FileClaimBumps, id: u64, amount: u64) -> Result<()> {
+        require!(amount > 0, InsuranceError::InvalidAmount);
+        self.claim.set_inner(Claim {
+            claimant: self.claimant.key(),
+            id,
+            amount,
+            approved: false,
+            paid: false,
+            bump: bumps.claim,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ApproveClaim<'info> {
+    pub adjuster: Signer<'info>,
+
+    #[account(
+        seeds = [b"fund"],
+        bump = fund.bump,
+        has_one = adjuster @ InsuranceError::Unauthorized
+    )]
+    pub fund: Account<'info, Fund>,
+
+    #[account(
+        mut,
+        seeds = [b"claim", claim.claimant.as_ref(), &claim.id.to_le_bytes()],
+        bump = claim.bump
+    )]
+    pub claim: Account<'info, Claim>,
+}
+
+impl<'info> ApproveClaim<'info> {
+    pub fn approve(&mut self) -> Result<()> {
+        require!(!self.claim.paid, InsuranceError::AlreadyPaid);
+        self.claim.approved = true;
+        Ok(())
+    }
+}
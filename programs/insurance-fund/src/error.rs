@@ -0,0 +1,23 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum InsuranceError {
+    #[msg("Unauthorized - not the fund's adjuster")]
+    Unauthorized,
+    #[msg("Invalid amount: cannot be zero")]
+    InvalidAmount,
+    #[msg("Claim has not been approved")]
+    NotApproved,
+    #[msg("Claim has already been paid")]
+    AlreadyPaid,
+    #[msg("Recipient is not the claimant")]
+    WrongRecipient,
+    #[msg("Math overflow detected")]
+    MathOverflow,
+}
+
+impl From<InsuranceError> for solsec_errors::ErrorClass {
+    fn from(error: InsuranceError) -> Self {
+        solsec_errors::INSURANCE_FUND.class(error as usize)
+    }
+}
@@ -0,0 +1,33 @@
+//! Initialize instruction for insurance-fund program
+//!
+//! Shared initialization - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::state::Fund;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub adjuster: Signer<'info>,
+
+    #[account(
+        init,
+        payer = adjuster,
+        space = 8 + Fund::INIT_SPACE,
+        seeds = [b"fund"],
+        bump
+    )]
+    pub fund: Account<'info, Fund>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        self.fund.adjuster = self.adjuster.key();
+        self.fund.total_paid = 0;
+        self.fund.bump = bumps.fund;
+        Ok(())
+    }
+}
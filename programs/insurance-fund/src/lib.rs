@@ -0,0 +1,79 @@
+//! Insurance Fund Drain - Anchor Program
+//!
+//! Demonstrates an insurance fund whose payout instruction is
+//! permissionless, so an approved claim is paid out by whoever cranks it,
+//! and sends the lamports to a recipient the caller names. Nothing ties the
+//! recipient to the claim record, so an attacker pays every approved claim
+//! to themselves and the claimants find them already paid.
+//!
+//! VULNERABILITY: the payout recipient isn't bound to the claim.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod claim;
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+use claim::*;
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use initialize::*;
+use secure::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("JZtFDLcbRmBX5aznX9fqokiYosZSSqRiEfooVmjB6hU");
+
+#[program]
+pub mod insurance_fund {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// Initialize the fund, with the signer as its adjuster
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps)
+    }
+
+    /// File claim `id` for `amount` lamports
+    pub fn file_claim(ctx: Context<FileClaim>, id: u64, amount: u64) -> Result<()> {
+        ctx.accounts.file(&ctx.bumps, id, amount)
+    }
+
+    /// Approve a filed claim; only the adjuster can
+    pub fn approve_claim(ctx: Context<ApproveClaim>) -> Result<()> {
+        ctx.accounts.approve()
+    }
+
+    /// VULNERABLE: Pay an approved claim to the recipient the caller passes
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_pay_claim(ctx: Context<VulnerablePayClaim>) -> Result<u64> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.pay()
+    }
+
+    /// SECURE: Pay an approved claim to the claimant it names
+    pub fn secure_pay_claim(ctx: Context<SecurePayClaim>) -> Result<u64> {
+        ctx.accounts.pay()
+    }
+}
+
+solsec_prelude::catalogued!(program::InsuranceFund, INSURANCE_FUND);
@@ -0,0 +1,63 @@
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::InsuranceError;
+use crate::state::{Claim, Fund};
+
+// ---------------------------------------------------------------------------
+// SECURE: Recipient Bound to the Claim Record
+// ---------------------------------------------------------------------------
+// FIX: Re-derive the claim from the claimant and id it stores, and take the
+// recipient only at the address the claim names. Anyone may still crank a
+// payout, but it can only ever reach the claimant. The paid claim is closed
+// to the claimant too, so its rent follows the payout and the record can't
+// be paid again.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SecurePayClaim<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(mut, seeds = [b"fund"], bump = fund.bump)]
+    pub fund: Account<'info, Fund>,
+
+    // SECURE: The claim's own address, and closed once paid
+    #[account(
+        mut,
+        seeds = [b"claim", claim.claimant.as_ref(), &claim.id.to_le_bytes()],
+        bump = claim.bump,
+        close = recipient
+    )]
+    pub claim: Account<'info, Claim>,
+
+    /// CHECK: The claimant the claim names, checked by `address`
+    #[account(mut, address = claim.claimant @ InsuranceError::WrongRecipient)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+impl<'info> SecurePayClaim<'info> {
+    /// Pay the claim's amount out of the fund to its claimant. Returns the
+    /// amount.
+    pub fn pay(&mut self) -> Result<u64> {
+        require!(self.claim.approved, InsuranceError::NotApproved);
+        require!(!self.claim.paid, InsuranceError::AlreadyPaid);
+
+        // No need to mark it paid: the `close` constraint closes it
+        let amount = self.claim.amount;
+        self.fund.total_paid = self
+            .fund
+            .total_paid
+            .checked_add(amount)
+            .ok_or(InsuranceError::MathOverflow)?;
+        // Keeps the fund rent exempt, whatever the claim asks for
+        transfer_lamports(
+            &self.fund.to_account_info(),
+            &self.recipient.to_account_info(),
+            amount,
+        )?;
+
+        msg!("Paid claim");
+        sol_log_data(&[&self.claim.id.to_le_bytes(), self.claim.claimant.as_ref()]);
+        Ok(amount)
+    }
+}
@@ -0,0 +1,38 @@
+use solsec_prelude::*;
+
+/// The fund every claim is paid from; its lamports above rent are the
+/// cover
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Fund {
+    /// Approves claims, bytes 8..40
+    pub adjuster: Pubkey,
+    /// Lamports paid out so far, bytes 40..48
+    pub total_paid: u64,
+    /// Bump seed for the fund PDA, byte 48
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Fund::INIT_SPACE == 49);
+
+/// One claim against the fund, at `[b"claim", claimant, id]`
+#[account]
+#[derive(InitSpace)]
+pub struct Claim {
+    /// Filed the claim and is owed the payout, bytes 8..40
+    pub claimant: Pubkey,
+    /// The claimant's own number for it, bytes 40..48
+    pub id: u64,
+    /// Lamports claimed, bytes 48..56
+    pub amount: u64,
+    /// Set by the adjuster, byte 56
+    pub approved: bool,
+    /// Set once the payout is made, byte 57
+    pub paid: bool,
+    /// Bump seed for the claim PDA, byte 58
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Claim::INIT_SPACE == 59);
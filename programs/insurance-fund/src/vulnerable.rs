@@ -0,0 +1,63 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::error::InsuranceError;
+use crate::state::{Claim, Fund};
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Payout Recipient Not Bound to the Claim
+// ---------------------------------------------------------------------------
+// Payouts are permissionless, so an approved claim doesn't wait for its
+// claimant. The claim is a genuine record, approved by the adjuster, but
+// it's taken as any `Claim` account with no seeds, and the lamports go to a
+// `recipient` the caller passes. Nothing links that recipient to the claim
+// or the claim's address to the claimant, so anyone can pay every approved
+// claim to themselves. Each is marked paid, and the claimants get nothing.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct VulnerablePayClaim<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(mut, seeds = [b"fund"], bump = fund.bump)]
+    pub fund: Account<'info, Fund>,
+
+    // VULNERABLE: Any claim, not derived from the recipient
+    #[account(mut)]
+    pub claim: Account<'info, Claim>,
+
+    /// CHECK: VULNERABLE - whoever the caller names
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerablePayClaim<'info> {
+    /// Pay the claim's amount out of the fund. Returns the amount.
+    /// DANGER: To the caller's recipient, not the claimant
+    pub fn pay(&mut self) -> Result<u64> {
+        require!(self.claim.approved, InsuranceError::NotApproved);
+        require!(!self.claim.paid, InsuranceError::AlreadyPaid);
+
+        let amount = self.claim.amount;
+        self.claim.paid = true;
+        self.fund.total_paid = self
+            .fund
+            .total_paid
+            .checked_add(amount)
+            .ok_or(InsuranceError::MathOverflow)?;
+        transfer_lamports(
+            &self.fund.to_account_info(),
+            &self.recipient.to_account_info(),
+            amount,
+        )?;
+
+        msg!("Paid claim {} of {}", self.claim.id, self.claim.claimant);
+        Ok(amount)
+    }
+}
@@ -0,0 +1,27 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum PayoutError {
+    #[msg("Unauthorized - not the treasury authority")]
+    Unauthorized,
+    #[msg("Payout not approved")]
+    NotApproved,
+    #[msg("Payout already executed")]
+    AlreadyExecuted,
+    #[msg("Too many recipients for one payout")]
+    TooManyRecipients,
+    #[msg("Account is not an address lookup table")]
+    InvalidLookupTable,
+    #[msg("Lookup table can still be extended")]
+    LookupTableNotFrozen,
+    #[msg("Index past the end of the lookup table")]
+    UnresolvedIndex,
+    #[msg("Recipient is not the one approved")]
+    RecipientMismatch,
+}
+
+impl From<PayoutError> for solsec_errors::ErrorClass {
+    fn from(error: PayoutError) -> Self {
+        solsec_errors::LOOKUP_TABLE_PITFALLS.class(error as usize)
+    }
+}
@@ -0,0 +1,32 @@
+//! Initialize instruction for lookup-table-pitfalls program
+//!
+//! Shared initialization - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::state::Treasury;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        self.treasury.authority = self.authority.key();
+        self.treasury.bump = bumps.treasury;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//! Lookup Table Pitfalls - Anchor Program
+//!
+//! Demonstrates how approving a payout whose recipients are indexes into an
+//! address lookup table, as a v0 transaction names the accounts it loads,
+//! approves whatever the table holds when the payout executes. The table's
+//! authority can append to it after the review.
+//!
+//! VULNERABILITY: an approval that pins indexes, not the addresses behind
+//! them, from a table that can still be extended.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod error;
+pub mod initialize;
+pub mod lookup_table;
+pub mod propose;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use initialize::*;
+use propose::*;
+use secure::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("GGhAyoDquTGBEZGuCJp72wj87EMkpf5SysS3Gu7Z3Byc");
+
+#[program]
+pub mod lookup_table_pitfalls {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// Initialize the treasury, with the signer as its authority
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps)
+    }
+
+    /// Propose paying `amount` to each address `indexes` point at in `table`
+    pub fn propose_payout(
+        ctx: Context<ProposePayout>,
+        id: u64,
+        table: Pubkey,
+        indexes: Vec<u8>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.propose(&ctx.bumps, id, table, indexes, amount)
+    }
+
+    /// VULNERABLE: Approve the payout's indexes, whatever they later
+    /// resolve to
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_approve(ctx: Context<VulnerableApprove>) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.approve()
+    }
+
+    /// VULNERABLE: Pay the addresses the table resolves the indexes to now
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_execute<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, VulnerableExecute<'info>>,
+    ) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.execute(ctx.remaining_accounts)
+    }
+
+    /// SECURE: Approve the addresses a frozen table resolves the indexes to,
+    /// and pin them
+    pub fn secure_approve(ctx: Context<SecureApprove>) -> Result<()> {
+        ctx.accounts.approve()
+    }
+
+    /// SECURE: Pay the pinned addresses
+    pub fn secure_execute<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SecureExecute<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.execute(ctx.remaining_accounts)
+    }
+}
+
+solsec_prelude::catalogued!(program::LookupTablePitfalls, LOOKUP_TABLE_PITFALLS);
@@ -0,0 +1,39 @@
+//! Reading an address lookup table's account in place
+//!
+//! The layout is the table program's: a `ProgramState` tag, then the
+//! table's metadata, then its addresses, 32 bytes each. Only the fields
+//! the program checks are read, as the runtime does when it resolves a v0
+//! transaction's lookups.
+
+use solsec_prelude::*;
+
+/// The address lookup table program
+pub const LOOKUP_TABLE_PROGRAM_ID: Pubkey = pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+/// `ProgramState::LookupTable`, bytes 0..4 of a table's data
+const LOOKUP_TABLE_TAG: [u8; 4] = [1, 0, 0, 0];
+
+/// Where the metadata's `Option<Pubkey>` authority starts: its tag, 0 once
+/// the table is frozen
+const AUTHORITY_OFFSET: usize = 21;
+
+/// Bytes before the first address
+const META_SIZE: usize = 56;
+
+/// `data` is an initialized lookup table's.
+pub fn is_lookup_table(data: &[u8]) -> bool {
+    data.len() >= META_SIZE && data.starts_with(&LOOKUP_TABLE_TAG)
+}
+
+/// The table has no authority left to extend it.
+pub fn is_frozen(data: &[u8]) -> bool {
+    data.get(AUTHORITY_OFFSET) == Some(&0)
+}
+
+/// The address at `index`, if the table holds one there yet.
+pub fn address(data: &[u8], index: u8) -> Option<Pubkey> {
+    data.get(META_SIZE..)?
+        .chunks_exact(32)
+        .nth(index as usize)
+        .map(|key| Pubkey::try_from(key).unwrap())
+}
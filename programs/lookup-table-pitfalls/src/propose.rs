@@ -0,0 +1,53 @@
+//! Propose a payout - shared by both approval flows
+//!
+//! Anyone can propose. The payout names its recipients the way a v0
+//! transaction names the accounts it loads: a lookup table and indexes into
+//! it. What those indexes point at is for the approval to settle.
+
+use solsec_prelude::*;
+
+use crate::error::PayoutError;
+use crate::state::{Payout, MAX_RECIPIENTS};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ProposePayout<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Payout::INIT_SPACE,
+        seeds = [b"payout", id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub payout: Account<'info, Payout>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposePayout<'info> {
+    pub fn propose(
+        &mut self,
+        bumps: &ProposePayoutBumps,
+        id: u64,
+        table: Pubkey,
+        indexes: Vec<u8>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            indexes.len() <= MAX_RECIPIENTS,
+            PayoutError::TooManyRecipients
+        );
+
+        let payout = &mut self.payout;
+        payout.id = id;
+        payout.table = table;
+        payout.indexes[..indexes.len()].copy_from_slice(&indexes);
+        payout.index_count = indexes.len() as u8;
+        payout.amount = amount;
+        payout.bump = bumps.payout;
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::PayoutError;
+use crate::lookup_table::{self, LOOKUP_TABLE_PROGRAM_ID};
+use crate::state::{Payout, Treasury};
+
+// ---------------------------------------------------------------------------
+// SECURE: Pinned Recipients, Frozen Tables
+// ---------------------------------------------------------------------------
+// FIX: Approve addresses, not indexes:
+// 1. The table must be frozen, so nothing can be appended after review
+// 2. Every index must resolve now - one past the end is refused, not hidden
+// 3. The resolved addresses are stored with the approval, and execution
+//    pays those and no others, whatever the table holds by then
+//
+// Either check alone closes this attack: pinning makes later changes to
+// the table irrelevant, and freezing means the addresses the approver saw
+// are the ones any later transaction loads.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SecureApprove<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+        has_one = authority @ PayoutError::Unauthorized
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"payout", payout.id.to_le_bytes().as_ref()],
+        bump = payout.bump
+    )]
+    pub payout: Account<'info, Payout>,
+
+    /// CHECK: The payout's table; its layout is checked in the handler
+    #[account(
+        address = payout.table,
+        owner = LOOKUP_TABLE_PROGRAM_ID @ PayoutError::InvalidLookupTable
+    )]
+    pub table: UncheckedAccount<'info>,
+}
+
+impl<'info> SecureApprove<'info> {
+    pub fn approve(&mut self) -> Result<()> {
+        require!(!self.payout.executed, PayoutError::AlreadyExecuted);
+
+        let data = self.table.try_borrow_data()?;
+        require!(
+            lookup_table::is_lookup_table(&data),
+            PayoutError::InvalidLookupTable
+        );
+        // SECURE: Step 1 - No authority left to append to the table
+        require!(
+            lookup_table::is_frozen(&data),
+            PayoutError::LookupTableNotFrozen
+        );
+
+        let payout = &mut self.payout;
+        let indexes = payout.indexes;
+        for (i, &index) in indexes[..payout.index_count as usize].iter().enumerate() {
+            // SECURE: Step 2 - Every index resolves to an address now
+            let recipient =
+                lookup_table::address(&data, index).ok_or(PayoutError::UnresolvedIndex)?;
+            // SECURE: Step 3 - Pin the address approved
+            payout.recipients[i] = recipient;
+        }
+        payout.approved = true;
+        payout.pinned = true;
+
+        msg!("Payout approved");
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SecureExecute<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"payout", payout.id.to_le_bytes().as_ref()],
+        bump = payout.bump
+    )]
+    pub payout: Account<'info, Payout>,
+    // remaining_accounts: the pinned recipients, writable, in order
+}
+
+impl<'info> SecureExecute<'info> {
+    pub fn execute(&mut self, remaining: &[AccountInfo<'info>]) -> Result<()> {
+        // A payout approved by index has nothing pinned to pay
+        require!(
+            self.payout.approved && self.payout.pinned,
+            PayoutError::NotApproved
+        );
+        require!(!self.payout.executed, PayoutError::AlreadyExecuted);
+
+        // SECURE: Exactly the pinned recipients, in order; the table isn't
+        // read at all
+        let recipients = self.payout.recipients();
+        require!(
+            remaining.len() == recipients.len()
+                && remaining
+                    .iter()
+                    .zip(recipients)
+                    .all(|(account, recipient)| account.key == recipient),
+            PayoutError::RecipientMismatch
+        );
+
+        self.payout.executed = true;
+        let amount = self.payout.amount;
+        for account in remaining {
+            transfer_lamports(&self.treasury.to_account_info(), account, amount)?;
+            sol_log_data(&[account.key.as_ref(), &amount.to_le_bytes()]);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use solsec_prelude::*;
+
+/// Most recipients one payout can name
+pub const MAX_RECIPIENTS: usize = 8;
+
+/// The treasury payouts are made from, and who approves them
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    /// Approves every payout, bytes 8..40
+    pub authority: Pubkey,
+    /// Bump seed for the treasury PDA, byte 40
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Treasury::INIT_SPACE == 41);
+
+/// A payout of `amount` to each recipient, named as indexes into a lookup
+/// table the way a v0 transaction names its loaded accounts
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Payout {
+    /// Unique payout ID, bytes 8..16
+    pub id: u64,
+    /// The address lookup table the indexes point into, bytes 16..48
+    pub table: Pubkey,
+    /// Indexes into `table`, the first `index_count` meaningful, bytes 48..56
+    pub indexes: [u8; MAX_RECIPIENTS],
+    /// Number of recipients, byte 56
+    pub index_count: u8,
+    /// Lamports paid to each recipient, bytes 57..65
+    pub amount: u64,
+    /// Whether the authority approved the payout, byte 65
+    pub approved: bool,
+    /// Whether `recipients` holds the addresses approved, byte 66
+    pub pinned: bool,
+    /// Whether the payout was made, byte 67
+    pub executed: bool,
+    /// The addresses the indexes resolved to when the payout was approved,
+    /// in order, written by `secure_approve`, bytes 68..324
+    pub recipients: [Pubkey; MAX_RECIPIENTS],
+    /// Bump seed for the payout PDA, byte 324
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Payout::INIT_SPACE == 325);
+
+impl Payout {
+    /// The indexes the payout names
+    pub fn indexes(&self) -> &[u8] {
+        &self.indexes[..self.index_count as usize]
+    }
+
+    /// The recipients pinned at approval
+    pub fn recipients(&self) -> &[Pubkey] {
+        &self.recipients[..self.index_count as usize]
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::error::PayoutError;
+use crate::lookup_table;
+use crate::state::{Payout, Treasury};
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Approving Lookup Table Indexes
+// ---------------------------------------------------------------------------
+// The approval covers the payout as stored: a table and indexes into it.
+// What the indexes point at is settled only when the payout executes,
+// against the table as it is then.
+//
+// A table's authority can append to it at any time. The approver checks
+// the recipients the table resolves to while they review, and an index
+// past its end resolves to nothing - a wallet or multisig UI leaves it out,
+// as the runtime can't load it yet. After the approval, the proposer
+// extends the table, that index resolves to their own address, and the
+// approved payout pays them.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct VulnerableApprove<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+        has_one = authority @ PayoutError::Unauthorized
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"payout", payout.id.to_le_bytes().as_ref()],
+        bump = payout.bump
+    )]
+    pub payout: Account<'info, Payout>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableApprove<'info> {
+    pub fn approve(&mut self) -> Result<()> {
+        require!(!self.payout.executed, PayoutError::AlreadyExecuted);
+
+        // VULNERABLE: Approves the indexes, not the addresses behind them
+        self.payout.approved = true;
+
+        msg!("VULNERABLE: Payout {} approved by index", self.payout.id);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct VulnerableExecute<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [b"payout", payout.id.to_le_bytes().as_ref()],
+        bump = payout.bump
+    )]
+    pub payout: Account<'info, Payout>,
+
+    /// CHECK: The payout's table, read in the handler
+    /// VULNERABLE: Read as it is now, not as it was approved
+    #[account(address = payout.table)]
+    pub table: UncheckedAccount<'info>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+    // remaining_accounts: the recipients, writable
+}
+
+impl<'info> VulnerableExecute<'info> {
+    pub fn execute(&mut self, remaining: &[AccountInfo<'info>]) -> Result<()> {
+        require!(self.payout.approved, PayoutError::NotApproved);
+        require!(!self.payout.executed, PayoutError::AlreadyExecuted);
+
+        let recipients = {
+            let data = self.table.try_borrow_data()?;
+            require!(
+                lookup_table::is_lookup_table(&data),
+                PayoutError::InvalidLookupTable
+            );
+            // VULNERABLE: Resolved at execution - anything appended to the
+            // table since the approval is paid
+            self.payout
+                .indexes()
+                .iter()
+                .map(|&index| {
+                    lookup_table::address(&data, index).ok_or(PayoutError::UnresolvedIndex)
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        self.payout.executed = true;
+        for recipient in recipients {
+            let account = remaining
+                .iter()
+                .find(|account| account.key() == recipient)
+                .ok_or(PayoutError::RecipientMismatch)?;
+            transfer_lamports(
+                &self.treasury.to_account_info(),
+                account,
+                self.payout.amount,
+            )?;
+            msg!("VULNERABLE: Paid {} to {}", self.payout.amount, recipient);
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 //! Error definitions
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum DaoError {
@@ -12,4 +12,14 @@ pub enum DaoError {
     TitleTooLong,
     #[msg("Proposal already executed")]
     AlreadyExecuted,
+    #[msg("Proposal log is full")]
+    LogFull,
+    #[msg("Squads proposal is not approved")]
+    ProposalNotApproved,
+}
+
+impl From<DaoError> for solsec_errors::ErrorClass {
+    fn from(error: DaoError) -> Self {
+        solsec_errors::MULTISIG_PAYER.class(error as usize)
+    }
 }
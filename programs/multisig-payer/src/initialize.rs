@@ -0,0 +1,51 @@
+//! Initialize instruction for multisig-payer program
+//!
+//! Sets up the DAO config and its treasury PDA for both the vulnerable and
+//! the secure proposal flows. Not part of the vulnerability.
+
+use anchor_lang::solana_program::log::sol_log_data;
+use crate::state::{DaoConfig, MultisigTreasury};
+use solsec_prelude::*;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + DaoConfig::INIT_SPACE,
+        seeds = [b"dao_config"],
+        bump
+    )]
+    pub config: Account<'info, DaoConfig>,
+
+    /// The multisig treasury PDA that will "own" funds
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MultisigTreasury::INIT_SPACE,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, MultisigTreasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        self.config.authority = self.treasury.key();
+        self.config.proposal_count = 0;
+        self.config.bump = bumps.config;
+        self.config.is_initialized = true;
+
+        self.treasury.dao = self.config.key();
+        self.treasury.bump = bumps.treasury;
+
+        msg!("DAO initialized");
+        sol_log_data(&[self.treasury.key().as_ref()]);
+        Ok(())
+    }
+}
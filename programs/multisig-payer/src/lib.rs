@@ -8,16 +8,35 @@
 //!
 //! Source: J4X_Security (2026)
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
+pub mod initialize;
+pub mod log;
 pub mod secure;
+#[cfg(feature = "squads")]
+pub mod squads;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use initialize::*;
+use log::*;
 use secure::*;
+#[cfg(feature = "squads")]
+use squads::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("Fvat4mBGBnCbz7eGpTYUUJq2gQ4bwevt5AYhAVibmjC2");
@@ -26,6 +45,13 @@ declare_id!("Fvat4mBGBnCbz7eGpTYUUJq2gQ4bwevt5AYhAVibmjC2");
 pub mod multisig_payer {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize the DAO config with a multisig PDA as authority
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps)
@@ -33,11 +59,13 @@ pub mod multisig_payer {
 
     /// VULNERABLE: Create proposal with multisig PDA as payer
     /// This will ALWAYS fail because PDAs cannot sign system transfers
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_create_proposal(
         ctx: Context<VulnerableCreateProposal>,
         proposal_id: u64,
         title: String,
     ) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.create_proposal(&ctx.bumps, proposal_id, title)
     }
 
@@ -51,8 +79,52 @@ pub mod multisig_payer {
         ctx.accounts.create_proposal(&ctx.bumps, proposal_id, title)
     }
 
-    /// Vote on a proposal
+    /// Initialize the DAO config with a Squads vault of `multisig` as
+    /// authority instead of the treasury PDA
+    #[cfg(feature = "squads")]
+    pub fn initialize_squads(ctx: Context<InitializeSquads>, vault_index: u8) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps, vault_index)
+    }
+
+    /// SECURE: Pay out of the treasury, signed by the Squads vault while it
+    /// executes a transaction the multisig approved
+    #[cfg(feature = "squads")]
+    pub fn squads_withdraw(
+        ctx: Context<SquadsWithdraw>,
+        vault_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        // Read by the vault's seeds constraint
+        let _ = vault_index;
+        ctx.accounts.withdraw(amount)
+    }
+
+    /// Vote on a proposal. Any signer can vote, as often as they like, so
+    /// it ships with the vulnerable instructions.
+    #[cfg(feature = "vulnerable")]
     pub fn vote(ctx: Context<Vote>, approve: bool) -> Result<()> {
         ctx.accounts.vote(approve)
     }
+
+    /// Create the DAO's Borsh proposal log
+    pub fn init_proposal_log(ctx: Context<InitProposalLog>) -> Result<()> {
+        ctx.accounts.init_log()
+    }
+
+    /// Append a proposal to the Borsh log, copying the whole log in and out
+    pub fn log_proposal(ctx: Context<LogProposal>) -> Result<()> {
+        ctx.accounts.log_proposal()
+    }
+
+    /// Create the DAO's zero-copy proposal log
+    pub fn init_zero_copy_log(ctx: Context<InitZeroCopyLog>) -> Result<()> {
+        ctx.accounts.init_log()
+    }
+
+    /// Append a proposal to the zero-copy log in place
+    pub fn log_proposal_zero_copy(ctx: Context<LogProposalZeroCopy>) -> Result<()> {
+        ctx.accounts.log_proposal()
+    }
 }
+
+solsec_prelude::catalogued!(program::MultisigPayer, MULTISIG_PAYER);
@@ -0,0 +1,127 @@
+//! Proposal logs - Borsh vs zero-copy
+//!
+//! Both logs hold the same ids. A Borsh `Account` deserializes the whole
+//! account when the instruction starts and serializes it back when it
+//! ends, so appending one id costs compute in proportion to the log's
+//! size, and the decoded struct has to live somewhere: inline in the
+//! accounts struct it would take most of a 4 KiB stack frame, so it is
+//! boxed onto the heap. An `AccountLoader` checks the owner and the
+//! discriminator and then hands out a reference into the account's data,
+//! so an append touches two words whatever the capacity.
+//!
+//! The discriminator is only checked through the loader, when the
+//! accounts are validated and again in `load`/`load_mut`. An instruction
+//! that takes the log as an `UncheckedAccount` and casts its data with
+//! `bytemuck` skips it, and any account of the program with the same
+//! length passes for this one.
+
+use crate::error::DaoError;
+use crate::state::{DaoConfig, Proposal, ProposalLog, ZeroCopyProposalLog};
+use solsec_prelude::*;
+
+#[derive(Accounts)]
+pub struct InitProposalLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"dao_config"], bump = config.bump)]
+    pub config: Account<'info, DaoConfig>,
+
+    // Boxed: the decoded log is too large for the stack frame
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ProposalLog::INIT_SPACE,
+        seeds = [b"proposal_log", config.key().as_ref()],
+        bump
+    )]
+    pub log: Box<Account<'info, ProposalLog>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitProposalLog<'info> {
+    pub fn init_log(&mut self) -> Result<()> {
+        self.log.count = 0;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct LogProposal<'info> {
+    #[account(seeds = [b"dao_config"], bump = config.bump)]
+    pub config: Account<'info, DaoConfig>,
+
+    #[account(
+        seeds = [b"proposal", config.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut, seeds = [b"proposal_log", config.key().as_ref()], bump)]
+    pub log: Box<Account<'info, ProposalLog>>,
+}
+
+impl<'info> LogProposal<'info> {
+    pub fn log_proposal(&mut self) -> Result<()> {
+        let count = self.log.count as usize;
+        *self.log.ids.get_mut(count).ok_or(DaoError::LogFull)? = self.proposal.id;
+        // Below the capacity, so it can't saturate
+        self.log.count = self.log.count.saturating_add(1);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitZeroCopyLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"dao_config"], bump = config.bump)]
+    pub config: Account<'info, DaoConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ZeroCopyProposalLog::LEN,
+        seeds = [b"zero_copy_log", config.key().as_ref()],
+        bump
+    )]
+    pub log: AccountLoader<'info, ZeroCopyProposalLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitZeroCopyLog<'info> {
+    pub fn init_log(&mut self) -> Result<()> {
+        // The discriminator is written when the instruction exits, so
+        // `load_mut` would fail here; the ids start zeroed
+        self.log.load_init()?.count = 0;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct LogProposalZeroCopy<'info> {
+    #[account(seeds = [b"dao_config"], bump = config.bump)]
+    pub config: Account<'info, DaoConfig>,
+
+    #[account(
+        seeds = [b"proposal", config.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut, seeds = [b"zero_copy_log", config.key().as_ref()], bump)]
+    pub log: AccountLoader<'info, ZeroCopyProposalLog>,
+}
+
+impl<'info> LogProposalZeroCopy<'info> {
+    pub fn log_proposal(&mut self) -> Result<()> {
+        let mut log = self.log.load_mut()?;
+        let count = log.count as usize;
+        *log.ids.get_mut(count).ok_or(DaoError::LogFull)? = self.proposal.id;
+        log.count = log.count.saturating_add(1);
+        Ok(())
+    }
+}
@@ -7,9 +7,10 @@
 //! The rent payer has NO authority over the DAO - they simply fund
 //! account creation. The multisig retains full control over governance.
 
-use anchor_lang::prelude::*;
-use crate::state::{DaoConfig, MultisigTreasury, Proposal, MAX_TITLE_LEN};
+use anchor_lang::solana_program::log::sol_log_data;
+use crate::state::{DaoConfig, MultisigTreasury, Proposal};
 use crate::error::DaoError;
+use solsec_prelude::*;
 
 /// SECURE: Separate rent payer from authority
 #[derive(Accounts)]
@@ -56,18 +57,22 @@ impl<'info> SecureCreateProposal<'info> {
         proposal_id: u64,
         title: String,
     ) -> Result<()> {
-        require!(title.len() <= MAX_TITLE_LEN, DaoError::TitleTooLong);
-
         self.proposal.id = proposal_id;
-        self.proposal.title = title;
+        self.proposal.set_title(&title)?;
         self.proposal.creator = self.creator.key();
         self.proposal.yes_votes = 0;
         self.proposal.no_votes = 0;
         self.proposal.executed = false;
         self.proposal.bump = bumps.proposal;
 
-        msg!("Proposal {} created by {}", proposal_id, self.creator.key());
-        msg!("Rent paid by {}", self.rent_payer.key());
+        // The id, creator and rent payer as raw bytes rather than formatted,
+        // which would allocate and base58-encode both keys
+        msg!("Proposal created");
+        sol_log_data(&[
+            &proposal_id.to_le_bytes(),
+            self.creator.key().as_ref(),
+            self.rent_payer.key().as_ref(),
+        ]);
         msg!("Authority validation: treasury PDA");
         Ok(())
     }
@@ -0,0 +1,177 @@
+//! SECURE, alternative flow - a Squads multisig as the DAO's authority
+//!
+//! Built with the `squads` feature. Instead of the treasury PDA, the DAO's
+//! authority is a vault of an external Squads v4 multisig: a PDA of the
+//! Squads program, which signs for it only while executing a transaction
+//! the members approved. The treasury pays out only when:
+//!
+//! 1. the vault derived from the multisig and vault index is the config's
+//!    authority, and signs;
+//! 2. the multisig and the proposal are the Squads program's accounts of
+//!    those types;
+//! 3. the proposal is the multisig's, and `Approved`. Squads marks it
+//!    `Executed` only after the transaction's CPIs return, so a proposal
+//!    the members haven't approved, or another multisig's, is refused.
+//!
+//! The Squads layouts are read by hand rather than through its crate,
+//! which pins another Anchor version.
+
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::DaoError;
+use crate::state::{DaoConfig, MultisigTreasury};
+
+/// The Squads v4 program
+pub const SQUADS_PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+/// `sha256("account:Multisig")[..8]`
+pub const MULTISIG_DISCRIMINATOR: [u8; 8] = [224, 116, 121, 186, 68, 161, 79, 236];
+
+/// `sha256("account:Proposal")[..8]`
+pub const PROPOSAL_DISCRIMINATOR: [u8; 8] = [26, 94, 189, 187, 116, 136, 53, 33];
+
+/// `ProposalStatus::Approved`, the enum's fourth variant
+pub const APPROVED: u8 = 3;
+
+/// A Squads proposal's data: its multisig at bytes 8..40, the transaction
+/// index at 40..48 and the status variant at byte 48.
+const STATUS_OFFSET: usize = 48;
+
+/// The vault Squads signs for at `vault_index` of `multisig`.
+pub fn vault_address(multisig: &Pubkey, vault_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"multisig", multisig.as_ref(), b"vault", &[vault_index]],
+        &SQUADS_PROGRAM_ID,
+    )
+    .0
+}
+
+fn check_type(account: &AccountInfo, discriminator: &[u8; 8]) -> Result<()> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.starts_with(discriminator),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(())
+}
+
+/// Initialize the DAO config with a Squads vault as authority
+#[derive(Accounts)]
+pub struct InitializeSquads<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: Owned by Squads; its type is checked in the handler
+    #[account(owner = SQUADS_PROGRAM_ID)]
+    pub multisig: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + DaoConfig::INIT_SPACE,
+        seeds = [b"dao_config"],
+        bump
+    )]
+    pub config: Account<'info, DaoConfig>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MultisigTreasury::INIT_SPACE,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, MultisigTreasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeSquads<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeSquadsBumps, vault_index: u8) -> Result<()> {
+        check_type(&self.multisig, &MULTISIG_DISCRIMINATOR)?;
+
+        self.config.authority = vault_address(&self.multisig.key(), vault_index);
+        self.config.proposal_count = 0;
+        self.config.bump = bumps.config;
+        self.config.is_initialized = true;
+
+        self.treasury.dao = self.config.key();
+        self.treasury.bump = bumps.treasury;
+
+        msg!("DAO initialized with a Squads authority");
+        sol_log_data(&[self.config.authority.as_ref()]);
+        Ok(())
+    }
+}
+
+/// SECURE: Pay out of the treasury in a Squads transaction the multisig
+/// approved
+#[derive(Accounts)]
+#[instruction(vault_index: u8)]
+pub struct SquadsWithdraw<'info> {
+    /// The Squads vault, signed for by the Squads program's CPI
+    #[account(
+        seeds = [b"multisig", multisig.key().as_ref(), b"vault", &[vault_index]],
+        bump,
+        seeds::program = SQUADS_PROGRAM_ID
+    )]
+    pub squads_vault: Signer<'info>,
+
+    /// CHECK: Owned by Squads; its type is checked in the handler
+    #[account(owner = SQUADS_PROGRAM_ID)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// CHECK: Owned by Squads; the proposal of the executing transaction,
+    /// checked in the handler
+    #[account(owner = SQUADS_PROGRAM_ID)]
+    pub proposal: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"dao_config"],
+        bump = config.bump,
+        constraint = config.authority == squads_vault.key() @ DaoError::Unauthorized
+    )]
+    pub config: Account<'info, DaoConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", config.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, MultisigTreasury>,
+
+    // solsec-lint: allow(unchecked-account) any account may receive lamports
+    /// CHECK: Only receives lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+impl<'info> SquadsWithdraw<'info> {
+    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+        check_type(&self.multisig, &MULTISIG_DISCRIMINATOR)?;
+        check_type(&self.proposal, &PROPOSAL_DISCRIMINATOR)?;
+        {
+            let data = self.proposal.try_borrow_data()?;
+            require!(
+                data.get(8..40) == Some(self.multisig.key().as_ref()),
+                DaoError::Unauthorized
+            );
+            require!(
+                data.get(STATUS_OFFSET) == Some(&APPROVED),
+                DaoError::ProposalNotApproved
+            );
+        }
+
+        // The treasury keeps its rent
+        transfer_lamports(
+            &self.treasury.to_account_info(),
+            &self.destination.to_account_info(),
+            amount,
+        )?;
+
+        msg!("Treasury paid on a Squads approval");
+        sol_log_data(&[&amount.to_le_bytes()]);
+        Ok(())
+    }
+}
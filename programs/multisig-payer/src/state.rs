@@ -1,51 +1,122 @@
 //! State definitions for DAO governance
 
-use anchor_lang::prelude::*;
+use crate::error::DaoError;
+use solsec_prelude::*;
 
 /// Maximum title length for proposals
 pub const MAX_TITLE_LEN: usize = 64;
 
 /// DAO configuration with multisig authority
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace)]
 pub struct DaoConfig {
-    /// The multisig PDA that controls this DAO
+    /// The multisig PDA that controls this DAO, bytes 8..40
     pub authority: Pubkey,
-    /// Number of proposals created
+    /// Number of proposals created, bytes 40..48
     pub proposal_count: u64,
-    /// Bump seed for the config PDA
+    /// Bump seed for the config PDA, byte 48
     pub bump: u8,
-    /// Whether the DAO is initialized
+    /// Whether the DAO is initialized, byte 49
     pub is_initialized: bool,
 }
 
+const _: () = assert!(8 + DaoConfig::INIT_SPACE == 50);
+
 /// Multisig treasury PDA
 #[account]
 #[derive(InitSpace)]
 pub struct MultisigTreasury {
-    /// The DAO this treasury belongs to
+    /// The DAO this treasury belongs to, bytes 8..40
     pub dao: Pubkey,
-    /// Bump seed for the treasury PDA  
+    /// Bump seed for the treasury PDA, byte 40
     pub bump: u8,
 }
 
+const _: () = assert!(8 + MultisigTreasury::INIT_SPACE == 41);
+
 /// A governance proposal
+///
+/// The title is a fixed buffer and a length rather than a `String`, so
+/// every field sits at a fixed offset and the account is 3 bytes smaller
+/// than a `String` of the same capacity with its 4-byte length prefix.
 #[account]
 #[derive(InitSpace)]
 pub struct Proposal {
-    /// Unique proposal ID
+    /// Unique proposal ID, bytes 8..16
     pub id: u64,
-    /// Proposal title
-    #[max_len(64)]
-    pub title: String,
-    /// Creator of the proposal
+    /// Proposal title, UTF-8, the first `title_len` bytes meaningful,
+    /// bytes 16..80
+    pub title: [u8; MAX_TITLE_LEN],
+    /// Length of the title, byte 80
+    pub title_len: u8,
+    /// Creator of the proposal, bytes 81..113
     pub creator: Pubkey,
-    /// Number of yes votes
+    /// Number of yes votes, bytes 113..121
     pub yes_votes: u64,
-    /// Number of no votes
+    /// Number of no votes, bytes 121..129
     pub no_votes: u64,
-    /// Whether the proposal is executed
+    /// Whether the proposal is executed, byte 129
     pub executed: bool,
-    /// Bump seed
+    /// Bump seed, byte 130
     pub bump: u8,
 }
+
+const _: () = assert!(8 + Proposal::INIT_SPACE == 131);
+
+impl Proposal {
+    /// Store `title`, which must fit in [`MAX_TITLE_LEN`] bytes.
+    pub fn set_title(&mut self, title: &str) -> Result<()> {
+        let bytes = title.as_bytes();
+        require!(bytes.len() <= MAX_TITLE_LEN, DaoError::TitleTooLong);
+        self.title = [0; MAX_TITLE_LEN];
+        self.title[..bytes.len()].copy_from_slice(bytes);
+        self.title_len = bytes.len() as u8;
+        Ok(())
+    }
+
+    /// The stored title. Bytes that aren't UTF-8 read as empty.
+    pub fn title(&self) -> &str {
+        let len = usize::from(self.title_len).min(MAX_TITLE_LEN);
+        core::str::from_utf8(&self.title[..len]).unwrap_or_default()
+    }
+}
+
+/// Proposals either log holds
+pub const LOG_CAPACITY: usize = 256;
+
+/// Ids of the DAO's proposals, in the order they were logged, as a Borsh
+/// account. Every instruction that names it copies all of it in and, if
+/// mutable, back out, whatever it touches.
+#[account]
+#[derive(InitSpace)]
+pub struct ProposalLog {
+    /// Number of ids logged, bytes 8..16
+    pub count: u64,
+    /// Logged ids, the first `count` meaningful, bytes 16..2064
+    pub ids: [u64; LOG_CAPACITY],
+}
+
+const _: () = assert!(8 + ProposalLog::INIT_SPACE == 2064);
+
+/// The same log as a zero-copy account, read in place through
+/// `AccountLoader` without copying it.
+///
+/// The layout is `#[repr(C)]` with no padding, and it is the stored
+/// bytes: reordering or resizing a field reinterprets every existing
+/// account rather than failing to decode it.
+#[account(zero_copy)]
+pub struct ZeroCopyProposalLog {
+    /// Number of ids logged, bytes 8..16
+    pub count: u64,
+    /// Logged ids, the first `count` meaningful, bytes 16..2064
+    pub ids: [u64; LOG_CAPACITY],
+}
+
+impl ZeroCopyProposalLog {
+    pub const LEN: usize = core::mem::size_of::<ZeroCopyProposalLog>();
+}
+
+// Same bytes as the Borsh log
+const _: () = assert!(ZeroCopyProposalLog::LEN == ProposalLog::INIT_SPACE);
@@ -10,51 +10,12 @@
 //!
 //! Error: "Cross-program invocation with unauthorized signer or writable account"
 
-use anchor_lang::prelude::*;
-use crate::state::{DaoConfig, MultisigTreasury, Proposal, MAX_TITLE_LEN};
-use crate::error::DaoError;
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + DaoConfig::INIT_SPACE,
-        seeds = [b"dao_config"],
-        bump
-    )]
-    pub config: Account<'info, DaoConfig>,
-
-    /// The multisig treasury PDA that will "own" funds
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + MultisigTreasury::INIT_SPACE,
-        seeds = [b"treasury", config.key().as_ref()],
-        bump
-    )]
-    pub treasury: Account<'info, MultisigTreasury>,
-
-    pub system_program: Program<'info, System>,
-}
-
-impl<'info> Initialize<'info> {
-    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
-        self.config.authority = self.treasury.key();
-        self.config.proposal_count = 0;
-        self.config.bump = bumps.config;
-        self.config.is_initialized = true;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
 
-        self.treasury.dao = self.config.key();
-        self.treasury.bump = bumps.treasury;
-
-        msg!("DAO initialized with treasury authority: {}", self.treasury.key());
-        Ok(())
-    }
-}
+use crate::state::{DaoConfig, MultisigTreasury, Proposal};
+use crate::error::DaoError;
+use solsec_prelude::*;
 
 /// VULNERABLE: This instruction will ALWAYS fail!
 /// The treasury PDA cannot sign the system transfer needed for init
@@ -91,6 +52,10 @@ pub struct VulnerableCreateProposal<'info> {
     pub creator: Signer<'info>,
 
     pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableCreateProposal<'info> {
@@ -100,10 +65,8 @@ impl<'info> VulnerableCreateProposal<'info> {
         proposal_id: u64,
         title: String,
     ) -> Result<()> {
-        require!(title.len() <= MAX_TITLE_LEN, DaoError::TitleTooLong);
-
         self.proposal.id = proposal_id;
-        self.proposal.title = title;
+        self.proposal.set_title(&title)?;
         self.proposal.creator = self.creator.key();
         self.proposal.yes_votes = 0;
         self.proposal.no_votes = 0;
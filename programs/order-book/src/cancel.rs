@@ -0,0 +1,36 @@
+//! Cancel instruction for order-book program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::error::BookError;
+use crate::state::{Book, Order};
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"order", order.sequence.to_le_bytes().as_ref()],
+        bump = order.bump,
+        has_one = owner @ BookError::Unauthorized
+    )]
+    pub order: Account<'info, Order>,
+}
+
+impl<'info> CancelOrder<'info> {
+    /// Take the order off the book. Closing it refunds its rent and
+    /// whatever a bid still escrows, including what fills below its price
+    /// left behind.
+    pub fn cancel(&mut self) -> Result<()> {
+        self.book.remove(self.order.side, self.order.sequence);
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum BookError {
+    #[msg("Unauthorized - not the order owner")]
+    Unauthorized,
+    #[msg("Invalid price: cannot be zero")]
+    InvalidPrice,
+    #[msg("Invalid quantity: cannot be zero")]
+    InvalidQuantity,
+    #[msg("This side of the book is full")]
+    BookFull,
+    #[msg("Order is on the wrong side of the book")]
+    WrongSide,
+    #[msg("Order has nothing left to fill")]
+    OrderFilled,
+    #[msg("Bid is below the ask")]
+    PricesDontCross,
+    #[msg("Order is not at the front of the book")]
+    NotBestOrder,
+    #[msg("Math overflow detected")]
+    MathOverflow,
+}
+
+impl From<BookError> for solsec_errors::ErrorClass {
+    fn from(error: BookError) -> Self {
+        solsec_errors::ORDER_BOOK.class(error as usize)
+    }
+}
@@ -0,0 +1,38 @@
+//! Initialize instruction for order-book program
+//!
+//! Shared initialization - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::state::Book;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Book::INIT_SPACE,
+        seeds = [b"book"],
+        bump
+    )]
+    pub book: Account<'info, Book>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        self.book.set_inner(Book {
+            authority: self.authority.key(),
+            next_sequence: 0,
+            last_price: 0,
+            bump: bumps.book,
+            bids: Vec::new(),
+            asks: Vec::new(),
+        });
+        Ok(())
+    }
+}
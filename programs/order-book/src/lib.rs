@@ -0,0 +1,90 @@
+//! Order Book Priority - Anchor Program
+//!
+//! Demonstrates a permissionless matching instruction that fills whichever
+//! bid and ask the caller passes, instead of the ones at the front of the
+//! book. Whoever cranks the book picks the counterparty and the price: they
+//! fill a resting bid against their own worse-priced ask, jump the queue at
+//! a price level, or match their own bid and ask far from the market to
+//! print any last price they like.
+//!
+//! VULNERABILITY: the caller-supplied orders are trusted to be the best ones.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod cancel;
+pub mod error;
+pub mod initialize;
+pub mod place;
+pub mod secure;
+pub mod settle;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+use cancel::*;
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use initialize::*;
+use place::*;
+use secure::*;
+use state::Side;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("C8rPCtBeogFAKQp97Yqj1RnfeTByYppVEJNYJVyG4Py3");
+
+#[program]
+pub mod order_book {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// Initialize the empty book
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps)
+    }
+
+    /// Rest an order of `quantity` units at `price` lamports each; a bid
+    /// escrows what it would pay
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        ctx.accounts.place(&ctx.bumps, side, price, quantity)
+    }
+
+    /// Take the signer's order off the book and refund what it holds
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    /// VULNERABLE: Match the bid and ask the caller passes
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_match_orders(ctx: Context<VulnerableMatchOrders>) -> Result<u64> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.match_orders()
+    }
+
+    /// SECURE: Match the best bid against the best ask
+    pub fn secure_match_orders(ctx: Context<SecureMatchOrders>) -> Result<u64> {
+        ctx.accounts.match_orders()
+    }
+}
+
+solsec_prelude::catalogued!(program::OrderBook, ORDER_BOOK);
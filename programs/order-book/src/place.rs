@@ -0,0 +1,73 @@
+//! Place instruction for order-book program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::error::BookError;
+use crate::state::{Book, Entry, Order, Side};
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [b"order", book.next_sequence.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    /// Queue the order behind everything at its price or better. A bid
+    /// moves `price * quantity` lamports into the order account; the units
+    /// an ask sells settle outside this program.
+    pub fn place(
+        &mut self,
+        bumps: &PlaceOrderBumps,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        require!(price > 0, BookError::InvalidPrice);
+        require!(quantity > 0, BookError::InvalidQuantity);
+
+        if side == Side::Bid {
+            let escrow = price.checked_mul(quantity).ok_or(BookError::MathOverflow)?;
+            let cpi_context = CpiContext::new(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: self.owner.to_account_info(),
+                    to: self.order.to_account_info(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, escrow)?;
+        }
+
+        let sequence = self.book.next_sequence;
+        require!(
+            self.book.insert(side, Entry { price, sequence }),
+            BookError::BookFull
+        );
+        self.book.next_sequence = sequence.checked_add(1).ok_or(BookError::MathOverflow)?;
+
+        self.order.set_inner(Order {
+            owner: self.owner.key(),
+            side,
+            price,
+            quantity,
+            sequence,
+            bump: bumps.order,
+        });
+        Ok(())
+    }
+}
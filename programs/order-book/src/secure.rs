@@ -0,0 +1,51 @@
+use solsec_prelude::*;
+
+use crate::error::BookError;
+use crate::settle::settle;
+use crate::state::{Book, Order, Side};
+
+// ---------------------------------------------------------------------------
+// SECURE: Price-Time Priority Checked Against the Book
+// ---------------------------------------------------------------------------
+// FIX: The book already keeps each side in price-time priority, so the only
+// orders that may trade are the ones at the front. The caller still passes
+// the accounts, since the program can't load them itself, but each must be
+// the one the book names as best. A crank can no longer pick a
+// counterparty or a price; it can only trigger the fill the book dictates.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SecureMatchOrders<'info> {
+    pub matcher: Signer<'info>,
+
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+
+    #[account(mut, constraint = bid.side == Side::Bid @ BookError::WrongSide)]
+    pub bid: Account<'info, Order>,
+
+    #[account(mut, constraint = ask.side == Side::Ask @ BookError::WrongSide)]
+    pub ask: Account<'info, Order>,
+
+    /// CHECK: The ask's owner, paid for the fill
+    #[account(mut, address = ask.owner @ BookError::Unauthorized)]
+    pub seller: UncheckedAccount<'info>,
+}
+
+impl<'info> SecureMatchOrders<'info> {
+    /// Fill the best bid against the best ask. Returns the units filled.
+    pub fn match_orders(&mut self) -> Result<u64> {
+        // SECURE: Only the front of each queue trades
+        for (order, side) in [(&self.bid, Side::Bid), (&self.ask, Side::Ask)] {
+            let best = self.book.best(side).map(|entry| entry.sequence);
+            require!(best == Some(order.sequence), BookError::NotBestOrder);
+        }
+
+        settle(
+            &mut self.book,
+            &mut self.bid,
+            &mut self.ask,
+            &self.seller.to_account_info(),
+        )
+    }
+}
@@ -0,0 +1,48 @@
+//! Settlement for order-book program
+//!
+//! Shared by both matching flows - not part of the vulnerability
+//! demonstration. Which orders reach it is the difference between them.
+
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::BookError;
+use crate::state::{Book, Order, Side};
+
+/// Fill `bid` against `ask` at the ask's price, for as many units as both
+/// have left. Pays `seller` from the bid's escrow, records the price and
+/// takes orders that filled off the book. Returns the units filled.
+pub fn settle<'info>(
+    book: &mut Book,
+    bid: &mut Account<'info, Order>,
+    ask: &mut Account<'info, Order>,
+    seller: &AccountInfo<'info>,
+) -> Result<u64> {
+    require!(bid.price >= ask.price, BookError::PricesDontCross);
+    let quantity = bid.quantity.min(ask.quantity);
+    require!(quantity > 0, BookError::OrderFilled);
+
+    let cost = quantity
+        .checked_mul(ask.price)
+        .ok_or(BookError::MathOverflow)?;
+    transfer_lamports(&bid.to_account_info(), seller, cost)?;
+
+    bid.quantity = bid
+        .quantity
+        .checked_sub(quantity)
+        .ok_or(BookError::MathOverflow)?;
+    ask.quantity = ask
+        .quantity
+        .checked_sub(quantity)
+        .ok_or(BookError::MathOverflow)?;
+    for (order, side) in [(&**bid, Side::Bid), (&**ask, Side::Ask)] {
+        if order.quantity == 0 {
+            book.remove(side, order.sequence);
+        }
+    }
+    book.last_price = ask.price;
+
+    msg!("Filled");
+    sol_log_data(&[&quantity.to_le_bytes(), &ask.price.to_le_bytes()]);
+    Ok(quantity)
+}
@@ -0,0 +1,111 @@
+use solsec_prelude::*;
+
+/// Resting orders each side of the book holds at most
+pub const MAX_ORDERS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Side {
+    /// Buys, best at the highest price
+    Bid,
+    /// Sells, best at the lowest price
+    Ask,
+}
+
+impl Side {
+    /// Whether `price` ranks level with or ahead of `other` on this side.
+    pub fn no_worse(&self, price: u64, other: u64) -> bool {
+        match self {
+            Side::Bid => price >= other,
+            Side::Ask => price <= other,
+        }
+    }
+}
+
+/// A resting order's place in its queue
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct Entry {
+    pub price: u64,
+    pub sequence: u64,
+}
+
+/// Both sides of the book, each in price-time priority
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Book {
+    /// Opened the book, bytes 8..40
+    pub authority: Pubkey,
+    /// Sequence the next order takes, bytes 40..48
+    pub next_sequence: u64,
+    /// Price of the last fill, bytes 48..56
+    pub last_price: u64,
+    /// Bump seed for the book PDA, byte 56
+    pub bump: u8,
+    /// Best bid first, from byte 57
+    #[max_len(MAX_ORDERS)]
+    pub bids: Vec<Entry>,
+    /// Best ask first, after the bids
+    #[max_len(MAX_ORDERS)]
+    pub asks: Vec<Entry>,
+}
+
+const _: () = assert!(8 + Book::INIT_SPACE == 577);
+
+impl Book {
+    pub fn queue(&self, side: Side) -> &Vec<Entry> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    fn queue_mut(&mut self, side: Side) -> &mut Vec<Entry> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    /// The order at the front of `side`'s queue.
+    pub fn best(&self, side: Side) -> Option<Entry> {
+        self.queue(side).first().copied()
+    }
+
+    /// Queue `entry` behind every order at its price or better. Returns
+    /// false if the side is full.
+    pub fn insert(&mut self, side: Side, entry: Entry) -> bool {
+        let queue = self.queue_mut(side);
+        if queue.len() >= MAX_ORDERS {
+            return false;
+        }
+        let at = queue.partition_point(|resting| side.no_worse(resting.price, entry.price));
+        queue.insert(at, entry);
+        true
+    }
+
+    pub fn remove(&mut self, side: Side, sequence: u64) {
+        self.queue_mut(side)
+            .retain(|entry| entry.sequence != sequence);
+    }
+}
+
+/// One resting order, and the lamports a bid escrows
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    /// Placed the order, and is paid when an ask fills, bytes 8..40
+    pub owner: Pubkey,
+    /// Bid or ask, byte 40
+    pub side: Side,
+    /// Lamports per unit, bytes 41..49
+    pub price: u64,
+    /// Units left to fill, bytes 49..57
+    pub quantity: u64,
+    /// Place in time priority, and the PDA seed, bytes 57..65
+    pub sequence: u64,
+    /// Bump seed for the order PDA, byte 65
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Order::INIT_SPACE == 66);
@@ -0,0 +1,57 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::error::BookError;
+use crate::settle::settle;
+use crate::state::{Book, Order, Side};
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Caller-Chosen Orders in a Permissionless Match
+// ---------------------------------------------------------------------------
+// Anyone may crank the book, and the instruction fills the bid and ask the
+// caller passes. Both are real orders on the right sides, and they cross,
+// so every check passes, but nothing ties them to the front of the book.
+// The caller fills a resting bid against their own ask when a cheaper one
+// is waiting, takes an earlier order's turn at the same price, or matches
+// their own bid and ask at a price nobody else would trade at, and the
+// book records it as the last price.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct VulnerableMatchOrders<'info> {
+    pub matcher: Signer<'info>,
+
+    #[account(mut, seeds = [b"book"], bump = book.bump)]
+    pub book: Account<'info, Book>,
+
+    // VULNERABLE: Any bid, not necessarily the best
+    #[account(mut, constraint = bid.side == Side::Bid @ BookError::WrongSide)]
+    pub bid: Account<'info, Order>,
+
+    // VULNERABLE: Any ask, not necessarily the best
+    #[account(mut, constraint = ask.side == Side::Ask @ BookError::WrongSide)]
+    pub ask: Account<'info, Order>,
+
+    /// CHECK: The ask's owner, paid for the fill
+    #[account(mut, address = ask.owner @ BookError::Unauthorized)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableMatchOrders<'info> {
+    /// Fill the bid against the ask. Returns the units filled.
+    /// DANGER: The caller chooses which orders trade, and so the price
+    pub fn match_orders(&mut self) -> Result<u64> {
+        settle(
+            &mut self.book,
+            &mut self.bid,
+            &mut self.ask,
+            &self.seller.to_account_info(),
+        )
+    }
+}
@@ -16,6 +16,7 @@ use pinocchio::{
 
 mod secure;
 mod state;
+#[cfg(feature = "vulnerable")]
 mod vulnerable;
 
 use state::Config;
@@ -34,6 +35,7 @@ pub fn process_instruction(
         .ok_or(ProgramError::InvalidInstructionData)?;
 
     match instruction {
+        #[cfg(feature = "vulnerable")]
         0 => vulnerable::process_read_config(program_id, accounts),
         1 => secure::process_read_config(program_id, accounts),
         2 => initialize_config(program_id, accounts, instruction_data),
@@ -80,3 +82,11 @@ fn initialize_config(program_id: &Address, accounts: &[AccountView], data: &[u8]
 
     Ok(())
 }
+
+/// The program, for its registry entry
+pub struct OwnerCheck;
+
+impl solsec_registry::Catalogued for OwnerCheck {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::OWNER_CHECK;
+}
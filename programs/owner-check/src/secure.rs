@@ -8,6 +8,7 @@ use pinocchio::{
     error::{ProgramError, ProgramResult},
     AccountView, Address,
 };
+use solsec_guards::{require_owner, require_signer};
 
 /// SECURE: Read admin from config WITH owner verification
 pub fn process_read_config(program_id: &Address, accounts: &[AccountView]) -> ProgramResult {
@@ -17,11 +18,8 @@ pub fn process_read_config(program_id: &Address, accounts: &[AccountView]) -> Pr
 
     // ---------------------------------------------------------------------------
     // FIX: Verify the account is owned by our program!
-    // SAFETY: owner() returns a reference to the owner pubkey
     // ---------------------------------------------------------------------------
-    if unsafe { config_account.owner() } != program_id {
-        return Err(ProgramError::IllegalOwner);
-    }
+    require_owner!(config_account, program_id);
 
     // Now we can safely read data
     let config_data = config_account.try_borrow()?;
@@ -35,6 +33,9 @@ pub fn process_read_config(program_id: &Address, accounts: &[AccountView]) -> Pr
         return Err(ProgramError::InvalidAccountData);
     }
 
+    // Anyone can pass the admin's key; only the admin can sign for it
+    require_signer!(caller);
+
     // SECURE: Admin action with verified owner
     Ok(())
 }
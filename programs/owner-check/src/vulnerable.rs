@@ -7,6 +7,9 @@
 
 #![allow(unused)]
 
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
 use pinocchio::{
     error::{ProgramError, ProgramResult},
     AccountView, Address,
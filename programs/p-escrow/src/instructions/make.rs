@@ -55,14 +55,8 @@ pub fn process_make(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         escrow_state.is_active = 1;
     }
 
-    // Transfer tokens to vault
-    pinocchio_token::instructions::Transfer {
-        from: maker_ata,
-        to: vault,
-        authority: maker,
-        amount: amount_to_give,
-    }
-    .invoke()?;
+    // Transfer tokens to vault, under SPL Token or Token-2022
+    crate::token::transfer(token_program, maker_ata, vault, maker, amount_to_give, &[])?;
 
     Ok(())
 }
@@ -4,7 +4,9 @@ pub mod make;
 pub mod refund;
 
 pub use make::process_make;
-pub use refund::{process_secure_refund, process_vulnerable_refund};
+pub use refund::process_secure_refund;
+#[cfg(feature = "vulnerable")]
+pub use refund::process_vulnerable_refund;
 
 use pinocchio::{account_info::AccountInfo, ProgramResult};
 
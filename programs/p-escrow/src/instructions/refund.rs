@@ -16,6 +16,7 @@ use pinocchio::{
 use crate::state::Escrow;
 
 /// VULNERABLE: Refund without validating the recipient
+#[cfg(feature = "vulnerable")]
 pub fn process_vulnerable_refund(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
     let [
         _caller,          // Anyone can call!
@@ -53,13 +54,15 @@ pub fn process_vulnerable_refund(accounts: &[AccountInfo], _data: &[u8]) -> Prog
     let signer = Signer::from(&seeds);
 
     // Transfer tokens to (unvalidated!) destination
-    pinocchio_token::instructions::Transfer {
-        from: vault,
-        to: destination,
-        authority: escrow_account, // Escrow PDA is authority
+    // Escrow PDA is authority
+    crate::token::transfer(
+        token_program,
+        vault,
+        destination,
+        escrow_account,
         amount,
-    }
-    .invoke_signed(&[signer])?;
+        &[signer],
+    )?;
 
     // Mark escrow as inactive
     let escrow = Escrow::from_account_info(escrow_account)?;
@@ -119,13 +122,14 @@ pub fn process_secure_refund(accounts: &[AccountInfo], _data: &[u8]) -> ProgramR
     ];
     let signer = Signer::from(&seeds);
 
-    pinocchio_token::instructions::Transfer {
-        from: vault,
-        to: destination,
-        authority: escrow_account,
+    crate::token::transfer(
+        token_program,
+        vault,
+        destination,
+        escrow_account,
         amount,
-    }
-    .invoke_signed(&[signer])?;
+        &[signer],
+    )?;
 
     // Mark escrow as inactive
     let escrow = Escrow::from_account_info(escrow_account)?;
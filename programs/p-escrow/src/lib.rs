@@ -10,6 +10,7 @@ use pinocchio::{account_info::AccountInfo, entrypoint, pubkey::Pubkey, ProgramRe
 
 pub mod instructions;
 pub mod state;
+pub mod token;
 
 #[cfg(test)]
 mod tests;
@@ -31,6 +32,7 @@ pub static ID: Pubkey = unsafe {
 pub enum EscrowInstruction {
     Make = 0,
     Take = 1,
+    #[cfg(feature = "vulnerable")]
     VulnerableRefund = 2,
     SecureRefund = 3,
 }
@@ -42,6 +44,7 @@ impl TryFrom<&u8> for EscrowInstruction {
         match value {
             0 => Ok(Self::Make),
             1 => Ok(Self::Take),
+            #[cfg(feature = "vulnerable")]
             2 => Ok(Self::VulnerableRefund),
             3 => Ok(Self::SecureRefund),
             _ => Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
@@ -66,9 +69,18 @@ pub fn process_instruction(
     match EscrowInstruction::try_from(discriminator)? {
         EscrowInstruction::Make => instructions::process_make(accounts, data),
         EscrowInstruction::Take => instructions::process_take(accounts, data),
+        #[cfg(feature = "vulnerable")]
         EscrowInstruction::VulnerableRefund => {
             instructions::process_vulnerable_refund(accounts, data)
         }
         EscrowInstruction::SecureRefund => instructions::process_secure_refund(accounts, data),
     }
 }
+
+/// The program, for its registry entry
+pub struct PEscrow;
+
+impl solsec_registry::Catalogued for PEscrow {
+    const VULNERABILITY: &'static solsec_registry::Vulnerability =
+        &solsec_registry::P_ESCROW;
+}
@@ -0,0 +1,57 @@
+//! Token transfers through whichever token program the caller passes
+//!
+//! `pinocchio_token::instructions::Transfer` always calls SPL Token. An
+//! escrow can hold either kind of token, so this builds the same
+//! instruction for the program passed in, once it is SPL Token or
+//! Token-2022.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+/// Token-2022's program id
+pub const TOKEN_2022_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// The token programs an escrow's tokens can live under
+pub const TOKEN_PROGRAMS: [Pubkey; 2] = [pinocchio_token::ID, TOKEN_2022_ID];
+
+/// `pinocchio_token`'s `Transfer` of `amount` from `from` to `to`, sent to
+/// `token_program`. Token-2022 takes the same instruction for mints
+/// without a transfer fee or hook.
+pub fn transfer(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    if !TOKEN_PROGRAMS.contains(token_program.key()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let accounts = [
+        AccountMeta::writable(from.key()),
+        AccountMeta::writable(to.key()),
+        AccountMeta::readonly_signer(authority.key()),
+    ];
+    // Discriminator 3, then the amount
+    let mut data = [3; 9];
+    data[1..].copy_from_slice(&amount.to_le_bytes());
+
+    invoke_signed(
+        &Instruction {
+            program_id: token_program.key(),
+            accounts: &accounts,
+            data: &data,
+        },
+        &[from, to, authority],
+        signers,
+    )
+}
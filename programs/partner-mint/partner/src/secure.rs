@@ -0,0 +1,60 @@
+//! The partner's side of partner-mint's secure mint
+//!
+//! The partner signs with its mint authority PDA, which no other program
+//! and no top-level transaction can sign for.
+
+use partner_mint::program::PartnerMint;
+use partner_mint::state::{Balance, Config, MINT_AUTHORITY_SEED};
+use solsec_prelude::*;
+
+use crate::state::{Receipt, REWARD};
+
+#[derive(Accounts)]
+pub struct SecureClaimReward<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Receipt::INIT_SPACE,
+        seeds = [b"receipt", user.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, constraint = balance.owner == user.key())]
+    pub balance: Account<'info, Balance>,
+
+    // solsec-lint: allow(missing-signer) this program's PDA, it signs the CPI itself
+    /// CHECK: This program's PDA; it signs the CPI and holds nothing
+    #[account(seeds = [MINT_AUTHORITY_SEED], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub minter_program: Program<'info, PartnerMint>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SecureClaimReward<'info> {
+    pub fn claim(&mut self, bumps: &SecureClaimRewardBumps) -> Result<()> {
+        self.receipt.set_inner(Receipt {
+            user: self.user.key(),
+            bump: bumps.receipt,
+        });
+
+        let signer_seeds: &[&[&[u8]]] = &[&[MINT_AUTHORITY_SEED, &[bumps.mint_authority]]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.minter_program.to_account_info(),
+            partner_mint::cpi::accounts::SecureMint {
+                config: self.config.to_account_info(),
+                partner_authority: self.mint_authority.to_account_info(),
+                balance: self.balance.to_account_info(),
+            },
+            signer_seeds,
+        );
+        partner_mint::cpi::secure_mint(cpi_context, REWARD)
+    }
+}
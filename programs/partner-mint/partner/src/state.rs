@@ -0,0 +1,18 @@
+use solsec_prelude::*;
+
+/// Tokens each user's one claim mints
+pub const REWARD: u64 = 100;
+
+/// A user's claim, at `[b"receipt", user]`; it can only be created once
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Receipt {
+    /// Claimed the reward, bytes 8..40
+    pub user: Pubkey,
+    /// Bump seed for the receipt PDA, byte 40
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Receipt::INIT_SPACE == 41);
@@ -0,0 +1,65 @@
+//! The partner's side of partner-mint's vulnerable mint
+//!
+//! Nothing here is wrong: the partner names its own program account, as the
+//! minter asks. The minter is what can't tell this call from anyone else's.
+
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use partner_mint::program::PartnerMint;
+use partner_mint::state::{Balance, Config};
+use solsec_prelude::*;
+
+use crate::program::PartnerRewards;
+use crate::state::{Receipt, REWARD};
+
+#[derive(Accounts)]
+pub struct VulnerableClaimReward<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Receipt::INIT_SPACE,
+        seeds = [b"receipt", user.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, constraint = balance.owner == user.key())]
+    pub balance: Account<'info, Balance>,
+
+    /// This program's own account, which the minter takes as its caller
+    pub partner_program: Program<'info, PartnerRewards>,
+
+    /// CHECK: The minter's cluster record, read by its `check_cluster`
+    #[account(seeds = [b"cluster"], bump, seeds::program = partner_mint::ID)]
+    pub cluster: UncheckedAccount<'info>,
+
+    pub minter_program: Program<'info, PartnerMint>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> VulnerableClaimReward<'info> {
+    pub fn claim(&mut self, bumps: &VulnerableClaimRewardBumps) -> Result<()> {
+        self.receipt.set_inner(Receipt {
+            user: self.user.key(),
+            bump: bumps.receipt,
+        });
+
+        let cpi_context = CpiContext::new(
+            self.minter_program.to_account_info(),
+            partner_mint::cpi::accounts::VulnerableMint {
+                config: self.config.to_account_info(),
+                caller_program: self.partner_program.to_account_info(),
+                balance: self.balance.to_account_info(),
+                cluster: self.cluster.to_account_info(),
+            },
+        );
+        partner_mint::cpi::vulnerable_mint(cpi_context, REWARD)
+    }
+}
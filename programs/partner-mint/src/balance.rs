@@ -0,0 +1,54 @@
+//! Balances and the mint itself for partner-mint program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::MintError;
+use crate::state::{Balance, Config};
+
+#[derive(Accounts)]
+pub struct OpenBalance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", owner.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenBalance<'info> {
+    pub fn open(&mut self, bumps: &OpenBalanceBumps) -> Result<()> {
+        self.balance.set_inner(Balance {
+            owner: self.owner.key(),
+            amount: 0,
+            bump: bumps.balance,
+        });
+        Ok(())
+    }
+}
+
+/// Mint `amount` into `balance`, once the caller has been allowed to.
+pub fn credit(config: &mut Config, balance: &mut Balance, amount: u64) -> Result<()> {
+    require!(amount > 0, MintError::InvalidAmount);
+    config.supply = config
+        .supply
+        .checked_add(amount)
+        .ok_or(MintError::MathOverflow)?;
+    balance.amount = balance
+        .amount
+        .checked_add(amount)
+        .ok_or(MintError::MathOverflow)?;
+
+    msg!("Minted");
+    sol_log_data(&[&amount.to_le_bytes(), balance.owner.as_ref()]);
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum MintError {
+    #[msg("Invalid amount: cannot be zero")]
+    InvalidAmount,
+    #[msg("Caller is not the partner program")]
+    NotPartner,
+    #[msg("Math overflow detected")]
+    MathOverflow,
+}
+
+impl From<MintError> for solsec_errors::ErrorClass {
+    fn from(error: MintError) -> Self {
+        solsec_errors::PARTNER_MINT.class(error as usize)
+    }
+}
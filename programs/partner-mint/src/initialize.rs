@@ -0,0 +1,36 @@
+//! Initialize instruction for partner-mint program
+//!
+//! Shared initialization - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeBumps, partner_program: Pubkey) -> Result<()> {
+        self.config.set_inner(Config {
+            authority: self.authority.key(),
+            partner_program,
+            supply: 0,
+            bump: bumps.config,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Partner Mint Bypass - Anchor Program
+//!
+//! Demonstrates a minter whose mint instruction is reserved for one partner
+//! program, which enforces its own limits before calling in. The vulnerable
+//! version decides a call came from the partner because the caller passed
+//! the partner's program account. Any transaction can pass that account,
+//! directly or from a program of the attacker's own, so anyone mints
+//! without the partner's limits.
+//!
+//! VULNERABILITY: the caller program is taken from an account argument.
+//!
+//! `partner/` is the partner program, and `malicious-caller/` the
+//! attacker's, a fixture the suite deploys beside them.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod balance;
+pub mod error;
+pub mod initialize;
+pub mod secure;
+pub mod state;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+use balance::*;
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use initialize::*;
+use secure::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("6EjzRS6xtjdfSfmBkKKgZT7r4PFRAzszNdVKt1qdETmv");
+
+#[program]
+pub mod partner_mint {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// Initialize the minter, with `partner_program` as the only program
+    /// allowed to mint
+    pub fn initialize(ctx: Context<Initialize>, partner_program: Pubkey) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps, partner_program)
+    }
+
+    /// Open the signer's balance
+    pub fn open_balance(ctx: Context<OpenBalance>) -> Result<()> {
+        ctx.accounts.open(&ctx.bumps)
+    }
+
+    /// VULNERABLE: Mint to a balance when the caller passes the partner's
+    /// program account
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_mint(ctx: Context<VulnerableMint>, amount: u64) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.mint(amount)
+    }
+
+    /// SECURE: Mint to a balance when the partner's PDA signs
+    pub fn secure_mint(ctx: Context<SecureMint>, amount: u64) -> Result<()> {
+        ctx.accounts.mint(amount)
+    }
+}
+
+solsec_prelude::catalogued!(program::PartnerMint, PARTNER_MINT);
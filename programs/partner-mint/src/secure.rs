@@ -0,0 +1,42 @@
+use solsec_prelude::*;
+
+use crate::balance::credit;
+use crate::state::{Balance, Config, MINT_AUTHORITY_SEED};
+
+// ---------------------------------------------------------------------------
+// SECURE: Partner PDA Signature
+// ---------------------------------------------------------------------------
+// FIX: Require a signature from a PDA derived under the partner's program
+// id. Only the partner program can sign for its PDAs, with
+// `invoke_signed`, so the signature proves the partner made the call. A
+// top-level transaction can't sign for a PDA, another program's
+// `invoke_signed` only signs for its own, and passing the partner's PDA
+// on as a signer is a privilege escalation the runtime refuses.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SecureMint<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    // SECURE: Signed for by the partner program, and no one else
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED],
+        bump,
+        seeds::program = config.partner_program
+    )]
+    pub partner_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"balance", balance.owner.as_ref()],
+        bump = balance.bump
+    )]
+    pub balance: Account<'info, Balance>,
+}
+
+impl<'info> SecureMint<'info> {
+    pub fn mint(&mut self, amount: u64) -> Result<()> {
+        credit(&mut self.config, &mut self.balance, amount)
+    }
+}
@@ -0,0 +1,38 @@
+use solsec_prelude::*;
+
+/// Seed of the PDA the partner program signs its mints with, derived
+/// under the partner's program id
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+
+/// The minter's settings and supply
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    /// Set up the minter, bytes 8..40
+    pub authority: Pubkey,
+    /// The only program allowed to mint, bytes 40..72
+    pub partner_program: Pubkey,
+    /// Minted so far, bytes 72..80
+    pub supply: u64,
+    /// Bump seed for the config PDA, byte 80
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Config::INIT_SPACE == 81);
+
+/// Tokens one owner holds, at `[b"balance", owner]`; a balance the
+/// program keeps rather than an SPL token account
+#[account]
+#[derive(InitSpace)]
+pub struct Balance {
+    /// Holds the tokens, bytes 8..40
+    pub owner: Pubkey,
+    /// Tokens held, bytes 40..48
+    pub amount: u64,
+    /// Bump seed for the balance PDA, byte 48
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Balance::INIT_SPACE == 49);
@@ -0,0 +1,55 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::balance::credit;
+use crate::error::MintError;
+use crate::state::{Balance, Config};
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Caller Program Taken From an Account Argument
+// ---------------------------------------------------------------------------
+// Only the partner program may mint, and the partner caps what each user
+// gets before it calls in. The minter decides the partner is calling
+// because the partner's program account was passed: the key matches the
+// config and the account is executable. But an account in the list says
+// nothing about who built the instruction. Any transaction can name the
+// partner's program, at the top level or from a program of the attacker's
+// own, and mint whatever it likes past the partner's cap.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct VulnerableMint<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: VULNERABLE - any transaction can pass this account
+    pub caller_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"balance", balance.owner.as_ref()],
+        bump = balance.bump
+    )]
+    pub balance: Account<'info, Balance>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableMint<'info> {
+    /// DANGER: The partner's program account proves the partner is present,
+    /// not that it is the caller
+    pub fn mint(&mut self, amount: u64) -> Result<()> {
+        require_keys_eq!(
+            self.caller_program.key(),
+            self.config.partner_program,
+            MintError::NotPartner
+        );
+        require!(self.caller_program.executable, MintError::NotPartner);
+
+        credit(&mut self.config, &mut self.balance, amount)
+    }
+}
@@ -1,6 +1,6 @@
 //! Error definitions for PDA Seeds program
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum PdaError {
@@ -9,3 +9,9 @@ pub enum PdaError {
     #[msg("Invalid PDA derivation")]
     InvalidPda,
 }
+
+impl From<PdaError> for solsec_errors::ErrorClass {
+    fn from(error: PdaError) -> Self {
+        solsec_errors::PDA_SECURITY.class(error as usize)
+    }
+}
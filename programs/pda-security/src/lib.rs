@@ -6,16 +6,27 @@
 //! VULNERABILITY: Using easily guessable or insufficient seeds for PDAs.
 //! ATTACK: Attacker pre-computes PDA with known seeds, front-runs creation.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("Cd9nrvpnf83Yfn2B3rV8sFP3TcAY3MTsZtyXgHWwdQ1k");
@@ -24,14 +35,25 @@ declare_id!("Cd9nrvpnf83Yfn2B3rV8sFP3TcAY3MTsZtyXgHWwdQ1k");
 pub mod pda_seeds {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// VULNERABLE: Create a user account with weak seeds (only user pubkey)
     /// Predictable PDA - anyone can compute it
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_create_user(ctx: Context<VulnerableCreateUser>) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.create_user(&ctx.bumps)
     }
 
     /// VULNERABLE: Update user data - vulnerable to seed collision
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_update(ctx: Context<VulnerableUpdate>, data: u64) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts.update(data)
     }
 
@@ -45,3 +67,5 @@ pub mod pda_seeds {
         ctx.accounts.update(data)
     }
 }
+
+solsec_prelude::catalogued!(program::PdaSeeds, PDA_SECURITY);
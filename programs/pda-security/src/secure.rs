@@ -7,7 +7,7 @@
 
 use crate::error::PdaError;
 use crate::state::StrongUserAccount;
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 #[instruction(nonce: u64)]
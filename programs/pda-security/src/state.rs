@@ -1,22 +1,35 @@
 //! State definitions for PDA Seeds program
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 /// User account with weak seeds (vulnerable version)
+///
+/// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace)]
 pub struct WeakUserAccount {
+    /// Bytes 8..40
     pub owner: Pubkey,
+    /// Bytes 40..48
     pub data: u64,
+    /// Byte 48
     pub bump: u8,
 }
 
+const _: () = assert!(8 + WeakUserAccount::INIT_SPACE == 49);
+
 /// User account with strong seeds (secure version)
 #[account]
 #[derive(InitSpace)]
 pub struct StrongUserAccount {
+    /// Bytes 8..40
     pub owner: Pubkey,
-    pub nonce: u64, // Random nonce makes PDA unpredictable
+    /// Random nonce makes PDA unpredictable, bytes 40..48
+    pub nonce: u64,
+    /// Bytes 48..56
     pub data: u64,
+    /// Byte 56
     pub bump: u8,
 }
+
+const _: () = assert!(8 + StrongUserAccount::INIT_SPACE == 57);
@@ -6,8 +6,11 @@
 //!
 //! ATTACK: Attacker can compute the same PDA and front-run account creation
 
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
 use crate::state::WeakUserAccount;
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[derive(Accounts)]
 pub struct VulnerableCreateUser<'info> {
@@ -29,6 +32,10 @@ pub struct VulnerableCreateUser<'info> {
     pub user_account: Account<'info, WeakUserAccount>,
 
     pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableCreateUser<'info> {
@@ -54,6 +61,10 @@ pub struct VulnerableUpdate<'info> {
         bump  // Should use: bump = user_account.bump
     )]
     pub user_account: Account<'info, WeakUserAccount>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableUpdate<'info> {
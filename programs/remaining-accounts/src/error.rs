@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum BatchError {
@@ -11,3 +11,9 @@ pub enum BatchError {
     #[msg("Account not owned by program")]
     InvalidOwner,
 }
+
+impl From<BatchError> for solsec_errors::ErrorClass {
+    fn from(error: BatchError) -> Self {
+        solsec_errors::REMAINING_ACCOUNTS.class(error as usize)
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Shared initialization - not part of the vulnerability demonstration.
 
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::state::BatchConfig;
 
@@ -14,7 +14,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 1,
+        space = 8 + BatchConfig::INIT_SPACE,
         seeds = [b"config"],
         bump
     )]
@@ -5,18 +5,29 @@
 //!
 //! VULNERABILITY: remaining_accounts are not validated by Anchor constraints.
 
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("Eypux1FctAjxFzjEXyP6RGab8brjy2LtBU9dtQThFacP");
@@ -25,6 +36,13 @@ declare_id!("Eypux1FctAjxFzjEXyP6RGab8brjy2LtBU9dtQThFacP");
 pub mod remaining_accounts {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize a batch processor config
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps)
@@ -32,10 +50,12 @@ pub mod remaining_accounts {
 
     /// VULNERABLE: Process rewards to remaining_accounts without validation
     /// Attacker can pass any accounts and receive rewards
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_batch_reward<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, VulnerableBatchReward<'info>>,
         amounts: Vec<u64>,
     ) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
         ctx.accounts
             .process_rewards(ctx.remaining_accounts, amounts)
     }
@@ -48,4 +68,16 @@ pub mod remaining_accounts {
         ctx.accounts
             .process_rewards(ctx.remaining_accounts, amounts)
     }
+
+    /// SECURE: `secure_batch_reward` deserializing each recipient in full,
+    /// the baseline its in-place reads are measured against
+    pub fn secure_batch_reward_deserialized<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SecureBatchReward<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .process_rewards_deserialized(ctx.remaining_accounts, amounts)
+    }
 }
+
+solsec_prelude::catalogued!(program::RemainingAccounts, REMAINING_ACCOUNTS);
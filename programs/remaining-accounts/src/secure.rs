@@ -1,4 +1,8 @@
-use anchor_lang::prelude::*;
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_guards::{
+    discriminator_is, min_len, not_executable, owned_by, read_bool, require_valid, Validator,
+};
+use solsec_prelude::*;
 
 use crate::error::BatchError;
 use crate::state::{BatchConfig, RewardRecipient};
@@ -9,8 +13,13 @@ use crate::ID;
 // ---------------------------------------------------------------------------
 // FIX: Manually validate each remaining_account:
 // 1. Check owner == program_id
-// 2. Deserialize and verify type/discriminator
+// 2. Verify type/discriminator
 // 3. Check business logic (eligibility, etc.)
+//
+// Only `is_eligible` is read, at its offset; deserializing each recipient
+// into a `RewardRecipient` would decode every field for one byte.
+// `process_rewards_deserialized` does, as the baseline both are tested and
+// measured against.
 // ---------------------------------------------------------------------------
 
 #[derive(Accounts)]
@@ -38,30 +47,50 @@ impl<'info> SecureBatchReward<'info> {
         // SECURE: Verify count matches
         require!(remaining.len() == amounts.len(), BatchError::CountMismatch);
 
-        for (i, account_info) in remaining.iter().enumerate() {
-            let amount = amounts[i];
+        // What every recipient must be: a RewardRecipient record, not a
+        // program, long enough to hold every field
+        let recipient = discriminator_is(RewardRecipient::DISCRIMINATOR)
+            .and(not_executable())
+            .and(min_len(RewardRecipient::LEN));
 
+        for (account_info, amount) in remaining.iter().zip(&amounts) {
             // SECURE: Step 1 - Verify owner is our program
-            require!(account_info.owner == &ID, BatchError::InvalidOwner);
+            require_valid!(account_info, owned_by(&ID), BatchError::InvalidOwner);
 
-            // SECURE: Step 2 - Deserialize and verify type
+            // SECURE: Step 2 - Verify type (the Anchor discriminator)
+            require_valid!(account_info, recipient, BatchError::InvalidAccount);
+
+            // SECURE: Step 3 - Check eligibility, reading only that field
+            let eligible = read_bool(account_info, RewardRecipient::IS_ELIGIBLE_OFFSET)
+                .map_err(|_| BatchError::InvalidAccount)?;
+            require!(eligible, BatchError::NotEligible);
+
+            // SECURE: Log the recipient and amount as raw bytes - a formatted
+            // `msg!` per recipient allocates and base58-encodes every key
+            sol_log_data(&[account_info.key.as_ref(), &amount.to_le_bytes()]);
+        }
+
+        Ok(())
+    }
+
+    /// The same checks, deserializing each recipient in full.
+    pub fn process_rewards_deserialized(
+        &self,
+        remaining: &[AccountInfo<'info>],
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        require!(remaining.len() == amounts.len(), BatchError::CountMismatch);
+
+        for (account_info, amount) in remaining.iter().zip(&amounts) {
+            require_valid!(account_info, owned_by(&ID), BatchError::InvalidOwner);
+
+            // Checks the discriminator, then decodes every field
             let data = account_info.try_borrow_data()?;
+            let recipient = RewardRecipient::try_deserialize(&mut &data[..])
+                .map_err(|_| BatchError::InvalidAccount)?;
+            require!(recipient.is_eligible, BatchError::NotEligible);
 
-            // Check discriminator (first 8 bytes for Anchor accounts)
-            // RewardRecipient discriminator would be checked here
-            if data.len() < 8 {
-                return Err(BatchError::InvalidAccount.into());
-            }
-
-            // SECURE: Step 3 - Deserialize and check eligibility
-            // In real code: let recipient = RewardRecipient::try_deserialize(&mut &data[..])?;
-            // require!(recipient.is_eligible, BatchError::NotEligible);
-
-            msg!(
-                "SECURE: Validated and rewarding {} with {}",
-                account_info.key(),
-                amount
-            );
+            sol_log_data(&[account_info.key.as_ref(), &amount.to_le_bytes()]);
         }
 
         Ok(())
@@ -1,15 +1,29 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
+// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
+#[derive(InitSpace)]
 pub struct BatchConfig {
-    pub authority: Pubkey,
-    pub bump: u8,
+    pub authority: Pubkey, // bytes 8..40
+    pub bump: u8,          // byte 40
 }
 
+const _: () = assert!(8 + BatchConfig::INIT_SPACE == 41);
+
 /// Structure for validated reward recipient
 #[account]
+#[derive(InitSpace)]
 pub struct RewardRecipient {
-    pub owner: Pubkey,
-    pub is_eligible: bool,
-    pub bump: u8,
+    pub owner: Pubkey,     // bytes 8..40
+    pub is_eligible: bool, // byte 40
+    pub bump: u8,          // byte 41
+}
+
+const _: () = assert!(8 + RewardRecipient::INIT_SPACE == 42);
+
+impl RewardRecipient {
+    /// Data length, the discriminator included
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+    /// Where `is_eligible` sits, for reading it in place
+    pub const IS_ELIGIBLE_OFFSET: usize = 40;
 }
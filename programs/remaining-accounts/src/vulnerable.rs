@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
 
 use crate::state::BatchConfig;
 
@@ -22,6 +25,10 @@ pub struct VulnerableBatchReward<'info> {
     pub config: Account<'info, BatchConfig>,
     // remaining_accounts will contain reward recipients
     // BUT THEY ARE NOT VALIDATED!
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableBatchReward<'info> {
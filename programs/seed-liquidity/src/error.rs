@@ -0,0 +1,29 @@
+use solsec_prelude::*;
+
+#[error_code]
+pub enum LaunchError {
+    #[msg("Unauthorized - not the launch creator")]
+    Unauthorized,
+    #[msg("Invalid amount: cannot be zero")]
+    InvalidAmount,
+    #[msg("Seed liquidity below the minimum")]
+    SeedTooSmall,
+    #[msg("Seed liquidity is still locked")]
+    LiquidityLocked,
+    #[msg("More shares than the pool has")]
+    InsufficientShares,
+    #[msg("Holding has fewer tokens than the sale")]
+    InsufficientTokens,
+    #[msg("More lamports than the reserve holds")]
+    InsufficientReserve,
+    #[msg("Slippage exceeded")]
+    SlippageExceeded,
+    #[msg("Math overflow detected")]
+    MathOverflow,
+}
+
+impl From<LaunchError> for solsec_errors::ErrorClass {
+    fn from(error: LaunchError) -> Self {
+        solsec_errors::SEED_LIQUIDITY.class(error as usize)
+    }
+}
@@ -0,0 +1,39 @@
+//! Open-holding instruction for seed-liquidity program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+
+use solsec_prelude::*;
+
+use crate::state::{Holding, Launch};
+
+#[derive(Accounts)]
+pub struct OpenHolding<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"launch", launch.creator.as_ref()], bump = launch.bump)]
+    pub launch: Account<'info, Launch>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Holding::INIT_SPACE,
+        seeds = [b"holding", launch.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub holding: Account<'info, Holding>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenHolding<'info> {
+    pub fn open(&mut self, bumps: &OpenHoldingBumps) -> Result<()> {
+        self.holding.set_inner(Holding {
+            owner: self.owner.key(),
+            launch: self.launch.key(),
+            tokens: 0,
+            bump: bumps.holding,
+        });
+        Ok(())
+    }
+}
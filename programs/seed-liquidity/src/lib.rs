@@ -0,0 +1,105 @@
+//! Seed Liquidity Rug - Anchor Program
+//!
+//! Demonstrates a token launch whose creator seeds a constant-product pool
+//! of lamports against the token's supply, then lets anyone buy. The
+//! vulnerable launch accepts any seed, however thin, and keeps an admin
+//! instruction that withdraws the lamport side of the pool straight to the
+//! creator. Buyers' lamports pile up in the pool; the creator takes them,
+//! and the tokens they bought can no longer be sold for anything.
+//!
+//! VULNERABILITY: the creator can take one side of the reserves at will.
+
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
+
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
+pub mod error;
+pub mod holding;
+pub mod secure;
+pub mod state;
+pub mod trade;
+#[cfg(feature = "vulnerable")]
+pub mod vulnerable;
+
+#[cfg(feature = "vulnerable")]
+use cluster::*;
+use holding::*;
+use secure::*;
+use trade::*;
+#[cfg(feature = "vulnerable")]
+use vulnerable::*;
+
+declare_id!("BQYYwL2NPVVE6C4EwxaBMnejrmKyKRC9z2MPj6tbCzFn");
+
+#[program]
+pub mod seed_liquidity {
+    use super::*;
+
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
+    /// VULNERABLE: Launch with any seed, and nothing locked
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_initialize(
+        ctx: Context<VulnerableInitialize>,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts
+            .initialize(&ctx.bumps, sol_amount, token_amount)
+    }
+
+    /// VULNERABLE: Withdraw lamports from the pool's reserve to the creator
+    #[cfg(feature = "vulnerable")]
+    pub fn vulnerable_withdraw_reserve(
+        ctx: Context<VulnerableWithdrawReserve>,
+        amount: u64,
+    ) -> Result<()> {
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        ctx.accounts.withdraw(amount)
+    }
+
+    /// SECURE: Launch with a minimum seed, its shares locked
+    pub fn secure_initialize(
+        ctx: Context<SecureInitialize>,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize(&ctx.bumps, sol_amount, token_amount)
+    }
+
+    /// SECURE: Redeem shares for both reserves, pro rata, once unlocked
+    pub fn secure_remove_liquidity(ctx: Context<SecureRemoveLiquidity>, shares: u64) -> Result<()> {
+        ctx.accounts.remove(shares)
+    }
+
+    /// Open the signer's holding in a launch
+    pub fn open_holding(ctx: Context<OpenHolding>) -> Result<()> {
+        ctx.accounts.open(&ctx.bumps)
+    }
+
+    /// Buy tokens for `sol_in` lamports. Returns the tokens bought.
+    pub fn buy(ctx: Context<Buy>, sol_in: u64, min_tokens_out: u64) -> Result<u64> {
+        ctx.accounts.buy(sol_in, min_tokens_out)
+    }
+
+    /// Sell `tokens_in` tokens for lamports. Returns the lamports paid.
+    pub fn sell(ctx: Context<Sell>, tokens_in: u64, min_sol_out: u64) -> Result<u64> {
+        ctx.accounts.sell(tokens_in, min_sol_out)
+    }
+}
+
+solsec_prelude::catalogued!(program::SeedLiquidity, SEED_LIQUIDITY);
@@ -0,0 +1,145 @@
+use anchor_lang::solana_program::log::sol_log_data;
+use solsec_prelude::*;
+
+use crate::error::LaunchError;
+use crate::state::{Holding, Launch, LOCK_SLOTS, MIN_SEED_LAMPORTS};
+
+// ---------------------------------------------------------------------------
+// SECURE: Locked Seed Liquidity, Redeemed Only Pro Rata
+// ---------------------------------------------------------------------------
+// FIX: Require a real lamport reserve behind the launch price, and lock the
+// creator's shares for `LOCK_SLOTS` after the launch. There is no
+// instruction that moves one reserve on its own: the creator's only way
+// out is redeeming shares, which takes both reserves in proportion and
+// leaves the price buyers sell at where it was. Until the unlock, every
+// lamport a buyer paid in can be sold back for.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct SecureInitialize<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Launch::INIT_SPACE,
+        seeds = [b"launch", creator.key().as_ref()],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SecureInitialize<'info> {
+    /// Seed the pool with `sol_amount` lamports against `token_amount`
+    /// tokens, and give the creator a share per lamport, locked for
+    /// `LOCK_SLOTS`.
+    pub fn initialize(
+        &mut self,
+        bumps: &SecureInitializeBumps,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        require!(token_amount > 0, LaunchError::InvalidAmount);
+        // SECURE: The creator's own lamports stand behind the price
+        require!(sol_amount >= MIN_SEED_LAMPORTS, LaunchError::SeedTooSmall);
+
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: self.creator.to_account_info(),
+                to: self.launch.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, sol_amount)?;
+
+        // SECURE: The shares can't be redeemed until the lock expires
+        let unlock_slot = Clock::get()?
+            .slot
+            .checked_add(LOCK_SLOTS)
+            .ok_or(LaunchError::MathOverflow)?;
+        self.launch.set_inner(Launch {
+            creator: self.creator.key(),
+            sol_reserve: sol_amount,
+            token_reserve: token_amount,
+            total_shares: sol_amount,
+            unlock_slot,
+            bump: bumps.launch,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SecureRemoveLiquidity<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"launch", creator.key().as_ref()],
+        bump = launch.bump,
+        has_one = creator @ LaunchError::Unauthorized
+    )]
+    pub launch: Account<'info, Launch>,
+
+    /// Where the creator's share of the token reserve goes
+    #[account(
+        mut,
+        seeds = [b"holding", launch.key().as_ref(), creator.key().as_ref()],
+        bump = holding.bump
+    )]
+    pub holding: Account<'info, Holding>,
+}
+
+impl<'info> SecureRemoveLiquidity<'info> {
+    /// Redeem `shares` for their part of both reserves.
+    pub fn remove(&mut self, shares: u64) -> Result<()> {
+        require!(shares > 0, LaunchError::InvalidAmount);
+        // SECURE: Nothing leaves before the lock expires
+        require!(
+            Clock::get()?.slot >= self.launch.unlock_slot,
+            LaunchError::LiquidityLocked
+        );
+        let launch = &mut self.launch;
+        require!(
+            shares <= launch.total_shares,
+            LaunchError::InsufficientShares
+        );
+
+        // SECURE: Both sides in proportion, so the price doesn't move
+        let sol_out = solsec_math::pro_rata(launch.sol_reserve, shares, launch.total_shares)
+            .map_err(|_| LaunchError::MathOverflow)?;
+        let tokens_out = solsec_math::pro_rata(launch.token_reserve, shares, launch.total_shares)
+            .map_err(|_| LaunchError::MathOverflow)?;
+
+        launch.total_shares = launch
+            .total_shares
+            .checked_sub(shares)
+            .ok_or(LaunchError::InsufficientShares)?;
+        launch.sol_reserve = launch
+            .sol_reserve
+            .checked_sub(sol_out)
+            .ok_or(LaunchError::MathOverflow)?;
+        launch.token_reserve = launch
+            .token_reserve
+            .checked_sub(tokens_out)
+            .ok_or(LaunchError::MathOverflow)?;
+        self.holding.tokens = self
+            .holding
+            .tokens
+            .checked_add(tokens_out)
+            .ok_or(LaunchError::MathOverflow)?;
+        transfer_lamports(
+            &launch.to_account_info(),
+            &self.creator.to_account_info(),
+            sol_out,
+        )?;
+
+        msg!("Removed liquidity");
+        sol_log_data(&[&sol_out.to_le_bytes(), &tokens_out.to_le_bytes()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use solsec_prelude::*;
+
+/// Lamports the secure initializer requires behind the price it sets
+pub const MIN_SEED_LAMPORTS: u64 = 1_000_000_000;
+/// Slots the creator's shares stay locked after a secure launch, about a
+/// day at 400ms a slot
+pub const LOCK_SLOTS: u64 = 216_000;
+
+/// A token launch priced by a constant-product pool of lamports against
+/// the token's supply
+///
+/// The token is a balance in this program rather than a mint, so the demo
+/// needs no token accounts; what matters is who can take the lamports.
+/// Offsets are into the account data, after the 8-byte discriminator.
+#[account]
+#[derive(InitSpace)]
+pub struct Launch {
+    /// Seeded the pool and holds its shares, bytes 8..40
+    pub creator: Pubkey,
+    /// Lamports in the pool, above its rent, bytes 40..48
+    pub sol_reserve: u64,
+    /// Tokens in the pool, bytes 48..56
+    pub token_reserve: u64,
+    /// Shares in the pool, all of them the creator's, bytes 56..64
+    pub total_shares: u64,
+    /// First slot the shares can be redeemed in, bytes 64..72
+    pub unlock_slot: u64,
+    /// Bump seed for the launch PDA, byte 72
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Launch::INIT_SPACE == 73);
+
+/// Tokens one user bought from a launch
+#[account]
+#[derive(InitSpace)]
+pub struct Holding {
+    /// Can sell the tokens, bytes 8..40
+    pub owner: Pubkey,
+    /// The launch they came from, bytes 40..72
+    pub launch: Pubkey,
+    /// Tokens held, bytes 72..80
+    pub tokens: u64,
+    /// Bump seed for the holding PDA, byte 80
+    pub bump: u8,
+}
+
+const _: () = assert!(8 + Holding::INIT_SPACE == 81);
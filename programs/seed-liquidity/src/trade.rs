@@ -0,0 +1,116 @@
+//! Buy and sell instructions for seed-liquidity program
+//!
+//! Shared by both flows - not part of the vulnerability demonstration.
+//! Both price on the pool's reserves with no fee, so whatever the creator
+//! takes out of a reserve comes out of what buyers can sell for.
+
+use solsec_prelude::*;
+
+use crate::error::LaunchError;
+use crate::state::{Holding, Launch};
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut, seeds = [b"launch", launch.creator.as_ref()], bump = launch.bump)]
+    pub launch: Account<'info, Launch>,
+
+    #[account(
+        mut,
+        seeds = [b"holding", launch.key().as_ref(), buyer.key().as_ref()],
+        bump = holding.bump
+    )]
+    pub holding: Account<'info, Holding>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Buy<'info> {
+    pub fn buy(&mut self, sol_in: u64, min_tokens_out: u64) -> Result<u64> {
+        require!(sol_in > 0, LaunchError::InvalidAmount);
+        let launch = &mut self.launch;
+        let tokens_out = solsec_math::swap_out(sol_in, launch.sol_reserve, launch.token_reserve, 0)
+            .map_err(|_| LaunchError::MathOverflow)?;
+        require!(
+            tokens_out > 0 && tokens_out >= min_tokens_out,
+            LaunchError::SlippageExceeded
+        );
+
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: self.buyer.to_account_info(),
+                to: launch.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, sol_in)?;
+
+        launch.sol_reserve = launch
+            .sol_reserve
+            .checked_add(sol_in)
+            .ok_or(LaunchError::MathOverflow)?;
+        // The swap pays out less than the reserve
+        launch.token_reserve = launch
+            .token_reserve
+            .checked_sub(tokens_out)
+            .ok_or(LaunchError::MathOverflow)?;
+        self.holding.tokens = self
+            .holding
+            .tokens
+            .checked_add(tokens_out)
+            .ok_or(LaunchError::MathOverflow)?;
+        Ok(tokens_out)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Sell<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(mut, seeds = [b"launch", launch.creator.as_ref()], bump = launch.bump)]
+    pub launch: Account<'info, Launch>,
+
+    #[account(
+        mut,
+        seeds = [b"holding", launch.key().as_ref(), seller.key().as_ref()],
+        bump = holding.bump
+    )]
+    pub holding: Account<'info, Holding>,
+}
+
+impl<'info> Sell<'info> {
+    pub fn sell(&mut self, tokens_in: u64, min_sol_out: u64) -> Result<u64> {
+        require!(tokens_in > 0, LaunchError::InvalidAmount);
+        self.holding.tokens = self
+            .holding
+            .tokens
+            .checked_sub(tokens_in)
+            .ok_or(LaunchError::InsufficientTokens)?;
+
+        let launch = &mut self.launch;
+        let sol_out = solsec_math::swap_out(tokens_in, launch.token_reserve, launch.sol_reserve, 0)
+            .map_err(|_| LaunchError::MathOverflow)?;
+        require!(
+            sol_out > 0 && sol_out >= min_sol_out,
+            LaunchError::SlippageExceeded
+        );
+
+        launch.token_reserve = launch
+            .token_reserve
+            .checked_add(tokens_in)
+            .ok_or(LaunchError::MathOverflow)?;
+        launch.sol_reserve = launch
+            .sol_reserve
+            .checked_sub(sol_out)
+            .ok_or(LaunchError::MathOverflow)?;
+        transfer_lamports(
+            &launch.to_account_info(),
+            &self.seller.to_account_info(),
+            sol_out,
+        )?;
+        Ok(sol_out)
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
+
+use crate::error::LaunchError;
+use crate::state::Launch;
+
+// ---------------------------------------------------------------------------
+// VULNERABILITY: Unlocked Seed Liquidity and a One-Sided Admin Withdrawal
+// ---------------------------------------------------------------------------
+// The creator seeds the pool with any amounts, so a launch can put a dust
+// lamport reserve behind the whole token supply: the price is whatever the
+// creator says, and nearly every lamport in the pool soon belongs to
+// buyers. Nothing locks the seed, and an admin instruction moves lamports
+// out of the reserve straight to the creator. It takes one side only, so
+// the tokens buyers hold are priced against a reserve that is gone, and a
+// sale pays nothing.
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct VulnerableInitialize<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Launch::INIT_SPACE,
+        seeds = [b"launch", creator.key().as_ref()],
+        bump
+    )]
+    pub launch: Account<'info, Launch>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableInitialize<'info> {
+    /// Seed the pool with `sol_amount` lamports against `token_amount`
+    /// tokens, and give the creator a share per lamport.
+    /// DANGER: Any seed, and the shares are never locked
+    pub fn initialize(
+        &mut self,
+        bumps: &VulnerableInitializeBumps,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        // VULNERABLE: Only non-zero; a single lamport prices the supply
+        require!(
+            sol_amount > 0 && token_amount > 0,
+            LaunchError::InvalidAmount
+        );
+
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: self.creator.to_account_info(),
+                to: self.launch.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, sol_amount)?;
+
+        self.launch.set_inner(Launch {
+            creator: self.creator.key(),
+            sol_reserve: sol_amount,
+            token_reserve: token_amount,
+            total_shares: sol_amount,
+            // VULNERABLE: Redeemable from the start
+            unlock_slot: 0,
+            bump: bumps.launch,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct VulnerableWithdrawReserve<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"launch", creator.key().as_ref()],
+        bump = launch.bump,
+        has_one = creator @ LaunchError::Unauthorized
+    )]
+    pub launch: Account<'info, Launch>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
+}
+
+impl<'info> VulnerableWithdrawReserve<'info> {
+    /// Move `amount` lamports of the reserve to the creator.
+    /// DANGER: One side of the pool, buyers' lamports included, at will
+    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+        // VULNERABLE: The token reserve stays, priced against nothing
+        self.launch.sol_reserve = self
+            .launch
+            .sol_reserve
+            .checked_sub(amount)
+            .ok_or(LaunchError::InsufficientReserve)?;
+        transfer_lamports(
+            &self.launch.to_account_info(),
+            &self.creator.to_account_info(),
+            amount,
+        )?;
+        msg!("Withdrew {} from the reserve", amount);
+        Ok(())
+    }
+}
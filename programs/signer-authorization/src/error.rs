@@ -1,7 +1,13 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 #[error_code]
 pub enum VaultError {
     #[msg("Unauthorized: caller is not the vault authority")]
     UnauthorizedAuthority,
 }
+
+impl From<VaultError> for solsec_errors::ErrorClass {
+    fn from(error: VaultError) -> Self {
+        solsec_errors::SIGNER_AUTHORIZATION.class(error as usize)
+    }
+}
@@ -1,15 +1,26 @@
-#![allow(unexpected_cfgs)]
+// Anchor 0.31 and what its expansions call, deprecated in the newer Solana
+// crates it shares with 0.32
+#![cfg_attr(feature = "anchor-031", allow(deprecated))]
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
 
+use solsec_prelude::*;
+
+#[cfg(feature = "vulnerable")]
+solsec_prelude::record_cluster!();
 pub mod error;
 pub mod initialize;
 pub mod secure;
 pub mod state;
+#[cfg(feature = "vulnerable")]
 pub mod vulnerable;
 
+#[cfg(feature = "vulnerable")]
+use cluster::*;
 use initialize::*;
 use secure::*;
+#[cfg(feature = "vulnerable")]
 use vulnerable::*;
 
 declare_id!("EXnhqXwkDbL63d2UPbERQ4BQSubRyLHwCJLiKhhW7zba");
@@ -18,6 +29,13 @@ declare_id!("EXnhqXwkDbL63d2UPbERQ4BQSubRyLHwCJLiKhhW7zba");
 pub mod signer_authorization {
     use super::*;
 
+    /// Record the genesis hash of the cluster the program was deployed to.
+    /// Only the upgrade authority can, once; see `cluster`.
+    #[cfg(feature = "vulnerable")]
+    pub fn record_cluster(ctx: Context<RecordCluster>, genesis_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.record(genesis_hash)
+    }
+
     /// Initialize a new vault for the signer.
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize(&ctx.bumps)
@@ -30,13 +48,21 @@ pub mod signer_authorization {
 
     /// VULNERABLE: Withdraw without proper signer validation.
     /// Demonstrates the vulnerability - anyone can drain funds.
+    #[cfg(feature = "vulnerable")]
     pub fn vulnerable_withdraw(ctx: Context<VulnerableWithdraw>, amount: u64) -> Result<()> {
-        ctx.accounts.withdraw(amount)
+        check_cluster(&ctx.accounts.cluster.to_account_info(), &ID)?;
+        let event = ctx.accounts.withdraw(amount)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     /// SECURE: Withdraw with proper signer validation.
     /// Only the vault authority can withdraw funds.
     pub fn secure_withdraw(ctx: Context<SecureWithdraw>, amount: u64) -> Result<()> {
-        ctx.accounts.withdraw(amount)
+        let event = ctx.accounts.withdraw(amount)?;
+        emit_cpi!(event);
+        Ok(())
     }
 }
+
+solsec_prelude::catalogued!(program::SignerAuthorization, SIGNER_AUTHORIZATION);
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 use crate::error::VaultError;
 use crate::state::Vault;
@@ -10,6 +10,7 @@ use crate::state::Vault;
 // the transaction. Combined with constraint check against stored authority.
 // ---------------------------------------------------------------------------
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SecureWithdraw<'info> {
     #[account(
@@ -24,6 +25,7 @@ pub struct SecureWithdraw<'info> {
     /// SECURE: Signer type enforces that this account must sign the transaction.
     pub authority: Signer<'info>,
 
+    // solsec-lint: allow(unchecked-account) any account may receive lamports
     #[account(mut)]
     /// CHECK: Destination for lamports
     pub destination: UncheckedAccount<'info>,
@@ -32,12 +34,23 @@ pub struct SecureWithdraw<'info> {
 impl<'info> SecureWithdraw<'info> {
     /// Withdraw lamports from vault.
     /// Only the vault authority can call this.
-    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
-        // Modern pattern: use Lamports trait for direct transfers
-        // Safe because Signer constraint ensures authority signed
-        self.vault.sub_lamports(amount)?;
-        self.destination.add_lamports(amount)?;
+    pub fn withdraw(&mut self, amount: u64) -> Result<SecurityEvent> {
+        // Safe because Signer constraint ensures authority signed; the
+        // vault record must survive the withdrawal
+        transfer_lamports(
+            &self.vault.to_account_info(),
+            &self.destination.to_account_info(),
+            amount,
+        )?;
 
-        Ok(())
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::Withdrawal {
+                vault: self.vault.key().to_bytes(),
+                authority: self.authority.key().to_bytes(),
+                destination: self.destination.key().to_bytes(),
+                amount,
+            },
+        ))
     }
 }
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use solsec_prelude::*;
 
 // ---------------------------------------------------------------------------
 // Vault State
@@ -6,9 +6,12 @@ use anchor_lang::prelude::*;
 // Stores lamports with an authority who can withdraw.
 // ---------------------------------------------------------------------------
 
+// Offsets are into the account data, after the 8-byte discriminator.
 #[account]
 #[derive(InitSpace)]
 pub struct Vault {
-    pub authority: Pubkey,
-    pub bump: u8,
+    pub authority: Pubkey, // bytes 8..40
+    pub bump: u8,          // byte 40
 }
+
+const _: () = assert!(8 + Vault::INIT_SPACE == 41);
@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+#[cfg(not(feature = "vulnerable"))]
+compile_error!("vulnerable instructions are only built with the `vulnerable` feature");
+
+use solsec_prelude::*;
 
 use crate::state::Vault;
 
@@ -9,6 +12,7 @@ use crate::state::Vault;
 // the vault.authority pubkey without signing, allowing unauthorized withdrawals.
 // ---------------------------------------------------------------------------
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct VulnerableWithdraw<'info> {
     #[account(
@@ -25,17 +29,29 @@ pub struct VulnerableWithdraw<'info> {
     #[account(mut)]
     /// CHECK: Destination for lamports
     pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: The program's cluster record, read by `check_cluster`
+    #[account(seeds = [b"cluster"], bump)]
+    pub cluster: UncheckedAccount<'info>,
 }
 
 impl<'info> VulnerableWithdraw<'info> {
     /// Withdraw lamports from vault.
     /// DANGER: No signature verification - anyone can drain!
-    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+    pub fn withdraw(&mut self, amount: u64) -> Result<SecurityEvent> {
         // Modern pattern: use Lamports trait for direct transfers
         // This is vulnerable because we don't verify authority signed
         self.vault.sub_lamports(amount)?;
         self.destination.add_lamports(amount)?;
 
-        Ok(())
+        Ok(SecurityEvent::new(
+            crate::ID.to_bytes(),
+            EventKind::Withdrawal {
+                vault: self.vault.key().to_bytes(),
+                authority: self.authority.key().to_bytes(),
+                destination: self.destination.key().to_bytes(),
+                amount,
+            },
+        ))
     }
 }
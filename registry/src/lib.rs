@@ -0,0 +1,1385 @@
+//! Machine-readable registry of the vulnerabilities the programs demonstrate
+//!
+//! One [`Vulnerability`] per program directory: its class, severity, CVSS
+//! [`Score`], the [`Weakness`] ids it goes by elsewhere, the
+//! instructions that are exploitable, the [`Mitigation`] that fixes them,
+//! the files where each side lives and the tests that cover it. This is the
+//! one copy of that metadata: `cargo xtask list` prints it, `cargo xtask
+//! report` writes a report per entry from its test suite, the coverage
+//! matrix checks its tests exist, and `tests/harness.rs` checks it against
+//! the tree, so a renamed instruction or a new program shows up as a failing
+//! test rather than stale docs.
+//!
+//! Every program crate ties itself to its entry by implementing
+//! [`Catalogued`], and so does each [`Variant`], the same program written
+//! again for another framework under `programs-native/` or
+//! `programs-pinocchio/`. The crate is `no_std` so the Pinocchio programs can
+//! depend on it; the `std` feature adds the helpers that build paths.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// What kind of mistake the vulnerable instruction makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VulnClass {
+    /// Acting for an authority that didn't sign or isn't the stored one
+    Authorization,
+    Arithmetic,
+    /// Trusting an account's owner, type or identity without checking it
+    AccountValidation,
+    PdaDerivation,
+    /// Closing, initializing or reviving accounts
+    AccountLifecycle,
+    /// Reading state a CPI has changed underneath
+    StaleState,
+    /// Letting someone else block a legitimate call
+    DenialOfService,
+    /// Several of the above in one program
+    Combined,
+}
+
+impl VulnClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            VulnClass::Authorization => "authorization",
+            VulnClass::Arithmetic => "arithmetic",
+            VulnClass::AccountValidation => "account-validation",
+            VulnClass::PdaDerivation => "pda-derivation",
+            VulnClass::AccountLifecycle => "account-lifecycle",
+            VulnClass::StaleState => "stale-state",
+            VulnClass::DenialOfService => "denial-of-service",
+            VulnClass::Combined => "combined",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Framework {
+    Anchor,
+    Pinocchio,
+    /// A raw `solana-program` entrypoint, with no framework
+    Native,
+}
+
+impl Framework {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Framework::Anchor => "anchor",
+            Framework::Pinocchio => "pinocchio",
+            Framework::Native => "native",
+        }
+    }
+}
+
+/// A CVSS v3.1 base vector: how an attacker reaches the hole and what it
+/// costs the victim once they have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub attack_vector: AttackVector,
+    pub attack_complexity: AttackComplexity,
+    pub privileges_required: PrivilegesRequired,
+    pub user_interaction: UserInteraction,
+    pub scope: Scope,
+    pub confidentiality: Impact,
+    pub integrity: Impact,
+    pub availability: Impact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttackVector {
+    Network,
+    Adjacent,
+    Local,
+    Physical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttackComplexity {
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrivilegesRequired {
+    None,
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserInteraction {
+    None,
+    Required,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Unchanged,
+    /// The exploit reaches state other programs or accounts own
+    Changed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Impact {
+    None,
+    Low,
+    High,
+}
+
+impl Impact {
+    fn weight(self) -> f64 {
+        match self {
+            Impact::None => 0.0,
+            Impact::Low => 0.22,
+            Impact::High => 0.56,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn abbrev(self) -> &'static str {
+        match self {
+            Impact::None => "N",
+            Impact::Low => "L",
+            Impact::High => "H",
+        }
+    }
+}
+
+impl Score {
+    /// The base score, 0.0 to 10.0, by the v3.1 specification's formulas
+    /// and rounding.
+    pub fn base_score(&self) -> f64 {
+        let iss = 1.0
+            - (1.0 - self.confidentiality.weight())
+                * (1.0 - self.integrity.weight())
+                * (1.0 - self.availability.weight());
+        let changed = self.scope == Scope::Changed;
+        let impact = if changed {
+            7.52 * (iss - 0.029) - 3.25 * pow(iss - 0.02, 15)
+        } else {
+            6.42 * iss
+        };
+        if impact <= 0.0 {
+            return 0.0;
+        }
+
+        let attack_vector = match self.attack_vector {
+            AttackVector::Network => 0.85,
+            AttackVector::Adjacent => 0.62,
+            AttackVector::Local => 0.55,
+            AttackVector::Physical => 0.2,
+        };
+        let attack_complexity = match self.attack_complexity {
+            AttackComplexity::Low => 0.77,
+            AttackComplexity::High => 0.44,
+        };
+        let privileges_required = match (self.privileges_required, changed) {
+            (PrivilegesRequired::None, _) => 0.85,
+            (PrivilegesRequired::Low, false) => 0.62,
+            (PrivilegesRequired::Low, true) => 0.68,
+            (PrivilegesRequired::High, false) => 0.27,
+            (PrivilegesRequired::High, true) => 0.5,
+        };
+        let user_interaction = match self.user_interaction {
+            UserInteraction::None => 0.85,
+            UserInteraction::Required => 0.62,
+        };
+        let exploitability =
+            8.22 * attack_vector * attack_complexity * privileges_required * user_interaction;
+
+        let sum = impact + exploitability;
+        roundup(if changed { 1.08 * sum } else { sum }.min(10.0))
+    }
+
+    /// The specification's rating for [`Score::base_score`], `None` for 0.0.
+    /// This is what the vector says; [`Vulnerability::severity`] is the
+    /// template's own call and may differ.
+    pub fn rating(&self) -> Option<Severity> {
+        match self.base_score() {
+            s if s >= 9.0 => Some(Severity::Critical),
+            s if s >= 7.0 => Some(Severity::High),
+            s if s >= 4.0 => Some(Severity::Medium),
+            s if s > 0.0 => Some(Severity::Low),
+            _ => None,
+        }
+    }
+
+    /// The vector string, as in `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+    #[cfg(feature = "std")]
+    pub fn vector(&self) -> String {
+        format!(
+            "CVSS:3.1/AV:{}/AC:{}/PR:{}/UI:{}/S:{}/C:{}/I:{}/A:{}",
+            match self.attack_vector {
+                AttackVector::Network => "N",
+                AttackVector::Adjacent => "A",
+                AttackVector::Local => "L",
+                AttackVector::Physical => "P",
+            },
+            match self.attack_complexity {
+                AttackComplexity::Low => "L",
+                AttackComplexity::High => "H",
+            },
+            match self.privileges_required {
+                PrivilegesRequired::None => "N",
+                PrivilegesRequired::Low => "L",
+                PrivilegesRequired::High => "H",
+            },
+            match self.user_interaction {
+                UserInteraction::None => "N",
+                UserInteraction::Required => "R",
+            },
+            match self.scope {
+                Scope::Unchanged => "U",
+                Scope::Changed => "C",
+            },
+            self.confidentiality.abbrev(),
+            self.integrity.abbrev(),
+            self.availability.abbrev(),
+        )
+    }
+}
+
+// `core` has neither `powi` nor `ceil`
+fn pow(base: f64, exp: u32) -> f64 {
+    (0..exp).fold(1.0, |acc, _| acc * base)
+}
+
+// The specification's Roundup: the smallest tenth at or above `value`,
+// computed on integers so 4.000000001 doesn't become 4.1
+fn roundup(value: f64) -> f64 {
+    let scaled = (value * 100_000.0 + 0.5) as u64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+/// An id for the same weakness in a community taxonomy, for tools that
+/// cross-reference findings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weakness {
+    /// A program of coral-xyz/sealevel-attacks, by its number
+    Sealevel(u8),
+    /// The Smart Contract Weakness Classification, EVM-centric but widely cited
+    Swc(u16),
+    Cwe(u16),
+}
+
+/// The programs of coral-xyz/sealevel-attacks, in order
+const SEALEVEL_ATTACKS: [&str; 11] = [
+    "signer-authorization",
+    "account-data-matching",
+    "owner-checks",
+    "type-cosplay",
+    "initialization",
+    "arbitrary-cpi",
+    "duplicate-mutable-accounts",
+    "bump-seed-canonicalization",
+    "pda-sharing",
+    "closing-accounts",
+    "sysvar-address-checking",
+];
+
+impl Weakness {
+    pub fn taxonomy(&self) -> &'static str {
+        match self {
+            Weakness::Sealevel(_) => "sealevel-attacks",
+            Weakness::Swc(_) => "swc",
+            Weakness::Cwe(_) => "cwe",
+        }
+    }
+
+    /// The name sealevel-attacks gives it, if it's one of theirs
+    pub fn sealevel_name(&self) -> Option<&'static str> {
+        match self {
+            Weakness::Sealevel(n) => SEALEVEL_ATTACKS.get(*n as usize).copied(),
+            _ => None,
+        }
+    }
+
+    /// As the taxonomy writes it: `0-signer-authorization`, `SWC-101`, `CWE-190`
+    #[cfg(feature = "std")]
+    pub fn id(&self) -> String {
+        match self {
+            Weakness::Sealevel(n) => format!("{}-{}", n, self.sealevel_name().unwrap_or("unknown")),
+            Weakness::Swc(n) => format!("SWC-{}", n),
+            Weakness::Cwe(n) => format!("CWE-{}", n),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn url(&self) -> String {
+        match self {
+            Weakness::Sealevel(_) => format!(
+                "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/{}",
+                self.id()
+            ),
+            Weakness::Swc(n) => format!("https://swcregistry.io/docs/SWC-{}", n),
+            Weakness::Cwe(n) => format!("https://cwe.mitre.org/data/definitions/{}.html", n),
+        }
+    }
+}
+
+/// How the secure side closes the hole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mitigation {
+    /// One line, as in the README's coverage table
+    pub summary: &'static str,
+    /// Instructions that apply it
+    pub instructions: &'static [&'static str],
+}
+
+/// Tests in the entry's suite, by what they show. `tests/coverage.rs`
+/// requires at least one of each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    /// The attack succeeds against the vulnerable path
+    pub exploit: &'static [&'static str],
+    /// The same attack fails against the secure path
+    pub rejects: &'static [&'static str],
+    /// Legitimate use of the secure path still works
+    pub accepts: &'static [&'static str],
+}
+
+/// A vulnerability and where the tree demonstrates it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vulnerability {
+    pub title: &'static str,
+    /// Directory under `programs/`
+    pub program: &'static str,
+    /// Test suite exploiting it, as in `cargo test --test <suite>`
+    pub suite: &'static str,
+    pub class: VulnClass,
+    /// The template's rating, as in the README's coverage table
+    pub severity: Severity,
+    pub score: Score,
+    /// The same weakness elsewhere, at least one per entry
+    pub weaknesses: &'static [Weakness],
+    pub framework: Framework,
+    /// Instructions an attacker can exploit. A directory holding more than
+    /// one program prefixes each with its crate, as in `buggy_amm::swap`.
+    pub vulnerable: &'static [&'static str],
+    pub mitigation: Mitigation,
+    /// Files with the vulnerable and secure code, from the repository root:
+    /// the vulnerable files first, then their secure counterparts in the
+    /// same order, or a single file holding both
+    pub sources: &'static [&'static str],
+    pub coverage: Coverage,
+}
+
+impl Vulnerability {
+    /// The program's README, from the repository root.
+    #[cfg(feature = "std")]
+    pub fn readme(&self) -> String {
+        format!("programs/{}/README.md", self.program)
+    }
+}
+
+/// Implemented by each program crate for its program type, naming the
+/// entry it demonstrates. Anchor programs use the `program::<Name>` type
+/// `#[program]` generates; Pinocchio programs declare a unit struct.
+pub trait Catalogued {
+    const VULNERABILITY: &'static Vulnerability;
+}
+
+/// Every vulnerability program, in the order the README's coverage table
+/// lists them, with its wording.
+pub const CATALOG: &[Vulnerability] = &[
+    SIGNER_AUTHORIZATION,
+    ARITHMETIC_OVERFLOW,
+    OWNER_CHECK,
+    PDA_SECURITY,
+    ACCOUNT_CLOSE,
+    ACCOUNT_TYPE_MISMATCH,
+    P_ESCROW,
+    DUPLICATE_ACCOUNTS,
+    INSECURE_INIT,
+    ACCOUNT_RELOADING,
+    REMAINING_ACCOUNTS,
+    AUTHORITY_TRANSFER,
+    ACCOUNT_GRIEFING,
+    MULTISIG_PAYER,
+    LOOKUP_TABLE_PITFALLS,
+    DURABLE_NONCE,
+    FEE_ROUNDING,
+    ORDER_BOOK,
+    SEED_LIQUIDITY,
+    INSURANCE_FUND,
+    PARTNER_MINT,
+    AMM,
+];
+
+/// The entry for a directory under `programs/`.
+pub fn find(program: &str) -> Option<&'static Vulnerability> {
+    CATALOG.iter().find(|v| v.program == program)
+}
+
+/// A catalogued program written again in another framework, or without
+/// one, to show what the framework's checks come down to. It lives in
+/// `programs-<framework>/<program of its entry>` and its crate implements
+/// [`Catalogued`] with the entry it ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    /// Package name, as the bench samples name it
+    pub program: &'static str,
+    pub framework: Framework,
+    pub of: &'static Vulnerability,
+    /// Test suite exploiting it, as in `cargo test --test <suite>`
+    pub suite: &'static str,
+    /// The port's instructions, which may differ from the entry's
+    pub vulnerable: &'static [&'static str],
+    pub secure: &'static [&'static str],
+    pub coverage: Coverage,
+}
+
+impl Variant {
+    /// The crate's directory, from the repository root.
+    #[cfg(feature = "std")]
+    pub fn dir(&self) -> String {
+        format!("programs-{}/{}", self.framework.name(), self.of.program)
+    }
+}
+
+/// Every variant, by framework and then in catalog order.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        program: "native-signer-authorization",
+        framework: Framework::Native,
+        of: &SIGNER_AUTHORIZATION,
+        suite: "native_signer_authorization",
+        vulnerable: &["vulnerable_withdraw"],
+        secure: &["secure_withdraw"],
+        coverage: Coverage {
+            exploit: &["test_vulnerable_withdraw_allows_attacker"],
+            rejects: &[
+                "test_secure_withdraw_rejects_attacker",
+                "test_secure_withdraw_rejects_forged_vault",
+            ],
+            accepts: &["test_secure_withdraw_works_for_authority"],
+        },
+    },
+    Variant {
+        program: "native-arithmetic-overflow",
+        framework: Framework::Native,
+        of: &ARITHMETIC_OVERFLOW,
+        suite: "native_arithmetic_overflow",
+        vulnerable: &["vulnerable_swap"],
+        secure: &["secure_swap"],
+        coverage: Coverage {
+            exploit: &["test_vulnerable_swap_overflow_corrupts_pool"],
+            rejects: &[
+                "test_secure_swap_rejects_overflow",
+                "test_secure_swap_rejects_slippage",
+            ],
+            accepts: &["test_secure_swap_works_normally"],
+        },
+    },
+    Variant {
+        program: "native-account-griefing",
+        framework: Framework::Native,
+        of: &ACCOUNT_GRIEFING,
+        suite: "native_account_griefing",
+        vulnerable: &["vulnerable_create_stake"],
+        secure: &["secure_create_stake", "secure_deposit"],
+        coverage: Coverage {
+            exploit: &["test_griefing_attack_blocks_creation"],
+            rejects: &[
+                "test_secure_rejects_mismatched_nonce",
+                "test_secure_create_refuses_existing_stake",
+            ],
+            accepts: &["test_secure_create_survives_prefunding"],
+        },
+    },
+    Variant {
+        program: "native-multisig-payer",
+        framework: Framework::Native,
+        of: &MULTISIG_PAYER,
+        suite: "native_multisig_payer",
+        vulnerable: &["vulnerable_create_proposal"],
+        secure: &["secure_create_proposal"],
+        coverage: Coverage {
+            exploit: &["test_vulnerable_pda_as_payer_fails"],
+            rejects: &["test_secure_rejects_forged_treasury"],
+            accepts: &["test_secure_separate_payer_succeeds"],
+        },
+    },
+    Variant {
+        program: "p-signer-authorization",
+        framework: Framework::Pinocchio,
+        of: &SIGNER_AUTHORIZATION,
+        suite: "cross_framework",
+        vulnerable: &["vulnerable_withdraw"],
+        secure: &["secure_withdraw"],
+        coverage: Coverage {
+            exploit: &["test_missing_signer_drains_every_vault"],
+            rejects: &["test_signer_check_stops_every_attacker"],
+            accepts: &["test_authority_withdraws_from_every_vault"],
+        },
+    },
+    Variant {
+        program: "p-account-griefing",
+        framework: Framework::Pinocchio,
+        of: &ACCOUNT_GRIEFING,
+        suite: "cross_framework",
+        vulnerable: &["vulnerable_create_stake"],
+        secure: &["secure_create_stake", "secure_deposit"],
+        coverage: Coverage {
+            exploit: &["test_prefunding_blocks_every_predictable_stake"],
+            rejects: &["test_every_secure_create_checks_the_nonce"],
+            accepts: &["test_every_secure_create_survives_prefunding"],
+        },
+    },
+];
+
+/// The variant with a package name.
+pub fn find_variant(program: &str) -> Option<&'static Variant> {
+    VARIANTS.iter().find(|v| v.program == program)
+}
+
+/// The framework of a directory under `programs/` or a variant's package.
+pub fn framework(program: &str) -> Option<Framework> {
+    find(program)
+        .map(|v| v.framework)
+        .or_else(|| find_variant(program).map(|v| v.framework))
+}
+
+pub const SIGNER_AUTHORIZATION: Vulnerability = Vulnerability {
+    title: "Missing Signer Check",
+    program: "signer-authorization",
+    suite: "signer_authorization",
+    class: VulnClass::Authorization,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[
+        Weakness::Sealevel(0),
+        Weakness::Swc(105),
+        Weakness::Cwe(306),
+    ],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_withdraw"],
+    mitigation: Mitigation {
+        summary: "Use `Signer<'info>` type",
+        instructions: &["secure_withdraw"],
+    },
+    sources: &[
+        "programs/signer-authorization/src/vulnerable.rs",
+        "programs/signer-authorization/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_withdraw_allows_attacker"],
+        rejects: &["test_secure_withdraw_rejects_attacker"],
+        accepts: &["test_secure_withdraw_works_for_authority"],
+    },
+};
+
+pub const ARITHMETIC_OVERFLOW: Vulnerability = Vulnerability {
+    title: "Integer Overflow",
+    program: "arithmetic-overflow",
+    suite: "arithmetic_overflow",
+    class: VulnClass::Arithmetic,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Swc(101), Weakness::Cwe(190)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_swap"],
+    mitigation: Mitigation {
+        summary: "Use `checked_*` methods",
+        instructions: &["secure_swap"],
+    },
+    sources: &[
+        "programs/arithmetic-overflow/src/vulnerable.rs",
+        "programs/arithmetic-overflow/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_swap_overflow"],
+        rejects: &["test_secure_swap_handles_large_values"],
+        accepts: &["test_secure_swap_normal"],
+    },
+};
+
+pub const OWNER_CHECK: Vulnerability = Vulnerability {
+    title: "Missing Owner Check",
+    program: "owner-check",
+    suite: "owner_check",
+    class: VulnClass::AccountValidation,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Sealevel(2), Weakness::Cwe(345)],
+    framework: Framework::Pinocchio,
+    vulnerable: &["vulnerable_read_config"],
+    mitigation: Mitigation {
+        summary: "Verify `account.owner == program_id`",
+        instructions: &["secure_read_config"],
+    },
+    sources: &[
+        "programs/owner-check/src/vulnerable.rs",
+        "programs/owner-check/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_accepts_fake_config"],
+        rejects: &["test_secure_rejects_fake_config"],
+        accepts: &["test_secure_accepts_real_config"],
+    },
+};
+
+pub const PDA_SECURITY: Vulnerability = Vulnerability {
+    title: "Weak PDA Seeds",
+    program: "pda-security",
+    suite: "pda_seeds",
+    class: VulnClass::PdaDerivation,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Sealevel(8), Weakness::Cwe(340)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_create_user", "vulnerable_update"],
+    mitigation: Mitigation {
+        summary: "Include user key + nonce in seeds",
+        instructions: &["secure_create_user", "secure_update"],
+    },
+    sources: &[
+        "programs/pda-security/src/vulnerable.rs",
+        "programs/pda-security/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_weak_seeds_predictable", "test_vulnerable_create_user"],
+        rejects: &["test_secure_rejects_mismatched_nonce"],
+        accepts: &["test_secure_create_user"],
+    },
+};
+
+pub const ACCOUNT_CLOSE: Vulnerability = Vulnerability {
+    title: "Account Revival",
+    program: "account-close",
+    suite: "account_close",
+    class: VulnClass::AccountLifecycle,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Sealevel(9), Weakness::Cwe(672)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_close"],
+    mitigation: Mitigation {
+        summary: "Zero data before close",
+        instructions: &["secure_close"],
+    },
+    sources: &[
+        "programs/account-close/src/vulnerable.rs",
+        "programs/account-close/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_close_data_not_zeroed"],
+        rejects: &["test_secure_close_rejects_already_closed"],
+        accepts: &["test_secure_close_zeros_data"],
+    },
+};
+
+pub const ACCOUNT_TYPE_MISMATCH: Vulnerability = Vulnerability {
+    title: "Discriminator Bypass",
+    program: "account-type-mismatch",
+    suite: "account_type_mismatch",
+    class: VulnClass::AccountValidation,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Sealevel(3), Weakness::Cwe(843)],
+    framework: Framework::Pinocchio,
+    vulnerable: &["vulnerable_action"],
+    mitigation: Mitigation {
+        summary: "Add discriminator byte",
+        instructions: &["secure_action"],
+    },
+    sources: &[
+        "programs/account-type-mismatch/src/vulnerable.rs",
+        "programs/account-type-mismatch/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_accepts_wrong_account_type"],
+        rejects: &["test_secure_rejects_wrong_account_type"],
+        accepts: &["test_secure_accepts_correct_account_type"],
+    },
+};
+
+pub const P_ESCROW: Vulnerability = Vulnerability {
+    title: "Missing Validation",
+    program: "p-escrow",
+    suite: "p_escrow",
+    class: VulnClass::AccountValidation,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Sealevel(1), Weakness::Cwe(639)],
+    framework: Framework::Pinocchio,
+    vulnerable: &["vulnerable_refund"],
+    mitigation: Mitigation {
+        summary: "Verify caller matches stored data",
+        instructions: &["secure_refund"],
+    },
+    sources: &["programs/p-escrow/src/instructions/refund.rs"],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_refund_allows_theft"],
+        rejects: &["test_secure_refund_rejects_wrong_recipient"],
+        accepts: &["test_secure_refund_works_for_maker"],
+    },
+};
+
+pub const DUPLICATE_ACCOUNTS: Vulnerability = Vulnerability {
+    title: "Duplicate Accounts",
+    program: "duplicate-accounts",
+    suite: "duplicate_accounts",
+    class: VulnClass::AccountValidation,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Sealevel(6), Weakness::Cwe(694)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_transfer"],
+    mitigation: Mitigation {
+        summary: "Add `key() != key()` constraint",
+        instructions: &["secure_transfer"],
+    },
+    sources: &[
+        "programs/duplicate-accounts/src/vulnerable.rs",
+        "programs/duplicate-accounts/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_same_account_inflates_balance"],
+        rejects: &["test_secure_rejects_same_account"],
+        accepts: &["test_secure_transfer_between_distinct_accounts"],
+    },
+};
+
+pub const INSECURE_INIT: Vulnerability = Vulnerability {
+    title: "Insecure Init",
+    program: "insecure-init",
+    suite: "insecure_init",
+    class: VulnClass::AccountLifecycle,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::High,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Sealevel(4), Weakness::Cwe(665)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_initialize"],
+    mitigation: Mitigation {
+        summary: "Use `init` or check `is_initialized`",
+        instructions: &["secure_initialize"],
+    },
+    sources: &[
+        "programs/insecure-init/src/vulnerable.rs",
+        "programs/insecure-init/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_allows_reinitialization"],
+        rejects: &["test_secure_blocks_reinitialization"],
+        accepts: &["test_secure_init_works_first_time"],
+    },
+};
+
+pub const ACCOUNT_RELOADING: Vulnerability = Vulnerability {
+    title: "Stale Data After CPI",
+    program: "account-reloading",
+    suite: "account_reloading",
+    class: VulnClass::StaleState,
+    severity: Severity::Medium,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::High,
+        privileges_required: PrivilegesRequired::Low,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Cwe(367)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_double_increment"],
+    mitigation: Mitigation {
+        summary: "Call `reload()` after CPI",
+        instructions: &["secure_double_increment"],
+    },
+    sources: &[
+        "programs/account-reloading/src/vulnerable.rs",
+        "programs/account-reloading/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_double_increment_uses_stale_data"],
+        rejects: &["test_secure_rejects_foreign_counter"],
+        accepts: &[
+            "test_secure_double_increment_reloads",
+            "test_basic_increment_works",
+        ],
+    },
+};
+
+pub const REMAINING_ACCOUNTS: Vulnerability = Vulnerability {
+    title: "Unvalidated Remaining",
+    program: "remaining-accounts",
+    suite: "remaining_accounts",
+    class: VulnClass::AccountValidation,
+    severity: Severity::Medium,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::Low,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::Low,
+        availability: Impact::Low,
+    },
+    weaknesses: &[
+        Weakness::Sealevel(2),
+        Weakness::Sealevel(3),
+        Weakness::Cwe(20),
+    ],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_batch_reward"],
+    mitigation: Mitigation {
+        summary: "Validate owner and type manually",
+        instructions: &["secure_batch_reward"],
+    },
+    sources: &[
+        "programs/remaining-accounts/src/vulnerable.rs",
+        "programs/remaining-accounts/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_accepts_any_remaining_accounts"],
+        rejects: &["test_secure_validates_remaining_accounts"],
+        accepts: &["test_secure_accepts_valid_recipients"],
+    },
+};
+
+pub const AUTHORITY_TRANSFER: Vulnerability = Vulnerability {
+    title: "Insecure Authority",
+    program: "authority-transfer",
+    suite: "authority_transfer",
+    class: VulnClass::Authorization,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::High,
+        privileges_required: PrivilegesRequired::High,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Cwe(269)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_transfer"],
+    mitigation: Mitigation {
+        summary: "Two-step propose/accept pattern",
+        instructions: &["propose_authority", "accept_authority"],
+    },
+    sources: &[
+        "programs/authority-transfer/src/vulnerable.rs",
+        "programs/authority-transfer/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_immediate_transfer"],
+        rejects: &["test_secure_requires_two_steps"],
+        accepts: &["test_secure_accept_works_for_pending"],
+    },
+};
+
+pub const ACCOUNT_GRIEFING: Vulnerability = Vulnerability {
+    title: "Account Griefing",
+    program: "account-griefing",
+    suite: "account_griefing",
+    class: VulnClass::DenialOfService,
+    severity: Severity::Medium,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::None,
+        availability: Impact::Low,
+    },
+    weaknesses: &[Weakness::Cwe(340), Weakness::Cwe(400)],
+    framework: Framework::Anchor,
+    vulnerable: &[
+        "vulnerable_create_stake",
+        "vulnerable_deposit",
+        "vulnerable_migrate_stake",
+    ],
+    mitigation: Mitigation {
+        summary: "Add nonce to PDA seeds",
+        instructions: &[
+            "secure_create_stake",
+            "secure_deposit",
+            "secure_migrate_stake",
+        ],
+    },
+    sources: &[
+        "programs/account-griefing/src/vulnerable.rs",
+        "programs/account-griefing/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &[
+            "test_griefing_attack_blocks_creation",
+            "test_vulnerable_migration_corrupts_migrated_stake",
+        ],
+        rejects: &[
+            "test_secure_rejects_mismatched_nonce",
+            "test_secure_rejects_outdated_and_forged_stake",
+        ],
+        accepts: &[
+            "test_secure_version_unpredictable",
+            "test_secure_migration_is_versioned",
+        ],
+    },
+};
+
+pub const MULTISIG_PAYER: Vulnerability = Vulnerability {
+    title: "Multisig as Payer",
+    program: "multisig-payer",
+    suite: "multisig_payer",
+    class: VulnClass::DenialOfService,
+    severity: Severity::Low,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::High,
+        privileges_required: PrivilegesRequired::Low,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::None,
+        availability: Impact::Low,
+    },
+    weaknesses: &[Weakness::Cwe(628)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_create_proposal"],
+    mitigation: Mitigation {
+        summary: "Separate rent payer from authority",
+        instructions: &["secure_create_proposal"],
+    },
+    sources: &[
+        "programs/multisig-payer/src/vulnerable.rs",
+        "programs/multisig-payer/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_pda_as_payer_fails"],
+        rejects: &["test_secure_rejects_pda_as_rent_payer"],
+        accepts: &["test_secure_separate_payer_succeeds"],
+    },
+};
+
+pub const LOOKUP_TABLE_PITFALLS: Vulnerability = Vulnerability {
+    title: "Mutable Lookup Table",
+    program: "lookup-table-pitfalls",
+    suite: "lookup_table_pitfalls",
+    class: VulnClass::Authorization,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::Low,
+        user_interaction: UserInteraction::Required,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Cwe(367)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_approve", "vulnerable_execute"],
+    mitigation: Mitigation {
+        summary: "Freeze the table and pin resolved addresses",
+        instructions: &["secure_approve", "secure_execute"],
+    },
+    sources: &[
+        "programs/lookup-table-pitfalls/src/vulnerable.rs",
+        "programs/lookup-table-pitfalls/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_pays_addresses_appended_after_approval"],
+        rejects: &[
+            "test_secure_rejects_unfrozen_table",
+            "test_secure_rejects_unresolved_index",
+            "test_secure_rejects_substituted_recipient",
+        ],
+        accepts: &["test_secure_pays_pinned_recipients"],
+    },
+};
+
+pub const DURABLE_NONCE: Vulnerability = Vulnerability {
+    title: "Durable Nonce Replay",
+    program: "durable-nonce",
+    suite: "durable_nonce",
+    class: VulnClass::Authorization,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::Low,
+        user_interaction: UserInteraction::Required,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Swc(121), Weakness::Cwe(294)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_withdraw"],
+    mitigation: Mitigation {
+        summary: "Track a sequence and an expiry slot in the vault",
+        instructions: &["secure_withdraw", "secure_revoke"],
+    },
+    sources: &[
+        "programs/durable-nonce/src/vulnerable.rs",
+        "programs/durable-nonce/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_pays_a_held_nonce_transaction_twice"],
+        rejects: &[
+            "test_secure_rejects_a_held_transaction_after_resending",
+            "test_secure_rejects_an_expired_authorization",
+            "test_secure_rejects_a_revoked_authorization",
+        ],
+        accepts: &["test_secure_pays_a_nonce_transaction_once"],
+    },
+};
+
+pub const FEE_ROUNDING: Vulnerability = Vulnerability {
+    title: "Fee Rounding Dust",
+    program: "fee-rounding",
+    suite: "fee_rounding",
+    class: VulnClass::Arithmetic,
+    severity: Severity::Medium,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::Low,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::Low,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Cwe(682), Weakness::Cwe(1339)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_withdraw"],
+    mitigation: Mitigation {
+        summary: "Round fees up, in the pool's favour",
+        instructions: &["secure_withdraw"],
+    },
+    sources: &[
+        "programs/fee-rounding/src/vulnerable.rs",
+        "programs/fee-rounding/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_split_withdrawals_pay_no_fee"],
+        rejects: &["test_secure_split_withdrawals_pay_at_least_the_fee"],
+        accepts: &["test_secure_withdraw_charges_the_fee"],
+    },
+};
+
+pub const ORDER_BOOK: Vulnerability = Vulnerability {
+    title: "Order Book Priority",
+    program: "order-book",
+    suite: "order_book",
+    class: VulnClass::AccountValidation,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::None,
+    },
+    weaknesses: &[Weakness::Cwe(807), Weakness::Cwe(20)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_match_orders"],
+    mitigation: Mitigation {
+        summary: "Match only the front of each side of the book",
+        instructions: &["secure_match_orders"],
+    },
+    sources: &[
+        "programs/order-book/src/vulnerable.rs",
+        "programs/order-book/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &[
+            "test_vulnerable_fills_a_bid_against_a_worse_ask",
+            "test_vulnerable_self_match_prints_any_price",
+        ],
+        rejects: &[
+            "test_secure_rejects_an_ask_behind_the_best",
+            "test_secure_rejects_a_self_match_behind_the_market",
+        ],
+        accepts: &["test_secure_fills_in_price_time_priority"],
+    },
+};
+
+pub const SEED_LIQUIDITY: Vulnerability = Vulnerability {
+    title: "Seed Liquidity Rug",
+    program: "seed-liquidity",
+    suite: "seed_liquidity",
+    class: VulnClass::Authorization,
+    severity: Severity::High,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::High,
+        user_interaction: UserInteraction::Required,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Cwe(269), Weakness::Cwe(284)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_initialize", "vulnerable_withdraw_reserve"],
+    mitigation: Mitigation {
+        summary: "Lock the seed liquidity and redeem it only pro rata",
+        instructions: &["secure_initialize", "secure_remove_liquidity"],
+    },
+    sources: &[
+        "programs/seed-liquidity/src/vulnerable.rs",
+        "programs/seed-liquidity/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_creator_rugs_the_lamport_reserve"],
+        rejects: &[
+            "test_secure_rejects_a_dust_seed",
+            "test_secure_rejects_removing_liquidity_before_the_unlock",
+        ],
+        accepts: &["test_secure_removes_liquidity_pro_rata_after_the_unlock"],
+    },
+};
+
+pub const INSURANCE_FUND: Vulnerability = Vulnerability {
+    title: "Insurance Fund Drain",
+    program: "insurance-fund",
+    suite: "insurance_fund",
+    class: VulnClass::AccountValidation,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Cwe(639), Weakness::Cwe(345)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_pay_claim"],
+    mitigation: Mitigation {
+        summary: "Pay only the claimant the claim record names",
+        instructions: &["secure_pay_claim"],
+    },
+    sources: &[
+        "programs/insurance-fund/src/vulnerable.rs",
+        "programs/insurance-fund/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_pays_approved_claims_to_the_caller"],
+        rejects: &[
+            "test_secure_rejects_a_recipient_other_than_the_claimant",
+            "test_secure_rejects_an_unapproved_claim",
+        ],
+        accepts: &["test_secure_pays_the_claimant_once"],
+    },
+};
+
+pub const PARTNER_MINT: Vulnerability = Vulnerability {
+    title: "Partner Mint Bypass",
+    program: "partner-mint",
+    suite: "partner_mint",
+    class: VulnClass::Authorization,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[Weakness::Sealevel(0), Weakness::Cwe(290)],
+    framework: Framework::Anchor,
+    vulnerable: &["vulnerable_mint"],
+    mitigation: Mitigation {
+        summary: "Require the partner program's PDA signature",
+        instructions: &["secure_mint"],
+    },
+    sources: &[
+        "programs/partner-mint/src/vulnerable.rs",
+        "programs/partner-mint/src/secure.rs",
+    ],
+    coverage: Coverage {
+        exploit: &["test_vulnerable_mints_for_anyone_naming_the_partner"],
+        rejects: &[
+            "test_secure_rejects_a_caller_signing_with_its_own_pda",
+            "test_secure_rejects_a_caller_forwarding_the_partner_pda",
+        ],
+        accepts: &["test_secure_mints_through_the_partner_once"],
+    },
+};
+
+/// buggy-amm and secure-amm live in their own workspace
+pub const AMM: Vulnerability = Vulnerability {
+    title: "Multiple Combined",
+    program: "amm",
+    suite: "amm",
+    class: VulnClass::Combined,
+    severity: Severity::Critical,
+    score: Score {
+        attack_vector: AttackVector::Network,
+        attack_complexity: AttackComplexity::Low,
+        privileges_required: PrivilegesRequired::None,
+        user_interaction: UserInteraction::None,
+        scope: Scope::Unchanged,
+        confidentiality: Impact::None,
+        integrity: Impact::High,
+        availability: Impact::High,
+    },
+    weaknesses: &[
+        Weakness::Sealevel(8),
+        Weakness::Swc(101),
+        Weakness::Swc(114),
+        Weakness::Cwe(190),
+        Weakness::Cwe(340),
+    ],
+    framework: Framework::Anchor,
+    vulnerable: &[
+        "buggy_amm::deposit",
+        "buggy_amm::swap",
+        "buggy_amm::withdraw",
+    ],
+    mitigation: Mitigation {
+        summary: "All of the above",
+        instructions: &[
+            "secure_amm::deposit",
+            "secure_amm::swap",
+            "secure_amm::withdraw",
+        ],
+    },
+    sources: &[
+        "programs/amm/buggy-amm/src/instructions/deposit.rs",
+        "programs/amm/buggy-amm/src/instructions/swap.rs",
+        "programs/amm/buggy-amm/src/instructions/withdraw.rs",
+        "programs/amm/secure-amm/src/instructions/deposit.rs",
+        "programs/amm/secure-amm/src/instructions/swap.rs",
+        "programs/amm/secure-amm/src/instructions/withdraw.rs",
+    ],
+    coverage: Coverage {
+        exploit: &[
+            "test_buggy_weak_pda_predictable",
+            "test_buggy_overflow_in_swap",
+            "test_buggy_no_slippage_check",
+        ],
+        rejects: &["test_secure_slippage_protection"],
+        accepts: &[
+            "test_secure_unique_pda_seeds",
+            "test_secure_checked_math_prevents_overflow",
+        ],
+    },
+};
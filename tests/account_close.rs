@@ -2,51 +2,31 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
-    use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::corrupt::{self, Corruption};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        self, Account, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+    use solsec_guards::CLOSED_ACCOUNT_DISCRIMINATOR;
 
-    fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/account_close-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
-    }
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
 
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/account_close.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+    fn program_id() -> Pubkey {
+        common::load_program_id("account_close")
     }
 
-    fn discriminator(name: &str) -> [u8; 8] {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let full_name = format!("global:{}", name);
-        let mut hasher = DefaultHasher::new();
-        full_name.hash(&mut hasher);
-        hasher.finish().to_le_bytes()
+    fn idl() -> Idl {
+        Idl::load("account_close")
     }
 
     fn derive_user_pda(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[b"user", owner.as_ref()], program_id)
+        common::find_pda(&[b"user", owner.as_ref()], program_id)
     }
 
     fn create_user_account_data(
@@ -55,7 +35,7 @@ mod tests {
         is_initialized: bool,
         bump: u8,
     ) -> Vec<u8> {
-        let mut data = vec![0u8; 8]; // discriminator
+        let mut data = idl().account_discriminator("UserAccount").to_vec();
         data.extend_from_slice(owner.as_ref());
         data.extend_from_slice(&balance.to_le_bytes());
         data.push(if is_initialized { 1 } else { 0 });
@@ -63,18 +43,18 @@ mod tests {
         data
     }
 
-    fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
+    fn setup(name: &str) -> (Scenario, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let payer = svm.keypair("payer");
         svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
             .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
+        svm.add_program("account_close");
         (svm, payer)
     }
 
     #[test]
     fn test_vulnerable_close_data_not_zeroed() {
-        let (mut svm, owner) = setup();
+        let (mut svm, owner) = setup("account_close/vulnerable_close_data_not_zeroed");
         let pid = program_id();
         let (user_pda, bump) = derive_user_pda(&owner.pubkey(), &pid);
 
@@ -93,25 +73,23 @@ mod tests {
         .unwrap();
 
         // Call vulnerable_close
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(owner.pubkey(), true),
-                AccountMeta::new(user_pda, false),
-            ],
-            data: discriminator("vulnerable_close").to_vec(),
-        };
+        let ix = idl()
+            .ix("vulnerable_close")
+            .account("owner", owner.pubkey())
+            .account("user_account", user_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&owner.pubkey()));
         let tx = Transaction::new(&[&owner], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_close/vulnerable_close_data_not_zeroed", &result);
         println!("Vulnerable close result: {:?}", result);
         // After close, if account receives lamports, data may still be readable
     }
 
     #[test]
     fn test_secure_close_zeros_data() {
-        let (mut svm, owner) = setup();
+        let (mut svm, owner) = setup("account_close/secure_close_zeros_data");
         let pid = program_id();
         let (user_pda, bump) = derive_user_pda(&owner.pubkey(), &pid);
 
@@ -129,24 +107,77 @@ mod tests {
         .unwrap();
 
         // Call secure_close - zeros data before closing
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(owner.pubkey(), true),
-                AccountMeta::new(user_pda, false),
-            ],
-            data: discriminator("secure_close").to_vec(),
-        };
+        let ix = idl()
+            .ix("secure_close")
+            .account("owner", owner.pubkey())
+            .account("user_account", user_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&owner.pubkey()));
         let tx = Transaction::new(&[&owner], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_close/secure_close_zeros_data", &result);
         println!("Secure close result: {:?}", result);
     }
 
+    #[test]
+    fn test_secure_close_leaves_nothing_to_revive() {
+        // SCENARIO: Owner closes, then refunds the address in the same tx
+        // EXPECTED: The revived account holds only the CLOSED discriminator
+        let (mut svm, owner) = setup("account_close/secure_close_leaves_nothing_to_revive");
+        let pid = program_id();
+        let (user_pda, bump) = derive_user_pda(&owner.pubkey(), &pid);
+
+        let user_data = create_user_account_data(owner.pubkey(), 100, true, bump);
+        let len = user_data.len();
+        svm.set_account(
+            user_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: user_data,
+                owner: pid,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let close = idl()
+            .ix("secure_close")
+            .account("owner", owner.pubkey())
+            .account("user_account", user_pda)
+            .build();
+        let revive =
+            sdk::system_instruction::transfer(&owner.pubkey(), &user_pda, LAMPORTS_PER_SOL);
+        let msg = Message::new(&[close, revive], Some(&owner.pubkey()));
+        let tx = Transaction::new(&[&owner], msg, svm.latest_blockhash());
+        let result = svm.send_transaction(tx);
+        assert_golden(
+            "account_close/secure_close_leaves_nothing_to_revive",
+            &result,
+        );
+        assert!(result.is_ok(), "{:?}", result);
+
+        let revived = svm.get_account(&user_pda).unwrap();
+        assert_eq!(revived.owner, pid);
+        let mut tombstone = CLOSED_ACCOUNT_DISCRIMINATOR.to_vec();
+        tombstone.resize(len, 0);
+        assert_eq!(revived.data, tombstone);
+
+        // Nor can it be closed, or read, again
+        let ix = idl()
+            .ix("secure_close")
+            .account("owner", owner.pubkey())
+            .account("user_account", user_pda)
+            .build();
+        let msg = Message::new(&[ix], Some(&owner.pubkey()));
+        let tx = Transaction::new(&[&owner], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+    }
+
     #[test]
     fn test_secure_close_rejects_already_closed() {
-        let (mut svm, owner) = setup();
+        let (mut svm, owner) = setup("account_close/secure_close_rejects_already_closed");
         let pid = program_id();
         let (user_pda, bump) = derive_user_pda(&owner.pubkey(), &pid);
 
@@ -164,19 +195,100 @@ mod tests {
         )
         .unwrap();
 
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(owner.pubkey(), true),
-                AccountMeta::new(user_pda, false),
-            ],
-            data: discriminator("secure_close").to_vec(),
-        };
+        let ix = idl()
+            .ix("secure_close")
+            .account("owner", owner.pubkey())
+            .account("user_account", user_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&owner.pubkey()));
         let tx = Transaction::new(&[&owner], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_close/secure_close_rejects_already_closed", &result);
         // Should fail - account already marked as closed
         println!("Secure close on already closed: {:?}", result);
+        assert_err!(result, CloseError::AlreadyClosed);
+    }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        // Owner closing their own initialized account through both
+        // instructions, from the same starting state
+        let (mut svm, owner) = setup("account_close/compute_units_secure_vs_vulnerable");
+        let pid = program_id();
+        let (user_pda, bump) = derive_user_pda(&owner.pubkey(), &pid);
+
+        let user_data = create_user_account_data(owner.pubkey(), 100, true, bump);
+        svm.set_account(
+            user_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: user_data,
+                owner: pid,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        let ready = svm.snapshot();
+
+        let close = |svm: &mut Scenario, instruction: &str| {
+            let ix = idl()
+                .ix(instruction)
+                .account("owner", owner.pubkey())
+                .account("user_account", user_pda)
+                .build();
+
+            let msg = Message::new(&[ix], Some(&owner.pubkey()));
+            let tx = Transaction::new(&[&owner], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        let vulnerable = close(&mut svm, "vulnerable_close");
+        svm.restore(ready);
+        let secure = close(&mut svm, "secure_close");
+
+        // Zeroing the data costs CU proportional to the account size
+        assert_cu_overhead(
+            "account_close/close",
+            &vulnerable,
+            &secure,
+            CuThreshold::default(),
+        );
+    }
+
+    #[test]
+    fn test_secure_close_rejects_corrupted_account() {
+        // SCENARIO: Owner closes their account after it was damaged
+        // EXPECTED: Every corruption fails with an Anchor error, no panics
+        let (mut svm, owner) = setup("account_close/secure_close_rejects_corrupted_account");
+        let pid = program_id();
+        let (user_pda, bump) = derive_user_pda(&owner.pubkey(), &pid);
+
+        let user_data = create_user_account_data(owner.pubkey(), 100, true, bump);
+        svm.set_account(
+            user_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: user_data,
+                owner: pid,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let mut corruptions = Corruption::standard();
+        corruptions.push(Corruption::zero_field(&idl(), "UserAccount", "is_initialized"));
+
+        corrupt::assert_rejects_corrupted(&svm, user_pda, &corruptions, |svm| {
+            let ix = idl()
+                .ix("secure_close")
+                .account("owner", owner.pubkey())
+                .account("user_account", user_pda)
+                .build();
+            let msg = Message::new(&[ix], Some(&owner.pubkey()));
+            Transaction::new(&[&owner], msg, svm.latest_blockhash())
+        });
     }
 }
@@ -6,62 +6,30 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
     use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{
+        Account, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
 
     fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/account_reloading-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
-    }
-
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/account_reloading.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+        common::load_program_id("account_reloading")
     }
 
     fn derive_counter_pda(authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[b"counter", authority.as_ref()], program_id)
+        common::find_pda(&[b"counter", authority.as_ref()], program_id)
     }
 
-    /// Anchor-compatible discriminator
-    fn discriminator(name: &str) -> [u8; 8] {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(format!("global:{}", name));
-        let result = hasher.finalize();
-        let mut disc = [0u8; 8];
-        disc.copy_from_slice(&result[..8]);
-        disc
+    fn idl() -> Idl {
+        Idl::load("account_reloading")
     }
 
     fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
-        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
-        (svm, payer)
+        common::setup("account_reloading")
     }
 
     fn setup_counter(svm: &mut LiteSVM, authority: &Keypair, initial_count: u64) -> Pubkey {
@@ -70,6 +38,7 @@ mod tests {
 
         // Counter: authority (32) + count (8) + bump (1) + discriminator (8)
         let mut counter_data = vec![0u8; 8 + 32 + 8 + 1];
+        counter_data[..8].copy_from_slice(&idl().account_discriminator("Counter"));
         counter_data[8..40].copy_from_slice(authority.pubkey().as_ref());
         counter_data[40..48].copy_from_slice(&initial_count.to_le_bytes());
         counter_data[48] = bump;
@@ -96,25 +65,20 @@ mod tests {
         // EXPECTED: Demonstrates the vulnerability pattern
 
         let (mut svm, authority) = setup();
-        let pid = program_id();
 
         let counter_pda = setup_counter(&mut svm, &authority, 0);
 
         // Call vulnerable_double_increment
-        let data = discriminator("vulnerable_double_increment").to_vec();
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new(counter_pda, false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("vulnerable_double_increment")
+            .account("authority", authority.pubkey())
+            .account("counter", counter_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_reloading/vulnerable_double_increment_uses_stale_data", &result);
 
         // This demonstrates the pattern - in real CPI, data would be stale
         println!("Vulnerable double increment result: {:?}", result);
@@ -131,25 +95,20 @@ mod tests {
         // EXPECTED: Secure version uses fresh data after CPI
 
         let (mut svm, authority) = setup();
-        let pid = program_id();
 
         let counter_pda = setup_counter(&mut svm, &authority, 0);
 
         // Call secure_double_increment
-        let data = discriminator("secure_double_increment").to_vec();
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new(counter_pda, false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_double_increment")
+            .account("authority", authority.pubkey())
+            .account("counter", counter_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_reloading/secure_double_increment_reloads", &result);
 
         println!("Secure double increment result: {:?}", result);
 
@@ -159,30 +118,53 @@ mod tests {
         println!("Counter value after secure double increment: {}", count);
     }
 
+    #[test]
+    fn test_secure_rejects_foreign_counter() {
+        // SCENARIO: Attacker signs for a counter that belongs to someone else
+        // ATTACK: Pass the victim's counter alongside the attacker's signature
+        // EXPECTED: Secure version REJECTS the foreign counter (FIX WORKS)
+
+        let (mut svm, victim) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let counter_pda = setup_counter(&mut svm, &victim, 0);
+
+        let ix = idl()
+            .ix("secure_double_increment")
+            .account("authority", attacker.pubkey())
+            .account("counter", counter_pda)
+            .build();
+
+        let msg = Message::new(&[ix], Some(&attacker.pubkey()));
+        let tx = Transaction::new(&[&attacker], msg, svm.latest_blockhash());
+        let result = svm.send_transaction(tx);
+        assert_golden("account_reloading/secure_rejects_foreign_counter", &result);
+
+        println!("Secure double increment by attacker (should fail): {:?}", result);
+        // The counter PDA is re-derived from the signer, so the seeds no longer match
+        assert_err!(result, ErrorCode::ConstraintSeeds);
+    }
+
     #[test]
     fn test_basic_increment_works() {
         // SCENARIO: Basic single increment operation
         // EXPECTED: Counter increments by 1
 
         let (mut svm, authority) = setup();
-        let pid = program_id();
 
         let counter_pda = setup_counter(&mut svm, &authority, 5);
 
-        let data = discriminator("increment").to_vec();
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new(counter_pda, false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("increment")
+            .account("authority", authority.pubkey())
+            .account("counter", counter_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_reloading/basic_increment_works", &result);
 
         println!("Basic increment result: {:?}", result);
 
@@ -191,4 +173,30 @@ mod tests {
         let count = u64::from_le_bytes(account.data[40..48].try_into().unwrap());
         println!("Counter value after increment: {} (expected: 6)", count);
     }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        let double_increment = |instruction: &str| {
+            let (mut svm, authority) = setup();
+            let counter_pda = setup_counter(&mut svm, &authority, 0);
+
+            let ix = idl()
+                .ix(instruction)
+                .account("authority", authority.pubkey())
+                .account("counter", counter_pda)
+                .build();
+
+            let msg = Message::new(&[ix], Some(&authority.pubkey()));
+            let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        // The reload re-deserializes the account once more
+        assert_cu_overhead(
+            "account_reloading/double_increment",
+            &double_increment("vulnerable_double_increment"),
+            &double_increment("secure_double_increment"),
+            CuThreshold::default(),
+        );
+    }
 }
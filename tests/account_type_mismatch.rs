@@ -2,47 +2,27 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
     use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{
+        Account, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
 
     fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/account_type_mismatch-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
+        common::load_program_id("account_type_mismatch")
     }
 
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/account_type_mismatch.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+    fn idl() -> Idl {
+        Idl::load("account_type_mismatch")
     }
 
     fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
-        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
-        (svm, payer)
+        common::setup("account_type_mismatch")
     }
 
     // Discriminators from lib.rs
@@ -85,18 +65,16 @@ mod tests {
         .unwrap();
 
         // Vulnerable instruction (discriminator = 0) - doesn't check account type
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(admin_account, false), // Pass admin as user!
-                AccountMeta::new_readonly(user.pubkey(), true),
-            ],
-            data: vec![0], // Vulnerable variant
-        };
+        let ix = idl()
+            .ix("vulnerable_action") // Vulnerable variant
+            .account("user_account", admin_account) // Pass admin as user!
+            .account("caller", user.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_type_mismatch/vulnerable_accepts_wrong_account_type", &result);
         // May succeed - reading admin.permissions as user.balance
         println!("Vulnerable with wrong account type: {:?}", result);
     }
@@ -123,20 +101,19 @@ mod tests {
         .unwrap();
 
         // Secure instruction (discriminator = 1) - checks account type
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(admin_account, false),
-                AccountMeta::new_readonly(user.pubkey(), true),
-            ],
-            data: vec![1], // Secure variant
-        };
+        let ix = idl()
+            .ix("secure_action") // Secure variant
+            .account("user_account", admin_account)
+            .account("caller", user.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_type_mismatch/secure_rejects_wrong_account_type", &result);
         // Should fail - wrong discriminator
         println!("Secure with wrong account type (should fail): {:?}", result);
+        assert_err!(result, InstructionError::InvalidAccountData);
     }
 
     #[test]
@@ -160,18 +137,87 @@ mod tests {
         )
         .unwrap();
 
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(user_account, false),
-                AccountMeta::new_readonly(user.pubkey(), true),
-            ],
-            data: vec![1], // Secure variant
-        };
+        let ix = idl()
+            .ix("secure_action") // Secure variant
+            .account("user_account", user_account)
+            .account("caller", user.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("account_type_mismatch/secure_accepts_correct_account_type", &result);
         println!("Secure with correct account type: {:?}", result);
     }
+
+    #[test]
+    fn test_secure_rejects_unsigned_caller() {
+        let (mut svm, user) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // Genuine USER account, passed without the user's signature
+        let user_account = Pubkey::new_unique();
+        svm.set_account(
+            user_account,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: create_user_data(&user.pubkey()),
+                owner: program_id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let ix = idl()
+            .ix("secure_action")
+            .account("user_account", user_account)
+            .account("caller", user.pubkey())
+            .build();
+        let ix = attacker::strip_signer(ix, &user.pubkey());
+
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+        assert_err!(result, InstructionError::MissingRequiredSignature);
+    }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        // A genuine USER account through both variants
+        let update = |instruction: &str| {
+            let (mut svm, user) = setup();
+            let pid = program_id();
+
+            let user_account = Pubkey::new_unique();
+            svm.set_account(
+                user_account,
+                Account {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: create_user_data(&user.pubkey()),
+                    owner: pid,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+
+            let ix = idl()
+                .ix(instruction)
+                .account("user_account", user_account)
+                .account("caller", user.pubkey())
+                .build();
+
+            let msg = Message::new(&[ix], Some(&user.pubkey()));
+            let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        // One byte comparison - Pinocchio keeps this in the tens of CU
+        assert_cu_overhead(
+            "account_type_mismatch/update_user",
+            &update("vulnerable_action"),
+            &update("secure_action"),
+            CuThreshold::default().max_overhead(200),
+        );
+    }
 }
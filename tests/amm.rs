@@ -4,78 +4,265 @@
 mod tests {
     use std::path::PathBuf;
 
+    use litesvm::types::TransactionResult;
     use litesvm::LiteSVM;
-    use solana_keypair::Keypair;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-
-    fn buggy_program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::idl::{ArgValue, Idl};
+    use security_tests::sdk::{
+        Account, COption, Instruction, Keypair, Message, Pack, Pubkey, Signer, Transaction,
+        LAMPORTS_PER_SOL,
+    };
+    use solsec_pda::Seeds;
+    use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+    };
+    use spl_token_2022::state::{Account as TokenAccount, AccountState, Mint};
+
+    const TOKEN_PROGRAM: Pubkey =
+        Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+    // Both AMMs take either token program, so each pool test runs on both
+    const TOKEN_PROGRAMS: [Pubkey; 2] = [TOKEN_PROGRAM, spl_token_2022::ID];
+    const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
+        Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xNHiWfmXdUFuXffay");
+
+    // The AMM pair is built in its own workspace under programs/amm
+    fn deploy_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .parent()
             .unwrap()
-            .join("programs/amm/target/deploy/buggy_amm-keypair.json");
+            .join("programs/amm/target/deploy")
+    }
 
-        if keypair_path.exists() {
-            let keypair_bytes: Vec<u8> = serde_json::from_str(
-                &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-            )
-            .expect("Failed to parse keypair");
-            Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
+    // PDA-only tests run without a build, so fall back to a throwaway id
+    fn program_id_or_unique(name: &str) -> Pubkey {
+        if deploy_dir().join(format!("{}-keypair.json", name)).exists() {
+            common::load_program_id_in(&deploy_dir(), name)
         } else {
             Pubkey::new_unique()
         }
     }
 
+    fn buggy_program_id() -> Pubkey {
+        program_id_or_unique("buggy_amm")
+    }
+
     fn secure_program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("programs/amm/target/deploy/secure_amm-keypair.json");
+        program_id_or_unique("secure_amm")
+    }
+
+    fn setup_buggy() -> (LiteSVM, Keypair) {
+        common::setup_in(&deploy_dir(), "buggy_amm")
+    }
 
-        if keypair_path.exists() {
-            let keypair_bytes: Vec<u8> = serde_json::from_str(
-                &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
+    fn setup_secure() -> (LiteSVM, Keypair) {
+        common::setup_in(&deploy_dir(), "secure_amm")
+    }
+
+    fn secure_idl() -> Idl {
+        let path = deploy_dir().parent().unwrap().join("idl/secure_amm.json");
+        Idl::from_path(&path, secure_program_id())
+    }
+
+    /// Tokens each side of the pool starts with in the user's accounts
+    const USER_BALANCE: u64 = 1_000_000;
+
+    /// The accounts of a secure pool, before or after it exists
+    struct Pool {
+        seed: u64,
+        token_program: Pubkey,
+        mint_x: Pubkey,
+        mint_y: Pubkey,
+        config: Pubkey,
+        mint_lp: Pubkey,
+        vault_x: Pubkey,
+        vault_y: Pubkey,
+        user_x: Pubkey,
+        user_y: Pubkey,
+        user_lp: Pubkey,
+    }
+
+    fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        common::find_pda(
+            &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM,
+        )
+        .0
+    }
+
+    fn plant_token_state(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        svm.set_account(
+            address,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+    }
+
+    fn mint_state() -> Mint {
+        Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            supply: USER_BALANCE,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+    }
+
+    /// Two mints of `token_program` and the user's funded accounts for
+    /// them, and the addresses the pool will have
+    fn pool_fixture(svm: &mut LiteSVM, user: &Keypair, seed: u64, token_program: Pubkey) -> Pool {
+        let pid = secure_program_id();
+        let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (user_x, user_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for mint in [mint_x, mint_y] {
+            let mut data = vec![0u8; Mint::LEN];
+            Mint::pack(mint_state(), &mut data).unwrap();
+            plant_token_state(svm, mint, token_program, data);
+        }
+        for (account, mint) in [(user_x, mint_x), (user_y, mint_y)] {
+            let mut data = vec![0u8; TokenAccount::LEN];
+            TokenAccount::pack(
+                TokenAccount {
+                    mint,
+                    owner: user.pubkey(),
+                    amount: USER_BALANCE,
+                    delegate: COption::None,
+                    state: AccountState::Initialized,
+                    is_native: COption::None,
+                    delegated_amount: 0,
+                    close_authority: COption::None,
+                },
+                &mut data,
             )
-            .expect("Failed to parse keypair");
-            Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
-        } else {
-            Pubkey::new_unique()
+            .unwrap();
+            plant_token_state(svm, account, token_program, data);
+        }
+
+        let (config, _) = Seeds::new("config").nonce(seed).find(&pid).unwrap();
+        let (mint_lp, _) = common::find_pda(&[b"lp", config.as_ref()], &pid);
+        Pool {
+            seed,
+            token_program,
+            mint_x,
+            mint_y,
+            config,
+            mint_lp,
+            vault_x: ata(&config, &mint_x, &token_program),
+            vault_y: ata(&config, &mint_y, &token_program),
+            user_x,
+            user_y,
+            user_lp: ata(&user.pubkey(), &mint_lp, &token_program),
         }
     }
 
-    fn read_buggy_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("programs/amm/target/deploy/buggy_amm.so");
-        std::fs::read(so_path).expect("Failed to read buggy_amm.so")
+    /// Replace `mint` with a Token-2022 mint carrying a transfer fee of
+    /// `bps` on every transfer
+    fn add_transfer_fee(svm: &mut LiteSVM, mint: Pubkey, bps: u16) {
+        let extensions = [ExtensionType::TransferFeeConfig];
+        let len = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let fee = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        fee.newer_transfer_fee.transfer_fee_basis_points = bps.into();
+        fee.newer_transfer_fee.maximum_fee = u64::MAX.into();
+        state.base = mint_state();
+        state.pack_base();
+        state.init_account_type().unwrap();
+        plant_token_state(svm, mint, spl_token_2022::ID, data);
     }
 
-    fn read_secure_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("programs/amm/target/deploy/secure_amm.so");
-        std::fs::read(so_path).expect("Failed to read secure_amm.so")
+    fn constant_product() -> ArgValue {
+        ArgValue::Enum("ConstantProduct".to_string(), vec![])
     }
 
-    fn setup_buggy() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
-        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .expect("Airdrop failed");
-        svm.add_program(buggy_program_id(), &read_buggy_program());
-        (svm, payer)
+    fn initialize_ix(pool: &Pool, user: &Keypair) -> Instruction {
+        secure_idl()
+            .ix("initialize")
+            .arg("seed", pool.seed)
+            .arg("fee", 30u16)
+            .arg("curve", constant_product())
+            .account("initializer", user.pubkey())
+            .account("mint_x", pool.mint_x)
+            .account("mint_y", pool.mint_y)
+            .account("config", pool.config)
+            .account("mint_lp", pool.mint_lp)
+            .account("vault_x", pool.vault_x)
+            .account("vault_y", pool.vault_y)
+            .account("token_program", pool.token_program)
+            .build()
     }
 
-    fn setup_secure() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
-        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .expect("Airdrop failed");
-        svm.add_program(secure_program_id(), &read_secure_program());
-        (svm, payer)
+    fn deposit_ix(pool: &Pool, user: &Keypair, amount: u64, x: u64, y: u64) -> Instruction {
+        secure_idl()
+            .ix("deposit")
+            .arg("amount", amount)
+            .arg("max_x", x)
+            .arg("max_y", y)
+            .account("user", user.pubkey())
+            .account("mint_x", pool.mint_x)
+            .account("mint_y", pool.mint_y)
+            .account("mint_lp", pool.mint_lp)
+            .account("config", pool.config)
+            .account("vault_x", pool.vault_x)
+            .account("vault_y", pool.vault_y)
+            .account("user_x", pool.user_x)
+            .account("user_y", pool.user_y)
+            .account("user_lp", pool.user_lp)
+            .account("token_program", pool.token_program)
+            .build()
+    }
+
+    fn initialize_and_deposit_ix(
+        pool: &Pool,
+        user: &Keypair,
+        amount: u64,
+        x: u64,
+        y: u64,
+    ) -> Instruction {
+        secure_idl()
+            .ix("initialize_and_deposit")
+            .arg("seed", pool.seed)
+            .arg("fee", 30u16)
+            .arg("curve", constant_product())
+            .arg("amount", amount)
+            .arg("x", x)
+            .arg("y", y)
+            .account("initializer", user.pubkey())
+            .account("mint_x", pool.mint_x)
+            .account("mint_y", pool.mint_y)
+            .account("config", pool.config)
+            .account("mint_lp", pool.mint_lp)
+            .account("vault_x", pool.vault_x)
+            .account("vault_y", pool.vault_y)
+            .account("user_x", pool.user_x)
+            .account("user_y", pool.user_y)
+            .account("user_lp", pool.user_lp)
+            .account("token_program", pool.token_program)
+            .build()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut LiteSVM, ixs: &[Instruction], payer: &Keypair) -> TransactionResult {
+        let msg = Message::new(ixs, Some(&payer.pubkey()));
+        let tx = Transaction::new(&[payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    // Token-2022 vaults carry the ATA program's `ImmutableOwner` extension
+    fn token_balance(svm: &LiteSVM, account: &Pubkey) -> u64 {
+        let data = svm.get_account(account).unwrap().data;
+        StateWithExtensions::<TokenAccount>::unpack(&data)
+            .unwrap()
+            .base
+            .amount
     }
 
     // ============= BUGGY-AMM VULNERABILITY TESTS =============
@@ -86,7 +273,7 @@ mod tests {
         let (_svm, _payer) = setup_buggy();
         let pid = buggy_program_id();
 
-        let (config_pda, _bump) = Pubkey::find_program_address(&[b"config"], &pid);
+        let (config_pda, _bump) = common::find_pda(&[b"config"], &pid);
 
         // Only one possible config address per program - collision risk
         println!(
@@ -163,8 +350,20 @@ mod tests {
         let seed1: u64 = 1;
         let seed2: u64 = 2;
 
-        let (pda1, _) = Pubkey::find_program_address(&[b"config", &seed1.to_le_bytes()], &pid);
-        let (pda2, _) = Pubkey::find_program_address(&[b"config", &seed2.to_le_bytes()], &pid);
+        let (pda1, bump1) = Seeds::new("config").nonce(seed1).find(&pid).unwrap();
+        let (pda2, _) = Seeds::new("config").nonce(seed2).find(&pid).unwrap();
+        assert_eq!(
+            (pda1, bump1),
+            common::find_pda(&[b"config", &seed1.to_le_bytes()], &pid)
+        );
+        // What `Config::signer_seeds` signs with
+        assert_eq!(
+            Seeds::new("config")
+                .nonce(seed1)
+                .bump(bump1)
+                .verify(&pda1, &pid),
+            Ok(())
+        );
 
         println!("Secure PDA with seed 1: {}", pda1);
         println!("Secure PDA with seed 2: {}", pda2);
@@ -206,4 +405,111 @@ mod tests {
         println!("Slippage check (900 >= 950): {} - FAIL", fails);
         assert!(!fails);
     }
+
+    #[test]
+    fn test_secure_initialize_and_deposit_in_one_instruction() {
+        for token_program in TOKEN_PROGRAMS {
+            let (mut svm, user) = setup_secure();
+            let pool = pool_fixture(&mut svm, &user, 1, token_program);
+
+            let ix = initialize_and_deposit_ix(&pool, &user, 500, 1_000, 4_000);
+            let result = send(&mut svm, &[ix], &user);
+            assert!(
+                result.is_ok(),
+                "initialize and deposit failed: {:?}",
+                result
+            );
+
+            // The first deposit sets the price: all of both amounts go in,
+            // to vaults of the pool's token program
+            assert_eq!(svm.get_account(&pool.vault_x).unwrap().owner, token_program);
+            assert_eq!(token_balance(&svm, &pool.vault_x), 1_000);
+            assert_eq!(token_balance(&svm, &pool.vault_y), 4_000);
+            assert_eq!(token_balance(&svm, &pool.user_x), USER_BALANCE - 1_000);
+            assert_eq!(token_balance(&svm, &pool.user_lp), 500);
+        }
+    }
+
+    #[test]
+    fn test_secure_initialize_and_deposit_rolls_back_together() {
+        for token_program in TOKEN_PROGRAMS {
+            let (mut svm, user) = setup_secure();
+            let pool = pool_fixture(&mut svm, &user, 1, token_program);
+
+            // The pool would be created, then the y transfer overdraws: none
+            // of the five new accounts survives, and x is back with the user
+            let ix = initialize_and_deposit_ix(&pool, &user, 500, 1_000, USER_BALANCE + 1);
+            let result = send(&mut svm, &[ix], &user);
+            assert!(result.is_err());
+            for account in [
+                pool.config,
+                pool.mint_lp,
+                pool.vault_x,
+                pool.vault_y,
+                pool.user_lp,
+            ] {
+                assert!(svm.get_account(&account).is_none());
+            }
+            assert_eq!(token_balance(&svm, &pool.user_x), USER_BALANCE);
+
+            // A zero LP amount fails after the same initialization
+            svm.expire_blockhash();
+            let ix = initialize_and_deposit_ix(&pool, &user, 0, 1_000, 4_000);
+            let result = send(&mut svm, &[ix], &user);
+            assert_err!(result, ErrorCode::ZeroAmount);
+            assert!(svm.get_account(&pool.config).is_none());
+        }
+    }
+
+    #[test]
+    fn test_secure_pool_takes_one_token_program() {
+        // A Token-2022 pool handed a legacy mint for x
+        let (mut svm, user) = setup_secure();
+        let pool = pool_fixture(&mut svm, &user, 1, spl_token_2022::ID);
+        let mut data = vec![0u8; Mint::LEN];
+        Mint::pack(mint_state(), &mut data).unwrap();
+        plant_token_state(&mut svm, pool.mint_x, TOKEN_PROGRAM, data);
+
+        let result = send(&mut svm, &[initialize_ix(&pool, &user)], &user);
+        assert_err!(result, ErrorCode::ConstraintMintTokenProgram);
+    }
+
+    #[test]
+    fn test_secure_rejects_transfer_fee_mints() {
+        // The vault would receive less than each deposit says, and every
+        // swap out would pay the fee from the pool
+        let (mut svm, user) = setup_secure();
+        let pool = pool_fixture(&mut svm, &user, 1, spl_token_2022::ID);
+        add_transfer_fee(&mut svm, pool.mint_x, 100);
+
+        let result = send(&mut svm, &[initialize_ix(&pool, &user)], &user);
+        assert_err!(result, InstructionError::InvalidAccountData);
+        assert!(svm.get_account(&pool.config).is_none());
+    }
+
+    #[test]
+    fn test_compute_units_initialize_and_deposit() {
+        // The same funded pool through two instructions and through one
+        let flow = |combined: bool| {
+            let (mut svm, user) = setup_secure();
+            let pool = pool_fixture(&mut svm, &user, 1, TOKEN_PROGRAM);
+            let ixs = if combined {
+                vec![initialize_and_deposit_ix(&pool, &user, 500, 1_000, 4_000)]
+            } else {
+                vec![
+                    initialize_ix(&pool, &user),
+                    deposit_ix(&pool, &user, 500, 1_000, 4_000),
+                ]
+            };
+            send(&mut svm, &ixs, &user)
+        };
+
+        // One instruction validates the mints, config and vaults once
+        assert_cu_overhead(
+            "amm/initialize_and_deposit",
+            &flow(false),
+            &flow(true),
+            CuThreshold::unlimited().max_ratio(1.0),
+        );
+    }
 }
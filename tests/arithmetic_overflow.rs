@@ -2,55 +2,33 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
-    use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::corrupt::{self, Corruption};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        Account, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
 
-    fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/arithmetic_overflow-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
 
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
-    }
-
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/arithmetic_overflow.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+    fn program_id() -> Pubkey {
+        common::load_program_id("arithmetic_overflow")
     }
 
-    fn discriminator(name: &str) -> [u8; 8] {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let full_name = format!("global:{}", name);
-        let mut hasher = DefaultHasher::new();
-        full_name.hash(&mut hasher);
-        hasher.finish().to_le_bytes()
+    fn idl() -> Idl {
+        Idl::load("arithmetic_overflow")
     }
 
     fn derive_pool_pda(authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[b"pool", authority.as_ref()], program_id)
+        common::find_pda(&[b"pool", authority.as_ref()], program_id)
     }
 
     fn create_pool_data(authority: Pubkey, reserve_a: u64, reserve_b: u64, bump: u8) -> Vec<u8> {
-        let mut data = vec![0u8; 8]; // discriminator
+        let mut data = idl().account_discriminator("Pool").to_vec();
         data.extend_from_slice(authority.as_ref());
         data.extend_from_slice(&reserve_a.to_le_bytes());
         data.extend_from_slice(&reserve_b.to_le_bytes());
@@ -58,18 +36,18 @@ mod tests {
         data
     }
 
-    fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
+    fn setup(name: &str) -> (Scenario, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let payer = svm.keypair("payer");
         svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
             .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
+        svm.add_program("arithmetic_overflow");
         (svm, payer)
     }
 
     #[test]
     fn test_vulnerable_swap_overflow() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("arithmetic_overflow/vulnerable_swap_overflow");
         let pid = program_id();
         let (pool_pda, bump) = derive_pool_pda(&user.pubkey(), &pid);
 
@@ -97,28 +75,24 @@ mod tests {
         let exploit_amount = u64::MAX / 4;
         let min_out: u64 = 1;
 
-        let mut data = discriminator("vulnerable_swap").to_vec();
-        data.extend_from_slice(&exploit_amount.to_le_bytes());
-        data.extend_from_slice(&min_out.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(pool_pda, false),
-                AccountMeta::new_readonly(user.pubkey(), true),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("vulnerable_swap")
+            .arg("amount_in", exploit_amount)
+            .arg("min_out", min_out)
+            .account("user", user.pubkey())
+            .account("pool", pool_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("arithmetic_overflow/vulnerable_swap_overflow", &result);
         println!("Vulnerable swap with overflow input: {:?}", result);
     }
 
     #[test]
     fn test_secure_swap_handles_large_values() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("arithmetic_overflow/secure_swap_handles_large_values");
         let pid = program_id();
         let (pool_pda, bump) = derive_pool_pda(&user.pubkey(), &pid);
 
@@ -139,29 +113,25 @@ mod tests {
         let exploit_amount = u64::MAX / 4;
         let min_out: u64 = 1;
 
-        let mut data = discriminator("secure_swap").to_vec();
-        data.extend_from_slice(&exploit_amount.to_le_bytes());
-        data.extend_from_slice(&min_out.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(pool_pda, false),
-                AccountMeta::new_readonly(user.pubkey(), true),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_swap")
+            .arg("amount_in", exploit_amount)
+            .arg("min_out", min_out)
+            .account("user", user.pubkey())
+            .account("pool", pool_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("arithmetic_overflow/secure_swap_handles_large_values", &result);
         println!("Secure swap with large values: {:?}", result);
         // Should either succeed (u128 handles it) or fail gracefully
     }
 
     #[test]
     fn test_secure_swap_normal() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("arithmetic_overflow/secure_swap_normal");
         let pid = program_id();
         let (pool_pda, bump) = derive_pool_pda(&user.pubkey(), &pid);
 
@@ -187,22 +157,103 @@ mod tests {
         let amount_in: u64 = 1000;
         let min_out: u64 = 900;
 
-        let mut data = discriminator("secure_swap").to_vec();
-        data.extend_from_slice(&amount_in.to_le_bytes());
-        data.extend_from_slice(&min_out.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(pool_pda, false),
-                AccountMeta::new_readonly(user.pubkey(), true),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_swap")
+            .arg("amount_in", amount_in)
+            .arg("min_out", min_out)
+            .account("user", user.pubkey())
+            .account("pool", pool_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("arithmetic_overflow/secure_swap_normal", &result);
         println!("Normal secure swap: {:?}", result);
     }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        // Same in-range swap through both instructions from the same pool
+        let (mut svm, user) = setup("arithmetic_overflow/compute_units_secure_vs_vulnerable");
+        let pid = program_id();
+        let (pool_pda, bump) = derive_pool_pda(&user.pubkey(), &pid);
+
+        let pool_data = create_pool_data(user.pubkey(), 1_000_000_000, 1_000_000_000, bump);
+        svm.set_account(
+            pool_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: pool_data,
+                owner: pid,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        let ready = svm.snapshot();
+
+        let swap = |svm: &mut Scenario, instruction: &str| {
+            let ix = idl()
+                .ix(instruction)
+                .arg("amount_in", 1000u64)
+                .arg("min_out", 900u64)
+                .account("user", user.pubkey())
+                .account("pool", pool_pda)
+                .build();
+
+            let msg = Message::new(&[ix], Some(&user.pubkey()));
+            let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        let vulnerable = swap(&mut svm, "vulnerable_swap");
+        svm.restore(ready);
+        let secure = swap(&mut svm, "secure_swap");
+
+        assert_cu_overhead(
+            "arithmetic_overflow/swap",
+            &vulnerable,
+            &secure,
+            CuThreshold::default(),
+        );
+    }
+
+    #[test]
+    fn test_secure_swap_rejects_corrupted_pool() {
+        // SCENARIO: An ordinary swap against a damaged pool
+        // EXPECTED: Every corruption fails with an Anchor error, no panics
+        let (mut svm, user) = setup("arithmetic_overflow/secure_swap_rejects_corrupted_pool");
+        let pid = program_id();
+        let (pool_pda, bump) = derive_pool_pda(&user.pubkey(), &pid);
+
+        let pool_data = create_pool_data(user.pubkey(), 1_000_000_000, 1_000_000_000, bump);
+        svm.set_account(
+            pool_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: pool_data,
+                owner: pid,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        // A zeroed authority no longer derives the pool address
+        let mut corruptions = Corruption::standard();
+        corruptions.push(Corruption::zero_field(&idl(), "Pool", "authority"));
+
+        corrupt::assert_rejects_corrupted(&svm, pool_pda, &corruptions, |svm| {
+            let ix = idl()
+                .ix("secure_swap")
+                .arg("amount_in", 1000u64)
+                .arg("min_out", 900u64)
+                .account("user", user.pubkey())
+                .account("pool", pool_pda)
+                .build();
+            let msg = Message::new(&[ix], Some(&user.pubkey()));
+            Transaction::new(&[&user], msg, svm.latest_blockhash())
+        });
+    }
 }
@@ -0,0 +1,150 @@
+//! Vulnerability coverage matrix
+//!
+//! Every program under `programs/`, `programs-native/` and
+//! `programs-pinocchio/` must ship with at least one test of each kind:
+//! - exploit: the attack succeeds against the vulnerable path
+//! - rejects: the same attack fails against the secure path
+//! - accepts: legitimate use of the secure path still works
+//!
+//! Test names across suites are not uniform enough to classify
+//! automatically, so each entry of the `solsec_registry` catalog declares
+//! its tests in `coverage`, and so does each of its variants. Adding a program without an entry, or an entry
+//! naming a test that does not exist, fails this check with the full list
+//! of gaps.
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::path::{Path, PathBuf};
+
+    use solsec_registry::{Coverage, CATALOG, VARIANTS};
+
+    fn tests_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn program_dirs(tree: &str) -> BTreeSet<String> {
+        let programs = tests_dir().parent().unwrap().join(tree);
+        std::fs::read_dir(&programs)
+            .expect("Failed to read programs directory")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join("Cargo.toml").exists())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Names of every `#[test]` function in a suite file.
+    fn test_fns(path: &Path) -> BTreeSet<String> {
+        let source = std::fs::read_to_string(path).unwrap_or_default();
+        let mut names = BTreeSet::new();
+        let mut after_attr = false;
+        for line in source.lines().map(str::trim) {
+            if line == "#[test]" {
+                after_attr = true;
+                continue;
+            }
+            if after_attr && !line.starts_with("#[") {
+                if let Some(rest) = line.strip_prefix("fn ") {
+                    let name: String = rest
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    names.insert(name);
+                }
+                after_attr = false;
+            }
+        }
+        names
+    }
+
+    /// What is missing from `program`'s suite and its coverage.
+    fn suite_gaps(program: &str, suite: &str, coverage: &Coverage, manifest: &str) -> Vec<String> {
+        let mut gaps = Vec::new();
+        let path = tests_dir().join(format!("{}.rs", suite));
+        if !path.exists() {
+            gaps.push(format!("{}: suite tests/{}.rs is missing", program, suite));
+            return gaps;
+        }
+        if !manifest.contains(&format!("path = \"{}.rs\"", suite)) {
+            gaps.push(format!(
+                "{}: suite {}.rs is not registered as a [[test]] in tests/Cargo.toml",
+                program, suite
+            ));
+        }
+
+        let defined = test_fns(&path);
+        for (kind, names) in [
+            ("exploit", coverage.exploit),
+            ("rejects", coverage.rejects),
+            ("accepts", coverage.accepts),
+        ] {
+            if names.is_empty() {
+                gaps.push(format!("{}: no {} test", program, kind));
+            }
+            for name in names {
+                if !defined.contains(*name) {
+                    gaps.push(format!(
+                        "{}: {} test {} not found in {}.rs",
+                        program, kind, name, suite
+                    ));
+                }
+            }
+        }
+        gaps
+    }
+
+    fn gaps() -> Vec<String> {
+        let mut gaps = Vec::new();
+        let manifest = std::fs::read_to_string(tests_dir().join("Cargo.toml")).unwrap();
+        let programs = program_dirs("programs");
+
+        for program in &programs {
+            if !CATALOG.iter().any(|row| row.program == program.as_str()) {
+                gaps.push(format!("{}: no entry in the registry", program));
+            }
+        }
+
+        for row in CATALOG {
+            if !programs.contains(row.program) {
+                gaps.push(format!("{}: listed in the registry but not under programs/", row.program));
+                continue;
+            }
+            gaps.extend(suite_gaps(row.program, row.suite, &row.coverage, &manifest));
+        }
+
+        // Variants live under their entry's directory name
+        let mut ported = BTreeSet::new();
+        for tree in ["programs-native", "programs-pinocchio"] {
+            for dir in program_dirs(tree) {
+                let path = format!("{}/{}", tree, dir);
+                if !VARIANTS.iter().any(|v| v.dir() == path) {
+                    gaps.push(format!("{}: no variant in the registry", path));
+                }
+                ported.insert(path);
+            }
+        }
+        for variant in VARIANTS {
+            if !ported.contains(&variant.dir()) {
+                gaps.push(format!("{}: not under {}", variant.program, variant.dir()));
+                continue;
+            }
+            gaps.extend(suite_gaps(
+                variant.program,
+                variant.suite,
+                &variant.coverage,
+                &manifest,
+            ));
+        }
+        gaps
+    }
+
+    #[test]
+    fn test_every_program_has_exploit_reject_and_accept_tests() {
+        let gaps = gaps();
+        assert!(
+            gaps.is_empty(),
+            "vulnerability coverage gaps:\n  {}",
+            gaps.join("\n  ")
+        );
+    }
+}
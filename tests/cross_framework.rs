@@ -0,0 +1,302 @@
+//! The same attacks against every framework's program
+//!
+//! `signer-authorization` and `account-griefing` are each written three
+//! times: in Anchor, against a raw `solana-program` entrypoint and in
+//! Pinocchio. Each test here runs one attack against all three and checks
+//! the attacker ends up the same way every time: what they take, and the
+//! kind of error that stops them. Errors compare by their `ErrorClass`,
+//! since each framework names the same failure differently.
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use litesvm::types::TransactionResult;
+    use litesvm::LiteSVM;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::errors;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{Account, Instruction, Keypair, Pubkey, Signer, LAMPORTS_PER_SOL};
+    use solsec_guards::MAINNET_BETA_GENESIS_HASH;
+
+    const VAULTS: [&str; 3] = [
+        "signer_authorization",
+        "native_signer_authorization",
+        "p_signer_authorization",
+    ];
+    const STAKES: [&str; 3] = [
+        "account_griefing",
+        "native_account_griefing",
+        "p_account_griefing",
+    ];
+    const NONCE: u64 = 847291;
+
+    /// How a transaction ended, comparable across frameworks: the class of
+    /// its error, or the runtime error itself where it has none, as for the
+    /// system program's.
+    fn outcome(result: &TransactionResult) -> Result<(), String> {
+        result
+            .as_ref()
+            .map(|_| ())
+            .map_err(|failed| match errors::error_class(failed) {
+                Some(class) => class.to_string(),
+                None => format!("{:?}", errors::instruction_error(failed)),
+            })
+    }
+
+    /// Replace the devnet record `common::setup` gave `program` with one of
+    /// `genesis_hash`, or with none at all.
+    fn record_cluster(svm: &mut LiteSVM, program: &str, genesis_hash: Option<[u8; 32]>) {
+        let program_id = common::load_program_id(program);
+        let record = match genesis_hash {
+            Some(hash) => common::cluster_record_account(svm, &program_id, hash),
+            None => Account::default(),
+        };
+        svm.set_account(common::cluster_record(&program_id), record)
+            .unwrap();
+    }
+
+    /// How a vulnerable instruction is refused off devnet: on mainnet-beta
+    /// with `solsec_guards::MAINNET_CLUSTER_ERROR`, which no class covers,
+    /// and as an invalid account before anything was recorded.
+    fn refused(genesis_hash: Option<[u8; 32]>) -> Result<(), String> {
+        match genesis_hash {
+            Some(_) => Err("Some(Custom(7000))".to_string()),
+            None => Err("invalid-account".to_string()),
+        }
+    }
+
+    /// Run `attack` against each program and assert every one ends as the
+    /// Anchor program, the first, does.
+    fn same_everywhere<T: PartialEq + Debug>(programs: &[&str], attack: impl Fn(&str) -> T) -> T {
+        let expected = attack(programs[0]);
+        for program in &programs[1..] {
+            assert_eq!(
+                attack(program),
+                expected,
+                "{} and {} differ",
+                program,
+                programs[0]
+            );
+        }
+        expected
+    }
+
+    /// A vault created for `authority` through `program`, holding 5 SOL
+    /// over its rent.
+    fn vault(program: &str) -> (LiteSVM, Keypair, Pubkey) {
+        let (mut svm, authority) = common::setup(program);
+        let program_id = common::load_program_id(program);
+        let (vault, _) = common::find_pda(&[b"vault", authority.pubkey().as_ref()], &program_id);
+
+        let ix = Idl::load(program)
+            .ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("vault", vault)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &authority));
+        assert!(
+            result.is_ok(),
+            "{}: vault setup failed: {:?}",
+            program,
+            result
+        );
+        svm.airdrop(&vault, 5 * LAMPORTS_PER_SOL).unwrap();
+        (svm, authority, vault)
+    }
+
+    fn withdraw(
+        program: &str,
+        name: &str,
+        vault: Pubkey,
+        authority: &Pubkey,
+        destination: &Pubkey,
+    ) -> Instruction {
+        Idl::load(program)
+            .ix(name)
+            .arg("amount", 4 * LAMPORTS_PER_SOL)
+            .account("vault", vault)
+            .account("authority", *authority)
+            .account("destination", *destination)
+            .build()
+    }
+
+    /// What an attacker who doesn't hold the authority's key gets from
+    /// `name`: how it ended, and the lamports that moved to them.
+    fn steal(program: &str, name: &str) -> (Result<(), String>, u64) {
+        let (svm, authority, vault) = vault(program);
+        steal_from(svm, program, name, &authority, vault)
+    }
+
+    fn steal_from(
+        mut svm: LiteSVM,
+        program: &str,
+        name: &str,
+        authority: &Keypair,
+        vault: Pubkey,
+    ) -> (Result<(), String>, u64) {
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let destination = Pubkey::new_unique();
+
+        let ix = withdraw(program, name, vault, &authority.pubkey(), &destination);
+        let ix = attacker::strip_signer(ix, &authority.pubkey());
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+        let taken = svm.get_balance(&destination).unwrap_or(0);
+        (outcome(&result), taken)
+    }
+
+    #[test]
+    fn test_missing_signer_drains_every_vault() {
+        let outcome = same_everywhere(&VAULTS, |program| steal(program, "vulnerable_withdraw"));
+        assert_eq!(outcome, (Ok(()), 4 * LAMPORTS_PER_SOL));
+    }
+
+    #[test]
+    fn test_signer_check_stops_every_attacker() {
+        let outcome = same_everywhere(&VAULTS, |program| steal(program, "secure_withdraw"));
+        assert_eq!(outcome, (Err("unauthorized".to_string()), 0));
+    }
+
+    #[test]
+    fn test_authority_withdraws_from_every_vault() {
+        let outcome = same_everywhere(&VAULTS, |program| {
+            let (mut svm, authority, vault) = vault(program);
+            let destination = Pubkey::new_unique();
+
+            let ix = withdraw(
+                program,
+                "secure_withdraw",
+                vault,
+                &authority.pubkey(),
+                &destination,
+            );
+            let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &authority));
+            (outcome(&result), svm.get_balance(&destination))
+        });
+        assert_eq!(outcome, (Ok(()), Some(4 * LAMPORTS_PER_SOL)));
+    }
+
+    #[test]
+    fn test_no_vault_drains_off_devnet() {
+        for genesis_hash in [Some(MAINNET_BETA_GENESIS_HASH), None] {
+            let outcome = same_everywhere(&VAULTS, |program| {
+                let (mut svm, authority, vault) = vault(program);
+                record_cluster(&mut svm, program, genesis_hash);
+                steal_from(svm, program, "vulnerable_withdraw", &authority, vault)
+            });
+            assert_eq!(outcome, (refused(genesis_hash), 0));
+        }
+    }
+
+    /// `program` deployed, a user, and an attacker who has funded the
+    /// user's stake address at `seeds` with the rent of an empty account.
+    fn griefed(
+        program: &str,
+        seeds: impl Fn(&Pubkey) -> Vec<Vec<u8>>,
+    ) -> (LiteSVM, Keypair, Pubkey) {
+        let (mut svm, user) = common::setup(program);
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let seeds = seeds(&user.pubkey());
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (stake, _) = common::find_pda(&seeds, &common::load_program_id(program));
+        let lamports = svm.minimum_balance_for_rent_exemption(0);
+        attacker::prefund(&mut svm, &attacker, &stake, lamports);
+        (svm, user, stake)
+    }
+
+    fn secure_seeds(user: &Pubkey) -> Vec<Vec<u8>> {
+        vec![
+            b"stake".to_vec(),
+            user.to_bytes().to_vec(),
+            NONCE.to_le_bytes().to_vec(),
+        ]
+    }
+
+    fn secure_create(program: &str, user: &Keypair, stake: Pubkey) -> Instruction {
+        Idl::load(program)
+            .ix("secure_create_stake")
+            .arg("nonce", NONCE)
+            .account("user", user.pubkey())
+            .account("stake_account", stake)
+            .build()
+    }
+
+    #[test]
+    fn test_prefunding_blocks_every_predictable_stake() {
+        let outcome = same_everywhere(&STAKES, |program| {
+            let (mut svm, user, stake) = griefed(program, |user| {
+                vec![b"stake".to_vec(), user.to_bytes().to_vec()]
+            });
+            let ix = Idl::load(program)
+                .ix("vulnerable_create_stake")
+                .account("user", user.pubkey())
+                .account("stake_account", stake)
+                .build();
+            outcome(&svm.send_transaction(attacker::transaction(&svm, &[ix], &user)))
+        });
+        // SystemError::AccountAlreadyInUse, from the CPI
+        assert_eq!(outcome, Err("Some(Custom(0))".to_string()));
+    }
+
+    #[test]
+    fn test_no_predictable_stake_created_off_devnet() {
+        for genesis_hash in [Some(MAINNET_BETA_GENESIS_HASH), None] {
+            let outcome = same_everywhere(&STAKES, |program| {
+                let (mut svm, user) = common::setup(program);
+                record_cluster(&mut svm, program, genesis_hash);
+                let program_id = common::load_program_id(program);
+                let (stake, _) = common::find_pda(&[b"stake", user.pubkey().as_ref()], &program_id);
+
+                let ix = Idl::load(program)
+                    .ix("vulnerable_create_stake")
+                    .account("user", user.pubkey())
+                    .account("stake_account", stake)
+                    .build();
+                let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+                (outcome(&result), svm.get_account(&stake).is_none())
+            });
+            assert_eq!(outcome, (refused(genesis_hash), true));
+        }
+    }
+
+    #[test]
+    fn test_every_secure_create_checks_the_nonce() {
+        let outcome = same_everywhere(&STAKES, |program| {
+            let (mut svm, user) = common::setup(program);
+            let mut seeds = secure_seeds(&user.pubkey());
+            seeds[2] = 12345u64.to_le_bytes().to_vec();
+            let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+            let (other, _) = common::find_pda(&seeds, &common::load_program_id(program));
+
+            let ix = secure_create(program, &user, other);
+            outcome(&svm.send_transaction(attacker::transaction(&svm, &[ix], &user)))
+        });
+        assert_eq!(outcome, Err("invalid-account".to_string()));
+    }
+
+    #[test]
+    fn test_every_secure_create_survives_prefunding() {
+        let outcome = same_everywhere(&STAKES, |program| {
+            let (mut svm, user, stake) = griefed(program, secure_seeds);
+            let deposit = Idl::load(program)
+                .ix("secure_deposit")
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("user", user.pubkey())
+                .account("stake_account", stake)
+                .build();
+            let ixs = [secure_create(program, &user, stake), deposit];
+            let result = svm.send_transaction(attacker::transaction(&svm, &ixs, &user));
+
+            // Owned by the program, holding the deposit over its rent
+            let account = svm.get_account(&stake).unwrap();
+            let rent = svm.minimum_balance_for_rent_exemption(account.data.len());
+            let owned = account.owner == common::load_program_id(program);
+            (outcome(&result), owned, account.lamports - rent)
+        });
+        assert_eq!(outcome, (Ok(()), true, LAMPORTS_PER_SOL));
+    }
+}
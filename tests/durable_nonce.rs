@@ -0,0 +1,263 @@
+//! Tests for the Durable Nonce Replay vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: A withdrawal the owner signed against a relayer's durable
+//!   nonce still lands after the owner gave up on it and paid again
+//! - Secure: Each withdrawal names the vault's next sequence and a last slot,
+//!   so a held copy pays nothing once another landed, the slot passed or the
+//!   owner revoked it
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::nonce;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        Clock, Instruction, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    const AMOUNT: u64 = LAMPORTS_PER_SOL;
+    /// How long the owner means an authorization to stand, about as long
+    /// as a blockhash does
+    const VALID_FOR_SLOTS: u64 = 150;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("durable_nonce")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("durable_nonce")
+    }
+
+    fn vault(owner: &Pubkey) -> Pubkey {
+        common::find_pda(&[b"vault", owner.as_ref()], &program_id()).0
+    }
+
+    /// The program with the owner's vault holding 10 SOL, and a relayer,
+    /// the attacker, with a nonce account of their own.
+    fn setup(name: &str) -> (Scenario, Keypair, Keypair, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let owner = svm.keypair("owner");
+        let relayer = svm.keypair("relayer");
+        for signer in [&owner, &relayer] {
+            svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        svm.add_program("durable_nonce");
+
+        let ix = idl()
+            .ix("initialize")
+            .account("owner", owner.pubkey())
+            .account("vault", vault(&owner.pubkey()))
+            .build();
+        send(&mut svm, ix, &[&owner]).expect("Initialize failed");
+        svm.airdrop(&vault(&owner.pubkey()), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let nonce = svm.keypair("relayer_nonce");
+        svm.create_nonce_account(&relayer, &nonce, &relayer.pubkey());
+        (svm, owner, relayer, nonce)
+    }
+
+    /// Send `ix` against the current blockhash.
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    fn vulnerable_withdraw(owner: &Pubkey, destination: &Pubkey) -> Instruction {
+        idl()
+            .ix("vulnerable_withdraw")
+            .arg("amount", AMOUNT)
+            .account("vault", vault(owner))
+            .account("owner", *owner)
+            .account("destination", *destination)
+            .build()
+    }
+
+    fn secure_withdraw(
+        owner: &Pubkey,
+        destination: &Pubkey,
+        sequence: u64,
+        expires_at: u64,
+    ) -> Instruction {
+        idl()
+            .ix("secure_withdraw")
+            .arg("amount", AMOUNT)
+            .arg("sequence", sequence)
+            .arg("expires_at", expires_at)
+            .account("vault", vault(owner))
+            .account("owner", *owner)
+            .account("destination", *destination)
+            .build()
+    }
+
+    /// What the owner signs and hands to the relayer: `ix` against the
+    /// relayer's nonce, with the relayer paying the fee.
+    fn hand_to_relayer(
+        svm: &Scenario,
+        ix: Instruction,
+        owner: &Keypair,
+        relayer: &Keypair,
+        nonce: &Keypair,
+    ) -> Transaction {
+        nonce::nonce_transaction(
+            svm,
+            &[ix],
+            &[relayer, owner],
+            &nonce.pubkey(),
+            &relayer.pubkey(),
+        )
+    }
+
+    fn expiry(svm: &Scenario) -> u64 {
+        svm.get_sysvar::<Clock>().slot + VALID_FOR_SLOTS
+    }
+
+    /// The sequence the vault expects next, at bytes 40..48.
+    fn sequence(svm: &Scenario, owner: &Pubkey) -> u64 {
+        let data = svm.get_account(&vault(owner)).unwrap().data;
+        u64::from_le_bytes(data[40..48].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_vulnerable_pays_a_held_nonce_transaction_twice() {
+        // SCENARIO: The owner signs a 1 SOL payment to the relayer, against
+        // the relayer's durable nonce
+        // ATTACK: Hold it until the owner believes it expired and pays
+        // again, then land it
+        // EXPECTED: Vulnerable version pays twice (EXPLOIT WORKS)
+
+        let (mut svm, owner, relayer, nonce) =
+            setup("durable_nonce/vulnerable_pays_a_held_nonce_transaction_twice");
+        let vault_before = svm.get_balance(&vault(&owner.pubkey())).unwrap();
+        let ix = vulnerable_withdraw(&owner.pubkey(), &relayer.pubkey());
+        let held = hand_to_relayer(&svm, ix.clone(), &owner, &relayer, &nonce);
+
+        // Step 1: The relayer sits on it. The blockhash moves on, and the
+        // owner, seeing nothing land, signs the payment again
+        svm.expire_blockhash();
+        let result = send(&mut svm, ix, &[&owner]);
+        assert!(result.is_ok(), "{:?}", result);
+        let relayer_before = svm.get_balance(&relayer.pubkey()).unwrap();
+
+        // Step 2: The relayer lands the transaction they held
+        let (result, diff) = svm.diff(|svm| svm.send_transaction(held));
+        assert!(result.is_ok(), "{:?}", result);
+        diff.with_idl(&idl()).expect_only_changed([
+            vault(&owner.pubkey()),
+            relayer.pubkey(),
+            nonce.pubkey(),
+        ]);
+
+        // The vault paid the same authorization twice; the relayer's
+        // second payment, less the fee for two signatures
+        assert_eq!(
+            svm.get_balance(&vault(&owner.pubkey())),
+            Some(vault_before - 2 * AMOUNT)
+        );
+        assert_eq!(
+            svm.get_balance(&relayer.pubkey()),
+            Some(relayer_before + AMOUNT - 10_000)
+        );
+    }
+
+    #[test]
+    fn test_secure_rejects_a_held_transaction_after_resending() {
+        // SCENARIO: The same payment, as sequence 0, held by the relayer
+        // while the owner pays again
+        // EXPECTED: Secure version REJECTS the held copy (FIX WORKS)
+
+        let (mut svm, owner, relayer, nonce) =
+            setup("durable_nonce/secure_rejects_a_held_transaction_after_resending");
+        let ix = secure_withdraw(&owner.pubkey(), &relayer.pubkey(), 0, expiry(&svm));
+        let held = hand_to_relayer(&svm, ix.clone(), &owner, &relayer, &nonce);
+
+        svm.expire_blockhash();
+        let result = send(&mut svm, ix, &[&owner]);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(sequence(&svm, &owner.pubkey()), 1);
+
+        assert_err!(svm.send_transaction(held), WithdrawError::StaleSequence);
+    }
+
+    #[test]
+    fn test_secure_rejects_an_expired_authorization() {
+        // SCENARIO: The owner doesn't pay again, and the relayer holds the
+        // payment past the slot the owner signed for
+        // EXPECTED: Secure version REJECTS it, though the nonce keeps the
+        // transaction valid (FIX WORKS)
+
+        let (mut svm, owner, relayer, nonce) =
+            setup("durable_nonce/secure_rejects_an_expired_authorization");
+        let expires_at = expiry(&svm);
+        let ix = secure_withdraw(&owner.pubkey(), &relayer.pubkey(), 0, expires_at);
+        let held = hand_to_relayer(&svm, ix, &owner, &relayer, &nonce);
+
+        svm.warp_to_slot(expires_at + 1);
+        svm.expire_blockhash();
+        assert_err!(
+            svm.send_transaction(held),
+            WithdrawError::AuthorizationExpired
+        );
+        assert_eq!(sequence(&svm, &owner.pubkey()), 0);
+    }
+
+    #[test]
+    fn test_secure_rejects_a_revoked_authorization() {
+        // SCENARIO: The owner stops trusting the relayer and revokes
+        // sequence 0 before it lands
+        // EXPECTED: Secure version REJECTS the held payment (FIX WORKS)
+
+        let (mut svm, owner, relayer, nonce) =
+            setup("durable_nonce/secure_rejects_a_revoked_authorization");
+        let ix = secure_withdraw(&owner.pubkey(), &relayer.pubkey(), 0, expiry(&svm));
+        let held = hand_to_relayer(&svm, ix, &owner, &relayer, &nonce);
+
+        let revoke = idl()
+            .ix("secure_revoke")
+            .arg("sequence", 0u64)
+            .account("vault", vault(&owner.pubkey()))
+            .account("owner", owner.pubkey())
+            .build();
+        let result = send(&mut svm, revoke, &[&owner]);
+        assert!(result.is_ok(), "{:?}", result);
+
+        assert_err!(svm.send_transaction(held), WithdrawError::StaleSequence);
+        assert_eq!(sequence(&svm, &owner.pubkey()), 1);
+    }
+
+    #[test]
+    fn test_secure_pays_a_nonce_transaction_once() {
+        let (mut svm, owner, relayer, nonce) =
+            setup("durable_nonce/secure_pays_a_nonce_transaction_once");
+        let vault_before = svm.get_balance(&vault(&owner.pubkey())).unwrap();
+        let ix = secure_withdraw(&owner.pubkey(), &relayer.pubkey(), 0, expiry(&svm));
+        let held = hand_to_relayer(&svm, ix, &owner, &relayer, &nonce);
+
+        // A nonce transaction still lands once its blockhash would have expired
+        svm.expire_blockhash();
+        let result = svm.send_transaction(held.clone());
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(
+            svm.get_balance(&vault(&owner.pubkey())),
+            Some(vault_before - AMOUNT)
+        );
+        assert_eq!(sequence(&svm, &owner.pubkey()), 1);
+
+        // Once: landing it advanced the nonce
+        svm.expire_blockhash();
+        assert_err!(
+            svm.send_transaction(held),
+            TransactionError::BlockhashNotFound
+        );
+    }
+}
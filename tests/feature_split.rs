@@ -0,0 +1,211 @@
+//! Release builds without the vulnerable instructions
+//!
+//! Every program gates its vulnerable instructions behind a `vulnerable`
+//! feature, on by default so the demos and suites keep working. Built with
+//! `--no-default-features` a program holds only its secure instructions;
+//! each `vulnerable` module refuses to compile without the feature, so one
+//! that loses its `#[cfg]` in `lib.rs` fails the build instead of shipping.
+//! `buggy-amm` has nothing secure to keep and refuses to build at all;
+//! `secure-amm`, its counterpart, has nothing to gate.
+//!
+//! Every Anchor program on the prelude also builds against Anchor 0.31
+//! with its `anchor-031` feature, checked the same way.
+//!
+//! These run `cargo check` on each program both ways, so they need no
+//! validator, SBF toolchain or CI.
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Output};
+
+    // `buggy-amm` is all exploit surface, see the module docs
+    const VULNERABLE_ONLY: &str = "buggy-amm";
+    // And its counterpart has no exploit surface to gate
+    const SECURE_ONLY: &str = "secure-amm";
+
+    fn root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .to_path_buf()
+    }
+
+    /// Every workspace crate under the program trees, nested ones
+    /// included, with its `cargo metadata` package. `programs/amm` is its
+    /// own workspace of two, so it's read separately.
+    fn program_crates() -> Vec<(PathBuf, serde_json::Value)> {
+        let trees = ["programs", "programs-native", "programs-pinocchio"].map(|t| root().join(t));
+        let mut crates: Vec<(PathBuf, serde_json::Value)> = [root(), root().join("programs/amm")]
+            .iter()
+            .flat_map(|workspace| {
+                let output = Command::new(cargo())
+                    .args(["metadata", "--no-deps", "--format-version", "1"])
+                    .current_dir(workspace)
+                    .output()
+                    .unwrap_or_else(|e| panic!("Failed to run cargo metadata: {}", e));
+                assert!(
+                    output.status.success(),
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+                metadata["packages"].as_array().unwrap().clone()
+            })
+            .map(|package| {
+                let manifest = PathBuf::from(package["manifest_path"].as_str().unwrap());
+                (manifest.parent().unwrap().to_path_buf(), package)
+            })
+            .filter(|(dir, _)| trees.iter().any(|tree| dir.starts_with(tree)))
+            .collect();
+        crates.sort_by(|(a, _), (b, _)| a.cmp(b));
+        crates
+    }
+
+    fn has_feature(package: &serde_json::Value, feature: &str) -> bool {
+        package["features"].get(feature).is_some()
+    }
+
+    /// Crate directories of every program with a `vulnerable` feature.
+    fn gated_programs() -> Vec<PathBuf> {
+        program_crates()
+            .into_iter()
+            .filter_map(|(dir, package)| has_feature(&package, "vulnerable").then_some(dir))
+            .collect()
+    }
+
+    /// Crate directories of every Anchor program on the prelude, with
+    /// whether it has an `anchor-031` feature.
+    fn prelude_programs() -> Vec<(PathBuf, bool)> {
+        program_crates()
+            .into_iter()
+            .filter(|(_, package)| {
+                package["dependencies"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|dep| dep["name"] == "solsec-prelude")
+            })
+            .map(|(dir, package)| (dir, has_feature(&package, "anchor-031")))
+            .collect()
+    }
+
+    fn cargo() -> String {
+        std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string())
+    }
+
+    fn name(crate_dir: &Path) -> String {
+        crate_dir
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn check(crate_dir: &Path, args: &[&str]) -> Output {
+        Command::new(cargo())
+            .args(["check", "--quiet", "--lib", "--manifest-path"])
+            .arg(crate_dir.join("Cargo.toml"))
+            .args(args)
+            .output()
+            .unwrap_or_else(|e| panic!("Failed to run cargo check: {}", e))
+    }
+
+    fn failures(args: &[&str]) -> Vec<String> {
+        let dirs: Vec<PathBuf> = gated_programs()
+            .into_iter()
+            .filter(|dir| name(dir) != VULNERABLE_ONLY)
+            .collect();
+        failures_in(&dirs, args)
+    }
+
+    fn failures_in(dirs: &[PathBuf], args: &[&str]) -> Vec<String> {
+        dirs.iter()
+            .filter_map(|dir| {
+                let output = check(dir, args);
+                (!output.status.success()).then(|| {
+                    format!(
+                        "{}:\n{}",
+                        name(dir),
+                        String::from_utf8_lossy(&output.stderr)
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_every_program_gates_its_vulnerable_instructions() {
+        let names: Vec<String> = gated_programs().iter().map(|dir| name(dir)).collect();
+        let missing: Vec<String> = program_crates()
+            .iter()
+            .filter(|(_, package)| !has_feature(package, "vulnerable"))
+            .map(|(dir, _)| name(dir))
+            .filter(|n| n != SECURE_ONLY)
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "programs without a `vulnerable` feature: {}",
+            missing.join(", ")
+        );
+        assert!(names.iter().any(|n| n == VULNERABLE_ONLY));
+
+        // The guard that catches a module compiled in without the feature
+        let unguarded: Vec<String> = gated_programs()
+            .iter()
+            .filter(|dir| {
+                std::fs::read_to_string(dir.join("src/vulnerable.rs"))
+                    .is_ok_and(|source| !source.contains("#[cfg(not(feature = \"vulnerable\"))]"))
+            })
+            .map(|dir| name(dir))
+            .collect();
+        assert!(
+            unguarded.is_empty(),
+            "vulnerable modules without a compile_error! guard: {}",
+            unguarded.join(", ")
+        );
+    }
+
+    #[test]
+    fn test_programs_build_with_vulnerable_instructions() {
+        let failures = failures(&[]);
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_programs_build_without_vulnerable_instructions() {
+        let failures = failures(&["--no-default-features"]);
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_anchor_programs_build_against_anchor_031() {
+        let programs = prelude_programs();
+        assert!(!programs.is_empty());
+        let missing: Vec<String> = programs
+            .iter()
+            .filter(|(_, offered)| !offered)
+            .map(|(dir, _)| name(dir))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "Anchor programs without an `anchor-031` feature: {}",
+            missing.join(", ")
+        );
+
+        let dirs: Vec<PathBuf> = programs.into_iter().map(|(dir, _)| dir).collect();
+        let failures = failures_in(&dirs, &["--features", "anchor-031"]);
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_buggy_amm_only_builds_with_vulnerable_instructions() {
+        let dir = root().join("programs/amm").join(VULNERABLE_ONLY);
+        assert!(check(&dir, &[]).status.success());
+
+        let output = check(&dir, &["--no-default-features"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("only builds with the `vulnerable` feature"));
+    }
+}
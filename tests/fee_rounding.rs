@@ -0,0 +1,196 @@
+//! Tests for the Fee Rounding Dust vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: A fee rounded down pays nothing on small withdrawals, so
+//!   splitting one withdrawal into many avoids the fee entirely
+//! - Secure: A fee rounded up charges every piece at least a lamport, so
+//!   the pieces pay at least what the whole would have
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        Instruction, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    const FEE_BPS: u16 = 30;
+    /// The largest withdrawal a 30 bps fee rounds down to nothing
+    const FEE_FREE: u64 = 333;
+    /// Withdrawals packed into one transaction
+    const PER_TX: usize = 20;
+    const TRANSACTIONS: usize = 5;
+    const TOTAL: u64 = FEE_FREE * (PER_TX * TRANSACTIONS) as u64;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("fee_rounding")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("fee_rounding")
+    }
+
+    fn pool() -> Pubkey {
+        common::find_pda(&[b"pool"], &program_id()).0
+    }
+
+    fn position(owner: &Pubkey) -> Pubkey {
+        common::find_pda(&[b"position", owner.as_ref()], &program_id()).0
+    }
+
+    /// The program with a 30 bps pool, and a user holding a 1 SOL position.
+    fn setup(name: &str) -> (Scenario, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let authority = svm.keypair("authority");
+        let user = svm.keypair("user");
+        for signer in [&authority, &user] {
+            svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        svm.add_program("fee_rounding");
+
+        let ix = idl()
+            .ix("initialize")
+            .arg("fee_bps", FEE_BPS)
+            .account("authority", authority.pubkey())
+            .account("pool", pool())
+            .build();
+        send(&mut svm, &[ix], &[&authority]).expect("Initialize failed");
+
+        let ix = idl()
+            .ix("deposit")
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("owner", user.pubkey())
+            .account("pool", pool())
+            .account("position", position(&user.pubkey()))
+            .build();
+        send(&mut svm, &[ix], &[&user]).expect("Deposit failed");
+        (svm, user)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ixs: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(ixs, Some(&payer.pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    fn withdraw(name: &str, owner: &Pubkey, amount: u64) -> Instruction {
+        idl()
+            .ix(name)
+            .arg("amount", amount)
+            .account("owner", *owner)
+            .account("pool", pool())
+            .account("position", position(owner))
+            .build()
+    }
+
+    /// Withdraw [`TOTAL`] in [`FEE_FREE`] pieces, [`PER_TX`] to a
+    /// transaction.
+    fn split_withdraw(svm: &mut Scenario, name: &str, user: &Keypair) {
+        let pieces = vec![withdraw(name, &user.pubkey(), FEE_FREE); PER_TX];
+        for i in 0..TRANSACTIONS {
+            // The same instructions again, so a new blockhash to sign
+            if i > 0 {
+                svm.expire_blockhash();
+            }
+            let result = send(svm, &pieces, &[user]);
+            assert!(result.is_ok(), "{:?}", result);
+        }
+    }
+
+    /// Lamports the pool has kept as fees, at bytes 42..50.
+    fn fees_collected(svm: &Scenario) -> u64 {
+        let data = svm.get_account(&pool()).unwrap().data;
+        u64::from_le_bytes(data[42..50].try_into().unwrap())
+    }
+
+    /// What the position has left, at bytes 40..48.
+    fn balance(svm: &Scenario, owner: &Pubkey) -> u64 {
+        let data = svm.get_account(&position(owner)).unwrap().data;
+        u64::from_le_bytes(data[40..48].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_vulnerable_split_withdrawals_pay_no_fee() {
+        // SCENARIO: A user withdraws 33,300 lamports from a 30 bps pool
+        // ATTACK: Withdraw it 333 lamports at a time, 20 to a transaction
+        // EXPECTED: Vulnerable version charges nothing (EXPLOIT WORKS)
+
+        let (mut svm, user) = setup("fee_rounding/vulnerable_split_withdrawals_pay_no_fee");
+        let ready = svm.snapshot();
+
+        // In one withdrawal, the fee is 99.9 lamports, rounded down
+        let ix = withdraw("vulnerable_withdraw", &user.pubkey(), TOTAL);
+        let result = send(&mut svm, &[ix], &[&user]);
+        assert!(result.is_ok(), "{:?}", result);
+        let whole_fee = fees_collected(&svm);
+        assert_eq!(whole_fee, 99);
+
+        // Split into 100 withdrawals, every fee rounds down to zero
+        svm.restore(ready);
+        let pool_before = svm.get_balance(&pool()).unwrap();
+        split_withdraw(&mut svm, "vulnerable_withdraw", &user);
+
+        assert_eq!(fees_collected(&svm), 0);
+        assert_eq!(svm.get_balance(&pool()), Some(pool_before - TOTAL));
+        assert_eq!(balance(&svm, &user.pubkey()), LAMPORTS_PER_SOL - TOTAL);
+    }
+
+    #[test]
+    fn test_secure_split_withdrawals_pay_at_least_the_fee() {
+        // SCENARIO: The same split withdrawal
+        // EXPECTED: Secure version charges every piece, and the pieces pay
+        // at least what the whole does (FIX WORKS)
+
+        let (mut svm, user) = setup("fee_rounding/secure_split_withdrawals_pay_at_least_the_fee");
+        let ready = svm.snapshot();
+
+        // 99.9 lamports, rounded up
+        let ix = withdraw("secure_withdraw", &user.pubkey(), TOTAL);
+        let result = send(&mut svm, &[ix], &[&user]);
+        assert!(result.is_ok(), "{:?}", result);
+        let whole_fee = fees_collected(&svm);
+        assert_eq!(whole_fee, 100);
+
+        svm.restore(ready);
+        let pool_before = svm.get_balance(&pool()).unwrap();
+        split_withdraw(&mut svm, "secure_withdraw", &user);
+
+        // One lamport per piece, where 0.999 is owed
+        let split_fee = fees_collected(&svm);
+        assert_eq!(split_fee, (PER_TX * TRANSACTIONS) as u64);
+        assert!(split_fee >= whole_fee);
+        assert_eq!(
+            svm.get_balance(&pool()),
+            Some(pool_before - TOTAL + split_fee)
+        );
+    }
+
+    #[test]
+    fn test_secure_withdraw_charges_the_fee() {
+        let (mut svm, user) = setup("fee_rounding/secure_withdraw_charges_the_fee");
+        let pool_before = svm.get_balance(&pool()).unwrap();
+
+        // 30 bps of 1,000,000 lamports is exactly 3,000, with nothing to round
+        let ix = withdraw("secure_withdraw", &user.pubkey(), 1_000_000);
+        let result = send(&mut svm, &[ix], &[&user]);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(fees_collected(&svm), 3_000);
+        assert_eq!(svm.get_balance(&pool()), Some(pool_before - 997_000));
+        assert_eq!(balance(&svm, &user.pubkey()), LAMPORTS_PER_SOL - 1_000_000);
+
+        // The position can't pay out more than it holds
+        let ix = withdraw("secure_withdraw", &user.pubkey(), LAMPORTS_PER_SOL);
+        assert_err!(
+            send(&mut svm, &[ix], &[&user]),
+            FeeError::InsufficientBalance
+        );
+    }
+}
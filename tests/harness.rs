@@ -0,0 +1,2535 @@
+//! Tests for the shared test harness itself
+//!
+//! These run without any program binaries, so they also guard the harness
+//! on machines that have not built the SBF artifacts yet.
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionMetadata;
+    use litesvm::LiteSVM;
+    use security_tests::alt;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::bench::{self, Sample};
+    use security_tests::build::ProgramSource;
+    use security_tests::client;
+    use security_tests::codama;
+    use security_tests::common;
+    use security_tests::compute::{
+        self, ComputeUnits, CuBudget, CuComparison, CuHistogram, CuRun, CuThreshold,
+        DEFAULT_CU_LIMIT, FUZZED_CU_LIMITS, MAX_CU_LIMIT,
+    };
+    use security_tests::corrupt::{self, Corruption};
+    use security_tests::demo::{self, Palette};
+    use security_tests::diff::{FieldChange, StateDiff};
+    use security_tests::docs;
+    use security_tests::errors::{failing_program, AnchorError};
+    use security_tests::events::{self, EventKind, SecurityEvent};
+    use security_tests::export;
+    use security_tests::fixtures::{load_fixture, AccountFixture};
+    use security_tests::golden::{self, assert_golden, TxSnapshot};
+    use security_tests::idl::{self, checked_in_idl_dir, instruction_discriminator, ArgValue, Idl};
+    use security_tests::interleave::{Order, TxTemplate};
+    use security_tests::live::{self, Backend, LiveResult};
+    use security_tests::nonce;
+    use security_tests::scenario::{seeded_keypair, Scenario, Step};
+    use security_tests::sdk::{
+        self, Account, AccountMeta, COption, CompiledInstruction, InnerInstruction, Keypair,
+        Message, Pack, Pubkey, Signer, Transaction, VersionedTransaction, LAMPORTS_PER_SOL,
+    };
+    use security_tests::sequence::{
+        invariants, pseudo_random_input, AccountTemplate, ArgTemplate, InstructionTemplate,
+        SequenceFuzzer, COMPUTE_INVARIANT,
+    };
+    use security_tests::trace::{CallResult, CallTree};
+    use security_tests::tutorial;
+    use security_tests::verify;
+    use sha2::{Digest, Sha256};
+    use solsec_registry::{Framework, CATALOG, VARIANTS};
+    use spl_token_2022::state::Mint;
+
+    fn mint_fixture() -> AccountFixture {
+        let mint = Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            supply: 1_000_000_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0u8; Mint::LEN];
+        Mint::pack(mint, &mut data).unwrap();
+
+        AccountFixture::new(
+            Pubkey::new_unique(),
+            Account {
+                lamports: 1_461_600,
+                data,
+                owner: spl_token_2022::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_fixture_roundtrip_json_and_bincode() {
+        let fixture = mint_fixture();
+        let dir = std::env::temp_dir().join(format!("solsec-fixtures-{}", std::process::id()));
+
+        for ext in ["json", "bin"] {
+            let path = dir.join(format!("mint.{}", ext));
+            fixture.write(&path).unwrap();
+            let loaded = AccountFixture::from_path(&path).unwrap();
+            assert_eq!(loaded, fixture, "{} fixture should round-trip", ext);
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_fixture_installs_real_layout() {
+        let fixture = mint_fixture();
+        let mut svm = LiteSVM::new();
+
+        let address = fixture.install(&mut svm);
+        let account = svm
+            .get_account(&address)
+            .expect("fixture should be installed");
+        let mint = Mint::unpack(&account.data).expect("mint layout should survive install");
+        assert_eq!(mint.decimals, 6);
+        assert_eq!(mint.supply, 1_000_000_000_000);
+
+        // Same layout under an attacker-chosen key
+        let forged = fixture.install_at(&mut svm, Pubkey::new_unique());
+        assert_eq!(svm.get_account(&forged).unwrap().data, account.data);
+    }
+
+    #[test]
+    fn test_checked_in_mint_fixture_installs() {
+        let mut svm = LiteSVM::new();
+        let address = load_fixture(&mut svm, "native_mint");
+        assert_eq!(
+            address,
+            "So11111111111111111111111111111111111111112"
+                .parse::<Pubkey>()
+                .unwrap()
+        );
+
+        let account = svm
+            .get_account(&address)
+            .expect("fixture should be installed");
+        assert_eq!(
+            account.owner,
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+                .parse::<Pubkey>()
+                .unwrap()
+        );
+        let mint = Mint::unpack(&account.data).expect("a token mint layout");
+        assert_eq!(mint.decimals, 9);
+        assert_eq!(mint.supply, 0);
+        assert_eq!(mint.mint_authority, COption::None);
+        assert_eq!(mint.freeze_authority, COption::None);
+    }
+
+    #[test]
+    fn test_fixture_space_must_match_its_data() {
+        let json = mint_fixture().to_json();
+        assert!(AccountFixture::from_json(json.as_bytes()).is_ok());
+
+        let short = json.replace("\"space\": 82", "\"space\": 81");
+        let err = AccountFixture::from_json(short.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("space is 81"), "{}", err);
+    }
+
+    #[test]
+    fn test_golden_redacts_pubkeys_in_order() {
+        let program = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let meta = TransactionMetadata {
+            logs: vec![
+                format!("Program {} invoke [1]", program),
+                format!("Program log: user={} owner={}", user, program),
+                "Program 11111111111111111111111111111111 success".to_string(),
+            ],
+            compute_units_consumed: 1234,
+            ..Default::default()
+        };
+
+        let snapshot = TxSnapshot::capture(&Ok(meta));
+        assert_eq!(snapshot.status, "ok");
+        assert_eq!(
+            snapshot.logs,
+            vec![
+                "Program <key:0> invoke [1]",
+                "Program log: user=<key:1> owner=<key:0>",
+                "Program 11111111111111111111111111111111 success",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_golden_system_transfer() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // Transfer more than the balance so the snapshot covers an error path
+        let ix = sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            2 * LAMPORTS_PER_SOL,
+        );
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+
+        let result = svm.send_transaction(tx);
+        assert_golden("harness/system_transfer_insufficient_funds", &result);
+    }
+
+    #[test]
+    fn test_golden_fails_on_a_missing_snapshot() {
+        // Recording is what UPDATE_GOLDEN=1 is for
+        if golden::update_mode() {
+            return;
+        }
+        let outcome = std::panic::catch_unwind(|| {
+            golden::assert_golden_text("harness/never_recorded", "status: ok\n")
+        });
+        let message = *outcome.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.starts_with("Missing golden snapshot") && message.contains("UPDATE_GOLDEN=1"),
+            "{}",
+            message
+        );
+        assert!(!golden::golden_dir()
+            .join("harness/never_recorded.snap")
+            .exists());
+    }
+
+    fn insufficient_funds_transfer() -> (LiteSVM, Transaction) {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let ix = sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            2 * LAMPORTS_PER_SOL,
+        );
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        (svm, tx)
+    }
+
+    #[test]
+    fn test_anchor_error_parsed_from_logs() {
+        let program = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            "Program log: Instruction: SecureWithdraw".to_string(),
+            "Program log: AnchorError caused by account: vault. Error Code: ConstraintSeeds. \
+             Error Number: 2006. Error Message: A seeds constraint was violated."
+                .to_string(),
+            format!("Program {} failed: custom program error: 0x7d6", program),
+        ];
+
+        let err = AnchorError::from_logs(&logs).unwrap();
+        assert_eq!(err.name, "ConstraintSeeds");
+        assert_eq!(err.number, 2006);
+        assert_eq!(err.message, "A seeds constraint was violated");
+        assert_eq!(err.account.as_deref(), Some("vault"));
+        assert_eq!(failing_program(&logs), Some(program));
+
+        let thrown = AnchorError::from_logs(&[
+            "Program log: AnchorError thrown in programs/x/src/secure.rs:42. Error Code: \
+             Unauthorized. Error Number: 6002. Error Message: Unauthorized."
+                .to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            (thrown.name.as_str(), thrown.number),
+            ("Unauthorized", 6002)
+        );
+        assert_eq!(thrown.account, None);
+    }
+
+    #[test]
+    fn test_assert_err_matches_instruction_error() {
+        let (mut svm, tx) = insufficient_funds_transfer();
+        let result = svm.send_transaction(tx);
+        // SystemError::ResultWithNegativeLamports
+        assert_err!(result, InstructionError::Custom(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected ErrorCode::ConstraintSeeds, but no AnchorError was logged")]
+    fn test_assert_err_rejects_unrelated_failure() {
+        let (mut svm, tx) = insufficient_funds_transfer();
+        let result = svm.send_transaction(tx);
+        assert_err!(result, ErrorCode::ConstraintSeeds);
+    }
+
+    #[test]
+    fn test_attacker_prefund_then_create_account_fails() {
+        let mut svm = LiteSVM::new();
+        let attacker = Keypair::new();
+        let victim = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&victim.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let target = Keypair::new();
+        attacker::prefund(&mut svm, &attacker, &target.pubkey(), 890_880);
+        assert_eq!(svm.get_balance(&target.pubkey()), Some(890_880));
+
+        let ix = sdk::system_instruction::create_account(
+            &victim.pubkey(),
+            &target.pubkey(),
+            LAMPORTS_PER_SOL / 10,
+            0,
+            &Pubkey::new_unique(),
+        );
+        let msg = Message::new(&[ix], Some(&victim.pubkey()));
+        let tx = Transaction::new(&[&victim, &target], msg, svm.latest_blockhash());
+        // SystemError::AccountAlreadyInUse
+        assert_err!(svm.send_transaction(tx), InstructionError::Custom(0));
+    }
+
+    #[test]
+    fn test_attacker_forge_and_clone_account() {
+        let mut svm = LiteSVM::new();
+        let owner = Pubkey::new_unique();
+        let original = Pubkey::new_unique();
+
+        let forged = attacker::forge_account(&mut svm, original, owner, vec![7u8; 41]);
+        assert_eq!(forged.lamports, svm.minimum_balance_for_rent_exemption(41));
+        assert_eq!(svm.get_account(&original), Some(forged.clone()));
+
+        let copy = Pubkey::new_unique();
+        attacker::clone_account(&mut svm, &original, copy);
+        assert_eq!(svm.get_account(&copy), Some(forged));
+    }
+
+    #[test]
+    fn test_attacker_strip_signer_leaves_account_in_place() {
+        let authority = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let ix = sdk::Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(authority, true),
+            ],
+            data: vec![],
+        };
+
+        let ix = attacker::strip_signer(ix, &authority);
+        assert_eq!(ix.accounts[1], AccountMeta::new_readonly(authority, false));
+        assert_eq!(ix.accounts[0], AccountMeta::new(vault, false));
+    }
+
+    #[test]
+    fn test_scenario_keypairs_follow_seed() {
+        let mut first = Scenario::new("harness/keypairs", 7);
+        let mut second = Scenario::new("harness/keypairs", 7);
+
+        assert_eq!(
+            first.keypair("attacker").pubkey(),
+            second.keypair("attacker").pubkey()
+        );
+        assert_eq!(
+            first.keypair("attacker").pubkey(),
+            seeded_keypair(7, "attacker").pubkey()
+        );
+        assert_ne!(
+            first.keypair("attacker").pubkey(),
+            first.keypair("victim").pubkey()
+        );
+        assert_ne!(
+            seeded_keypair(7, "attacker").pubkey(),
+            seeded_keypair(8, "attacker").pubkey()
+        );
+    }
+
+    #[test]
+    fn test_scenario_save_and_replay() {
+        let mut svm = Scenario::new("harness/save_and_replay", 1);
+        let payer = svm.keypair("payer");
+        let recipient = svm.keypair("recipient").pubkey();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // One transfer that lands and one that overdraws
+        for lamports in [1_000, 2 * LAMPORTS_PER_SOL] {
+            let ix = sdk::system_instruction::transfer(&payer.pubkey(), &recipient, lamports);
+            let msg = Message::new(&[ix], Some(&payer.pubkey()));
+            let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+            let _ = svm.send_transaction(tx);
+        }
+        assert!(matches!(
+            &svm.log().steps[..],
+            [
+                Step::Airdrop { .. },
+                Step::Transaction { .. },
+                Step::Transaction { .. }
+            ]
+        ));
+
+        let path = std::env::temp_dir().join(format!("scenario-{}.json", std::process::id()));
+        svm.save(&path).unwrap();
+        let replayed = Scenario::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed.log(), svm.log());
+        assert_eq!(replayed.get_balance(&recipient), Some(1_000));
+    }
+
+    #[test]
+    fn test_scenario_restore_branches_from_snapshot() {
+        let mut svm = Scenario::new("harness/snapshot", 1);
+        let payer = svm.keypair("payer");
+        let recipient = svm.keypair("recipient").pubkey();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let funded = svm.snapshot();
+
+        let ix = sdk::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+
+        // The identical transaction lands in every branch, since restoring
+        // also rewinds the transaction history
+        for _ in 0..2 {
+            assert!(svm.send_transaction(tx.clone()).is_ok());
+            assert_eq!(svm.get_balance(&recipient), Some(1_000));
+            assert_eq!(svm.log().steps.len(), 2);
+
+            svm.restore(funded);
+            assert_eq!(svm.get_balance(&recipient), None);
+            assert_eq!(svm.get_balance(&payer.pubkey()), Some(LAMPORTS_PER_SOL));
+            assert!(matches!(&svm.log().steps[..], [Step::Airdrop { .. }]));
+        }
+    }
+
+    #[test]
+    fn test_alt_v0_batch_replays_from_log() {
+        let mut svm = Scenario::new("harness/alt", 1);
+        let payer = svm.keypair("payer");
+        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        // More recipients than a legacy transaction has room for
+        let recipients: Vec<Pubkey> = (0..30)
+            .map(|i| svm.keypair(&format!("recipient_{}", i)).pubkey())
+            .collect();
+        let table = svm.create_lookup_table(&payer, &recipients[..25]);
+        let table = svm.extend_lookup_table(&table.key, &payer, &recipients[25..]);
+        assert_eq!(table.addresses, recipients);
+
+        let ixs: Vec<_> = recipients
+            .iter()
+            .map(|r| sdk::system_instruction::transfer(&payer.pubkey(), r, LAMPORTS_PER_SOL / 100))
+            .collect();
+        let legacy = Transaction::new(
+            &[&payer],
+            Message::new(&ixs, Some(&payer.pubkey())),
+            svm.latest_blockhash(),
+        );
+        let tx = alt::v0_transaction(&svm, &ixs, &[&payer], &[table]);
+        assert!(bincode::serialized_size(&legacy).unwrap() > 1232);
+        assert!(bincode::serialized_size(&tx).unwrap() <= 1232);
+
+        // Accounts loaded through the table still count as touched
+        let (result, diff) = svm.diff(|svm| svm.send_transaction(tx));
+        assert!(result.is_ok(), "{:?}", result);
+        diff.expect_only_changed(recipients.iter().copied().chain([payer.pubkey()]));
+        assert_eq!(diff.changed().len(), recipients.len() + 1);
+        assert!(svm
+            .log()
+            .steps
+            .iter()
+            .any(|s| matches!(s, Step::Warp { .. })));
+
+        let path = std::env::temp_dir().join(format!("scenario-alt-{}.json", std::process::id()));
+        svm.save(&path).unwrap();
+        let replayed = Scenario::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replayed.log(), svm.log());
+        assert_eq!(
+            replayed.get_balance(&recipients[29]),
+            Some(LAMPORTS_PER_SOL / 100)
+        );
+    }
+
+    #[test]
+    fn test_alt_addresses_unusable_in_slot_extended() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let table = alt::create_lookup_table(&mut svm, &payer, &[Pubkey::new_unique()]);
+
+        // Extend by hand, without moving to the next slot
+        let ix = sdk::address_lookup_table::extend_lookup_table(
+            table.key,
+            payer.pubkey(),
+            Some(payer.pubkey()),
+            vec![recipient],
+        );
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        svm.send_transaction(Transaction::new(&[&payer], msg, svm.latest_blockhash()))
+            .unwrap();
+        let table = alt::lookup_table(&svm, &table.key);
+
+        let transfer = sdk::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+        let tx = alt::v0_transaction(
+            &svm,
+            std::slice::from_ref(&transfer),
+            &[&payer],
+            std::slice::from_ref(&table),
+        );
+        assert!(svm.send_transaction(tx).is_err());
+
+        let slot = svm.get_sysvar::<sdk::Clock>().slot;
+        alt::warp_to_slot(&mut svm, slot + 1);
+        let tx = alt::v0_transaction(&svm, &[transfer], &[&payer], &[table]);
+        assert!(svm.send_transaction(tx).is_ok());
+        assert_eq!(svm.get_balance(&recipient), Some(1_000_000));
+    }
+
+    #[test]
+    fn test_alt_frozen_table_refuses_extension() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let first = Pubkey::new_unique();
+        let table = alt::create_lookup_table(&mut svm, &payer, &[first]);
+        alt::freeze_lookup_table(&mut svm, &table.key, &payer);
+
+        let ix = sdk::address_lookup_table::extend_lookup_table(
+            table.key,
+            payer.pubkey(),
+            Some(payer.pubkey()),
+            vec![Pubkey::new_unique()],
+        );
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let result = svm.send_transaction(Transaction::new(&[&payer], msg, svm.latest_blockhash()));
+        assert!(result.is_err());
+        assert_eq!(alt::lookup_table(&svm, &table.key).addresses, [first]);
+    }
+
+    #[test]
+    fn test_nonce_transaction_outlives_its_blockhash() {
+        let mut svm = Scenario::new("harness/nonce_outlives_blockhash", 1);
+        let payer = svm.keypair("payer");
+        let nonce = svm.keypair("nonce");
+        let recipient = svm.keypair("recipient").pubkey();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let value = svm.create_nonce_account(&payer, &nonce, &payer.pubkey());
+        assert_eq!(nonce::nonce_value(&svm, &nonce.pubkey()), value);
+
+        let ix =
+            sdk::system_instruction::transfer(&payer.pubkey(), &recipient, LAMPORTS_PER_SOL / 100);
+        let recent = Transaction::new(
+            &[&payer],
+            Message::new(std::slice::from_ref(&ix), Some(&payer.pubkey())),
+            svm.latest_blockhash(),
+        );
+        let held =
+            nonce::nonce_transaction(&svm, &[ix], &[&payer], &nonce.pubkey(), &payer.pubkey());
+        svm.expire_blockhash();
+
+        assert_err!(
+            svm.send_transaction(recent),
+            TransactionError::BlockhashNotFound
+        );
+        let result = svm.send_transaction(held.clone());
+        assert!(result.is_ok(), "{:?}", result);
+        assert_ne!(nonce::nonce_value(&svm, &nonce.pubkey()), value);
+
+        // The nonce moved on, so the same transaction can't land again
+        svm.expire_blockhash();
+        assert!(svm.send_transaction(held).is_err());
+        assert_eq!(svm.get_balance(&recipient), Some(LAMPORTS_PER_SOL / 100));
+
+        let path = std::env::temp_dir().join(format!("scenario-nonce-{}.json", std::process::id()));
+        svm.save(&path).unwrap();
+        let replayed = Scenario::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replayed.log(), svm.log());
+        assert!(svm
+            .log()
+            .steps
+            .iter()
+            .any(|s| matches!(s, Step::ExpireBlockhash)));
+    }
+
+    #[test]
+    fn test_advanced_nonce_drops_held_transaction() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        let nonce = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        nonce::create_nonce_account(&mut svm, &payer, &nonce, &payer.pubkey());
+
+        let ix = sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL / 100,
+        );
+        let held =
+            nonce::nonce_transaction(&svm, &[ix], &[&payer], &nonce.pubkey(), &payer.pubkey());
+        nonce::advance_nonce_account(&mut svm, &nonce.pubkey(), &payer);
+
+        assert_err!(
+            svm.send_transaction(held),
+            TransactionError::BlockhashNotFound
+        );
+    }
+
+    /// Pay out of a funded account twice, the second time more than it has
+    /// left; the live results of both.
+    fn overdraw(backend: &mut impl Backend) -> (LiveResult, LiveResult) {
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        backend.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let mut transfer = |lamports| {
+            let ix = sdk::system_instruction::transfer(&payer.pubkey(), &recipient, lamports);
+            let blockhash = backend.latest_blockhash().unwrap();
+            let tx = Transaction::new_signed_with_payer(
+                &[ix],
+                Some(&payer.pubkey()),
+                &[&payer],
+                blockhash,
+            );
+            backend.send(&tx)
+        };
+        let paid = transfer(LAMPORTS_PER_SOL / 2);
+        let overdrawn = transfer(LAMPORTS_PER_SOL);
+        let balance = backend.account(&recipient).unwrap().map(|a| a.lamports);
+        assert_eq!(balance, Some(LAMPORTS_PER_SOL / 2));
+        (paid, overdrawn)
+    }
+
+    fn error(result: &LiveResult) -> Option<&serde_json::Value> {
+        match result {
+            LiveResult::Landed { err, .. } => err.as_ref(),
+            LiveResult::Dropped(reason) => panic!("dropped: {}", reason),
+        }
+    }
+
+    #[test]
+    fn test_litesvm_backend_encodes_errors_as_rpc() {
+        let (paid, overdrawn) = overdraw(&mut LiteSVM::new());
+        assert!(paid.is_ok(), "{:?}", paid);
+        // The system program's `ResultWithNegativeLamports`, as an RPC node
+        // reports it
+        assert_eq!(
+            error(&overdrawn),
+            Some(&serde_json::json!({ "InstructionError": [0, { "Custom": 1 }] }))
+        );
+    }
+
+    #[test]
+    fn test_validator_agrees_with_litesvm() {
+        let Some((_validator, mut rpc, _running)) = live::validator_test() else {
+            return;
+        };
+        let (local, remote) = (overdraw(&mut LiteSVM::new()), overdraw(&mut rpc));
+        assert!(remote.0.is_ok(), "{:?}", remote.0);
+        assert_eq!(error(&remote.1), error(&local.1));
+
+        // What LiteSVM leaves out: the validator votes
+        let votes = rpc.vote_accounts().unwrap();
+        assert!(!votes.is_empty());
+        let vote = Backend::account(&rpc, &votes[0]).unwrap().unwrap();
+        assert_eq!(vote.owner, sdk::vote::ID);
+        assert_eq!(LiteSVM::new().get_account(&votes[0]), None);
+    }
+
+    #[test]
+    fn test_call_tree_nests_cpis_from_logs() {
+        let (outer, inner, system) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            sdk::system_program::ID,
+        );
+        let lines = [
+            format!("Program {} invoke [1]", outer),
+            "Program log: Instruction: Borrow".to_string(),
+            format!("Program {} invoke [2]", inner),
+            "Program data: AQI= Aw==".to_string(),
+            format!("Program {} invoke [3]", system),
+            format!("Program {} success", system),
+            format!("Program return: {} BQ==", inner),
+            format!("Program {} consumed 1200 of 190000 compute units", inner),
+            format!("Program {} success", inner),
+            format!("Program {} invoke [2]", outer),
+            "Program log: reentered".to_string(),
+            format!("Program {} failed: custom program error: 0x1", outer),
+            format!("Program {} consumed 5000 of 200000 compute units", outer),
+            format!("Program {} failed: custom program error: 0x1", outer),
+            format!("Program {} invoke [1]", system),
+            "Log truncated".to_string(),
+        ];
+        let calls = CallTree::from_logs(&lines).named(outer, "lender");
+
+        assert_eq!(
+            calls.shape(),
+            [(1, outer), (2, inner), (3, system), (2, outer), (1, system)]
+        );
+        assert_eq!(calls.max_depth(), 3);
+        assert_eq!(calls.calls_to(&outer).len(), 2);
+
+        let root = &calls.roots[0];
+        assert_eq!(root.instruction.as_deref(), Some("Borrow"));
+        assert_eq!(root.compute_units, Some(5000));
+        assert_eq!(
+            root.result,
+            CallResult::Failed("custom program error: 0x1".to_string())
+        );
+        let callee = &root.children[0];
+        assert!(callee.succeeded());
+        assert_eq!(callee.data, [vec![vec![1, 2], vec![3]]]);
+        assert_eq!(callee.return_data, Some(vec![5]));
+        assert_eq!(root.children[1].logs, ["reentered"]);
+        assert_eq!(calls.roots[1].result, CallResult::Incomplete);
+
+        let expected = format!(
+            "lender::Borrow failed: custom program error: 0x1 (5000 CU)
+│  log: Instruction: Borrow
+├─ {inner} ok (1200 CU)
+│  │  data: AQI= Aw==
+│  │  return: BQ==
+│  └─ system ok
+└─ lender failed: custom program error: 0x1
+      log: reentered
+system incomplete
+"
+        );
+        assert_eq!(calls.to_string(), expected);
+    }
+
+    #[test]
+    fn test_call_tree_matches_inner_instructions() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // Creating a table CPIs into the system program for its account
+        let slot = svm.get_sysvar::<sdk::Clock>().slot;
+        let (ix, table) =
+            sdk::address_lookup_table::create_lookup_table(payer.pubkey(), payer.pubkey(), slot);
+        let msg = Message::new(std::slice::from_ref(&ix), Some(&payer.pubkey()));
+        let tx =
+            VersionedTransaction::from(Transaction::new(&[&payer], msg, svm.latest_blockhash()));
+        let result = svm.send_transaction(tx.clone());
+        assert!(result.is_ok(), "{:?}", result);
+        let calls = CallTree::from_litesvm(&svm, &tx, &result);
+
+        let root = &calls.roots[0];
+        assert_eq!(root.program, ix.program_id);
+        assert_eq!(root.input, ix.data);
+        assert!(root.accounts.contains(&table));
+        assert!(!root.children.is_empty());
+        for call in &root.children {
+            assert_eq!((call.depth, call.program), (2, sdk::system_program::ID));
+            assert!(call.succeeded());
+            assert!(call.accounts.contains(&table), "{:?}", call);
+        }
+        assert!(calls.to_string().starts_with("address-lookup-table ok"));
+    }
+
+    #[test]
+    fn test_diff_reports_only_touched_changes() {
+        let mut svm = Scenario::new("harness/diff", 1);
+        let payer = svm.keypair("payer");
+        let recipient = svm.keypair("recipient").pubkey();
+        let bystander = svm.keypair("bystander").pubkey();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&bystander, LAMPORTS_PER_SOL).unwrap();
+
+        let ix = sdk::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        let (result, diff) = svm.diff(|svm| svm.send_transaction(tx));
+        assert!(result.is_ok());
+
+        // The payer spent more than the fee, so it counts as changed
+        assert_eq!(diff.changed(), vec![payer.pubkey(), recipient]);
+        assert_eq!(diff.get(&recipient).unwrap().lamports_delta(), 1_000);
+        assert!(diff.get(&recipient).unwrap().before.is_none());
+        diff.expect_only_changed([payer.pubkey(), recipient]);
+        diff.expect_unchanged([bystander]);
+
+        let outcome = std::panic::catch_unwind(|| diff.expect_only_changed([recipient]));
+        assert!(outcome.is_err(), "payer change should be flagged");
+    }
+
+    #[test]
+    fn test_diff_decodes_fields_with_idl() {
+        let owner = Pubkey::new_unique();
+        let idl = Idl::from_json(TEST_IDL, owner);
+        let vault = Pubkey::new_unique();
+        let (old_authority, new_authority) = (
+            Pubkey::new_from_array([1; 32]),
+            Pubkey::new_from_array([2; 32]),
+        );
+        let vault_data = |authority: &Pubkey| {
+            let mut data = idl.account_discriminator("Vault").to_vec();
+            data.extend_from_slice(authority.as_ref());
+            data.push(254);
+            data
+        };
+        let account = |data: Vec<u8>| Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let diff = StateDiff::between(
+            |_| Some(account(vault_data(&old_authority))),
+            |_| Some(account(vault_data(&new_authority))),
+            &[vault],
+            &[],
+        );
+        assert_eq!(diff.get(&vault).unwrap().changed_ranges(), vec![8..40]);
+
+        let diff = diff.with_idl(&idl);
+        assert_eq!(
+            diff.get(&vault).unwrap().fields,
+            vec![FieldChange {
+                name: "Vault.authority".to_string(),
+                before: Some(ArgValue::Pubkey(old_authority)),
+                after: Some(ArgValue::Pubkey(new_authority)),
+            }]
+        );
+        assert!(diff.to_string().contains("Vault.authority"));
+    }
+
+    #[test]
+    fn test_interleave_explores_each_order() {
+        let mut svm = Scenario::new("harness/interleave", 1);
+        let victim = svm.keypair("victim");
+        let attacker = svm.keypair("attacker");
+        let target = svm.keypair("target");
+        svm.airdrop(&victim.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let start_steps = svm.log().steps.len();
+
+        // The victim creates `target`; any lamports already there make that
+        // fail, so the outcome depends on who lands first
+        let create = sdk::system_instruction::create_account(
+            &victim.pubkey(),
+            &target.pubkey(),
+            LAMPORTS_PER_SOL / 10,
+            0,
+            &Pubkey::new_unique(),
+        );
+        let fund = |lamports| {
+            sdk::system_instruction::transfer(&attacker.pubkey(), &target.pubkey(), lamports)
+        };
+        let victim_tx = TxTemplate::new("victim", vec![create], &[&victim, &target]);
+        let front = TxTemplate::new("front", vec![fund(1_000)], &[&attacker]);
+        let back = TxTemplate::new("back", vec![fund(2_000)], &[&attacker]);
+
+        let runs = svm.interleave(&victim_tx, &[front, back]);
+        // Exploring leaves the scenario where it started
+        assert_eq!(svm.get_balance(&target.pubkey()), None);
+        assert_eq!(svm.log().steps.len(), start_steps);
+
+        let orders: Vec<Order> = runs.iter().map(|run| run.order).collect();
+        assert_eq!(
+            orders,
+            [
+                Order::VictimFirst,
+                Order::AttackerFirst,
+                Order::Sandwich,
+                Order::Bundled
+            ]
+        );
+
+        for run in &runs {
+            let victim_result = match run.order {
+                Order::Bundled => run.result("bundle"),
+                _ => run.result("victim"),
+            };
+            match run.order {
+                Order::VictimFirst => assert!(victim_result.is_ok()),
+                // SystemError::AccountAlreadyInUse
+                _ => assert_err!(victim_result.clone(), InstructionError::Custom(0)),
+            }
+
+            svm.restore(run.end);
+            let expected = match run.order {
+                Order::VictimFirst => LAMPORTS_PER_SOL / 10 + 3_000,
+                Order::AttackerFirst | Order::Sandwich => 3_000,
+                Order::Bundled => 0,
+            };
+            assert_eq!(
+                svm.get_balance(&target.pubkey()).unwrap_or(0),
+                expected,
+                "{:?}",
+                run.order
+            );
+        }
+    }
+
+    fn account_with_data(data: Vec<u8>) -> Account {
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_corruption_shapes() {
+        let idl = Idl::from_json(TEST_IDL, Pubkey::new_unique());
+        let mut original = idl.account_discriminator("Vault").to_vec();
+        original.extend_from_slice(&[7; 32]);
+        original.push(254);
+
+        let corrupted = |corruption: Corruption| {
+            let mut account = account_with_data(original.clone());
+            corruption.apply(&mut account);
+            account
+        };
+        assert_eq!(corrupted(Corruption::Truncate(8)).data, original[..8]);
+        assert_eq!(corrupted(Corruption::DropLast(1)).data, original[..40]);
+        assert_eq!(
+            corrupted(Corruption::FlipDiscriminator).data[..8],
+            [!9u8; 8]
+        );
+        assert_eq!(
+            corrupted(Corruption::FlipDiscriminator).data[8..],
+            original[8..]
+        );
+        assert_eq!(
+            corrupted(Corruption::Owner(Pubkey::default())).owner,
+            Pubkey::default()
+        );
+
+        assert_eq!(idl.field_range("Vault", "authority"), 8..40);
+        assert_eq!(idl.field_range("Vault", "bump"), 40..41);
+        let zeroed = corrupted(Corruption::zero_field(&idl, "Vault", "authority")).data;
+        assert_eq!(zeroed[8..40], [0; 32]);
+        assert_eq!((&zeroed[..8], zeroed[40]), (&original[..8], 254));
+
+        // Ranges past the end are clamped rather than panicking
+        assert_eq!(corrupted(Corruption::Zero(40..100)).data[40], 0);
+    }
+
+    // Fee payer plus a separate source account the transfer spends from
+    fn transfer_from_source() -> (LiteSVM, Keypair, Keypair, Pubkey) {
+        let mut svm = LiteSVM::new();
+        let (payer, source) = (Keypair::new(), Keypair::new());
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&source.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        (svm, payer, source, Pubkey::new_unique())
+    }
+
+    #[test]
+    fn test_corrupt_owner_is_rejected_cleanly() {
+        let (svm, payer, source, recipient) = transfer_from_source();
+        let tx = |svm: &LiteSVM| {
+            let ix = sdk::system_instruction::transfer(&source.pubkey(), &recipient, 1_000);
+            let msg = Message::new(&[ix], Some(&payer.pubkey()));
+            Transaction::new(&[&payer, &source], msg, svm.latest_blockhash())
+        };
+
+        corrupt::assert_rejects_corrupted(
+            &svm,
+            source.pubkey(),
+            &[Corruption::Owner(Pubkey::new_unique())],
+            tx,
+        );
+        // Every corruption ran on a copy
+        assert_eq!(
+            svm.get_account(&source.pubkey()).unwrap().owner,
+            sdk::system_program::ID
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "corrupted account was accepted")]
+    fn test_corrupt_flags_accepted_account() {
+        let (svm, payer, source, recipient) = transfer_from_source();
+        let tx = |svm: &LiteSVM| {
+            let ix = sdk::system_instruction::transfer(&source.pubkey(), &recipient, 1_000);
+            let msg = Message::new(&[ix], Some(&payer.pubkey()));
+            Transaction::new(&[&payer, &source], msg, svm.latest_blockhash())
+        };
+
+        // A system account has no data, so zeroing it changes nothing
+        corrupt::assert_rejects_corrupted(&svm, source.pubkey(), &[Corruption::Zero(0..8)], tx);
+    }
+
+    #[test]
+    fn test_build_finds_program_sources() {
+        let root = common::deploy_dir();
+
+        let anchor = ProgramSource::find(&root, "signer_authorization").unwrap();
+        assert!(anchor.anchor);
+        assert!(anchor.crate_dir.ends_with("programs/signer-authorization"));
+
+        let pinocchio = ProgramSource::find(&root, "owner_check").unwrap();
+        assert!(!pinocchio.anchor);
+
+        let native = ProgramSource::find(&root, "native_signer_authorization").unwrap();
+        assert!(!native.anchor);
+        assert!(native
+            .crate_dir
+            .ends_with("programs-native/signer-authorization"));
+
+        let ported = ProgramSource::find(&root, "p_signer_authorization").unwrap();
+        assert!(!ported.anchor);
+        assert!(ported
+            .crate_dir
+            .ends_with("programs-pinocchio/signer-authorization"));
+
+        // No `[lib] name`, so the package name decides
+        assert!(ProgramSource::find(&root, "account_reloading").is_some());
+
+        // The AMM workspace has no Anchor.toml and builds with cargo build-sbf
+        let amm_deploy = root
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("programs/amm/target/deploy");
+        let amm = ProgramSource::find(&amm_deploy, "buggy_amm").unwrap();
+        assert!(!amm.anchor);
+        assert_eq!(amm.deploy_dir(), amm_deploy);
+
+        // A build against Anchor 0.31 has its own deploy dir in the workspace
+        let workspace = anchor.workspace.clone();
+        let release_deploy = workspace.join("target/anchor-031/deploy");
+        let release = ProgramSource::find(&release_deploy, "signer_authorization").unwrap();
+        assert_eq!(release.release.as_deref(), Some("anchor-031"));
+        assert_eq!(release.workspace, workspace);
+        assert_eq!(release.deploy_dir(), release_deploy);
+        assert_eq!(release.idl_path(), anchor.idl_path());
+        assert_eq!(anchor.release, None);
+
+        // A program's helper programs are found beside it
+        let partner = ProgramSource::find(&root, "partner_rewards").unwrap();
+        assert!(partner.anchor);
+        assert!(partner.crate_dir.ends_with("programs/partner-mint/partner"));
+
+        // Library crates build no program
+        assert_eq!(ProgramSource::find(&root, "solsec_guards"), None);
+        assert_eq!(ProgramSource::find(&root, "no_such_program"), None);
+    }
+
+    #[test]
+    fn test_build_fingerprint_tracks_sources() {
+        let workspace =
+            std::env::temp_dir().join(format!("build-fingerprint-{}", std::process::id()));
+        let crate_dir = workspace.join("programs/demo");
+        std::fs::create_dir_all(crate_dir.join("src")).unwrap();
+        std::fs::write(workspace.join("Cargo.toml"), "[workspace]\n").unwrap();
+        std::fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nname = \"demo-program\"\n\n[lib]\ncrate-type = [\"cdylib\"]\n",
+        )
+        .unwrap();
+        std::fs::write(crate_dir.join("src/lib.rs"), "// v1\n").unwrap();
+
+        let deploy = workspace.join("target/deploy");
+        let source = ProgramSource::find(&deploy, "demo_program").unwrap();
+        let v1 = source.fingerprint();
+        assert_eq!(source.fingerprint(), v1);
+        assert!(source.is_stale(), "no binary yet");
+
+        // What a successful build leaves behind
+        std::fs::create_dir_all(deploy.join(".fingerprints")).unwrap();
+        std::fs::write(deploy.join("demo_program.so"), b"elf").unwrap();
+        std::fs::write(deploy.join(".fingerprints/demo_program.sha256"), &v1).unwrap();
+        assert!(!source.is_stale());
+
+        std::fs::write(crate_dir.join("src/lib.rs"), "// v2\n").unwrap();
+        assert_ne!(source.fingerprint(), v1);
+        assert!(source.is_stale(), "edited source must trigger a rebuild");
+
+        std::fs::remove_dir_all(&workspace).unwrap();
+    }
+
+    #[test]
+    fn test_compute_units_from_result() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let ix = sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL / 2,
+        );
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+
+        let result = svm.send_transaction(tx);
+        assert!(result.is_ok());
+        assert_eq!(result.compute_units(), 150);
+    }
+
+    #[test]
+    fn test_cu_budget_prepends_and_limits() {
+        let budget = CuBudget::default().unit_limit(300_000).unit_price(2_500);
+        let ixs = budget.instructions();
+        assert_eq!(ixs.len(), 2);
+        assert!(ixs
+            .iter()
+            .all(|ix| ix.program_id == sdk::compute_budget::ID));
+        assert_eq!(ixs[0].data[0], 2); // SetComputeUnitLimit
+        assert_eq!(ixs[1].data[0], 3); // SetComputeUnitPrice
+        assert_eq!(budget.priority_fee(), 750);
+        assert!(CuBudget::default().instructions().is_empty());
+        assert_eq!(
+            CuBudget::default()
+                .unit_limit(1)
+                .unit_price(1)
+                .priority_fee(),
+            1
+        );
+
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let transfer =
+            sdk::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+        let transfer = std::slice::from_ref(&transfer);
+
+        let min = compute::min_cu_limit(&svm, transfer, &[&payer]).unwrap();
+        assert!(compute::run_with_limit(&svm, transfer, &[&payer], min).is_ok());
+        let starved = compute::run_with_limit(&svm, transfer, &[&payer], min - 1);
+        compute::assert_exhausted(&starved);
+
+        // Runs on copies, svm itself is untouched
+        assert_eq!(svm.get_balance(&payer.pubkey()), Some(LAMPORTS_PER_SOL));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected the compute budget to run out")]
+    fn test_cu_exhausted_rejects_success() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let ix =
+            sdk::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+        let tx = CuBudget::default()
+            .unit_limit(MAX_CU_LIMIT)
+            .transaction(&svm, &[ix], &[&payer]);
+        compute::assert_exhausted(&svm.send_transaction(tx));
+    }
+
+    #[test]
+    fn test_compute_threshold_limits() {
+        let comparison = CuComparison {
+            name: "harness/threshold".to_string(),
+            vulnerable: 1_000,
+            secure: 1_600,
+        };
+        assert_eq!(comparison.overhead(), 600);
+
+        assert!(comparison.check(CuThreshold::default()).is_ok());
+        assert!(comparison
+            .check(CuThreshold::default().max_ratio(1.5))
+            .is_err());
+        assert!(comparison
+            .check(CuThreshold::unlimited().max_overhead(500))
+            .is_err());
+        assert!(comparison
+            .check(CuThreshold::unlimited().max_overhead(600))
+            .is_ok());
+    }
+
+    // Trimmed-down `anchor build` output in the 0.30+ IDL format
+    const TEST_IDL: &str = r#"{
+        "address": "11111111111111111111111111111111",
+        "metadata": { "name": "test_program", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [
+            {
+                "name": "create_proposal",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "payer", "writable": true, "signer": true },
+                    { "name": "config" },
+                    { "name": "proposal", "writable": true },
+                    { "name": "system_program", "address": "11111111111111111111111111111111" }
+                ],
+                "args": [
+                    { "name": "proposal_id", "type": "u64" },
+                    { "name": "title", "type": "string" },
+                    { "name": "amounts", "type": { "vec": "u64" } },
+                    { "name": "delegate", "type": { "option": "pubkey" } }
+                ]
+            },
+            {
+                "name": "legacy",
+                "accounts": [],
+                "args": [{ "name": "flag", "type": "bool" }]
+            }
+        ],
+        "accounts": [
+            { "name": "Vault", "discriminator": [9, 9, 9, 9, 9, 9, 9, 9] }
+        ],
+        "types": [
+            {
+                "name": "Vault",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "authority", "type": "pubkey" },
+                        { "name": "bump", "type": "u8" }
+                    ]
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_idl_builder_orders_accounts_and_encodes_args() {
+        let idl = Idl::from_json(TEST_IDL, Pubkey::new_unique());
+        let (payer, config, proposal) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        // Bound out of order on purpose
+        let ix = idl
+            .ix("create_proposal")
+            .account("proposal", proposal)
+            .account("config", config)
+            .account("payer", payer)
+            .arg("title", "Hi")
+            .arg("amounts", vec![100u64, 200])
+            .arg("delegate", None::<Pubkey>)
+            .arg("proposal_id", 7u64)
+            .remaining(AccountMeta::new(Pubkey::new_unique(), false))
+            .build();
+
+        assert_eq!(ix.program_id, idl.program_id);
+        assert_eq!(
+            ix.accounts[..4],
+            [
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new_readonly(sdk::system_program::ID, false),
+            ]
+        );
+        assert_eq!(ix.accounts.len(), 5);
+
+        let mut expected = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        expected.extend_from_slice(&7u64.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(b"Hi");
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&100u64.to_le_bytes());
+        expected.extend_from_slice(&200u64.to_le_bytes());
+        expected.push(0);
+        assert_eq!(ix.data, expected);
+    }
+
+    #[test]
+    fn test_idl_legacy_discriminator_is_sha256() {
+        let idl = Idl::from_json(TEST_IDL, Pubkey::new_unique());
+        let ix = idl.ix("legacy").arg("flag", true).build();
+
+        assert_eq!(ix.data[..8], instruction_discriminator("legacy"));
+        assert_eq!(ix.data[8], 1);
+    }
+
+    #[test]
+    fn test_idl_checked_in_pinocchio_interfaces() {
+        for program in ["owner_check", "account_type_mismatch", "p_escrow"] {
+            let path = checked_in_idl_dir().join(format!("{}.json", program));
+            let idl = Idl::from_path(&path, Pubkey::new_unique());
+            assert_eq!(idl.name, program);
+
+            // The handlers match on the first byte only
+            let mut tags: Vec<&[u8]> = idl
+                .instructions
+                .iter()
+                .map(|ix| ix.discriminator.as_slice())
+                .collect();
+            assert!(tags.iter().all(|t| t.len() == 1), "{}: {:?}", program, tags);
+            tags.sort();
+            tags.dedup();
+            assert_eq!(tags.len(), idl.instructions.len());
+        }
+
+        let path = checked_in_idl_dir().join("owner_check.json");
+        let admin = Pubkey::new_unique();
+        let ix = Idl::from_path(&path, Pubkey::new_unique())
+            .ix("initialize_config")
+            .arg("admin", admin)
+            .account("config", Pubkey::new_unique())
+            .build();
+        assert_eq!(ix.data[0], 2);
+        assert_eq!(&ix.data[1..], admin.as_ref());
+    }
+
+    #[test]
+    fn test_cached_fixtures_match_fresh_ones() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let seeds: &[&[u8]] = &[b"vault", user.as_ref()];
+        for _ in 0..2 {
+            assert_eq!(
+                common::find_pda(seeds, &program_id),
+                Pubkey::find_program_address(seeds, &program_id)
+            );
+        }
+        // The seeds are the key, not just the first of them
+        assert_ne!(
+            common::find_pda(&[b"vault"], &program_id),
+            common::find_pda(seeds, &program_id)
+        );
+
+        let path = checked_in_idl_dir().join("owner_check.json");
+        let fresh = Idl::from_json(&std::fs::read_to_string(&path).unwrap(), program_id);
+        for _ in 0..2 {
+            let cached = Idl::from_path(&path, program_id);
+            assert_eq!(format!("{:?}", cached), format!("{:?}", fresh));
+        }
+        // One file, loaded under another id, keeps that id
+        let other = Pubkey::new_unique();
+        assert_eq!(Idl::from_path(&path, other).program_id, other);
+    }
+
+    #[test]
+    fn test_idl_layout_lists_interface() {
+        let idl = Idl::from_json(TEST_IDL, Pubkey::new_unique());
+        let layout = idl.layout();
+        let lines: Vec<&str> = layout.lines().collect();
+
+        assert_eq!(
+            lines[..9],
+            [
+                "instruction create_proposal [1, 2, 3, 4, 5, 6, 7, 8]",
+                "  account payer writable signer",
+                "  account config",
+                "  account proposal writable",
+                "  account system_program address=11111111111111111111111111111111",
+                "  arg proposal_id: u64",
+                "  arg title: string",
+                "  arg amounts: vec<u64>",
+                "  arg delegate: option<pubkey>",
+            ]
+        );
+        assert!(lines.contains(&"account Vault [9, 9, 9, 9, 9, 9, 9, 9]"));
+        assert!(lines.contains(&"  8..40 authority: pubkey"));
+        assert!(lines.contains(&"  40..41 bump: u8"));
+    }
+
+    #[test]
+    #[should_panic(expected = "test_program::create_proposal: missing account proposal")]
+    fn test_idl_builder_rejects_missing_account() {
+        let idl = Idl::from_json(TEST_IDL, Pubkey::new_unique());
+        idl.ix("create_proposal")
+            .account("payer", Pubkey::new_unique())
+            .account("config", Pubkey::new_unique())
+            .arg("proposal_id", 1u64)
+            .arg("title", "")
+            .arg("amounts", Vec::<u64>::new())
+            .arg("delegate", None::<Pubkey>)
+            .build();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in u64")]
+    fn test_idl_builder_rejects_out_of_range_arg() {
+        let idl = Idl::from_json(TEST_IDL, Pubkey::new_unique());
+        idl.ix("create_proposal")
+            .account("payer", Pubkey::new_unique())
+            .account("config", Pubkey::new_unique())
+            .account("proposal", Pubkey::new_unique())
+            .arg("proposal_id", -1i64)
+            .arg("title", "")
+            .arg("amounts", Vec::<u64>::new())
+            .arg("delegate", None::<Pubkey>)
+            .build();
+    }
+
+    // The system program's transfer, described the way an IDL would
+    const SYSTEM_IDL: &str = r#"{
+        "address": "11111111111111111111111111111111",
+        "metadata": { "name": "system_program", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [
+            {
+                "name": "transfer",
+                "discriminator": [2, 0, 0, 0],
+                "accounts": [
+                    { "name": "from", "writable": true, "signer": true },
+                    { "name": "to", "writable": true }
+                ],
+                "args": [{ "name": "lamports", "type": "u64" }]
+            }
+        ]
+    }"#;
+
+    fn transfer_fuzzer(from: AccountTemplate) -> (LiteSVM, SequenceFuzzer) {
+        let mut svm = LiteSVM::new();
+        let actors: Vec<Keypair> = ["alice", "bob", "mallory"]
+            .iter()
+            .map(|name| seeded_keypair(3, name))
+            .collect();
+        for actor in &actors {
+            svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        }
+        let idl = Idl::from_json(SYSTEM_IDL, sdk::system_program::ID);
+        let fuzzer = SequenceFuzzer::new(idl, actors).instruction(
+            InstructionTemplate::new("transfer")
+                .arg("lamports", ArgTemplate::Amount(20 * LAMPORTS_PER_SOL))
+                .account("from", from)
+                .account("to", AccountTemplate::AnyActor),
+        );
+        (svm, fuzzer)
+    }
+
+    #[test]
+    fn test_sequence_decode_is_bounded_and_deterministic() {
+        let (_, fuzzer) = transfer_fuzzer(AccountTemplate::Caller);
+        let fuzzer = fuzzer.max_steps(5);
+        assert!(fuzzer.decode(&[]).is_empty());
+        for seed in 0..50 {
+            let data = pseudo_random_input(seed, 256);
+            let steps = fuzzer.decode(&data);
+            assert_eq!(steps.len(), 5);
+            assert_eq!(steps, fuzzer.decode(&data));
+            for step in &steps {
+                // The caller template always resolves to whoever sends
+                assert_eq!(step.accounts[0].1, fuzzer.actors()[step.caller].pubkey());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequence_transfers_conserve_lamports() {
+        let (svm, fuzzer) = transfer_fuzzer(AccountTemplate::Caller);
+        let fuzzer = fuzzer.invariant("lamports are conserved", invariants::lamports_conserved);
+        for seed in 0..50 {
+            if let Err(violation) = fuzzer.run(&svm, &pseudo_random_input(seed, 128)) {
+                panic!("{}", violation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequence_leaves_other_signers_unsigned_without_co_sign() {
+        let (svm, fuzzer) = transfer_fuzzer(AccountTemplate::AnyActor);
+        let fuzzer = fuzzer.invariant("only signed transfers land", |check| {
+            // The caller pays, so its key comes first
+            let own = check.account("from") == check.tx.message.account_keys[0];
+            if check.landed && !own && !check.step.co_sign {
+                Err("a transfer from another actor landed unsigned".into())
+            } else {
+                Ok(())
+            }
+        });
+        for seed in 0..50 {
+            if let Err(violation) = fuzzer.run(&svm, &pseudo_random_input(seed, 128)) {
+                panic!("{}", violation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequence_reports_steps_up_to_violation() {
+        let (svm, fuzzer) = transfer_fuzzer(AccountTemplate::Caller);
+        // Wrong on purpose: ignores the fee, so the first landed step breaks it
+        let fuzzer = fuzzer.invariant("balances never drop", |check| {
+            let total = |svm: &LiteSVM| -> u64 {
+                check
+                    .tx
+                    .message
+                    .account_keys
+                    .iter()
+                    .filter_map(|key| svm.get_account(key))
+                    .map(|a| a.lamports)
+                    .sum()
+            };
+            if total(check.after) < total(check.before) {
+                Err(format!(
+                    "{} lamports lost",
+                    total(check.before) - total(check.after)
+                ))
+            } else {
+                Ok(())
+            }
+        });
+
+        let violation = (0..50)
+            .find_map(|seed| fuzzer.run(&svm, &pseudo_random_input(seed, 128)).err())
+            .expect("some transfer lands");
+        assert_eq!(violation.invariant, "balances never drop");
+        assert_eq!(violation.message, "5000 lamports lost");
+
+        // Only the last step landed, and replaying the steps breaks it again
+        let replayed = fuzzer.run_steps(&svm, &violation.steps).unwrap_err();
+        assert_eq!(replayed.steps, violation.steps);
+        assert!(violation.to_string().starts_with(&format!(
+            "invariant \"balances never drop\" broken at step {}",
+            violation.steps.len() - 1
+        )));
+    }
+
+    #[test]
+    fn test_sequence_cu_limits_feed_the_histogram() {
+        let (svm, fuzzer) = transfer_fuzzer(AccountTemplate::Caller);
+        assert!(fuzzer
+            .decode(&pseudo_random_input(1, 256))
+            .iter()
+            .all(|step| step.cu_limit.is_none()));
+
+        let fuzzer = fuzzer.cu_limits(&FUZZED_CU_LIMITS);
+        let mut steps = Vec::new();
+        for seed in 0..20 {
+            steps.extend(fuzzer.run(&svm, &pseudo_random_input(seed, 128)).unwrap());
+        }
+        assert!(steps.iter().any(|step| step.cu_limit.is_none()));
+        assert!(steps
+            .iter()
+            .filter_map(|step| step.cu_limit)
+            .all(|limit| FUZZED_CU_LIMITS.contains(&limit)));
+        assert!(steps.iter().any(|step| step.cu_limit == Some(1_000)));
+
+        let histogram = fuzzer.histogram();
+        let stats = histogram.stats("transfer").unwrap();
+        assert_eq!(stats.runs, steps.len() as u64);
+        assert_eq!(stats.buckets[0], stats.runs);
+        assert_eq!(stats.exhausted, 0);
+
+        let path = fuzzer.write_histogram("harness/transfer").unwrap();
+        let table = std::fs::read_to_string(path).unwrap();
+        assert!(table.contains(&format!("| transfer | {} | 0 |", stats.runs)));
+    }
+
+    // The same transfer twice, so every secure step has a counterpart
+    const TWIN_IDL: &str = r#"{
+        "address": "11111111111111111111111111111111",
+        "metadata": { "name": "system_program", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [
+            {
+                "name": "vulnerable_transfer",
+                "discriminator": [2, 0, 0, 0],
+                "accounts": [
+                    { "name": "from", "writable": true, "signer": true },
+                    { "name": "to", "writable": true }
+                ],
+                "args": [{ "name": "lamports", "type": "u64" }]
+            },
+            {
+                "name": "secure_transfer",
+                "discriminator": [2, 0, 0, 0],
+                "accounts": [
+                    { "name": "from", "writable": true, "signer": true },
+                    { "name": "to", "writable": true }
+                ],
+                "args": [{ "name": "lamports", "type": "u64" }]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_sequence_replays_secure_steps_as_their_vulnerable_twin() {
+        let mut svm = LiteSVM::new();
+        let actors: Vec<Keypair> = ["alice", "bob"]
+            .iter()
+            .map(|name| seeded_keypair(4, name))
+            .collect();
+        for actor in &actors {
+            svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        }
+        let idl = Idl::from_json(TWIN_IDL, sdk::system_program::ID);
+        // Starved or plenty, never a limit that splits the twins
+        let fuzzer = SequenceFuzzer::new(idl, actors)
+            .instruction(
+                InstructionTemplate::new("secure_transfer")
+                    .arg("lamports", ArgTemplate::Amount(LAMPORTS_PER_SOL))
+                    .account("from", AccountTemplate::Caller)
+                    .account("to", AccountTemplate::AnyActor),
+            )
+            .cu_limits(&[100, MAX_CU_LIMIT])
+            .cu_budget(100);
+
+        let step = &fuzzer.decode(&pseudo_random_input(0, 32))[0];
+        let twin = fuzzer.counterpart(step).unwrap();
+        assert_eq!(twin.instruction, "vulnerable_transfer");
+        assert_eq!((&twin.args, &twin.accounts), (&step.args, &step.accounts));
+        assert_eq!(twin.cu_limit, step.cu_limit);
+        assert!(fuzzer.counterpart(&twin).is_none());
+
+        // Both twins blow a 100 CU budget alike, so nothing is flagged
+        for seed in 0..20 {
+            if let Err(violation) = fuzzer.run(&svm, &pseudo_random_input(seed, 64)) {
+                assert_ne!(violation.invariant, COMPUTE_INVARIANT);
+                panic!("{}", violation);
+            }
+        }
+        let histogram = fuzzer.histogram();
+        let (secure, vulnerable) = (
+            histogram.stats("secure_transfer").unwrap(),
+            histogram.stats("vulnerable_transfer").unwrap(),
+        );
+        assert_eq!(secure, vulnerable);
+        assert!(secure.exhausted > 0 && secure.exhausted < secure.runs);
+    }
+
+    #[test]
+    fn test_cu_run_exceeds_budget() {
+        let run = |limit, consumed, exhausted| CuRun {
+            limit,
+            consumed,
+            exhausted,
+        };
+        assert!(run(MAX_CU_LIMIT, 250_000, false).exceeds(DEFAULT_CU_LIMIT));
+        assert!(run(DEFAULT_CU_LIMIT, DEFAULT_CU_LIMIT as u64, true).exceeds(DEFAULT_CU_LIMIT));
+        // Starved below the budget: the budget might still be enough
+        assert!(!run(1_000, 1_000, true).exceeds(DEFAULT_CU_LIMIT));
+        assert!(!run(MAX_CU_LIMIT, 5_000, false).exceeds(DEFAULT_CU_LIMIT));
+
+        let mut histogram = CuHistogram::default();
+        histogram.record("a", run(MAX_CU_LIMIT, 4_000, false));
+        histogram.record("a", run(MAX_CU_LIMIT, 900_000, true));
+        let stats = histogram.stats("a").unwrap();
+        assert_eq!((stats.runs, stats.exhausted), (2, 1));
+        assert_eq!((stats.min, stats.max), (4_000, 900_000));
+        assert_eq!(stats.buckets[1], 1);
+        assert_eq!(stats.buckets[stats.buckets.len() - 1], 1);
+        assert!(histogram
+            .render("t")
+            .contains("| a | 2 | 1 | 4000 | 900000 | 0 | 1 |"));
+    }
+
+    #[test]
+    fn test_catalog_matches_the_programs() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap();
+
+        // Every program directory has exactly one entry
+        let mut dirs: Vec<String> = std::fs::read_dir(root.join("programs"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join("Cargo.toml").exists())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        dirs.sort();
+        let mut listed: Vec<String> = CATALOG.iter().map(|v| v.program.to_string()).collect();
+        listed.sort();
+        assert_eq!(listed, dirs);
+
+        for vulnerability in CATALOG {
+            let dir = root.join("programs").join(vulnerability.program);
+            for file in vulnerability
+                .sources
+                .iter()
+                .copied()
+                .chain([vulnerability.readme().as_str()])
+            {
+                assert!(
+                    root.join(file).exists(),
+                    "{}: no {}",
+                    vulnerability.program,
+                    file
+                );
+            }
+
+            assert!(
+                root.join("tests")
+                    .join(format!("{}.rs", vulnerability.suite))
+                    .exists(),
+                "{}: no suite {}",
+                vulnerability.program,
+                vulnerability.suite
+            );
+            // Vulnerable files pair up with the secure ones after them
+            let sources = vulnerability.sources;
+            if sources.len() > 1 {
+                let (vulnerable, secure) = sources.split_at(sources.len() / 2);
+                assert_eq!(vulnerable.len(), secure.len(), "{}", vulnerability.program);
+                let file = |path: &str| path.rsplit('/').next().unwrap().to_string();
+                for (v, s) in vulnerable.iter().zip(secure) {
+                    assert!(
+                        (file(v) == file(s) && v != s)
+                            || (file(v), file(s)) == ("vulnerable.rs".into(), "secure.rs".into()),
+                        "{}: {} does not pair with {}",
+                        vulnerability.program,
+                        v,
+                        s
+                    );
+                }
+            }
+
+            // Each instruction name resolves to `(crate, instruction)`
+            let instructions = vulnerability
+                .vulnerable
+                .iter()
+                .chain(vulnerability.mitigation.instructions);
+            let split = |name: &'static str| match name.split_once("::") {
+                Some((krate, ix)) => (krate.to_string(), ix),
+                None => (vulnerability.program.replace('-', "_"), name),
+            };
+            match vulnerability.framework {
+                Framework::Anchor => {
+                    for (krate, ix) in instructions.copied().map(split) {
+                        let lib = match dir.join("src/lib.rs").exists() {
+                            true => dir.join("src/lib.rs"),
+                            false => dir.join(krate.replace('_', "-")).join("src/lib.rs"),
+                        };
+                        let source = std::fs::read_to_string(&lib).unwrap();
+                        let defined = [format!("pub fn {}(", ix), format!("pub fn {}<", ix)]
+                            .iter()
+                            .any(|decl| source.contains(decl.as_str()));
+                        assert!(
+                            defined,
+                            "{}: {} defines no {}",
+                            vulnerability.program,
+                            lib.display(),
+                            ix
+                        );
+                    }
+                }
+                Framework::Pinocchio | Framework::Native => {
+                    for (krate, ix) in instructions.copied().map(split) {
+                        let path = checked_in_idl_dir().join(format!("{}.json", krate));
+                        Idl::from_path(&path, Pubkey::new_unique()).instruction(ix);
+                    }
+                }
+            }
+
+            // Each program crate points its `Catalogued` impl at this entry
+            let libs: Vec<std::path::PathBuf> = match dir.join("src/lib.rs").exists() {
+                true => vec![dir.join("src/lib.rs")],
+                false => std::fs::read_dir(&dir)
+                    .unwrap()
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path().join("src/lib.rs"))
+                    .filter(|lib| lib.exists())
+                    .collect(),
+            };
+            let constant = vulnerability.program.to_uppercase().replace('-', "_");
+            let entry = format!("&solsec_registry::{};", constant);
+            // ...or through `solsec_prelude::catalogued!`
+            let prelude_entry = format!(", {});", constant);
+            assert!(
+                !libs.is_empty(),
+                "{}: no program crate",
+                vulnerability.program
+            );
+            for lib in libs {
+                let source = std::fs::read_to_string(&lib).unwrap();
+                assert!(
+                    (source.contains("impl solsec_registry::Catalogued for")
+                        && source.contains(&entry))
+                        || source
+                            .lines()
+                            .any(|line| line.starts_with("solsec_prelude::catalogued!(")
+                                && line.ends_with(&prelude_entry)),
+                    "{}: {} does not implement Catalogued with {}",
+                    vulnerability.program,
+                    lib.display(),
+                    entry
+                );
+            }
+
+            // A vulnerable instruction the entry leaves out is a gap in it
+            if vulnerability.framework == Framework::Anchor && dir.join("src/lib.rs").exists() {
+                let source = std::fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+                for ix in source
+                    .split("pub fn ")
+                    .skip(1)
+                    .filter_map(|rest| rest.split(['(', '<']).next())
+                    .filter(|name| name.starts_with("vulnerable_"))
+                {
+                    assert!(
+                        vulnerability.vulnerable.contains(&ix),
+                        "{}: {} is not in the catalog",
+                        vulnerability.program,
+                        ix
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            solsec_registry::find("owner-check").unwrap().framework,
+            Framework::Pinocchio
+        );
+        assert!(solsec_registry::find("nope").is_none());
+    }
+
+    #[test]
+    fn test_variants_match_the_programs() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap();
+
+        for variant in VARIANTS {
+            assert_ne!(
+                variant.framework, variant.of.framework,
+                "{}",
+                variant.program
+            );
+            let dir = root.join(variant.dir());
+            let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+            assert!(
+                manifest.contains(&format!("name = \"{}\"", variant.program)),
+                "{}: not the package in {}",
+                variant.program,
+                variant.dir()
+            );
+            assert_eq!(
+                ProgramSource::find(&common::deploy_dir(), &variant.program.replace('-', "_"))
+                    .map(|source| source.crate_dir),
+                Some(dir.clone())
+            );
+
+            // Its interface defines every instruction the variant names
+            let idl =
+                checked_in_idl_dir().join(format!("{}.json", variant.program.replace('-', "_")));
+            let idl = Idl::from_path(&idl, Pubkey::new_unique());
+            for ix in variant.vulnerable.iter().chain(variant.secure) {
+                idl.instruction(ix);
+            }
+            for ix in variant.vulnerable {
+                assert!(
+                    variant.of.vulnerable.contains(ix),
+                    "{}: {} is not a vulnerable instruction of {}",
+                    variant.program,
+                    ix,
+                    variant.of.program
+                );
+            }
+
+            // The crate points its `Catalogued` impl at the entry it ports
+            let source = std::fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+            let entry = format!(
+                "&solsec_registry::{};",
+                variant.of.program.to_uppercase().replace('-', "_")
+            );
+            assert!(
+                source.contains("impl solsec_registry::Catalogued for") && source.contains(&entry),
+                "{}: does not implement Catalogued with {}",
+                variant.program,
+                entry
+            );
+        }
+        assert_eq!(
+            solsec_registry::framework("native-multisig-payer"),
+            Some(Framework::Native)
+        );
+        assert_eq!(
+            solsec_registry::framework("p-account-griefing"),
+            Some(Framework::Pinocchio)
+        );
+        assert_eq!(
+            solsec_registry::framework("multisig-payer"),
+            Some(Framework::Anchor)
+        );
+        assert!(solsec_registry::find_variant("multisig-payer").is_none());
+    }
+
+    #[test]
+    fn test_error_sets_match_the_programs() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap();
+
+        // Every `#[error_code]` enum in a program's src/, with its variants
+        let mut declared: Vec<(String, String, Vec<String>)> = Vec::new();
+        for program in std::fs::read_dir(root.join("programs")).unwrap() {
+            let program = program.unwrap();
+            let Ok(files) = std::fs::read_dir(program.path().join("src")) else {
+                continue;
+            };
+            for file in files {
+                let path = file.unwrap().path();
+                if path.extension().is_none_or(|ext| ext != "rs") {
+                    continue;
+                }
+                let source = std::fs::read_to_string(path).unwrap();
+                for block in source.split("#[error_code]").skip(1) {
+                    let (head, body) = block.split_once('{').unwrap();
+                    let name = head.trim().trim_start_matches("pub enum ").to_string();
+                    let variants = body
+                        .split("\n}")
+                        .next()
+                        .unwrap()
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with(['#', '/']))
+                        .map(|line| line.trim_end_matches(',').to_string())
+                        .collect();
+                    let program = program.file_name().to_string_lossy().into_owned();
+                    declared.push((program, name, variants));
+                }
+            }
+        }
+        declared.sort();
+
+        let mut sets: Vec<(String, String, Vec<String>)> = solsec_errors::ERROR_SETS
+            .iter()
+            .map(|set| {
+                let variants = set.variants.iter().map(|(v, _)| v.to_string()).collect();
+                (set.program.to_string(), set.name.to_string(), variants)
+            })
+            .collect();
+        sets.sort();
+        assert_eq!(sets, declared);
+    }
+
+    #[test]
+    fn test_scores_follow_cvss() {
+        use solsec_registry::{
+            AttackComplexity as AC, AttackVector as AV, Impact, PrivilegesRequired as PR, Scope,
+            Score, UserInteraction as UI, Weakness,
+        };
+        let score = |ac, pr, ui, scope, [c, i, a]: [Impact; 3]| Score {
+            attack_vector: AV::Network,
+            attack_complexity: ac,
+            privileges_required: pr,
+            user_interaction: ui,
+            scope,
+            confidentiality: c,
+            integrity: i,
+            availability: a,
+        };
+        let high = [Impact::High; 3];
+        // Reference scores from the specification's calculator
+        for (s, base, vector) in [
+            (
+                score(AC::Low, PR::None, UI::None, Scope::Unchanged, high),
+                9.8,
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+            ),
+            (
+                score(AC::Low, PR::None, UI::None, Scope::Changed, high),
+                10.0,
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H",
+            ),
+            (
+                score(
+                    AC::Low,
+                    PR::None,
+                    UI::None,
+                    Scope::Unchanged,
+                    [Impact::None, Impact::None, Impact::High],
+                ),
+                7.5,
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H",
+            ),
+            (
+                score(
+                    AC::Low,
+                    PR::None,
+                    UI::Required,
+                    Scope::Changed,
+                    [Impact::Low, Impact::Low, Impact::None],
+                ),
+                6.1,
+                "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N",
+            ),
+            (
+                score(
+                    AC::High,
+                    PR::High,
+                    UI::Required,
+                    Scope::Unchanged,
+                    [Impact::None; 3],
+                ),
+                0.0,
+                "CVSS:3.1/AV:N/AC:H/PR:H/UI:R/S:U/C:N/I:N/A:N",
+            ),
+        ] {
+            assert_eq!(s.base_score(), base, "{}", vector);
+            assert_eq!(s.vector(), vector);
+        }
+
+        for vulnerability in CATALOG {
+            let base = vulnerability.score.base_score();
+            assert!(
+                base > 0.0 && vulnerability.score.rating().is_some(),
+                "{}: scores {}",
+                vulnerability.program,
+                base
+            );
+            assert!(
+                !vulnerability.weaknesses.is_empty(),
+                "{}: maps to no weakness",
+                vulnerability.program
+            );
+            for weakness in vulnerability.weaknesses {
+                if let Weakness::Sealevel(_) = weakness {
+                    assert!(
+                        weakness.sealevel_name().is_some(),
+                        "{}: no sealevel-attacks program {:?}",
+                        vulnerability.program,
+                        weakness
+                    );
+                }
+            }
+        }
+        assert_eq!(Weakness::Sealevel(3).id(), "3-type-cosplay");
+        assert_eq!(
+            Weakness::Cwe(190).url(),
+            "https://cwe.mitre.org/data/definitions/190.html"
+        );
+    }
+
+    #[test]
+    fn test_demos_play_catalogued_instructions() {
+        for demo in demo::DEMOS {
+            let vulnerability = demo.vulnerability();
+            assert!(
+                vulnerability.vulnerable.contains(&demo.vulnerable),
+                "{}: {} is not a catalogued vulnerable instruction",
+                demo.program,
+                demo.vulnerable
+            );
+            assert!(
+                vulnerability.mitigation.instructions.contains(&demo.secure),
+                "{}: {} is not a catalogued secure instruction",
+                demo.program,
+                demo.secure
+            );
+            assert_eq!(demo.deploy, demo.program.replace('-', "_"));
+        }
+        assert!(demo::find("signer-authorization").is_some());
+        assert!(demo::find("nope").is_none());
+
+        let (plain, colored) = (Palette::new(false), Palette::new(true));
+        assert_eq!(plain.red("failed"), "failed");
+        assert_eq!(colored.red("failed"), "\x1b[31mfailed\x1b[0m");
+    }
+
+    #[test]
+    fn test_played_demo_reports_errors_and_balances() {
+        let demo = demo::find("signer-authorization").unwrap();
+        let run = demo::play(demo);
+        assert!(run.outcome.exploited && run.outcome.blocked);
+
+        let stolen = run.vulnerable.transactions.last().unwrap();
+        assert!(stolen.ok && stolen.error.is_none());
+        let vault = &stolen.balances[0];
+        assert_eq!(vault.label, "vault");
+        assert_eq!(vault.delta, -(LAMPORTS_PER_SOL as i64));
+        assert_eq!(vault.after as i64 - vault.before as i64, vault.delta);
+
+        let rejected = run.secure.transactions.last().unwrap();
+        assert!(!rejected.ok);
+        assert_eq!(rejected.anchor_error.as_deref(), Some("AccountNotSigner"));
+        assert_eq!(rejected.error_code, Some(3010));
+        assert!(rejected.balances.iter().all(|b| b.delta == 0));
+
+        let json = serde_json::to_value(&run).unwrap();
+        assert_eq!(json["program"], "signer-authorization");
+        assert_eq!(json["outcome"]["blocked"], true);
+        assert_eq!(json["secure"]["transactions"][0]["error_code"], 3010);
+        assert!(json["secure"]["transactions"][0].get("state").is_none());
+    }
+
+    #[test]
+    fn test_tutorial_pauses_and_quits() {
+        let demo = demo::find("signer-authorization").unwrap();
+        let mut out = Vec::new();
+        let outcome = tutorial::run(demo, None, &mut out, Palette::new(false)).unwrap();
+        assert_eq!(
+            outcome,
+            Some(demo::Outcome {
+                exploited: true,
+                blocked: true
+            })
+        );
+        let text = String::from_utf8(out).unwrap();
+        assert!(!text.contains("Enter, q to quit"));
+        // The vulnerable code comes before the attack, the secure code after it
+        let at = |needle: &str| text.find(needle).unwrap_or_else(|| panic!("no {}", needle));
+        assert!(
+            at("programs/signer-authorization/src/vulnerable.rs") < at("== secure_withdraw ==")
+        );
+        assert!(at("== secure_withdraw ==") < at("programs/signer-authorization/src/secure.rs"));
+        assert!(!text.contains("use anchor_lang"));
+
+        // Past the vulnerable attack, then quit at the secure version
+        let mut input: &[u8] = b"\nq\n";
+        let mut out = Vec::new();
+        let outcome = tutorial::run(demo, Some(&mut input), &mut out, Palette::new(false)).unwrap();
+        assert_eq!(outcome, None);
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("vulnerable_withdraw: the attack landed"));
+        assert!(!text.contains("== secure_withdraw =="));
+    }
+
+    #[test]
+    fn test_exported_transactions_replay_from_their_accounts() {
+        let demo = demo::find("signer-authorization").unwrap();
+        let (bundle, accounts) = export::capture(demo);
+        assert_eq!(
+            bundle
+                .sides
+                .iter()
+                .map(|s| s.instruction.as_str())
+                .collect::<Vec<_>>(),
+            [demo.vulnerable, demo.secure]
+        );
+
+        // Nothing but the bundle, the accounts and the programs
+        for side in &bundle.sides {
+            let mut svm = LiteSVM::new();
+            for program in &bundle.programs {
+                svm.add_program(
+                    program.program_id.parse().unwrap(),
+                    &security_tests::load_program_bytes(&program.name),
+                );
+            }
+            for fixture in &accounts {
+                fixture.install(&mut svm);
+            }
+            for exported in &side.transactions {
+                let mut tx = exported.decode().unwrap();
+                assert_eq!(tx.signatures[0].to_string(), exported.signature);
+                // Signed for the exporting run's blockhash, which this one doesn't have
+                let signers: Vec<Keypair> = tx.message.static_account_keys()
+                    [..tx.message.header().num_required_signatures as usize]
+                    .iter()
+                    .map(|key| {
+                        let label = bundle
+                            .keypairs
+                            .iter()
+                            .find(|(_, pubkey)| **pubkey == key.to_string())
+                            .map(|(label, _)| label)
+                            .expect("an exported keypair signs");
+                        seeded_keypair(bundle.seed, label)
+                    })
+                    .collect();
+                tx.message.set_recent_blockhash(svm.latest_blockhash());
+                let tx = VersionedTransaction::try_new(tx.message, &signers).unwrap();
+                let result = svm.send_transaction(tx);
+                assert_eq!(
+                    result.is_ok(),
+                    exported.result == "ok",
+                    "{}: {:?}",
+                    side.instruction,
+                    result
+                );
+            }
+        }
+        let [vulnerable, secure] = &bundle.sides[..] else {
+            panic!("two sides");
+        };
+        assert_eq!(vulnerable.transactions.last().unwrap().result, "ok");
+        assert_ne!(secure.transactions.last().unwrap().result, "ok");
+    }
+
+    #[test]
+    fn test_verify_trims_programdata_padding() {
+        let program_id = Pubkey::new_unique();
+        let programdata = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let elf = b"\x7fELF program bytes".to_vec();
+
+        let mut program = vec![2, 0, 0, 0];
+        program.extend_from_slice(programdata.as_ref());
+        // Deployed with room to grow: zeros after the ELF
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&elf);
+        data.extend_from_slice(&[0; 64]);
+        let account = |data: Vec<u8>, executable| Account {
+            lamports: 1,
+            data,
+            owner: sdk::bpf_loader_upgradeable::ID,
+            executable,
+            rent_epoch: 0,
+        };
+        let accounts = [
+            (program_id, account(program, true)),
+            (programdata, account(data, false)),
+        ];
+        let fetch = |address: &Pubkey| {
+            Ok(accounts
+                .iter()
+                .find(|(a, _)| a == address)
+                .map(|(_, account)| account.clone()))
+        };
+
+        let deployed = verify::deployed_elf(&program_id, fetch).unwrap().unwrap();
+        assert_eq!(deployed.upgrade_authority, Some(authority));
+        assert_eq!(deployed.bytes.len(), elf.len() + 64);
+
+        let verification = verify::verify(&program_id, &elf, fetch).unwrap();
+        assert!(verification.matches);
+        let stored = verification.deployed.unwrap();
+        assert_eq!(stored.trimmed, verification.local);
+        assert_ne!(stored.padded, verification.local);
+        assert_eq!(stored.upgrade_authority, Some(authority.to_string()));
+
+        assert!(
+            !verify::verify(&program_id, b"\x7fELF other bytes", fetch)
+                .unwrap()
+                .matches
+        );
+        // Not a program
+        let missing = verify::verify(&programdata, &elf, fetch).unwrap();
+        assert!(missing.deployed.is_none() && !missing.matches);
+    }
+
+    #[test]
+    fn test_writeup_renders_registry_readme_and_diff() {
+        let v = solsec_registry::find("pda-security").unwrap();
+        let page = docs::render(v, None);
+        assert!(page.starts_with(docs::GENERATED));
+        assert!(page.contains("# Weak PDA Seeds\n"));
+        assert!(page.contains(&v.score.vector()));
+        assert!(page.contains("[CWE-340](https://cwe.mitre.org/data/definitions/340.html)"));
+        // The README's explanation and scenario, then the suite's exploits
+        let at = |needle: &str| page.find(needle).unwrap_or_else(|| panic!("no {}", needle));
+        assert!(at("## The vulnerability") < at("## Exploit walkthrough"));
+        assert!(at("## Exploit walkthrough") < at("`test_weak_seeds_predictable`"));
+        assert!(at("`test_weak_seeds_predictable`") < at("## Mitigation"));
+        assert!(page.contains(
+            "`programs/pda-security/src/vulnerable.rs` -> `programs/pda-security/src/secure.rs`"
+        ));
+        assert!(page.contains("```diff\ndiff --git"));
+        assert!(page.ends_with("```\n"));
+
+        let markdown =
+            "# T\n\n## Overview\n\nIntro\n\n## Code\n\n```\n## not a heading\n```\n\n## Next\n";
+        assert_eq!(
+            docs::section(markdown, &["Missing", "Code"]).as_deref(),
+            Some("```\n## not a heading\n```")
+        );
+        assert_eq!(
+            docs::section(markdown, &["Overview"]).as_deref(),
+            Some("Intro")
+        );
+        assert_eq!(docs::section(markdown, &["Missing"]), None);
+
+        let index = docs::index();
+        for v in solsec_registry::CATALOG {
+            assert!(index.contains(&format!("[{p}.md]({p}.md)", p = v.program)));
+        }
+    }
+
+    #[test]
+    fn test_client_mirrors_idl() {
+        let generated = client::generate(TEST_IDL).unwrap();
+        assert_eq!(generated.program, "test-program");
+        let file = |name: &str| {
+            let (_, text) = generated
+                .files
+                .iter()
+                .find(|(f, _)| f.ends_with(name))
+                .unwrap();
+            text.as_str()
+        };
+        assert!(file("Cargo.toml").contains("name = \"test-program-client\""));
+
+        let lib = file("lib.rs");
+        assert!(lib.contains(
+            "pub const ID: Pubkey = Pubkey::from_str_const(\"11111111111111111111111111111111\");"
+        ));
+        assert!(lib.contains("    pub const DISCRIMINATOR: [u8; 8] = [9, 9, 9, 9, 9, 9, 9, 9];"));
+        // Bound accounts in IDL order, the fixed one filled in
+        let at = |needle: &str| lib.find(needle).unwrap_or_else(|| panic!("no {}", needle));
+        assert!(
+            at("AccountMeta::new(accounts.payer, true)")
+                < at("AccountMeta::new_readonly(accounts.config, false)")
+        );
+        assert!(
+            at("AccountMeta::new(accounts.proposal, false)")
+                < at("AccountMeta::new_readonly(Pubkey::from_str_const(\"1111")
+        );
+        assert!(lib.contains("        pub delegate: Option<Pubkey>,"));
+        let legacy = instruction_discriminator("legacy");
+        assert!(lib.contains(&format!("pub const LEGACY: [u8; 8] = {:?};", legacy)));
+
+        let pda = r#"{
+            "metadata": { "name": "seeded" },
+            "instructions": [{
+                "name": "open",
+                "accounts": [
+                    { "name": "user", "signer": true },
+                    { "name": "vault", "writable": true, "pda": { "seeds": [
+                        { "kind": "const", "value": [118, 97, 117, 108, 116] },
+                        { "kind": "account", "path": "user" },
+                        { "kind": "arg", "path": "id" }
+                    ] } },
+                    { "name": "mirror", "pda": { "seeds": [{ "kind": "account", "path": "vault.authority" }] } }
+                ],
+                "args": [{ "name": "id", "type": "u64" }]
+            }]
+        }"#;
+        let lib = client::generate(pda).unwrap().files.remove(1).1;
+        assert!(lib.contains(
+            "    pub fn vault(program_id: &Pubkey, user: &Pubkey, id: u64) -> (Pubkey, u8) {"
+        ));
+        assert!(lib.contains(
+            "find_program_address(&[b\"vault\", user.as_ref(), &id.to_le_bytes()], program_id)"
+        ));
+        // Seeds read from another account's data need that account
+        assert!(!lib.contains("fn mirror"));
+
+        let checked_in: Vec<_> = client::idls()
+            .iter()
+            .filter(|(_, path)| path.starts_with(checked_in_idl_dir()))
+            .map(|(_, path)| client::generate(&std::fs::read_to_string(path).unwrap()).unwrap())
+            .collect();
+        assert_eq!(checked_in.len(), 9);
+        assert_eq!(
+            client::stale(&client::clients_dir(), &checked_in),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_codama_nodes_mirror_idl() {
+        let raw = serde_json::from_str(TEST_IDL).unwrap();
+        let root = codama::root_node("test_program", &raw).unwrap();
+        assert_eq!(root["kind"], "rootNode");
+        let program = &root["program"];
+        assert_eq!(program["name"], "testProgram");
+        assert_eq!(program["publicKey"], "11111111111111111111111111111111");
+
+        // The discriminator leads, omitted from what callers pass
+        let ix = &program["instructions"][0];
+        assert_eq!(ix["name"], "createProposal");
+        let disc = &ix["arguments"][0];
+        assert_eq!(disc["defaultValue"]["data"], "0102030405060708");
+        assert_eq!(disc["defaultValueStrategy"], "omitted");
+        assert_eq!(ix["arguments"][1]["name"], "proposalId");
+        assert_eq!(ix["arguments"][4]["type"]["kind"], "optionTypeNode");
+        let accounts = ix["accounts"].as_array().unwrap();
+        assert_eq!(accounts[0]["isSigner"], true);
+        assert_eq!(accounts[3]["defaultValue"]["kind"], "publicKeyValueNode");
+        let legacy: String = instruction_discriminator("legacy")
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let legacy_disc = &program["instructions"][1]["arguments"][0]["defaultValue"];
+        assert_eq!(legacy_disc["data"], legacy.as_str());
+
+        // An account's layout is its data, not a defined type as well
+        let vault = &program["accounts"][0];
+        assert_eq!(vault["data"]["fields"][1]["name"], "authority");
+        assert_eq!(program["definedTypes"], serde_json::json!([]));
+
+        let raw = serde_json::json!({
+            "metadata": { "name": "seeded" },
+            "instructions": [{
+                "name": "open",
+                "accounts": [
+                    { "name": "user", "signer": true },
+                    { "name": "vault", "writable": true, "pda": { "seeds": [
+                        { "kind": "const", "value": [118, 97, 117, 108, 116] },
+                        { "kind": "account", "path": "user" },
+                        { "kind": "arg", "path": "id" }
+                    ] } },
+                    { "name": "mirror", "pda": { "seeds": [{ "kind": "account", "path": "vault.authority" }] } }
+                ],
+                "args": [{ "name": "id", "type": "u64" }]
+            }]
+        });
+        let program = &codama::root_node("seeded", &raw).unwrap()["program"];
+        let pda = &program["pdas"][0];
+        assert_eq!(pda["seeds"][0]["value"]["string"], "vault");
+        assert_eq!(pda["seeds"][2]["type"]["format"], "u64");
+        let vault = &program["instructions"][0]["accounts"][1]["defaultValue"];
+        assert_eq!(vault["pda"]["name"], "vault");
+        assert_eq!(vault["seeds"][1]["value"]["kind"], "argumentValueNode");
+        // Seeds read from another account's data need that account
+        assert_eq!(program["pdas"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_events_read_from_inner_instructions() {
+        let disc = Sha256::digest(b"event:SecurityEvent");
+        assert_eq!(solsec_events::DISCRIMINATOR, disc[..8]);
+
+        let (payer, program) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let event = SecurityEvent::new(
+            program.to_bytes(),
+            EventKind::Withdrawal {
+                vault: Pubkey::new_unique().to_bytes(),
+                authority: Pubkey::new_unique().to_bytes(),
+                destination: Pubkey::new_unique().to_bytes(),
+                amount: 5 * LAMPORTS_PER_SOL,
+            },
+        );
+        let data = event.to_ix_data();
+
+        // The same envelope CPI'd into the system program is a forgery
+        let system = sdk::system_program::ID;
+        let message = Message {
+            account_keys: vec![payer, program, system],
+            ..Message::default()
+        };
+        let inner = |program_id_index: u8| InnerInstruction {
+            instruction: CompiledInstruction {
+                program_id_index,
+                accounts: vec![],
+                data: data.clone(),
+            },
+            stack_height: 2,
+        };
+        let meta = TransactionMetadata {
+            inner_instructions: vec![vec![inner(2), inner(1)]],
+            ..TransactionMetadata::default()
+        };
+        assert_eq!(events::from_litesvm(&message, &meta), [event]);
+
+        // Over RPC the program is a looked-up account
+        let encoded = |index: u8| {
+            serde_json::json!({
+                "programIdIndex": index,
+                "accounts": [],
+                "data": bs58::encode(&data).into_string(),
+            })
+        };
+        let result = serde_json::json!({
+            "transaction": { "message": { "accountKeys": [payer.to_string(), system.to_string()] } },
+            "meta": {
+                "loadedAddresses": { "writable": [], "readonly": [program.to_string()] },
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [encoded(1), encoded(2)],
+                }],
+            },
+        });
+        assert_eq!(events::from_rpc(&result), Ok(vec![event]));
+
+        // The builder fills in `#[event_cpi]`'s accounts
+        let idl = r#"{
+            "metadata": { "name": "emitter" },
+            "instructions": [{
+                "name": "act",
+                "discriminator": [1],
+                "accounts": [
+                    { "name": "authority", "signer": true },
+                    { "name": "event_authority", "pda": { "seeds": [] } },
+                    { "name": "program", "address": "11111111111111111111111111111111" }
+                ],
+                "args": []
+            }]
+        }"#;
+        let authority = Pubkey::new_unique();
+        let ix = Idl::from_json(idl, program)
+            .ix("act")
+            .account("authority", authority)
+            .build();
+        assert_eq!(
+            ix.accounts,
+            [
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new_readonly(idl::event_authority(&program), false),
+                AccountMeta::new_readonly(program, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_benches_cover_the_catalog() {
+        for b in bench::BENCHES {
+            assert!(
+                solsec_registry::framework(b.program).is_some(),
+                "{}: no registry entry",
+                b.program
+            );
+            assert_eq!(b.deploy, b.program.replace('-', "_"));
+        }
+        // Both need SPL token accounts the benches don't set up
+        let unbenched: Vec<&str> = CATALOG
+            .iter()
+            .map(|v| v.program)
+            .filter(|p| bench::find(p).is_none())
+            .collect();
+        assert_eq!(unbenched, ["p-escrow", "amm"]);
+        for variant in VARIANTS {
+            assert!(
+                bench::find(variant.program).is_some(),
+                "{}",
+                variant.program
+            );
+        }
+
+        let sample = |error: Option<&str>, expected_failure| Sample {
+            program: "multisig-payer".to_string(),
+            instruction: "vulnerable_create_proposal".to_string(),
+            compute_units: 1_000,
+            data_bytes: 0,
+            error: error.map(str::to_string),
+            expected_failure,
+            binary_bytes: 0,
+            port: None,
+        };
+        assert!(!sample(None, false).unexpected());
+        assert!(!sample(Some("failed"), true).unexpected());
+        assert!(sample(Some("failed"), false).unexpected());
+        assert!(sample(None, true).unexpected());
+    }
+
+    #[test]
+    fn test_ports_compare_benched_variants_across_frameworks() {
+        for port in bench::PORTS {
+            assert!(
+                port.variants.len() > 1,
+                "{}: nothing to compare",
+                port.logic
+            );
+            let mut frameworks = std::collections::HashSet::new();
+            for &(program, instruction) in port.variants {
+                assert!(bench::find(program).is_some(), "{}: not benched", program);
+                assert_eq!(
+                    bench::port(program, instruction).map(|p| p.logic),
+                    Some(port.logic),
+                    "{}/{} is a variant of one port",
+                    program,
+                    instruction
+                );
+                assert!(
+                    frameworks.insert(solsec_registry::framework(program).unwrap()),
+                    "{}: one variant per framework",
+                    port.logic
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_suites_import_through_the_sdk_module() {
+        // Only `sdk` names a Solana crate, so either dependency set builds
+        // every suite; the client generator's output strings are exempt
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut files = Vec::new();
+        for sub in [dir.to_path_buf(), dir.join("src"), dir.join("src/bin")] {
+            for entry in std::fs::read_dir(sub).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().is_some_and(|ext| ext == "rs") && !path.ends_with("src/sdk.rs")
+                {
+                    files.push(path);
+                }
+            }
+        }
+        assert!(files.len() > 40, "{} files", files.len());
+        for path in files {
+            let source = std::fs::read_to_string(&path).unwrap();
+            for (n, line) in source.lines().enumerate() {
+                let code = line.split('"').next().unwrap();
+                assert!(
+                    !code.contains("solana_"),
+                    "{}:{} names a Solana crate; import it from `security_tests::sdk`",
+                    path.display(),
+                    n + 1
+                );
+            }
+        }
+    }
+}
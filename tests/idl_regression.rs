@@ -0,0 +1,64 @@
+//! IDL regression baselines
+//!
+//! The byte layouts of instructions and accounts are part of what each
+//! example teaches, and fixtures across the suites write those bytes by
+//! hand. Every Anchor program's generated IDL is rendered with
+//! `Idl::layout` (discriminators, account order and flags, argument types,
+//! field offsets, error codes) and compared against
+//! `tests/golden/idl/<program>.snap`, so a reordered account, a retyped
+//! argument or a shifted field fails here with a line diff naming it.
+//!
+//! Every program needs a committed baseline, and every baseline a program.
+//! IDLs come from `anchor build`; with `AUTO_BUILD_PROGRAMS=1` stale ones
+//! are rebuilt first. After an intentional interface change:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test idl_regression
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use security_tests::build::{self, ProgramSource};
+    use security_tests::common;
+    use security_tests::golden::{assert_golden_text, golden_dir};
+    use security_tests::idl::Idl;
+
+    #[test]
+    fn test_idl_layouts_match_baselines() {
+        let programs: Vec<ProgramSource> = ProgramSource::all(&common::deploy_dir())
+            .into_iter()
+            .filter(|p| p.anchor)
+            .collect();
+        assert!(!programs.is_empty(), "no Anchor programs found under programs/");
+
+        // A baseline left behind by a renamed or removed program
+        let baselines = std::fs::read_dir(golden_dir().join("idl")).unwrap();
+        for entry in baselines {
+            let path = entry.unwrap().path();
+            let name = path.file_stem().unwrap().to_string_lossy();
+            assert!(
+                programs.iter().any(|p| p.name == name),
+                "{} has no Anchor program; delete it",
+                path.display()
+            );
+        }
+
+        for program in programs {
+            if build::enabled() {
+                build::ensure_built(&program.deploy_dir(), &program.name);
+            }
+            let path = program.idl_path();
+            assert!(
+                path.exists(),
+                "{} has no IDL at {} - run `anchor build` or set {}=1",
+                program.name,
+                path.display(),
+                build::AUTO_BUILD_ENV
+            );
+
+            // The program id is not part of the interface being pinned
+            let idl = Idl::from_path(&path, Default::default());
+            assert_golden_text(&format!("idl/{}", program.name), &idl.layout());
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Tests for the Insurance Fund Drain vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: A permissionless payout sends an approved claim to the
+//!   recipient the caller passes, so an attacker collects every approved
+//!   claim in the fund
+//! - Secure: The claim is re-derived from the claimant it stores, and only
+//!   that claimant can be paid
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        Instruction, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    /// Cover in the fund, above its rent
+    const COVER: u64 = 10 * LAMPORTS_PER_SOL;
+    /// One signature
+    const TX_FEE: u64 = 5_000;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("insurance_fund")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("insurance_fund")
+    }
+
+    fn fund() -> Pubkey {
+        common::find_pda(&[b"fund"], &program_id()).0
+    }
+
+    fn claim(claimant: &Pubkey, id: u64) -> Pubkey {
+        common::find_pda(
+            &[b"claim", claimant.as_ref(), &id.to_le_bytes()],
+            &program_id(),
+        )
+        .0
+    }
+
+    /// The program with a fund holding [`COVER`], its adjuster, two
+    /// policyholders and the attacker.
+    fn setup(name: &str) -> (Scenario, Keypair, Keypair, Keypair, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let adjuster = svm.keypair("adjuster");
+        let alice = svm.keypair("alice");
+        let bob = svm.keypair("bob");
+        let attacker = svm.keypair("attacker");
+        for signer in [&adjuster, &alice, &bob, &attacker] {
+            svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        svm.add_program("insurance_fund");
+
+        let ix = idl()
+            .ix("initialize")
+            .account("adjuster", adjuster.pubkey())
+            .account("fund", fund())
+            .build();
+        send(&mut svm, ix, &[&adjuster]).expect("Initialize failed");
+        svm.airdrop(&fund(), COVER).unwrap();
+        (svm, adjuster, alice, bob, attacker)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    /// File claim 0 for `amount`, and return it.
+    fn file(svm: &mut Scenario, claimant: &Keypair, amount: u64) -> Pubkey {
+        let claim = claim(&claimant.pubkey(), 0);
+        let ix = idl()
+            .ix("file_claim")
+            .arg("id", 0u64)
+            .arg("amount", amount)
+            .account("claimant", claimant.pubkey())
+            .account("claim", claim)
+            .build();
+        send(svm, ix, &[claimant]).expect("File failed");
+        claim
+    }
+
+    fn approve(svm: &mut Scenario, adjuster: &Keypair, claim: Pubkey) {
+        let ix = idl()
+            .ix("approve_claim")
+            .account("adjuster", adjuster.pubkey())
+            .account("fund", fund())
+            .account("claim", claim)
+            .build();
+        send(svm, ix, &[adjuster]).expect("Approve failed");
+    }
+
+    fn pay(name: &str, caller: &Pubkey, claim: Pubkey, recipient: &Pubkey) -> Instruction {
+        idl()
+            .ix(name)
+            .account("caller", *caller)
+            .account("fund", fund())
+            .account("claim", claim)
+            .account("recipient", *recipient)
+            .build()
+    }
+
+    /// Alice's 1 SOL claim and Bob's 2 SOL claim, both filed and approved.
+    fn approved_claims(
+        svm: &mut Scenario,
+        adjuster: &Keypair,
+        alice: &Keypair,
+        bob: &Keypair,
+    ) -> [(Pubkey, u64); 2] {
+        let claims = [
+            (file(svm, alice, LAMPORTS_PER_SOL), LAMPORTS_PER_SOL),
+            (file(svm, bob, 2 * LAMPORTS_PER_SOL), 2 * LAMPORTS_PER_SOL),
+        ];
+        for (claim, _) in claims {
+            approve(svm, adjuster, claim);
+        }
+        claims
+    }
+
+    #[test]
+    fn test_vulnerable_pays_approved_claims_to_the_caller() {
+        // SCENARIO: The adjuster approves Alice's and Bob's claims
+        // ATTACK: Pay out both, naming the attacker as recipient
+        // EXPECTED: Vulnerable version pays the attacker 3 SOL, and the
+        // claimants' payouts are refused as already paid (EXPLOIT WORKS)
+
+        let (mut svm, adjuster, alice, bob, attacker) =
+            setup("insurance_fund/vulnerable_pays_approved_claims_to_the_caller");
+        let claims = approved_claims(&mut svm, &adjuster, &alice, &bob);
+        let fund_before = svm.get_balance(&fund()).unwrap();
+        let attacker_before = svm.get_balance(&attacker.pubkey()).unwrap();
+
+        for (claim, _) in claims {
+            let ix = pay(
+                "vulnerable_pay_claim",
+                &attacker.pubkey(),
+                claim,
+                &attacker.pubkey(),
+            );
+            let (result, diff) = svm.diff(|svm| send(svm, ix, &[&attacker]));
+            assert!(result.is_ok(), "{:?}", result);
+            diff.with_idl(&idl())
+                .expect_only_changed([fund(), claim, attacker.pubkey()]);
+        }
+
+        let drained: u64 = claims.iter().map(|(_, amount)| amount).sum();
+        assert_eq!(svm.get_balance(&fund()), Some(fund_before - drained));
+        assert_eq!(
+            svm.get_balance(&attacker.pubkey()),
+            Some(attacker_before + drained - 2 * TX_FEE)
+        );
+
+        // Alice comes to collect, and her claim is spent
+        let ix = pay(
+            "vulnerable_pay_claim",
+            &alice.pubkey(),
+            claims[0].0,
+            &alice.pubkey(),
+        );
+        assert_err!(send(&mut svm, ix, &[&alice]), InsuranceError::AlreadyPaid);
+    }
+
+    #[test]
+    fn test_secure_rejects_a_recipient_other_than_the_claimant() {
+        // SCENARIO: The same approved claims
+        // EXPECTED: Secure version REJECTS a payout to the attacker
+        // (FIX WORKS)
+
+        let (mut svm, adjuster, alice, bob, attacker) =
+            setup("insurance_fund/secure_rejects_a_recipient_other_than_the_claimant");
+        let claims = approved_claims(&mut svm, &adjuster, &alice, &bob);
+        let fund_before = svm.get_balance(&fund()).unwrap();
+
+        for (claim, _) in claims {
+            let ix = pay(
+                "secure_pay_claim",
+                &attacker.pubkey(),
+                claim,
+                &attacker.pubkey(),
+            );
+            assert_err!(
+                send(&mut svm, ix, &[&attacker]),
+                InsuranceError::WrongRecipient
+            );
+        }
+        assert_eq!(svm.get_balance(&fund()), Some(fund_before));
+    }
+
+    #[test]
+    fn test_secure_rejects_an_unapproved_claim() {
+        // SCENARIO: The attacker files a claim of their own for the whole
+        // fund, which the adjuster never approves
+        // EXPECTED: Secure version REJECTS paying it (FIX WORKS)
+
+        let (mut svm, _, _, _, attacker) =
+            setup("insurance_fund/secure_rejects_an_unapproved_claim");
+        let claim = file(&mut svm, &attacker, COVER);
+
+        let ix = pay(
+            "secure_pay_claim",
+            &attacker.pubkey(),
+            claim,
+            &attacker.pubkey(),
+        );
+        assert_err!(
+            send(&mut svm, ix, &[&attacker]),
+            InsuranceError::NotApproved
+        );
+    }
+
+    #[test]
+    fn test_secure_pays_the_claimant_once() {
+        let (mut svm, adjuster, alice, bob, attacker) =
+            setup("insurance_fund/secure_pays_the_claimant_once");
+        let claims = approved_claims(&mut svm, &adjuster, &alice, &bob);
+        let fund_before = svm.get_balance(&fund()).unwrap();
+
+        // Anyone may crank a payout; it reaches the claimant with the
+        // claim's rent
+        for ((claim, amount), claimant) in claims.into_iter().zip([&alice, &bob]) {
+            let rent = svm.get_balance(&claim).unwrap();
+            let claimant_before = svm.get_balance(&claimant.pubkey()).unwrap();
+            let ix = pay(
+                "secure_pay_claim",
+                &attacker.pubkey(),
+                claim,
+                &claimant.pubkey(),
+            );
+            let result = send(&mut svm, ix, &[&attacker]);
+            assert!(result.is_ok(), "{:?}", result);
+            assert_eq!(
+                svm.get_balance(&claimant.pubkey()),
+                Some(claimant_before + amount + rent)
+            );
+            assert!(svm.get_account(&claim).is_none());
+        }
+        assert_eq!(
+            svm.get_balance(&fund()),
+            Some(fund_before - 3 * LAMPORTS_PER_SOL)
+        );
+
+        // Closed, the claim can't be paid again
+        let ix = pay(
+            "secure_pay_claim",
+            &alice.pubkey(),
+            claims[0].0,
+            &alice.pubkey(),
+        );
+        assert_err!(
+            send(&mut svm, ix, &[&alice]),
+            ErrorCode::AccountNotInitialized
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Tests for the Mutable Lookup Table vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: A payout approved by lookup table index pays whatever the
+//!   table holds at execution, including addresses appended after review
+//! - Secure: Only a frozen table's addresses are approved, and execution
+//!   pays the addresses pinned at approval
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::alt;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::{ArgValue, Idl};
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        AccountMeta, AddressLookupTableAccount, Instruction, Keypair, Pubkey, Signer,
+        LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    const AMOUNT: u64 = LAMPORTS_PER_SOL;
+    const PAYOUT_ID: u64 = 1;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("lookup_table_pitfalls")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("lookup_table_pitfalls")
+    }
+
+    fn treasury() -> Pubkey {
+        common::find_pda(&[b"treasury"], &program_id()).0
+    }
+
+    fn payout() -> Pubkey {
+        common::find_pda(&[b"payout", &PAYOUT_ID.to_le_bytes()], &program_id()).0
+    }
+
+    /// The program with a treasury holding 10 SOL, its authority, and a
+    /// funded attacker.
+    fn setup(name: &str) -> (Scenario, Keypair, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let authority = svm.keypair("authority");
+        let attacker = svm.keypair("attacker");
+        for signer in [&authority, &attacker] {
+            svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        svm.add_program("lookup_table_pitfalls");
+
+        let ix = idl()
+            .ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("treasury", treasury())
+            .build();
+        send(&mut svm, ix, &[&authority]).expect("Initialize failed");
+        svm.airdrop(&treasury(), 10 * LAMPORTS_PER_SOL).unwrap();
+        (svm, authority, attacker)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let tx = alt::v0_transaction(svm, &[ix], signers, &[]);
+        svm.send_transaction(tx)
+    }
+
+    fn propose(svm: &mut Scenario, proposer: &Keypair, table: &Pubkey, indexes: Vec<u8>) {
+        let ix = idl()
+            .ix("propose_payout")
+            .arg("id", PAYOUT_ID)
+            .arg("table", *table)
+            .arg("indexes", indexes)
+            .arg("amount", AMOUNT)
+            .account("proposer", proposer.pubkey())
+            .account("payout", payout())
+            .build();
+        send(svm, ix, &[proposer]).expect("Propose failed");
+    }
+
+    /// `secure_approve` reads the table; `vulnerable_approve` doesn't look.
+    fn approve(name: &str, authority: &Keypair, table: &Pubkey) -> Instruction {
+        let idl = idl();
+        let ix = idl
+            .ix(name)
+            .account("authority", authority.pubkey())
+            .account("treasury", treasury())
+            .account("payout", payout());
+        match name {
+            "secure_approve" => ix.account("table", *table),
+            _ => ix,
+        }
+        .build()
+    }
+
+    /// `vulnerable_execute` reads the table; `secure_execute` only the
+    /// recipients pinned in the payout.
+    fn execute(
+        name: &str,
+        executor: &Keypair,
+        table: &Pubkey,
+        recipients: &[Pubkey],
+    ) -> Instruction {
+        let idl = idl();
+        let mut ix = idl
+            .ix(name)
+            .account("executor", executor.pubkey())
+            .account("treasury", treasury())
+            .account("payout", payout());
+        if name == "vulnerable_execute" {
+            ix = ix.account("table", *table);
+        }
+        recipients
+            .iter()
+            .fold(ix, |ix, recipient| {
+                ix.remaining(AccountMeta::new(*recipient, false))
+            })
+            .build()
+    }
+
+    /// What an approver reviewing the payout is shown: the addresses its
+    /// indexes resolve to in the table now. An index past the end resolves
+    /// to nothing, so it's left out, as `alt::account_keys` leaves out a
+    /// transaction's unresolvable lookups.
+    fn approver_view(svm: &Scenario, table: &Pubkey, indexes: &[u8]) -> Vec<Pubkey> {
+        let table = alt::lookup_table(svm, table);
+        indexes
+            .iter()
+            .filter_map(|&i| table.addresses.get(i as usize).copied())
+            .collect()
+    }
+
+    /// A frozen table of `addresses`, created by `authority`.
+    fn frozen_table(
+        svm: &mut Scenario,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> AddressLookupTableAccount {
+        let table = svm.create_lookup_table(authority, addresses);
+        svm.freeze_lookup_table(&table.key, authority);
+        table
+    }
+
+    fn pinned_recipients(svm: &Scenario) -> Vec<Pubkey> {
+        let account = svm.get_account(&payout()).unwrap();
+        let (_, fields) = idl().decode_account(&account.data).unwrap();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+        };
+        let Some(ArgValue::UInt(count)) = field("index_count") else {
+            panic!("payout has no index_count");
+        };
+        let Some(ArgValue::Vec(recipients)) = field("recipients") else {
+            panic!("payout has no recipients");
+        };
+        recipients
+            .into_iter()
+            .take(count as usize)
+            .map(|r| match r {
+                ArgValue::Pubkey(key) => key,
+                other => panic!("recipient is {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_vulnerable_pays_addresses_appended_after_approval() {
+        // SCENARIO: The attacker proposes a payout to alice from their own
+        // lookup table, with a second index past the table's end
+        // ATTACK: Extend the table with their address once the authority
+        // approves what it was shown
+        // EXPECTED: Vulnerable version pays the attacker (EXPLOIT WORKS)
+
+        let (mut svm, authority, attacker) =
+            setup("lookup_table_pitfalls/vulnerable_pays_addresses_appended_after_approval");
+        let alice = svm.keypair("alice").pubkey();
+        let table = svm.create_lookup_table(&attacker, &[alice]);
+        propose(&mut svm, &attacker, &table.key, vec![0, 1]);
+
+        // Step 1: The authority reviews one recipient, alice, and approves
+        assert_eq!(approver_view(&svm, &table.key, &[0, 1]), [alice]);
+        let result = send(
+            &mut svm,
+            approve("vulnerable_approve", &authority, &table.key),
+            &[&authority],
+        );
+        assert!(result.is_ok(), "{:?}", result);
+
+        // Step 2: The attacker appends a wallet of theirs at index 1
+        let wallet = svm.keypair("attacker_wallet").pubkey();
+        let table = svm.extend_lookup_table(&table.key, &attacker, &[wallet]);
+        assert_eq!(approver_view(&svm, &table.key, &[0, 1]), [alice, wallet]);
+
+        // Step 3: Execute, loading both recipients from the same table
+        let ix = execute(
+            "vulnerable_execute",
+            &attacker,
+            &table.key,
+            &[alice, wallet],
+        );
+        let tx = alt::v0_transaction(&svm, &[ix], &[&attacker], &[table]);
+        let (result, diff) = svm.diff(|svm| svm.send_transaction(tx));
+        assert!(result.is_ok(), "{:?}", result);
+        diff.with_idl(&idl()).expect_only_changed([
+            treasury(),
+            payout(),
+            alice,
+            wallet,
+            attacker.pubkey(),
+        ]);
+
+        // Alice got her share, and so did a recipient nobody approved
+        assert_eq!(svm.get_balance(&alice), Some(AMOUNT));
+        assert_eq!(svm.get_balance(&wallet), Some(AMOUNT));
+    }
+
+    #[test]
+    fn test_secure_rejects_unfrozen_table() {
+        // SCENARIO: The same payout, from a table its authority can extend
+        // EXPECTED: Secure version REJECTS the approval (FIX WORKS)
+
+        let (mut svm, authority, attacker) =
+            setup("lookup_table_pitfalls/secure_rejects_unfrozen_table");
+        let alice = svm.keypair("alice").pubkey();
+        let table = svm.create_lookup_table(&attacker, &[alice]);
+        propose(&mut svm, &attacker, &table.key, vec![0]);
+
+        let result = send(
+            &mut svm,
+            approve("secure_approve", &authority, &table.key),
+            &[&authority],
+        );
+        assert_err!(result, PayoutError::LookupTableNotFrozen);
+    }
+
+    #[test]
+    fn test_secure_rejects_unresolved_index() {
+        // SCENARIO: A frozen table, and an index past its end the approver
+        // would not be shown
+        // EXPECTED: Secure version REJECTS the approval (FIX WORKS)
+
+        let (mut svm, authority, attacker) =
+            setup("lookup_table_pitfalls/secure_rejects_unresolved_index");
+        let alice = svm.keypair("alice").pubkey();
+        let table = frozen_table(&mut svm, &attacker, &[alice]);
+        propose(&mut svm, &attacker, &table.key, vec![0, 1]);
+
+        let result = send(
+            &mut svm,
+            approve("secure_approve", &authority, &table.key),
+            &[&authority],
+        );
+        assert_err!(result, PayoutError::UnresolvedIndex);
+    }
+
+    #[test]
+    fn test_secure_rejects_substituted_recipient() {
+        // SCENARIO: An approved payout to alice and bob
+        // ATTACK: Execute it with the attacker in bob's place
+        // EXPECTED: Secure version REJECTS the execution (FIX WORKS)
+
+        let (mut svm, authority, attacker) =
+            setup("lookup_table_pitfalls/secure_rejects_substituted_recipient");
+        let alice = svm.keypair("alice").pubkey();
+        let bob = svm.keypair("bob").pubkey();
+        let table = frozen_table(&mut svm, &attacker, &[alice, bob]);
+        propose(&mut svm, &attacker, &table.key, vec![0, 1]);
+        let result = send(
+            &mut svm,
+            approve("secure_approve", &authority, &table.key),
+            &[&authority],
+        );
+        assert!(result.is_ok(), "{:?}", result);
+
+        let ix = execute(
+            "secure_execute",
+            &attacker,
+            &table.key,
+            &[alice, attacker.pubkey()],
+        );
+        let tx = alt::v0_transaction(&svm, &[ix], &[&attacker], &[table]);
+        assert_err!(svm.send_transaction(tx), PayoutError::RecipientMismatch);
+        assert_eq!(svm.get_balance(&alice), None);
+    }
+
+    #[test]
+    fn test_secure_pays_pinned_recipients() {
+        let (mut svm, authority, attacker) =
+            setup("lookup_table_pitfalls/secure_pays_pinned_recipients");
+        let alice = svm.keypair("alice").pubkey();
+        let bob = svm.keypair("bob").pubkey();
+        let table = frozen_table(&mut svm, &authority, &[alice, bob]);
+        propose(&mut svm, &attacker, &table.key, vec![1, 0]);
+
+        let result = send(
+            &mut svm,
+            approve("secure_approve", &authority, &table.key),
+            &[&authority],
+        );
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(pinned_recipients(&svm), [bob, alice]);
+
+        // Anyone may execute it, loading the recipients from the table
+        let ix = execute("secure_execute", &attacker, &table.key, &[bob, alice]);
+        let tx = alt::v0_transaction(&svm, &[ix], &[&attacker], std::slice::from_ref(&table));
+        let result = svm.send_transaction(tx);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(svm.get_balance(&alice), Some(AMOUNT));
+        assert_eq!(svm.get_balance(&bob), Some(AMOUNT));
+
+        // Once
+        let ix = execute("secure_execute", &authority, &table.key, &[bob, alice]);
+        let tx = alt::v0_transaction(&svm, &[ix], &[&authority], &[table]);
+        assert_err!(svm.send_transaction(tx), PayoutError::AlreadyExecuted);
+    }
+}
@@ -0,0 +1,147 @@
+//! Tests for the native Account Griefing program
+//!
+//! Without Anchor's `init` there is nothing between the program and a bare
+//! `create_account`, which fails for any address that already holds
+//! lamports. The secure program adds a nonce to the seeds and creates the
+//! account the way `init` does, so a pre-funded address still works.
+
+#[cfg(test)]
+mod tests {
+    use litesvm::LiteSVM;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{Instruction, Keypair, Pubkey, Signer, LAMPORTS_PER_SOL};
+
+    const NONCE: u64 = 847291;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("native_account_griefing")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("native_account_griefing")
+    }
+
+    fn setup() -> (LiteSVM, Keypair) {
+        common::setup("native_account_griefing")
+    }
+
+    fn secure_pda(user: &Pubkey, nonce: u64) -> Pubkey {
+        common::find_pda(
+            &[b"stake", user.as_ref(), &nonce.to_le_bytes()],
+            &program_id(),
+        )
+        .0
+    }
+
+    fn secure_create(user: &Keypair, stake: Pubkey, nonce: u64) -> Instruction {
+        idl()
+            .ix("secure_create_stake")
+            .arg("nonce", nonce)
+            .account("user", user.pubkey())
+            .account("stake_account", stake)
+            .build()
+    }
+
+    fn secure_deposit(user: &Keypair, stake: Pubkey) -> Instruction {
+        idl()
+            .ix("secure_deposit")
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("user", user.pubkey())
+            .account("stake_account", stake)
+            .build()
+    }
+
+    #[test]
+    fn test_griefing_attack_blocks_creation() {
+        let (mut svm, victim) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // The victim's stake PDA needs only their key, and the rent of an
+        // empty account there is enough
+        let (stake, _) = common::find_pda(&[b"stake", victim.pubkey().as_ref()], &program_id());
+        let lamports = svm.minimum_balance_for_rent_exemption(0);
+        attacker::prefund(&mut svm, &attacker, &stake, lamports);
+
+        let ix = idl()
+            .ix("vulnerable_create_stake")
+            .account("user", victim.pubkey())
+            .account("stake_account", stake)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &victim));
+        // SystemError::AccountAlreadyInUse
+        assert_err!(result, InstructionError::Custom(0));
+    }
+
+    #[test]
+    fn test_secure_create_survives_prefunding() {
+        let (mut svm, user) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // Even an attacker who learns the nonce only tops up the rent
+        let stake = secure_pda(&user.pubkey(), NONCE);
+        let lamports = svm.minimum_balance_for_rent_exemption(0);
+        attacker::prefund(&mut svm, &attacker, &stake, lamports);
+
+        let ixs = [
+            secure_create(&user, stake, NONCE),
+            secure_deposit(&user, stake),
+        ];
+        let result = svm.send_transaction(attacker::transaction(&svm, &ixs, &user));
+        assert!(result.is_ok(), "create failed: {:?}", result);
+
+        // SecureStakeAccount: tag (1) + owner (32) + amount (8) + ...
+        let account = svm.get_account(&stake).unwrap();
+        assert_eq!(account.owner, program_id());
+        assert_eq!(account.data.len(), 58);
+        assert_eq!(&account.data[1..33], user.pubkey().as_ref());
+        assert_eq!(account.data[33..41], LAMPORTS_PER_SOL.to_le_bytes());
+    }
+
+    #[test]
+    fn test_secure_rejects_mismatched_nonce() {
+        let (mut svm, user) = setup();
+
+        let ix = secure_create(&user, secure_pda(&user.pubkey(), 12345), NONCE);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert_err!(result, InstructionError::InvalidSeeds);
+    }
+
+    #[test]
+    fn test_secure_create_refuses_existing_stake() {
+        let (mut svm, user) = setup();
+        let stake = secure_pda(&user.pubkey(), NONCE);
+        let ix = secure_create(&user, stake, NONCE);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert!(result.is_ok(), "create failed: {:?}", result);
+        svm.expire_blockhash();
+
+        // `init` refuses an account that is already created, before any CPI
+        // into the system program
+        let ix = secure_create(&user, stake, NONCE);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert_err!(result, InstructionError::AccountAlreadyInitialized);
+    }
+
+    #[test]
+    fn test_secure_deposit_rejects_foreign_stake() {
+        let (mut svm, user) = setup();
+        let stake = secure_pda(&user.pubkey(), NONCE);
+        let ix = secure_create(&user, stake, NONCE);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert!(result.is_ok(), "create failed: {:?}", result);
+
+        // The seeds tie the stake to its user; the stored owner only
+        // repeats them
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .unwrap();
+        let ix = secure_deposit(&attacker, stake);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+        assert_err!(result, InstructionError::InvalidSeeds);
+    }
+}
@@ -0,0 +1,114 @@
+//! Tests for the native Integer Overflow program
+//!
+//! `overflow-checks = true` in the release profile doesn't reach
+//! `wrapping_*`, which is what the vulnerable swap uses; the secure one
+//! widens to `u128` and checks every step, as the Anchor program does.
+
+#[cfg(test)]
+mod tests {
+    use litesvm::LiteSVM;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{Instruction, Keypair, Pubkey, Signer};
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("native_arithmetic_overflow")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("native_arithmetic_overflow")
+    }
+
+    /// A pool created through the program with reserves `x` and `y`.
+    fn setup(x: u64, y: u64) -> (LiteSVM, Keypair, Pubkey) {
+        let (mut svm, user) = common::setup("native_arithmetic_overflow");
+        let (pool, _) = common::find_pda(&[b"pool", user.pubkey().as_ref()], &program_id());
+
+        let ix = idl()
+            .ix("initialize")
+            .arg("initial_x", x)
+            .arg("initial_y", y)
+            .arg("fee_bps", 30u16)
+            .account("authority", user.pubkey())
+            .account("pool", pool)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert!(result.is_ok(), "pool setup failed: {:?}", result);
+        (svm, user, pool)
+    }
+
+    fn swap(
+        name: &str,
+        amount_in: u64,
+        min_out: u64,
+        user: &Keypair,
+        pool: Pubkey,
+    ) -> Instruction {
+        idl()
+            .ix(name)
+            .arg("amount_in", amount_in)
+            .arg("min_out", min_out)
+            .account("user", user.pubkey())
+            .account("pool", pool)
+            .build()
+    }
+
+    // Pool: tag (1) + authority (32) + reserve_x (8) + reserve_y (8) + ...
+    fn reserves(svm: &LiteSVM, pool: &Pubkey) -> (u64, u64) {
+        let data = svm.get_account(pool).unwrap().data;
+        let read = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        (read(33), read(41))
+    }
+
+    #[test]
+    fn test_vulnerable_swap_overflow_corrupts_pool() {
+        let (mut svm, user, pool) = setup(1000, 1000);
+
+        // reserve_x + amount_in wraps to 1, so the pool prices its 1000 Y
+        // against a single X and pays out nearly u64::MAX
+        let amount_in = u64::MAX - 998;
+        let ix = swap("vulnerable_swap", amount_in, 0, &user, pool);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert!(result.is_ok(), "swap failed: {:?}", result);
+
+        let amount_out = u64::MAX - 998_999;
+        assert_eq!(
+            result.unwrap().return_data.data,
+            amount_out.to_le_bytes().to_vec()
+        );
+        assert_eq!(reserves(&svm, &pool), (1, 1000u64.wrapping_sub(amount_out)));
+    }
+
+    #[test]
+    fn test_secure_swap_rejects_overflow() {
+        let (mut svm, user, pool) = setup(1000, 1000);
+
+        let ix = swap("secure_swap", u64::MAX - 998, 0, &user, pool);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        // PoolError::MathOverflow
+        assert_err!(result, InstructionError::Custom(6000));
+        assert_eq!(reserves(&svm, &pool), (1000, 1000));
+    }
+
+    #[test]
+    fn test_secure_swap_rejects_slippage() {
+        let (mut svm, user, pool) = setup(1_000_000_000, 1_000_000_000);
+
+        let ix = swap("secure_swap", 1000, 1000, &user, pool);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        // PoolError::SlippageExceeded
+        assert_err!(result, InstructionError::Custom(6001));
+    }
+
+    #[test]
+    fn test_secure_swap_works_normally() {
+        let (mut svm, user, pool) = setup(1_000_000_000, 1_000_000_000);
+
+        let ix = swap("secure_swap", 1000, 900, &user, pool);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &user));
+        assert!(result.is_ok(), "swap failed: {:?}", result);
+        assert_eq!(reserves(&svm, &pool), (1_000_001_000, 1_000_000_000 - 999));
+    }
+}
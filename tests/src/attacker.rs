@@ -0,0 +1,111 @@
+//! Attack primitives shared by the exploit tests
+//!
+//! Each vulnerability suite used to rebuild the same moves by hand: fund an
+//! address the victim is about to create, plant an account with whatever
+//! owner and bytes the attack needs, or pass an authority without its
+//! signature. These helpers do it once:
+//!
+//! ```ignore
+//! use security_tests::attacker;
+//!
+//! // Grief a predictable PDA before the victim creates it
+//! attacker::prefund(&mut svm, &attacker, &victim_pda, 890_880);
+//!
+//! // Plant a config the program never wrote
+//! let data = attacker.pubkey().to_bytes().to_vec();
+//! attacker::forge_account(&mut svm, fake_config, system_program::ID, data);
+//!
+//! // Call an instruction "as" the authority without their signature
+//! let ix = attacker::strip_signer(ix, &authority.pubkey());
+//! let result = svm.send_transaction(attacker::transaction(&svm, &[ix], &attacker));
+//! ```
+
+use litesvm::LiteSVM;
+
+use crate::sdk::{self, Account, Instruction, Keypair, Message, Pubkey, Signer, Transaction};
+
+/// Send `lamports` from `attacker` to `target` with a real system transfer.
+///
+/// This is what griefing looks like on chain: anyone can fund any address,
+/// and a plain `create_account` fails once the target holds lamports.
+/// Panics if the transfer itself fails, which would mean the test is broken
+/// rather than the program.
+pub fn prefund(svm: &mut LiteSVM, attacker: &Keypair, target: &Pubkey, lamports: u64) {
+    let ix = sdk::system_instruction::transfer(&attacker.pubkey(), target, lamports);
+    let result = svm.send_transaction(transaction(svm, &[ix], attacker));
+    assert!(result.is_ok(), "prefund of {} failed: {:?}", target, result);
+}
+
+/// Install an account with arbitrary owner and data at `address`, funded
+/// to rent exemption.
+///
+/// Stands in for any account the attacker controls or has crafted: a fake
+/// config owned by the wrong program, an account of the wrong type, data
+/// with a spoofed authority field.
+pub fn forge_account(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, data: Vec<u8>) -> Account {
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    forge_account_with_lamports(svm, address, owner, data, lamports)
+}
+
+/// [`forge_account`] with an explicit balance, for attacks that depend on
+/// the lamports (rent boundaries, drained accounts, inflated vaults).
+pub fn forge_account_with_lamports(
+    svm: &mut LiteSVM,
+    address: Pubkey,
+    owner: Pubkey,
+    data: Vec<u8>,
+    lamports: u64,
+) -> Account {
+    let account = Account {
+        lamports,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(address, account.clone())
+        .unwrap_or_else(|e| panic!("Failed to forge account {}: {:?}", address, e));
+    account
+}
+
+/// Copy the account at `source` to `target`, byte for byte.
+///
+/// The copy has the same owner, data and lamports but a different key -
+/// exactly what a program that checks contents but not addresses (seeds,
+/// `has_one`, `address =`) cannot tell apart from the original.
+pub fn clone_account(svm: &mut LiteSVM, source: &Pubkey, target: Pubkey) -> Account {
+    let account = svm
+        .get_account(source)
+        .unwrap_or_else(|| panic!("Cannot clone {}: account does not exist", source));
+    svm.set_account(target, account.clone())
+        .unwrap_or_else(|e| panic!("Failed to clone {} to {}: {:?}", source, target, e));
+    account
+}
+
+/// Clear the signer flag on every meta for `pubkey`.
+///
+/// The account is still passed, so a program that only reads the key
+/// (instead of requiring `Signer`) will treat it as the authority.
+pub fn strip_signer(mut ix: Instruction, pubkey: &Pubkey) -> Instruction {
+    let mut found = false;
+    for meta in ix.accounts.iter_mut().filter(|m| m.pubkey == *pubkey) {
+        meta.is_signer = false;
+        found = true;
+    }
+    assert!(
+        found,
+        "strip_signer: {} is not an account of the instruction",
+        pubkey
+    );
+    ix
+}
+
+/// A transaction paying from and signed by `attacker` alone.
+///
+/// Combined with [`strip_signer`] this is the classic missing-signer
+/// exploit: the authority's key is in the instruction, their signature is
+/// not in the transaction.
+pub fn transaction(svm: &LiteSVM, ixs: &[Instruction], attacker: &Keypair) -> Transaction {
+    let msg = Message::new(ixs, Some(&attacker.pubkey()));
+    Transaction::new(&[attacker], msg, svm.latest_blockhash())
+}
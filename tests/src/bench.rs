@@ -0,0 +1,1622 @@
+//! Compute units and account data of every instruction
+//!
+//! Each [`Bench`] deploys one program and makes a legitimate call of each of
+//! its instructions. [`run`] plays every [`Case`] from the state `setup` left
+//! behind: first its prelude (the calls that put the accounts in place), then
+//! the instruction itself, recording the compute it used and the data bytes
+//! of the accounts it names. `cargo xtask bench-cu` keeps a history of the
+//! samples and compares them across commits:
+//!
+//! ```bash
+//! cargo xtask bench-cu
+//! ```
+//!
+//! The AMM and p-escrow need SPL mints and token accounts the harness
+//! doesn't set up, so they have no bench yet.
+//!
+//! Secure logic written for more than one framework is listed in [`PORTS`].
+//! Each variant is benched like any other instruction, and its samples name
+//! the port, so `bench-cu` can set compute, binary size and account space
+//! side by side for readers weighing Anchor's checks against a leaner
+//! framework.
+
+use serde::{Deserialize, Serialize};
+
+use crate::compute::ComputeUnits;
+use crate::idl::{ArgValue, Idl};
+use crate::scenario::Scenario;
+use crate::sdk::{
+    system_instruction, Account, AccountMeta, Instruction, Keypair, Message, Pubkey, Signer,
+    Transaction, LAMPORTS_PER_SOL,
+};
+
+// Every keypair in a bench derives from this seed, so runs are comparable
+const SEED: u64 = 1;
+
+/// One program's instructions, called the way a client would.
+pub struct Bench {
+    /// Directory under `programs/`, or a variant's package name
+    pub program: &'static str,
+    /// Name of the `.so` in `target/deploy/`
+    pub deploy: &'static str,
+    pub setup: fn(&mut Scenario) -> Vec<Case>,
+}
+
+/// A measured call and the transactions it needs to land first.
+pub struct Case {
+    pub instruction: &'static str,
+    pub prelude: Vec<Transaction>,
+    pub tx: Transaction,
+    /// The instruction can't succeed by design; its cost is still recorded
+    pub fails: bool,
+}
+
+impl Case {
+    pub fn new(instruction: &'static str, tx: Transaction) -> Self {
+        Self {
+            instruction,
+            prelude: Vec::new(),
+            tx,
+            fails: false,
+        }
+    }
+
+    pub fn after(mut self, prelude: &[&Transaction]) -> Self {
+        self.prelude = prelude.iter().map(|tx| (*tx).clone()).collect();
+        self
+    }
+
+    pub fn fails(mut self) -> Self {
+        self.fails = true;
+        self
+    }
+}
+
+/// What one instruction cost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sample {
+    pub program: String,
+    pub instruction: String,
+    pub compute_units: u64,
+    /// Data length of every account the instruction names, after it ran
+    pub data_bytes: u64,
+    /// Set when the call failed, or when its prelude did
+    pub error: Option<String>,
+    pub expected_failure: bool,
+    /// Size of the program's `.so`
+    #[serde(default)]
+    pub binary_bytes: u64,
+    /// The [`Port`] the instruction is a variant of
+    #[serde(default)]
+    pub port: Option<String>,
+}
+
+impl Sample {
+    /// It failed when it should have landed, or the other way round.
+    pub fn unexpected(&self) -> bool {
+        self.error.is_some() != self.expected_failure
+    }
+}
+
+/// Every benched program.
+pub const BENCHES: &[Bench] = &[
+    Bench {
+        program: "signer-authorization",
+        deploy: "signer_authorization",
+        setup: signer_authorization,
+    },
+    Bench {
+        program: "arithmetic-overflow",
+        deploy: "arithmetic_overflow",
+        setup: arithmetic_overflow,
+    },
+    Bench {
+        program: "owner-check",
+        deploy: "owner_check",
+        setup: owner_check,
+    },
+    Bench {
+        program: "pda-security",
+        deploy: "pda_security",
+        setup: pda_security,
+    },
+    Bench {
+        program: "account-close",
+        deploy: "account_close",
+        setup: account_close,
+    },
+    Bench {
+        program: "account-type-mismatch",
+        deploy: "account_type_mismatch",
+        setup: account_type_mismatch,
+    },
+    Bench {
+        program: "duplicate-accounts",
+        deploy: "duplicate_accounts",
+        setup: duplicate_accounts,
+    },
+    Bench {
+        program: "insecure-init",
+        deploy: "insecure_init",
+        setup: insecure_init,
+    },
+    Bench {
+        program: "account-reloading",
+        deploy: "account_reloading",
+        setup: account_reloading,
+    },
+    Bench {
+        program: "remaining-accounts",
+        deploy: "remaining_accounts",
+        setup: remaining_accounts,
+    },
+    Bench {
+        program: "authority-transfer",
+        deploy: "authority_transfer",
+        setup: authority_transfer,
+    },
+    Bench {
+        program: "account-griefing",
+        deploy: "account_griefing",
+        setup: account_griefing,
+    },
+    Bench {
+        program: "multisig-payer",
+        deploy: "multisig_payer",
+        setup: multisig_payer,
+    },
+    Bench {
+        program: "lookup-table-pitfalls",
+        deploy: "lookup_table_pitfalls",
+        setup: lookup_table_pitfalls,
+    },
+    Bench {
+        program: "durable-nonce",
+        deploy: "durable_nonce",
+        setup: durable_nonce,
+    },
+    Bench {
+        program: "fee-rounding",
+        deploy: "fee_rounding",
+        setup: fee_rounding,
+    },
+    Bench {
+        program: "order-book",
+        deploy: "order_book",
+        setup: order_book,
+    },
+    Bench {
+        program: "seed-liquidity",
+        deploy: "seed_liquidity",
+        setup: seed_liquidity,
+    },
+    Bench {
+        program: "insurance-fund",
+        deploy: "insurance_fund",
+        setup: insurance_fund,
+    },
+    Bench {
+        program: "partner-mint",
+        deploy: "partner_mint",
+        setup: partner_mint,
+    },
+    Bench {
+        program: "native-signer-authorization",
+        deploy: "native_signer_authorization",
+        setup: native_signer_authorization,
+    },
+    Bench {
+        program: "native-arithmetic-overflow",
+        deploy: "native_arithmetic_overflow",
+        setup: native_arithmetic_overflow,
+    },
+    Bench {
+        program: "native-account-griefing",
+        deploy: "native_account_griefing",
+        setup: native_account_griefing,
+    },
+    Bench {
+        program: "native-multisig-payer",
+        deploy: "native_multisig_payer",
+        setup: native_multisig_payer,
+    },
+    Bench {
+        program: "p-signer-authorization",
+        deploy: "p_signer_authorization",
+        setup: p_signer_authorization,
+    },
+    Bench {
+        program: "p-account-griefing",
+        deploy: "p_account_griefing",
+        setup: p_account_griefing,
+    },
+];
+
+/// The same secure logic in more than one framework.
+pub struct Port {
+    /// What every variant does, e.g. "withdraw with a stored authority"
+    pub logic: &'static str,
+    /// `(program, instruction)` of each variant, in benched programs of
+    /// different frameworks
+    pub variants: &'static [(&'static str, &'static str)],
+}
+
+/// Every ported piece of secure logic. A port adds its program to
+/// [`BENCHES`] and a row here.
+pub const PORTS: &[Port] = &[
+    Port {
+        logic: "withdraw with a stored authority",
+        variants: &[
+            ("signer-authorization", "secure_withdraw"),
+            ("native-signer-authorization", "secure_withdraw"),
+            ("p-signer-authorization", "secure_withdraw"),
+        ],
+    },
+    Port {
+        logic: "checked constant-product swap",
+        variants: &[
+            ("arithmetic-overflow", "secure_swap"),
+            ("native-arithmetic-overflow", "secure_swap"),
+        ],
+    },
+    Port {
+        logic: "create a stake at a nonce PDA",
+        variants: &[
+            ("account-griefing", "secure_create_stake"),
+            ("native-account-griefing", "secure_create_stake"),
+            ("p-account-griefing", "secure_create_stake"),
+        ],
+    },
+    Port {
+        logic: "create a proposal with a separate rent payer",
+        variants: &[
+            ("multisig-payer", "secure_create_proposal"),
+            ("native-multisig-payer", "secure_create_proposal"),
+        ],
+    },
+];
+
+/// The port `instruction` of `program` is a variant of.
+pub fn port(program: &str, instruction: &str) -> Option<&'static Port> {
+    PORTS
+        .iter()
+        .find(|p| p.variants.contains(&(program, instruction)))
+}
+
+/// The bench for a directory under `programs/` or a variant.
+pub fn find(program: &str) -> Option<&'static Bench> {
+    BENCHES.iter().find(|b| b.program == program)
+}
+
+/// Play every case of `bench`, each from the state `setup` left.
+pub fn run(bench: &Bench) -> Vec<Sample> {
+    let mut svm = Scenario::new(&format!("bench/{}", bench.program), SEED);
+    svm.add_program(bench.deploy);
+    let binary_bytes = crate::load_program_bytes(bench.deploy).len() as u64;
+    let cases = (bench.setup)(&mut svm);
+    let ready = svm.snapshot();
+
+    cases
+        .into_iter()
+        .map(|case| {
+            svm.restore(ready);
+            let mut sample = Sample {
+                program: bench.program.to_string(),
+                instruction: case.instruction.to_string(),
+                compute_units: 0,
+                data_bytes: 0,
+                error: None,
+                expected_failure: case.fails,
+                binary_bytes,
+                port: port(bench.program, case.instruction).map(|p| p.logic.to_string()),
+            };
+            for tx in case.prelude {
+                if let Err(failed) = svm.send_transaction(tx) {
+                    sample.error = Some(format!("prelude failed: {:?}", failed.err));
+                    return sample;
+                }
+            }
+
+            let accounts = named_accounts(&case.tx);
+            let result = svm.send_transaction(case.tx);
+            sample.compute_units = result.compute_units();
+            sample.error = result.err().map(|failed| format!("{:?}", failed.err));
+            sample.data_bytes = accounts
+                .iter()
+                .filter_map(|address| svm.get_account(address))
+                .map(|account| account.data.len() as u64)
+                .sum();
+            sample
+        })
+        .collect()
+}
+
+// Accounts of the transaction's last instruction
+fn named_accounts(tx: &Transaction) -> Vec<Pubkey> {
+    let message = &tx.message;
+    let ix = message
+        .instructions
+        .last()
+        .expect("a case sends an instruction");
+    ix.accounts
+        .iter()
+        .map(|&i| message.account_keys[i as usize])
+        .collect()
+}
+
+fn tx(svm: &Scenario, ix: Instruction, signers: &[&Keypair]) -> Transaction {
+    let payer = signers.first().expect("a transaction needs a fee payer");
+    let msg = Message::new(&[ix], Some(&payer.pubkey()));
+    Transaction::new(signers, msg, svm.latest_blockhash())
+}
+
+fn funded(svm: &mut Scenario, label: &str) -> Keypair {
+    let keypair = svm.keypair(label);
+    svm.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .unwrap();
+    keypair
+}
+
+fn program_account(svm: &mut Scenario, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    svm.set_account(
+        address,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+fn signer_authorization(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("signer_authorization");
+    let authority = funded(svm, "authority");
+    let destination = svm.keypair("destination").pubkey();
+    let (vault, _) =
+        crate::common::find_pda(&[b"vault", authority.pubkey().as_ref()], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("vault", vault)
+            .build(),
+        &[&authority],
+    );
+    let fund = tx(
+        svm,
+        system_instruction::transfer(&authority.pubkey(), &vault, 2 * LAMPORTS_PER_SOL),
+        &[&authority],
+    );
+    let withdraw = |name| {
+        idl.ix(name)
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("vault", vault)
+            .account("authority", authority.pubkey())
+            .account("destination", destination)
+            .build()
+    };
+    let deposit = idl
+        .ix("deposit")
+        .arg("amount", LAMPORTS_PER_SOL)
+        .account("authority", authority.pubkey())
+        .account("vault", vault)
+        .build();
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        // Debits the authority's system account, which the program can't
+        Case::new("deposit", tx(svm, deposit, &[&authority])).fails(),
+        Case::new(
+            "vulnerable_withdraw",
+            tx(svm, withdraw("vulnerable_withdraw"), &[&authority]),
+        )
+        .after(&[&initialize, &fund]),
+        Case::new(
+            "secure_withdraw",
+            tx(svm, withdraw("secure_withdraw"), &[&authority]),
+        )
+        .after(&[&initialize, &fund]),
+    ]
+}
+
+fn arithmetic_overflow(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("arithmetic_overflow");
+    let authority = funded(svm, "authority");
+    let (pool, _) =
+        crate::common::find_pda(&[b"pool", authority.pubkey().as_ref()], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .arg("initial_x", 1_000_000u64)
+            .arg("initial_y", 1_000_000u64)
+            .arg("fee_bps", 30u16)
+            .account("authority", authority.pubkey())
+            .account("pool", pool)
+            .build(),
+        &[&authority],
+    );
+    let swap = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("amount_in", 1_000u64)
+            .arg("min_out", 1u64)
+            .account("user", authority.pubkey())
+            .account("pool", pool)
+            .build();
+        Case::new(name, tx(svm, ix, &[&authority])).after(&[&initialize])
+    };
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        swap("vulnerable_swap"),
+        swap("secure_swap"),
+    ]
+}
+
+fn owner_check(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("owner_check");
+    let admin = funded(svm, "admin");
+    // Config: admin (32), created by the client and owned by the program
+    let config = svm.keypair("config").pubkey();
+    program_account(svm, config, idl.program_id, vec![0; 32]);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize_config")
+            .arg("admin", admin.pubkey())
+            .account("config", config)
+            .build(),
+        &[&admin],
+    );
+    let read = |name| {
+        let ix = idl
+            .ix(name)
+            .account("config", config)
+            .account("caller", admin.pubkey())
+            .build();
+        Case::new(name, tx(svm, ix, &[&admin])).after(&[&initialize])
+    };
+
+    vec![
+        Case::new("initialize_config", initialize.clone()),
+        read("vulnerable_read_config"),
+        read("secure_read_config"),
+    ]
+}
+
+fn pda_security(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("pda_security");
+    let user = funded(svm, "user");
+    let nonce = 7u64;
+    let (weak, _) = crate::common::find_pda(&[user.pubkey().as_ref()], &idl.program_id);
+    let (strong, _) = crate::common::find_pda(
+        &[b"user_v1", user.pubkey().as_ref(), &nonce.to_le_bytes()],
+        &idl.program_id,
+    );
+
+    let create_weak = tx(
+        svm,
+        idl.ix("vulnerable_create_user")
+            .account("user", user.pubkey())
+            .account("user_account", weak)
+            .build(),
+        &[&user],
+    );
+    let create_strong = tx(
+        svm,
+        idl.ix("secure_create_user")
+            .arg("nonce", nonce)
+            .account("user", user.pubkey())
+            .account("user_account", strong)
+            .build(),
+        &[&user],
+    );
+    let update = |name, account| {
+        idl.ix(name)
+            .arg("data", 42u64)
+            .account("user", user.pubkey())
+            .account("user_account", account)
+            .build()
+    };
+
+    vec![
+        Case::new("vulnerable_create_user", create_weak.clone()),
+        Case::new(
+            "vulnerable_update",
+            tx(svm, update("vulnerable_update", weak), &[&user]),
+        )
+        .after(&[&create_weak]),
+        Case::new("secure_create_user", create_strong.clone()),
+        Case::new(
+            "secure_update",
+            tx(svm, update("secure_update", strong), &[&user]),
+        )
+        .after(&[&create_strong]),
+    ]
+}
+
+fn account_close(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("account_close");
+    let owner = funded(svm, "owner");
+    let (user_account, _) =
+        crate::common::find_pda(&[b"user", owner.pubkey().as_ref()], &idl.program_id);
+
+    let ix = |name| {
+        idl.ix(name)
+            .account("owner", owner.pubkey())
+            .account("user_account", user_account)
+            .build()
+    };
+    let initialize = tx(svm, ix("initialize"), &[&owner]);
+    let after_init = |name| Case::new(name, tx(svm, ix(name), &[&owner])).after(&[&initialize]);
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        after_init("vulnerable_close"),
+        after_init("secure_close"),
+        after_init("use_data"),
+    ]
+}
+
+fn account_type_mismatch(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("account_type_mismatch");
+    let user = funded(svm, "user");
+    // User: discriminator (1) + padding (7) + balance (8) + pubkey (32)
+    let fresh = |svm: &mut Scenario, label| {
+        let address = svm.keypair(label).pubkey();
+        program_account(svm, address, idl.program_id, vec![0; 48]);
+        address
+    };
+    let user_account = fresh(svm, "user_account");
+    let admin_account = fresh(svm, "admin_account");
+
+    // The actions read discriminator (1) + pubkey (32) + balance (8), the
+    // layout the suite plants
+    let readable = svm.keypair("readable").pubkey();
+    let mut data = vec![1];
+    data.extend_from_slice(user.pubkey().as_ref());
+    data.extend_from_slice(&100u64.to_le_bytes());
+    program_account(svm, readable, idl.program_id, data);
+
+    let init = |name, account| {
+        let ix = idl
+            .ix(name)
+            .arg("pubkey", user.pubkey())
+            .account("account", account)
+            .build();
+        Case::new(name, tx(svm, ix, &[&user]))
+    };
+    let action = |name| {
+        let ix = idl
+            .ix(name)
+            .account("user_account", readable)
+            .account("caller", user.pubkey())
+            .build();
+        Case::new(name, tx(svm, ix, &[&user]))
+    };
+
+    vec![
+        init("init_user", user_account),
+        init("init_admin", admin_account),
+        action("vulnerable_action"),
+        action("secure_action"),
+    ]
+}
+
+fn duplicate_accounts(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("duplicate_accounts");
+    let alice = funded(svm, "alice");
+    let bob = funded(svm, "bob");
+    let balance = |user: &Keypair| {
+        crate::common::find_pda(&[b"balance", user.pubkey().as_ref()], &idl.program_id).0
+    };
+    let (from, to) = (balance(&alice), balance(&bob));
+
+    let initialize = |user: &Keypair, account| {
+        let ix = idl
+            .ix("initialize")
+            .arg("initial_balance", 1_000u64)
+            .account("user", user.pubkey())
+            .account("user_account", account)
+            .build();
+        tx(svm, ix, &[user])
+    };
+    let (init_alice, init_bob) = (initialize(&alice, from), initialize(&bob, to));
+    let transfer = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("amount", 100u64)
+            .account("authority", alice.pubkey())
+            .account("from_account", from)
+            .account("to_account", to)
+            .build();
+        Case::new(name, tx(svm, ix, &[&alice])).after(&[&init_alice, &init_bob])
+    };
+
+    vec![
+        Case::new("initialize", init_alice.clone()),
+        transfer("vulnerable_transfer"),
+        transfer("secure_transfer"),
+    ]
+}
+
+fn insecure_init(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("insecure_init");
+    let payer = funded(svm, "payer");
+    let config = |seed: &[u8]| crate::common::find_pda(&[seed], &idl.program_id).0;
+    let (config, secure_config) = (config(b"config"), config(b"secure_config"));
+
+    let initialize = |name, config| {
+        let ix = idl
+            .ix(name)
+            .arg("admin", payer.pubkey())
+            .account("payer", payer.pubkey())
+            .account("config", config)
+            .build();
+        tx(svm, ix, &[&payer])
+    };
+    let secure_initialize = initialize("secure_initialize", secure_config);
+    let admin_action = idl
+        .ix("admin_action")
+        .account("config", secure_config)
+        .account("admin", payer.pubkey())
+        .build();
+
+    vec![
+        Case::new(
+            "vulnerable_initialize",
+            initialize("vulnerable_initialize", config),
+        ),
+        Case::new("secure_initialize", secure_initialize.clone()),
+        Case::new("admin_action", tx(svm, admin_action, &[&payer])).after(&[&secure_initialize]),
+    ]
+}
+
+fn account_reloading(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("account_reloading");
+    let authority = funded(svm, "authority");
+    let (counter, _) =
+        crate::common::find_pda(&[b"counter", authority.pubkey().as_ref()], &idl.program_id);
+
+    let ix = |name| {
+        idl.ix(name)
+            .account("authority", authority.pubkey())
+            .account("counter", counter)
+            .build()
+    };
+    let initialize = tx(svm, ix("initialize"), &[&authority]);
+    let after_init = |name| Case::new(name, tx(svm, ix(name), &[&authority])).after(&[&initialize]);
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        after_init("increment"),
+        after_init("vulnerable_double_increment"),
+        after_init("secure_double_increment"),
+    ]
+}
+
+fn remaining_accounts(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("remaining_accounts");
+    let authority = funded(svm, "authority");
+    let (config, _) = crate::common::find_pda(&[b"config"], &idl.program_id);
+
+    // RewardRecipient: discriminator (8) + owner (32) + is_eligible (1) + bump (1)
+    let recipient = svm.keypair("recipient").pubkey();
+    let mut data = idl.account_discriminator("RewardRecipient").to_vec();
+    data.extend_from_slice(authority.pubkey().as_ref());
+    data.extend_from_slice(&[1, 0]);
+    program_account(svm, recipient, idl.program_id, data);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("config", config)
+            .build(),
+        &[&authority],
+    );
+    let reward = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("amounts", vec![100u64])
+            .account("authority", authority.pubkey())
+            .account("config", config)
+            .remaining(AccountMeta::new(recipient, false))
+            .build();
+        Case::new(name, tx(svm, ix, &[&authority])).after(&[&initialize])
+    };
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        reward("vulnerable_batch_reward"),
+        reward("secure_batch_reward"),
+        reward("secure_batch_reward_deserialized"),
+    ]
+}
+
+fn authority_transfer(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("authority_transfer");
+    let authority = funded(svm, "authority");
+    let successor = funded(svm, "successor");
+    let (config, _) = crate::common::find_pda(&[b"config"], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("config", config)
+            .build(),
+        &[&authority],
+    );
+    let hand_over = |name| {
+        idl.ix(name)
+            .arg("new_authority", successor.pubkey())
+            .account("authority", authority.pubkey())
+            .account("config", config)
+            .build()
+    };
+    let propose = tx(svm, hand_over("propose_authority"), &[&authority]);
+    let accept = idl
+        .ix("accept_authority")
+        .account("new_authority", successor.pubkey())
+        .account("config", config)
+        .build();
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new(
+            "vulnerable_transfer",
+            tx(svm, hand_over("vulnerable_transfer"), &[&authority]),
+        )
+        .after(&[&initialize]),
+        Case::new("propose_authority", propose.clone()).after(&[&initialize]),
+        Case::new("accept_authority", tx(svm, accept, &[&successor]))
+            .after(&[&initialize, &propose]),
+    ]
+}
+
+fn account_griefing(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("account_griefing");
+    let user = funded(svm, "user");
+    let nonce = 7u64;
+    let (stake, _) = crate::common::find_pda(&[b"stake", user.pubkey().as_ref()], &idl.program_id);
+    let (secure_stake, _) = crate::common::find_pda(
+        &[b"stake", user.pubkey().as_ref(), &nonce.to_le_bytes()],
+        &idl.program_id,
+    );
+
+    let create = idl
+        .ix("vulnerable_create_stake")
+        .account("user", user.pubkey())
+        .account("stake_account", stake)
+        .build();
+    let secure_create = tx(
+        svm,
+        idl.ix("secure_create_stake")
+            .arg("nonce", nonce)
+            .account("user", user.pubkey())
+            .account("stake_account", secure_stake)
+            .build(),
+        &[&user],
+    );
+
+    // vulnerable_create_stake leaves the data empty, so the deposit runs
+    // against a planted StakeAccount: discriminator (8) + owner (32) +
+    // amount (8) + last_deposit_slot (8) + bump (1) + is_initialized (1)
+    let planted = svm.keypair("planted_user");
+    svm.airdrop(&planted.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .unwrap();
+    let (planted_stake, planted_bump) =
+        crate::common::find_pda(&[b"stake", planted.pubkey().as_ref()], &idl.program_id);
+    let mut data = idl.account_discriminator("StakeAccount").to_vec();
+    data.extend_from_slice(planted.pubkey().as_ref());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&[planted_bump, 1]);
+    program_account(svm, planted_stake, idl.program_id, data);
+
+    // The migrations each rewrite an account planted in the old layout,
+    // without last_deposit_slot, and SecureStakeAccount's at version 1
+    let old = svm.keypair("old_user");
+    let (old_stake, old_bump) =
+        crate::common::find_pda(&[b"stake", old.pubkey().as_ref()], &idl.program_id);
+    let mut data = idl.account_discriminator("StakeAccount").to_vec();
+    data.extend_from_slice(old.pubkey().as_ref());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&[old_bump, 1]);
+    program_account(svm, old_stake, idl.program_id, data);
+    let (old_secure_stake, old_secure_bump) = crate::common::find_pda(
+        &[b"stake", old.pubkey().as_ref(), &nonce.to_le_bytes()],
+        &idl.program_id,
+    );
+    let mut data = idl.account_discriminator("SecureStakeAccount").to_vec();
+    data.push(1);
+    data.extend_from_slice(old.pubkey().as_ref());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&nonce.to_le_bytes());
+    data.extend_from_slice(&[old_secure_bump, 1]);
+    program_account(svm, old_secure_stake, idl.program_id, data);
+
+    let deposit = |name, user: &Keypair, account| {
+        let ix = idl
+            .ix(name)
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("user", user.pubkey())
+            .account("stake_account", account)
+            .build();
+        tx(svm, ix, &[user])
+    };
+    let migrate = |name, account| {
+        let ix = idl
+            .ix(name)
+            .account("payer", user.pubkey())
+            .account("stake_account", account)
+            .build();
+        tx(svm, ix, &[&user])
+    };
+    let (combined_stake, _) = crate::common::find_pda(
+        &[b"stake", user.pubkey().as_ref(), &(nonce + 1).to_le_bytes()],
+        &idl.program_id,
+    );
+    let create_and_deposit = idl
+        .ix("create_and_deposit_stake")
+        .arg("nonce", nonce + 1)
+        .arg("amount", LAMPORTS_PER_SOL)
+        .account("user", user.pubkey())
+        .account("stake_account", combined_stake)
+        .build();
+
+    vec![
+        Case::new("vulnerable_create_stake", tx(svm, create, &[&user])),
+        Case::new(
+            "vulnerable_deposit",
+            deposit("vulnerable_deposit", &planted, planted_stake),
+        ),
+        Case::new(
+            "vulnerable_migrate_stake",
+            migrate("vulnerable_migrate_stake", old_stake),
+        ),
+        Case::new("secure_create_stake", secure_create.clone()),
+        Case::new(
+            "secure_deposit",
+            deposit("secure_deposit", &user, secure_stake),
+        )
+        .after(&[&secure_create]),
+        Case::new(
+            "secure_migrate_stake",
+            migrate("secure_migrate_stake", old_secure_stake),
+        ),
+        Case::new(
+            "create_and_deposit_stake",
+            tx(svm, create_and_deposit, &[&user]),
+        ),
+    ]
+}
+
+fn multisig_payer(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("multisig_payer");
+    let creator = funded(svm, "creator");
+    let rent_payer = funded(svm, "rent_payer");
+    let (config, _) = crate::common::find_pda(&[b"dao_config"], &idl.program_id);
+    let (treasury, _) = crate::common::find_pda(&[b"treasury", config.as_ref()], &idl.program_id);
+    let proposal_id = 1u64;
+    let (proposal, _) = crate::common::find_pda(
+        &[b"proposal", config.as_ref(), &proposal_id.to_le_bytes()],
+        &idl.program_id,
+    );
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("creator", creator.pubkey())
+            .account("config", config)
+            .account("treasury", treasury)
+            .build(),
+        &[&creator],
+    );
+    let create = |name| {
+        idl.ix(name)
+            .arg("proposal_id", proposal_id)
+            .arg("title", "Bench Proposal")
+            .account("creator", creator.pubkey())
+            .account("treasury", treasury)
+            .account("config", config)
+            .account("proposal", proposal)
+    };
+    let secure_create = tx(
+        svm,
+        create("secure_create_proposal")
+            .account("rent_payer", rent_payer.pubkey())
+            .build(),
+        &[&rent_payer, &creator],
+    );
+    let vote = idl
+        .ix("vote")
+        .arg("approve", true)
+        .account("voter", creator.pubkey())
+        .account("proposal", proposal)
+        .build();
+
+    // Borsh and zero-copy logs, so the table shows what each append costs
+    let init_log = |name, seed: &[u8]| {
+        let (log, _) = crate::common::find_pda(&[seed, config.as_ref()], &idl.program_id);
+        let ix = idl
+            .ix(name)
+            .account("payer", creator.pubkey())
+            .account("config", config)
+            .account("log", log)
+            .build();
+        (log, ix)
+    };
+    let (borsh_log, init_borsh) = init_log("init_proposal_log", b"proposal_log");
+    let (zero_copy_log, init_zero_copy) = init_log("init_zero_copy_log", b"zero_copy_log");
+    let init_borsh = tx(svm, init_borsh, &[&creator]);
+    let init_zero_copy = tx(svm, init_zero_copy, &[&creator]);
+    let log = |name, log| {
+        idl.ix(name)
+            .account("config", config)
+            .account("proposal", proposal)
+            .account("log", log)
+            .build()
+    };
+    let log_borsh = tx(svm, log("log_proposal", borsh_log), &[&creator]);
+    let log_zero_copy = tx(
+        svm,
+        log("log_proposal_zero_copy", zero_copy_log),
+        &[&creator],
+    );
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        // The treasury PDA is the payer, and a PDA can't sign the transfer
+        Case::new(
+            "vulnerable_create_proposal",
+            tx(
+                svm,
+                create("vulnerable_create_proposal").build(),
+                &[&creator],
+            ),
+        )
+        .after(&[&initialize])
+        .fails(),
+        Case::new("secure_create_proposal", secure_create.clone()).after(&[&initialize]),
+        Case::new("vote", tx(svm, vote, &[&creator])).after(&[&initialize, &secure_create]),
+        Case::new("init_proposal_log", init_borsh.clone()).after(&[&initialize]),
+        Case::new("init_zero_copy_log", init_zero_copy.clone()).after(&[&initialize]),
+        Case::new("log_proposal", log_borsh).after(&[&initialize, &secure_create, &init_borsh]),
+        Case::new("log_proposal_zero_copy", log_zero_copy).after(&[
+            &initialize,
+            &secure_create,
+            &init_zero_copy,
+        ]),
+    ]
+}
+
+fn lookup_table_pitfalls(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("lookup_table_pitfalls");
+    let authority = funded(svm, "authority");
+    let (treasury, _) = crate::common::find_pda(&[b"treasury"], &idl.program_id);
+    let (payout, _) = crate::common::find_pda(&[b"payout", &1u64.to_le_bytes()], &idl.program_id);
+    svm.airdrop(&treasury, 10 * LAMPORTS_PER_SOL).unwrap();
+
+    // A frozen table of one recipient, so both approvals succeed
+    let recipient = svm.keypair("recipient").pubkey();
+    let table = svm.create_lookup_table(&authority, &[recipient]).key;
+    svm.freeze_lookup_table(&table, &authority);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("treasury", treasury)
+            .build(),
+        &[&authority],
+    );
+    let propose = tx(
+        svm,
+        idl.ix("propose_payout")
+            .arg("id", 1u64)
+            .arg("table", table)
+            .arg("indexes", vec![0u8])
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("proposer", authority.pubkey())
+            .account("payout", payout)
+            .build(),
+        &[&authority],
+    );
+    let approve = |name, with_table: bool| {
+        let ix = idl
+            .ix(name)
+            .account("authority", authority.pubkey())
+            .account("treasury", treasury)
+            .account("payout", payout);
+        let ix = if with_table {
+            ix.account("table", table)
+        } else {
+            ix
+        };
+        tx(svm, ix.build(), &[&authority])
+    };
+    let execute = |name, with_table: bool| {
+        let ix = idl
+            .ix(name)
+            .account("executor", authority.pubkey())
+            .account("treasury", treasury)
+            .account("payout", payout);
+        let ix = if with_table {
+            ix.account("table", table)
+        } else {
+            ix
+        };
+        let ix = ix.remaining(AccountMeta::new(recipient, false)).build();
+        tx(svm, ix, &[&authority])
+    };
+    let vulnerable_approve = approve("vulnerable_approve", false);
+    let secure_approve = approve("secure_approve", true);
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new("propose_payout", propose.clone()).after(&[&initialize]),
+        Case::new("vulnerable_approve", vulnerable_approve.clone()).after(&[&initialize, &propose]),
+        Case::new("secure_approve", secure_approve.clone()).after(&[&initialize, &propose]),
+        Case::new("vulnerable_execute", execute("vulnerable_execute", true)).after(&[
+            &initialize,
+            &propose,
+            &vulnerable_approve,
+        ]),
+        Case::new("secure_execute", execute("secure_execute", false)).after(&[
+            &initialize,
+            &propose,
+            &secure_approve,
+        ]),
+    ]
+}
+
+fn durable_nonce(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("durable_nonce");
+    let owner = funded(svm, "owner");
+    let destination = svm.keypair("destination").pubkey();
+    let (vault, _) = crate::common::find_pda(&[b"vault", owner.pubkey().as_ref()], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("owner", owner.pubkey())
+            .account("vault", vault)
+            .build(),
+        &[&owner],
+    );
+    let fund = tx(
+        svm,
+        system_instruction::transfer(&owner.pubkey(), &vault, 2 * LAMPORTS_PER_SOL),
+        &[&owner],
+    );
+    let vulnerable_withdraw = idl
+        .ix("vulnerable_withdraw")
+        .arg("amount", LAMPORTS_PER_SOL)
+        .account("vault", vault)
+        .account("owner", owner.pubkey())
+        .account("destination", destination)
+        .build();
+    let secure_withdraw = idl
+        .ix("secure_withdraw")
+        .arg("amount", LAMPORTS_PER_SOL)
+        .arg("sequence", 0u64)
+        .arg("expires_at", u64::MAX)
+        .account("vault", vault)
+        .account("owner", owner.pubkey())
+        .account("destination", destination)
+        .build();
+    let secure_revoke = idl
+        .ix("secure_revoke")
+        .arg("sequence", 0u64)
+        .account("vault", vault)
+        .account("owner", owner.pubkey())
+        .build();
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new(
+            "vulnerable_withdraw",
+            tx(svm, vulnerable_withdraw, &[&owner]),
+        )
+        .after(&[&initialize, &fund]),
+        Case::new("secure_withdraw", tx(svm, secure_withdraw, &[&owner]))
+            .after(&[&initialize, &fund]),
+        Case::new("secure_revoke", tx(svm, secure_revoke, &[&owner])).after(&[&initialize]),
+    ]
+}
+
+fn fee_rounding(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("fee_rounding");
+    let authority = funded(svm, "authority");
+    let (pool, _) = crate::common::find_pda(&[b"pool"], &idl.program_id);
+    let (position, _) =
+        crate::common::find_pda(&[b"position", authority.pubkey().as_ref()], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .arg("fee_bps", 30u16)
+            .account("authority", authority.pubkey())
+            .account("pool", pool)
+            .build(),
+        &[&authority],
+    );
+    let deposit = tx(
+        svm,
+        idl.ix("deposit")
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("owner", authority.pubkey())
+            .account("pool", pool)
+            .account("position", position)
+            .build(),
+        &[&authority],
+    );
+    let withdraw = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("amount", 1_000_000u64)
+            .account("owner", authority.pubkey())
+            .account("pool", pool)
+            .account("position", position)
+            .build();
+        tx(svm, ix, &[&authority])
+    };
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new("deposit", deposit.clone()).after(&[&initialize]),
+        Case::new("vulnerable_withdraw", withdraw("vulnerable_withdraw"))
+            .after(&[&initialize, &deposit]),
+        Case::new("secure_withdraw", withdraw("secure_withdraw")).after(&[&initialize, &deposit]),
+    ]
+}
+
+fn order_book(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("order_book");
+    let authority = funded(svm, "authority");
+    let (book, _) = crate::common::find_pda(&[b"book"], &idl.program_id);
+    let order = |sequence: u64| {
+        crate::common::find_pda(&[b"order", &sequence.to_le_bytes()], &idl.program_id).0
+    };
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("book", book)
+            .build(),
+        &[&authority],
+    );
+    let place = |side: &str, sequence| {
+        let ix = idl
+            .ix("place_order")
+            .arg("side", ArgValue::Enum(side.to_string(), vec![]))
+            .arg("price", 100_000u64)
+            .arg("quantity", 10u64)
+            .account("owner", authority.pubkey())
+            .account("book", book)
+            .account("order", order(sequence))
+            .build();
+        tx(svm, ix, &[&authority])
+    };
+    let bid = place("Bid", 0);
+    let ask = place("Ask", 1);
+    let match_orders = |name| {
+        let ix = idl
+            .ix(name)
+            .account("matcher", authority.pubkey())
+            .account("book", book)
+            .account("bid", order(0))
+            .account("ask", order(1))
+            .account("seller", authority.pubkey())
+            .build();
+        tx(svm, ix, &[&authority])
+    };
+
+    let vulnerable = match_orders("vulnerable_match_orders");
+    let secure = match_orders("secure_match_orders");
+
+    let crossed = [&initialize, &bid, &ask];
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new("place_order", bid.clone()).after(&[&initialize]),
+        Case::new("vulnerable_match_orders", vulnerable).after(&crossed),
+        Case::new("secure_match_orders", secure).after(&crossed),
+    ]
+}
+
+fn seed_liquidity(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("seed_liquidity");
+    let creator = funded(svm, "creator");
+    let (launch, _) =
+        crate::common::find_pda(&[b"launch", creator.pubkey().as_ref()], &idl.program_id);
+    let (holding, _) = crate::common::find_pda(
+        &[b"holding", launch.as_ref(), creator.pubkey().as_ref()],
+        &idl.program_id,
+    );
+
+    let initialize = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("sol_amount", LAMPORTS_PER_SOL)
+            .arg("token_amount", 1_000_000_000u64)
+            .account("creator", creator.pubkey())
+            .account("launch", launch)
+            .build();
+        tx(svm, ix, &[&creator])
+    };
+    let vulnerable_initialize = initialize("vulnerable_initialize");
+    let secure_initialize = initialize("secure_initialize");
+    let open = tx(
+        svm,
+        idl.ix("open_holding")
+            .account("owner", creator.pubkey())
+            .account("launch", launch)
+            .account("holding", holding)
+            .build(),
+        &[&creator],
+    );
+    let buy = tx(
+        svm,
+        idl.ix("buy")
+            .arg("sol_in", 1_000_000u64)
+            .arg("min_tokens_out", 1u64)
+            .account("buyer", creator.pubkey())
+            .account("launch", launch)
+            .account("holding", holding)
+            .build(),
+        &[&creator],
+    );
+    let withdraw = tx(
+        svm,
+        idl.ix("vulnerable_withdraw_reserve")
+            .arg("amount", 1_000_000u64)
+            .account("creator", creator.pubkey())
+            .account("launch", launch)
+            .build(),
+        &[&creator],
+    );
+    let remove = tx(
+        svm,
+        idl.ix("secure_remove_liquidity")
+            .arg("shares", 1_000_000u64)
+            .account("creator", creator.pubkey())
+            .account("launch", launch)
+            .account("holding", holding)
+            .build(),
+        &[&creator],
+    );
+
+    vec![
+        Case::new("vulnerable_initialize", vulnerable_initialize.clone()),
+        Case::new("secure_initialize", secure_initialize.clone()),
+        Case::new("open_holding", open.clone()).after(&[&secure_initialize]),
+        Case::new("buy", buy).after(&[&secure_initialize, &open]),
+        Case::new("vulnerable_withdraw_reserve", withdraw).after(&[&vulnerable_initialize]),
+        // The shares stay locked for a day of slots after the launch
+        Case::new("secure_remove_liquidity", remove)
+            .after(&[&secure_initialize, &open])
+            .fails(),
+    ]
+}
+
+fn insurance_fund(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("insurance_fund");
+    let adjuster = funded(svm, "adjuster");
+    let (fund, _) = crate::common::find_pda(&[b"fund"], &idl.program_id);
+    let (claim, _) = crate::common::find_pda(
+        &[b"claim", adjuster.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &idl.program_id,
+    );
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("adjuster", adjuster.pubkey())
+            .account("fund", fund)
+            .build(),
+        &[&adjuster],
+    );
+    let cover = tx(
+        svm,
+        system_instruction::transfer(&adjuster.pubkey(), &fund, 2 * LAMPORTS_PER_SOL),
+        &[&adjuster],
+    );
+    let file = tx(
+        svm,
+        idl.ix("file_claim")
+            .arg("id", 0u64)
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("claimant", adjuster.pubkey())
+            .account("claim", claim)
+            .build(),
+        &[&adjuster],
+    );
+    let approve = tx(
+        svm,
+        idl.ix("approve_claim")
+            .account("adjuster", adjuster.pubkey())
+            .account("fund", fund)
+            .account("claim", claim)
+            .build(),
+        &[&adjuster],
+    );
+    let pay = |name| {
+        let ix = idl
+            .ix(name)
+            .account("caller", adjuster.pubkey())
+            .account("fund", fund)
+            .account("claim", claim)
+            .account("recipient", adjuster.pubkey())
+            .build();
+        tx(svm, ix, &[&adjuster])
+    };
+    let approved = [&initialize, &cover, &file, &approve];
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new("file_claim", file.clone()).after(&[&initialize]),
+        Case::new("approve_claim", approve.clone()).after(&[&initialize, &file]),
+        Case::new("vulnerable_pay_claim", pay("vulnerable_pay_claim")).after(&approved),
+        Case::new("secure_pay_claim", pay("secure_pay_claim")).after(&approved),
+    ]
+}
+
+fn partner_mint(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("partner_mint");
+    let authority = funded(svm, "authority");
+    // The vulnerable mint checks the partner's account is executable
+    let partner = svm.add_program("partner_rewards");
+    let (config, _) = crate::common::find_pda(&[b"config"], &idl.program_id);
+    let (balance, _) =
+        crate::common::find_pda(&[b"balance", authority.pubkey().as_ref()], &idl.program_id);
+    let (partner_authority, _) = crate::common::find_pda(&[b"mint_authority"], &partner);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .arg("partner_program", partner)
+            .account("authority", authority.pubkey())
+            .account("config", config)
+            .build(),
+        &[&authority],
+    );
+    let open = tx(
+        svm,
+        idl.ix("open_balance")
+            .account("owner", authority.pubkey())
+            .account("balance", balance)
+            .build(),
+        &[&authority],
+    );
+    let vulnerable = tx(
+        svm,
+        idl.ix("vulnerable_mint")
+            .arg("amount", 100u64)
+            .account("config", config)
+            .account("caller_program", partner)
+            .account("balance", balance)
+            .build(),
+        &[&authority],
+    );
+    let secure = tx(
+        svm,
+        idl.ix("secure_mint")
+            .arg("amount", 100u64)
+            .account("config", config)
+            .account_meta(
+                "partner_authority",
+                AccountMeta::new_readonly(partner_authority, false),
+            )
+            .account("balance", balance)
+            .build(),
+        &[&authority],
+    );
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new("open_balance", open.clone()).after(&[&initialize]),
+        Case::new("vulnerable_mint", vulnerable).after(&[&initialize, &open]),
+        // Only the partner's CPI can sign for its PDA
+        Case::new("secure_mint", secure)
+            .after(&[&initialize, &open])
+            .fails(),
+    ]
+}
+
+fn native_signer_authorization(svm: &mut Scenario) -> Vec<Case> {
+    vault_port(svm, "native_signer_authorization")
+}
+
+fn p_signer_authorization(svm: &mut Scenario) -> Vec<Case> {
+    vault_port(svm, "p_signer_authorization")
+}
+
+/// The native and Pinocchio signer-authorization, which share their IDL.
+fn vault_port(svm: &mut Scenario, deploy: &str) -> Vec<Case> {
+    let idl = Idl::load(deploy);
+    let authority = funded(svm, "authority");
+    let destination = svm.keypair("destination").pubkey();
+    let (vault, _) =
+        crate::common::find_pda(&[b"vault", authority.pubkey().as_ref()], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("authority", authority.pubkey())
+            .account("vault", vault)
+            .build(),
+        &[&authority],
+    );
+    // Unlike Anchor's, this deposit moves the lamports with a CPI
+    let deposit = tx(
+        svm,
+        idl.ix("deposit")
+            .arg("amount", 2 * LAMPORTS_PER_SOL)
+            .account("authority", authority.pubkey())
+            .account("vault", vault)
+            .build(),
+        &[&authority],
+    );
+    let withdraw = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("amount", LAMPORTS_PER_SOL)
+            .account("vault", vault)
+            .account("authority", authority.pubkey())
+            .account("destination", destination)
+            .build();
+        Case::new(name, tx(svm, ix, &[&authority])).after(&[&initialize, &deposit])
+    };
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        Case::new("deposit", deposit.clone()).after(&[&initialize]),
+        withdraw("vulnerable_withdraw"),
+        withdraw("secure_withdraw"),
+    ]
+}
+
+fn native_arithmetic_overflow(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("native_arithmetic_overflow");
+    let authority = funded(svm, "authority");
+    let (pool, _) =
+        crate::common::find_pda(&[b"pool", authority.pubkey().as_ref()], &idl.program_id);
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .arg("initial_x", 1_000_000u64)
+            .arg("initial_y", 1_000_000u64)
+            .arg("fee_bps", 30u16)
+            .account("authority", authority.pubkey())
+            .account("pool", pool)
+            .build(),
+        &[&authority],
+    );
+    let swap = |name| {
+        let ix = idl
+            .ix(name)
+            .arg("amount_in", 1_000u64)
+            .arg("min_out", 1u64)
+            .account("user", authority.pubkey())
+            .account("pool", pool)
+            .build();
+        Case::new(name, tx(svm, ix, &[&authority])).after(&[&initialize])
+    };
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        swap("vulnerable_swap"),
+        swap("secure_swap"),
+    ]
+}
+
+fn native_account_griefing(svm: &mut Scenario) -> Vec<Case> {
+    stake_port(svm, "native_account_griefing")
+}
+
+fn p_account_griefing(svm: &mut Scenario) -> Vec<Case> {
+    stake_port(svm, "p_account_griefing")
+}
+
+/// The native and Pinocchio account-griefing, which share their IDL.
+fn stake_port(svm: &mut Scenario, deploy: &str) -> Vec<Case> {
+    let idl = Idl::load(deploy);
+    let user = funded(svm, "user");
+    let nonce = 7u64;
+    let (stake, _) = crate::common::find_pda(&[b"stake", user.pubkey().as_ref()], &idl.program_id);
+    let (secure_stake, _) = crate::common::find_pda(
+        &[b"stake", user.pubkey().as_ref(), &nonce.to_le_bytes()],
+        &idl.program_id,
+    );
+
+    let create = idl
+        .ix("vulnerable_create_stake")
+        .account("user", user.pubkey())
+        .account("stake_account", stake)
+        .build();
+    let secure_create = tx(
+        svm,
+        idl.ix("secure_create_stake")
+            .arg("nonce", nonce)
+            .account("user", user.pubkey())
+            .account("stake_account", secure_stake)
+            .build(),
+        &[&user],
+    );
+    let deposit = idl
+        .ix("secure_deposit")
+        .arg("amount", LAMPORTS_PER_SOL)
+        .account("user", user.pubkey())
+        .account("stake_account", secure_stake)
+        .build();
+
+    vec![
+        Case::new("vulnerable_create_stake", tx(svm, create, &[&user])),
+        Case::new("secure_create_stake", secure_create.clone()),
+        Case::new("secure_deposit", tx(svm, deposit, &[&user])).after(&[&secure_create]),
+    ]
+}
+
+fn native_multisig_payer(svm: &mut Scenario) -> Vec<Case> {
+    let idl = Idl::load("native_multisig_payer");
+    let creator = funded(svm, "creator");
+    let rent_payer = funded(svm, "rent_payer");
+    let (config, _) = crate::common::find_pda(&[b"dao_config"], &idl.program_id);
+    let (treasury, _) = crate::common::find_pda(&[b"treasury", config.as_ref()], &idl.program_id);
+    let proposal_id = 1u64;
+    let (proposal, _) = crate::common::find_pda(
+        &[b"proposal", config.as_ref(), &proposal_id.to_le_bytes()],
+        &idl.program_id,
+    );
+
+    let initialize = tx(
+        svm,
+        idl.ix("initialize")
+            .account("creator", creator.pubkey())
+            .account("config", config)
+            .account("treasury", treasury)
+            .build(),
+        &[&creator],
+    );
+    let create = |name| {
+        idl.ix(name)
+            .arg("proposal_id", proposal_id)
+            .arg("title", "Bench Proposal")
+            .account("creator", creator.pubkey())
+            .account("treasury", treasury)
+            .account("config", config)
+            .account("proposal", proposal)
+    };
+    let secure_create = create("secure_create_proposal")
+        .account("rent_payer", rent_payer.pubkey())
+        .build();
+
+    vec![
+        Case::new("initialize", initialize.clone()),
+        // As in Anchor: the treasury PDA never signs the transfer
+        Case::new(
+            "vulnerable_create_proposal",
+            tx(
+                svm,
+                create("vulnerable_create_proposal").build(),
+                &[&creator],
+            ),
+        )
+        .after(&[&initialize])
+        .fails(),
+        Case::new(
+            "secure_create_proposal",
+            tx(svm, secure_create, &[&rent_payer, &creator]),
+        )
+        .after(&[&initialize]),
+    ]
+}
@@ -0,0 +1,31 @@
+//! Run the compute-unit benches and print the samples as JSON
+//!
+//! Usage:
+//!   cargo run --bin bench-cu -- [PROGRAM]...
+//!
+//! PROGRAM is a directory under `programs/`; without one every bench runs.
+//! `cargo xtask bench-cu` reads the output, keeps the history and compares.
+
+use security_tests::bench::{self, Bench, BENCHES};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let selected: Vec<&Bench> = if args.is_empty() {
+        BENCHES.iter().collect()
+    } else {
+        args.iter()
+            .map(|program| {
+                bench::find(program).unwrap_or_else(|| {
+                    eprintln!("No bench for {}; benched programs:", program);
+                    for b in BENCHES {
+                        eprintln!("  {}", b.program);
+                    }
+                    std::process::exit(2);
+                })
+            })
+            .collect()
+    };
+
+    let samples: Vec<_> = selected.into_iter().flat_map(bench::run).collect();
+    println!("{}", serde_json::to_string_pretty(&samples).unwrap());
+}
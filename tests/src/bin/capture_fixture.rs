@@ -0,0 +1,75 @@
+//! Capture a cluster account into `tests/fixtures/`
+//!
+//! Usage:
+//!   cargo run --bin capture-fixture -- <ADDRESS> [--url <RPC_URL>] [--name <NAME>] [--bin]
+//!
+//! Defaults to mainnet-beta and writes `<NAME>.json` (or `.bin` with `--bin`),
+//! where NAME defaults to the address itself.
+
+use security_tests::fixtures::{fixtures_dir, AccountFixture};
+
+const DEFAULT_URL: &str = "https://api.mainnet-beta.solana.com";
+
+fn usage() -> ! {
+    eprintln!("Usage: capture-fixture <ADDRESS> [--url <RPC_URL>] [--name <NAME>] [--bin]");
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut address = None;
+    let mut url = DEFAULT_URL.to_string();
+    let mut name = None;
+    let mut bincode = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => url = args.next().unwrap_or_else(|| usage()),
+            "--name" => name = Some(args.next().unwrap_or_else(|| usage())),
+            "--bin" => bincode = true,
+            "-h" | "--help" => usage(),
+            _ if address.is_none() => address = Some(arg),
+            _ => usage(),
+        }
+    }
+    let address = address.unwrap_or_else(|| usage());
+
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getAccountInfo",
+        "params": [address, { "encoding": "base64" }],
+    });
+
+    let response: serde_json::Value = ureq::post(&url)
+        .send_json(request)
+        .and_then(|r| r.into_json().map_err(Into::into))
+        .unwrap_or_else(|e| panic!("RPC request to {} failed: {}", url, e));
+
+    if let Some(err) = response.get("error") {
+        panic!("RPC error: {}", err);
+    }
+    let value = &response["result"]["value"];
+    if value.is_null() {
+        panic!("Account {} does not exist on {}", address, url);
+    }
+
+    // The RPC account object has the same shape as the fixture JSON
+    let wrapped = serde_json::json!({ "pubkey": address, "account": value });
+    let fixture = AccountFixture::from_json(wrapped.to_string().as_bytes())
+        .unwrap_or_else(|e| panic!("Unexpected RPC response: {}", e));
+
+    let ext = if bincode { "bin" } else { "json" };
+    let path = fixtures_dir().join(format!("{}.{}", name.unwrap_or(address), ext));
+    fixture
+        .write(&path)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+
+    println!(
+        "Captured {} ({} bytes, owner {}) -> {}",
+        fixture.address,
+        fixture.account.data.len(),
+        fixture.account.owner,
+        path.display()
+    );
+}
@@ -0,0 +1,67 @@
+//! Write each demo's exploit transactions and pre-state to disk
+//!
+//! Usage:
+//!   cargo run --bin export-exploits -- [--out DIR] [PROGRAM]...
+//!
+//! Each program gets `<DIR>/<program>/` (default `target/exports/`), laid
+//! out as `security_tests::export` describes. Without PROGRAM every demo is
+//! exported.
+
+use std::path::PathBuf;
+
+use security_tests::demo::{self, DEMOS};
+use security_tests::export::{self, exports_dir};
+
+fn usage() -> ! {
+    eprintln!("Usage: export-exploits [--out DIR] [PROGRAM]...");
+    eprintln!();
+    eprintln!("Programs:");
+    for demo in DEMOS {
+        eprintln!("  {:<22} {}", demo.program, demo.vulnerability().title);
+    }
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut out = exports_dir();
+    let mut programs = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = PathBuf::from(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => usage(),
+            flag if flag.starts_with("--") => usage(),
+            _ => programs.push(arg),
+        }
+    }
+
+    let demos: Vec<_> = if programs.is_empty() {
+        DEMOS.iter().collect()
+    } else {
+        programs
+            .iter()
+            .map(|program| {
+                demo::find(program).unwrap_or_else(|| {
+                    eprintln!("No demo for {}", program);
+                    usage()
+                })
+            })
+            .collect()
+    };
+
+    for demo in demos {
+        let dir = out.join(demo.program);
+        let bundle = export::export(demo, &dir).unwrap_or_else(|e| {
+            eprintln!("{}: {}", demo.program, e);
+            std::process::exit(1);
+        });
+        let transactions: usize = bundle.sides.iter().map(|s| s.transactions.len()).sum();
+        println!(
+            "{}: {} transactions, {} accounts -> {}",
+            demo.program,
+            transactions,
+            bundle.accounts.len(),
+            dir.display()
+        );
+    }
+}
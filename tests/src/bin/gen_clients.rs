@@ -0,0 +1,78 @@
+//! Generate the typed client crates in `clients/`
+//!
+//! Usage:
+//!   cargo run --bin gen-clients -- [--check] [--out DIR] [PROGRAM]...
+//!
+//! Writes `<program>/` for each program with an IDL on disk (or the ones
+//! named), as `security_tests::client` describes. `--check` writes nothing
+//! and exits non-zero if any crate on disk is out of date.
+
+use std::path::PathBuf;
+
+use security_tests::client::{self, clients_dir};
+
+fn usage() -> ! {
+    eprintln!("Usage: gen-clients [--check] [--out DIR] [PROGRAM]...");
+    eprintln!();
+    eprintln!("Programs with an IDL:");
+    for (program, idl) in client::idls() {
+        eprintln!("  {:<22} {}", program, idl.display());
+    }
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut check = false;
+    let mut out = clients_dir();
+    let mut programs = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--out" => out = PathBuf::from(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => usage(),
+            flag if flag.starts_with("--") => usage(),
+            _ => programs.push(arg.replace('-', "_")),
+        }
+    }
+
+    let idls = client::idls();
+    for program in &programs {
+        if !idls.iter().any(|(name, _)| name == program) {
+            eprintln!("no IDL for {}; run anchor build", program);
+            usage();
+        }
+    }
+
+    let clients: Vec<_> = idls
+        .iter()
+        .filter(|(name, _)| programs.is_empty() || programs.contains(name))
+        .map(|(_, path)| {
+            let idl = std::fs::read_to_string(path)
+                .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            client::generate(&idl).unwrap_or_else(|e| {
+                eprintln!("{}: {}", path.display(), e);
+                std::process::exit(1);
+            })
+        })
+        .collect();
+
+    if check {
+        let stale = client::stale(&out, &clients);
+        if !stale.is_empty() {
+            eprintln!(
+                "out of date in {}: {}; run cargo xtask clients",
+                out.display(),
+                stale.join(", ")
+            );
+            std::process::exit(1);
+        }
+        eprintln!("{} clients up to date", clients.len());
+        return;
+    }
+    if let Err(e) = client::write(&out, &clients) {
+        eprintln!("{}: {}", out.display(), e);
+        std::process::exit(1);
+    }
+    eprintln!("{} clients written to {}", clients.len(), out.display());
+}
@@ -0,0 +1,78 @@
+//! Generate the vulnerability writeups in `generated-docs/`
+//!
+//! Usage:
+//!   cargo run --bin gen-docs -- [--check] [--out DIR] [PROGRAM]...
+//!
+//! Writes `<program>.md` for each catalogued program (or the ones named) and
+//! a `README.md` index, as `security_tests::docs` describes. `--check`
+//! writes nothing and exits non-zero if any page on disk is out of date.
+
+use std::path::PathBuf;
+
+use security_tests::docs::{self, docs_dir};
+use solsec_registry::CATALOG;
+
+fn usage() -> ! {
+    eprintln!("Usage: gen-docs [--check] [--out DIR] [PROGRAM]...");
+    eprintln!();
+    eprintln!("Programs:");
+    for v in CATALOG {
+        eprintln!("  {:<22} {}", v.program, v.title);
+    }
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut check = false;
+    let mut out = docs_dir();
+    let mut programs = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--out" => out = PathBuf::from(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => usage(),
+            flag if flag.starts_with("--") => usage(),
+            _ => programs.push(arg),
+        }
+    }
+
+    let selected: Vec<_> = if programs.is_empty() {
+        CATALOG.iter().collect()
+    } else {
+        programs
+            .iter()
+            .map(|program| {
+                solsec_registry::find(program).unwrap_or_else(|| {
+                    eprintln!("unknown program {}", program);
+                    usage()
+                })
+            })
+            .collect()
+    };
+
+    let mut pages: Vec<(String, String)> = selected
+        .into_iter()
+        .map(|v| (format!("{}.md", v.program), docs::writeup(v)))
+        .collect();
+    pages.push(("README.md".to_string(), docs::index()));
+
+    if check {
+        let stale = docs::stale(&out, &pages);
+        if !stale.is_empty() {
+            eprintln!(
+                "out of date in {}: {}; run cargo xtask docs",
+                out.display(),
+                stale.join(", ")
+            );
+            std::process::exit(1);
+        }
+        eprintln!("{} pages up to date", pages.len());
+        return;
+    }
+    if let Err(e) = docs::write(&out, &pages) {
+        eprintln!("{}: {}", out.display(), e);
+        std::process::exit(1);
+    }
+    eprintln!("{} pages written to {}", pages.len(), out.display());
+}
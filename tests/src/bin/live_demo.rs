@@ -0,0 +1,216 @@
+//! Play the exploit demos on a real validator and compare them with LiteSVM
+//!
+//! Usage:
+//!   cargo run --bin live-demo -- [--cluster local|devnet|URL] [--keypair FILE]
+//!       [--port PORT] [--seed N] [--close-programs] [PROGRAM]...
+//!
+//! `local` (the default) starts a `solana-test-validator` in
+//! `target/live-demo/` and stops it afterwards. Any other cluster is paid
+//! for by `--keypair` (default `~/.config/solana/id.json`): the programs are
+//! deployed there with the `solana` CLI and the demo keypairs are swept back
+//! to it afterwards; `--close-programs` closes the programs too, after which
+//! their addresses can't be deployed to again. Each program's deployed
+//! bytecode is checked against its `.so` before any demo runs. Without
+//! PROGRAM every demo runs. Exits non-zero when any demo differs from
+//! LiteSVM.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use security_tests::demo::{self, Palette, DEMOS};
+use security_tests::live::{self, Cluster, Rehearsal};
+
+const DEVNET_URL: &str = "https://api.devnet.solana.com";
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: live-demo [--cluster local|devnet|URL] [--keypair FILE] [--port PORT] \
+         [--seed N] [--close-programs] [PROGRAM]..."
+    );
+    eprintln!();
+    eprintln!("Programs:");
+    for demo in DEMOS {
+        eprintln!("  {:<22} {}", demo.program, demo.vulnerability().title);
+    }
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut cluster = "local".to_string();
+    let mut keypair = None;
+    let mut port = 8899u16;
+    let mut seed = None;
+    let mut close_programs = false;
+    let mut programs = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cluster" => cluster = args.next().unwrap_or_else(|| usage()),
+            "--keypair" => keypair = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--port" => {
+                port = args
+                    .next()
+                    .and_then(|port| port.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--seed" => {
+                seed = Some(
+                    args.next()
+                        .and_then(|seed| seed.parse().ok())
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--close-programs" => close_programs = true,
+            "-h" | "--help" => usage(),
+            flag if flag.starts_with("--") => usage(),
+            _ => programs.push(arg),
+        }
+    }
+
+    let demos: Vec<_> = if programs.is_empty() {
+        DEMOS.iter().collect()
+    } else {
+        programs
+            .iter()
+            .map(|program| {
+                demo::find(program).unwrap_or_else(|| {
+                    eprintln!("No demo for {}", program);
+                    usage()
+                })
+            })
+            .collect()
+    };
+
+    let local = cluster == "local";
+    // A remote cluster keeps what earlier runs left behind, so each run
+    // gets fresh keypairs unless told otherwise
+    let seed = seed.unwrap_or_else(|| {
+        if local {
+            1
+        } else {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        }
+    });
+    eprintln!("seed {}", seed);
+
+    let mut rehearsals: Vec<Rehearsal> = demos
+        .into_iter()
+        .map(|demo| Rehearsal::new(demo, seed))
+        .collect();
+
+    let cluster = if local {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target/live-demo");
+        eprintln!("starting solana-test-validator on port {}", port);
+        Cluster::local(&dir, port, &rehearsals)
+    } else {
+        rehearsals.retain(|rehearsal| {
+            let planted = rehearsal.planted().is_empty();
+            if !planted {
+                eprintln!(
+                    "skipping {}: its setup plants accounts, which only a local validator can load",
+                    rehearsal.demo.program
+                );
+            }
+            planted
+        });
+        let url = if cluster == "devnet" {
+            DEVNET_URL
+        } else {
+            &cluster
+        };
+        let keypair = keypair.unwrap_or_else(|| {
+            PathBuf::from(std::env::var_os("HOME").unwrap_or_default())
+                .join(".config/solana/id.json")
+        });
+        Cluster::remote(url, &keypair)
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    if rehearsals.is_empty() {
+        eprintln!("nothing to run on {}", cluster.rpc.url());
+        std::process::exit(1);
+    }
+
+    let mut deployed = Vec::new();
+    if !cluster.is_local() {
+        for rehearsal in &rehearsals {
+            for name in rehearsal.programs() {
+                if deployed.contains(&name) {
+                    continue;
+                }
+                if let Err(e) = cluster.deploy(&name) {
+                    eprintln!("deploying {} failed: {}", name, e);
+                    std::process::exit(1);
+                }
+                deployed.push(name);
+            }
+        }
+    }
+
+    // What runs at each address must be what's built here, or the demo
+    // proves nothing about these programs
+    let mut verified: Vec<String> = Vec::new();
+    for name in rehearsals.iter().flat_map(|rehearsal| rehearsal.programs()) {
+        if verified.contains(&name) {
+            continue;
+        }
+        match cluster.verify(&name) {
+            Ok(verification) if verification.matches => {
+                eprintln!("verified {} at {}", name, verification.program_id)
+            }
+            Ok(verification) => {
+                eprintln!(
+                    "{} at {} is not target/deploy/{}.so; check it with `cargo xtask verify-deploy`",
+                    name, verification.program_id, name
+                );
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("verifying {} failed: {}", name, e);
+                std::process::exit(1);
+            }
+        }
+        verified.push(name);
+    }
+
+    let palette = Palette::detect();
+    let mut failed = Vec::new();
+    for rehearsal in &mut rehearsals {
+        let mut out = std::io::stdout().lock();
+        match live::run(rehearsal, &cluster, &mut out, palette) {
+            Ok(0) => {}
+            Ok(_) => failed.push(rehearsal.demo.program),
+            Err(e) => {
+                eprintln!("{}: {}", rehearsal.demo.program, e);
+                failed.push(rehearsal.demo.program);
+            }
+        }
+        println!();
+    }
+
+    // Teardown: a local validator goes when `cluster` is dropped
+    for rehearsal in &rehearsals {
+        for left in cluster.sweep(rehearsal) {
+            eprintln!("not swept: {}", left);
+        }
+    }
+    if close_programs {
+        for name in &deployed {
+            let program_id = security_tests::load_program_id(name);
+            if let Err(e) = cluster.close_program(&program_id) {
+                eprintln!("closing {} failed: {}", name, e);
+            }
+        }
+    }
+    drop(cluster);
+
+    if !failed.is_empty() {
+        eprintln!("differs from LiteSVM: {}", failed.join(", "));
+        std::process::exit(1);
+    }
+}
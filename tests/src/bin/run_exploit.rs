@@ -0,0 +1,76 @@
+//! Play a program's exploit and its fix as a narrated demo
+//!
+//! Usage:
+//!   cargo run --bin run-exploit -- [--format text|json] <PROGRAM>...
+//!
+//! PROGRAM is a directory under `programs/`, or `--all` for every demo.
+//! `--format json` prints an array with one `ExploitRun` per program
+//! instead of the narration. Exits non-zero when a secure instruction lets
+//! the attack through. Set `NO_COLOR` for plain output.
+
+use std::io::Write;
+
+use security_tests::demo::{self, Palette, DEMOS};
+
+fn usage() -> ! {
+    eprintln!("Usage: run-exploit [--format text|json] <PROGRAM>... | --all");
+    eprintln!();
+    eprintln!("Programs:");
+    for demo in DEMOS {
+        eprintln!("  {:<22} {}", demo.program, demo.vulnerability().title);
+    }
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut json = false;
+    let mut all = false;
+    let mut programs = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref() {
+                Some("json") => json = true,
+                Some("text") => json = false,
+                _ => usage(),
+            },
+            "--all" => all = true,
+            "-h" | "--help" => usage(),
+            flag if flag.starts_with('-') => usage(),
+            _ => programs.push(arg),
+        }
+    }
+    let demos: Vec<_> = match (all, programs.is_empty()) {
+        (true, true) => DEMOS.iter().collect(),
+        (false, false) => programs
+            .iter()
+            .map(|program| {
+                demo::find(program).unwrap_or_else(|| {
+                    eprintln!("No demo for {}", program);
+                    usage()
+                })
+            })
+            .collect(),
+        _ => usage(),
+    };
+
+    let mut blocked = true;
+    if json {
+        let runs: Vec<_> = demos.into_iter().map(demo::play).collect();
+        blocked = runs.iter().all(|run| run.outcome.blocked);
+        println!("{}", serde_json::to_string_pretty(&runs).unwrap());
+    } else {
+        let mut out = std::io::stdout().lock();
+        for (i, demo) in demos.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(out).expect("Failed to write to stdout");
+            }
+            let outcome =
+                demo::run(demo, &mut out, Palette::detect()).expect("Failed to write to stdout");
+            blocked &= outcome.blocked;
+        }
+    }
+    if !blocked {
+        std::process::exit(1);
+    }
+}
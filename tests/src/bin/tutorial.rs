@@ -0,0 +1,50 @@
+//! Step through a program's exploit and its fix, pausing between steps
+//!
+//! Usage:
+//!   cargo run --bin tutorial -- [--no-pause] <PROGRAM>
+//!
+//! PROGRAM is a directory under `programs/`. Pauses only when stdin is a
+//! terminal; `--no-pause` plays straight through. Set `NO_COLOR` for plain
+//! output.
+
+use std::io::IsTerminal;
+
+use security_tests::demo::{self, Palette, DEMOS};
+use security_tests::tutorial;
+
+fn usage() -> ! {
+    eprintln!("Usage: tutorial [--no-pause] <PROGRAM>");
+    eprintln!();
+    eprintln!("Programs:");
+    for demo in DEMOS {
+        eprintln!("  {:<22} {}", demo.program, demo.vulnerability().title);
+    }
+    std::process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (pause, program) = match &args[..] {
+        [flag, program] if flag == "--no-pause" => (false, program),
+        [program] if !program.starts_with('-') => (true, program),
+        _ => usage(),
+    };
+    let demo = demo::find(program).unwrap_or_else(|| {
+        eprintln!("No demo for {}", program);
+        usage()
+    });
+
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
+    let pause = pause && stdin.is_terminal();
+    let outcome = tutorial::run(
+        demo,
+        pause.then_some(&mut input as &mut dyn std::io::BufRead),
+        &mut std::io::stdout().lock(),
+        Palette::detect(),
+    )
+    .expect("Failed to write to stdout");
+    if outcome.is_some_and(|outcome| !outcome.blocked) {
+        std::process::exit(1);
+    }
+}
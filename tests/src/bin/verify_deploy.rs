@@ -0,0 +1,226 @@
+//! Check that deployed programs run the bytecode built here
+//!
+//! Usage:
+//!   cargo run --bin verify-deploy -- [--cluster local|devnet|URL] [--format text|json] NAME...
+//!   cargo run --bin verify-deploy -- [--cluster ...] --program ID --so FILE
+//!   cargo run --bin verify-deploy -- --demo [--port PORT]
+//!
+//! NAME is a `.so` in `target/deploy/` or `programs/amm/target/deploy/`,
+//! checked at its keypair's address. `local` is a validator already running
+//! on port 8899. Exits non-zero when any program differs or isn't there.
+//!
+//! `--demo` starts a `solana-test-validator` whose `secure_amm` address runs
+//! `buggy_amm.so`. Both programs answer the same instructions, so nothing a
+//! client sees gives the swap away; only comparing the bytecode does.
+
+use std::path::{Path, PathBuf};
+
+use security_tests::common::{self, deploy_dir};
+use security_tests::live::{Rpc, Validator};
+use security_tests::sdk::Pubkey;
+use security_tests::verify::{self, Verification};
+use serde_json::json;
+
+const DEVNET_URL: &str = "https://api.devnet.solana.com";
+const LOCAL_URL: &str = "http://127.0.0.1:8899";
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: verify-deploy [--cluster local|devnet|URL] [--format text|json] NAME...\n       \
+         verify-deploy [--cluster local|devnet|URL] [--format text|json] --program ID --so FILE\n       \
+         verify-deploy --demo [--port PORT]"
+    );
+    std::process::exit(2);
+}
+
+fn amm_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../programs/amm/target/deploy")
+}
+
+// The deploy dir holding `<name>-keypair.json`
+fn dir_for(name: &str) -> PathBuf {
+    [deploy_dir(), amm_dir()]
+        .into_iter()
+        .find(|dir| dir.join(format!("{}-keypair.json", name)).exists())
+        .unwrap_or_else(|| {
+            eprintln!(
+                "no keypair for {} in target/deploy or programs/amm/target/deploy",
+                name
+            );
+            std::process::exit(2);
+        })
+}
+
+fn check(rpc: &Rpc, program_id: &Pubkey, so: &Path) -> Verification {
+    let local = std::fs::read(so).unwrap_or_else(|e| {
+        eprintln!("{}: {}", so.display(), e);
+        std::process::exit(2);
+    });
+    verify::verify(program_id, &local, |address| rpc.account(address)).unwrap_or_else(|e| {
+        eprintln!("{}: {}", program_id, e);
+        std::process::exit(1);
+    })
+}
+
+fn print(name: &str, verification: &Verification) {
+    let status = match (&verification.deployed, verification.matches) {
+        (None, _) => "NOT DEPLOYED",
+        (Some(_), true) => "verified",
+        (Some(_), false) => "DIFFERS",
+    };
+    println!("{} {}: {}", name, verification.program_id, status);
+    println!(
+        "  local     {} ({} bytes)",
+        verification.local.sha256, verification.local.len
+    );
+    if let Some(deployed) = &verification.deployed {
+        println!(
+            "  deployed  {} ({} bytes, trailing zeros trimmed)",
+            deployed.trimmed.sha256, deployed.trimmed.len
+        );
+        println!(
+            "            {} ({} bytes as stored)",
+            deployed.padded.sha256, deployed.padded.len
+        );
+        match &deployed.upgrade_authority {
+            Some(authority) => println!(
+                "  upgrade authority {}: it can still be replaced",
+                authority
+            ),
+            None => println!("  immutable"),
+        }
+    }
+}
+
+// An impostor at `secure_amm`'s address, caught by its bytecode
+fn demo(port: u16) -> bool {
+    let dir = amm_dir();
+    let buggy = common::load_program_id_in(&dir, "buggy_amm");
+    let secure = common::load_program_id_in(&dir, "secure_amm");
+    let buggy_so = dir.join("buggy_amm.so");
+    let secure_so = dir.join("secure_amm.so");
+
+    let ledger = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target/verify-demo");
+    eprintln!("starting solana-test-validator on port {}", port);
+    let (validator, rpc) = Validator::start(
+        &ledger,
+        port,
+        &[(buggy, buggy_so.clone()), (secure, buggy_so)],
+        &[],
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    println!(
+        "{} is the secure AMM's address, but the validator loaded buggy_amm.so there.\n\
+         Its instructions, accounts and IDL are the buggy AMM's, which has the same ones,\n\
+         so a client calling it can't tell. Comparing each address with the local builds:\n",
+        secure
+    );
+    let honest = check(&rpc, &buggy, &dir.join("buggy_amm.so"));
+    print("buggy_amm", &honest);
+    println!();
+    let impostor = check(&rpc, &secure, &secure_so);
+    print("secure_amm", &impostor);
+    drop(validator);
+
+    println!();
+    let caught = honest.matches && !impostor.matches;
+    if caught {
+        println!(
+            "secure_amm's address doesn't run secure_amm.so. Verify before trusting a fix: \
+             the source you audited only protects anyone if it's what was deployed."
+        );
+    } else {
+        println!("the impostor went unnoticed");
+    }
+    caught
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut url = LOCAL_URL.to_string();
+    let mut json = false;
+    let mut program = None;
+    let mut so = None;
+    let mut run_demo = false;
+    let mut port = 8899u16;
+    let mut names = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cluster" => {
+                url = match args.next().unwrap_or_else(|| usage()).as_str() {
+                    "local" => LOCAL_URL.to_string(),
+                    "devnet" => DEVNET_URL.to_string(),
+                    other => other.to_string(),
+                }
+            }
+            "--format" => match args.next().as_deref() {
+                Some("json") => json = true,
+                Some("text") => json = false,
+                _ => usage(),
+            },
+            "--program" => {
+                program = Some(
+                    args.next()
+                        .and_then(|id| id.parse::<Pubkey>().ok())
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--so" => so = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--demo" => run_demo = true,
+            "--port" => {
+                port = args
+                    .next()
+                    .and_then(|port| port.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "-h" | "--help" => usage(),
+            flag if flag.starts_with("--") => usage(),
+            _ => names.push(arg),
+        }
+    }
+
+    if run_demo {
+        std::process::exit(if demo(port) { 0 } else { 1 });
+    }
+    let targets: Vec<(String, Pubkey, PathBuf)> = match (program, so) {
+        (Some(program), Some(so)) if names.is_empty() => {
+            vec![(so.display().to_string(), program, so)]
+        }
+        (None, None) if !names.is_empty() => names
+            .into_iter()
+            .map(|name| {
+                let dir = dir_for(&name);
+                let program_id = common::load_program_id_in(&dir, &name);
+                let so = dir.join(format!("{}.so", name));
+                (name, program_id, so)
+            })
+            .collect(),
+        _ => usage(),
+    };
+
+    let rpc = Rpc::new(url);
+    let mut all_match = true;
+    let mut report = Vec::new();
+    for (i, (name, program_id, so)) in targets.iter().enumerate() {
+        let verification = check(&rpc, program_id, so);
+        all_match &= verification.matches;
+        if json {
+            report.push(json!({ "name": name, "verification": verification }));
+        } else {
+            if i > 0 {
+                println!();
+            }
+            print(name, &verification);
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    }
+    if !all_match {
+        std::process::exit(1);
+    }
+}
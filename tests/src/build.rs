@@ -0,0 +1,307 @@
+//! Build program binaries on demand
+//!
+//! The suites load `target/deploy/<name>.so` and panic when it is missing.
+//! With `AUTO_BUILD_PROGRAMS=1` the loaders in [`crate::common`] first call
+//! [`ensure_built`], which rebuilds a program when its binary is missing or
+//! its sources changed since the last build, so a clean checkout works with
+//! a plain
+//!
+//! ```bash
+//! AUTO_BUILD_PROGRAMS=1 cargo test
+//! ```
+//!
+//! Anchor programs in a workspace with an `Anchor.toml` are built with
+//! `anchor build -p <name>` (which also refreshes the IDL); everything else,
+//! Pinocchio and native programs and the AMM workspace, with
+//! `cargo build-sbf`.
+//!
+//! A deploy dir under `target/anchor-031/`, which the suites load with
+//! their `anchor-031` feature, is built into with `cargo build-sbf` and
+//! `--features anchor-031`, reusing the program's keypair from
+//! `target/deploy/` so it keeps its declared id.
+//!
+//! Staleness is decided by a fingerprint, a sha256 over the crate's
+//! `Cargo.toml` and `src/` plus the workspace manifest, stored next to the
+//! binary in `target/deploy/.fingerprints/<name>.sha256`. Timestamps are not
+//! used, so a fresh clone or a `git checkout` that restores identical files
+//! doesn't trigger a rebuild.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+
+pub const AUTO_BUILD_ENV: &str = "AUTO_BUILD_PROGRAMS";
+
+/// Whether `AUTO_BUILD_PROGRAMS` is set to something other than `0`.
+pub fn enabled() -> bool {
+    std::env::var(AUTO_BUILD_ENV).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// The crate a deployed program is built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramSource {
+    /// Library name, as used for `<name>.so`
+    pub name: String,
+    pub crate_dir: PathBuf,
+    /// Workspace containing the crate; `target/deploy/` lives under it
+    pub workspace: PathBuf,
+    pub anchor: bool,
+    /// The Anchor release feature, such as `anchor-031`, of a build into
+    /// `target/<release>/deploy/`
+    pub release: Option<String>,
+}
+
+// `[lib] name`, else the package name with dashes replaced
+fn lib_name(manifest: &str) -> Option<String> {
+    let mut section = "";
+    let mut package = None;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line;
+            continue;
+        }
+        let Some(value) = line
+            .strip_prefix("name")
+            .map(str::trim_start)
+            .and_then(|rest| rest.strip_prefix('='))
+        else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match section {
+            "[lib]" => return Some(value),
+            "[package]" => package = Some(value.replace('-', "_")),
+            _ => {}
+        }
+    }
+    package
+}
+
+impl ProgramSource {
+    /// Every program crate of the workspace owning `deploy_dir`, from
+    /// `<workspace>/programs/*`, the ports under
+    /// `<workspace>/programs-native/*` and `<workspace>/programs-pinocchio/*`,
+    /// and `<workspace>/*`, sorted by path. A program's own helper programs,
+    /// such as `programs/partner-mint/partner`, come with it; library crates,
+    /// which build no `cdylib`, are left out.
+    pub fn all(deploy_dir: &Path) -> Vec<Self> {
+        let Some(target) = deploy_dir.parent() else {
+            return Vec::new();
+        };
+        // `target/deploy/`, or `target/anchor-031/deploy/`
+        let release = target
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| name.starts_with("anchor-"))
+            .map(str::to_string);
+        let workspace = match release {
+            Some(_) => target.parent().and_then(Path::parent),
+            None => target.parent(),
+        };
+        let Some(workspace) = workspace else {
+            return Vec::new();
+        };
+        let anchor_workspace = workspace.join("Anchor.toml").exists();
+
+        let mut dirs: Vec<PathBuf> = ["programs", "programs-native", "programs-pinocchio", "."]
+            .iter()
+            .filter_map(|sub| std::fs::read_dir(workspace.join(sub)).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.join("Cargo.toml").exists())
+            .collect();
+        // Crates nested in a package, not in a workspace of their own
+        let nested: Vec<PathBuf> = dirs
+            .iter()
+            .filter(|dir| {
+                std::fs::read_to_string(dir.join("Cargo.toml"))
+                    .is_ok_and(|manifest| manifest.contains("[package]"))
+            })
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.join("Cargo.toml").exists())
+            .collect();
+        dirs.extend(nested);
+        dirs.sort();
+        dirs.dedup();
+
+        dirs.into_iter()
+            .filter_map(|crate_dir| {
+                let manifest = std::fs::read_to_string(crate_dir.join("Cargo.toml")).ok()?;
+                if !manifest.contains("cdylib") {
+                    return None;
+                }
+                Some(Self {
+                    name: lib_name(&manifest)?,
+                    anchor: anchor_workspace && manifest.contains("anchor-lang"),
+                    crate_dir,
+                    workspace: workspace.to_path_buf(),
+                    release: release.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Find the crate building `<name>.so` for `deploy_dir`.
+    pub fn find(deploy_dir: &Path, name: &str) -> Option<Self> {
+        Self::all(deploy_dir).into_iter().find(|p| p.name == name)
+    }
+
+    /// Where `anchor build` writes this program's IDL.
+    pub fn idl_path(&self) -> PathBuf {
+        self.workspace
+            .join("target/idl")
+            .join(format!("{}.json", self.name))
+    }
+
+    pub fn deploy_dir(&self) -> PathBuf {
+        match &self.release {
+            Some(release) => self.workspace.join("target").join(release).join("deploy"),
+            None => self.workspace.join("target/deploy"),
+        }
+    }
+
+    fn fingerprint_path(&self) -> PathBuf {
+        self.deploy_dir()
+            .join(".fingerprints")
+            .join(format!("{}.sha256", self.name))
+    }
+
+    /// sha256 over the crate manifest, every file under `src/` and the
+    /// workspace manifest, each prefixed with its relative path.
+    pub fn fingerprint(&self) -> String {
+        let mut files = vec![
+            self.crate_dir.join("Cargo.toml"),
+            self.workspace.join("Cargo.toml"),
+        ];
+        collect_files(&self.crate_dir.join("src"), &mut files);
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for file in files {
+            let Ok(contents) = std::fs::read(&file) else {
+                continue;
+            };
+            let relative = file.strip_prefix(&self.workspace).unwrap_or(&file);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(&contents);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// True when the binary is missing or was built from different sources.
+    pub fn is_stale(&self) -> bool {
+        let binary = self.deploy_dir().join(format!("{}.so", self.name));
+        if !binary.exists() {
+            return true;
+        }
+        std::fs::read_to_string(self.fingerprint_path())
+            .map(|stored| stored.trim() != self.fingerprint())
+            .unwrap_or(true)
+    }
+
+    fn command(&self) -> Command {
+        if self.anchor && self.release.is_none() {
+            let mut cmd = Command::new("anchor");
+            cmd.current_dir(&self.workspace)
+                .args(["build", "-p", &self.name]);
+            cmd
+        } else {
+            let mut cmd = Command::new("cargo");
+            cmd.current_dir(&self.workspace)
+                .arg("build-sbf")
+                .arg("--manifest-path")
+                .arg(self.crate_dir.join("Cargo.toml"))
+                .arg("--sbf-out-dir")
+                .arg(self.deploy_dir());
+            if let Some(release) = self.release.as_deref().filter(|_| self.anchor) {
+                cmd.args(["--features", release]);
+            }
+            cmd
+        }
+    }
+
+    // `cargo build-sbf` makes up a keypair when there's none, whose address
+    // wouldn't be the `declare_id!` the program checks against
+    fn copy_keypair(&self) {
+        let file = format!("{}-keypair.json", self.name);
+        let keypair = self.workspace.join("target/deploy").join(&file);
+        let copy = self.deploy_dir().join(&file);
+        if keypair.exists() && !copy.exists() {
+            std::fs::create_dir_all(self.deploy_dir())
+                .and_then(|_| std::fs::copy(&keypair, &copy))
+                .unwrap_or_else(|e| panic!("Failed to copy {}: {}", keypair.display(), e));
+        }
+    }
+
+    /// Build the program and record its fingerprint. Panics with the build
+    /// output on failure.
+    pub fn build(&self) {
+        if self.release.is_some() {
+            self.copy_keypair();
+        }
+        let mut cmd = self.command();
+        eprintln!("building {}: {:?}", self.name, cmd);
+        let output = cmd
+            .output()
+            .unwrap_or_else(|e| panic!("Failed to run {:?} for {}: {}", cmd, self.name, e));
+        if !output.status.success() {
+            panic!(
+                "Building {} failed ({}):\n{}{}",
+                self.name,
+                output.status,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let path = self.fingerprint_path();
+        std::fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| std::fs::write(&path, self.fingerprint() + "\n"))
+            .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+    }
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+// Programs already checked by this test binary. Holding the lock while
+// building also keeps parallel tests from starting the same build twice.
+static CHECKED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Rebuild `<name>.so` in `deploy_dir` if it is missing or stale.
+///
+/// Each program is checked once per test binary. Programs without a source
+/// crate in the workspace are left to the loader's usual error.
+pub fn ensure_built(deploy_dir: &Path, name: &str) {
+    let key = deploy_dir.join(name);
+    let mut checked = CHECKED.lock().unwrap_or_else(|e| e.into_inner());
+    if checked.contains(&key) {
+        return;
+    }
+
+    if let Some(source) = ProgramSource::find(deploy_dir, name) {
+        if source.is_stale() {
+            source.build();
+        }
+    }
+    checked.push(key);
+}
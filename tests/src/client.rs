@@ -0,0 +1,776 @@
+//! Typed Rust client crates generated from the programs' IDLs
+//!
+//! [`generate`] turns one IDL into a crate under `clients/<program>/`:
+//!
+//! - `instructions`: an `<Name>Accounts` struct of pubkeys and an
+//!   `<Name>Args` struct per instruction, and a builder returning the
+//!   `Instruction` with the discriminator, Borsh-encoded arguments and
+//!   account metas in IDL order, fixed addresses filled in;
+//! - `pda`: a finder per PDA the IDL declares seeds for;
+//! - each account type with its `DISCRIMINATOR`, `decode` and `fetch`,
+//!   and the IDL's other types, deriving Borsh;
+//! - `codama.json`: the IDL as a Codama node tree (see [`codama`]), for
+//!   Codama's renderers in other languages.
+//!
+//! Anchor programs' IDLs come from `anchor build`, so their clients appear
+//! once it has run; the Pinocchio programs' checked-in IDLs always
+//! generate one. `cargo xtask clients --check` fails when a crate on disk
+//! is out of date.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde_json::Value as Json;
+
+use crate::build::ProgramSource;
+use crate::codama;
+use crate::idl::{account_discriminator, checked_in_idl_dir, instruction_discriminator};
+use crate::sdk::Pubkey;
+
+/// First line of every generated file.
+pub const GENERATED: &str =
+    "Generated by `cargo xtask clients` from the program's IDL. Do not edit.";
+
+/// `clients/` at the repository root.
+pub fn clients_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("clients")
+}
+
+/// A generated crate: its directory name and `(path, contents)` files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    pub program: String,
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// Every program with an IDL on disk: `target/idl/` for Anchor programs in
+/// both workspaces, `tests/idl/` for the rest. `(program, IDL path)`.
+pub fn idls() -> Vec<(String, PathBuf)> {
+    let root = clients_dir().parent().unwrap().to_path_buf();
+    let mut found = BTreeMap::new();
+    for workspace in [root.clone(), root.join("programs/amm")] {
+        for program in ProgramSource::all(&workspace.join("target/deploy")) {
+            let generated = program.idl_path();
+            let checked_in = checked_in_idl_dir().join(format!("{}.json", program.name));
+            if let Some(path) = [generated, checked_in].into_iter().find(|p| p.exists()) {
+                found.insert(program.name, path);
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// The client crate for one IDL.
+pub fn generate(idl: &str) -> Result<Client, String> {
+    let raw: Json = serde_json::from_str(idl).map_err(|e| format!("bad IDL: {}", e))?;
+    let name = raw
+        .pointer("/metadata/name")
+        .or_else(|| raw.get("name"))
+        .and_then(Json::as_str)
+        .ok_or("IDL has no name")?
+        .to_string();
+    let program = name.replace('_', "-");
+    let lib = Generator::new(&name, &raw)
+        .lib()
+        .map_err(|e| format!("{}: {}", name, e))?;
+    let nodes = codama::root_node(&name, &raw).map_err(|e| format!("{}: {}", name, e))?;
+    Ok(Client {
+        files: vec![
+            (PathBuf::from("Cargo.toml"), manifest(&name, &program)),
+            (PathBuf::from("src/lib.rs"), lib),
+            (
+                PathBuf::from("codama.json"),
+                serde_json::to_string_pretty(&nodes).unwrap() + "\n",
+            ),
+        ],
+        program,
+    })
+}
+
+fn manifest(name: &str, program: &str) -> String {
+    format!(
+        r#"# {GENERATED}
+
+[package]
+name = "{program}-client"
+description = "Instruction builders, PDA finders and account decoders for {name}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+name = "{name}_client"
+
+[dependencies]
+borsh = {{ version = "1", features = ["derive"] }}
+solana-instruction = "2.2.1"
+solana-pubkey = {{ version = "2.2.1", features = ["borsh", "curve25519"] }}
+"#
+    )
+}
+
+/// The crates on disk under `dir` that differ from `clients`.
+pub fn stale(dir: &Path, clients: &[Client]) -> Vec<String> {
+    clients
+        .iter()
+        .filter(|client| {
+            client.files.iter().any(|(file, text)| {
+                let path = dir.join(&client.program).join(file);
+                std::fs::read_to_string(path).ok().as_ref() != Some(text)
+            })
+        })
+        .map(|client| client.program.clone())
+        .collect()
+}
+
+/// Write `clients` under `dir`, one directory each.
+pub fn write(dir: &Path, clients: &[Client]) -> io::Result<()> {
+    for client in clients {
+        for (file, text) in &client.files {
+            let path = dir.join(&client.program).join(file);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, text)?;
+        }
+    }
+    Ok(())
+}
+
+/// `snake_case` from the IDL's snake or legacy camelCase names.
+fn snake(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+pub(crate) fn camel(name: &str) -> String {
+    snake(name)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn ident(name: &str) -> String {
+    let name = snake(name);
+    match name.as_str() {
+        "type" | "move" | "ref" | "mut" | "fn" | "struct" | "enum" | "match" | "loop" | "impl"
+        | "use" | "mod" | "crate" | "self" | "super" | "where" | "in" | "as" => {
+            format!("r#{}", name)
+        }
+        _ => name,
+    }
+}
+
+fn bytes(bytes: &[u8]) -> String {
+    let list: Vec<String> = bytes.iter().map(u8::to_string).collect();
+    format!("[{}]", list.join(", "))
+}
+
+fn pubkey_const(key: &Pubkey) -> String {
+    format!("Pubkey::from_str_const(\"{}\")", key)
+}
+
+/// `fn name(params) -> ret {`, one parameter a line once it's too long.
+fn signature(name: &str, params: &[String], ret: &str) -> String {
+    let line = format!("    pub fn {}({}) -> {} {{", name, params.join(", "), ret);
+    if line.len() <= 100 {
+        return line;
+    }
+    let params: String = params.iter().map(|p| format!("        {},\n", p)).collect();
+    format!("    pub fn {}(\n{}    ) -> {} {{", name, params, ret)
+}
+
+pub(crate) fn json_bytes(value: &Json) -> Option<Vec<u8>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+        .collect()
+}
+
+/// The Rust type for an IDL type.
+fn rust_type(ty: &Json) -> Result<String, String> {
+    if let Some(name) = ty.as_str() {
+        return Ok(match name {
+            "bool" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128"
+            | "i128" | "f32" | "f64" => name.to_string(),
+            "string" => "String".to_string(),
+            "bytes" => "Vec<u8>".to_string(),
+            "pubkey" | "publicKey" => "Pubkey".to_string(),
+            other => return Err(format!("unsupported type {}", other)),
+        });
+    }
+    if let Some(inner) = ty.get("vec") {
+        return Ok(format!("Vec<{}>", rust_type(inner)?));
+    }
+    if let Some(inner) = ty.get("option") {
+        return Ok(format!("Option<{}>", rust_type(inner)?));
+    }
+    if let Some([inner, len]) = ty.get("array").and_then(Json::as_array).map(Vec::as_slice) {
+        let len = len.as_u64().ok_or("array length is not a number")?;
+        return Ok(format!("[{}; {}]", rust_type(inner)?, len));
+    }
+    if let Some(defined) = ty.get("defined") {
+        let name = defined
+            .as_str()
+            .or_else(|| defined.get("name").and_then(Json::as_str))
+            .ok_or("defined type has no name")?;
+        return Ok(camel(name));
+    }
+    Err(format!("unsupported type {}", ty))
+}
+
+/// The bytes a PDA seed of this argument type derives from, as Anchor does.
+fn arg_seed(name: &str, ty: &Json) -> Option<String> {
+    match ty.as_str()? {
+        "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128" => {
+            Some(format!("&{}.to_le_bytes()", name))
+        }
+        "pubkey" | "publicKey" => Some(format!("{}.as_ref()", name)),
+        "string" => Some(format!("{}.as_bytes()", name)),
+        "bytes" => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+struct Pda {
+    /// The finder, named after the account
+    name: String,
+    /// `(parameter, Rust type)`
+    params: Vec<(String, String)>,
+    seeds: Vec<String>,
+    /// Another program's bytes, when the PDA isn't this program's
+    program: Option<Vec<u8>>,
+}
+
+struct Generator<'a> {
+    name: &'a str,
+    raw: &'a Json,
+    out: String,
+}
+
+impl<'a> Generator<'a> {
+    fn new(name: &'a str, raw: &'a Json) -> Self {
+        Generator {
+            name,
+            raw,
+            out: String::new(),
+        }
+    }
+
+    fn list(&self, key: &str) -> &'a [Json] {
+        self.raw
+            .get(key)
+            .and_then(Json::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn lib(mut self) -> Result<String, String> {
+        let name = self.name;
+        writeln!(self.out, "//! Client for the `{name}` program.").unwrap();
+        writeln!(self.out, "//!").unwrap();
+        writeln!(self.out, "//! {GENERATED}").unwrap();
+        writeln!(self.out).unwrap();
+        writeln!(self.out, "pub use solana_pubkey::Pubkey;").unwrap();
+        writeln!(self.out).unwrap();
+
+        if let Some(address) = self
+            .raw
+            .get("address")
+            .and_then(Json::as_str)
+            .and_then(|a| Pubkey::from_str(a).ok())
+        {
+            writeln!(self.out, "/// The IDL's program address").unwrap();
+            writeln!(
+                self.out,
+                "pub const ID: Pubkey = {};",
+                pubkey_const(&address)
+            )
+            .unwrap();
+            writeln!(self.out).unwrap();
+        }
+
+        self.types()?;
+        self.accounts()?;
+        self.instructions()?;
+        self.pdas()?;
+        Ok(self.out)
+    }
+
+    fn fields(&mut self, fields: &[Json], public: &str) -> Result<(), String> {
+        for field in fields {
+            let name = field["name"].as_str().ok_or("field has no name")?;
+            let ty = rust_type(&field["type"])?;
+            writeln!(self.out, "    {}{}: {},", public, ident(name), ty).unwrap();
+        }
+        Ok(())
+    }
+
+    fn types(&mut self) -> Result<(), String> {
+        // Legacy IDLs describe account types inline instead
+        let inline: Vec<&Json> = self
+            .list("accounts")
+            .iter()
+            .filter(|a| a.get("type").is_some())
+            .collect();
+        for ty in self.list("types").iter().chain(inline) {
+            let name = camel(ty["name"].as_str().ok_or("type has no name")?);
+            let body = &ty["type"];
+            writeln!(
+                self.out,
+                "#[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]"
+            )
+            .unwrap();
+            match body["kind"].as_str() {
+                Some("struct") => {
+                    let fields = body["fields"]
+                        .as_array()
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    if fields.first().is_some_and(|f| f.is_object()) {
+                        writeln!(self.out, "pub struct {} {{", name).unwrap();
+                        self.fields(fields, "pub ")?;
+                        writeln!(self.out, "}}").unwrap();
+                    } else {
+                        let tuple: Result<Vec<String>, String> = fields
+                            .iter()
+                            .map(|f| rust_type(f).map(|t| format!("pub {}", t)))
+                            .collect();
+                        writeln!(self.out, "pub struct {}({});", name, tuple?.join(", ")).unwrap();
+                    }
+                }
+                Some("enum") => {
+                    writeln!(self.out, "pub enum {} {{", name).unwrap();
+                    for variant in body["variants"]
+                        .as_array()
+                        .map(Vec::as_slice)
+                        .unwrap_or_default()
+                    {
+                        let variant_name =
+                            camel(variant["name"].as_str().ok_or("variant has no name")?);
+                        match variant.get("fields").and_then(Json::as_array) {
+                            None => writeln!(self.out, "    {},", variant_name).unwrap(),
+                            Some(fields)
+                                if fields.first().is_some_and(|f| f.get("name").is_some()) =>
+                            {
+                                writeln!(self.out, "    {} {{", variant_name).unwrap();
+                                for field in fields {
+                                    let field_name = field["name"].as_str().unwrap_or_default();
+                                    let ty = rust_type(&field["type"])?;
+                                    writeln!(self.out, "        {}: {},", ident(field_name), ty)
+                                        .unwrap();
+                                }
+                                writeln!(self.out, "    }},").unwrap();
+                            }
+                            Some(fields) => {
+                                let tuple: Result<Vec<String>, String> =
+                                    fields.iter().map(rust_type).collect();
+                                writeln!(self.out, "    {}({}),", variant_name, tuple?.join(", "))
+                                    .unwrap();
+                            }
+                        }
+                    }
+                    writeln!(self.out, "}}").unwrap();
+                }
+                other => return Err(format!("type {} has unsupported kind {:?}", name, other)),
+            }
+            writeln!(self.out).unwrap();
+        }
+        Ok(())
+    }
+
+    fn accounts(&mut self) -> Result<(), String> {
+        let accounts = self.list("accounts");
+        if accounts.is_empty() {
+            return Ok(());
+        }
+        writeln!(
+            self.out,
+            r#"/// Why an account's data isn't the type asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {{
+    /// No account at the address
+    Missing,
+    /// The data starts with another type's discriminator
+    Discriminator,
+    /// The data after the discriminator doesn't deserialize
+    Layout,
+}}
+"#
+        )
+        .unwrap();
+        for account in accounts {
+            let raw_name = account["name"].as_str().ok_or("account has no name")?;
+            let name = camel(raw_name);
+            let disc = account
+                .get("discriminator")
+                .and_then(json_bytes)
+                .unwrap_or_else(|| account_discriminator(raw_name).to_vec());
+            writeln!(
+                self.out,
+                r#"impl {name} {{
+    pub const DISCRIMINATOR: [u8; {len}] = {disc};
+
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {{
+        let body = data
+            .strip_prefix(&Self::DISCRIMINATOR[..])
+            .ok_or(DecodeError::Discriminator)?;
+        borsh::BorshDeserialize::deserialize(&mut &body[..]).map_err(|_| DecodeError::Layout)
+    }}
+
+    /// Decode the account `get` returns the data of, from an RPC client,
+    /// LiteSVM or a fixture.
+    pub fn fetch(
+        address: &Pubkey,
+        get: impl FnOnce(&Pubkey) -> Option<Vec<u8>>,
+    ) -> Result<Self, DecodeError> {{
+        Self::decode(&get(address).ok_or(DecodeError::Missing)?)
+    }}
+}}
+"#,
+                len = disc.len(),
+                disc = bytes(&disc),
+            )
+            .unwrap();
+        }
+        Ok(())
+    }
+
+    fn instructions(&mut self) -> Result<(), String> {
+        writeln!(self.out, "pub mod instructions {{").unwrap();
+        writeln!(self.out, "    use super::*;").unwrap();
+        writeln!(
+            self.out,
+            "    use solana_instruction::{{AccountMeta, Instruction}};"
+        )
+        .unwrap();
+        for ix in self.list("instructions") {
+            writeln!(self.out).unwrap();
+            self.instruction(ix)?;
+        }
+        writeln!(self.out, "}}").unwrap();
+        Ok(())
+    }
+
+    fn instruction(&mut self, ix: &Json) -> Result<(), String> {
+        let raw_name = ix["name"].as_str().ok_or("instruction has no name")?;
+        let (name, ty) = (ident(raw_name), camel(raw_name));
+        let disc = ix
+            .get("discriminator")
+            .and_then(json_bytes)
+            .unwrap_or_else(|| instruction_discriminator(raw_name).to_vec());
+
+        let mut slots = Vec::new();
+        flatten(
+            ix["accounts"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            &mut slots,
+        );
+        let args = ix["args"].as_array().map(Vec::as_slice).unwrap_or_default();
+        // `#[event_cpi]` appends these two, both derived from the program id
+        let event_cpi = matches!(
+            slots[..],
+            [.., authority, program]
+                if authority["name"] == "event_authority" && program["name"] == "program"
+        );
+        let fixed = slots.len() - if event_cpi { 2 } else { 0 };
+        let keyed: Vec<&&Json> = slots[..fixed]
+            .iter()
+            .filter(|a| a.get("address").is_none())
+            .collect();
+
+        if !keyed.is_empty() {
+            writeln!(self.out, "    /// Accounts for [`{}`]", name).unwrap();
+            writeln!(self.out, "    #[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+            writeln!(self.out, "    pub struct {}Accounts {{", ty).unwrap();
+            for account in &keyed {
+                let field = ident(account["name"].as_str().unwrap_or_default());
+                let optional = flag(account, "optional", "isOptional");
+                let key = if optional { "Option<Pubkey>" } else { "Pubkey" };
+                writeln!(self.out, "        pub {}: {},", field, key).unwrap();
+            }
+            writeln!(self.out, "    }}").unwrap();
+            writeln!(self.out).unwrap();
+        }
+        if !args.is_empty() {
+            writeln!(self.out, "    /// Arguments for [`{}`]", name).unwrap();
+            writeln!(
+                self.out,
+                "    #[derive(Debug, Clone, PartialEq, borsh::BorshSerialize, borsh::BorshDeserialize)]"
+            )
+            .unwrap();
+            writeln!(self.out, "    pub struct {}Args {{", ty).unwrap();
+            for arg in args {
+                let arg_name = arg["name"].as_str().ok_or("argument has no name")?;
+                let arg_ty = rust_type(&arg["type"])?;
+                writeln!(self.out, "        pub {}: {},", ident(arg_name), arg_ty).unwrap();
+            }
+            writeln!(self.out, "    }}").unwrap();
+            writeln!(self.out).unwrap();
+        }
+
+        let upper = snake(raw_name).to_ascii_uppercase();
+        writeln!(
+            self.out,
+            "    pub const {}: [u8; {}] = {};",
+            upper,
+            disc.len(),
+            bytes(&disc)
+        )
+        .unwrap();
+        writeln!(self.out).unwrap();
+
+        let mut params = vec!["program_id: &Pubkey".to_string()];
+        if !keyed.is_empty() {
+            params.push(format!("accounts: &{}Accounts", ty));
+        }
+        if !args.is_empty() {
+            params.push(format!("args: &{}Args", ty));
+        }
+        writeln!(self.out, "{}", signature(&name, &params, "Instruction")).unwrap();
+        if args.is_empty() {
+            writeln!(self.out, "        let data = {}.to_vec();", upper).unwrap();
+        } else {
+            writeln!(self.out, "        let mut data = {}.to_vec();", upper).unwrap();
+            writeln!(
+                self.out,
+                "        borsh::BorshSerialize::serialize(args, &mut data).expect(\"writing to a Vec\");"
+            )
+            .unwrap();
+        }
+        writeln!(self.out, "        Instruction {{").unwrap();
+        writeln!(self.out, "            program_id: *program_id,").unwrap();
+        if slots.is_empty() {
+            writeln!(self.out, "            accounts: vec![],").unwrap();
+        } else {
+            writeln!(self.out, "            accounts: vec![").unwrap();
+        }
+        for (i, account) in slots.iter().enumerate() {
+            let writable = flag(account, "writable", "isMut");
+            let signer = flag(account, "signer", "isSigner");
+            let meta = if writable {
+                "AccountMeta::new"
+            } else {
+                "AccountMeta::new_readonly"
+            };
+            let field = ident(account["name"].as_str().unwrap_or_default());
+            let key = match account.get("address").and_then(Json::as_str) {
+                _ if i == fixed => {
+                    "Pubkey::find_program_address(&[b\"__event_authority\"], program_id).0"
+                        .to_string()
+                }
+                _ if i > fixed => "*program_id".to_string(),
+                Some(address) => {
+                    let key =
+                        Pubkey::from_str(address).map_err(|e| format!("{}: {}", address, e))?;
+                    pubkey_const(&key)
+                }
+                None => format!("accounts.{}", field),
+            };
+            if flag(account, "optional", "isOptional") {
+                // Anchor reads the program id in an optional slot as None
+                writeln!(
+                    self.out,
+                    "                {}\n                    .map_or(AccountMeta::new_readonly(*program_id, false), |key| {{\n                        {}(key, {})\n                    }}),",
+                    key, meta, signer
+                )
+                .unwrap();
+            } else {
+                writeln!(self.out, "                {}({}, {}),", meta, key, signer).unwrap();
+            }
+        }
+        if !slots.is_empty() {
+            writeln!(self.out, "            ],").unwrap();
+        }
+        writeln!(self.out, "            data,").unwrap();
+        writeln!(self.out, "        }}").unwrap();
+        writeln!(self.out, "    }}").unwrap();
+        Ok(())
+    }
+
+    fn pdas(&mut self) -> Result<(), String> {
+        let mut pdas: Vec<Pda> = Vec::new();
+        for ix in self.list("instructions") {
+            let mut slots = Vec::new();
+            flatten(
+                ix["accounts"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                &mut slots,
+            );
+            let args = ix["args"].as_array().map(Vec::as_slice).unwrap_or_default();
+            for account in slots {
+                let Some(pda) = pda(account, args) else {
+                    continue;
+                };
+                let same = |other: &Pda| other.seeds == pda.seeds && other.program == pda.program;
+                if pdas.iter().any(same) {
+                    continue;
+                }
+                // Two accounts of one name with different seeds keep each
+                let name = if pdas.iter().any(|other| other.name == pda.name) {
+                    format!(
+                        "{}_{}",
+                        snake(ix["name"].as_str().unwrap_or_default()),
+                        pda.name
+                    )
+                } else {
+                    pda.name.clone()
+                };
+                pdas.push(Pda { name, ..pda });
+            }
+        }
+        if pdas.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.out).unwrap();
+        writeln!(self.out, "pub mod pda {{").unwrap();
+        writeln!(self.out, "    use super::*;").unwrap();
+        for pda in pdas {
+            let mut params = if pda.program.is_some() {
+                Vec::new()
+            } else {
+                vec!["program_id: &Pubkey".to_string()]
+            };
+            params.extend(
+                pda.params
+                    .iter()
+                    .map(|(name, ty)| format!("{}: {}", name, ty)),
+            );
+            let program = match &pda.program {
+                Some(key) => {
+                    let key = <[u8; 32]>::try_from(key.as_slice())
+                        .map_err(|_| "PDA program is not 32 bytes")?;
+                    format!("&{}", pubkey_const(&Pubkey::new_from_array(key)))
+                }
+                None => "program_id".to_string(),
+            };
+            writeln!(self.out).unwrap();
+            writeln!(
+                self.out,
+                "{}",
+                signature(&pda.name, &params, "(Pubkey, u8)")
+            )
+            .unwrap();
+            let find = format!(
+                "        Pubkey::find_program_address(&[{}], {})",
+                pda.seeds.join(", "),
+                program
+            );
+            if find.len() <= 100 {
+                writeln!(self.out, "{}", find).unwrap();
+            } else {
+                writeln!(self.out, "        Pubkey::find_program_address(").unwrap();
+                writeln!(self.out, "            &[").unwrap();
+                for seed in &pda.seeds {
+                    writeln!(self.out, "                {},", seed).unwrap();
+                }
+                writeln!(self.out, "            ],").unwrap();
+                writeln!(self.out, "            {},", program).unwrap();
+                writeln!(self.out, "        )").unwrap();
+            }
+            writeln!(self.out, "    }}").unwrap();
+        }
+        writeln!(self.out, "}}").unwrap();
+        Ok(())
+    }
+}
+
+pub(crate) fn flag(account: &Json, new: &str, legacy: &str) -> bool {
+    account
+        .get(new)
+        .or_else(|| account.get(legacy))
+        .and_then(Json::as_bool)
+        .unwrap_or(false)
+}
+
+// Composite `#[derive(Accounts)]` fields nest their own list
+pub(crate) fn flatten<'a>(list: &'a [Json], out: &mut Vec<&'a Json>) {
+    for account in list {
+        match account.get("accounts").and_then(Json::as_array) {
+            Some(nested) => flatten(nested, out),
+            None => out.push(account),
+        }
+    }
+}
+
+/// The finder for an account's declared seeds, if they can be expressed
+/// without reading other accounts' data.
+fn pda(account: &Json, args: &[Json]) -> Option<Pda> {
+    let declared = account.get("pda")?;
+    let mut params = Vec::new();
+    let mut seeds = Vec::new();
+    for seed in declared.get("seeds")?.as_array()? {
+        let path = seed.get("path").and_then(Json::as_str);
+        match seed.get("kind")?.as_str()? {
+            "const" => {
+                let value = json_bytes(&seed["value"])?;
+                match std::str::from_utf8(&value) {
+                    Ok(text) if text.bytes().all(|b| b.is_ascii_graphic()) => seeds.push(format!(
+                        "b\"{}\"",
+                        text.replace('\\', "\\\\").replace('"', "\\\"")
+                    )),
+                    _ => seeds.push(format!("&{}", bytes(&value))),
+                }
+            }
+            // A field of another account's data, e.g. `config.authority`
+            "account" if path?.contains('.') => return None,
+            "account" => {
+                let name = ident(path?);
+                seeds.push(format!("{}.as_ref()", name));
+                params.push((name, "&Pubkey".to_string()));
+            }
+            "arg" => {
+                let path = path?;
+                let arg = args.iter().find(|a| a["name"].as_str() == Some(path))?;
+                let name = ident(path);
+                seeds.push(arg_seed(&name, &arg["type"])?);
+                let ty = match rust_type(&arg["type"]).ok()?.as_str() {
+                    "String" => "&str".to_string(),
+                    "Vec<u8>" => "&[u8]".to_string(),
+                    "Pubkey" => "&Pubkey".to_string(),
+                    other => other.to_string(),
+                };
+                params.push((name, ty));
+            }
+            _ => return None,
+        }
+    }
+    let program = match declared.get("program") {
+        None => None,
+        Some(program) if program.get("kind")?.as_str()? == "const" => {
+            Some(json_bytes(&program["value"])?)
+        }
+        Some(_) => return None,
+    };
+    Some(Pda {
+        name: ident(account["name"].as_str()?),
+        params,
+        seeds,
+        program,
+    })
+}
@@ -0,0 +1,419 @@
+//! Codama node trees of the programs' IDLs
+//!
+//! [`root_node`] converts one Anchor IDL into the `rootNode` Codama's
+//! renderers read, written next to each generated client as
+//! `clients/<program>/codama.json`. The Rust crate beside it is ours; the
+//! tree is there for clients in other languages, e.g. through
+//! `@codama/renderers-js`, describing the same instructions, accounts,
+//! PDAs and errors.
+//!
+//! Each instruction and account leads with its discriminator as an
+//! `omitted` field, and every PDA an account's seeds declare becomes a
+//! `pdaNode` the account defaults to, as `@codama/nodes-from-anchor` does.
+
+use std::str::FromStr;
+
+use serde_json::{json, Value as Json};
+
+use crate::client::{camel, flag, flatten, json_bytes};
+use crate::idl::{account_discriminator, instruction_discriminator};
+use crate::sdk::Pubkey;
+
+/// The version of the Codama standard the tree follows.
+pub const STANDARD_VERSION: &str = "1.0.0";
+
+/// Codama's names are `lowerCamelCase`.
+fn name(raw: &str) -> String {
+    let upper = camel(raw);
+    let mut chars = upper.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+fn list<'a>(value: &'a Json, key: &str) -> &'a [Json] {
+    value
+        .get(key)
+        .and_then(Json::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn docs(value: &Json) -> Json {
+    value.get("docs").cloned().unwrap_or_else(|| json!([]))
+}
+
+fn number(format: &str) -> Json {
+    json!({ "kind": "numberTypeNode", "format": format, "endian": "le" })
+}
+
+fn bytes_value(bytes: &[u8]) -> Json {
+    let data: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    json!({ "kind": "bytesValueNode", "data": data, "encoding": "base16" })
+}
+
+/// The `omitted` discriminator field leading an account or instruction.
+fn discriminator(kind: &str, disc: &[u8]) -> Json {
+    json!({
+        "kind": kind,
+        "name": "discriminator",
+        "type": {
+            "kind": "fixedSizeTypeNode",
+            "size": disc.len(),
+            "type": { "kind": "bytesTypeNode" },
+        },
+        "docs": [],
+        "defaultValue": bytes_value(disc),
+        "defaultValueStrategy": "omitted",
+    })
+}
+
+fn first_field() -> Json {
+    json!([{ "kind": "fieldDiscriminatorNode", "name": "discriminator", "offset": 0 }])
+}
+
+/// The Codama type node for an IDL type, Borsh-encoded as Anchor does.
+fn type_node(ty: &Json) -> Result<Json, String> {
+    let u32_prefix = number("u32");
+    if let Some(ty) = ty.as_str() {
+        return Ok(match ty {
+            "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128"
+            | "f32" | "f64" => number(ty),
+            "bool" => json!({ "kind": "booleanTypeNode", "size": number("u8") }),
+            "string" => json!({
+                "kind": "sizePrefixTypeNode",
+                "type": { "kind": "stringTypeNode", "encoding": "utf8" },
+                "prefix": u32_prefix,
+            }),
+            "bytes" => json!({
+                "kind": "sizePrefixTypeNode",
+                "type": { "kind": "bytesTypeNode" },
+                "prefix": u32_prefix,
+            }),
+            "pubkey" | "publicKey" => json!({ "kind": "publicKeyTypeNode" }),
+            other => return Err(format!("unsupported type {}", other)),
+        });
+    }
+    if let Some(inner) = ty.get("vec") {
+        return Ok(json!({
+            "kind": "arrayTypeNode",
+            "item": type_node(inner)?,
+            "count": { "kind": "prefixedCountNode", "prefix": u32_prefix },
+        }));
+    }
+    if let Some(inner) = ty.get("option") {
+        return Ok(json!({
+            "kind": "optionTypeNode",
+            "fixed": false,
+            "item": type_node(inner)?,
+            "prefix": number("u8"),
+        }));
+    }
+    if let Some([inner, len]) = ty.get("array").and_then(Json::as_array).map(Vec::as_slice) {
+        let len = len.as_u64().ok_or("array length is not a number")?;
+        return Ok(json!({
+            "kind": "arrayTypeNode",
+            "item": type_node(inner)?,
+            "count": { "kind": "fixedCountNode", "value": len },
+        }));
+    }
+    if let Some(defined) = ty.get("defined") {
+        let defined = defined
+            .as_str()
+            .or_else(|| defined.get("name").and_then(Json::as_str))
+            .ok_or("defined type has no name")?;
+        return Ok(json!({ "kind": "definedTypeLinkNode", "name": name(defined) }));
+    }
+    Err(format!("unsupported type {}", ty))
+}
+
+fn struct_fields(fields: &[Json]) -> Result<Vec<Json>, String> {
+    fields
+        .iter()
+        .map(|field| {
+            let field_name = field["name"].as_str().ok_or("field has no name")?;
+            Ok(json!({
+                "kind": "structFieldTypeNode",
+                "name": name(field_name),
+                "type": type_node(&field["type"])?,
+                "docs": docs(field),
+            }))
+        })
+        .collect()
+}
+
+/// A struct with named fields, or a tuple of its field types.
+fn fields_node(fields: &[Json]) -> Result<Json, String> {
+    if fields.first().is_some_and(|f| f.get("name").is_some()) {
+        Ok(json!({ "kind": "structTypeNode", "fields": struct_fields(fields)? }))
+    } else {
+        let items: Result<Vec<Json>, String> = fields.iter().map(type_node).collect();
+        Ok(json!({ "kind": "tupleTypeNode", "items": items? }))
+    }
+}
+
+fn defined_type(ty: &Json) -> Result<Json, String> {
+    let ty_name = ty["name"].as_str().ok_or("type has no name")?;
+    let body = &ty["type"];
+    let node = match body["kind"].as_str() {
+        Some("struct") => fields_node(list(body, "fields"))?,
+        Some("enum") => {
+            let variants: Result<Vec<Json>, String> = list(body, "variants")
+                .iter()
+                .map(|variant| {
+                    let variant_name = name(variant["name"].as_str().ok_or("variant has no name")?);
+                    Ok(match variant.get("fields").and_then(Json::as_array) {
+                        None => json!({ "kind": "enumEmptyVariantTypeNode", "name": variant_name }),
+                        Some(fields) => match fields_node(fields)? {
+                            tuple if tuple["kind"] == "tupleTypeNode" => json!({
+                                "kind": "enumTupleVariantTypeNode",
+                                "name": variant_name,
+                                "tuple": tuple,
+                            }),
+                            fields => json!({
+                                "kind": "enumStructVariantTypeNode",
+                                "name": variant_name,
+                                "struct": fields,
+                            }),
+                        },
+                    })
+                })
+                .collect();
+            json!({ "kind": "enumTypeNode", "variants": variants?, "size": number("u8") })
+        }
+        other => return Err(format!("type {} has unsupported kind {:?}", ty_name, other)),
+    };
+    Ok(json!({
+        "kind": "definedTypeNode",
+        "name": name(ty_name),
+        "type": node,
+        "docs": docs(ty),
+    }))
+}
+
+fn account(raw: &Json, types: &[Json]) -> Result<Json, String> {
+    let raw_name = raw["name"].as_str().ok_or("account has no name")?;
+    let disc = raw
+        .get("discriminator")
+        .and_then(json_bytes)
+        .unwrap_or_else(|| account_discriminator(raw_name).to_vec());
+    // New IDLs describe the layout in `types`, legacy ones inline
+    let layout = raw
+        .get("type")
+        .map(|_| raw)
+        .or_else(|| types.iter().find(|t| t["name"] == raw["name"]))
+        .ok_or_else(|| format!("account {} has no type", raw_name))?;
+    let mut fields = vec![discriminator("structFieldTypeNode", &disc)];
+    fields.extend(struct_fields(list(&layout["type"], "fields"))?);
+    Ok(json!({
+        "kind": "accountNode",
+        "name": name(raw_name),
+        "data": { "kind": "structTypeNode", "fields": fields },
+        "discriminators": first_field(),
+        "docs": docs(raw),
+    }))
+}
+
+/// The `pdaNode` for an account's declared seeds, and the seed values an
+/// instruction fills them from, if they don't read other accounts' data.
+fn pda(account: &Json, args: &[Json]) -> Option<(Json, Vec<Json>)> {
+    let declared = account.get("pda")?;
+    let mut seeds = Vec::new();
+    let mut values = Vec::new();
+    for seed in declared.get("seeds")?.as_array()? {
+        let path = seed.get("path").and_then(Json::as_str);
+        let (ty, value) = match seed.get("kind")?.as_str()? {
+            "const" => {
+                let value = json_bytes(&seed["value"])?;
+                let node = match std::str::from_utf8(&value) {
+                    Ok(text) if text.bytes().all(|b| b.is_ascii_graphic()) => json!({
+                        "kind": "constantPdaSeedNode",
+                        "type": { "kind": "stringTypeNode", "encoding": "utf8" },
+                        "value": { "kind": "stringValueNode", "string": text },
+                    }),
+                    _ => json!({
+                        "kind": "constantPdaSeedNode",
+                        "type": { "kind": "bytesTypeNode" },
+                        "value": bytes_value(&value),
+                    }),
+                };
+                seeds.push(node);
+                continue;
+            }
+            "account" if path?.contains('.') => return None,
+            "account" => (
+                json!({ "kind": "publicKeyTypeNode" }),
+                json!({ "kind": "accountValueNode", "name": name(path?) }),
+            ),
+            "arg" => {
+                let path = path?;
+                let arg = args.iter().find(|a| a["name"].as_str() == Some(path))?;
+                // Seeds hold the raw bytes, without Borsh's length prefix
+                let ty = match arg["type"].as_str()? {
+                    "string" => json!({ "kind": "stringTypeNode", "encoding": "utf8" }),
+                    "bytes" => json!({ "kind": "bytesTypeNode" }),
+                    _ => type_node(&arg["type"]).ok()?,
+                };
+                (
+                    ty,
+                    json!({ "kind": "argumentValueNode", "name": name(path) }),
+                )
+            }
+            _ => return None,
+        };
+        let seed_name = name(path?);
+        seeds.push(json!({
+            "kind": "variablePdaSeedNode",
+            "name": seed_name,
+            "type": ty,
+            "docs": [],
+        }));
+        values.push(json!({ "kind": "pdaSeedValueNode", "name": seed_name, "value": value }));
+    }
+    let mut node = json!({
+        "kind": "pdaNode",
+        "name": name(account["name"].as_str()?),
+        "seeds": seeds,
+        "docs": [],
+    });
+    match declared.get("program") {
+        None => {}
+        Some(program) if program.get("kind")?.as_str()? == "const" => {
+            let key = <[u8; 32]>::try_from(json_bytes(&program["value"])?).ok()?;
+            node["programId"] = json!(Pubkey::new_from_array(key).to_string());
+        }
+        Some(_) => return None,
+    }
+    Some((node, values))
+}
+
+/// Every instruction's node, adding the PDAs its accounts derive to `pdas`.
+fn instruction(ix: &Json, pdas: &mut Vec<Json>) -> Result<Json, String> {
+    let raw_name = ix["name"].as_str().ok_or("instruction has no name")?;
+    let disc = ix
+        .get("discriminator")
+        .and_then(json_bytes)
+        .unwrap_or_else(|| instruction_discriminator(raw_name).to_vec());
+    let args = list(ix, "args");
+
+    let mut slots = Vec::new();
+    flatten(list(ix, "accounts"), &mut slots);
+    let mut accounts = Vec::new();
+    for slot in slots {
+        let slot_name = slot["name"].as_str().ok_or("account has no name")?;
+        let mut node = json!({
+            "kind": "instructionAccountNode",
+            "name": name(slot_name),
+            "isWritable": flag(slot, "writable", "isMut"),
+            "isSigner": flag(slot, "signer", "isSigner"),
+            "isOptional": flag(slot, "optional", "isOptional"),
+            "docs": docs(slot),
+        });
+        if let Some(address) = slot.get("address").and_then(Json::as_str) {
+            Pubkey::from_str(address).map_err(|e| format!("{}: {}", address, e))?;
+            node["defaultValue"] = json!({ "kind": "publicKeyValueNode", "publicKey": address });
+        } else if let Some((found, seeds)) = pda(slot, args) {
+            let same = |other: &Json| {
+                other["seeds"] == found["seeds"] && other.get("programId") == found.get("programId")
+            };
+            let link = match pdas.iter().find(|other| same(other)) {
+                Some(existing) => existing["name"].clone(),
+                None => {
+                    let mut found = found;
+                    // Two accounts of one name with different seeds keep each
+                    if pdas.iter().any(|other| other["name"] == found["name"]) {
+                        found["name"] = json!(name(&format!("{}_{}", raw_name, slot_name)));
+                    }
+                    pdas.push(found);
+                    pdas.last().unwrap()["name"].clone()
+                }
+            };
+            node["defaultValue"] = json!({
+                "kind": "pdaValueNode",
+                "pda": { "kind": "pdaLinkNode", "name": link },
+                "seeds": seeds,
+            });
+        }
+        accounts.push(node);
+    }
+
+    let mut arguments = vec![discriminator("instructionArgumentNode", &disc)];
+    for arg in args {
+        let arg_name = arg["name"].as_str().ok_or("argument has no name")?;
+        arguments.push(json!({
+            "kind": "instructionArgumentNode",
+            "name": name(arg_name),
+            "type": type_node(&arg["type"])?,
+            "docs": docs(arg),
+        }));
+    }
+    Ok(json!({
+        "kind": "instructionNode",
+        "name": name(raw_name),
+        "docs": docs(ix),
+        "optionalAccountStrategy": "programId",
+        "accounts": accounts,
+        "arguments": arguments,
+        "discriminators": first_field(),
+    }))
+}
+
+/// The `rootNode` for the IDL `raw` of the program `program_name`.
+pub fn root_node(program_name: &str, raw: &Json) -> Result<Json, String> {
+    let types = list(raw, "types");
+    // An account's layout is its `accountNode`'s data, not a type of its own
+    let account_names: Vec<&Json> = list(raw, "accounts").iter().map(|a| &a["name"]).collect();
+    let defined: Result<Vec<Json>, String> = types
+        .iter()
+        .filter(|ty| !account_names.contains(&&ty["name"]))
+        .map(defined_type)
+        .collect();
+    let accounts: Result<Vec<Json>, String> = list(raw, "accounts")
+        .iter()
+        .map(|a| account(a, types))
+        .collect();
+    let mut pdas = Vec::new();
+    let instructions: Result<Vec<Json>, String> = list(raw, "instructions")
+        .iter()
+        .map(|ix| instruction(ix, &mut pdas))
+        .collect();
+    let instructions = instructions?;
+    let errors: Vec<Json> = list(raw, "errors")
+        .iter()
+        .map(|error| {
+            json!({
+                "kind": "errorNode",
+                "name": name(error["name"].as_str().unwrap_or_default()),
+                "code": error["code"],
+                "message": error.get("msg").cloned().unwrap_or_else(|| json!("")),
+                "docs": [],
+            })
+        })
+        .collect();
+
+    let version = raw
+        .pointer("/metadata/version")
+        .or_else(|| raw.get("version"))
+        .cloned()
+        .unwrap_or_else(|| json!("0.0.0"));
+    Ok(json!({
+        "kind": "rootNode",
+        "standard": "codama",
+        "version": STANDARD_VERSION,
+        "program": {
+            "kind": "programNode",
+            "name": name(program_name),
+            "publicKey": raw.get("address").cloned().unwrap_or_else(|| json!("")),
+            "version": version,
+            "origin": "anchor",
+            "docs": docs(raw),
+            "accounts": accounts?,
+            "instructions": instructions,
+            "definedTypes": defined?,
+            "pdas": pdas,
+            "errors": errors,
+        },
+        "additionalPrograms": [],
+    }))
+}
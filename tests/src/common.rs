@@ -0,0 +1,219 @@
+//! Setup shared by every program suite
+//!
+//! Each suite used to carry its own copy of the keypair/`.so` loaders and the
+//! same `setup()`; they all reduce to these:
+//!
+//! ```ignore
+//! fn program_id() -> Pubkey {
+//!     common::load_program_id("signer_authorization")
+//! }
+//!
+//! fn setup() -> (LiteSVM, Keypair) {
+//!     common::setup("signer_authorization")
+//! }
+//! ```
+//!
+//! Program ids, `.so` bytes and PDAs are cached for the life of the test
+//! process, so the hundreds of tests in a suite read each file and search
+//! each bump once rather than once per test.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use litesvm::LiteSVM;
+use solsec_guards::ClusterRecord;
+
+use crate::build;
+use crate::sdk::{self, Account, Keypair, Pubkey, Signer, LAMPORTS_PER_SOL};
+
+/// Names a target dir to load programs and IDLs from instead of the root
+/// workspace's, e.g. one built with another Anchor release.
+pub const TARGET_DIR_ENV: &str = "PROGRAMS_TARGET_DIR";
+
+/// The Anchor release the suites load binaries for besides the
+/// workspace's: with the `anchor-031` feature, the programs built with
+/// their own `anchor-031` feature, which live in `target/anchor-031/`.
+pub const ANCHOR_RELEASE: Option<&str> = if cfg!(feature = "anchor-031") {
+    Some("anchor-031")
+} else {
+    None
+};
+
+/// `target/` of the root workspace, or `PROGRAMS_TARGET_DIR` when set.
+///
+/// An overridden dir is used as it is: `AUTO_BUILD_PROGRAMS` only rebuilds
+/// into the workspaces its programs live in.
+pub fn target_dir() -> PathBuf {
+    match std::env::var_os(TARGET_DIR_ENV).filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("target"),
+    }
+}
+
+/// `deploy/` under [`target_dir`], or under its [`ANCHOR_RELEASE`] dir.
+pub fn deploy_dir() -> PathBuf {
+    match ANCHOR_RELEASE {
+        Some(release) => target_dir().join(release).join("deploy"),
+        None => target_dir().join("deploy"),
+    }
+}
+
+/// Address of `<name>-keypair.json` in the root workspace's deploy dir.
+pub fn load_program_id(name: &str) -> Pubkey {
+    load_program_id_in(&deploy_dir(), name)
+}
+
+/// Read `<name>.so` from the root workspace's deploy dir.
+pub fn load_program_bytes(name: &str) -> Arc<[u8]> {
+    load_program_bytes_in(&deploy_dir(), name)
+}
+
+/// [`load_program_id`] for programs built in their own workspace, such as
+/// `programs/amm`.
+pub fn load_program_id_in(dir: &Path, name: &str) -> Pubkey {
+    static IDS: OnceLock<Mutex<HashMap<PathBuf, Pubkey>>> = OnceLock::new();
+    let keypair_path = dir.join(format!("{}-keypair.json", name));
+    cached(&IDS, keypair_path.clone(), || {
+        if build::enabled() {
+            build::ensure_built(dir, name);
+        }
+        let keypair_bytes: Vec<u8> = serde_json::from_str(
+            &std::fs::read_to_string(&keypair_path)
+                .unwrap_or_else(|_| panic!("Failed to read {} keypair", name)),
+        )
+        .expect("Failed to parse keypair");
+
+        Keypair::from_bytes(&keypair_bytes).unwrap().pubkey()
+    })
+}
+
+pub fn load_program_bytes_in(dir: &Path, name: &str) -> Arc<[u8]> {
+    static PROGRAMS: OnceLock<Mutex<HashMap<PathBuf, Arc<[u8]>>>> = OnceLock::new();
+    let so = dir.join(format!("{}.so", name));
+    cached(&PROGRAMS, so.clone(), || {
+        if build::enabled() {
+            build::ensure_built(dir, name);
+        }
+        std::fs::read(&so)
+            .unwrap_or_else(|_| panic!("Failed to read {}.so", name))
+            .into()
+    })
+}
+
+/// `Pubkey::find_program_address`, searched once per seeds and program.
+pub fn find_pda(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    type Seeds = (Vec<Vec<u8>>, Pubkey);
+    static PDAS: OnceLock<Mutex<HashMap<Seeds, (Pubkey, u8)>>> = OnceLock::new();
+    let key = (
+        seeds.iter().map(|seed| seed.to_vec()).collect(),
+        *program_id,
+    );
+    cached(&PDAS, key, || {
+        Pubkey::find_program_address(seeds, program_id)
+    })
+}
+
+// The value for `key`, from `load` the first time it's asked for. The lock
+// is held while loading, so a program is built or read by one test only.
+pub(crate) fn cached<K: Eq + Hash, V: Clone>(
+    cache: &OnceLock<Mutex<HashMap<K, V>>>,
+    key: K,
+    load: impl FnOnce() -> V,
+) -> V {
+    // A test that panicked while loading leaves nothing half-written
+    let mut cache = cache
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    cache.entry(key).or_insert_with(load).clone()
+}
+
+/// Fresh LiteSVM with `program` deployed and a payer holding 10 SOL.
+pub fn setup(program: &str) -> (LiteSVM, Keypair) {
+    setup_in(&deploy_dir(), program)
+}
+
+pub fn setup_in(dir: &Path, program: &str) -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .expect("Airdrop failed");
+    let program_id = load_program_id_in(dir, program);
+    svm.add_program(program_id, &load_program_bytes_in(dir, program));
+    record_cluster(&mut svm, &program_id, DEVNET_GENESIS_HASH);
+    (svm, payer)
+}
+
+/// Deploy `program` under the upgradeable loader with `authority` as its
+/// upgrade authority, as `solana program deploy` does. [`setup`] uses the
+/// non-upgradeable loader, which has no authority to send `record_cluster`.
+pub fn add_upgradeable_program(svm: &mut LiteSVM, program: &str, authority: &Pubkey) -> Pubkey {
+    let program_id = load_program_id(program);
+    let loader = sdk::bpf_loader_upgradeable::ID;
+    let programdata = find_pda(&[program_id.as_ref()], &loader).0;
+
+    // `UpgradeableLoaderState::ProgramData` at slot 0, then the ELF
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(&load_program_bytes(program));
+    // `UpgradeableLoaderState::Program`
+    let mut program_data = vec![2, 0, 0, 0];
+    program_data.extend_from_slice(programdata.as_ref());
+
+    // The programdata first: the program is loaded from it when it's set
+    for (address, data, executable) in
+        [(programdata, data, false), (program_id, program_data, true)]
+    {
+        let account = Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: loader,
+            executable,
+            rent_epoch: 0,
+        };
+        svm.set_account(address, account)
+            .expect("Failed to deploy the upgradeable program");
+    }
+    program_id
+}
+
+/// `EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG`, the cluster programs in
+/// LiteSVM are recorded as deployed to
+pub const DEVNET_GENESIS_HASH: [u8; 32] =
+    sdk::pubkey!("EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG").to_bytes();
+
+/// Where `program_id` keeps its [`ClusterRecord`].
+pub fn cluster_record(program_id: &Pubkey) -> Pubkey {
+    find_pda(&[solsec_guards::CLUSTER_SEED], program_id).0
+}
+
+/// The record `record_cluster` leaves for `genesis_hash`, rent-exempt.
+pub fn cluster_record_account(
+    svm: &LiteSVM,
+    program_id: &Pubkey,
+    genesis_hash: [u8; 32],
+) -> Account {
+    Account {
+        lamports: svm.minimum_balance_for_rent_exemption(ClusterRecord::LEN),
+        data: ClusterRecord { genesis_hash }.to_bytes().to_vec(),
+        owner: *program_id,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Record `program_id` as deployed to the cluster with `genesis_hash`, as
+/// its upgrade authority would right after deploying. LiteSVM deploys
+/// without one, so the record is written directly.
+pub fn record_cluster(svm: &mut LiteSVM, program_id: &Pubkey, genesis_hash: [u8; 32]) {
+    let account = cluster_record_account(svm, program_id, genesis_hash);
+    svm.set_account(cluster_record(program_id), account)
+        .expect("Failed to record the cluster");
+}
@@ -0,0 +1,495 @@
+//! Compute-unit measurement for secure vs vulnerable instructions
+//!
+//! Every secure instruction adds checks its vulnerable twin skips. This
+//! module puts a number on that: run the same legitimate call through both,
+//! assert the secure overhead stays within a threshold, and append the
+//! result to a summary table under `target/compute-units/summary.md`.
+//!
+//! ```ignore
+//! use security_tests::compute::{assert_cu_overhead, CuThreshold};
+//!
+//! assert_cu_overhead(
+//!     "signer_authorization/withdraw",
+//!     &vulnerable_result,
+//!     &secure_result,
+//!     CuThreshold::default().max_overhead(500),
+//! );
+//! ```
+//!
+//! [`CuBudget`] prepends `ComputeBudget` instructions to a transaction, and
+//! [`min_cu_limit`] / [`assert_exhausted`] pin down how far an instruction
+//! gets under a constrained limit:
+//!
+//! ```ignore
+//! let budget = CuBudget::default().unit_limit(50_000).unit_price(10_000);
+//! let result = svm.send_transaction(budget.transaction(&svm, &[ix], &[&payer]));
+//! let logs = assert_exhausted(&result); // last lines show where it stopped
+//! ```
+//!
+//! [`CuHistogram`] counts the compute each instruction used over a fuzz
+//! campaign; see [`crate::sequence::SequenceFuzzer::cu_limits`].
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+
+use crate::sdk::{
+    ComputeBudgetInstruction, Instruction, InstructionError, Keypair, Message, Signer, Transaction,
+    TransactionError,
+};
+
+/// Highest limit `SetComputeUnitLimit` accepts.
+pub const MAX_CU_LIMIT: u32 = 1_400_000;
+
+/// Limit an instruction gets when the transaction sets none.
+pub const DEFAULT_CU_LIMIT: u32 = 200_000;
+
+/// Limits a compute-aware sequence fuzzer picks from: starved, tight, the
+/// default, and the most a transaction may ask for.
+pub const FUZZED_CU_LIMITS: [u32; 5] = [1_000, 20_000, 50_000, DEFAULT_CU_LIMIT, MAX_CU_LIMIT];
+
+/// Upper bounds of the histogram buckets, the last one catching the rest.
+const CU_BUCKETS: [u64; 9] = [
+    1_000,
+    5_000,
+    10_000,
+    25_000,
+    50_000,
+    100_000,
+    200_000,
+    400_000,
+    u64::MAX,
+];
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Compute units consumed by a transaction, whether it succeeded or not.
+pub trait ComputeUnits {
+    fn compute_units(&self) -> u64;
+}
+
+impl ComputeUnits for TransactionResult {
+    fn compute_units(&self) -> u64 {
+        match self {
+            Ok(meta) => meta.compute_units_consumed,
+            Err(failed) => failed.meta.compute_units_consumed,
+        }
+    }
+}
+
+/// Upper bounds on how much more the secure instruction may cost.
+///
+/// Both limits apply; `None` disables a limit.
+#[derive(Debug, Clone, Copy)]
+pub struct CuThreshold {
+    /// Extra CU allowed on top of the vulnerable instruction
+    pub max_overhead: Option<u64>,
+    /// `secure / vulnerable` ratio allowed
+    pub max_ratio: Option<f64>,
+}
+
+impl Default for CuThreshold {
+    /// Secure code may cost up to twice as much. Individual checks are
+    /// cheap, so anything beyond that usually means an accidental loop or
+    /// an extra CPI rather than a new constraint.
+    fn default() -> Self {
+        Self {
+            max_overhead: None,
+            max_ratio: Some(2.0),
+        }
+    }
+}
+
+impl CuThreshold {
+    pub fn max_overhead(mut self, cu: u64) -> Self {
+        self.max_overhead = Some(cu);
+        self
+    }
+
+    pub fn max_ratio(mut self, ratio: f64) -> Self {
+        self.max_ratio = Some(ratio);
+        self
+    }
+
+    pub fn unlimited() -> Self {
+        Self {
+            max_overhead: None,
+            max_ratio: None,
+        }
+    }
+}
+
+/// One row of the summary table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuComparison {
+    pub name: String,
+    pub vulnerable: u64,
+    pub secure: u64,
+}
+
+impl CuComparison {
+    pub fn overhead(&self) -> i64 {
+        self.secure as i64 - self.vulnerable as i64
+    }
+
+    pub fn ratio(&self) -> f64 {
+        if self.vulnerable == 0 {
+            return f64::INFINITY;
+        }
+        self.secure as f64 / self.vulnerable as f64
+    }
+
+    /// Returns a description of the first limit that is exceeded.
+    pub fn check(&self, threshold: CuThreshold) -> Result<(), String> {
+        if let Some(max) = threshold.max_overhead {
+            if self.overhead() > max as i64 {
+                return Err(format!(
+                    "secure costs {} CU more than vulnerable (limit {})",
+                    self.overhead(),
+                    max
+                ));
+            }
+        }
+        if let Some(max) = threshold.max_ratio {
+            if self.ratio() > max {
+                return Err(format!(
+                    "secure costs {:.2}x vulnerable (limit {:.2}x)",
+                    self.ratio(),
+                    max
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn row(&self) -> String {
+        format!(
+            "| {} | {} | {} | {:+} | {:.2}x |",
+            self.name,
+            self.vulnerable,
+            self.secure,
+            self.overhead(),
+            self.ratio()
+        )
+    }
+}
+
+/// Directory the summary table is written to.
+pub fn report_dir() -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"));
+    target.join("compute-units")
+}
+
+/// Compare the CU cost of the same call through both instructions.
+///
+/// Both transactions must succeed - a rejected transaction stops early and
+/// says nothing about the cost of the checks. The comparison is recorded in
+/// the summary table before the threshold is enforced, so a failing run
+/// still shows the numbers.
+pub fn assert_cu_overhead(
+    name: &str,
+    vulnerable: &TransactionResult,
+    secure: &TransactionResult,
+    threshold: CuThreshold,
+) -> CuComparison {
+    if let Err(e) = vulnerable {
+        panic!("{}: vulnerable transaction failed: {:?}", name, e);
+    }
+    if let Err(e) = secure {
+        panic!("{}: secure transaction failed: {:?}", name, e);
+    }
+
+    let comparison = CuComparison {
+        name: name.to_string(),
+        vulnerable: vulnerable.compute_units(),
+        secure: secure.compute_units(),
+    };
+    println!("{}", comparison.row());
+    record(&comparison);
+
+    if let Err(msg) = comparison.check(threshold) {
+        panic!("{}: compute-unit regression: {}", name, msg);
+    }
+    comparison
+}
+
+// Suites run as separate processes, so each comparison gets its own row
+// file and the table is rebuilt from all of them after every write.
+fn record(comparison: &CuComparison) {
+    let rows = report_dir().join("rows");
+    if std::fs::create_dir_all(&rows).is_err() {
+        return;
+    }
+    let file = rows.join(comparison.name.replace('/', "__"));
+    if std::fs::write(&file, comparison.row()).is_err() {
+        return;
+    }
+    write_summary();
+}
+
+/// Rebuild `summary.md` from every recorded comparison.
+pub fn write_summary() {
+    let dir = report_dir();
+    let Ok(entries) = std::fs::read_dir(dir.join("rows")) else {
+        return;
+    };
+
+    let mut rows: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .collect();
+    rows.sort();
+
+    let mut table = String::from(
+        "# Compute Units: Secure vs Vulnerable\n\n\
+         | Instruction | Vulnerable CU | Secure CU | Overhead | Ratio |\n\
+         |-------------|---------------|-----------|----------|-------|\n",
+    );
+    for row in rows {
+        table.push_str(&row);
+        table.push('\n');
+    }
+
+    // Rename so a concurrent reader never sees a half-written table
+    let tmp = dir.join(format!("summary.md.{}", std::process::id()));
+    if std::fs::write(&tmp, table).is_ok() {
+        let _ = std::fs::rename(&tmp, dir.join("summary.md"));
+    }
+}
+
+/// `ComputeBudget` instructions to put in front of a transaction's own.
+///
+/// Without a limit a transaction gets LiteSVM's default of 200 000 CU per
+/// instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CuBudget {
+    /// `SetComputeUnitLimit`
+    pub unit_limit: Option<u32>,
+    /// `SetComputeUnitPrice`, in micro-lamports per CU
+    pub unit_price: Option<u64>,
+}
+
+impl CuBudget {
+    pub fn unit_limit(mut self, units: u32) -> Self {
+        self.unit_limit = Some(units);
+        self
+    }
+
+    pub fn unit_price(mut self, micro_lamports: u64) -> Self {
+        self.unit_price = Some(micro_lamports);
+        self
+    }
+
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let limit = self
+            .unit_limit
+            .map(ComputeBudgetInstruction::set_compute_unit_limit);
+        let price = self
+            .unit_price
+            .map(ComputeBudgetInstruction::set_compute_unit_price);
+        limit.into_iter().chain(price).collect()
+    }
+
+    /// The budget instructions followed by `instructions`.
+    pub fn prepend(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let mut all = self.instructions();
+        all.extend_from_slice(instructions);
+        all
+    }
+
+    /// A signed legacy transaction of [`prepend`](Self::prepend), paid by
+    /// `signers[0]`.
+    pub fn transaction(
+        &self,
+        svm: &LiteSVM,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Transaction {
+        let payer = signers.first().expect("a transaction needs a fee payer");
+        let msg = Message::new(&self.prepend(instructions), Some(&payer.pubkey()));
+        Transaction::new(signers, msg, svm.latest_blockhash())
+    }
+
+    /// Lamports a cluster charges on top of signature fees: limit times
+    /// price, rounded up. LiteSVM does not charge it.
+    pub fn priority_fee(&self) -> u64 {
+        let limit = self.unit_limit.unwrap_or(0) as u128;
+        let price = self.unit_price.unwrap_or(0) as u128;
+        (limit * price).div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+    }
+}
+
+/// Send `instructions` with a limit of `units` on a copy of `svm`.
+#[allow(clippy::result_large_err)]
+pub fn run_with_limit(
+    svm: &LiteSVM,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+    units: u32,
+) -> TransactionResult {
+    let tx = CuBudget::default()
+        .unit_limit(units)
+        .transaction(svm, instructions, signers);
+    svm.clone().send_transaction(tx)
+}
+
+/// Smallest limit under which `instructions` succeed, found by bisection on
+/// copies of `svm`, or `None` if they fail even at [`MAX_CU_LIMIT`]. The
+/// limit covers the `SetComputeUnitLimit` instruction itself.
+pub fn min_cu_limit(
+    svm: &LiteSVM,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Option<u32> {
+    let passes = |units| run_with_limit(svm, instructions, signers, units).is_ok();
+    if !passes(MAX_CU_LIMIT) {
+        return None;
+    }
+    let (mut low, mut high) = (0, MAX_CU_LIMIT);
+    while low + 1 < high {
+        let mid = low + (high - low) / 2;
+        if passes(mid) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    Some(high)
+}
+
+/// Whether a transaction failed by running out of compute.
+pub fn is_exhausted(err: &TransactionError) -> bool {
+    matches!(
+        err,
+        TransactionError::InstructionError(_, InstructionError::ComputationalBudgetExceeded)
+    )
+}
+
+/// Panic unless `result` ran out of compute. Returns the program logs up to
+/// that point, so a test can see how many iterations fit.
+#[track_caller]
+pub fn assert_exhausted(result: &TransactionResult) -> &[String] {
+    match result {
+        Ok(meta) => panic!(
+            "expected the compute budget to run out, but the transaction succeeded \
+             using {} CU\n{}",
+            meta.compute_units_consumed,
+            meta.logs.join("\n")
+        ),
+        Err(failed) => {
+            assert!(
+                is_exhausted(&failed.err),
+                "expected the compute budget to run out, got {:?}\n{}",
+                failed.err,
+                failed.meta.logs.join("\n")
+            );
+            &failed.meta.logs
+        }
+    }
+}
+
+/// One instruction run under a given compute limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CuRun {
+    pub limit: u32,
+    pub consumed: u64,
+    /// It stopped on `ComputationalBudgetExceeded`
+    pub exhausted: bool,
+}
+
+impl CuRun {
+    /// Whether it needs more than `budget`: it used more than that, or ran
+    /// out under a limit of at least `budget`. Running out under a smaller
+    /// limit says nothing about the budget.
+    pub fn exceeds(&self, budget: u32) -> bool {
+        self.consumed > budget as u64 || (self.exhausted && self.limit >= budget)
+    }
+}
+
+/// How the runs of one instruction were spread.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CuStats {
+    pub runs: u64,
+    pub exhausted: u64,
+    pub min: u64,
+    pub max: u64,
+    /// Runs per bucket, see [`CuHistogram::render`]
+    pub buckets: [u64; CU_BUCKETS.len()],
+}
+
+/// Compute units used per instruction over a fuzz campaign, kept as bucket
+/// counts so a long campaign doesn't grow it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CuHistogram {
+    instructions: BTreeMap<String, CuStats>,
+}
+
+impl CuHistogram {
+    pub fn record(&mut self, instruction: &str, run: CuRun) {
+        let stats = self
+            .instructions
+            .entry(instruction.to_string())
+            .or_default();
+        if stats.runs == 0 {
+            stats.min = run.consumed;
+        }
+        stats.runs += 1;
+        stats.exhausted += run.exhausted as u64;
+        stats.min = stats.min.min(run.consumed);
+        stats.max = stats.max.max(run.consumed);
+        let bucket = CU_BUCKETS
+            .iter()
+            .position(|&upper| run.consumed <= upper)
+            .unwrap();
+        stats.buckets[bucket] += 1;
+    }
+
+    pub fn stats(&self, instruction: &str) -> Option<&CuStats> {
+        self.instructions.get(instruction)
+    }
+
+    /// A markdown table with one row per instruction and one column per
+    /// bucket; a bucket holds the runs that used at most its bound.
+    pub fn render(&self, title: &str) -> String {
+        let mut table = format!(
+            "# Compute Units: {}\n\n| Instruction | Runs | Exhausted | Min | Max |",
+            title
+        );
+        for upper in &CU_BUCKETS[..CU_BUCKETS.len() - 1] {
+            write!(table, " ≤{} |", upper).unwrap();
+        }
+        table.push_str(" more |\n|---|---|---|---|---|");
+        table.push_str(&"---|".repeat(CU_BUCKETS.len()));
+        table.push('\n');
+        for (name, stats) in &self.instructions {
+            write!(
+                table,
+                "| {} | {} | {} | {} | {} |",
+                name, stats.runs, stats.exhausted, stats.min, stats.max
+            )
+            .unwrap();
+            for count in &stats.buckets {
+                write!(table, " {} |", count).unwrap();
+            }
+            table.push('\n');
+        }
+        table
+    }
+
+    /// Write [`render`](Self::render) to `histograms/<name>.md` under
+    /// [`report_dir`] and return the path.
+    pub fn write(&self, name: &str) -> std::io::Result<PathBuf> {
+        let dir = report_dir().join("histograms");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.md", name.replace('/', "__")));
+        let tmp = path.with_extension(format!("md.{}", std::process::id()));
+        std::fs::write(&tmp, self.render(name))?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+}
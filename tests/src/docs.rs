@@ -0,0 +1,319 @@
+//! Writeups generated from the registry and what the exploits really do
+//!
+//! [`writeup`] renders one Markdown page per [`CATALOG`] entry into
+//! `generated-docs/`:
+//!
+//! - the vulnerability: the registry's class, severity, CVSS vector and
+//!   weakness ids, with the program README's explanation;
+//! - the exploit walkthrough: the [`Demo`](crate::demo::Demo) played in
+//!   LiteSVM, each transaction with its result, balance changes and program
+//!   logs, or for programs without one the README's attack scenario, the
+//!   exploit tests and their golden snapshots;
+//! - the mitigation: its summary and a diff of each vulnerable source
+//!   against its secure counterpart.
+//!
+//! Pubkeys in logs are redacted as in golden snapshots, so a page only
+//! changes when the code or the registry does. `cargo xtask docs --check`
+//! fails when a page on disk is out of date.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use solsec_registry::{Vulnerability, CATALOG};
+
+use crate::demo::{self, ExploitRun, SideRun};
+use crate::golden::{self, golden_dir};
+
+/// The first line of every generated page
+pub const GENERATED: &str =
+    "<!-- Generated by `cargo xtask docs` from solsec_registry and the exploit demos. Do not edit. -->";
+
+/// Where the pages are written.
+pub fn docs_dir() -> PathBuf {
+    repo_root().join("generated-docs")
+}
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+/// The page for `v`, playing its demo if it has one.
+pub fn writeup(v: &'static Vulnerability) -> String {
+    let run = demo::find(v.program).map(demo::play);
+    render(v, run.as_ref())
+}
+
+/// The page for `v` given its demo's run, `None` without a demo.
+pub fn render(v: &Vulnerability, run: Option<&ExploitRun>) -> String {
+    let root = repo_root();
+    let readme = std::fs::read_to_string(root.join(v.readme())).unwrap_or_default();
+    let mut out = String::new();
+    let _ = writeln!(out, "{}\n", GENERATED);
+    let _ = writeln!(out, "# {}\n", v.title);
+    let _ = writeln!(
+        out,
+        "- Program: [`programs/{p}`](../programs/{p}) ({})",
+        v.framework.name(),
+        p = v.program
+    );
+    let _ = writeln!(out, "- Class: {}", v.class.name());
+    let _ = writeln!(
+        out,
+        "- Severity: {} (CVSS {:.1}, `{}`)",
+        v.severity.name(),
+        v.score.base_score(),
+        v.score.vector()
+    );
+    let weaknesses: Vec<String> = v
+        .weaknesses
+        .iter()
+        .map(|w| format!("[{}]({})", w.id(), w.url()))
+        .collect();
+    let _ = writeln!(out, "- Weaknesses: {}", weaknesses.join(", "));
+    let _ = writeln!(out, "- Vulnerable: {}", code_list(v.vulnerable));
+    let _ = writeln!(out, "- Tests: `cargo test --test {}`\n", v.suite);
+
+    let _ = writeln!(out, "## The vulnerability\n");
+    match section(
+        &readme,
+        &[
+            "The Vulnerability",
+            "Vulnerabilities Demonstrated",
+            "Overview",
+        ],
+    ) {
+        Some(text) => {
+            let _ = writeln!(out, "{}\n", text);
+        }
+        None => {
+            let _ = writeln!(out, "See [{}](../{}).\n", v.readme(), v.readme());
+        }
+    }
+
+    let _ = writeln!(out, "## Exploit walkthrough\n");
+    match run {
+        Some(run) => walkthrough(&mut out, run),
+        None => scripted(&mut out, v, &readme),
+    }
+
+    let _ = writeln!(out, "## Mitigation\n");
+    let _ = writeln!(out, "{}.\n", v.mitigation.summary.trim_end_matches('.'));
+    let _ = writeln!(
+        out,
+        "Secure instructions: {}.\n",
+        code_list(v.mitigation.instructions)
+    );
+    let sources = v.sources;
+    if sources.len() < 2 {
+        let _ = writeln!(out, "Both versions live in {}.", code_list(sources));
+    } else {
+        let (vulnerable, secure) = sources.split_at(sources.len() / 2);
+        for (vulnerable, secure) in vulnerable.iter().zip(secure) {
+            let _ = writeln!(out, "`{}` -> `{}`\n", vulnerable, secure);
+            let _ = writeln!(out, "```diff\n{}```\n", diff(&root, vulnerable, secure));
+        }
+    }
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    out.push('\n');
+    out
+}
+
+fn walkthrough(out: &mut String, run: &ExploitRun) {
+    let _ = writeln!(
+        out,
+        "Played in LiteSVM by `cargo xtask run-exploit {}`: the same attack against \
+         the vulnerable instruction, then the secure one.\n",
+        run.program
+    );
+    for side in [&run.vulnerable, &run.secure] {
+        side_section(out, side);
+    }
+    let _ = writeln!(
+        out,
+        "Exploited: {}. Blocked by the fix: {}.\n",
+        yes_no(run.outcome.exploited),
+        yes_no(run.outcome.blocked)
+    );
+}
+
+fn side_section(out: &mut String, side: &SideRun) {
+    let _ = writeln!(out, "### `{}`\n", side.instruction);
+    for (i, tx) in side.transactions.iter().enumerate() {
+        let result = match (&tx.anchor_error, tx.error_code, &tx.error) {
+            _ if tx.ok => "landed".to_string(),
+            (Some(name), Some(code), _) => format!("failed with `{}` ({})", name, code),
+            (_, _, Some(error)) => format!("failed: `{}`", error),
+            _ => "failed".to_string(),
+        };
+        let _ = writeln!(out, "{}. {}: {}", i + 1, tx.narration, result);
+        let _ = writeln!(out, "   - Compute units: {}", tx.compute_units);
+        for balance in tx.balances.iter().filter(|b| b.delta != 0) {
+            let _ = writeln!(
+                out,
+                "   - `{}`: {} -> {} lamports ({:+})",
+                balance.label, balance.before, balance.after, balance.delta
+            );
+        }
+        out.push('\n');
+        log_block(out, &golden::redact(&tx.logs), "   ");
+    }
+}
+
+// Programs without a demo: the README's scenario and the suite's artifacts
+fn scripted(out: &mut String, v: &Vulnerability, readme: &str) {
+    if let Some(text) = section(readme, &["Attack Scenario"]) {
+        let _ = writeln!(out, "{}\n", text);
+    }
+    let _ = writeln!(
+        out,
+        "The suite `{}` exploits it in {}.\n",
+        v.suite,
+        code_list(v.coverage.exploit)
+    );
+    for (case, snapshot) in snapshots(v.suite) {
+        let _ = writeln!(out, "### `{}`\n", case);
+        // The format `TxSnapshot::render` writes
+        let mut lines = snapshot.lines();
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|l| l.strip_prefix(name))
+                .unwrap_or_default()
+                .to_string()
+        };
+        let status = field("status: ");
+        let compute_units = field("compute_units: ");
+        let _ = writeln!(
+            out,
+            "- Status: `{}`\n- Compute units: {}\n",
+            status, compute_units
+        );
+        let logs: Vec<String> = lines
+            .skip(1)
+            .map(|l| l.strip_prefix("  ").unwrap_or(l).to_string())
+            .collect();
+        log_block(out, &logs, "");
+    }
+}
+
+fn log_block(out: &mut String, logs: &[String], indent: &str) {
+    if logs.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "{}```text", indent);
+    for log in logs {
+        let _ = writeln!(out, "{}{}", indent, log);
+    }
+    let _ = writeln!(out, "{}```\n", indent);
+}
+
+// `tests/golden/<suite>/<case>.snap`, sorted by case
+fn snapshots(suite: &str) -> Vec<(String, String)> {
+    let mut snapshots: Vec<(String, String)> = std::fs::read_dir(golden_dir().join(suite))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "snap"))
+                .filter_map(|p| {
+                    let case = p.file_stem()?.to_string_lossy().into_owned();
+                    Some((case, std::fs::read_to_string(&p).ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    snapshots.sort();
+    snapshots
+}
+
+/// The body of the first `## ` section of `markdown` titled one of
+/// `titles`, in their order of preference.
+pub fn section(markdown: &str, titles: &[&str]) -> Option<String> {
+    titles.iter().find_map(|title| {
+        let heading = format!("## {}", title);
+        let mut lines = markdown.lines().skip_while(|l| l.trim_end() != heading);
+        lines.next()?;
+        let mut fenced = false;
+        let body: Vec<&str> = lines
+            .take_while(|l| {
+                if l.starts_with("```") {
+                    fenced = !fenced;
+                }
+                fenced || !l.starts_with("## ")
+            })
+            .collect();
+        let body = body.join("\n").trim().to_string();
+        (!body.is_empty()).then_some(body)
+    })
+}
+
+fn diff(root: &Path, vulnerable: &str, secure: &str) -> String {
+    // Exits 1 when the files differ, which they always should
+    let output = Command::new("git")
+        .args(["diff", "--no-index", "--no-color", "--", vulnerable, secure])
+        .current_dir(root)
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run git diff: {}", e));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn code_list(items: &[&str]) -> String {
+    items
+        .iter()
+        .map(|i| format!("`{}`", i))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// `generated-docs/README.md`: a table linking every page.
+pub fn index() -> String {
+    let mut out = format!(
+        "{}\n\n# Vulnerability writeups\n\n\
+         | Vulnerability | Class | Severity | Writeup |\n\
+         |---------------|-------|----------|---------|\n",
+        GENERATED
+    );
+    for v in CATALOG {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | [{p}.md]({p}.md) |",
+            v.title,
+            v.class.name(),
+            v.severity.name(),
+            p = v.program
+        );
+    }
+    out
+}
+
+/// Pages that differ from what's in `dir`, or are missing from it.
+pub fn stale(dir: &Path, pages: &[(String, String)]) -> Vec<String> {
+    pages
+        .iter()
+        .filter(|(file, text)| std::fs::read_to_string(dir.join(file)).ok().as_ref() != Some(text))
+        .map(|(file, _)| file.clone())
+        .collect()
+}
+
+/// Write `pages` (file name, contents) to `dir`.
+pub fn write(dir: &Path, pages: &[(String, String)]) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for (file, text) in pages {
+        std::fs::write(dir.join(file), text)?;
+    }
+    Ok(())
+}
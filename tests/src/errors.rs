@@ -0,0 +1,230 @@
+//! Typed error assertions for failed transactions
+//!
+//! `assert!(result.is_err())` passes for any failure, including a fixture
+//! with the wrong discriminator or a missing account. `assert_err!` names
+//! the failure the test is about:
+//!
+//! ```ignore
+//! use security_tests::assert_err;
+//!
+//! // Program errors, by the variant of the program's #[error_code] enum
+//! assert_err!(result, StakeError::Unauthorized);
+//! // Anchor framework errors
+//! assert_err!(result, ErrorCode::ConstraintSeeds);
+//! // Runtime errors, e.g. from Pinocchio programs or the system program
+//! assert_err!(result, InstructionError::IllegalOwner);
+//! assert_err!(result, InstructionError::Custom(1));
+//! ```
+//!
+//! Anchor errors are matched against the `AnchorError ... Error Code: <name>`
+//! line the program logs, and the logged number must equal the instruction's
+//! `Custom` code. When `target/idl/` has an IDL for the failing program, a
+//! program error must also be declared there with that code, which catches
+//! a stale IDL or an error enum that was reordered since the last build.
+//!
+//! [`assert_class`] asserts the kind of failure instead, by its
+//! [`ErrorClass`], for a check that holds across programs whatever each
+//! calls its error.
+
+use litesvm::types::{FailedTransactionMetadata, TransactionResult};
+pub use solsec_errors::ErrorClass;
+
+use crate::idl::{idl_dir, Idl};
+use crate::sdk::{InstructionError, Pubkey, TransactionError};
+
+/// An error parsed from Anchor's `AnchorError ...` log line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorError {
+    /// Variant name, e.g. `ConstraintSeeds` or `Unauthorized`
+    pub name: String,
+    pub number: u32,
+    pub message: String,
+    /// Set for constraint errors raised on a specific account
+    pub account: Option<String>,
+}
+
+impl AnchorError {
+    /// The last Anchor error in the logs, i.e. the one that aborted the
+    /// transaction when several programs in a CPI chain logged one.
+    pub fn from_logs(logs: &[String]) -> Option<Self> {
+        logs.iter().rev().find_map(|line| Self::parse(line))
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.strip_prefix("Program log: ").unwrap_or(line);
+        let rest = line.strip_prefix("AnchorError ")?;
+
+        let account = rest
+            .strip_prefix("caused by account: ")
+            .and_then(|r| r.split(". Error Code: ").next())
+            .map(str::to_string);
+
+        let (_, rest) = rest.split_once("Error Code: ")?;
+        let (name, rest) = rest.split_once(". Error Number: ")?;
+        let (number, message) = rest.split_once(". Error Message: ")?;
+
+        Some(Self {
+            name: name.to_string(),
+            number: number.parse().ok()?,
+            message: message.strip_suffix('.').unwrap_or(message).to_string(),
+            account,
+        })
+    }
+}
+
+/// The `InstructionError` a failed transaction aborted with, if any.
+pub fn instruction_error(failed: &FailedTransactionMetadata) -> Option<&InstructionError> {
+    match &failed.err {
+        TransactionError::InstructionError(_, err) => Some(err),
+        _ => None,
+    }
+}
+
+/// The program whose instruction failed, from the runtime's
+/// `Program <id> failed: ...` log line.
+pub fn failing_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().rev().find_map(|line| {
+        let rest = line.strip_prefix("Program ")?;
+        let (id, _) = rest.split_once(" failed: ")?;
+        id.parse().ok()
+    })
+}
+
+// IDLs are bound to deploy keypairs, so find the one whose keypair gives
+// this program id. Programs without a keypair (never built) are skipped.
+fn idl_for(program_id: &Pubkey) -> Option<Idl> {
+    let deploy = idl_dir().parent()?.join("deploy");
+    std::fs::read_dir(idl_dir())
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            let stem = path.file_stem()?.to_str()?.to_string();
+            let keypair = deploy.join(format!("{}-keypair.json", stem));
+            (path.extension()? == "json" && keypair.exists()).then_some((path, stem))
+        })
+        .find(|(_, stem)| crate::load_program_id(stem) == *program_id)
+        .map(|(path, _)| Idl::from_path(&path, *program_id))
+}
+
+/// Assert `result` failed with `expected`, written as `Enum::Variant`.
+///
+/// Use through [`assert_err!`](crate::assert_err), which passes the path
+/// tokens as written.
+pub fn assert_error(result: &TransactionResult, expected: &str) {
+    let expected: String = expected.chars().filter(|c| !c.is_whitespace()).collect();
+    let (kind, variant) = expected
+        .split_once("::")
+        .unwrap_or_else(|| panic!("assert_err!: expected `Enum::Variant`, got `{}`", expected));
+
+    let failed = match result {
+        Ok(meta) => panic!(
+            "expected {}, but the transaction succeeded\nlogs:\n  {}",
+            expected,
+            meta.logs.join("\n  ")
+        ),
+        Err(failed) => failed,
+    };
+    let logs = &failed.meta.logs;
+    let context = || format!("err: {:?}\nlogs:\n  {}", failed.err, logs.join("\n  "));
+
+    match kind {
+        "TransactionError" => {
+            let got = format!("{:?}", failed.err);
+            assert!(
+                got == variant || got.starts_with(&format!("{}(", variant)),
+                "expected {}, got {}",
+                expected,
+                context()
+            );
+        }
+        "InstructionError" => {
+            let got = instruction_error(failed).map(|e| format!("{:?}", e));
+            assert_eq!(
+                got.as_deref(),
+                Some(variant),
+                "expected {}, got {}",
+                expected,
+                context()
+            );
+        }
+        _ => {
+            let Some(anchor) = AnchorError::from_logs(logs) else {
+                panic!("expected {}, but no AnchorError was logged\n{}", expected, context());
+            };
+            assert_eq!(
+                anchor.name,
+                variant,
+                "expected {}, got {} ({}: {})\n{}",
+                expected,
+                anchor.name,
+                anchor.number,
+                anchor.message,
+                context()
+            );
+            assert_eq!(
+                instruction_error(failed),
+                Some(&InstructionError::Custom(anchor.number)),
+                "{} was logged, but the transaction failed with a different error\n{}",
+                expected,
+                context()
+            );
+
+            // Framework errors live in anchor_lang::error::ErrorCode, not the IDL
+            if kind != "ErrorCode" {
+                if let Some(idl) = failing_program(logs).and_then(|pid| idl_for(&pid)) {
+                    let declared = idl.error(variant);
+                    assert!(
+                        declared.is_some_and(|e| e.code == anchor.number),
+                        "{}: {} is not declared in the IDL with code {}",
+                        idl.name,
+                        expected,
+                        anchor.number
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// What kind of failure `failed` is: a program error by its variant and
+/// code, an Anchor framework error by its name, or a runtime error.
+pub fn error_class(failed: &FailedTransactionMetadata) -> Option<ErrorClass> {
+    if let Some(anchor) = AnchorError::from_logs(&failed.meta.logs) {
+        return if anchor.number >= solsec_errors::ERROR_CODE_OFFSET {
+            solsec_errors::program_class(&anchor.name, anchor.number)
+        } else {
+            solsec_errors::anchor_class(&anchor.name)
+        };
+    }
+    let runtime = format!("{:?}", instruction_error(failed)?);
+    solsec_errors::runtime_class(&runtime)
+}
+
+/// Assert `result` failed with an error of class `expected`.
+pub fn assert_class(result: &TransactionResult, expected: ErrorClass) {
+    let failed = match result {
+        Ok(meta) => panic!(
+            "expected a {} error, but the transaction succeeded\nlogs:\n  {}",
+            expected,
+            meta.logs.join("\n  ")
+        ),
+        Err(failed) => failed,
+    };
+    assert_eq!(
+        error_class(failed),
+        Some(expected),
+        "expected a {} error, got {:?}\nlogs:\n  {}",
+        expected,
+        failed.err,
+        failed.meta.logs.join("\n  ")
+    );
+}
+
+/// Assert a transaction failed with a specific error. See [`crate::errors`].
+#[macro_export]
+macro_rules! assert_err {
+    ($result:expr, $($expected:tt)+) => {
+        $crate::errors::assert_error(&$result, stringify!($($expected)+))
+    };
+}
@@ -0,0 +1,77 @@
+//! Security events out of LiteSVM and RPC transaction metadata
+//!
+//! The programs emit a `solsec_events::SecurityEvent` through `emit_cpi!`
+//! on authority changes and withdrawals; these read them back from a
+//! transaction's inner instructions so a suite can assert on them:
+//!
+//! ```ignore
+//! let meta = svm.send_transaction(tx.clone()).unwrap();
+//! let events = events::from_litesvm(&tx.message, &meta);
+//! assert!(matches!(events[..], [SecurityEvent { kind: EventKind::Withdrawal { .. }, .. }]));
+//! ```
+
+use litesvm::types::TransactionMetadata;
+use serde_json::Value as Json;
+use std::str::FromStr;
+
+pub use solsec_events::{EventKind, SecurityEvent};
+
+use crate::sdk::{Message, Pubkey};
+
+/// The events `meta` recorded for `message`, in execution order.
+pub fn from_litesvm(message: &Message, meta: &TransactionMetadata) -> Vec<SecurityEvent> {
+    let keys = &message.account_keys;
+    solsec_events::decode_inner(
+        meta.inner_instructions
+            .iter()
+            .flatten()
+            .filter_map(|inner| {
+                let program = keys.get(inner.instruction.program_id_index as usize)?;
+                Some((program.to_bytes(), &inner.instruction.data[..]))
+            }),
+    )
+}
+
+/// The events of a `getTransaction` result with `"encoding": "json"`,
+/// including a v0 transaction's looked-up accounts.
+pub fn from_rpc(result: &Json) -> Result<Vec<SecurityEvent>, String> {
+    let keys = |value: &Json| -> Result<Vec<Pubkey>, String> {
+        value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|key| {
+                key.as_str()
+                    .and_then(|key| Pubkey::from_str(key).ok())
+                    .ok_or_else(|| format!("bad account key {}", key))
+            })
+            .collect()
+    };
+    // Static keys, then the lookup tables' writable and readonly ones
+    let mut accounts = keys(&result["transaction"]["message"]["accountKeys"])?;
+    let loaded = &result["meta"]["loadedAddresses"];
+    accounts.extend(keys(&loaded["writable"])?);
+    accounts.extend(keys(&loaded["readonly"])?);
+
+    let mut inner = Vec::new();
+    for group in result["meta"]["innerInstructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        for ix in group["instructions"].as_array().into_iter().flatten() {
+            let program = ix["programIdIndex"]
+                .as_u64()
+                .and_then(|index| accounts.get(index as usize))
+                .ok_or_else(|| format!("bad program index in {}", ix))?;
+            let data = ix["data"]
+                .as_str()
+                .and_then(|data| bs58::decode(data).into_vec().ok())
+                .ok_or_else(|| format!("bad instruction data in {}", ix))?;
+            inner.push((program.to_bytes(), data));
+        }
+    }
+    Ok(solsec_events::decode_inner(
+        inner.iter().map(|(program, data)| (*program, &data[..])),
+    ))
+}
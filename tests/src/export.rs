@@ -0,0 +1,207 @@
+//! Exploit transactions on disk, for tools other than this harness
+//!
+//! [`export`] plays a [`Demo`] the way `run-exploit` does and writes what it
+//! sent under `target/exports/<program>/`, so explorer simulators,
+//! `solana-test-validator` or other replay tooling can run the attack
+//! without the Rust tests:
+//!
+//! - `bundle.json`: the [`Bundle`], with every signed transaction of both
+//!   sides in base64 wire format and the result LiteSVM gave it
+//! - `accounts/<address>.json`: each account the transactions name, as it
+//!   was before the attack, in the fixture JSON `solana-test-validator
+//!   --account` loads
+//! - `keypairs/<label>.json`: the demo's keypairs in the `solana` CLI's
+//!   format, for re-signing against another blockhash
+//!
+//! ```bash
+//! cargo xtask export-exploits signer-authorization
+//! ```
+//!
+//! Both sides start from the same state, so one set of accounts serves
+//! both. The transactions are signed for LiteSVM's blockhash: a validator
+//! needs them re-signed, a simulator with `replaceRecentBlockhash` doesn't.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::demo::{Demo, SEED};
+use crate::fixtures::AccountFixture;
+use crate::scenario::{seeded_keypair, Scenario, Step};
+use crate::sdk::{self, VersionedTransaction};
+
+/// Where exports are written.
+pub fn exports_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("target/exports")
+}
+
+/// The index of one program's export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    /// Directory under `programs/`
+    pub program: String,
+    pub seed: u64,
+    /// Blockhash every transaction is signed for
+    pub blockhash: String,
+    pub programs: Vec<ExportedProgram>,
+    /// Addresses with a file under `accounts/`
+    pub accounts: Vec<String>,
+    /// Label -> pubkey of each file under `keypairs/`
+    pub keypairs: BTreeMap<String, String>,
+    /// The vulnerable instruction's attack, then the secure one's
+    pub sides: Vec<Side>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedProgram {
+    /// Name of the `.so`
+    pub name: String,
+    pub program_id: String,
+    /// The `.so` from the repository root
+    pub so: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Side {
+    pub instruction: String,
+    pub transactions: Vec<ExportedTransaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedTransaction {
+    pub narration: String,
+    pub signature: String,
+    /// Base64 of the serialized transaction, as `sendTransaction` takes it
+    pub tx: String,
+    /// `Debug` of LiteSVM's error, `"ok"` if it landed
+    pub result: String,
+    pub compute_units: u64,
+    pub logs: Vec<String>,
+}
+
+impl ExportedTransaction {
+    pub fn decode(&self) -> io::Result<VersionedTransaction> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let bytes = BASE64
+            .decode(&self.tx)
+            .map_err(|e| invalid(e.to_string()))?;
+        bincode::deserialize(&bytes).map_err(|e| invalid(e.to_string()))
+    }
+}
+
+/// Play `demo` and return its bundle with the pre-attack accounts, without
+/// writing anything.
+pub fn capture(demo: &Demo) -> (Bundle, Vec<AccountFixture>) {
+    let mut svm = Scenario::new(&format!("export/{}", demo.program), SEED);
+    svm.add_program(demo.deploy);
+    let stage = (demo.setup)(&mut svm);
+    let ready = svm.snapshot();
+    let blockhash = svm.latest_blockhash().to_string();
+
+    let mut sides = Vec::new();
+    let mut named = Vec::new();
+    for instruction in [demo.vulnerable, demo.secure] {
+        svm.restore(ready);
+        let mut transactions = Vec::new();
+        for action in (stage.script)(&svm, instruction) {
+            let tx = VersionedTransaction::from(action.tx);
+            named.extend(crate::alt::account_keys(&svm, &tx));
+            let encoded = BASE64.encode(bincode::serialize(&tx).expect("transaction serializes"));
+            let signature = tx.signatures[0].to_string();
+            let result = svm.send_transaction(tx);
+            let meta = match &result {
+                Ok(meta) => meta,
+                Err(failed) => &failed.meta,
+            };
+            transactions.push(ExportedTransaction {
+                narration: action.narration,
+                signature,
+                tx: encoded,
+                result: match &result {
+                    Ok(_) => "ok".to_string(),
+                    Err(failed) => format!("{:?}", failed.err),
+                },
+                compute_units: meta.compute_units_consumed,
+                logs: meta.logs.clone(),
+            });
+        }
+        sides.push(Side {
+            instruction: instruction.to_string(),
+            transactions,
+        });
+    }
+
+    // The state both sides started from
+    svm.restore(ready);
+    let programs: Vec<ExportedProgram> = svm
+        .log()
+        .steps
+        .iter()
+        .filter_map(|step| match step {
+            Step::Program { name } => Some(ExportedProgram {
+                name: name.clone(),
+                program_id: crate::load_program_id(name).to_string(),
+                so: format!("target/deploy/{}.so", name),
+            }),
+            _ => None,
+        })
+        .collect();
+    let mut accounts: Vec<AccountFixture> = Vec::new();
+    for address in named {
+        let loaded = programs.iter().any(|p| p.program_id == address.to_string());
+        if loaded || accounts.iter().any(|a| a.address == address) {
+            continue;
+        }
+        let Some(account) = svm.get_account(&address) else {
+            continue;
+        };
+        // Builtins and sysvars are every cluster's own
+        if !account.executable && account.owner != sdk::sysvar::ID {
+            accounts.push(AccountFixture::new(address, account));
+        }
+    }
+
+    let bundle = Bundle {
+        program: demo.program.to_string(),
+        seed: SEED,
+        blockhash,
+        programs,
+        accounts: accounts.iter().map(|a| a.address.to_string()).collect(),
+        keypairs: svm.log().keypairs.clone(),
+        sides,
+    };
+    (bundle, accounts)
+}
+
+/// Play `demo` and write its export to `dir`, replacing an earlier one.
+pub fn export(demo: &Demo, dir: &Path) -> io::Result<Bundle> {
+    let (bundle, accounts) = capture(demo);
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    for fixture in &accounts {
+        fixture.write(
+            &dir.join("accounts")
+                .join(format!("{}.json", fixture.address)),
+        )?;
+    }
+    let keypairs = dir.join("keypairs");
+    std::fs::create_dir_all(&keypairs)?;
+    for label in bundle.keypairs.keys() {
+        let bytes = seeded_keypair(bundle.seed, label).to_bytes().to_vec();
+        std::fs::write(
+            keypairs.join(format!("{}.json", label)),
+            serde_json::to_string(&bytes).expect("bytes serialize"),
+        )?;
+    }
+    let json = serde_json::to_string_pretty(&bundle).expect("bundle serialization cannot fail");
+    std::fs::write(dir.join("bundle.json"), json + "\n")?;
+    Ok(bundle)
+}
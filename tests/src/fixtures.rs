@@ -0,0 +1,226 @@
+//! Account fixtures captured from a live cluster
+//!
+//! Fixtures live under `tests/fixtures/` and are loaded into LiteSVM so the
+//! exploits run against real data layouts (token mints, oracle price
+//! accounts) instead of hand-packed byte vectors.
+//!
+//! Two formats are supported, selected by file extension:
+//! - `.json` - the same shape `solana account <ADDR> --output json` writes,
+//!   so files can also be fed to `solana-test-validator --account`
+//! - `.bin`  - bincode, for large accounts where JSON/base64 is wasteful
+//!
+//! Capture new fixtures with the `capture-fixture` binary:
+//!
+//! ```bash
+//! cd tests
+//! cargo run --bin capture-fixture -- EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v --name usdc_mint
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use litesvm::LiteSVM;
+use serde::{Deserialize, Serialize};
+
+use crate::sdk::{Account, Pubkey};
+
+/// Directory holding all checked-in fixtures.
+pub fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// An account snapshot together with the address it was captured from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountFixture {
+    pub address: Pubkey,
+    pub account: Account,
+}
+
+// JSON layout written by `solana account --output json`
+#[derive(Serialize, Deserialize)]
+struct JsonFixture {
+    pubkey: String,
+    account: JsonAccount,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonAccount {
+    lamports: u64,
+    /// `[payload, encoding]`, where encoding is always "base64"
+    data: (String, String),
+    owner: String,
+    executable: bool,
+    rent_epoch: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    space: Option<u64>,
+}
+
+// Bincode layout - plain bytes, no base58/base64 round trips
+#[derive(Serialize, Deserialize)]
+struct BinFixture {
+    address: [u8; 32],
+    lamports: u64,
+    data: Vec<u8>,
+    owner: [u8; 32],
+    executable: bool,
+    rent_epoch: u64,
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn parse_pubkey(s: &str) -> io::Result<Pubkey> {
+    Pubkey::from_str(s).map_err(|e| invalid(format!("invalid pubkey {}: {}", s, e)))
+}
+
+impl AccountFixture {
+    pub fn new(address: Pubkey, account: Account) -> Self {
+        Self { address, account }
+    }
+
+    /// Load `tests/fixtures/<name>.json` (or `<name>.bin` if no JSON exists).
+    /// Panics if neither is present, like the program loaders in this crate.
+    pub fn load(name: &str) -> Self {
+        let dir = fixtures_dir();
+        let json = dir.join(format!("{}.json", name));
+        let path = if json.exists() {
+            json
+        } else {
+            dir.join(format!("{}.bin", name))
+        };
+
+        Self::from_path(&path)
+            .unwrap_or_else(|e| panic!("Failed to load fixture {}: {}", path.display(), e))
+    }
+
+    /// Read a fixture from an explicit path; the format follows the extension.
+    pub fn from_path(path: &Path) -> io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("bin") => Self::from_bincode(&bytes),
+            _ => Self::from_json(&bytes),
+        }
+    }
+
+    pub fn from_json(bytes: &[u8]) -> io::Result<Self> {
+        let raw: JsonFixture = serde_json::from_slice(bytes).map_err(|e| invalid(e.to_string()))?;
+
+        let (payload, encoding) = raw.account.data;
+        if encoding != "base64" {
+            return Err(invalid(format!("unsupported data encoding: {}", encoding)));
+        }
+        let data = BASE64
+            .decode(payload.as_bytes())
+            .map_err(|e| invalid(e.to_string()))?;
+        // A truncated or hand-edited payload would install short
+        if let Some(space) = raw.account.space {
+            if space != data.len() as u64 {
+                return Err(invalid(format!(
+                    "space is {} but the data is {} bytes",
+                    space,
+                    data.len()
+                )));
+            }
+        }
+
+        Ok(Self {
+            address: parse_pubkey(&raw.pubkey)?,
+            account: Account {
+                lamports: raw.account.lamports,
+                data,
+                owner: parse_pubkey(&raw.account.owner)?,
+                executable: raw.account.executable,
+                rent_epoch: raw.account.rent_epoch,
+            },
+        })
+    }
+
+    pub fn from_bincode(bytes: &[u8]) -> io::Result<Self> {
+        let raw: BinFixture = bincode::deserialize(bytes).map_err(|e| invalid(e.to_string()))?;
+        Ok(Self {
+            address: Pubkey::new_from_array(raw.address),
+            account: Account {
+                lamports: raw.lamports,
+                data: raw.data,
+                owner: Pubkey::new_from_array(raw.owner),
+                executable: raw.executable,
+                rent_epoch: raw.rent_epoch,
+            },
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        let raw = JsonFixture {
+            pubkey: self.address.to_string(),
+            account: JsonAccount {
+                lamports: self.account.lamports,
+                data: (BASE64.encode(&self.account.data), "base64".to_string()),
+                owner: self.account.owner.to_string(),
+                executable: self.account.executable,
+                rent_epoch: self.account.rent_epoch,
+                space: Some(self.account.data.len() as u64),
+            },
+        };
+        serde_json::to_string_pretty(&raw).expect("fixture serialization cannot fail")
+    }
+
+    pub fn to_bincode(&self) -> Vec<u8> {
+        let raw = BinFixture {
+            address: self.address.to_bytes(),
+            lamports: self.account.lamports,
+            data: self.account.data.clone(),
+            owner: self.account.owner.to_bytes(),
+            executable: self.account.executable,
+            rent_epoch: self.account.rent_epoch,
+        };
+        bincode::serialize(&raw).expect("fixture serialization cannot fail")
+    }
+
+    /// Write the fixture; the format follows the extension of `path`.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("bin") => std::fs::write(path, self.to_bincode()),
+            _ => std::fs::write(path, self.to_json() + "\n"),
+        }
+    }
+
+    /// Install the account into LiteSVM at its captured address.
+    pub fn install(&self, svm: &mut LiteSVM) -> Pubkey {
+        self.install_at(svm, self.address)
+    }
+
+    /// Install the account at a different address - handy when an exploit
+    /// needs a realistic layout under an attacker-controlled key.
+    pub fn install_at(&self, svm: &mut LiteSVM, address: Pubkey) -> Pubkey {
+        svm.set_account(address, self.account.clone())
+            .unwrap_or_else(|e| panic!("Failed to install fixture {}: {:?}", address, e));
+        address
+    }
+}
+
+/// Load a named fixture into LiteSVM and return its address.
+pub fn load_fixture(svm: &mut LiteSVM, name: &str) -> Pubkey {
+    AccountFixture::load(name).install(svm)
+}
+
+/// Load every `.json`/`.bin` fixture in `dir` into LiteSVM.
+pub fn load_fixtures_dir(svm: &mut LiteSVM, dir: &Path) -> io::Result<Vec<Pubkey>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("json" | "bin")))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|p| AccountFixture::from_path(p).map(|f| f.install(svm)))
+        .collect()
+}
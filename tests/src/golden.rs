@@ -0,0 +1,186 @@
+//! Golden snapshots of exploit transactions
+//!
+//! Captures the outcome, compute units and program logs of a transaction
+//! and compares them against a checked-in file under `tests/golden/`. If a
+//! constraint is silently removed from a secure instruction, its logs and
+//! error change and the snapshot comparison fails loudly.
+//!
+//! Pubkeys are redacted to `<key:N>` in order of first appearance, so
+//! snapshots stay stable across random keypairs and per-machine program ids.
+//!
+//! Regenerate after an intentional change:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test
+//! ```
+//!
+//! A missing snapshot fails like a mismatch, so one deleted or never
+//! committed can't pass unnoticed. Record a new test's snapshot with
+//! `UPDATE_GOLDEN=1` and commit the file.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use litesvm::types::TransactionResult;
+
+use crate::sdk::Pubkey;
+
+/// Directory holding all golden snapshots.
+pub fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("golden")
+}
+
+/// Whether `UPDATE_GOLDEN` asks for snapshots to be rewritten.
+pub fn update_mode() -> bool {
+    std::env::var("UPDATE_GOLDEN").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Normalized view of a transaction result that is written to disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxSnapshot {
+    /// `ok` or the transaction error
+    pub status: String,
+    pub compute_units: u64,
+    pub logs: Vec<String>,
+}
+
+impl TxSnapshot {
+    pub fn capture(result: &TransactionResult) -> Self {
+        let (status, meta) = match result {
+            Ok(meta) => ("ok".to_string(), meta),
+            Err(failed) => (format!("err {:?}", failed.err), &failed.meta),
+        };
+
+        let mut redactor = Redactor::default();
+        Self {
+            status: redactor.redact(&status),
+            compute_units: meta.compute_units_consumed,
+            logs: meta.logs.iter().map(|l| redactor.redact(l)).collect(),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "status: {}\ncompute_units: {}\nlogs:\n",
+            self.status, self.compute_units
+        );
+        for log in &self.logs {
+            out.push_str("  ");
+            out.push_str(log);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// `lines` with their pubkeys redacted the way a snapshot's are.
+pub fn redact(lines: &[String]) -> Vec<String> {
+    let mut redactor = Redactor::default();
+    lines.iter().map(|l| redactor.redact(l)).collect()
+}
+
+/// Replaces base58 pubkeys with stable placeholders.
+#[derive(Default)]
+struct Redactor {
+    seen: HashMap<String, usize>,
+}
+
+impl Redactor {
+    fn redact(&mut self, line: &str) -> String {
+        let mut out = String::with_capacity(line.len());
+        let mut token = String::new();
+
+        for c in line.chars().chain(std::iter::once(' ')) {
+            if c.is_ascii_alphanumeric() {
+                token.push(c);
+                continue;
+            }
+            out.push_str(&self.redact_token(&token));
+            token.clear();
+            out.push(c);
+        }
+        out.pop();
+        out
+    }
+
+    fn redact_token(&mut self, token: &str) -> String {
+        // Well-known builtins stay readable
+        if token.len() < 32 || token.len() > 44 || token.chars().all(|c| c == '1') {
+            return token.to_string();
+        }
+        if token.starts_with("Sysvar") || token.starts_with("Tokenkeg") || token.starts_with("TokenzQd")
+        {
+            return token.to_string();
+        }
+        if Pubkey::from_str(token).is_err() {
+            return token.to_string();
+        }
+
+        let next = self.seen.len();
+        let id = *self.seen.entry(token.to_string()).or_insert(next);
+        format!("<key:{}>", id)
+    }
+}
+
+/// Compare a transaction against `tests/golden/<name>.snap`.
+///
+/// `name` may contain `/` to group snapshots per suite,
+/// e.g. `assert_golden("signer_authorization/vulnerable_withdraw", &result)`.
+pub fn assert_golden(name: &str, result: &TransactionResult) {
+    assert_golden_text(name, &TxSnapshot::capture(result).render());
+}
+
+/// Compare already-rendered text against `tests/golden/<name>.snap`, with
+/// the same `UPDATE_GOLDEN` rule as [`assert_golden`].
+pub fn assert_golden_text(name: &str, actual: &str) {
+    let path = golden_dir().join(format!("{}.snap", name));
+
+    let write = |reason: &str| {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+        println!("golden: {} {}", reason, path.display());
+    };
+
+    if update_mode() {
+        return write("updated");
+    }
+
+    let expected = match std::fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(_) => panic!(
+            "Missing golden snapshot {}\nRe-run with UPDATE_GOLDEN=1 to record it, and commit the file.",
+            path.display()
+        ),
+    };
+
+    if expected != actual {
+        panic!(
+            "Golden snapshot mismatch for {}\n{}\nRe-run with UPDATE_GOLDEN=1 if the change is intended.",
+            name,
+            line_diff(&expected, actual)
+        );
+    }
+}
+
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => {}
+            (e, a) => {
+                if let Some(e) = e {
+                    out.push_str(&format!("- {}\n", e));
+                }
+                if let Some(a) = a {
+                    out.push_str(&format!("+ {}\n", a));
+                }
+            }
+        }
+    }
+    out
+}
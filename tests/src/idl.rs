@@ -0,0 +1,1011 @@
+//! Instruction builder driven by Anchor IDLs
+//!
+//! Instead of hashing `global:<name>` and packing argument bytes by hand,
+//! suites load the IDL `anchor build` writes to `target/idl/` and build
+//! instructions by name. Pinocchio programs have no generated IDL; theirs are
+//! written by hand in the same format under `tests/idl/`, with the
+//! single-byte instruction tag as the discriminator:
+//!
+//! ```ignore
+//! let idl = Idl::load("multisig_payer");
+//! let ix = idl
+//!     .ix("secure_create_proposal")
+//!     .arg("proposal_id", 1u64)
+//!     .arg("title", "Test Proposal")
+//!     .account("rent_payer", rent_payer.pubkey())
+//!     .account("creator", creator.pubkey())
+//!     .account("treasury", treasury_pda)
+//!     .account("config", config_pda)
+//!     .account("proposal", proposal_pda)
+//!     .build();
+//! ```
+//!
+//! Account order, signer and writable flags come from the IDL, accounts with
+//! a fixed address (system program, sysvars, `#[event_cpi]`'s two) are filled
+//! in automatically, and arguments are Borsh-encoded against their declared
+//! types. A renamed account, a reordered field or a wrong argument type fails
+//! with a message naming the instruction instead of producing a silently
+//! different transaction.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+use serde_json::Value as Json;
+use sha2::{Digest, Sha256};
+
+use crate::sdk::{AccountMeta, Instruction, Pubkey};
+
+/// Argument value supplied to [`IxBuilder::arg`].
+///
+/// Integers are range-checked against the IDL type when encoded, so plain
+/// literals work for any width.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    Bool(bool),
+    Int(i128),
+    UInt(u128),
+    String(String),
+    Bytes(Vec<u8>),
+    Pubkey(Pubkey),
+    Vec(Vec<ArgValue>),
+    Option(Option<Box<ArgValue>>),
+    /// Fields of a `defined` struct, by name
+    Struct(Vec<(String, ArgValue)>),
+    /// Variant of a `defined` enum, with its fields for tuple/struct variants
+    Enum(String, Vec<ArgValue>),
+}
+
+macro_rules! from_uint {
+    ($($t:ty),*) => {$(
+        impl From<$t> for ArgValue {
+            fn from(v: $t) -> Self {
+                ArgValue::UInt(v as u128)
+            }
+        }
+    )*};
+}
+macro_rules! from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for ArgValue {
+            fn from(v: $t) -> Self {
+                ArgValue::Int(v as i128)
+            }
+        }
+    )*};
+}
+from_uint!(u8, u16, u32, u64, u128);
+from_int!(i8, i16, i32, i64, i128);
+
+impl From<bool> for ArgValue {
+    fn from(v: bool) -> Self {
+        ArgValue::Bool(v)
+    }
+}
+
+impl From<&str> for ArgValue {
+    fn from(v: &str) -> Self {
+        ArgValue::String(v.to_string())
+    }
+}
+
+impl From<String> for ArgValue {
+    fn from(v: String) -> Self {
+        ArgValue::String(v)
+    }
+}
+
+impl From<Pubkey> for ArgValue {
+    fn from(v: Pubkey) -> Self {
+        ArgValue::Pubkey(v)
+    }
+}
+
+impl<T: Into<ArgValue>> From<Vec<T>> for ArgValue {
+    fn from(v: Vec<T>) -> Self {
+        ArgValue::Vec(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<ArgValue>, const N: usize> From<[T; N]> for ArgValue {
+    fn from(v: [T; N]) -> Self {
+        ArgValue::Vec(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<ArgValue>> From<Option<T>> for ArgValue {
+    fn from(v: Option<T>) -> Self {
+        ArgValue::Option(v.map(|v| Box::new(v.into())))
+    }
+}
+
+/// One account slot of an instruction, flattened from nested account groups.
+#[derive(Debug, Clone)]
+pub struct IdlAccount {
+    pub name: String,
+    pub writable: bool,
+    pub signer: bool,
+    pub optional: bool,
+    /// Fixed address declared in the IDL, e.g. the system program
+    pub address: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlInstruction {
+    pub name: String,
+    /// 8 bytes for Anchor, the 1-byte tag for Pinocchio programs
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<IdlAccount>,
+    /// `(name, type)`; types stay as IDL JSON and are interpreted on encode
+    pub args: Vec<(String, Json)>,
+}
+
+/// An entry of the program's `#[error_code]` enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlError {
+    pub code: u32,
+    pub name: String,
+    pub msg: Option<String>,
+}
+
+/// A parsed Anchor IDL bound to the program id the tests deploy under.
+#[derive(Debug, Clone)]
+pub struct Idl {
+    pub name: String,
+    pub program_id: Pubkey,
+    pub instructions: Vec<IdlInstruction>,
+    pub errors: Vec<IdlError>,
+    /// `(name, discriminator)` of each `#[account]` type
+    accounts: Vec<(String, [u8; 8])>,
+    types: Vec<Json>,
+}
+
+/// Directory `anchor build` writes IDLs to, under
+/// [`common::target_dir`](crate::common::target_dir).
+///
+/// Binaries built for an [`ANCHOR_RELEASE`](crate::common::ANCHOR_RELEASE)
+/// share these: Anchor 0.31 and 0.32 generate every program's IDL the same.
+pub fn idl_dir() -> PathBuf {
+    crate::common::target_dir().join("idl")
+}
+
+/// Hand-written IDLs for programs without an IDL generator.
+pub fn checked_in_idl_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("idl")
+}
+
+/// Anchor's instruction discriminator: `sha256("global:<name>")[..8]`.
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{}", name).as_bytes());
+    let mut disc = [0u8; 8];
+    disc.copy_from_slice(&hash[..8]);
+    disc
+}
+
+/// Anchor's account discriminator: `sha256("account:<Name>")[..8]`.
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{}", name).as_bytes());
+    let mut disc = [0u8; 8];
+    disc.copy_from_slice(&hash[..8]);
+    disc
+}
+
+fn discriminator_bytes(entry: &Json) -> Option<Vec<u8>> {
+    let bytes = entry.get("discriminator")?.as_array()?;
+    Some(bytes.iter().map(|b| b.as_u64().unwrap_or_default() as u8).collect())
+}
+
+fn discriminator(entry: &Json) -> Option<[u8; 8]> {
+    let bytes = entry.get("discriminator")?.as_array()?;
+    let mut disc = [0u8; 8];
+    for (d, b) in disc.iter_mut().zip(bytes) {
+        *d = b.as_u64().unwrap_or_default() as u8;
+    }
+    Some(disc)
+}
+
+fn flag(account: &Json, new: &str, legacy: &str) -> bool {
+    account
+        .get(new)
+        .or_else(|| account.get(legacy))
+        .and_then(Json::as_bool)
+        .unwrap_or(false)
+}
+
+fn flatten_accounts(list: &[Json], out: &mut Vec<IdlAccount>) {
+    for account in list {
+        // Composite `#[derive(Accounts)]` fields nest their own list
+        if let Some(nested) = account.get("accounts").and_then(Json::as_array) {
+            flatten_accounts(nested, out);
+            continue;
+        }
+        out.push(IdlAccount {
+            name: account["name"].as_str().unwrap_or_default().to_string(),
+            writable: flag(account, "writable", "isMut"),
+            signer: flag(account, "signer", "isSigner"),
+            optional: flag(account, "optional", "isOptional"),
+            address: account
+                .get("address")
+                .and_then(Json::as_str)
+                .and_then(|a| Pubkey::from_str(a).ok()),
+        });
+    }
+}
+
+/// Where `#[event_cpi]`'s accounts go: the program's event authority and
+/// the program itself, which the IDL gives the declared id for.
+fn fill_event_cpi(accounts: &mut [IdlAccount], program_id: &Pubkey) {
+    if let [.., authority, program] = accounts {
+        if authority.name == "event_authority" && program.name == "program" {
+            authority.address = Some(event_authority(program_id));
+            program.address = Some(*program_id);
+        }
+    }
+}
+
+/// The program's cluster record, which the vulnerable instructions take.
+fn fill_cluster(accounts: &mut [IdlAccount], program_id: &Pubkey) {
+    for account in accounts.iter_mut().filter(|a| a.name == "cluster") {
+        account.address = Some(crate::common::cluster_record(program_id));
+    }
+}
+
+/// The PDA `emit_cpi!` signs its self-CPI with.
+pub fn event_authority(program_id: &Pubkey) -> Pubkey {
+    crate::common::find_pda(&[b"__event_authority"], program_id).0
+}
+
+impl Idl {
+    /// Load `target/idl/<program>.json`, falling back to the checked-in
+    /// `tests/idl/<program>.json`. The program id comes from the deploy
+    /// keypair, matching what `setup()` hands to LiteSVM, rather than from the
+    /// IDL's `address`, which goes stale whenever keys are regenerated.
+    pub fn load(program: &str) -> Self {
+        // First, so an auto-build has written the IDL before it is looked up
+        let program_id = crate::load_program_id(program);
+        let file = format!("{}.json", program);
+        let generated = idl_dir().join(&file);
+        let path = if generated.exists() {
+            generated
+        } else {
+            checked_in_idl_dir().join(&file)
+        };
+        Self::from_path(&path, program_id)
+    }
+
+    /// Load an IDL from an explicit path, e.g. `programs/amm/target/idl/`.
+    /// Each file is parsed once per test process.
+    pub fn from_path(path: &Path, program_id: Pubkey) -> Self {
+        static IDLS: OnceLock<Mutex<HashMap<(PathBuf, Pubkey), Idl>>> = OnceLock::new();
+        crate::common::cached(&IDLS, (path.to_path_buf(), program_id), || {
+            let json = std::fs::read_to_string(path).unwrap_or_else(|_| {
+                panic!(
+                    "Failed to read IDL {} - run `anchor build` first",
+                    path.display()
+                )
+            });
+            Self::from_json(&json, program_id)
+        })
+    }
+
+    pub fn from_json(json: &str, program_id: Pubkey) -> Self {
+        let raw: Json = serde_json::from_str(json).expect("Failed to parse IDL");
+
+        let name = raw
+            .pointer("/metadata/name")
+            .or_else(|| raw.get("name"))
+            .and_then(Json::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let instructions = raw["instructions"]
+            .as_array()
+            .expect("IDL has no instructions")
+            .iter()
+            .map(|ix| {
+                let name = ix["name"].as_str().unwrap_or_default().to_string();
+                // Pre-0.30 IDLs don't carry discriminators
+                let discriminator = discriminator_bytes(ix)
+                    .unwrap_or_else(|| instruction_discriminator(&name).to_vec());
+
+                let mut accounts = Vec::new();
+                flatten_accounts(ix["accounts"].as_array().unwrap_or(&Vec::new()), &mut accounts);
+                fill_event_cpi(&mut accounts, &program_id);
+                fill_cluster(&mut accounts, &program_id);
+
+                let args = ix["args"]
+                    .as_array()
+                    .unwrap_or(&Vec::new())
+                    .iter()
+                    .map(|a| {
+                        (
+                            a["name"].as_str().unwrap_or_default().to_string(),
+                            a["type"].clone(),
+                        )
+                    })
+                    .collect();
+
+                IdlInstruction {
+                    name,
+                    discriminator,
+                    accounts,
+                    args,
+                }
+            })
+            .collect();
+
+        let errors = raw["errors"]
+            .as_array()
+            .unwrap_or(&Vec::new())
+            .iter()
+            .map(|e| IdlError {
+                code: e["code"].as_u64().unwrap_or_default() as u32,
+                name: e["name"].as_str().unwrap_or_default().to_string(),
+                msg: e["msg"].as_str().map(str::to_string),
+            })
+            .collect();
+
+        let accounts = raw["accounts"]
+            .as_array()
+            .unwrap_or(&Vec::new())
+            .iter()
+            .map(|a| {
+                let name = a["name"].as_str().unwrap_or_default().to_string();
+                let disc = discriminator(a).unwrap_or_else(|| account_discriminator(&name));
+                (name, disc)
+            })
+            .collect();
+
+        Self {
+            name,
+            program_id,
+            instructions,
+            errors,
+            accounts,
+            types: raw["types"].as_array().cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Discriminator of an `#[account]` type, for writing fixture data with
+    /// `svm.set_account`.
+    pub fn account_discriminator(&self, name: &str) -> [u8; 8] {
+        self.accounts
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, disc)| *disc)
+            .unwrap_or_else(|| panic!("{}: no account type named {}", self.name, name))
+    }
+
+    /// Decode account data by its discriminator into the `#[account]` type
+    /// name and its fields. `None` when no account type matches or the data
+    /// is too short for the declared layout.
+    pub fn decode_account(&self, data: &[u8]) -> Option<(String, Vec<(String, ArgValue)>)> {
+        let (name, disc) = self.accounts.iter().find(|(_, d)| data.starts_with(d))?;
+        let def = self.types.iter().find(|t| t["name"].as_str() == Some(name))?;
+        let mut rest = &data[disc.len()..];
+        match decode_defined(self, def, &mut rest).ok()? {
+            ArgValue::Struct(fields) => Some((name.clone(), fields)),
+            _ => None,
+        }
+    }
+
+    /// Byte range of `field` in the data of `#[account]` type `account`,
+    /// discriminator included. Panics if the field doesn't exist or follows
+    /// a variable-size field (string, vec, option), whose offset depends on
+    /// the data.
+    pub fn field_range(&self, account: &str, field: &str) -> std::ops::Range<usize> {
+        let fields = self.defined_type(account)["type"]["fields"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let mut offset = self.account_discriminator(account).len();
+        for f in &fields {
+            let size = fixed_size(self, &f["type"]);
+            if f["name"].as_str() == Some(field) {
+                let size = size.unwrap_or_else(|| {
+                    panic!("{}: {}.{} has no fixed size", self.name, account, field)
+                });
+                return offset..offset + size;
+            }
+            offset += size.unwrap_or_else(|| {
+                panic!(
+                    "{}: {}.{} comes after variable-size field {}",
+                    self.name, account, field, f["name"]
+                )
+            });
+        }
+        panic!("{}: {} has no field {}", self.name, account, field)
+    }
+
+    /// One line per interface detail a client or a byte-level fixture relies
+    /// on: instruction discriminators, account order and flags, argument
+    /// types, account layouts with byte offsets, other types, and error
+    /// codes. Used as the checked-in IDL baseline, so a diff of two layouts
+    /// names exactly what changed.
+    pub fn layout(&self) -> String {
+        let mut out = String::new();
+        for ix in &self.instructions {
+            out.push_str(&format!("instruction {} {:?}\n", ix.name, ix.discriminator));
+            for account in &ix.accounts {
+                let mut flags = String::new();
+                for (set, flag) in [
+                    (account.writable, " writable"),
+                    (account.signer, " signer"),
+                    (account.optional, " optional"),
+                ] {
+                    if set {
+                        flags.push_str(flag);
+                    }
+                }
+                if let Some(address) = account.address {
+                    flags.push_str(&format!(" address={}", address));
+                }
+                out.push_str(&format!("  account {}{}\n", account.name, flags));
+            }
+            for (name, ty) in &ix.args {
+                out.push_str(&format!("  arg {}: {}\n", name, type_name(ty)));
+            }
+        }
+
+        for (name, disc) in &self.accounts {
+            out.push_str(&format!("account {} {:?}\n", name, disc));
+            let fields = self
+                .types
+                .iter()
+                .find(|t| t["name"].as_str() == Some(name))
+                .and_then(|t| t["type"]["fields"].as_array().cloned())
+                .unwrap_or_default();
+            // Offsets stop being fixed after the first variable-size field
+            let mut offset = Some(disc.len());
+            for field in &fields {
+                let size = fixed_size(self, &field["type"]);
+                let at = match (offset, size) {
+                    (Some(start), Some(size)) => format!("{}..{}", start, start + size),
+                    (Some(start), None) => format!("{}..", start),
+                    (None, _) => "?".to_string(),
+                };
+                out.push_str(&format!(
+                    "  {} {}: {}\n",
+                    at,
+                    field["name"].as_str().unwrap_or_default(),
+                    type_name(&field["type"])
+                ));
+                offset = offset.zip(size).map(|(start, size)| start + size);
+            }
+        }
+
+        for ty in &self.types {
+            let name = ty["name"].as_str().unwrap_or_default();
+            if self.accounts.iter().any(|(n, _)| n == name) {
+                continue;
+            }
+            let kind = &ty["type"];
+            out.push_str(&format!(
+                "type {} {}\n",
+                name,
+                kind["kind"].as_str().unwrap_or_default()
+            ));
+            for field in kind["fields"].as_array().unwrap_or(&Vec::new()) {
+                out.push_str(&format!(
+                    "  {}: {}\n",
+                    field["name"].as_str().unwrap_or_default(),
+                    type_name(&field["type"])
+                ));
+            }
+            for variant in kind["variants"].as_array().unwrap_or(&Vec::new()) {
+                let fields: Vec<String> = variant["fields"]
+                    .as_array()
+                    .unwrap_or(&Vec::new())
+                    .iter()
+                    .map(|f| match f.get("name").and_then(Json::as_str) {
+                        Some(n) => format!("{}: {}", n, type_name(&f["type"])),
+                        None => type_name(f),
+                    })
+                    .collect();
+                let name = variant["name"].as_str().unwrap_or_default();
+                if fields.is_empty() {
+                    out.push_str(&format!("  {}\n", name));
+                } else {
+                    out.push_str(&format!("  {}({})\n", name, fields.join(", ")));
+                }
+            }
+        }
+
+        for error in &self.errors {
+            out.push_str(&format!("error {} {}\n", error.code, error.name));
+        }
+        out
+    }
+
+    /// Field names of `#[account]` type `account`, in layout order.
+    pub fn account_fields(&self, account: &str) -> Vec<String> {
+        self.defined_type(account)["type"]["fields"]
+            .as_array()
+            .unwrap_or(&Vec::new())
+            .iter()
+            .filter_map(|f| f["name"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    pub fn error(&self, name: &str) -> Option<&IdlError> {
+        self.errors.iter().find(|e| e.name == name)
+    }
+
+    pub fn instruction(&self, name: &str) -> &IdlInstruction {
+        self.instructions
+            .iter()
+            .find(|ix| ix.name == name)
+            .unwrap_or_else(|| panic!("{}: no instruction named {}", self.name, name))
+    }
+
+    /// Start building the named instruction.
+    pub fn ix(&self, name: &str) -> IxBuilder<'_> {
+        IxBuilder {
+            idl: self,
+            ix: self.instruction(name),
+            args: Vec::new(),
+            accounts: Vec::new(),
+            remaining: Vec::new(),
+        }
+    }
+
+    fn defined_type(&self, name: &str) -> &Json {
+        self.types
+            .iter()
+            .find(|t| t["name"].as_str() == Some(name))
+            .unwrap_or_else(|| panic!("{}: no type named {}", self.name, name))
+    }
+}
+
+/// Builder returned by [`Idl::ix`].
+pub struct IxBuilder<'a> {
+    idl: &'a Idl,
+    ix: &'a IdlInstruction,
+    args: Vec<(String, ArgValue)>,
+    accounts: Vec<(String, AccountMeta)>,
+    remaining: Vec<AccountMeta>,
+}
+
+impl IxBuilder<'_> {
+    pub fn arg(mut self, name: &str, value: impl Into<ArgValue>) -> Self {
+        self.args.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// Bind a named account; signer/writable flags follow the IDL.
+    pub fn account(mut self, name: &str, pubkey: Pubkey) -> Self {
+        let slot = self.slot(name);
+        let meta = AccountMeta {
+            pubkey,
+            is_signer: slot.signer,
+            is_writable: slot.writable,
+        };
+        self.accounts.push((name.to_string(), meta));
+        self
+    }
+
+    /// Bind a named account with explicit flags, for exploits that pass an
+    /// account differently than the IDL expects (e.g. an unsigned authority).
+    pub fn account_meta(mut self, name: &str, meta: AccountMeta) -> Self {
+        self.slot(name);
+        self.accounts.push((name.to_string(), meta));
+        self
+    }
+
+    /// Append to `ctx.remaining_accounts`, after all named accounts.
+    pub fn remaining(mut self, meta: AccountMeta) -> Self {
+        self.remaining.push(meta);
+        self
+    }
+
+    fn slot(&self, name: &str) -> &IdlAccount {
+        self.ix
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "{}::{}: no account named {} (expected one of: {})",
+                    self.idl.name,
+                    self.ix.name,
+                    name,
+                    self.ix
+                        .accounts
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+
+    pub fn build(self) -> Instruction {
+        let context = format!("{}::{}", self.idl.name, self.ix.name);
+
+        if let Some((name, _)) = self
+            .args
+            .iter()
+            .find(|(name, _)| !self.ix.args.iter().any(|(n, _)| n == name))
+        {
+            panic!("{}: unknown argument {}", context, name);
+        }
+
+        let mut data = self.ix.discriminator.clone();
+        for (name, ty) in &self.ix.args {
+            let value = self
+                .args
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v)
+                .unwrap_or_else(|| panic!("{}: missing argument {}", context, name));
+            encode(self.idl, ty, value, &mut data)
+                .unwrap_or_else(|e| panic!("{}: argument {}: {}", context, name, e));
+        }
+
+        let mut metas = Vec::with_capacity(self.ix.accounts.len() + self.remaining.len());
+        for slot in &self.ix.accounts {
+            let bound = self.accounts.iter().find(|(n, _)| *n == slot.name);
+            let meta = match (bound, slot.address) {
+                (Some((_, meta)), _) => meta.clone(),
+                (None, Some(address)) => AccountMeta {
+                    pubkey: address,
+                    is_signer: slot.signer,
+                    is_writable: slot.writable,
+                },
+                // Anchor reads the program id as "not provided"
+                (None, None) if slot.optional => {
+                    AccountMeta::new_readonly(self.idl.program_id, false)
+                }
+                (None, None) => panic!("{}: missing account {}", context, slot.name),
+            };
+            metas.push(meta);
+        }
+        metas.extend(self.remaining);
+
+        Instruction {
+            program_id: self.idl.program_id,
+            accounts: metas,
+            data,
+        }
+    }
+}
+
+fn int_bounds(ty: &str) -> Option<(i128, u128, usize)> {
+    Some(match ty {
+        "u8" => (0, u8::MAX as u128, 1),
+        "u16" => (0, u16::MAX as u128, 2),
+        "u32" => (0, u32::MAX as u128, 4),
+        "u64" => (0, u64::MAX as u128, 8),
+        "u128" => (0, u128::MAX, 16),
+        "i8" => (i8::MIN as i128, i8::MAX as u128, 1),
+        "i16" => (i16::MIN as i128, i16::MAX as u128, 2),
+        "i32" => (i32::MIN as i128, i32::MAX as u128, 4),
+        "i64" => (i64::MIN as i128, i64::MAX as u128, 8),
+        "i128" => (i128::MIN, i128::MAX as u128, 16),
+        _ => return None,
+    })
+}
+
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+// Borsh layout, which is what Anchor deserializes instruction args with
+fn encode(idl: &Idl, ty: &Json, value: &ArgValue, out: &mut Vec<u8>) -> Result<(), String> {
+    if let Some(name) = ty.as_str() {
+        if let Some((min, max, width)) = int_bounds(name) {
+            // Two's complement truncated to the declared width
+            let bits = match *value {
+                ArgValue::UInt(v) if v <= max => v,
+                ArgValue::Int(v) if v >= min && (v < 0 || v as u128 <= max) => v as u128,
+                _ => return Err(format!("{:?} does not fit in {}", value, name)),
+            };
+            out.extend_from_slice(&bits.to_le_bytes()[..width]);
+            return Ok(());
+        }
+
+        return match (name, value) {
+            ("bool", ArgValue::Bool(b)) => {
+                out.push(*b as u8);
+                Ok(())
+            }
+            ("string", ArgValue::String(s)) => {
+                encode_len(s.len(), out);
+                out.extend_from_slice(s.as_bytes());
+                Ok(())
+            }
+            ("bytes", ArgValue::Bytes(b)) => {
+                encode_len(b.len(), out);
+                out.extend_from_slice(b);
+                Ok(())
+            }
+            ("bytes", ArgValue::Vec(items)) => {
+                encode_len(items.len(), out);
+                for item in items {
+                    encode(idl, &Json::from("u8"), item, out)?;
+                }
+                Ok(())
+            }
+            ("pubkey" | "publicKey", ArgValue::Pubkey(key)) => {
+                out.extend_from_slice(key.as_ref());
+                Ok(())
+            }
+            _ => Err(format!("expected {}, got {:?}", name, value)),
+        };
+    }
+
+    if let Some(inner) = ty.get("vec") {
+        let ArgValue::Vec(items) = value else {
+            return Err(format!("expected vec, got {:?}", value));
+        };
+        encode_len(items.len(), out);
+        return items.iter().try_for_each(|item| encode(idl, inner, item, out));
+    }
+
+    if let Some(inner) = ty.get("option") {
+        let ArgValue::Option(opt) = value else {
+            return Err(format!("expected option, got {:?}", value));
+        };
+        return match opt {
+            None => {
+                out.push(0);
+                Ok(())
+            }
+            Some(v) => {
+                out.push(1);
+                encode(idl, inner, v, out)
+            }
+        };
+    }
+
+    if let Some(array) = ty.get("array").and_then(Json::as_array) {
+        let len = array.get(1).and_then(Json::as_u64).unwrap_or_default() as usize;
+        let items = match value {
+            ArgValue::Vec(items) => items.clone(),
+            ArgValue::Bytes(b) => b.iter().map(|&b| ArgValue::UInt(b as u128)).collect(),
+            _ => return Err(format!("expected array, got {:?}", value)),
+        };
+        if items.len() != len {
+            return Err(format!("expected {} elements, got {}", len, items.len()));
+        }
+        return items.iter().try_for_each(|item| encode(idl, &array[0], item, out));
+    }
+
+    if let Some(defined) = ty.get("defined") {
+        // 0.30 uses {"defined": {"name": ..}}, older IDLs {"defined": ".."}
+        let name = defined
+            .get("name")
+            .and_then(Json::as_str)
+            .or_else(|| defined.as_str())
+            .unwrap_or_default();
+        return encode_defined(idl, idl.defined_type(name), value, out);
+    }
+
+    Err(format!("unsupported IDL type {}", ty))
+}
+
+fn encode_defined(idl: &Idl, def: &Json, value: &ArgValue, out: &mut Vec<u8>) -> Result<(), String> {
+    let name = def["name"].as_str().unwrap_or_default();
+    let kind = &def["type"];
+
+    match (kind["kind"].as_str(), value) {
+        (Some("struct"), ArgValue::Struct(fields)) => {
+            for field in kind["fields"].as_array().unwrap_or(&Vec::new()) {
+                let field_name = field["name"].as_str().unwrap_or_default();
+                let (_, v) = fields
+                    .iter()
+                    .find(|(n, _)| n == field_name)
+                    .ok_or_else(|| format!("{} is missing field {}", name, field_name))?;
+                encode(idl, &field["type"], v, out)?;
+            }
+            Ok(())
+        }
+        (Some("enum"), ArgValue::Enum(variant, values)) => {
+            let variants = kind["variants"].as_array().cloned().unwrap_or_default();
+            let index = variants
+                .iter()
+                .position(|v| v["name"].as_str() == Some(variant))
+                .ok_or_else(|| format!("{} has no variant {}", name, variant))?;
+            out.push(index as u8);
+
+            let fields = variants[index]["fields"].as_array().cloned().unwrap_or_default();
+            if fields.len() != values.len() {
+                return Err(format!(
+                    "{}::{} takes {} fields, got {}",
+                    name,
+                    variant,
+                    fields.len(),
+                    values.len()
+                ));
+            }
+            for (field, v) in fields.iter().zip(values) {
+                // Named fields carry {"name", "type"}, tuple fields are bare types
+                let ty = field.get("type").unwrap_or(field);
+                encode(idl, ty, v, out)?;
+            }
+            Ok(())
+        }
+        _ => Err(format!("expected {}, got {:?}", name, value)),
+    }
+}
+
+// Rust-like spelling of an IDL type, e.g. `vec<u64>` or `[u8; 32]`
+fn type_name(ty: &Json) -> String {
+    if let Some(name) = ty.as_str() {
+        return name.to_string();
+    }
+    if let Some(inner) = ty.get("vec") {
+        return format!("vec<{}>", type_name(inner));
+    }
+    if let Some(inner) = ty.get("option") {
+        return format!("option<{}>", type_name(inner));
+    }
+    if let Some(array) = ty.get("array").and_then(Json::as_array) {
+        let len = array.get(1).map(Json::to_string).unwrap_or_default();
+        return format!("[{}; {}]", type_name(&array[0]), len);
+    }
+    if let Some(defined) = ty.get("defined") {
+        return defined
+            .get("name")
+            .and_then(Json::as_str)
+            .or_else(|| defined.as_str())
+            .unwrap_or_default()
+            .to_string();
+    }
+    ty.to_string()
+}
+
+// Borsh size of a type, if it doesn't depend on the value
+fn fixed_size(idl: &Idl, ty: &Json) -> Option<usize> {
+    if let Some(name) = ty.as_str() {
+        if let Some((_, _, width)) = int_bounds(name) {
+            return Some(width);
+        }
+        return match name {
+            "bool" => Some(1),
+            "pubkey" | "publicKey" => Some(32),
+            _ => None,
+        };
+    }
+
+    if let Some(array) = ty.get("array").and_then(Json::as_array) {
+        let len = array.get(1).and_then(Json::as_u64)? as usize;
+        return Some(fixed_size(idl, &array[0])? * len);
+    }
+
+    let defined = ty.get("defined")?;
+    let name = defined
+        .get("name")
+        .and_then(Json::as_str)
+        .or_else(|| defined.as_str())?;
+    let kind = &idl.defined_type(name)["type"];
+    match kind["kind"].as_str()? {
+        "struct" => kind["fields"]
+            .as_array()?
+            .iter()
+            .map(|f| fixed_size(idl, &f["type"]))
+            .sum(),
+        // Fieldless enums are a single tag byte
+        "enum" => kind["variants"]
+            .as_array()?
+            .iter()
+            .all(|v| v.get("fields").is_none())
+            .then_some(1),
+        _ => None,
+    }
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if data.len() < len {
+        return Err(format!("needed {} bytes, {} left", len, data.len()));
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+fn decode_len(data: &mut &[u8]) -> Result<usize, String> {
+    Ok(u32::from_le_bytes(take(data, 4)?.try_into().unwrap()) as usize)
+}
+
+// Inverse of `encode`, for reading account data back
+fn decode(idl: &Idl, ty: &Json, data: &mut &[u8]) -> Result<ArgValue, String> {
+    if let Some(name) = ty.as_str() {
+        if let Some((min, _, width)) = int_bounds(name) {
+            let mut bytes = [0u8; 16];
+            bytes[..width].copy_from_slice(take(data, width)?);
+            if min == 0 {
+                return Ok(ArgValue::UInt(u128::from_le_bytes(bytes)));
+            }
+            // Sign-extend from the declared width
+            let shift = 128 - 8 * width as u32;
+            return Ok(ArgValue::Int(i128::from_le_bytes(bytes) << shift >> shift));
+        }
+
+        return match name {
+            "bool" => Ok(ArgValue::Bool(take(data, 1)?[0] != 0)),
+            "string" => {
+                let len = decode_len(data)?;
+                let bytes = take(data, len)?;
+                String::from_utf8(bytes.to_vec())
+                    .map(ArgValue::String)
+                    .map_err(|e| e.to_string())
+            }
+            "bytes" => {
+                let len = decode_len(data)?;
+                Ok(ArgValue::Bytes(take(data, len)?.to_vec()))
+            }
+            "pubkey" | "publicKey" => Ok(ArgValue::Pubkey(Pubkey::try_from(take(data, 32)?).unwrap())),
+            _ => Err(format!("unsupported IDL type {}", name)),
+        };
+    }
+
+    if let Some(inner) = ty.get("vec") {
+        let len = decode_len(data)?;
+        return (0..len)
+            .map(|_| decode(idl, inner, data))
+            .collect::<Result<_, _>>()
+            .map(ArgValue::Vec);
+    }
+
+    if let Some(inner) = ty.get("option") {
+        return match take(data, 1)?[0] {
+            0 => Ok(ArgValue::Option(None)),
+            _ => Ok(ArgValue::Option(Some(Box::new(decode(idl, inner, data)?)))),
+        };
+    }
+
+    if let Some(array) = ty.get("array").and_then(Json::as_array) {
+        let len = array.get(1).and_then(Json::as_u64).unwrap_or_default() as usize;
+        return (0..len)
+            .map(|_| decode(idl, &array[0], data))
+            .collect::<Result<_, _>>()
+            .map(ArgValue::Vec);
+    }
+
+    if let Some(defined) = ty.get("defined") {
+        let name = defined
+            .get("name")
+            .and_then(Json::as_str)
+            .or_else(|| defined.as_str())
+            .unwrap_or_default();
+        return decode_defined(idl, idl.defined_type(name), data);
+    }
+
+    Err(format!("unsupported IDL type {}", ty))
+}
+
+fn decode_defined(idl: &Idl, def: &Json, data: &mut &[u8]) -> Result<ArgValue, String> {
+    let name = def["name"].as_str().unwrap_or_default();
+    let kind = &def["type"];
+
+    match kind["kind"].as_str() {
+        Some("struct") => {
+            let mut fields = Vec::new();
+            for field in kind["fields"].as_array().unwrap_or(&Vec::new()) {
+                let field_name = field["name"].as_str().unwrap_or_default().to_string();
+                fields.push((field_name, decode(idl, &field["type"], data)?));
+            }
+            Ok(ArgValue::Struct(fields))
+        }
+        Some("enum") => {
+            let variants = kind["variants"].as_array().cloned().unwrap_or_default();
+            let index = take(data, 1)?[0] as usize;
+            let variant = variants
+                .get(index)
+                .ok_or_else(|| format!("{} has no variant {}", name, index))?;
+            let values = variant["fields"]
+                .as_array()
+                .unwrap_or(&Vec::new())
+                .iter()
+                .map(|field| decode(idl, field.get("type").unwrap_or(field), data))
+                .collect::<Result<_, _>>()?;
+            Ok(ArgValue::Enum(
+                variant["name"].as_str().unwrap_or_default().to_string(),
+                values,
+            ))
+        }
+        _ => Err(format!("{} is not a struct or enum", name)),
+    }
+}
@@ -0,0 +1,167 @@
+//! Run the same victim and attacker transactions in every relevant order
+//!
+//! Front-running, sandwiches and initialization races only show up for some
+//! orderings, and a test that hand-picks one of them proves little about the
+//! others. [`Scenario::interleave`] takes the victim's transaction and the
+//! attacker's (one for a front-run, two for a sandwich), replays them from
+//! the same starting state in each [`Order`], and hands back the results and
+//! a snapshot of where each ordering ended:
+//!
+//! ```ignore
+//! let victim = TxTemplate::new("victim", vec![init_ix], &[&admin]);
+//! let attack = TxTemplate::new("attacker", vec![attack_ix], &[&attacker]);
+//!
+//! for run in svm.interleave(&victim, &[attack]) {
+//!     svm.restore(run.end);
+//!     assert_eq!(read_admin(&svm), attacker.pubkey(), "{:?}", run.order);
+//! }
+//! ```
+//!
+//! The scenario is left at the starting state afterwards.
+
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+
+use crate::scenario::{Scenario, SnapshotId};
+use crate::sdk::{Instruction, Keypair, Message, Signer, Transaction};
+
+/// Instructions plus the keypairs that sign them; the first signer pays.
+///
+/// Transactions are only built when an ordering runs, so each one gets the
+/// blockhash current at that point.
+pub struct TxTemplate<'a> {
+    pub label: String,
+    pub instructions: Vec<Instruction>,
+    pub signers: Vec<&'a Keypair>,
+}
+
+impl<'a> TxTemplate<'a> {
+    pub fn new(label: &str, instructions: Vec<Instruction>, signers: &[&'a Keypair]) -> Self {
+        assert!(
+            !signers.is_empty(),
+            "{}: a transaction needs a fee payer",
+            label
+        );
+        Self {
+            label: label.to_string(),
+            instructions,
+            signers: signers.to_vec(),
+        }
+    }
+
+    fn transaction(&self, svm: &LiteSVM) -> Transaction {
+        let msg = Message::new(&self.instructions, Some(&self.signers[0].pubkey()));
+        Transaction::new(&self.signers, msg, svm.latest_blockhash())
+    }
+
+    // Every transaction of `parts` as one atomic transaction, paid by the
+    // first part's payer
+    fn bundle(parts: &[&TxTemplate<'a>]) -> Self {
+        let mut signers: Vec<&Keypair> = Vec::new();
+        for signer in parts.iter().flat_map(|p| &p.signers) {
+            if !signers.iter().any(|s| s.pubkey() == signer.pubkey()) {
+                signers.push(signer);
+            }
+        }
+        Self {
+            label: "bundle".to_string(),
+            instructions: parts
+                .iter()
+                .flat_map(|p| p.instructions.iter().cloned())
+                .collect(),
+            signers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Victim lands, then every attacker transaction
+    VictimFirst,
+    /// Every attacker transaction lands before the victim's
+    AttackerFirst,
+    /// First attacker transaction, victim, then the rest. Only run with two
+    /// or more attacker transactions.
+    Sandwich,
+    /// Attacker's first instructions, the victim's, then the rest, all in
+    /// one transaction paid by the attacker
+    Bundled,
+}
+
+/// One explored ordering.
+pub struct Interleaving {
+    pub order: Order,
+    /// Template label and result of each transaction, in the order sent
+    pub results: Vec<(String, TransactionResult)>,
+    /// State after the last transaction, for [`Scenario::restore`]
+    pub end: SnapshotId,
+}
+
+impl Interleaving {
+    /// Result of the transaction labelled `label`. For [`Order::Bundled`]
+    /// the only label is `"bundle"`.
+    pub fn result(&self, label: &str) -> &TransactionResult {
+        self.results
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, result)| result)
+            .unwrap_or_else(|| panic!("{:?}: no transaction labelled {}", self.order, label))
+    }
+}
+
+impl Scenario {
+    /// Run `victim` and `attacker` in every [`Order`] that applies, each
+    /// from the current state.
+    pub fn interleave(
+        &mut self,
+        victim: &TxTemplate<'_>,
+        attacker: &[TxTemplate<'_>],
+    ) -> Vec<Interleaving> {
+        assert!(
+            !attacker.is_empty(),
+            "interleave needs at least one attacker transaction"
+        );
+        let (front, back) = (&attacker[0], &attacker[1..]);
+        let start = self.snapshot();
+
+        let bundle =
+            TxTemplate::bundle(&[front, victim].into_iter().chain(back).collect::<Vec<_>>());
+        let mut orders: Vec<(Order, Vec<&TxTemplate>)> = vec![
+            (
+                Order::VictimFirst,
+                [victim].into_iter().chain(attacker).collect(),
+            ),
+            (
+                Order::AttackerFirst,
+                attacker.iter().chain([victim]).collect(),
+            ),
+        ];
+        if !back.is_empty() {
+            orders.push((
+                Order::Sandwich,
+                [front, victim].into_iter().chain(back).collect(),
+            ));
+        }
+        orders.push((Order::Bundled, vec![&bundle]));
+
+        let mut runs = Vec::new();
+        for (order, txs) in orders {
+            self.restore(start);
+            let results = txs
+                .into_iter()
+                .map(|template| {
+                    let tx = template.transaction(self);
+                    (template.label.clone(), self.send_transaction(tx))
+                })
+                .collect();
+            runs.push(Interleaving {
+                order,
+                results,
+                end: self.snapshot(),
+            });
+        }
+
+        self.restore(start);
+        runs
+    }
+}
@@ -0,0 +1,1006 @@
+//! The demos against a real validator
+//!
+//! [`crate::demo`] plays each exploit in LiteSVM only. Here the same setup
+//! and scripts run on a live cluster and, transaction for transaction, in a
+//! LiteSVM [`Rehearsal`], and [`run`] reports every place the two disagree:
+//! a result, an error, or the state of the stage's accounts after each side.
+//!
+//! A [`Cluster`] is either a `solana-test-validator` started and stopped
+//! here, with the programs and the accounts the demos plant loaded at
+//! genesis, or a remote RPC node such as devnet. On a remote cluster the
+//! programs are deployed with the `solana` CLI and record the cluster they
+//! are on, the demo keypairs are funded by a payer instead of the faucet and
+//! swept back to it afterwards, and demos that plant accounts are left out,
+//! since nothing can write them.
+//!
+//! A live cluster can't be restored, so unlike [`crate::demo::run`] the secure side
+//! runs on the state the vulnerable side left, in the rehearsal too.
+//!
+//! Checks that LiteSVM can't answer, such as those on vote accounts or on
+//! real slot progression, can be written once against a [`Backend`] and run
+//! on both. They need a validator, so the harness runs them only with
+//! `SOLSEC_VALIDATOR=1`, each on a [`Validator::workspace`] with every
+//! deployed program loaded:
+//!
+//! ```bash
+//! SOLSEC_VALIDATOR=1 cargo test --test harness validator
+//! ```
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::demo::{Demo, Palette, Stage};
+use crate::errors::AnchorError;
+use crate::fixtures::AccountFixture;
+use crate::idl::{ArgValue, Idl};
+use crate::scenario::{seeded_keypair, Scenario, Step};
+use crate::sdk::{self, Account, Hash, Keypair, Pubkey, Signer, Transaction, VersionedTransaction};
+use crate::verify::{self, Verification};
+
+/// How long a transaction may take to reach `confirmed`
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a local validator may take to answer `getHealth`
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(90);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Fee of a transaction with one signature, kept back when sweeping
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Port of the validator a [`validator_test`] starts
+pub const TEST_VALIDATOR_PORT: u16 = 8899;
+
+pub const VALIDATOR_ENV: &str = "SOLSEC_VALIDATOR";
+
+/// Whether `SOLSEC_VALIDATOR` is set to something other than `0`.
+pub fn validator_enabled() -> bool {
+    std::env::var(VALIDATOR_ENV).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// A workspace validator in `target/test-validator/` for a test, or `None`
+/// without `SOLSEC_VALIDATOR`. Validators share ports, so tests holding one
+/// run one at a time; drop it to let the next start.
+pub fn validator_test() -> Option<(Validator, Rpc, std::sync::MutexGuard<'static, ()>)> {
+    static RUNNING: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    if !validator_enabled() {
+        return None;
+    }
+    let running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = crate::common::target_dir().join("test-validator");
+    let (validator, rpc) = Validator::workspace(&dir, TEST_VALIDATOR_PORT)
+        .unwrap_or_else(|e| panic!("Failed to start the test validator: {}", e));
+    Some((validator, rpc, running))
+}
+
+/// The ledger a check runs against: LiteSVM, or a cluster over [`Rpc`].
+/// LiteSVM executes every transaction it is given, so its results are all
+/// [`LiveResult::Landed`], with the error as the RPC would encode it.
+pub trait Backend {
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>, String>;
+    fn airdrop(&mut self, address: &Pubkey, lamports: u64) -> Result<(), String>;
+    fn latest_blockhash(&self) -> Result<Hash, String>;
+    fn send(&mut self, tx: &Transaction) -> LiveResult;
+}
+
+impl Backend for LiteSVM {
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>, String> {
+        Ok(self.get_account(address))
+    }
+
+    fn airdrop(&mut self, address: &Pubkey, lamports: u64) -> Result<(), String> {
+        LiteSVM::airdrop(self, address, lamports)
+            .map(|_| ())
+            .map_err(|failed| format!("airdrop to {} failed: {:?}", address, failed.err))
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash, String> {
+        Ok(LiteSVM::latest_blockhash(self))
+    }
+
+    fn send(&mut self, tx: &Transaction) -> LiveResult {
+        let signature = tx.signatures[0].to_string();
+        let (err, meta) = match self.send_transaction(tx.clone()) {
+            Ok(meta) => (None, meta),
+            Err(failed) => (
+                Some(serde_json::to_value(&failed.err).expect("error serialization cannot fail")),
+                failed.meta,
+            ),
+        };
+        LiveResult::Landed {
+            signature,
+            err,
+            logs: meta.logs,
+            compute_units: meta.compute_units_consumed,
+        }
+    }
+}
+
+impl Backend for Rpc {
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>, String> {
+        Rpc::account(self, address)
+    }
+
+    fn airdrop(&mut self, address: &Pubkey, lamports: u64) -> Result<(), String> {
+        Rpc::airdrop(self, address, lamports)
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash, String> {
+        Rpc::latest_blockhash(self)
+    }
+
+    fn send(&mut self, tx: &Transaction) -> LiveResult {
+        Rpc::send(self, tx)
+    }
+}
+
+/// JSON-RPC client for the few calls the live runs make.
+pub struct Rpc {
+    url: String,
+}
+
+/// How a transaction ended on the cluster.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveResult {
+    /// Executed; `err` is the RPC's JSON of the error, if it failed
+    Landed {
+        signature: String,
+        err: Option<Value>,
+        logs: Vec<String>,
+        compute_units: u64,
+    },
+    /// Rejected before execution, or never confirmed
+    Dropped(String),
+}
+
+impl LiveResult {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, LiveResult::Landed { err: None, .. })
+    }
+}
+
+impl Rpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .and_then(|r| r.into_json().map_err(Into::into))
+            .map_err(|e| format!("{} to {} failed: {}", method, self.url, e))?;
+        match response.get("error") {
+            Some(err) => Err(format!(
+                "{}: {}",
+                method,
+                err["message"].as_str().unwrap_or(&err.to_string())
+            )),
+            None => Ok(response["result"].take()),
+        }
+    }
+
+    pub fn healthy(&self) -> bool {
+        self.call("getHealth", json!([]))
+            .is_ok_and(|health| health == "ok")
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash, String> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|hash| hash.parse().ok())
+            .ok_or_else(|| "getLatestBlockhash: no blockhash in the response".to_string())
+    }
+
+    /// The hash `solana genesis-hash` prints, which tells clusters apart.
+    pub fn genesis_hash(&self) -> Result<Hash, String> {
+        self.call("getGenesisHash", json!([]))?
+            .as_str()
+            .and_then(|hash| hash.parse().ok())
+            .ok_or_else(|| "getGenesisHash: no hash in the response".to_string())
+    }
+
+    /// The account at `address`, `None` if it doesn't exist.
+    pub fn account(&self, address: &Pubkey) -> Result<Option<Account>, String> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        let value = &result["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+        // The RPC account object has the same shape as the fixture JSON
+        let wrapped = json!({ "pubkey": address.to_string(), "account": value });
+        AccountFixture::from_json(wrapped.to_string().as_bytes())
+            .map(|fixture| Some(fixture.account))
+            .map_err(|e| format!("getAccountInfo: {}", e))
+    }
+
+    /// Airdrop from the cluster's faucet and wait for it to confirm.
+    pub fn airdrop(&self, address: &Pubkey, lamports: u64) -> Result<(), String> {
+        let signature = self.call(
+            "requestAirdrop",
+            json!([address.to_string(), lamports, { "commitment": "confirmed" }]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or("requestAirdrop: no signature in the response")?;
+        match self.confirm(signature)? {
+            None => Ok(()),
+            Some(err) => Err(format!("airdrop to {} failed: {}", address, err)),
+        }
+    }
+
+    /// The cluster's current vote accounts, which LiteSVM has none of.
+    pub fn vote_accounts(&self) -> Result<Vec<Pubkey>, String> {
+        let result = self.call("getVoteAccounts", json!([{ "commitment": "confirmed" }]))?;
+        result["current"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|account| {
+                account["votePubkey"]
+                    .as_str()
+                    .and_then(|key| key.parse().ok())
+                    .ok_or_else(|| "getVoteAccounts: bad votePubkey".to_string())
+            })
+            .collect()
+    }
+
+    /// Send `tx` without preflight, so that failing transactions land with
+    /// their logs too, and wait for it to confirm.
+    pub fn send(&self, tx: &Transaction) -> LiveResult {
+        let bytes = bincode::serialize(tx).expect("transaction serialization cannot fail");
+        let sent = self.call(
+            "sendTransaction",
+            json!([BASE64.encode(bytes), { "encoding": "base64", "skipPreflight": true }]),
+        );
+        let signature = match sent {
+            Ok(Value::String(signature)) => signature,
+            Ok(other) => return LiveResult::Dropped(format!("sendTransaction returned {}", other)),
+            Err(e) => return LiveResult::Dropped(e),
+        };
+        if let Err(e) = self.confirm(&signature) {
+            return LiveResult::Dropped(e);
+        }
+
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        );
+        let meta = match result {
+            Ok(result) if !result["meta"].is_null() => result["meta"].clone(),
+            Ok(_) => return LiveResult::Dropped(format!("{} has no metadata", signature)),
+            Err(e) => return LiveResult::Dropped(e),
+        };
+        LiveResult::Landed {
+            signature,
+            err: Some(meta["err"].clone()).filter(|err| !err.is_null()),
+            logs: meta["logMessages"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|log| log.as_str().map(str::to_string))
+                .collect(),
+            compute_units: meta["computeUnitsConsumed"].as_u64().unwrap_or(0),
+        }
+    }
+
+    // Poll until `signature` is confirmed; the error it failed with, if any
+    fn confirm(&self, signature: &str) -> Result<Option<Value>, String> {
+        let deadline = Instant::now() + CONFIRM_TIMEOUT;
+        while Instant::now() < deadline {
+            let statuses = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &statuses["value"][0];
+            if matches!(
+                status["confirmationStatus"].as_str(),
+                Some("confirmed" | "finalized")
+            ) {
+                return Ok(Some(status["err"].clone()).filter(|err| !err.is_null()));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        Err(format!(
+            "{} not confirmed after {}s",
+            signature,
+            CONFIRM_TIMEOUT.as_secs()
+        ))
+    }
+}
+
+/// A `solana-test-validator` process; dropping it kills the validator and
+/// removes its directory.
+pub struct Validator {
+    child: Child,
+    dir: PathBuf,
+}
+
+impl Validator {
+    /// Start a validator on `port` with its ledger in `dir`, the `programs`
+    /// (address and `.so`) and `accounts` loaded at genesis, and wait until
+    /// it serves RPC.
+    pub fn start(
+        dir: &Path,
+        port: u16,
+        programs: &[(Pubkey, PathBuf)],
+        accounts: &[AccountFixture],
+    ) -> io::Result<(Self, Rpc)> {
+        let accounts_dir = dir.join("accounts");
+        std::fs::create_dir_all(&accounts_dir)?;
+
+        let ledger = dir.join("ledger");
+        let mut command = Command::new("solana-test-validator");
+        command
+            .args(["--reset", "--quiet", "--rpc-port", &port.to_string()])
+            .arg("--ledger")
+            .arg(&ledger);
+        for (program_id, so) in programs {
+            command
+                .arg("--bpf-program")
+                .arg(program_id.to_string())
+                .arg(so);
+        }
+        for fixture in accounts {
+            let path = accounts_dir.join(format!("{}.json", fixture.address));
+            fixture.write(&path)?;
+            command
+                .arg("--account")
+                .arg(fixture.address.to_string())
+                .arg(&path);
+        }
+
+        let child = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => io::Error::new(
+                    e.kind(),
+                    "solana-test-validator not found; install the Solana CLI",
+                ),
+                _ => e,
+            })?;
+        let mut validator = Self {
+            child,
+            dir: dir.to_path_buf(),
+        };
+        let rpc = Rpc::new(format!("http://127.0.0.1:{}", port));
+
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while !rpc.healthy() {
+            if let Some(status) = validator.child.try_wait()? {
+                return Err(io::Error::other(format!(
+                    "solana-test-validator exited with {}; see {}",
+                    status,
+                    ledger.join("validator.log").display()
+                )));
+            }
+            if Instant::now() > deadline {
+                return Err(io::Error::other(format!(
+                    "solana-test-validator did not answer on port {} within {}s",
+                    port,
+                    STARTUP_TIMEOUT.as_secs()
+                )));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        Ok((validator, rpc))
+    }
+
+    /// [`Validator::start`] with every program in the deploy dir loaded at
+    /// its keypair's address and recorded as on devnet, as in LiteSVM.
+    pub fn workspace(dir: &Path, port: u16) -> io::Result<(Self, Rpc)> {
+        let deploy = crate::common::deploy_dir();
+        let mut names: Vec<String> = std::fs::read_dir(&deploy)?
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                let name = name.strip_suffix(".so")?.to_string();
+                deploy
+                    .join(format!("{}-keypair.json", name))
+                    .exists()
+                    .then_some(name)
+            })
+            .collect();
+        names.sort();
+
+        let svm = LiteSVM::new();
+        let mut programs = Vec::new();
+        let mut accounts = Vec::new();
+        for name in names {
+            let program_id = crate::load_program_id(&name);
+            let record = crate::common::cluster_record_account(
+                &svm,
+                &program_id,
+                crate::common::DEVNET_GENESIS_HASH,
+            );
+            accounts.push(AccountFixture::new(
+                crate::common::cluster_record(&program_id),
+                record,
+            ));
+            programs.push((program_id, deploy.join(format!("{}.so", name))));
+        }
+        Self::start(dir, port, &programs, &accounts)
+    }
+}
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Where the demos run live, and who pays for them.
+pub struct Cluster {
+    pub rpc: Rpc,
+    /// Funds the demo keypairs on clusters without a usable faucet
+    payer: Option<(Keypair, PathBuf)>,
+    validator: Option<Validator>,
+}
+
+impl Cluster {
+    /// A local validator in `dir` with every rehearsal's programs, their
+    /// cluster records and planted accounts loaded at genesis.
+    pub fn local(dir: &Path, port: u16, rehearsals: &[Rehearsal]) -> io::Result<Self> {
+        let mut programs: Vec<(Pubkey, PathBuf)> = Vec::new();
+        let mut accounts = Vec::new();
+        for rehearsal in rehearsals {
+            for name in rehearsal.programs() {
+                let so = crate::common::deploy_dir().join(format!("{}.so", name));
+                let program_id = crate::load_program_id(&name);
+                if !programs.iter().any(|(id, _)| *id == program_id) {
+                    programs.push((program_id, so));
+                    // Loaded without an upgrade authority to record it
+                    let record = crate::common::cluster_record(&program_id);
+                    if let Some(account) = rehearsal.svm.get_account(&record) {
+                        accounts.push(AccountFixture::new(record, account));
+                    }
+                }
+            }
+            accounts.extend(rehearsal.planted());
+        }
+        let (validator, rpc) = Validator::start(dir, port, &programs, &accounts)?;
+        Ok(Self {
+            rpc,
+            payer: None,
+            validator: Some(validator),
+        })
+    }
+
+    /// A remote cluster at `url`, paid for by the keypair file at `payer`.
+    pub fn remote(url: &str, payer: &Path) -> io::Result<Self> {
+        let bytes: Vec<u8> = serde_json::from_str(&std::fs::read_to_string(payer)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let keypair = Keypair::from_bytes(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(Self {
+            rpc: Rpc::new(url),
+            payer: Some((keypair, payer.to_path_buf())),
+            validator: None,
+        })
+    }
+
+    pub fn is_local(&self) -> bool {
+        self.validator.is_some()
+    }
+
+    /// The payer's address on a remote cluster.
+    pub fn payer(&self) -> Option<Pubkey> {
+        self.payer.as_ref().map(|(keypair, _)| keypair.pubkey())
+    }
+
+    /// Deploy `target/deploy/<name>.so` at its keypair's address with the
+    /// `solana` CLI, then record the cluster; programs on a local validator
+    /// are loaded at genesis.
+    pub fn deploy(&self, name: &str) -> io::Result<()> {
+        let dir = crate::common::deploy_dir();
+        let program_id = dir.join(format!("{}-keypair.json", name));
+        let so = dir.join(format!("{}.so", name));
+        self.solana(&["program", "deploy"], |command| {
+            command.arg("--program-id").arg(&program_id).arg(&so);
+        })?;
+        self.record_cluster(name).map_err(io::Error::other)
+    }
+
+    // Send `record_cluster` with this cluster's genesis hash, as the upgrade
+    // authority the deploy made the payer; without the record the vulnerable
+    // instructions refuse to run
+    fn record_cluster(&self, name: &str) -> Result<(), String> {
+        let idl = Idl::load(name);
+        if !idl
+            .instructions
+            .iter()
+            .any(|ix| ix.name == "record_cluster")
+        {
+            return Ok(());
+        }
+        let program_id = crate::load_program_id(name);
+        // Recorded by an earlier deploy to the same address
+        if self
+            .rpc
+            .account(&crate::common::cluster_record(&program_id))?
+            .is_some()
+        {
+            return Ok(());
+        }
+        let (payer, _) = self
+            .payer
+            .as_ref()
+            .ok_or("only remote clusters record with the payer")?;
+        let program_data =
+            crate::common::find_pda(&[program_id.as_ref()], &sdk::bpf_loader_upgradeable::ID).0;
+        let genesis_hash = self.rpc.genesis_hash()?;
+        let ix = idl
+            .ix("record_cluster")
+            .arg(
+                "genesis_hash",
+                ArgValue::Bytes(genesis_hash.to_bytes().to_vec()),
+            )
+            .account("authority", payer.pubkey())
+            .account("program", program_id)
+            .account("program_data", program_data)
+            .build();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            self.rpc.latest_blockhash()?,
+        );
+        match self.rpc.send(&tx) {
+            result if result.is_ok() => Ok(()),
+            result => Err(format!(
+                "recording the cluster of {} failed: {:?}",
+                name, result
+            )),
+        }
+    }
+
+    /// Compare the program deployed for `name` with `target/deploy/<name>.so`.
+    pub fn verify(&self, name: &str) -> Result<Verification, String> {
+        verify::verify(
+            &crate::load_program_id(name),
+            &crate::load_program_bytes(name),
+            |address| self.rpc.account(address),
+        )
+    }
+
+    /// Close the program at `program_id`, returning its rent to the payer.
+    /// The address can't be deployed to again afterwards.
+    pub fn close_program(&self, program_id: &Pubkey) -> io::Result<()> {
+        self.solana(&["program", "close", "--bypass-warning"], |command| {
+            command.arg(program_id.to_string());
+        })
+    }
+
+    fn solana(&self, subcommand: &[&str], args: impl FnOnce(&mut Command)) -> io::Result<()> {
+        let (_, path) = self
+            .payer
+            .as_ref()
+            .ok_or_else(|| io::Error::other("only remote clusters deploy with the solana CLI"))?;
+        let mut command = Command::new("solana");
+        command
+            .args(subcommand)
+            .args(["--url", self.rpc.url()])
+            .arg("--keypair")
+            .arg(path);
+        args(&mut command);
+        let status = command.status().map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => {
+                io::Error::new(e.kind(), "solana not found; install the Solana CLI")
+            }
+            _ => e,
+        })?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "solana {} exited with {}",
+                subcommand.join(" "),
+                status
+            )))
+        }
+    }
+
+    /// Give `address` the lamports an airdrop in the rehearsal gave it.
+    pub fn fund(&self, address: &Pubkey, lamports: u64) -> Result<(), String> {
+        let Some((payer, _)) = &self.payer else {
+            return self.rpc.airdrop(address, lamports);
+        };
+        let ix = sdk::system_instruction::transfer(&payer.pubkey(), address, lamports);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            self.rpc.latest_blockhash()?,
+        );
+        match self.rpc.send(&tx) {
+            result if result.is_ok() => Ok(()),
+            result => Err(format!("funding {} failed: {:?}", address, result)),
+        }
+    }
+
+    /// Return what is left on the rehearsal's keypairs to the payer.
+    ///
+    /// Only plain system accounts are swept; the rest are reported.
+    pub fn sweep(&self, rehearsal: &Rehearsal) -> Vec<String> {
+        let Some((payer, _)) = &self.payer else {
+            return Vec::new();
+        };
+        let mut left = Vec::new();
+        for keypair in rehearsal.keypairs() {
+            let account = match self.rpc.account(&keypair.pubkey()) {
+                Ok(Some(account)) => account,
+                Ok(None) => continue,
+                Err(e) => {
+                    left.push(e);
+                    continue;
+                }
+            };
+            if account.owner != sdk::system_program::ID || !account.data.is_empty() {
+                left.push(format!(
+                    "{} ({} lamports) is not a plain system account",
+                    keypair.pubkey(),
+                    account.lamports
+                ));
+                continue;
+            }
+            if account.lamports <= LAMPORTS_PER_SIGNATURE {
+                continue;
+            }
+            let ix = sdk::system_instruction::transfer(
+                &keypair.pubkey(),
+                &payer.pubkey(),
+                account.lamports - LAMPORTS_PER_SIGNATURE,
+            );
+            let swept = self.rpc.latest_blockhash().and_then(|blockhash| {
+                let tx = Transaction::new_signed_with_payer(
+                    &[ix],
+                    Some(&keypair.pubkey()),
+                    &[&keypair],
+                    blockhash,
+                );
+                match self.rpc.send(&tx) {
+                    result if result.is_ok() => Ok(()),
+                    result => Err(format!(
+                        "sweeping {} failed: {:?}",
+                        keypair.pubkey(),
+                        result
+                    )),
+                }
+            });
+            if let Err(e) = swept {
+                left.push(e);
+            }
+        }
+        left
+    }
+}
+
+/// A demo set up in LiteSVM, played next to the live cluster.
+pub struct Rehearsal {
+    pub demo: &'static Demo,
+    svm: Scenario,
+    stage: Stage,
+}
+
+impl Rehearsal {
+    /// Set `demo` up in LiteSVM with keys derived from `seed` and the
+    /// program, so demos sharing a validator don't share keypairs.
+    pub fn new(demo: &'static Demo, seed: u64) -> Self {
+        let digest = Sha256::new()
+            .chain_update(seed.to_le_bytes())
+            .chain_update(demo.program.as_bytes())
+            .finalize();
+        let seed = u64::from_le_bytes(digest[..8].try_into().unwrap());
+
+        let mut svm = Scenario::new(&format!("live/{}", demo.program), seed);
+        svm.add_program(demo.deploy);
+        let stage = (demo.setup)(&mut svm);
+        Self { demo, svm, stage }
+    }
+
+    /// Programs the setup deployed, by `.so` name.
+    pub fn programs(&self) -> Vec<String> {
+        self.svm
+            .log()
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                Step::Program { name } => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Accounts the setup wrote directly rather than through a transaction.
+    pub fn planted(&self) -> Vec<AccountFixture> {
+        self.svm
+            .log()
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                Step::SetAccount { account, .. } => BASE64.decode(account).ok(),
+                _ => None,
+            })
+            .map(|bytes| AccountFixture::from_bincode(&bytes).expect("the scenario wrote it"))
+            .collect()
+    }
+
+    fn keypairs(&self) -> Vec<Keypair> {
+        let log = self.svm.log();
+        log.keypairs
+            .keys()
+            .map(|label| seeded_keypair(log.seed, label))
+            .collect()
+    }
+}
+
+/// Sign `tx` again for `blockhash` with whichever scenario keypairs it needs.
+fn resign(
+    mut tx: Transaction,
+    keypairs: &[Keypair],
+    blockhash: Hash,
+) -> Result<Transaction, String> {
+    let required = tx.message.header.num_required_signatures as usize;
+    let signers = tx.message.account_keys[..required]
+        .iter()
+        .map(|key| {
+            keypairs
+                .iter()
+                .find(|keypair| keypair.pubkey() == *key)
+                .ok_or_else(|| format!("no scenario keypair signs for {}", key))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    tx.try_sign(&signers, blockhash)
+        .map_err(|e| format!("re-signing failed: {}", e))?;
+    Ok(tx)
+}
+
+/// Replay the rehearsal's setup on `cluster`, then play both sides there
+/// and in LiteSVM, narrating each comparison to `out`.
+///
+/// Returns the number of transactions and account states that differ. An
+/// `Err` means the live setup itself failed and nothing was compared.
+pub fn run(
+    rehearsal: &mut Rehearsal,
+    cluster: &Cluster,
+    out: &mut impl Write,
+    palette: Palette,
+) -> io::Result<usize> {
+    let demo = rehearsal.demo;
+    writeln!(
+        out,
+        "{} {}",
+        palette.bold(demo.vulnerability().title),
+        palette.dim(&format!("({} on {})", demo.program, cluster.rpc.url()))
+    )?;
+    let keypairs = rehearsal.keypairs();
+    replay_setup(rehearsal, cluster, &keypairs).map_err(io::Error::other)?;
+
+    let mut mismatches = 0;
+    for instruction in [demo.vulnerable, demo.secure] {
+        writeln!(out)?;
+        writeln!(out, "{}", palette.bold(&format!("== {} ==", instruction)))?;
+        for action in (rehearsal.stage.script)(&rehearsal.svm, instruction) {
+            writeln!(out, "{} {}", palette.yellow(">"), action.narration)?;
+            let live = cluster
+                .rpc
+                .latest_blockhash()
+                .and_then(|blockhash| resign(action.tx.clone(), &keypairs, blockhash))
+                .map_or_else(LiveResult::Dropped, |tx| cluster.rpc.send(&tx));
+            let local = rehearsal.svm.send_transaction(action.tx);
+            if !compare(&local, &live, out, palette)? {
+                mismatches += 1;
+            }
+        }
+        mismatches += compare_state(rehearsal, cluster, out, palette)?;
+    }
+
+    writeln!(out)?;
+    if mismatches == 0 {
+        writeln!(
+            out,
+            "{}: {}",
+            demo.program,
+            palette.green("matches LiteSVM")
+        )?;
+    } else {
+        writeln!(
+            out,
+            "{}: {}",
+            demo.program,
+            palette.red(&format!("{} differences from LiteSVM", mismatches))
+        )?;
+    }
+    Ok(mismatches)
+}
+
+fn replay_setup(
+    rehearsal: &Rehearsal,
+    cluster: &Cluster,
+    keypairs: &[Keypair],
+) -> Result<(), String> {
+    for (i, step) in rehearsal.svm.log().steps.iter().enumerate() {
+        match step {
+            // Deployed or loaded at genesis before the run
+            Step::Program { .. } | Step::SetAccount { .. } => {}
+            Step::Airdrop { pubkey, lamports } => {
+                let pubkey = pubkey
+                    .parse()
+                    .map_err(|_| format!("step {}: bad pubkey", i))?;
+                cluster
+                    .fund(&pubkey, *lamports)
+                    .map_err(|e| format!("setup step {}: {}", i, e))?;
+            }
+            Step::Transaction { tx, result } => {
+                let bytes = BASE64.decode(tx).map_err(|e| e.to_string())?;
+                let tx: VersionedTransaction =
+                    bincode::deserialize(&bytes).map_err(|e| e.to_string())?;
+                let tx = tx.into_legacy_transaction().ok_or_else(|| {
+                    format!("setup step {}: v0 transactions aren't replayed live", i)
+                })?;
+                let live = resign(tx, keypairs, cluster.rpc.latest_blockhash()?)
+                    .map_or_else(LiveResult::Dropped, |tx| cluster.rpc.send(&tx));
+                if live.is_ok() != (result == "ok") {
+                    return Err(format!(
+                        "setup step {}: LiteSVM gave {}, the cluster {:?}",
+                        i, result, live
+                    ));
+                }
+            }
+            Step::Warp { .. } => {
+                return Err(format!("setup step {}: a live cluster can't warp", i));
+            }
+            // A live cluster's blockhash moves on by itself
+            Step::ExpireBlockhash => {}
+        }
+    }
+    Ok(())
+}
+
+// Print both results; true if they agree
+fn compare(
+    local: &TransactionResult,
+    live: &LiveResult,
+    out: &mut impl Write,
+    palette: Palette,
+) -> io::Result<bool> {
+    let anchor = |logs: &[String]| {
+        AnchorError::from_logs(logs)
+            .map(|err| format!(" {} ({})", err.name, err.number))
+            .unwrap_or_default()
+    };
+
+    let local_err = match local {
+        Ok(meta) => {
+            writeln!(out, "  litesvm: ok ({} CU)", meta.compute_units_consumed)?;
+            None
+        }
+        Err(failed) => {
+            let err = serde_json::to_value(&failed.err).expect("error serialization cannot fail");
+            writeln!(
+                out,
+                "  litesvm: failed {}{} ({} CU)",
+                err,
+                anchor(&failed.meta.logs),
+                failed.meta.compute_units_consumed
+            )?;
+            Some(err)
+        }
+    };
+
+    let agree = match live {
+        LiveResult::Landed {
+            signature,
+            err,
+            logs,
+            compute_units,
+        } => {
+            match err {
+                None => writeln!(
+                    out,
+                    "  live:    ok ({} CU) {}",
+                    compute_units,
+                    palette.dim(signature)
+                )?,
+                Some(err) => writeln!(
+                    out,
+                    "  live:    failed {}{} ({} CU) {}",
+                    err,
+                    anchor(logs),
+                    compute_units,
+                    palette.dim(signature)
+                )?,
+            }
+            *err == local_err
+        }
+        LiveResult::Dropped(reason) => {
+            writeln!(out, "  live:    dropped: {}", reason)?;
+            false
+        }
+    };
+    if agree {
+        writeln!(out, "  {}", palette.green("match"))?;
+    } else {
+        writeln!(out, "  {}", palette.red("MISMATCH"))?;
+    }
+    Ok(agree)
+}
+
+// Compare the stage's accounts on both sides; the number that differ
+fn compare_state(
+    rehearsal: &Rehearsal,
+    cluster: &Cluster,
+    out: &mut impl Write,
+    palette: Palette,
+) -> io::Result<usize> {
+    // An account drained to zero is gone on a validator
+    let key = |account: Option<Account>| {
+        account
+            .filter(|a| a.lamports > 0)
+            .map(|a| (a.lamports, a.owner, a.data))
+    };
+
+    let mut differ = 0;
+    writeln!(out, "  state:")?;
+    for (label, address) in &rehearsal.stage.accounts {
+        let local = key(rehearsal.svm.get_account(address));
+        let live = match cluster.rpc.account(address) {
+            Ok(account) => key(account),
+            Err(e) => {
+                writeln!(out, "    {:<12} {}", label, palette.red(&e))?;
+                differ += 1;
+                continue;
+            }
+        };
+        let describe = |account: &Option<(u64, Pubkey, Vec<u8>)>| match account {
+            Some((lamports, owner, data)) => {
+                format!(
+                    "{} lamports, {} bytes, owner {}",
+                    lamports,
+                    data.len(),
+                    owner
+                )
+            }
+            None => "missing".to_string(),
+        };
+        if local == live {
+            writeln!(out, "    {:<12} {}", label, palette.dim(&describe(&local)))?;
+        } else {
+            differ += 1;
+            writeln!(
+                out,
+                "    {:<12} {} litesvm {}, live {}",
+                label,
+                palette.red("differs:"),
+                describe(&local),
+                describe(&live)
+            )?;
+        }
+    }
+    Ok(differ)
+}
@@ -0,0 +1,141 @@
+//! Durable nonce accounts and the transactions signed against them
+//!
+//! A transaction normally names a recent blockhash and expires with it,
+//! about a minute and a half after signing. One whose first instruction
+//! advances a durable nonce account names the nonce instead, and stays
+//! valid until the nonce is advanced, however long that takes. These helpers
+//! create nonce accounts inside LiteSVM and sign transactions against them:
+//!
+//! ```ignore
+//! use security_tests::nonce;
+//!
+//! svm.create_nonce_account(&payer, &nonce, &owner.pubkey());
+//! let held = nonce::nonce_transaction(&svm, &[withdraw_ix], &[&owner], &nonce.pubkey());
+//! svm.expire_blockhash();
+//! let result = svm.send_transaction(held); // still lands
+//! ```
+//!
+//! LiteSVM only accepts a nonce transaction once the blockhash has moved
+//! past the one the nonce was taken from, so creating or advancing a nonce
+//! account expires the blockhash afterwards. A [`Scenario`] logs the
+//! expiry, so replays find the same nonces.
+
+use litesvm::LiteSVM;
+
+use crate::alt::Ledger;
+use crate::scenario::Scenario;
+use crate::sdk::{
+    self, system_instruction as system_ix, Hash, Instruction, Keypair, Message, Pubkey, Signer,
+    Transaction,
+};
+
+/// Size of a nonce account's data: version and state tags, the authority,
+/// the nonce and the fee calculator.
+pub const NONCE_ACCOUNT_SIZE: usize = 80;
+
+/// The `Initialized` state tag, after the version tag.
+const INITIALIZED: u32 = 1;
+
+/// Create the nonce account `nonce`, funded rent-exempt by `payer`, that
+/// only `authority` can advance. It can be signed against once this returns.
+pub fn create_nonce_account(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    nonce: &Keypair,
+    authority: &Pubkey,
+) -> Hash {
+    create(svm, payer, nonce, authority)
+}
+
+/// Advance `nonce`, which invalidates every transaction signed against its
+/// current value, and return the new one.
+pub fn advance_nonce_account(svm: &mut LiteSVM, nonce: &Pubkey, authority: &Keypair) -> Hash {
+    advance(svm, nonce, authority)
+}
+
+/// The value stored in the nonce account at `nonce`, which a transaction
+/// names in place of a recent blockhash.
+///
+/// Read by hand: the authority is at bytes 8..40 and the nonce at 40..72,
+/// after the `u32` version and state tags.
+pub fn nonce_value(svm: &LiteSVM, nonce: &Pubkey) -> Hash {
+    let account = svm
+        .get_account(nonce)
+        .unwrap_or_else(|| panic!("nonce account {} does not exist", nonce));
+    let data = &account.data;
+    let initialized = account.owner == sdk::system_program::ID
+        && data.len() == NONCE_ACCOUNT_SIZE
+        && data[4..8] == INITIALIZED.to_le_bytes();
+    assert!(initialized, "{} is not an initialized nonce account", nonce);
+    Hash::new_from_array(data[40..72].try_into().unwrap())
+}
+
+/// Sign `instructions` against `nonce`, paid by `signers[0]`. An
+/// `AdvanceNonceAccount` by `authority` is prepended, as the runtime
+/// requires; `authority` must be one of `signers`.
+pub fn nonce_transaction(
+    svm: &LiteSVM,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+    nonce: &Pubkey,
+    authority: &Pubkey,
+) -> Transaction {
+    let payer = signers.first().expect("a transaction needs a fee payer");
+    let mut ixs = vec![system_ix::advance_nonce_account(nonce, authority)];
+    ixs.extend_from_slice(instructions);
+    let msg = Message::new(&ixs, Some(&payer.pubkey()));
+    Transaction::new(signers, msg, nonce_value(svm, nonce))
+}
+
+fn send_or_panic(ledger: &mut impl Ledger, what: &str, tx: Transaction) {
+    if let Err(failed) = ledger.send(tx) {
+        panic!(
+            "Failed to {}: {:?}\n{}",
+            what,
+            failed.err,
+            failed.meta.logs.join("\n")
+        );
+    }
+}
+
+fn create(ledger: &mut impl Ledger, payer: &Keypair, nonce: &Keypair, authority: &Pubkey) -> Hash {
+    let lamports = ledger
+        .svm()
+        .minimum_balance_for_rent_exemption(NONCE_ACCOUNT_SIZE);
+    let ixs =
+        system_ix::create_nonce_account(&payer.pubkey(), &nonce.pubkey(), authority, lamports);
+    let msg = Message::new(&ixs, Some(&payer.pubkey()));
+    let tx = Transaction::new(&[payer, nonce], msg, ledger.svm().latest_blockhash());
+    send_or_panic(ledger, "create nonce account", tx);
+
+    // The nonce was taken from the current blockhash
+    ledger.expire();
+    nonce_value(ledger.svm(), &nonce.pubkey())
+}
+
+fn advance(ledger: &mut impl Ledger, nonce: &Pubkey, authority: &Keypair) -> Hash {
+    let ix = system_ix::advance_nonce_account(nonce, &authority.pubkey());
+    let msg = Message::new(&[ix], Some(&authority.pubkey()));
+    let tx = Transaction::new(&[authority], msg, ledger.svm().latest_blockhash());
+    send_or_panic(ledger, "advance nonce account", tx);
+
+    ledger.expire();
+    nonce_value(ledger.svm(), nonce)
+}
+
+impl Scenario {
+    /// [`create_nonce_account`], recorded in the scenario log.
+    pub fn create_nonce_account(
+        &mut self,
+        payer: &Keypair,
+        nonce: &Keypair,
+        authority: &Pubkey,
+    ) -> Hash {
+        create(self, payer, nonce, authority)
+    }
+
+    /// [`advance_nonce_account`], recorded in the scenario log.
+    pub fn advance_nonce_account(&mut self, nonce: &Pubkey, authority: &Keypair) -> Hash {
+        advance(self, nonce, authority)
+    }
+}
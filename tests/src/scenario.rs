@@ -0,0 +1,378 @@
+//! Deterministic, replayable test scenarios
+//!
+//! A `Scenario` wraps LiteSVM so that every keypair comes from a seed and
+//! every state change (program deploy, airdrop, `set_account`, transaction)
+//! is appended to a log. Running the same test twice therefore produces the
+//! same addresses, the same signatures and the same results, and when a test
+//! panics the log is written to `target/scenarios/<name>.json`:
+//!
+//! ```ignore
+//! let mut svm = Scenario::new("signer_authorization/vulnerable_withdraw", 1);
+//! svm.add_program("signer_authorization");
+//! let attacker = svm.keypair("attacker");
+//! svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+//! ```
+//!
+//! A dumped log can be loaded again with [`Scenario::replay`], which re-runs
+//! each step and stops at the first transaction whose result differs from
+//! the recorded one.
+//!
+//! Read-only `LiteSVM` methods (`get_account`, `latest_blockhash`, ...) are
+//! reachable through `Deref`; the mutating ones are re-implemented here so
+//! nothing slips past the log.
+//!
+//! Expensive setup can be done once and shared by several attack variants
+//! with [`Scenario::snapshot`] and [`Scenario::restore`]:
+//!
+//! ```ignore
+//! let ready = svm.snapshot();
+//! let vulnerable = svm.send_transaction(exploit("vulnerable_withdraw"));
+//! svm.restore(ready);
+//! let secure = svm.send_transaction(exploit("secure_withdraw"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::io;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use litesvm::error::LiteSVMError;
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::fixtures::AccountFixture;
+use crate::sdk::{keypair_from_seed, Account, Keypair, Pubkey, Signer, VersionedTransaction};
+
+/// Signature fee of LiteSVM's default fee structure.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Where failing scenarios are dumped.
+pub fn scenarios_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("target/scenarios")
+}
+
+/// One recorded state change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    /// `target/deploy/<name>.so` deployed under its keypair address
+    Program {
+        name: String,
+    },
+    Airdrop {
+        pubkey: String,
+        lamports: u64,
+    },
+    /// Base64 bincode of the [`AccountFixture`]
+    SetAccount {
+        pubkey: String,
+        account: String,
+    },
+    /// Base64 bincode of the signed transaction, legacy or v0, and `Debug`
+    /// of its outcome (`"ok"` on success)
+    Transaction {
+        tx: String,
+        result: String,
+    },
+    /// Clock moved to `slot`, see [`crate::alt::warp_to_slot`]
+    Warp {
+        slot: u64,
+    },
+    /// Blockhash moved on, see [`Scenario::expire_blockhash`]
+    ExpireBlockhash,
+}
+
+/// The serializable part of a scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioLog {
+    pub name: String,
+    pub seed: u64,
+    /// Label -> pubkey of every keypair handed out, for reading dumps
+    pub keypairs: BTreeMap<String, String>,
+    pub steps: Vec<Step>,
+}
+
+pub struct Scenario {
+    svm: LiteSVM,
+    log: ScenarioLog,
+    snapshots: Vec<Snapshot>,
+}
+
+/// Handle returned by [`Scenario::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotId(usize);
+
+struct Snapshot {
+    svm: LiteSVM,
+    /// The log as it was when the snapshot was taken
+    steps: Vec<Step>,
+}
+
+/// Derive the keypair for `label` under `seed`.
+///
+/// sha256("scenario" || seed || label) is used as the ed25519 secret, so
+/// labels are independent of each other and of the order they are requested.
+pub fn seeded_keypair(seed: u64, label: &str) -> Keypair {
+    let secret = Sha256::new()
+        .chain_update(b"scenario")
+        .chain_update(seed.to_le_bytes())
+        .chain_update(label.as_bytes())
+        .finalize();
+    keypair_from_seed(&secret).expect("sha256 output is a valid ed25519 seed")
+}
+
+fn outcome(result: &TransactionResult) -> String {
+    match result {
+        Ok(_) => "ok".to_string(),
+        Err(failed) => format!("{:?}", failed.err),
+    }
+}
+
+impl Scenario {
+    pub fn new(name: &str, seed: u64) -> Self {
+        Self {
+            svm: LiteSVM::new(),
+            log: ScenarioLog {
+                name: name.to_string(),
+                seed,
+                keypairs: BTreeMap::new(),
+                steps: Vec::new(),
+            },
+            snapshots: Vec::new(),
+        }
+    }
+
+    pub fn log(&self) -> &ScenarioLog {
+        &self.log
+    }
+
+    /// The keypair for `label`; asking twice returns the same key.
+    pub fn keypair(&mut self, label: &str) -> Keypair {
+        let keypair = seeded_keypair(self.log.seed, label);
+        self.log
+            .keypairs
+            .insert(label.to_string(), keypair.pubkey().to_string());
+        keypair
+    }
+
+    /// Deploy `target/deploy/<name>.so` at the address of its deploy keypair
+    /// and record the cluster, as on devnet.
+    pub fn add_program(&mut self, name: &str) -> Pubkey {
+        let program_id = crate::load_program_id(name);
+        self.svm
+            .add_program(program_id, &crate::load_program_bytes(name));
+        // Part of the deploy, so not a step of its own
+        crate::common::record_cluster(
+            &mut self.svm,
+            &program_id,
+            crate::common::DEVNET_GENESIS_HASH,
+        );
+        self.log.steps.push(Step::Program {
+            name: name.to_string(),
+        });
+        program_id
+    }
+
+    // Same signature as LiteSVM, so suites can swap it in unchanged
+    #[allow(clippy::result_large_err)]
+    pub fn airdrop(&mut self, pubkey: &Pubkey, lamports: u64) -> TransactionResult {
+        let result = self.svm.airdrop(pubkey, lamports);
+        if result.is_ok() {
+            self.log.steps.push(Step::Airdrop {
+                pubkey: pubkey.to_string(),
+                lamports,
+            });
+        }
+        result
+    }
+
+    pub fn set_account(&mut self, pubkey: Pubkey, account: Account) -> Result<(), LiteSVMError> {
+        let fixture = AccountFixture::new(pubkey, account.clone());
+        self.svm.set_account(pubkey, account)?;
+        self.log.steps.push(Step::SetAccount {
+            pubkey: pubkey.to_string(),
+            account: BASE64.encode(fixture.to_bincode()),
+        });
+        Ok(())
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn send_transaction(&mut self, tx: impl Into<VersionedTransaction>) -> TransactionResult {
+        let tx = tx.into();
+        let encoded = BASE64.encode(bincode::serialize(&tx).expect("transaction serializes"));
+        let result = self.svm.send_transaction(tx);
+        self.log.steps.push(Step::Transaction {
+            tx: encoded,
+            result: outcome(&result),
+        });
+        result
+    }
+
+    /// Move the clock to `slot`, keeping `SlotHashes` in step so lookup
+    /// tables can be created there.
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        crate::alt::warp_to_slot(&mut self.svm, slot);
+        self.log.steps.push(Step::Warp { slot });
+    }
+
+    /// Move to a new blockhash, expiring every transaction signed against
+    /// the current one. Transactions signed with a durable nonce survive;
+    /// see [`crate::nonce`].
+    pub fn expire_blockhash(&mut self) {
+        self.svm.expire_blockhash();
+        self.log.steps.push(Step::ExpireBlockhash);
+    }
+
+    /// Capture the full state (accounts, programs, blockhash, transaction
+    /// history) so it can be returned to with [`restore`](Self::restore).
+    pub fn snapshot(&mut self) -> SnapshotId {
+        self.snapshots.push(Snapshot {
+            svm: self.svm.clone(),
+            steps: self.log.steps.clone(),
+        });
+        SnapshotId(self.snapshots.len() - 1)
+    }
+
+    /// Return to the state captured by `id`. A snapshot can be restored any
+    /// number of times, one branch after another.
+    ///
+    /// The log is put back to what it was at the snapshot, so a dump always
+    /// replays the branch that is currently live. Transaction history
+    /// is rewound too, which lets a branch resend a transaction an earlier
+    /// branch already landed.
+    pub fn restore(&mut self, id: SnapshotId) {
+        let snapshot = self
+            .snapshots
+            .get(id.0)
+            .unwrap_or_else(|| panic!("{:?} was not taken on this scenario", id));
+        self.svm = snapshot.svm.clone();
+        self.log.steps = snapshot.steps.clone();
+    }
+
+    /// Accounts referenced by the steps recorded from `start` on, and the
+    /// fee each landed transaction charged its payer. Failed transactions
+    /// change nothing in LiteSVM, fees included.
+    pub(crate) fn touched_since(&self, start: usize) -> (Vec<Pubkey>, Vec<(Pubkey, u64)>) {
+        let (mut touched, mut fees) = (Vec::new(), Vec::new());
+        for step in &self.log.steps[start.min(self.log.steps.len())..] {
+            match step {
+                Step::Airdrop { pubkey, .. } | Step::SetAccount { pubkey, .. } => {
+                    touched.extend(pubkey.parse::<Pubkey>().ok());
+                }
+                Step::Transaction { tx, result } => {
+                    let tx: VersionedTransaction = BASE64
+                        .decode(tx)
+                        .ok()
+                        .and_then(|bytes| bincode::deserialize(&bytes).ok())
+                        .expect("recorded transactions decode");
+                    let payer = tx.message.static_account_keys().first();
+                    if let (Some(payer), "ok") = (payer, &result[..]) {
+                        let signatures = tx.message.header().num_required_signatures as u64;
+                        fees.push((*payer, signatures * LAMPORTS_PER_SIGNATURE));
+                    }
+                    touched.extend(crate::alt::account_keys(&self.svm, &tx));
+                }
+                Step::Program { .. } | Step::Warp { .. } | Step::ExpireBlockhash => {}
+            }
+        }
+        (touched, fees)
+    }
+
+    /// Write the log as pretty JSON.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json =
+            serde_json::to_string_pretty(&self.log).expect("scenario serialization cannot fail");
+        std::fs::write(path, json + "\n")
+    }
+
+    /// Rebuild a scenario from a saved log by re-running every step.
+    ///
+    /// Panics at the first transaction whose result differs from the log,
+    /// naming the step, since that means the run is no longer reproducible.
+    pub fn replay(path: &Path) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let log: ScenarioLog =
+            serde_json::from_slice(&std::fs::read(path)?).map_err(|e| invalid(e.to_string()))?;
+        let mut scenario = Self::new(&log.name, log.seed);
+        scenario.log.keypairs = log.keypairs;
+
+        for (i, step) in log.steps.into_iter().enumerate() {
+            match step {
+                Step::Program { name } => {
+                    scenario.add_program(&name);
+                }
+                Step::Airdrop { pubkey, lamports } => {
+                    let pubkey = pubkey
+                        .parse()
+                        .map_err(|_| invalid(format!("step {}: bad pubkey", i)))?;
+                    let result = scenario.airdrop(&pubkey, lamports);
+                    assert!(
+                        result.is_ok(),
+                        "step {}: airdrop failed on replay: {:?}",
+                        i,
+                        result
+                    );
+                }
+                Step::SetAccount { account, .. } => {
+                    let bytes = BASE64.decode(account).map_err(|e| invalid(e.to_string()))?;
+                    let fixture = AccountFixture::from_bincode(&bytes)?;
+                    scenario
+                        .set_account(fixture.address, fixture.account)
+                        .map_err(|e| invalid(format!("step {}: {:?}", i, e)))?;
+                }
+                Step::Transaction { tx, result } => {
+                    let bytes = BASE64.decode(tx).map_err(|e| invalid(e.to_string()))?;
+                    let tx: VersionedTransaction =
+                        bincode::deserialize(&bytes).map_err(|e| invalid(e.to_string()))?;
+                    let replayed = outcome(&scenario.send_transaction(tx));
+                    assert_eq!(
+                        replayed, result,
+                        "step {}: transaction result diverged from the recorded run",
+                        i
+                    );
+                }
+                Step::Warp { slot } => scenario.warp_to_slot(slot),
+                Step::ExpireBlockhash => scenario.expire_blockhash(),
+            }
+        }
+
+        Ok(scenario)
+    }
+}
+
+impl Deref for Scenario {
+    type Target = LiteSVM;
+
+    fn deref(&self) -> &LiteSVM {
+        &self.svm
+    }
+}
+
+impl Drop for Scenario {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            return;
+        }
+        let path = scenarios_dir().join(format!("{}.json", self.log.name));
+        match self.save(&path) {
+            Ok(()) => eprintln!(
+                "scenario {} (seed {}) saved to {}; reload it with Scenario::replay",
+                self.log.name,
+                self.log.seed,
+                path.display()
+            ),
+            Err(e) => eprintln!("failed to save scenario {}: {}", self.log.name, e),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! The Solana types the suites use, from either dependency set
+//!
+//! Every suite and harness module imports Solana types from here rather
+//! than from a Solana crate, so the same tests build against the split
+//! 2.2 crates (`split-crates`, the default) or the solana-sdk 2.2 facade
+//! (`--no-default-features --features sdk`):
+//!
+//! ```ignore
+//! use security_tests::sdk::{self, Signer, LAMPORTS_PER_SOL};
+//!
+//! let ix = sdk::system_instruction::transfer(&payer.pubkey(), &to, LAMPORTS_PER_SOL);
+//! assert_eq!(account.owner, sdk::system_program::ID);
+//! ```
+//!
+//! The facade re-exports the same split crates, so both sets give the
+//! types LiteSVM 0.6.1 takes. With both features on, `sdk` wins. Several of
+//! the facade's paths are deprecated in favor of the split crates; they are
+//! the only route to these items for a solana-sdk consumer, so the arm
+//! below allows it.
+
+#[cfg(not(any(feature = "split-crates", feature = "sdk")))]
+compile_error!("security-tests needs the `split-crates` or the `sdk` feature");
+
+#[cfg(all(feature = "split-crates", not(feature = "sdk")))]
+pub use split::*;
+
+#[cfg(feature = "sdk")]
+pub use facade::*;
+
+#[cfg(all(feature = "split-crates", not(feature = "sdk")))]
+mod split {
+    pub use solana_account::Account;
+    pub use solana_address_lookup_table_interface::state::AddressLookupTable;
+    pub use solana_clock::Clock;
+    pub use solana_compute_budget_interface::ComputeBudgetInstruction;
+    pub use solana_hash::Hash;
+    pub use solana_instruction::{error::InstructionError, AccountMeta, Instruction};
+    pub use solana_keypair::{keypair_from_seed, Keypair};
+    pub use solana_message::compiled_instruction::CompiledInstruction;
+    pub use solana_message::inner_instruction::InnerInstruction;
+    pub use solana_message::v0::MessageAddressTableLookup;
+    pub use solana_message::{v0, AddressLookupTableAccount, Message, VersionedMessage};
+    pub use solana_native_token::LAMPORTS_PER_SOL;
+    pub use solana_program_option::COption;
+    pub use solana_program_pack::Pack;
+    pub use solana_pubkey::{pubkey, Pubkey};
+    pub use solana_signer::Signer;
+    pub use solana_slot_hashes::SlotHashes;
+    pub use solana_transaction::{versioned::VersionedTransaction, Transaction};
+    pub use solana_transaction_error::TransactionError;
+
+    pub use solana_sdk_ids::{
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, compute_budget, loader_v4,
+        system_program, sysvar, vote,
+    };
+
+    /// System program instruction builders
+    pub mod system_instruction {
+        pub use solana_system_interface::instruction::{
+            advance_nonce_account, create_account, create_nonce_account, transfer,
+        };
+    }
+
+    /// Address lookup table instruction builders
+    pub mod address_lookup_table {
+        pub use solana_address_lookup_table_interface::instruction::{
+            create_lookup_table, extend_lookup_table, freeze_lookup_table,
+        };
+    }
+}
+
+#[cfg(feature = "sdk")]
+#[allow(deprecated)]
+mod facade {
+    pub use solana_sdk::account::Account;
+    pub use solana_sdk::address_lookup_table::state::AddressLookupTable;
+    pub use solana_sdk::clock::Clock;
+    pub use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    pub use solana_sdk::hash::Hash;
+    pub use solana_sdk::inner_instruction::InnerInstruction;
+    pub use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+    pub use solana_sdk::message::compiled_instruction::CompiledInstruction;
+    pub use solana_sdk::message::v0::MessageAddressTableLookup;
+    pub use solana_sdk::message::{v0, AddressLookupTableAccount, Message, VersionedMessage};
+    pub use solana_sdk::native_token::LAMPORTS_PER_SOL;
+    pub use solana_sdk::program_option::COption;
+    pub use solana_sdk::program_pack::Pack;
+    pub use solana_sdk::pubkey;
+    pub use solana_sdk::pubkey::Pubkey;
+    pub use solana_sdk::signature::{keypair_from_seed, Keypair, Signer};
+    pub use solana_sdk::slot_hashes::SlotHashes;
+    pub use solana_sdk::transaction::{Transaction, TransactionError, VersionedTransaction};
+
+    pub use solana_sdk::{
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, compute_budget, loader_v4,
+        system_program, sysvar,
+    };
+
+    /// The vote program's id
+    pub mod vote {
+        pub use solana_sdk::vote::program::ID;
+    }
+
+    /// System program instruction builders
+    pub mod system_instruction {
+        pub use solana_sdk::system_instruction::{
+            advance_nonce_account, create_account, create_nonce_account, transfer,
+        };
+    }
+
+    /// Address lookup table instruction builders
+    pub mod address_lookup_table {
+        pub use solana_sdk::address_lookup_table::instruction::{
+            create_lookup_table, extend_lookup_table, freeze_lookup_table,
+        };
+    }
+}
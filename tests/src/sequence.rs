@@ -0,0 +1,659 @@
+//! Stateful fuzzing over instruction sequences
+//!
+//! Single-shot fuzzing only finds what one transaction can break. Most
+//! exploits here need a setup step, a second actor, or an ordering. A
+//! [`SequenceFuzzer`] describes a program's instructions as
+//! [`InstructionTemplate`]s, turns fuzzer bytes into a sequence of steps by
+//! several actors, runs it, and checks every invariant after each step:
+//!
+//! ```ignore
+//! use security_tests::sequence::{AccountTemplate as A, ArgTemplate, InstructionTemplate, Seed, SequenceFuzzer};
+//!
+//! let vault = A::Pda(vec![Seed::bytes(b"vault"), Seed::AnyActor]);
+//! let fuzzer = SequenceFuzzer::new(idl(), actors)
+//!     .instruction(InstructionTemplate::new("initialize")
+//!         .account("authority", A::Caller)
+//!         .account("vault", A::Pda(vec![Seed::bytes(b"vault"), Seed::Caller])))
+//!     .instruction(InstructionTemplate::new("secure_withdraw")
+//!         .arg("amount", ArgTemplate::Amount(5 * LAMPORTS_PER_SOL))
+//!         .account("vault", vault)
+//!         .account("authority", A::AnyActor)
+//!         .account("destination", A::AnyActor))
+//!     .invariant("lamports are conserved", invariants::lamports_conserved);
+//!
+//! if let Err(violation) = fuzzer.run(&svm, data) {
+//!     panic!("{}", violation);
+//! }
+//! ```
+//!
+//! The caller of each step signs and pays. An actor placed in any other
+//! signer slot signs only when the step's `co_sign` says so, so the fuzzer
+//! also explores missing signatures.
+//!
+//! With [`SequenceFuzzer::cu_limits`] each step also runs under a compute
+//! limit read from the input. Every `secure_*` step is then replayed as its
+//! `vulnerable_*` counterpart under the same limit, and a secure
+//! instruction that needs more than a realistic budget where the
+//! vulnerable one doesn't is a violation: anyone who can make the checks
+//! that expensive can keep legitimate calls from landing. The compute used
+//! by each instruction goes into a [`CuHistogram`], which
+//! [`SequenceFuzzer::write_histogram`] writes next to the compute summary.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
+
+use litesvm::LiteSVM;
+
+use crate::compute::{self, ComputeUnits, CuBudget, CuHistogram, CuRun, DEFAULT_CU_LIMIT};
+use crate::idl::{ArgValue, Idl};
+use crate::sdk::{Instruction, Keypair, Message, Pubkey, Signer, Transaction, TransactionError};
+
+/// Signature fee of LiteSVM's default fee structure.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Reads values from fuzzer input. Once the input is used up every read
+/// returns zero, so any byte string decodes to something.
+pub struct FuzzInput<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> FuzzInput<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn u8(&mut self) -> u8 {
+        match self.data.split_first() {
+            Some((first, rest)) => {
+                self.data = rest;
+                *first
+            }
+            None => 0,
+        }
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.u8() & 1 == 1
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes.iter_mut().for_each(|b| *b = self.u8());
+        u64::from_le_bytes(bytes)
+    }
+
+    /// An index below `len`.
+    pub fn choose(&mut self, len: usize) -> usize {
+        assert!(len > 0 && len <= 256, "choose: {} options", len);
+        self.u8() as usize % len
+    }
+
+    /// An amount up to `max`. Small values, edge values and the maximum come
+    /// up far more often than a uniform pick would give them.
+    pub fn amount(&mut self, max: u64) -> u64 {
+        let modulus = max.saturating_add(1);
+        match self.u8() % 4 {
+            0 => self.u8() as u64 % modulus,
+            1 => max,
+            2 => max / 2,
+            _ => self.u64() % modulus,
+        }
+    }
+
+    /// Lamports for an account whose rent-exempt minimum is `rent_exempt`:
+    /// zero, one below the minimum, the minimum, one above it, `u64::MAX`,
+    /// or an [`amount`](Self::amount) up to `max`.
+    pub fn lamports(&mut self, rent_exempt: u64, max: u64) -> u64 {
+        match self.u8() % 8 {
+            0 => 0,
+            1 => rent_exempt.saturating_sub(1),
+            2 => rent_exempt,
+            3 => rent_exempt.saturating_add(1),
+            4 => u64::MAX,
+            _ => self.amount(max),
+        }
+    }
+}
+
+/// Deterministic bytes for running fuzz logic as a plain test.
+pub fn pseudo_random_input(seed: u64, len: usize) -> Vec<u8> {
+    // splitmix64, one output byte per step
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_add(0x9E3779B97F4A7C15);
+            let mut x = state;
+            x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+            (x ^ (x >> 31)) as u8
+        })
+        .collect()
+}
+
+/// Part of a PDA's seeds.
+#[derive(Debug, Clone)]
+pub enum Seed {
+    Bytes(Vec<u8>),
+    /// The key of the step's caller
+    Caller,
+    /// The key of an actor picked for this seed
+    AnyActor,
+}
+
+impl Seed {
+    pub fn bytes(bytes: &[u8]) -> Self {
+        Seed::Bytes(bytes.to_vec())
+    }
+}
+
+/// Where an account slot's key comes from.
+#[derive(Debug, Clone)]
+pub enum AccountTemplate {
+    /// The actor sending the step
+    Caller,
+    /// An actor picked for this slot
+    AnyActor,
+    /// The program's PDA for these seeds
+    Pda(Vec<Seed>),
+    /// One of these addresses
+    OneOf(Vec<Pubkey>),
+}
+
+/// How an argument's value is picked.
+#[derive(Debug, Clone)]
+pub enum ArgTemplate {
+    /// Up to the given maximum, biased towards edge values
+    Amount(u64),
+    Bool,
+    /// One of these values
+    OneOf(Vec<ArgValue>),
+}
+
+/// An instruction the fuzzer may send, with how to fill in each argument
+/// and account. Slots with a fixed address in the IDL, like the system
+/// program, fill themselves in.
+#[derive(Debug, Clone)]
+pub struct InstructionTemplate {
+    pub name: String,
+    pub args: Vec<(String, ArgTemplate)>,
+    pub accounts: Vec<(String, AccountTemplate)>,
+}
+
+impl InstructionTemplate {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            args: Vec::new(),
+            accounts: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &str, template: ArgTemplate) -> Self {
+        self.args.push((name.to_string(), template));
+        self
+    }
+
+    pub fn account(mut self, name: &str, template: AccountTemplate) -> Self {
+        self.accounts.push((name.to_string(), template));
+        self
+    }
+}
+
+/// One step of a generated sequence, fully resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub instruction: String,
+    /// Index of the actor that signs and pays
+    pub caller: usize,
+    /// Whether actors in other signer slots sign too
+    pub co_sign: bool,
+    pub args: Vec<(String, ArgValue)>,
+    pub accounts: Vec<(String, Pubkey)>,
+    /// `SetComputeUnitLimit` sent ahead of the instruction, if any
+    pub cu_limit: Option<u32>,
+}
+
+impl Step {
+    /// Limit the instruction ran under.
+    pub fn effective_cu_limit(&self) -> u32 {
+        self.cu_limit.unwrap_or(DEFAULT_CU_LIMIT)
+    }
+}
+
+/// What an invariant gets to look at after each step.
+pub struct StepCheck<'a> {
+    pub step: &'a Step,
+    /// The transaction the step sent
+    pub tx: &'a Transaction,
+    pub landed: bool,
+    /// Why the transaction failed, if it did
+    pub error: Option<&'a TransactionError>,
+    pub compute_units: u64,
+    pub before: &'a LiteSVM,
+    pub after: &'a LiteSVM,
+}
+
+impl StepCheck<'_> {
+    /// Fee the step's transaction paid. A transaction that failed while
+    /// executing still pays; one rejected before that, e.g. for a missing
+    /// signature, doesn't.
+    pub fn fee(&self) -> u64 {
+        match self.error {
+            None | Some(TransactionError::InstructionError(..)) => {
+                self.tx.signatures.len() as u64 * LAMPORTS_PER_SIGNATURE
+            }
+            Some(_) => 0,
+        }
+    }
+
+    /// Address bound to the named slot of the step.
+    pub fn account(&self, name: &str) -> Pubkey {
+        self.step
+            .accounts
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, key)| *key)
+            .unwrap_or_else(|| panic!("{}: no account named {}", self.step.instruction, name))
+    }
+}
+
+/// Name of the violation a compute-aware fuzzer reports for a secure
+/// instruction that blows the budget its vulnerable twin stays within.
+pub const COMPUTE_INVARIANT: &str = "secure instructions fit the compute budget";
+
+type Invariant = Box<dyn Fn(&StepCheck) -> Result<(), String>>;
+
+/// An invariant that failed, with the sequence that led there.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub invariant: String,
+    pub message: String,
+    /// Every step up to and including the one that broke it
+    pub steps: Vec<Step>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "invariant \"{}\" broken at step {}: {}",
+            self.invariant,
+            self.steps.len() - 1,
+            self.message
+        )?;
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(
+                f,
+                "  {}: {} by actor {}{}{} {:?} {:?}",
+                i,
+                step.instruction,
+                step.caller,
+                if step.co_sign { " (co-signed)" } else { "" },
+                match step.cu_limit {
+                    Some(limit) => format!(" under {} CU", limit),
+                    None => String::new(),
+                },
+                step.args,
+                step.accounts
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates and runs instruction sequences against one program.
+pub struct SequenceFuzzer {
+    idl: Idl,
+    actors: Vec<Keypair>,
+    instructions: Vec<InstructionTemplate>,
+    invariants: Vec<(String, Invariant)>,
+    max_steps: usize,
+    cu_limits: Vec<u32>,
+    cu_budget: u32,
+    histogram: RefCell<CuHistogram>,
+}
+
+impl SequenceFuzzer {
+    pub fn new(idl: Idl, actors: Vec<Keypair>) -> Self {
+        assert!(!actors.is_empty(), "a sequence needs at least one actor");
+        Self {
+            idl,
+            actors,
+            instructions: Vec::new(),
+            invariants: Vec::new(),
+            max_steps: 16,
+            cu_limits: Vec::new(),
+            cu_budget: DEFAULT_CU_LIMIT,
+            histogram: RefCell::default(),
+        }
+    }
+
+    pub fn instruction(mut self, template: InstructionTemplate) -> Self {
+        let ix = self.idl.instruction(&template.name);
+        for (name, _) in &template.args {
+            assert!(
+                ix.args.iter().any(|(n, _)| n == name),
+                "{}: unknown argument {}",
+                template.name,
+                name
+            );
+        }
+        self.instructions.push(template);
+        self
+    }
+
+    /// Check `f` after every step; `Err` is a violation.
+    pub fn invariant(
+        mut self,
+        name: &str,
+        f: impl Fn(&StepCheck) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.invariants.push((name.to_string(), Box::new(f)));
+        self
+    }
+
+    /// Longest sequence read from one input (16 by default).
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Run each step under one of `limits` or the default limit, picked by
+    /// the input, and compare `secure_*` steps with their `vulnerable_*`
+    /// counterparts. [`compute::FUZZED_CU_LIMITS`] covers the usual range.
+    pub fn cu_limits(mut self, limits: &[u32]) -> Self {
+        self.cu_limits = limits.to_vec();
+        self
+    }
+
+    /// Compute a secure instruction may need before it counts as a denial
+    /// of service ([`DEFAULT_CU_LIMIT`] by default).
+    pub fn cu_budget(mut self, units: u32) -> Self {
+        self.cu_budget = units;
+        self
+    }
+
+    /// Compute used per instruction by every step run so far, with
+    /// [`cu_limits`](Self::cu_limits) set.
+    pub fn histogram(&self) -> CuHistogram {
+        self.histogram.borrow().clone()
+    }
+
+    /// Write [`histogram`](Self::histogram) to
+    /// `target/compute-units/histograms/<name>.md`.
+    pub fn write_histogram(&self, name: &str) -> std::io::Result<PathBuf> {
+        self.histogram.borrow().write(name)
+    }
+
+    pub fn actors(&self) -> &[Keypair] {
+        &self.actors
+    }
+
+    /// Decode `data` into a sequence of steps.
+    pub fn decode(&self, data: &[u8]) -> Vec<Step> {
+        assert!(
+            !self.instructions.is_empty(),
+            "a sequence fuzzer needs at least one instruction"
+        );
+        let mut input = FuzzInput::new(data);
+        let mut steps = Vec::new();
+        while !input.is_empty() && steps.len() < self.max_steps {
+            steps.push(self.decode_step(&mut input));
+        }
+        steps
+    }
+
+    fn decode_step(&self, input: &mut FuzzInput) -> Step {
+        let template = &self.instructions[input.choose(self.instructions.len())];
+        let caller = input.choose(self.actors.len());
+        let co_sign = input.bool();
+        // Read only in compute mode, so other sequences decode as before
+        let cu_limit = if self.cu_limits.is_empty() {
+            None
+        } else {
+            match input.choose(self.cu_limits.len() + 1) {
+                0 => None,
+                i => Some(self.cu_limits[i - 1]),
+            }
+        };
+        let actor = |input: &mut FuzzInput| self.actors[input.choose(self.actors.len())].pubkey();
+
+        let args = template
+            .args
+            .iter()
+            .map(|(name, arg)| {
+                let value = match arg {
+                    ArgTemplate::Amount(max) => ArgValue::UInt(input.amount(*max) as u128),
+                    ArgTemplate::Bool => ArgValue::Bool(input.bool()),
+                    ArgTemplate::OneOf(values) => values[input.choose(values.len())].clone(),
+                };
+                (name.clone(), value)
+            })
+            .collect();
+
+        let accounts = template
+            .accounts
+            .iter()
+            .map(|(name, account)| {
+                let key = match account {
+                    AccountTemplate::Caller => self.actors[caller].pubkey(),
+                    AccountTemplate::AnyActor => actor(input),
+                    AccountTemplate::OneOf(keys) => keys[input.choose(keys.len())],
+                    AccountTemplate::Pda(seeds) => {
+                        let parts: Vec<Vec<u8>> = seeds
+                            .iter()
+                            .map(|seed| match seed {
+                                Seed::Bytes(bytes) => bytes.clone(),
+                                Seed::Caller => self.actors[caller].pubkey().to_bytes().to_vec(),
+                                Seed::AnyActor => actor(input).to_bytes().to_vec(),
+                            })
+                            .collect();
+                        let parts: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+                        crate::common::find_pda(&parts, &self.idl.program_id).0
+                    }
+                };
+                (name.clone(), key)
+            })
+            .collect();
+
+        Step {
+            instruction: template.name.clone(),
+            caller,
+            co_sign,
+            args,
+            accounts,
+            cu_limit,
+        }
+    }
+
+    /// Decode `data` and run the sequence on a copy of `svm`, checking every
+    /// invariant after each step. Returns the steps run.
+    pub fn run(&self, svm: &LiteSVM, data: &[u8]) -> Result<Vec<Step>, Violation> {
+        self.run_steps(svm, &self.decode(data))
+    }
+
+    /// Run already decoded `steps`, e.g. a sequence from a past violation.
+    pub fn run_steps(&self, svm: &LiteSVM, steps: &[Step]) -> Result<Vec<Step>, Violation> {
+        let mut svm = svm.clone();
+        for (i, step) in steps.iter().enumerate() {
+            let before = svm.clone();
+            let tx = self.transaction(&svm, step);
+            let result = svm.send_transaction(tx.clone());
+            let compute_units = result.compute_units();
+            let error = result.err().map(|e| e.err);
+            let check = StepCheck {
+                step,
+                tx: &tx,
+                landed: error.is_none(),
+                error: error.as_ref(),
+                compute_units,
+                before: &before,
+                after: &svm,
+            };
+            if !self.cu_limits.is_empty() {
+                if let Err(message) = self.check_compute(&check) {
+                    return Err(Violation {
+                        invariant: COMPUTE_INVARIANT.to_string(),
+                        message,
+                        steps: steps[..=i].to_vec(),
+                    });
+                }
+            }
+            for (name, invariant) in &self.invariants {
+                if let Err(message) = invariant(&check) {
+                    return Err(Violation {
+                        invariant: name.clone(),
+                        message,
+                        steps: steps[..=i].to_vec(),
+                    });
+                }
+            }
+            svm.expire_blockhash();
+        }
+        Ok(steps.to_vec())
+    }
+
+    // Record the step's compute, and for a secure step replay its
+    // vulnerable counterpart on the state the step started from
+    fn check_compute(&self, check: &StepCheck) -> Result<(), String> {
+        let run = |step: &Step, compute_units, error: Option<&TransactionError>| CuRun {
+            limit: step.effective_cu_limit(),
+            consumed: compute_units,
+            exhausted: error.is_some_and(compute::is_exhausted),
+        };
+        let secure = run(check.step, check.compute_units, check.error);
+        self.histogram
+            .borrow_mut()
+            .record(&check.step.instruction, secure);
+
+        let Some(counterpart) = self.counterpart(check.step) else {
+            return Ok(());
+        };
+        let result = check
+            .before
+            .clone()
+            .send_transaction(self.transaction(check.before, &counterpart));
+        let error = result.as_ref().err().map(|e| e.err.clone());
+        let vulnerable = run(&counterpart, result.compute_units(), error.as_ref());
+        self.histogram
+            .borrow_mut()
+            .record(&counterpart.instruction, vulnerable);
+
+        if secure.exceeds(self.cu_budget) && !vulnerable.exceeds(self.cu_budget) {
+            return Err(format!(
+                "{} needs more than {} CU ({} used{} under a limit of {}) where {} fits in {}",
+                check.step.instruction,
+                self.cu_budget,
+                secure.consumed,
+                if secure.exhausted { ", exhausted" } else { "" },
+                secure.limit,
+                counterpart.instruction,
+                vulnerable.consumed
+            ));
+        }
+        Ok(())
+    }
+
+    /// The step sent as the `vulnerable_*` twin of its `secure_*`
+    /// instruction, with the arguments and accounts the twin shares with
+    /// it. `None` for other instructions, or a twin that needs something
+    /// the step doesn't have.
+    pub fn counterpart(&self, step: &Step) -> Option<Step> {
+        let name = format!("vulnerable_{}", step.instruction.strip_prefix("secure_")?);
+        let ix = self.idl.instructions.iter().find(|ix| ix.name == name)?;
+
+        let mut args = Vec::new();
+        for (arg, _) in &ix.args {
+            args.push(step.args.iter().find(|(n, _)| n == arg)?.clone());
+        }
+        let mut accounts = Vec::new();
+        for slot in &ix.accounts {
+            match step.accounts.iter().find(|(n, _)| *n == slot.name) {
+                Some(bound) => accounts.push(bound.clone()),
+                None if slot.address.is_some() || slot.optional => {}
+                None => return None,
+            }
+        }
+        Some(Step {
+            instruction: name,
+            args,
+            accounts,
+            ..step.clone()
+        })
+    }
+
+    fn instruction_for(&self, step: &Step) -> Instruction {
+        let mut builder = self.idl.ix(&step.instruction);
+        for (name, value) in &step.args {
+            builder = builder.arg(name, value.clone());
+        }
+        for (name, key) in &step.accounts {
+            builder = builder.account(name, *key);
+        }
+        builder.build()
+    }
+
+    fn transaction(&self, svm: &LiteSVM, step: &Step) -> Transaction {
+        let mut ix = self.instruction_for(step);
+        let caller = &self.actors[step.caller];
+
+        let mut signers = vec![caller];
+        for meta in ix.accounts.iter_mut().filter(|m| m.is_signer) {
+            if meta.pubkey == caller.pubkey() {
+                continue;
+            }
+            match self.actors.iter().find(|a| a.pubkey() == meta.pubkey) {
+                Some(actor) if step.co_sign => {
+                    if !signers.iter().any(|s| s.pubkey() == actor.pubkey()) {
+                        signers.push(actor);
+                    }
+                }
+                // Not an actor, or not co-signing: pass the key unsigned
+                _ => meta.is_signer = false,
+            }
+        }
+        let budget = CuBudget {
+            unit_limit: step.cu_limit,
+            unit_price: None,
+        };
+        let msg = Message::new(&budget.prepend(&[ix]), Some(&caller.pubkey()));
+        Transaction::new(&signers, msg, svm.latest_blockhash())
+    }
+}
+
+/// Invariants most programs should keep.
+pub mod invariants {
+    use super::StepCheck;
+
+    /// Lamports summed over every account the transaction referenced only
+    /// go down by the fee it paid.
+    pub fn lamports_conserved(check: &StepCheck) -> Result<(), String> {
+        let total = |svm: &litesvm::LiteSVM| -> u128 {
+            check
+                .tx
+                .message
+                .account_keys
+                .iter()
+                .filter_map(|key| svm.get_account(key))
+                .map(|a| a.lamports as u128)
+                .sum()
+        };
+        let (before, after) = (total(check.before), total(check.after));
+        let expected = before - check.fee() as u128;
+        if after == expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "referenced accounts held {} lamports, {} after a {} lamport fee",
+                before,
+                after,
+                check.fee()
+            ))
+        }
+    }
+}
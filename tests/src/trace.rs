@@ -0,0 +1,391 @@
+//! Call trees of a transaction's program invocations
+//!
+//! A multi-CPI exploit (a program calling back into the one that called it,
+//! a borrow repaid through another program, a token transfer three calls
+//! deep) reads badly as a flat log. [`CallTree`] rebuilds the invocations
+//! from a LiteSVM result: one [`CallNode`] per program call, with its depth,
+//! the accounts and data it was invoked with, the messages, `sol_log_data`
+//! entries and return data it logged, its compute units and how it ended.
+//!
+//! ```ignore
+//! let tx = VersionedTransaction::from(tx);
+//! let result = svm.send_transaction(tx.clone());
+//! let calls = CallTree::from_litesvm(&svm, &tx, &result).named(program_id, "amm");
+//! println!("{}", calls);
+//! assert_eq!(calls.shape(), [(1, program_id), (2, spl_token), (2, spl_token)]);
+//! ```
+//!
+//! The structure comes from the logs, the instructions from the message and
+//! LiteSVM's inner instructions. A log cut short by the runtime's log limit
+//! leaves the calls it never closed [`CallResult::Incomplete`], and any call
+//! it lost has no instruction attached.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use litesvm::types::{TransactionMetadata, TransactionResult};
+use litesvm::LiteSVM;
+
+use crate::sdk::{pubkey, Pubkey, VersionedTransaction};
+
+/// Programs named in a rendered tree without being [`CallTree::named`].
+const KNOWN_PROGRAMS: [(Pubkey, &str); 6] = [
+    (pubkey!("11111111111111111111111111111111"), "system"),
+    (
+        pubkey!("ComputeBudget111111111111111111111111111111"),
+        "compute-budget",
+    ),
+    (
+        pubkey!("AddressLookupTab1e1111111111111111111111111"),
+        "address-lookup-table",
+    ),
+    (
+        pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        "spl-token",
+    ),
+    (
+        pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
+        "spl-token-2022",
+    ),
+    (
+        pubkey!("ATokenGPvbdGVxr1b2hRvZHniFPyHBjMhbyJ8qXuXX1"),
+        "associated-token",
+    ),
+];
+
+/// How one call ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallResult {
+    Success,
+    /// The error the runtime logged for it
+    Failed(String),
+    /// The log ends before the call does
+    Incomplete,
+}
+
+/// One program invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallNode {
+    pub program: Pubkey,
+    /// 1 for a top-level instruction, one more per CPI
+    pub depth: usize,
+    /// The instruction an Anchor program logged it was running
+    pub instruction: Option<String>,
+    /// What it was invoked with; empty where the call couldn't be matched
+    /// to an instruction
+    pub accounts: Vec<Pubkey>,
+    pub input: Vec<u8>,
+    /// `msg!` messages, without the `Program log: ` prefix
+    pub logs: Vec<String>,
+    /// `sol_log_data` entries, each a list of fields
+    pub data: Vec<Vec<Vec<u8>>>,
+    pub return_data: Option<Vec<u8>>,
+    pub compute_units: Option<u64>,
+    pub result: CallResult,
+    /// The calls it made, in order
+    pub children: Vec<CallNode>,
+}
+
+impl CallNode {
+    fn new(program: Pubkey, depth: usize) -> Self {
+        CallNode {
+            program,
+            depth,
+            instruction: None,
+            accounts: Vec::new(),
+            input: Vec::new(),
+            logs: Vec::new(),
+            data: Vec::new(),
+            return_data: None,
+            compute_units: None,
+            result: CallResult::Incomplete,
+            children: Vec::new(),
+        }
+    }
+
+    /// This call and every call under it, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &CallNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    fn iter_mut(&mut self, visit: &mut impl FnMut(&mut CallNode)) {
+        visit(self);
+        for child in &mut self.children {
+            child.iter_mut(visit);
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.result == CallResult::Success
+    }
+}
+
+/// Every call a transaction made, one root per top-level instruction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallTree {
+    pub roots: Vec<CallNode>,
+    /// Labels for rendering, from [`CallTree::named`]
+    names: HashMap<Pubkey, String>,
+}
+
+impl CallTree {
+    /// The calls `logs` record, without their instructions.
+    pub fn from_logs(logs: &[String]) -> Self {
+        let mut roots = Vec::new();
+        let mut stack: Vec<CallNode> = Vec::new();
+        let close = |stack: &mut Vec<CallNode>, roots: &mut Vec<CallNode>, result| {
+            if let Some(mut node) = stack.pop() {
+                node.result = result;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => roots.push(node),
+                }
+            }
+        };
+
+        for line in logs {
+            match parse(line) {
+                Some(Line::Invoke(program, depth)) => {
+                    // A call the log never closed, cut short before this one
+                    while stack.len() >= depth {
+                        close(&mut stack, &mut roots, CallResult::Incomplete);
+                    }
+                    stack.push(CallNode::new(program, depth));
+                }
+                Some(Line::Exit(program, result)) => {
+                    if stack.last().is_some_and(|node| node.program == program) {
+                        close(&mut stack, &mut roots, result);
+                    }
+                }
+                Some(line) => {
+                    let Some(node) = stack.last_mut() else {
+                        continue;
+                    };
+                    match line {
+                        Line::Log(message) => {
+                            if let Some(name) = message.strip_prefix("Instruction: ") {
+                                node.instruction.get_or_insert_with(|| name.to_string());
+                            }
+                            node.logs.push(message.to_string());
+                        }
+                        Line::Data(fields) => node.data.push(fields),
+                        Line::Return(data) => node.return_data = Some(data),
+                        Line::Consumed(units) => node.compute_units = Some(units),
+                        Line::Invoke(..) | Line::Exit(..) => unreachable!(),
+                    }
+                }
+                None => {}
+            }
+        }
+        while !stack.is_empty() {
+            close(&mut stack, &mut roots, CallResult::Incomplete);
+        }
+
+        CallTree {
+            roots,
+            names: HashMap::new(),
+        }
+    }
+
+    /// The calls `tx` made, as `result` reports them, with the accounts and
+    /// data of each. `svm` resolves a v0 transaction's lookups, so call it
+    /// before anything changes the tables.
+    pub fn from_litesvm(
+        svm: &LiteSVM,
+        tx: &VersionedTransaction,
+        result: &TransactionResult,
+    ) -> Self {
+        let meta: &TransactionMetadata = match result {
+            Ok(meta) => meta,
+            Err(failed) => &failed.meta,
+        };
+        let mut tree = Self::from_logs(&meta.logs);
+        let keys = crate::alt::account_keys(svm, tx);
+        let key = |index: u8| keys.get(index as usize).copied();
+
+        let instructions = tx.message.instructions();
+        for (i, root) in tree.roots.iter_mut().enumerate() {
+            let Some(ix) = instructions.get(i) else {
+                break;
+            };
+            if key(ix.program_id_index) != Some(root.program) {
+                continue;
+            }
+            root.accounts = ix.accounts.iter().filter_map(|&a| key(a)).collect();
+            root.input = ix.data.clone();
+
+            // Inner instructions run in the order the log invokes them
+            let Some(inner) = meta.inner_instructions.get(i) else {
+                continue;
+            };
+            let calls: Vec<&CallNode> = root.children.iter().flat_map(CallNode::iter).collect();
+            let matches = calls.len() == inner.len()
+                && calls.iter().zip(inner).all(|(node, inner)| {
+                    key(inner.instruction.program_id_index) == Some(node.program)
+                        && inner.stack_height as usize == node.depth
+                });
+            if !matches {
+                continue;
+            }
+            let mut inner = inner.iter();
+            for child in &mut root.children {
+                child.iter_mut(&mut |node| {
+                    let ix = &inner.next().unwrap().instruction;
+                    node.accounts = ix.accounts.iter().filter_map(|&a| key(a)).collect();
+                    node.input = ix.data.clone();
+                });
+            }
+        }
+        tree
+    }
+
+    /// Label `program` as `name` when rendering.
+    pub fn named(mut self, program: Pubkey, name: &str) -> Self {
+        self.names.insert(program, name.to_string());
+        self
+    }
+
+    /// Every call, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &CallNode> {
+        self.roots.iter().flat_map(CallNode::iter)
+    }
+
+    /// Each call's depth and program, depth first: the tree's shape, to
+    /// assert on in one comparison.
+    pub fn shape(&self) -> Vec<(usize, Pubkey)> {
+        self.iter().map(|node| (node.depth, node.program)).collect()
+    }
+
+    /// The deepest call, 0 for no calls.
+    pub fn max_depth(&self) -> usize {
+        self.iter().map(|node| node.depth).max().unwrap_or(0)
+    }
+
+    /// Every call into `program`, depth first.
+    pub fn calls_to(&self, program: &Pubkey) -> Vec<&CallNode> {
+        self.iter()
+            .filter(|node| node.program == *program)
+            .collect()
+    }
+
+    fn label(&self, program: &Pubkey) -> String {
+        self.names
+            .get(program)
+            .cloned()
+            .or_else(|| {
+                KNOWN_PROGRAMS
+                    .iter()
+                    .find(|(id, _)| id == program)
+                    .map(|(_, name)| name.to_string())
+            })
+            .unwrap_or_else(|| program.to_string())
+    }
+
+    fn render(
+        &self,
+        node: &CallNode,
+        f: &mut fmt::Formatter<'_>,
+        prefix: &str,
+        last: bool,
+        root: bool,
+    ) -> fmt::Result {
+        let (branch, indent) = match (root, last) {
+            (true, _) => ("", ""),
+            (false, true) => ("└─ ", "   "),
+            (false, false) => ("├─ ", "│  "),
+        };
+        write!(f, "{}{}{}", prefix, branch, self.label(&node.program))?;
+        if let Some(instruction) = &node.instruction {
+            write!(f, "::{}", instruction)?;
+        }
+        match &node.result {
+            CallResult::Success => write!(f, " ok")?,
+            CallResult::Failed(error) => write!(f, " failed: {}", error)?,
+            CallResult::Incomplete => write!(f, " incomplete")?,
+        }
+        if let Some(units) = node.compute_units {
+            write!(f, " ({} CU)", units)?;
+        }
+        writeln!(f)?;
+
+        let body = format!("{}{}", prefix, indent);
+        let rail = if node.children.is_empty() {
+            "   "
+        } else {
+            "│  "
+        };
+        for log in &node.logs {
+            writeln!(f, "{}{}log: {}", body, rail, log)?;
+        }
+        for fields in &node.data {
+            let fields: Vec<String> = fields.iter().map(|d| BASE64.encode(d)).collect();
+            writeln!(f, "{}{}data: {}", body, rail, fields.join(" "))?;
+        }
+        if let Some(data) = &node.return_data {
+            writeln!(f, "{}{}return: {}", body, rail, BASE64.encode(data))?;
+        }
+        for (i, child) in node.children.iter().enumerate() {
+            self.render(child, f, &body, i + 1 == node.children.len(), false)?;
+        }
+        Ok(())
+    }
+}
+
+/// A rendering of every call, one line each, its logs under it.
+impl fmt::Display for CallTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for root in &self.roots {
+            self.render(root, f, "", true, true)?;
+        }
+        Ok(())
+    }
+}
+
+enum Line<'a> {
+    Invoke(Pubkey, usize),
+    Exit(Pubkey, CallResult),
+    Log(&'a str),
+    Data(Vec<Vec<u8>>),
+    Return(Vec<u8>),
+    Consumed(u64),
+}
+
+fn parse(line: &str) -> Option<Line<'_>> {
+    if let Some(message) = line.strip_prefix("Program log: ") {
+        return Some(Line::Log(message));
+    }
+    if let Some(fields) = line.strip_prefix("Program data: ") {
+        let fields = fields.split(' ').map(|field| BASE64.decode(field).ok());
+        return fields.collect::<Option<_>>().map(Line::Data);
+    }
+    if let Some(rest) = line.strip_prefix("Program return: ") {
+        let (_, data) = rest.split_once(' ')?;
+        return BASE64.decode(data).ok().map(Line::Return);
+    }
+
+    let rest = line.strip_prefix("Program ")?;
+    let (program, rest) = rest.split_once(' ')?;
+    let program: Pubkey = program.parse().ok()?;
+    if let Some(depth) = rest.strip_prefix("invoke [") {
+        return depth
+            .strip_suffix(']')?
+            .parse()
+            .ok()
+            .map(|depth| Line::Invoke(program, depth));
+    }
+    if rest == "success" {
+        return Some(Line::Exit(program, CallResult::Success));
+    }
+    if let Some(error) = rest.strip_prefix("failed: ") {
+        return Some(Line::Exit(program, CallResult::Failed(error.to_string())));
+    }
+    let units = rest.strip_prefix("consumed ")?.split(' ').next()?;
+    units.parse().ok().map(Line::Consumed)
+}
@@ -0,0 +1,170 @@
+//! A demo played one step at a time, for workshops
+//!
+//! [`run`] plays the same script as [`demo::run`], but stops along the way:
+//! it prints the vulnerable code and waits, then sends the attack one
+//! transaction at a time, waiting before each and printing its logs,
+//! balances and state diff after, then does the same with the secure code.
+//!
+//! ```bash
+//! cargo xtask tutorial signer-authorization
+//! ```
+//!
+//! Enter moves on and `q` stops. When the input isn't a terminal (or ends)
+//! the tutorial runs straight through, so it can be piped or recorded.
+
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use crate::demo::{self, Demo, Outcome, Palette};
+use crate::scenario::Scenario;
+
+/// Whether to carry on after a pause.
+enum Next {
+    Continue,
+    Quit,
+}
+
+struct Pauses<'a> {
+    input: Option<&'a mut dyn BufRead>,
+}
+
+impl Pauses<'_> {
+    fn wait(&mut self, prompt: &str, out: &mut impl Write, palette: Palette) -> io::Result<Next> {
+        let Some(input) = self.input.as_mut() else {
+            return Ok(Next::Continue);
+        };
+        write!(
+            out,
+            "{} ",
+            palette.dim(&format!("[{}: Enter, q to quit]", prompt))
+        )?;
+        out.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            // Out of input: play the rest without stopping
+            writeln!(out)?;
+            self.input = None;
+            return Ok(Next::Continue);
+        }
+        Ok(match line.trim() {
+            "q" | "quit" => Next::Quit,
+            _ => Next::Continue,
+        })
+    }
+}
+
+/// The files holding one side of `demo`, from its registry entry: the
+/// first half of the sources for the vulnerable side, the second for the
+/// secure one, or the single file holding both.
+fn side_sources(demo: &Demo, secure: bool) -> &'static [&'static str] {
+    let sources = demo.vulnerability().sources;
+    if sources.len() < 2 {
+        return sources;
+    }
+    let (vulnerable, fixed) = sources.split_at(sources.len() / 2);
+    if secure {
+        fixed
+    } else {
+        vulnerable
+    }
+}
+
+/// `path` with line numbers, leaving out its `use` lines.
+fn excerpt(path: &str) -> io::Result<Vec<(usize, String)>> {
+    let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(path);
+    let source = std::fs::read_to_string(file)?;
+    let mut lines: Vec<(usize, String)> = source
+        .lines()
+        .enumerate()
+        .skip_while(|(_, line)| line.starts_with("use ") || line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line.to_string()))
+        .collect();
+    while lines.last().is_some_and(|(_, line)| line.trim().is_empty()) {
+        lines.pop();
+    }
+    Ok(lines)
+}
+
+/// Play `demo` step by step, narrating to `out` and waiting on `input`
+/// between steps; `None` plays it straight through. Returns `None` if the
+/// reader quit before the end.
+pub fn run(
+    demo: &Demo,
+    input: Option<&mut dyn BufRead>,
+    out: &mut impl Write,
+    palette: Palette,
+) -> io::Result<Option<Outcome>> {
+    let mut pauses = Pauses { input };
+    let vuln = demo.vulnerability();
+    writeln!(
+        out,
+        "{} {}",
+        palette.bold(vuln.title),
+        palette.dim(&format!(
+            "({}, {} {})",
+            vuln.program,
+            vuln.severity.name(),
+            vuln.class.name()
+        ))
+    )?;
+    writeln!(out, "read more: {}", vuln.readme())?;
+
+    let mut svm = Scenario::new(&format!("tutorial/{}", demo.program), demo::SEED);
+    svm.add_program(demo.deploy);
+    let stage = (demo.setup)(&mut svm);
+    let ready = svm.snapshot();
+
+    let mut landed = [false; 2];
+    for (i, instruction) in [demo.vulnerable, demo.secure].into_iter().enumerate() {
+        svm.restore(ready);
+        writeln!(out)?;
+        writeln!(out, "{}", palette.bold(&format!("== {} ==", instruction)))?;
+        if i == 1 {
+            writeln!(out, "fix: {}", vuln.mitigation.summary)?;
+        }
+        for path in side_sources(demo, i == 1) {
+            writeln!(out)?;
+            writeln!(out, "{}", palette.bold(path))?;
+            for (number, line) in excerpt(path)? {
+                writeln!(out, "{} {}", palette.dim(&format!("{:>4} |", number)), line)?;
+            }
+        }
+        writeln!(out)?;
+        if let Next::Quit = pauses.wait("run the attack", out, palette)? {
+            return Ok(None);
+        }
+
+        let actions = (stage.script)(&svm, instruction);
+        let steps = actions.len();
+        for (step, action) in actions.into_iter().enumerate() {
+            if step > 0 {
+                let prompt = format!("step {} of {}", step + 1, steps);
+                if let Next::Quit = pauses.wait(&prompt, out, palette)? {
+                    return Ok(None);
+                }
+            }
+            landed[i] = demo::narrate(&mut svm, &stage, action, out, palette)?;
+        }
+
+        writeln!(out)?;
+        let verdict = match (i, landed[i]) {
+            (0, true) => palette.red("the attack landed: this is the bug"),
+            (0, false) => palette.yellow("the attack did not land"),
+            (_, false) => palette.green("the attack was rejected: this is the fix"),
+            (_, true) => palette.red("the attack landed against the fix"),
+        };
+        writeln!(out, "{}: {}", instruction, verdict)?;
+        if i == 0 {
+            if let Next::Quit = pauses.wait("now the secure version", out, palette)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    Ok(Some(Outcome {
+        exploited: landed[0],
+        blocked: !landed[1],
+    }))
+}
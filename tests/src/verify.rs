@@ -0,0 +1,180 @@
+//! Is the program at an address the one built here?
+//!
+//! A "secure" variant is only as good as the bytecode actually deployed at
+//! its address: whoever holds the upgrade authority can replace it, and a
+//! client calling the address can't tell from the interface. [`verify`]
+//! fetches the ELF a program account points to and compares it with a local
+//! `.so`.
+//!
+//! The loaders store the ELF after a header, and the upgradeable loader
+//! pads it with zeros to the size allocated at deploy time, so a program
+//! that was deployed with room to grow never hashes like its `.so`. Both
+//! hashes are reported: of the ELF as stored, and with the trailing zeros
+//! trimmed, which is what's compared.
+//!
+//! ```bash
+//! cargo xtask verify-deploy --cluster devnet signer_authorization
+//! ```
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::sdk::{
+    bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4, Account, Pubkey,
+};
+
+/// Bytes before the ELF in an upgradeable loader's programdata account:
+/// the state tag (4), the deploy slot (8) and the optional authority (33)
+const PROGRAMDATA_HEADER: usize = 45;
+
+/// Bytes before the ELF in a loader-v4 program account: slot (8),
+/// authority (32) and status (8)
+const LOADER_V4_HEADER: usize = 48;
+
+/// The ELF deployed at a program address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployedElf {
+    pub loader: Pubkey,
+    /// Who can replace it; `None` once upgrades are disabled, or for loaders
+    /// without upgrades
+    pub upgrade_authority: Option<Pubkey>,
+    /// As stored, padding included
+    pub bytes: Vec<u8>,
+}
+
+/// What a comparison found, as `verify-deploy --format json` prints it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verification {
+    pub program_id: String,
+    pub local: Digest256,
+    /// `None` if nothing executable is deployed there
+    pub deployed: Option<Deployed>,
+    pub matches: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Digest256 {
+    pub len: usize,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deployed {
+    pub loader: String,
+    pub upgrade_authority: Option<String>,
+    /// The ELF as stored
+    pub padded: Digest256,
+    /// The ELF without its trailing zeros
+    pub trimmed: Digest256,
+}
+
+fn digest(bytes: &[u8]) -> Digest256 {
+    let mut sha256 = String::with_capacity(64);
+    for byte in Sha256::digest(bytes) {
+        let _ = write!(sha256, "{:02x}", byte);
+    }
+    Digest256 {
+        len: bytes.len(),
+        sha256,
+    }
+}
+
+fn trim_zeros(bytes: &[u8]) -> &[u8] {
+    let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    &bytes[..end]
+}
+
+/// The ELF behind `program_id`, reading accounts with `fetch`: an RPC node
+/// with [`crate::live::Rpc::account`], or LiteSVM. `None` if the address
+/// isn't an executable program of a loader this knows.
+pub fn deployed_elf(
+    program_id: &Pubkey,
+    fetch: impl Fn(&Pubkey) -> Result<Option<Account>, String>,
+) -> Result<Option<DeployedElf>, String> {
+    let Some(program) = fetch(program_id)? else {
+        return Ok(None);
+    };
+    if !program.executable {
+        return Ok(None);
+    }
+    let loader = program.owner;
+    if loader == bpf_loader::ID || loader == bpf_loader_deprecated::ID {
+        return Ok(Some(DeployedElf {
+            loader,
+            upgrade_authority: None,
+            bytes: program.data,
+        }));
+    }
+    if loader == loader_v4::ID {
+        let bytes = program
+            .data
+            .get(LOADER_V4_HEADER..)
+            .ok_or("loader-v4 program account shorter than its header")?;
+        let authority = Pubkey::try_from(&program.data[8..40]).unwrap();
+        // Status 2 is `Finalized`: the authority field then names the next
+        // version, not someone who can write this one
+        let finalized = program.data[40..48] == 2u64.to_le_bytes();
+        return Ok(Some(DeployedElf {
+            loader,
+            upgrade_authority: (!finalized).then_some(authority),
+            bytes: bytes.to_vec(),
+        }));
+    }
+    if loader != bpf_loader_upgradeable::ID {
+        return Ok(None);
+    }
+
+    // `UpgradeableLoaderState::Program { programdata_address }`
+    let state = program
+        .data
+        .get(..36)
+        .filter(|state| state[..4] == 2u32.to_le_bytes());
+    let Some(state) = state else {
+        return Err(format!(
+            "{} is not an upgradeable program account",
+            program_id
+        ));
+    };
+    let programdata = Pubkey::try_from(&state[4..36]).unwrap();
+    let data = fetch(&programdata)?
+        .ok_or_else(|| format!("programdata account {} is missing", programdata))?
+        .data;
+    // `UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }`
+    if data.len() < PROGRAMDATA_HEADER || data[..4] != 3u32.to_le_bytes() {
+        return Err(format!("{} is not a programdata account", programdata));
+    }
+    let upgrade_authority = match data[12] {
+        0 => None,
+        _ => Some(Pubkey::try_from(&data[13..45]).unwrap()),
+    };
+    Ok(Some(DeployedElf {
+        loader,
+        upgrade_authority,
+        bytes: data[PROGRAMDATA_HEADER..].to_vec(),
+    }))
+}
+
+/// Compare what `fetch` finds at `program_id` with the local `.so` bytes.
+pub fn verify(
+    program_id: &Pubkey,
+    local: &[u8],
+    fetch: impl Fn(&Pubkey) -> Result<Option<Account>, String>,
+) -> Result<Verification, String> {
+    let deployed = deployed_elf(program_id, fetch)?;
+    let matches = deployed
+        .as_ref()
+        .is_some_and(|elf| trim_zeros(&elf.bytes) == trim_zeros(local));
+    Ok(Verification {
+        program_id: program_id.to_string(),
+        local: digest(local),
+        deployed: deployed.map(|elf| Deployed {
+            loader: elf.loader.to_string(),
+            upgrade_authority: elf.upgrade_authority.map(|a| a.to_string()),
+            trimmed: digest(trim_zeros(&elf.bytes)),
+            padded: digest(&elf.bytes),
+        }),
+        matches,
+    })
+}
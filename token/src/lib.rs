@@ -0,0 +1,382 @@
+//! Validated SPL Token and Token-2022 calls for the secure programs
+//!
+//! anchor-spl's CPIs move tokens between whatever accounts they're handed,
+//! and a Token-2022 mint can carry extensions that change what a transfer
+//! does: a fee taken out of it, a hook program run inside it, a permanent
+//! delegate that can take the tokens back. The secure programs route their
+//! token handling through this crate, so every demo checks the same things
+//! in the same place:
+//!
+//! - [`transfer_checked_from_user`] moves a signer's tokens out of their own
+//!   account, always with `transfer_checked`.
+//! - [`vault_ata`] is the associated token account a PDA holds a mint in,
+//!   and [`check_vault`] checks an account is it and nobody else can move
+//!   or close it.
+//! - [`mint_extensions`] lists a mint's Token-2022 extensions and
+//!   [`check_mint_extensions`] rejects any not on an allow list.
+//! - [`check_no_delegate`] and [`check_close_authority`] from
+//!   `solsec-guards`, re-exported.
+//!
+//! # Extensions
+//!
+//! A pool or vault that accepts any Token-2022 mint accepts these too, and
+//! each breaks an assumption the programs here make:
+//!
+//! - `TransferFeeConfig`: the vault receives less than the amount sent.
+//! - `TransferHook`: another program runs inside every transfer.
+//! - `PermanentDelegate`: someone else can move or burn the vault's tokens.
+//! - `MintCloseAuthority`: the mint can be closed and recreated.
+//! - `DefaultAccountState`: new accounts start frozen.
+//! - `NonTransferable`, `Pausable`: transfers out can fail forever.
+//! - `ConfidentialTransferMint`: balances move without the amount showing.
+//! - `InterestBearingConfig`, `ScaledUiAmount`: the displayed amount isn't
+//!   the raw one.
+//!
+//! Pass [`METADATA_EXTENSIONS`] to accept only the ones that describe a
+//! mint without changing how it moves.
+
+// `anchor-031` swaps in Anchor 0.31 and its anchor-spl under the same names
+#[cfg(feature = "anchor-031")]
+extern crate anchor_lang_031 as anchor_lang;
+#[cfg(feature = "anchor-031")]
+extern crate anchor_spl_031 as anchor_spl;
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint as MintState,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+use solsec_guards::{check_owner, check_signer, check_token_account};
+use solsec_pda::Seeds;
+
+pub use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+pub use solsec_guards::{check_close_authority, check_no_delegate};
+
+/// The token programs these helpers call: SPL Token and Token-2022
+pub const TOKEN_PROGRAMS: [Pubkey; 2] = [anchor_spl::token::ID, anchor_spl::token_2022::ID];
+
+/// Extensions that only describe a mint: its metadata and group membership
+pub const METADATA_EXTENSIONS: [ExtensionType; 6] = [
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+/// The account is SPL Token or Token-2022.
+pub fn check_token_program(program: &AccountInfo) -> Result<()> {
+    if !TOKEN_PROGRAMS.contains(program.key) {
+        return Err(ErrorCode::InvalidProgramId.into());
+    }
+    Ok(())
+}
+
+/// Move `amount` of `mint` from `from`, the signing `user`'s own token
+/// account, to `to`.
+///
+/// `from` must be owned by `user`, not just delegated to them, and `to`
+/// must hold the same mint. The transfer passes the mint's decimals, so
+/// the token program checks the mint as well, and Token-2022 applies the
+/// mint's extensions rather than refusing the plain `transfer`.
+pub fn transfer_checked_from_user<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    user: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    check_token_program(token_program)?;
+    check_signer(user)?;
+    for account in [from, mint, to] {
+        check_owner(account, token_program.key)?;
+    }
+    check_token_account(from, mint.key, user.key)?;
+    let destination = TokenAccount::try_deserialize(&mut &to.try_borrow_data()?[..])?;
+    require_keys_eq!(destination.mint, *mint.key, ErrorCode::ConstraintTokenMint);
+    let decimals = Mint::try_deserialize(&mut &mint.try_borrow_data()?[..])?.decimals;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program.clone(),
+            TransferChecked {
+                from: from.clone(),
+                mint: mint.clone(),
+                to: to.clone(),
+                authority: user.clone(),
+            },
+        ),
+        amount,
+        decimals,
+    )
+}
+
+/// The associated token account holding `mint` for the PDA `seeds` derive
+/// under `program_id`.
+///
+/// Seeds with their stored bump are used as they are; seeds without one
+/// are searched for the canonical bump.
+pub fn vault_ata(
+    seeds: &Seeds,
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Pubkey> {
+    let authority = match seeds.stored_bump() {
+        Some(_) => {
+            let slices = seeds.slices().map_err(|_| ErrorCode::ConstraintSeeds)?;
+            Pubkey::create_program_address(&slices, program_id)
+                .map_err(|_| ErrorCode::ConstraintSeeds)?
+        }
+        None => {
+            seeds
+                .find(program_id)
+                .map_err(|_| ErrorCode::ConstraintSeeds)?
+                .0
+        }
+    };
+    Ok(get_associated_token_address_with_program_id(
+        &authority,
+        mint,
+        token_program,
+    ))
+}
+
+/// `vault` is [`vault_ata`] for `mint`, under the token program that owns
+/// it, with no delegate and no close authority.
+pub fn check_vault(
+    vault: &AccountInfo,
+    seeds: &Seeds,
+    program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<()> {
+    if !TOKEN_PROGRAMS.contains(vault.owner) {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    let expected = vault_ata(seeds, program_id, mint, vault.owner)?;
+    require_keys_eq!(*vault.key, expected, ErrorCode::ConstraintAssociated);
+    check_no_delegate(vault)?;
+    check_close_authority(vault, None)?;
+    Ok(())
+}
+
+/// The extensions on `mint`, in the order they're stored. A legacy SPL
+/// Token mint has none.
+pub fn mint_extensions(mint: &AccountInfo) -> Result<Vec<ExtensionType>> {
+    if *mint.owner == anchor_spl::token::ID {
+        return Ok(Vec::new());
+    }
+    if *mint.owner != anchor_spl::token_2022::ID {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(state.get_extension_types()?)
+}
+
+/// Every extension on `mint` is in `allowed`. Pass `&[]` to accept only
+/// mints without extensions.
+pub fn check_mint_extensions(mint: &AccountInfo, allowed: &[ExtensionType]) -> Result<()> {
+    for extension in mint_extensions(mint)? {
+        if !allowed.contains(&extension) {
+            msg!("Mint {} has the {:?} extension", mint.key, extension);
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer, mint_close_authority::MintCloseAuthority,
+            BaseStateWithExtensionsMut, StateWithExtensionsMut,
+        },
+        state::{Account as AccountState, AccountState as Initialized},
+    };
+
+    struct Stored {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        signer: bool,
+    }
+
+    impl Stored {
+        fn new(owner: Pubkey, data: Vec<u8>) -> Self {
+            Stored {
+                key: Pubkey::new_unique(),
+                owner,
+                lamports: 1,
+                data,
+                signer: false,
+            }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                self.signer,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn mint(extensions: &[ExtensionType]) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<MintState>(extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::MintCloseAuthority => {
+                    state.init_extension::<MintCloseAuthority>(true).unwrap();
+                }
+                ExtensionType::MetadataPointer => {
+                    state.init_extension::<MetadataPointer>(true).unwrap();
+                }
+                _ => unreachable!(),
+            }
+        }
+        state.base = MintState {
+            decimals: 6,
+            is_initialized: true,
+            ..MintState::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn token_account(mint: Pubkey, owner: Pubkey) -> Vec<u8> {
+        let mut data = vec![0; AccountState::LEN];
+        AccountState {
+            mint,
+            owner,
+            state: Initialized::Initialized,
+            ..AccountState::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn test_mint_extensions() {
+        let mut legacy = Stored::new(anchor_spl::token::ID, vec![0; MintState::LEN]);
+        assert_eq!(mint_extensions(&legacy.info()).unwrap(), vec![]);
+
+        let mut plain = Stored::new(anchor_spl::token_2022::ID, mint(&[]));
+        assert_eq!(mint_extensions(&plain.info()).unwrap(), vec![]);
+        assert!(check_mint_extensions(&plain.info(), &[]).is_ok());
+
+        let mut metadata = Stored::new(
+            anchor_spl::token_2022::ID,
+            mint(&[ExtensionType::MetadataPointer]),
+        );
+        assert!(check_mint_extensions(&metadata.info(), &METADATA_EXTENSIONS).is_ok());
+        assert!(check_mint_extensions(&metadata.info(), &[]).is_err());
+
+        let extensions = [
+            ExtensionType::MetadataPointer,
+            ExtensionType::MintCloseAuthority,
+        ];
+        let mut closable = Stored::new(anchor_spl::token_2022::ID, mint(&extensions));
+        assert_eq!(mint_extensions(&closable.info()).unwrap(), extensions);
+        assert!(check_mint_extensions(&closable.info(), &METADATA_EXTENSIONS).is_err());
+
+        let mut fake = Stored::new(Pubkey::new_unique(), mint(&[]));
+        assert!(mint_extensions(&fake.info()).is_err());
+    }
+
+    #[test]
+    fn test_vault_ata() {
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (config, bump) =
+            Pubkey::find_program_address(&[b"config", &7u64.to_le_bytes()], &program_id);
+        let expected =
+            get_associated_token_address_with_program_id(&config, &mint, &anchor_spl::token::ID);
+
+        // With the stored bump or found again, the same account
+        for seeds in [
+            Seeds::new("config").nonce(7),
+            Seeds::new("config").nonce(7).bump(bump),
+        ] {
+            assert_eq!(
+                vault_ata(&seeds, &program_id, &mint, &anchor_spl::token::ID).unwrap(),
+                expected
+            );
+        }
+        let token_2022 = vault_ata(
+            &Seeds::new("config").nonce(7),
+            &program_id,
+            &mint,
+            &anchor_spl::token_2022::ID,
+        );
+        assert_ne!(token_2022.unwrap(), expected);
+
+        let seeds = Seeds::new("config").nonce(7).bump(bump);
+        let mut vault = Stored::new(anchor_spl::token::ID, token_account(mint, config));
+        vault.key = expected;
+        assert!(check_vault(&vault.info(), &seeds, &program_id, &mint).is_ok());
+
+        // Another account of the PDA's, or the right one with a delegate
+        let mut other = Stored::new(anchor_spl::token::ID, token_account(mint, config));
+        assert!(check_vault(&other.info(), &seeds, &program_id, &mint).is_err());
+        vault.data[72] = 1;
+        assert!(check_vault(&vault.info(), &seeds, &program_id, &mint).is_err());
+    }
+
+    /// A transfer from `user`'s account of a mint to an account of `to_mint`,
+    /// through `program`, stopped before the CPI
+    fn transfer(
+        program: Pubkey,
+        from_owner: Option<Pubkey>,
+        to_mint: Option<Pubkey>,
+        signed: bool,
+    ) -> Result<()> {
+        let mut user = Stored::new(Pubkey::default(), vec![]);
+        user.signer = signed;
+        let mut mint = Stored::new(anchor_spl::token::ID, vec![0; MintState::LEN]);
+        let mut from = Stored::new(
+            anchor_spl::token::ID,
+            token_account(mint.key, from_owner.unwrap_or(user.key)),
+        );
+        let mut to = Stored::new(
+            anchor_spl::token::ID,
+            token_account(to_mint.unwrap_or(mint.key), Pubkey::new_unique()),
+        );
+        let mut token_program = Stored::new(Pubkey::default(), vec![]);
+        token_program.key = program;
+        transfer_checked_from_user(
+            &token_program.info(),
+            &from.info(),
+            &mint.info(),
+            &to.info(),
+            &user.info(),
+            1,
+        )
+    }
+
+    #[test]
+    fn test_transfer_rejects_before_calling() {
+        let token = anchor_spl::token::ID;
+        assert!(transfer(Pubkey::new_unique(), None, None, true).is_err());
+        assert!(transfer(token, None, None, false).is_err());
+        assert!(transfer(token, Some(Pubkey::new_unique()), None, true).is_err());
+        assert!(transfer(token, None, Some(Pubkey::new_unique()), true).is_err());
+    }
+}
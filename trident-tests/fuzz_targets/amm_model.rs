@@ -0,0 +1,41 @@
+//! Reference model of the arithmetic-overflow pool
+//!
+//! The model itself is `solsec_math::amm_model`, shared with anything else
+//! that prices these swaps; its tests here hold it to the secure swap's
+//! mirror in `security-fuzz`.
+
+pub use solsec_math::amm_model::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use security_fuzz::swap::secure_swap;
+
+    proptest! {
+        // The model and the shared mirror of secure.rs are written
+        // independently; they must agree on every swap
+        #[test]
+        fn test_model_matches_the_secure_swap(
+            amount_in: u64,
+            min_out: u64,
+            reserve_x: u64,
+            reserve_y: u64,
+        ) {
+            let pool = Pool { reserve_x, reserve_y };
+            match (pool.swap(amount_in, min_out), secure_swap(amount_in, min_out, reserve_x, reserve_y)) {
+                (Ok(quote), Ok((out, new_x, new_y))) => {
+                    prop_assert_eq!(quote.amount_out, out);
+                    prop_assert_eq!(quote.after, Pool { reserve_x: new_x, reserve_y: new_y });
+                    prop_assert!(quote.admits(quote.after));
+                    prop_assert_eq!(
+                        judge(pool, amount_in, min_out, &Observed::Landed(quote.after)),
+                        Verdict::Agrees
+                    );
+                }
+                (Err(rejection), Err(error)) => prop_assert_eq!(rejection.name(), error),
+                (model, mirror) => prop_assert!(false, "model {:?}, secure swap {:?}", model, mirror),
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! One `#[test]` per recorded finding of the targets here; see
+//! `security_fuzz::stubs`.
+
+use std::path::PathBuf;
+
+const TARGETS: [&str; 9] = [
+    "arithmetic",
+    "signer_authorization",
+    "account_griefing",
+    "multisig_payer",
+    "differential",
+    "pda_derivation",
+    "ix_mutation",
+    "account_meta",
+    "roles",
+];
+
+fn main() {
+    let findings = security_fuzz::findings::findings_root();
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", findings.display());
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("cargo sets OUT_DIR"));
+    for target in TARGETS {
+        security_fuzz::stubs::write(&findings, target, &out_dir);
+    }
+}
@@ -0,0 +1,373 @@
+//! Fuzz test for the Account Creation Griefing vulnerability
+//!
+//! Each input decodes to a sequence of attacker pre-funds and user stake
+//! creations against the deployed account-griefing program, in any order,
+//! with random lamport amounts and nonces drawn from a small set so the
+//! attacker's guesses actually collide with the user's.
+//!
+//! Invariant, checked on every `secure_create_stake`:
+//!
+//! > secure_create_stake never fails because its address was pre-funded.
+//!
+//! When the stake address holds pre-funded lamports, the same creation is
+//! replayed on a copy of the state with that address emptied. If it succeeds
+//! there but failed for real, the pre-funding is what broke it. A violation
+//! is recorded under `regressions/account_griefing/` before the target
+//! crashes, and the tests below replay every recorded sequence.
+//!
+//! Pre-funding amounts come from `FuzzInput::lamports` around the rent-exempt
+//! minimum of the stake account at the address: zero, one below, exactly
+//! it, one above and `u64::MAX`. After every step no stake account may hold
+//! less than its minimum.
+
+use fuzz_targets::{record_counterexample, rent_collectable, rent_exempt, FuzzInput};
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::idl::Idl;
+use security_tests::scenario::seeded_keypair;
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const PROGRAM: &str = "account_griefing";
+
+const TARGET: &str = "account_griefing";
+
+/// Actors are derived from this seed, so a crash input replays exactly
+const SEED: u64 = 1;
+
+/// Longest action sequence read from one input
+const MAX_ACTIONS: usize = 12;
+
+/// Nonces both sides pick from: small, edge values, and the one the
+/// suite's user picks
+const NONCES: [u64; 4] = [0, 1, 847_291, u64::MAX];
+
+/// Data of a vulnerable stake account: discriminator, owner, amount, bump
+/// and initialized flag
+const STAKE_SPACE: usize = 8 + 32 + 8 + 1 + 1;
+
+/// A secure stake account also stores its nonce
+const SECURE_STAKE_SPACE: usize = STAKE_SPACE + 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Attacker transfers `lamports` to the user's vulnerable stake PDA, or
+    /// to the secure one for `nonce`
+    Prefund {
+        secure: bool,
+        nonce: u64,
+        lamports: u64,
+    },
+    /// User creates their stake account
+    Create { secure: bool, nonce: u64 },
+}
+
+impl Action {
+    fn decode(input: &mut FuzzInput) -> Self {
+        let secure = input.bool();
+        let nonce = NONCES[input.choose(NONCES.len())];
+        if input.bool() {
+            let space = if secure {
+                SECURE_STAKE_SPACE
+            } else {
+                STAKE_SPACE
+            };
+            Self::Prefund {
+                secure,
+                nonce,
+                lamports: input.lamports(rent_exempt(space), 2 * LAMPORTS_PER_SOL),
+            }
+        } else {
+            Self::Create { secure, nonce }
+        }
+    }
+}
+
+/// Decode a whole input into at most [`MAX_ACTIONS`] actions.
+pub fn decode(data: &[u8]) -> Vec<Action> {
+    let mut input = FuzzInput::new(data);
+    let mut actions = Vec::new();
+    while !input.is_empty() && actions.len() < MAX_ACTIONS {
+        actions.push(Action::decode(&mut input));
+    }
+    actions
+}
+
+/// A stake creation that pre-funding made fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the failing creation in the sequence
+    pub step: usize,
+    pub action: Action,
+    /// Lamports the attacker had left at the address
+    pub prefunded: u64,
+}
+
+/// The program deployed with a funded user and attacker; each run starts
+/// from a copy of this.
+pub struct Harness {
+    svm: LiteSVM,
+    program_id: Pubkey,
+    idl: Idl,
+    user: Keypair,
+    attacker: Keypair,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let (mut svm, _) = common::setup(PROGRAM);
+        let user = seeded_keypair(SEED, "user");
+        let attacker = seeded_keypair(SEED, "attacker");
+        for actor in [&user, &attacker] {
+            svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        Self {
+            svm,
+            program_id: common::load_program_id(PROGRAM),
+            idl: Idl::load(PROGRAM),
+            user,
+            attacker,
+        }
+    }
+
+    fn stake_address(&self, secure: bool, nonce: u64) -> Pubkey {
+        let user = self.user.pubkey();
+        let nonce = nonce.to_le_bytes();
+        let seeds: &[&[u8]] = if secure {
+            &[b"stake", user.as_ref(), &nonce]
+        } else {
+            &[b"stake", user.as_ref()]
+        };
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+
+    fn create_tx(&self, svm: &LiteSVM, secure: bool, nonce: u64) -> Transaction {
+        let stake_account = self.stake_address(secure, nonce);
+        let ix = if secure {
+            self.idl
+                .ix("secure_create_stake")
+                .arg("nonce", nonce)
+                .account("user", self.user.pubkey())
+                .account("stake_account", stake_account)
+                .build()
+        } else {
+            self.idl
+                .ix("vulnerable_create_stake")
+                .account("user", self.user.pubkey())
+                .account("stake_account", stake_account)
+                .build()
+        };
+        let msg = Message::new(&[ix], Some(&self.user.pubkey()));
+        Transaction::new(&[&self.user], msg, svm.latest_blockhash())
+    }
+
+    /// Run `actions` from a fresh copy of the base state and return every
+    /// invariant violation. Other failures are part of the game and ignored.
+    pub fn run(&self, actions: &[Action]) -> Vec<Violation> {
+        let mut svm = self.svm.clone();
+        let mut violations = Vec::new();
+
+        for (step, action) in actions.iter().enumerate() {
+            match *action {
+                Action::Prefund {
+                    secure,
+                    nonce,
+                    lamports,
+                } => {
+                    let target = self.stake_address(secure, nonce);
+                    let ix = solana_system_interface::instruction::transfer(
+                        &self.attacker.pubkey(),
+                        &target,
+                        lamports,
+                    );
+                    let msg = Message::new(&[ix], Some(&self.attacker.pubkey()));
+                    let tx = Transaction::new(&[&self.attacker], msg, svm.latest_blockhash());
+                    let _ = svm.send_transaction(tx);
+                }
+                Action::Create { secure, nonce } => {
+                    let address = self.stake_address(secure, nonce);
+                    // Lamports sitting at an address nobody created yet
+                    let prefunded = svm
+                        .get_account(&address)
+                        .filter(|a| a.owner == solana_sdk_ids::system_program::ID)
+                        .map_or(0, |a| a.lamports);
+
+                    if prefunded > 0 && secure {
+                        let mut clean = svm.clone();
+                        clean.set_account(address, Account::default()).unwrap();
+                        let unblocked = clean
+                            .send_transaction(self.create_tx(&clean, secure, nonce))
+                            .is_ok();
+                        let result = svm.send_transaction(self.create_tx(&svm, secure, nonce));
+                        if unblocked && result.is_err() {
+                            violations.push(Violation {
+                                step,
+                                action: action.clone(),
+                                prefunded,
+                            });
+                        }
+                    } else {
+                        let _ = svm.send_transaction(self.create_tx(&svm, secure, nonce));
+                    }
+                }
+            }
+            let stakes = NONCES.iter().flat_map(|&nonce| {
+                [
+                    self.stake_address(false, nonce),
+                    self.stake_address(true, nonce),
+                ]
+            });
+            if let Some(account) = rent_collectable(&svm, stakes) {
+                panic!(
+                    "step {} left a stake account below its rent-exempt minimum: {:?}\naction: {:?}",
+                    step, account, action
+                );
+            }
+            svm.expire_blockhash();
+        }
+        violations
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check(harness: &Harness, actions: &[Action]) {
+    findings::attach_scenario(&actions);
+    if let Some(v) = harness.run(actions).first() {
+        let path = record_counterexample(TARGET, &actions);
+        panic!(
+            "secure_create_stake was blocked by {} pre-funded lamports at step {}\n\
+             recorded as {}\nsequence: {:#?}",
+            v.prefunded,
+            v.step,
+            path.display(),
+            actions
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(TARGET, data, || check(&harness, &decode(data)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{corpus_dir, pseudo_random_input, regressions, replay_corpus};
+
+    #[test]
+    fn test_decode_is_bounded_and_total() {
+        assert!(decode(&[]).is_empty());
+        for seed in 0..100 {
+            let actions = decode(&pseudo_random_input(seed, 512));
+            assert!(!actions.is_empty() && actions.len() <= MAX_ACTIONS);
+        }
+    }
+
+    #[test]
+    fn test_prefunded_vulnerable_create_is_not_a_violation() {
+        // The vulnerable path may be blocked; only the secure one is checked
+        let actions = [
+            Action::Prefund {
+                secure: false,
+                nonce: 0,
+                lamports: 890_880,
+            },
+            Action::Create {
+                secure: false,
+                nonce: 0,
+            },
+        ];
+        assert!(Harness::new().run(&actions).is_empty());
+    }
+
+    #[test]
+    fn test_recorded_regressions_hold() {
+        let harness = Harness::new();
+        let recorded = regressions::<Vec<Action>>(TARGET);
+        assert!(
+            !recorded.is_empty(),
+            "no regressions recorded for {}",
+            TARGET
+        );
+        for (path, actions) in recorded {
+            let violations = harness.run(&actions);
+            assert!(
+                violations.is_empty(),
+                "{} fails again: {:?}",
+                path.display(),
+                violations
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_sequences_hold_for_secure_create() {
+        let harness = Harness::new();
+        for seed in 0..200 {
+            check(&harness, &decode(&pseudo_random_input(seed, 128)));
+        }
+    }
+
+    #[test]
+    fn test_prefunding_at_the_rent_boundary_keeps_stakes_exempt() {
+        let rent = rent_exempt(SECURE_STAKE_SPACE);
+        let harness = Harness::new();
+        for lamports in [1, rent - 1, rent, rent + 1] {
+            let actions = [
+                Action::Prefund {
+                    secure: true,
+                    nonce: 847_291,
+                    lamports,
+                },
+                Action::Create {
+                    secure: true,
+                    nonce: 847_291,
+                },
+            ];
+            assert!(harness.run(&actions).is_empty(), "{} lamports", lamports);
+        }
+    }
+
+    #[test]
+    fn test_corpus_seed_decodes_to_recorded_regression() {
+        let seed = std::fs::read(corpus_dir(TARGET).join("prefund_guessed_nonce")).unwrap();
+        let (_, recorded) = regressions::<Vec<Action>>(TARGET)
+            .into_iter()
+            .find(|(path, _)| path.ends_with("guessed_nonce_prefund.json"))
+            .unwrap();
+        assert_eq!(decode(&seed), recorded);
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        // Not `check`: it records every failing input, minimization steps included
+        let harness = Harness::new();
+        let replayed = replay_corpus(TARGET, |data| !harness.run(&decode(data)).is_empty());
+        assert!(replayed > 0, "no corpus for {}", TARGET);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, &decode(data));
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_account_griefing.rs"));
+}
@@ -0,0 +1,709 @@
+//! Account-meta permutation fuzzing of the secure instructions
+//!
+//! Each input picks a fixture, a secure instruction with the state it runs
+//! from and the accounts its legitimate client passes, and a few changes to
+//! the instruction's account metas: two slots swapped, a signer or
+//! writable flag toggled, one slot's account copied into another. The
+//! attacker pays for and sends the result, signing for whichever actors it
+//! now needs.
+//!
+//! Property, checked on every run:
+//!
+//! > A secure instruction rejects every account list its legitimate client
+//! > would not produce.
+//!
+//! What the program sees is the message's privileges, which merge every
+//! meta of the same key, so the comparison is on those. A list the client
+//! could produce keeps every account in its slot with at least its original
+//! privileges; slots the client fills freely, like a withdrawal's
+//! destination, may hold any account. Anything else that lands is the
+//! signer-authorization bug generalized, and crashes the target, as does a
+//! program panic.
+
+use fuzz_targets::FuzzInput;
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::corrupt;
+use security_tests::idl::Idl;
+use security_tests::scenario::seeded_keypair;
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const TARGET: &str = "account_meta";
+
+/// Actors are derived from this seed, so a crash input replays exactly
+const SEED: u64 = 1;
+
+/// Alice owns the fixture's state, Bob is a second user, Mallory pays for
+/// and sends every permutation.
+const ACTORS: [&str; 3] = ["alice", "bob", "mallory"];
+
+const ALICE: usize = 0;
+const BOB: usize = 1;
+const ATTACKER: usize = 2;
+
+/// Most changes applied to one account list
+const MAX_CHANGES: usize = 3;
+
+/// State shared by a fixture's setup.
+pub struct Ctx<'a> {
+    pub svm: &'a mut LiteSVM,
+    pub program_id: Pubkey,
+    pub idl: &'a Idl,
+    pub actors: &'a [Keypair],
+}
+
+impl Ctx<'_> {
+    fn key(&self, actor: usize) -> Pubkey {
+        self.actors[actor].pubkey()
+    }
+
+    fn pda(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+
+    fn send(&mut self, ix: Instruction, signer: usize) {
+        let signer = &self.actors[signer];
+        let msg = Message::new(&[ix], Some(&signer.pubkey()));
+        let tx = Transaction::new(&[signer], msg, self.svm.latest_blockhash());
+        self.svm
+            .send_transaction(tx)
+            .unwrap_or_else(|e| panic!("Fixture setup failed: {:?}", e.err));
+        self.svm.expire_blockhash();
+    }
+
+    // An account the program owns, as Pinocchio programs expect them
+    fn program_account(&mut self, data: Vec<u8>) -> Pubkey {
+        let address = Pubkey::new_unique();
+        let account = Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner: self.program_id,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+        address
+    }
+}
+
+/// A secure instruction as its legitimate client sends it.
+pub struct Fixture {
+    pub program: &'static str,
+    pub instruction: &'static str,
+    /// Slots the client fills with any account it likes
+    pub free: &'static [&'static str],
+    /// Build the state and return the instruction.
+    pub setup: fn(&mut Ctx) -> Instruction,
+}
+
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        program: "signer_authorization",
+        instruction: "secure_withdraw",
+        free: &["destination"],
+        setup: |ctx| {
+            let authority = ctx.key(ALICE);
+            let vault = ctx.pda(&[b"vault", authority.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .account("authority", authority)
+                .account("vault", vault)
+                .build();
+            ctx.send(ix, ALICE);
+            ctx.svm.airdrop(&vault, LAMPORTS_PER_SOL).unwrap();
+            ctx.idl
+                .ix("secure_withdraw")
+                .arg("amount", LAMPORTS_PER_SOL / 2)
+                .account("vault", vault)
+                .account("authority", authority)
+                .account("destination", authority)
+                .build()
+        },
+    },
+    Fixture {
+        program: "duplicate_accounts",
+        instruction: "secure_transfer",
+        free: &[],
+        setup: |ctx| {
+            let mut balances = Vec::new();
+            for actor in [ALICE, BOB] {
+                let user = ctx.key(actor);
+                let balance = ctx.pda(&[b"balance", user.as_ref()]);
+                let ix = ctx
+                    .idl
+                    .ix("initialize")
+                    .arg("initial_balance", 1_000u64)
+                    .account("user", user)
+                    .account("user_account", balance)
+                    .build();
+                ctx.send(ix, actor);
+                balances.push(balance);
+            }
+            ctx.idl
+                .ix("secure_transfer")
+                .arg("amount", 100u64)
+                .account("authority", ctx.key(ALICE))
+                .account("from_account", balances[0])
+                .account("to_account", balances[1])
+                .build()
+        },
+    },
+    Fixture {
+        program: "arithmetic_overflow",
+        instruction: "secure_swap",
+        free: &[],
+        setup: |ctx| {
+            let authority = ctx.key(ALICE);
+            let pool = ctx.pda(&[b"pool", authority.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .arg("initial_x", 1_000_000u64)
+                .arg("initial_y", 1_000_000u64)
+                .arg("fee_bps", 30u16)
+                .account("authority", authority)
+                .account("pool", pool)
+                .build();
+            ctx.send(ix, ALICE);
+            ctx.idl
+                .ix("secure_swap")
+                .arg("amount_in", 1_000u64)
+                .arg("min_out", 0u64)
+                .account("user", authority)
+                .account("pool", pool)
+                .build()
+        },
+    },
+    Fixture {
+        program: "account_close",
+        instruction: "secure_close",
+        free: &[],
+        setup: |ctx| {
+            let owner = ctx.key(ALICE);
+            let user_account = ctx.pda(&[b"user", owner.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .account("owner", owner)
+                .account("user_account", user_account)
+                .build();
+            ctx.send(ix, ALICE);
+            ctx.idl
+                .ix("secure_close")
+                .account("owner", owner)
+                .account("user_account", user_account)
+                .build()
+        },
+    },
+    Fixture {
+        program: "account_reloading",
+        instruction: "secure_double_increment",
+        free: &[],
+        setup: |ctx| {
+            let authority = ctx.key(ALICE);
+            let counter = ctx.pda(&[b"counter", authority.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .account("authority", authority)
+                .account("counter", counter)
+                .build();
+            ctx.send(ix, ALICE);
+            ctx.idl
+                .ix("secure_double_increment")
+                .account("authority", authority)
+                .account("counter", counter)
+                .build()
+        },
+    },
+    Fixture {
+        program: "authority_transfer",
+        instruction: "propose_authority",
+        free: &[],
+        setup: |ctx| {
+            let authority = ctx.key(ALICE);
+            let config = ctx.pda(&[b"config"]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .account("authority", authority)
+                .account("config", config)
+                .build();
+            ctx.send(ix, ALICE);
+            ctx.idl
+                .ix("propose_authority")
+                .arg("new_authority", ctx.key(BOB))
+                .account("authority", authority)
+                .account("config", config)
+                .build()
+        },
+    },
+    Fixture {
+        program: "pda_security",
+        instruction: "secure_update",
+        free: &[],
+        setup: |ctx| {
+            let user = ctx.key(ALICE);
+            let nonce = 12_345u64;
+            let user_account = ctx.pda(&[b"user_v1", user.as_ref(), &nonce.to_le_bytes()]);
+            let ix = ctx
+                .idl
+                .ix("secure_create_user")
+                .arg("nonce", nonce)
+                .account("user", user)
+                .account("user_account", user_account)
+                .build();
+            ctx.send(ix, ALICE);
+            ctx.idl
+                .ix("secure_update")
+                .arg("data", 7u64)
+                .account("user", user)
+                .account("user_account", user_account)
+                .build()
+        },
+    },
+    Fixture {
+        program: "owner_check",
+        instruction: "secure_read_config",
+        free: &[],
+        setup: |ctx| {
+            let admin = ctx.key(ALICE);
+            let config = ctx.program_account(admin.to_bytes().to_vec());
+            ctx.idl
+                .ix("secure_read_config")
+                .account("config", config)
+                .account("caller", admin)
+                .build()
+        },
+    },
+    Fixture {
+        program: "account_type_mismatch",
+        instruction: "secure_action",
+        free: &[],
+        setup: |ctx| {
+            let user = ctx.key(ALICE);
+            // User layout: discriminator 1, pubkey, balance
+            let mut data = vec![1u8];
+            data.extend(user.to_bytes());
+            data.extend(100u64.to_le_bytes());
+            let user_account = ctx.program_account(data);
+            ctx.idl
+                .ix("secure_action")
+                .account("user_account", user_account)
+                .account("caller", user)
+                .build()
+        },
+    },
+];
+
+/// One change to the account list, applied in order. Slots wrap around
+/// the number of accounts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Change {
+    /// Exchange two slots, flags included
+    Swap {
+        a: usize,
+        b: usize,
+    },
+    ToggleSigner {
+        slot: usize,
+    },
+    ToggleWritable {
+        slot: usize,
+    },
+    /// Put the account of slot `from` in slot `to`, keeping `to`'s flags
+    Duplicate {
+        from: usize,
+        to: usize,
+    },
+}
+
+/// One generated input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Case {
+    pub program: String,
+    pub instruction: String,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The program panicked or ran out of compute
+    Crash { error: String, logs: Vec<String> },
+    /// An account list the client wouldn't produce landed; `(slot, meta)`
+    Accepted {
+        accounts: Vec<(String, AccountMeta)>,
+    },
+}
+
+struct Program {
+    svm: LiteSVM,
+    idl: Idl,
+    ix: Instruction,
+}
+
+/// Every fixture's state and instruction, built once; each case runs from
+/// a copy of its fixture's state.
+pub struct Harness {
+    programs: Vec<Program>,
+    actors: Vec<Keypair>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let actors: Vec<Keypair> = ACTORS
+            .iter()
+            .map(|label| seeded_keypair(SEED, label))
+            .collect();
+        let programs = FIXTURES
+            .iter()
+            .map(|fixture| {
+                let (mut svm, _) = common::setup(fixture.program);
+                for actor in &actors {
+                    svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL)
+                        .expect("Airdrop failed");
+                }
+                let idl = Idl::load(fixture.program);
+                let ix = (fixture.setup)(&mut Ctx {
+                    svm: &mut svm,
+                    program_id: common::load_program_id(fixture.program),
+                    idl: &idl,
+                    actors: &actors,
+                });
+                Program { svm, idl, ix }
+            })
+            .collect();
+        Self { programs, actors }
+    }
+
+    fn fixture(&self, case: &Case) -> (&Fixture, &Program) {
+        let i = FIXTURES
+            .iter()
+            .position(|f| f.program == case.program && f.instruction == case.instruction)
+            .unwrap_or_else(|| panic!("no fixture for {}::{}", case.program, case.instruction));
+        (&FIXTURES[i], &self.programs[i])
+    }
+
+    /// Read a case out of fuzzer input.
+    pub fn decode(&self, data: &[u8]) -> Case {
+        let mut input = FuzzInput::new(data);
+        let fixture = &FIXTURES[input.choose(FIXTURES.len())];
+        let count = 1 + input.choose(MAX_CHANGES);
+        let changes = (0..count)
+            .map(|_| {
+                let kind = input.choose(4);
+                let (a, b) = (input.u8() as usize, input.u8() as usize);
+                match kind {
+                    0 => Change::Swap { a, b },
+                    1 => Change::ToggleSigner { slot: a },
+                    2 => Change::ToggleWritable { slot: a },
+                    _ => Change::Duplicate { from: a, to: b },
+                }
+            })
+            .collect();
+        Case {
+            program: fixture.program.to_string(),
+            instruction: fixture.instruction.to_string(),
+            changes,
+        }
+    }
+
+    /// The fixture's account metas with `case`'s changes applied.
+    pub fn permuted(&self, case: &Case) -> Vec<AccountMeta> {
+        let (_, program) = self.fixture(case);
+        let mut metas = program.ix.accounts.clone();
+        let n = metas.len();
+        for change in &case.changes {
+            match *change {
+                Change::Swap { a, b } => metas.swap(a % n, b % n),
+                Change::ToggleSigner { slot } => metas[slot % n].is_signer ^= true,
+                Change::ToggleWritable { slot } => metas[slot % n].is_writable ^= true,
+                Change::Duplicate { from, to } => metas[to % n].pubkey = metas[from % n].pubkey,
+            }
+        }
+        metas
+    }
+
+    /// Send `case`'s account list and report what it broke. Lists that need
+    /// a signature nobody here holds, like a PDA's, can't be sent and are
+    /// skipped.
+    pub fn run(&self, case: &Case) -> Option<Violation> {
+        let (fixture, program) = self.fixture(case);
+        let metas = self.permuted(case);
+        let payer = self.actors[ATTACKER].pubkey();
+
+        let mut signers = vec![&self.actors[ATTACKER]];
+        for (key, signer, _) in effective(&metas, &payer) {
+            if !signer || key == payer || signers.iter().any(|s| s.pubkey() == key) {
+                continue;
+            }
+            signers.push(self.actors.iter().find(|a| a.pubkey() == key)?);
+        }
+
+        let ix = Instruction {
+            accounts: metas.clone(),
+            ..program.ix.clone()
+        };
+        let mut svm = program.svm.clone();
+        let msg = Message::new(&[ix], Some(&payer));
+        let tx = Transaction::new(&signers, msg, svm.latest_blockhash());
+        match svm.send_transaction(tx) {
+            Err(failed) if corrupt::crashed(&failed) => Some(Violation::Crash {
+                error: format!("{:?}", failed.err),
+                logs: failed.meta.logs,
+            }),
+            Err(_) => None,
+            Ok(_) => {
+                let slots = &program.idl.instruction(&case.instruction).accounts;
+                let free: Vec<usize> = slots
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, slot)| fixture.free.contains(&slot.name.as_str()))
+                    .map(|(i, _)| i)
+                    .collect();
+                if legitimate(&program.ix.accounts, &metas, &payer, &free) {
+                    return None;
+                }
+                Some(Violation::Accepted {
+                    accounts: slots.iter().map(|s| s.name.clone()).zip(metas).collect(),
+                })
+            }
+        }
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `(key, signer, writable)` of each slot as the program sees it: the
+/// privileges of every meta with the same key merged, plus the fee payer's.
+fn effective(metas: &[AccountMeta], payer: &Pubkey) -> Vec<(Pubkey, bool, bool)> {
+    metas
+        .iter()
+        .map(|meta| {
+            let same = || metas.iter().filter(|m| m.pubkey == meta.pubkey);
+            let paying = meta.pubkey == *payer;
+            (
+                meta.pubkey,
+                paying || same().any(|m| m.is_signer),
+                paying || same().any(|m| m.is_writable),
+            )
+        })
+        .collect()
+}
+
+/// Whether the client could have sent `permuted` in place of `original`:
+/// every slot keeps its account, `free` slots excepted, and still has the
+/// privileges its meta asked for. Privileges a slot only had because
+/// another slot named the same account don't count, since a free slot can
+/// name another account.
+fn legitimate(
+    original: &[AccountMeta],
+    permuted: &[AccountMeta],
+    payer: &Pubkey,
+    free: &[usize],
+) -> bool {
+    original.len() == permuted.len()
+        && original
+            .iter()
+            .zip(effective(permuted, payer))
+            .enumerate()
+            .all(|(i, (meta, (key, signer, writable)))| {
+                (free.contains(&i) || meta.pubkey == key)
+                    && (!meta.is_signer || signer)
+                    && (!meta.is_writable || writable)
+            })
+}
+
+fn check(harness: &Harness, case: &Case) {
+    findings::attach_scenario(case);
+    if let Some(violation) = harness.run(case) {
+        panic!(
+            "{}::{} mishandled a permuted account list\n{:#?}\ncase: {:#?}",
+            case.program, case.instruction, violation, case
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(TARGET, data, || check(&harness, &harness.decode(data)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{pseudo_random_input, replay_corpus};
+
+    fn meta(key: Pubkey, signer: bool, writable: bool) -> AccountMeta {
+        AccountMeta {
+            pubkey: key,
+            is_signer: signer,
+            is_writable: writable,
+        }
+    }
+
+    // vault, authority, destination of a withdrawal
+    fn withdraw_metas() -> (Vec<AccountMeta>, Pubkey) {
+        let (vault, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let metas = vec![
+            meta(vault, false, true),
+            meta(authority, true, false),
+            meta(authority, false, true),
+        ];
+        (metas, Pubkey::new_unique())
+    }
+
+    #[test]
+    fn test_unchanged_and_escalated_lists_are_legitimate() {
+        let (original, payer) = withdraw_metas();
+        assert!(legitimate(&original, &original, &payer, &[]));
+
+        let mut escalated = original.clone();
+        escalated[0].is_signer = false;
+        escalated[1].is_writable = true;
+        assert!(legitimate(&original, &escalated, &payer, &[]));
+    }
+
+    #[test]
+    fn test_dropped_signature_is_not_legitimate() {
+        let (original, payer) = withdraw_metas();
+        let mut unsigned = original.clone();
+        unsigned[1].is_signer = false;
+        assert!(!legitimate(&original, &unsigned, &payer, &[]));
+    }
+
+    #[test]
+    fn test_duplicate_meta_keeps_the_merged_privilege() {
+        // Clearing the signer flag on one of two metas for the same key
+        // doesn't unsign it, and the payer always signs
+        let (original, payer) = withdraw_metas();
+        let mut partial = original.clone();
+        partial[2].is_signer = true;
+        partial[1].is_signer = false;
+        assert!(legitimate(&original, &partial, &payer, &[]));
+
+        let paid = vec![meta(payer, false, false)];
+        assert_eq!(effective(&paid, &payer), vec![(payer, true, true)]);
+    }
+
+    #[test]
+    fn test_moved_account_is_legitimate_only_in_a_free_slot() {
+        let (original, payer) = withdraw_metas();
+        let mut redirected = original.clone();
+        redirected[2].pubkey = original[0].pubkey;
+        assert!(!legitimate(&original, &redirected, &payer, &[]));
+        assert!(legitimate(&original, &redirected, &payer, &[2]));
+
+        let mut swapped = original.clone();
+        swapped.swap(0, 1);
+        assert!(!legitimate(&original, &swapped, &payer, &[2]));
+    }
+
+    #[test]
+    fn test_unchanged_fixtures_land() {
+        let harness = Harness::new();
+        for fixture in FIXTURES {
+            let case = Case {
+                program: fixture.program.to_string(),
+                instruction: fixture.instruction.to_string(),
+                changes: vec![],
+            };
+            let (_, program) = harness.fixture(&case);
+            let mut svm = program.svm.clone();
+            let signers: Vec<&Keypair> = harness
+                .actors
+                .iter()
+                .filter(|a| {
+                    a.pubkey() == harness.actors[ATTACKER].pubkey()
+                        || program
+                            .ix
+                            .accounts
+                            .iter()
+                            .any(|m| m.is_signer && m.pubkey == a.pubkey())
+                })
+                .collect();
+            let msg = Message::new(
+                std::slice::from_ref(&program.ix),
+                Some(&harness.actors[ATTACKER].pubkey()),
+            );
+            let tx = Transaction::new(&signers, msg, svm.latest_blockhash());
+            let result = svm.send_transaction(tx);
+            assert!(
+                result.is_ok(),
+                "{}::{}: {:?}",
+                fixture.program,
+                fixture.instruction,
+                result.err().map(|e| e.err)
+            );
+        }
+    }
+
+    #[test]
+    fn test_stripped_signer_is_rejected_everywhere() {
+        // The signer-authorization exploit, tried on every fixture
+        let harness = Harness::new();
+        for fixture in FIXTURES {
+            let (_, program) = harness.fixture(&Case {
+                program: fixture.program.to_string(),
+                instruction: fixture.instruction.to_string(),
+                changes: vec![],
+            });
+            for (slot, _) in program
+                .ix
+                .accounts
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| m.is_signer)
+            {
+                check(
+                    &harness,
+                    &Case {
+                        program: fixture.program.to_string(),
+                        instruction: fixture.instruction.to_string(),
+                        changes: vec![Change::ToggleSigner { slot }],
+                    },
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_permutations_are_rejected() {
+        let harness = Harness::new();
+        for seed in 0..200 {
+            check(&harness, &harness.decode(&pseudo_random_input(seed, 32)));
+        }
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        let harness = Harness::new();
+        let replayed = replay_corpus(TARGET, |data| harness.run(&harness.decode(data)).is_some());
+        assert!(replayed > 0, "no corpus for {}", TARGET);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, &harness.decode(data));
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_account_meta.rs"));
+}
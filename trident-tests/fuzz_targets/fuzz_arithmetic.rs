@@ -5,6 +5,20 @@
 //!
 //! The vulnerable version wraps on overflow; the secure version returns
 //! an error when overflow would occur.
+//!
+//! `min_out` is drawn around the correct output (exactly it, one either
+//! side, or anywhere below it), so the secure swap's slippage check is hit
+//! from both sides; the vulnerable swap ignores it.
+//!
+//! Each run checks the seeds `FUZZ_SEED..FUZZ_SEED + FUZZ_ITERATIONS`, and a
+//! failure reports the seed that replays it. The failing seed's bytes are
+//! also recorded under `fuzz/findings/arithmetic/`.
+
+use arbitrary::{Arbitrary, Unstructured};
+use fuzz_targets::pseudo_random_input;
+use security_fuzz::findings;
+
+const TARGET: &str = "arithmetic";
 
 /// Fuzz data - the inputs we're randomizing
 #[derive(Debug, Clone)]
@@ -19,28 +33,109 @@ pub struct SwapFuzzData {
     pub initial_reserve_y: u64,
 }
 
+impl<'a> Arbitrary<'a> for SwapFuzzData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let amount_in = u.arbitrary()?;
+        let initial_reserve_x = u.int_in_range(1..=u64::MAX)?; // Avoid zero
+        let initial_reserve_y = u.int_in_range(1..=u64::MAX)?; // Avoid zero
+
+        // A uniform u64 is almost always above any output, so most picks
+        // are relative to the output the swap should give
+        let expected = expected_out(amount_in, initial_reserve_x, initial_reserve_y)
+            .min(u64::MAX as u128) as u64;
+        let min_out = match u.int_in_range(0..=5u8)? {
+            0 => 0,
+            1 => expected,
+            2 => expected.saturating_add(1),
+            3 => expected.saturating_sub(1),
+            4 => u.int_in_range(0..=expected)?,
+            _ => u.arbitrary()?,
+        };
+
+        Ok(Self {
+            amount_in,
+            min_out,
+            initial_reserve_x,
+            initial_reserve_y,
+        })
+    }
+}
+
+/// The constant-product output computed without overflow: what both swaps
+/// should return.
+fn expected_out(amount_in: u64, reserve_x: u64, reserve_y: u64) -> u128 {
+    let denominator = reserve_x as u128 + amount_in as u128;
+    if denominator == 0 {
+        return 0;
+    }
+    amount_in as u128 * reserve_y as u128 / denominator
+}
+
 impl SwapFuzzData {
-    /// Generate random fuzz data
-    pub fn random() -> Self {
-        use std::collections::hash_map::RandomState;
-        use std::hash::{BuildHasher, Hasher};
-        
-        let s = RandomState::new();
-        let mut h = s.build_hasher();
-        h.write_u64(std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64);
-        
-        Self {
-            amount_in: h.finish(),
-            min_out: 0,
-            initial_reserve_x: h.finish().wrapping_add(1), // Avoid zero
-            initial_reserve_y: h.finish().wrapping_add(1), // Avoid zero
-        }
+    /// Fuzz data for `seed`. The same seed always gives the same data, so a
+    /// failure replays exactly from the seed it reports.
+    pub fn from_seed(seed: u64) -> Self {
+        Self::from_bytes(&pseudo_random_input(seed, INPUT_LEN))
+    }
+
+    /// Fuzz data decoded from raw bytes; missing bytes read as zero.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::arbitrary(&mut Unstructured::new(bytes)).expect("any bytes decode")
     }
 }
 
+/// Bytes a seed expands to: three u64s, the `min_out` pick and one more
+const INPUT_LEN: usize = 33;
+
+/// `FUZZ_SEED`: first seed of a run
+pub const SEED_VAR: &str = "FUZZ_SEED";
+
+/// `FUZZ_ITERATIONS`: number of seeds a run checks
+pub const ITERATIONS_VAR: &str = "FUZZ_ITERATIONS";
+
+fn env_u64(name: &str) -> Option<u64> {
+    let value = std::env::var(name).ok()?;
+    Some(
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a u64, got {:?}", name, value)),
+    )
+}
+
+/// Check both properties for seeds `first..first + iterations`. Panics
+/// with the failing seed and the command that replays it.
+pub fn run_seeds(first: u64, iterations: u64) {
+    for seed in first..first.saturating_add(iterations) {
+        let bytes = pseudo_random_input(seed, INPUT_LEN);
+        let replay = format!("{}={} {}=1", SEED_VAR, seed, ITERATIONS_VAR);
+        findings::capture(TARGET, &bytes, || {
+            check(&SwapFuzzData::from_bytes(&bytes), &replay)
+        });
+    }
+}
+
+fn check(data: &SwapFuzzData, replay: &str) {
+    findings::attach_decoded(data);
+    assert!(
+        check_overflow_property(data),
+        "Overflow property failed: {:?}\nreplay with {}",
+        data,
+        replay
+    );
+    assert!(
+        check_underflow_property(data),
+        "Underflow property failed: {:?}\nreplay with {}",
+        data,
+        replay
+    );
+    assert!(
+        check_slippage_property(data),
+        "Slippage property failed: {:?}\nreplay with {}",
+        data,
+        replay
+    );
+}
+
 /// Vulnerable swap implementation (mirrors the on-chain vulnerable code)
 ///
 /// This function uses raw arithmetic that can overflow.
@@ -210,9 +305,33 @@ fn check_underflow_property(data: &SwapFuzzData) -> bool {
     }
 }
 
+/// Property: The secure swap honours `min_out`
+///
+/// It never returns less than `min_out`, and rejects for slippage exactly
+/// when the correct output is below it.
+fn check_slippage_property(data: &SwapFuzzData) -> bool {
+    let expected = expected_out(data.amount_in, data.initial_reserve_x, data.initial_reserve_y);
+    match secure_swap(
+        data.amount_in,
+        data.min_out,
+        data.initial_reserve_x,
+        data.initial_reserve_y,
+    ) {
+        Ok((amount_out, _, _)) => amount_out >= data.min_out,
+        Err(e) if e.starts_with("SlippageExceeded") => expected < data.min_out as u128,
+        Err(_) => true, // Rejected before the slippage check
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Whether the vulnerable swap pays out less than `min_out` for `data`
+    fn vulnerable_breaks_slippage(data: &SwapFuzzData) -> bool {
+        vulnerable_swap(data.amount_in, data.initial_reserve_x, data.initial_reserve_y)
+            .is_some_and(|(amount_out, _, _)| amount_out < data.min_out)
+    }
     
     #[test]
     fn test_known_overflow_case() {
@@ -294,31 +413,79 @@ mod tests {
     }
     
     #[test]
-    fn test_random_fuzz_iterations() {
-        // Run 1000 random iterations
-        for i in 0..1000 {
-            let data = SwapFuzzData {
-                amount_in: rand_u64(i),
-                min_out: 0,
-                initial_reserve_x: rand_u64(i + 1000).wrapping_add(1),
-                initial_reserve_y: rand_u64(i + 2000).wrapping_add(1),
-            };
-            
-            assert!(check_overflow_property(&data), 
-                "Overflow property failed for iteration {}: {:?}", i, data);
-            assert!(check_underflow_property(&data),
-                "Underflow property failed for iteration {}: {:?}", i, data);
+    fn test_slippage_case() {
+        // One unit more than the pool gives: secure refuses, vulnerable pays
+        let data = SwapFuzzData {
+            amount_in: 1000,
+            min_out: 1000,
+            initial_reserve_x: 1_000_000_000,
+            initial_reserve_y: 1_000_000_000,
+        };
+        assert_eq!(
+            secure_swap(data.amount_in, data.min_out, data.initial_reserve_x, data.initial_reserve_y),
+            Err("SlippageExceeded: output below minimum")
+        );
+        assert!(check_slippage_property(&data));
+        assert!(vulnerable_breaks_slippage(&data));
+
+        // Exactly the output passes
+        let exact = SwapFuzzData { min_out: 999, ..data };
+        assert!(check_slippage_property(&exact));
+        assert!(!vulnerable_breaks_slippage(&exact));
+    }
+
+    #[test]
+    fn test_min_out_hits_both_sides_of_the_output() {
+        let (mut passed, mut rejected, mut broken) = (0, 0, 0);
+        for seed in 0..1000 {
+            let data = SwapFuzzData::from_seed(seed);
+            match secure_swap(
+                data.amount_in,
+                data.min_out,
+                data.initial_reserve_x,
+                data.initial_reserve_y,
+            ) {
+                Ok(_) if data.min_out > 0 => passed += 1,
+                Err(e) if e.starts_with("SlippageExceeded") => rejected += 1,
+                _ => {}
+            }
+            broken += vulnerable_breaks_slippage(&data) as u32;
         }
-        println!("All 1000 random iterations passed!");
+        // A non-zero min_out both holds and trips, and the vulnerable
+        // swap demonstrably pays below it
+        assert!(passed > 0, "no swap passed a non-zero min_out");
+        assert!(rejected > 0, "no swap tripped the slippage check");
+        assert!(broken > 0, "the vulnerable swap never broke min_out");
     }
-    
-    /// Simple pseudo-random number generator for testing
-    fn rand_u64(seed: u64) -> u64 {
-        let mut x = seed.wrapping_add(0x9E3779B97F4A7C15);
-        x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
-        x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
-        x ^ (x >> 31)
+
+    #[test]
+    fn test_random_fuzz_iterations() {
+        // Deterministic unless FUZZ_SEED says otherwise
+        let first = env_u64(SEED_VAR).unwrap_or(0);
+        let iterations = env_u64(ITERATIONS_VAR).unwrap_or(1000);
+        println!("{}={} {}={}", SEED_VAR, first, ITERATIONS_VAR, iterations);
+        run_seeds(first, iterations);
+        println!("All {} seeded iterations passed!", iterations);
+    }
+
+    #[test]
+    fn test_seed_replays_exactly() {
+        let (a, b) = (SwapFuzzData::from_seed(42), SwapFuzzData::from_seed(42));
+        assert_eq!(
+            (a.amount_in, a.min_out, a.initial_reserve_x, a.initial_reserve_y),
+            (b.amount_in, b.min_out, b.initial_reserve_x, b.initial_reserve_y)
+        );
+        // Neighbouring seeds don't share values the way clock reads did
+        let c = SwapFuzzData::from_seed(43);
+        assert_ne!(a.amount_in, c.amount_in);
+        assert!(a.initial_reserve_x > 0 && a.initial_reserve_y > 0);
     }
+
+    fn replay_finding(data: &[u8]) {
+        check(&SwapFuzzData::from_bytes(data), "the finding's test");
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_arithmetic.rs"));
 }
 
 // Entry point for trident fuzzer (when using full trident)
@@ -334,4 +501,19 @@ fn main() {
     println!("  1. The secure swap never silently overflows");
     println!("  2. The secure swap catches all underflow cases");
     println!("  3. Normal swaps produce correct results");
+    println!("  4. The secure swap never pays out less than min_out");
+    println!();
+
+    // Without FUZZ_SEED the clock only picks the first seed, which is printed
+    // so the run can be repeated
+    let first = env_u64(SEED_VAR).unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64
+    });
+    let iterations = env_u64(ITERATIONS_VAR).unwrap_or(10_000);
+    println!("Checking {} seeds from {}={}", iterations, SEED_VAR, first);
+    run_seeds(first, iterations);
+    println!("All {} iterations passed", iterations);
 }
@@ -0,0 +1,765 @@
+//! Differential fuzzing of `vulnerable_*` against `secure_*` instructions
+//!
+//! Each input picks a fixture (a program and one of its instruction pairs),
+//! the values its setup uses, the pair's arguments, which account goes in
+//! each slot and which actors sign. Both instructions then run from copies
+//! of the same state, and the target compares every account they could
+//! have touched:
+//!
+//! > When the vulnerable instruction lands, the secure one lands too and
+//! > leaves the same state.
+//!
+//! A case that breaks this is where the two versions disagree, which is the
+//! bug each program demonstrates. Those cases aren't crashes: the target
+//! shrinks each one to the smallest case that still diverges and writes it
+//! to `findings/<program>/` along with the diff, so a fuzzing run ends
+//! with a handful of minimal exploits rather than thousands of raw inputs.
+//!
+//! With `FUZZ_AMM_MODEL` set, swap cases are also replayed against the
+//! reference AMM in [`amm_model`]: the secure swap must match it within its
+//! rounding bounds, and how far the vulnerable one strays is logged.
+
+use fuzz_targets::amm_model::{self, judge, Observed, Rejection, Verdict};
+use fuzz_targets::{record_finding, FuzzInput};
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::diff::StateDiff;
+use security_tests::idl::{ArgValue, Idl};
+use security_tests::scenario::seeded_keypair;
+use serde::{Deserialize, Serialize};
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const TARGET: &str = "differential";
+
+/// Actors are derived from this seed, so a finding replays exactly
+const SEED: u64 = 1;
+
+/// The victim owns the fixture's state; the attacker pays for and sends
+/// both instructions.
+const ACTORS: [&str; 2] = ["alice", "mallory"];
+
+const ATTACKER: usize = 1;
+
+/// Account pool entries: the actors first, then what the fixture's setup
+/// returns
+const FIRST_FIXTURE_ACCOUNT: usize = ACTORS.len();
+
+/// `FUZZ_AMM_MODEL`: when set, every swap case is also judged against
+/// [`amm_model`]
+pub const MODEL_VAR: &str = "FUZZ_AMM_MODEL";
+
+/// Shrinking passes before a finding is written as it stands
+const MAX_SHRINK_ROUNDS: usize = 32;
+
+/// State shared by a fixture's setup and its instructions.
+pub struct Ctx<'a> {
+    pub svm: &'a mut LiteSVM,
+    pub program_id: Pubkey,
+    pub idl: &'a Idl,
+    pub actors: &'a [Keypair],
+}
+
+impl Ctx<'_> {
+    fn pda(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+
+    fn send(&mut self, ix: solana_instruction::Instruction, signer: usize) {
+        let signer = &self.actors[signer];
+        let msg = Message::new(&[ix], Some(&signer.pubkey()));
+        let tx = Transaction::new(&[signer], msg, self.svm.latest_blockhash());
+        // A setup value the program rejects leaves less state to attack,
+        // which is still a valid case
+        let _ = self.svm.send_transaction(tx);
+        self.svm.expire_blockhash();
+    }
+}
+
+/// An instruction pair and the state it runs from.
+pub struct Fixture {
+    pub program: &'static str,
+    /// `X` of `vulnerable_X` / `secure_X`
+    pub instruction: &'static str,
+    /// Values [`Fixture::setup`] reads
+    pub params: usize,
+    /// Build the state from `params` and return the accounts, beyond the
+    /// actors, the fuzzer may put in a slot.
+    pub setup: fn(&mut Ctx, &[u64]) -> Vec<Pubkey>,
+}
+
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        program: "signer_authorization",
+        instruction: "withdraw",
+        params: 1,
+        setup: |ctx, params| {
+            let alice = ctx.actors[0].pubkey();
+            let vault = ctx.pda(&[b"vault", alice.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .account("authority", alice)
+                .account("vault", vault)
+                .build();
+            ctx.send(ix, 0);
+            let _ = ctx
+                .svm
+                .airdrop(&vault, params[0] % (5 * LAMPORTS_PER_SOL) + 1);
+            vec![vault]
+        },
+    },
+    Fixture {
+        program: "duplicate_accounts",
+        instruction: "transfer",
+        params: 2,
+        setup: |ctx, params| {
+            let mut balances = Vec::new();
+            for (actor, &initial_balance) in params.iter().enumerate() {
+                let user = ctx.actors[actor].pubkey();
+                let balance = ctx.pda(&[b"balance", user.as_ref()]);
+                let ix = ctx
+                    .idl
+                    .ix("initialize")
+                    .arg("initial_balance", initial_balance)
+                    .account("user", user)
+                    .account("user_account", balance)
+                    .build();
+                ctx.send(ix, actor);
+                balances.push(balance);
+            }
+            balances
+        },
+    },
+    Fixture {
+        program: "arithmetic_overflow",
+        instruction: "swap",
+        params: 3,
+        setup: |ctx, params| {
+            let alice = ctx.actors[0].pubkey();
+            let pool = ctx.pda(&[b"pool", alice.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .arg("initial_x", params[0])
+                .arg("initial_y", params[1])
+                .arg("fee_bps", params[2] % 10_001)
+                .account("authority", alice)
+                .account("pool", pool)
+                .build();
+            ctx.send(ix, 0);
+            vec![pool]
+        },
+    },
+];
+
+/// One generated input, in the form findings are written in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Case {
+    pub program: String,
+    pub instruction: String,
+    pub params: Vec<u64>,
+    /// `(name, value)` of each argument; bools are 0 or 1
+    pub args: Vec<(String, u64)>,
+    /// `(slot, choice)`: the slot gets entry `choice` of the account pool,
+    /// modulo its length
+    pub accounts: Vec<(String, usize)>,
+    /// Whether each of [`ACTORS`] signs, when placed in any slot. The
+    /// attacker always signs as the fee payer.
+    pub signs: Vec<bool>,
+}
+
+/// How the two instructions disagreed on a case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    /// The secure instruction failed where the vulnerable one landed
+    SecureRejected(String),
+    /// Both landed, with different results; lines of the diff from the
+    /// secure state to the vulnerable one
+    State(Vec<String>),
+}
+
+#[derive(Debug, Serialize)]
+struct Finding<'a> {
+    case: &'a Case,
+    secure_error: Option<&'a str>,
+    diff: &'a [String],
+}
+
+/// Both versions of one swap, judged against the AMM model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelReport {
+    pub before: amm_model::Pool,
+    pub amount_in: u64,
+    pub min_out: u64,
+    pub vulnerable: Verdict,
+    pub secure: Verdict,
+}
+
+struct Program {
+    svm: LiteSVM,
+    program_id: Pubkey,
+    idl: Idl,
+}
+
+/// Every fixture program deployed with funded actors; each case runs from
+/// a copy of this.
+pub struct Harness {
+    programs: Vec<Program>,
+    actors: Vec<Keypair>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let actors: Vec<Keypair> = ACTORS
+            .iter()
+            .map(|label| seeded_keypair(SEED, label))
+            .collect();
+        let programs = FIXTURES
+            .iter()
+            .map(|fixture| {
+                let (mut svm, _) = common::setup(fixture.program);
+                for actor in &actors {
+                    svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL)
+                        .expect("Airdrop failed");
+                }
+                Program {
+                    svm,
+                    program_id: common::load_program_id(fixture.program),
+                    idl: Idl::load(fixture.program),
+                }
+            })
+            .collect();
+        Self { programs, actors }
+    }
+
+    fn fixture(&self, case: &Case) -> (&Fixture, &Program) {
+        let i = FIXTURES
+            .iter()
+            .position(|f| f.program == case.program && f.instruction == case.instruction)
+            .unwrap_or_else(|| panic!("no fixture for {}::{}", case.program, case.instruction));
+        (&FIXTURES[i], &self.programs[i])
+    }
+
+    /// Read a case out of fuzzer input.
+    pub fn decode(&self, data: &[u8]) -> Case {
+        let mut input = FuzzInput::new(data);
+        let i = input.choose(FIXTURES.len());
+        let (fixture, program) = (&FIXTURES[i], &self.programs[i]);
+        let params = (0..fixture.params)
+            .map(|_| input.amount(u64::MAX))
+            .collect();
+
+        let mut args = Vec::new();
+        let mut accounts = Vec::new();
+        for version in ["vulnerable", "secure"] {
+            let ix = program
+                .idl
+                .instruction(&format!("{}_{}", version, fixture.instruction));
+            for (name, ty) in &ix.args {
+                if args.iter().any(|(n, _)| n == name) {
+                    continue;
+                }
+                let value = match ty.as_str() {
+                    Some("bool") => input.bool() as u64,
+                    Some("u8") => input.amount(u8::MAX as u64),
+                    Some("u16") => input.amount(u16::MAX as u64),
+                    Some("u32") => input.amount(u32::MAX as u64),
+                    Some("u64") => input.amount(u64::MAX),
+                    _ => panic!("{}: argument {} has type {}", ix.name, name, ty),
+                };
+                args.push((name.clone(), value));
+            }
+            for slot in ix.accounts.iter().filter(|a| a.address.is_none()) {
+                if !accounts.iter().any(|(n, _)| *n == slot.name) {
+                    accounts.push((slot.name.clone(), input.u8() as usize));
+                }
+            }
+        }
+        let signs = (0..ACTORS.len()).map(|_| input.bool()).collect();
+
+        Case {
+            program: fixture.program.to_string(),
+            instruction: fixture.instruction.to_string(),
+            params,
+            args,
+            accounts,
+            signs,
+        }
+    }
+
+    /// Run both instructions of `case` and report how they disagree, if
+    /// they do.
+    pub fn run(&self, case: &Case) -> Option<Divergence> {
+        let (fixture, program, base, pool) = self.prepare(case);
+        let mut vulnerable = base.clone();
+        let mut secure = base.clone();
+        let name = |version| format!("{}_{}", version, fixture.instruction);
+        if self
+            .send(&mut vulnerable, program, &name("vulnerable"), case, &pool)
+            .is_err()
+        {
+            return None;
+        }
+        if let Err(e) = self.send(&mut secure, program, &name("secure"), case, &pool) {
+            return Some(Divergence::SecureRejected(e));
+        }
+
+        // Both landed with the same signers, so fees are equal and need no
+        // allowance
+        let diff = StateDiff::between(
+            |address| secure.get_account(address),
+            |address| vulnerable.get_account(address),
+            &pool,
+            &[],
+        )
+        .with_idl(&program.idl);
+        if diff.changed().is_empty() {
+            return None;
+        }
+        Some(Divergence::State(
+            diff.to_string().lines().map(str::to_string).collect(),
+        ))
+    }
+
+    // The fixture's state for `case`, and the account pool its slots pick
+    // from
+    fn prepare(&self, case: &Case) -> (&Fixture, &Program, LiteSVM, Vec<Pubkey>) {
+        let (fixture, program) = self.fixture(case);
+        let mut base = program.svm.clone();
+        let mut ctx = Ctx {
+            svm: &mut base,
+            program_id: program.program_id,
+            idl: &program.idl,
+            actors: &self.actors,
+        };
+        let mut params = case.params.clone();
+        params.resize(fixture.params, 0);
+        let mut pool: Vec<Pubkey> = self.actors.iter().map(|a| a.pubkey()).collect();
+        pool.extend((fixture.setup)(&mut ctx, &params));
+        (fixture, program, base, pool)
+    }
+
+    /// Replay a swap case against [`amm_model`] and judge both versions.
+    /// Only swaps of the fixture's pool by a signing user are judged; the
+    /// model has nothing to say about the others.
+    pub fn model_check(&self, case: &Case) -> Option<ModelReport> {
+        if (case.program.as_str(), case.instruction.as_str()) != ("arithmetic_overflow", "swap") {
+            return None;
+        }
+        let (fixture, program, base, pool) = self.prepare(case);
+        let slot = |name: &str| {
+            let choice = case
+                .accounts
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(0, |(_, c)| *c);
+            choice % pool.len()
+        };
+        let user = slot("user");
+        let signs =
+            user == ATTACKER || (user < ACTORS.len() && case.signs.get(user) == Some(&true));
+        if slot("pool") != FIRST_FIXTURE_ACCOUNT || !signs {
+            return None;
+        }
+
+        let arg = |name: &str| {
+            case.args
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(0, |(_, v)| *v)
+        };
+        let (amount_in, min_out) = (arg("amount_in"), arg("min_out"));
+        let reserves = |svm: &LiteSVM| {
+            let data = svm
+                .get_account(&pool[FIRST_FIXTURE_ACCOUNT])
+                .expect("pool exists")
+                .data;
+            let field = |name| {
+                let range = program.idl.field_range("Pool", name);
+                u64::from_le_bytes(data[range].try_into().unwrap())
+            };
+            amm_model::Pool {
+                reserve_x: field("reserve_x"),
+                reserve_y: field("reserve_y"),
+            }
+        };
+        let before = reserves(&base);
+        let verdict = |version| {
+            let mut svm = base.clone();
+            let instruction = format!("{}_{}", version, fixture.instruction);
+            let observed = match self.send(&mut svm, program, &instruction, case, &pool) {
+                Ok(()) => Observed::Landed(reserves(&svm)),
+                Err(error) => Observed::Failed(rejection(&program.idl, &error)),
+            };
+            judge(before, amount_in, min_out, &observed)
+        };
+        Some(ModelReport {
+            before,
+            amount_in,
+            min_out,
+            vulnerable: verdict("vulnerable"),
+            secure: verdict("secure"),
+        })
+    }
+
+    // Send one instruction of the pair; `Err` carries the failure
+    fn send(
+        &self,
+        svm: &mut LiteSVM,
+        program: &Program,
+        instruction: &str,
+        case: &Case,
+        pool: &[Pubkey],
+    ) -> Result<(), String> {
+        let idl = &program.idl;
+        let ix_def = idl.instruction(instruction);
+        let mut builder = idl.ix(instruction);
+        for (name, ty) in &ix_def.args {
+            let value = case
+                .args
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(0, |(_, v)| *v);
+            builder = match ty.as_str() {
+                Some("bool") => builder.arg(name, ArgValue::Bool(value != 0)),
+                _ => builder.arg(name, value),
+            };
+        }
+        for slot in ix_def.accounts.iter().filter(|a| a.address.is_none()) {
+            let choice = case
+                .accounts
+                .iter()
+                .find(|(n, _)| *n == slot.name)
+                .map_or(0, |(_, c)| *c);
+            builder = builder.account(&slot.name, pool[choice % pool.len()]);
+        }
+        let mut ix = builder.build();
+
+        // Signatures are the fuzzed permission, whatever the instruction
+        // declares
+        let mut signers = vec![&self.actors[ATTACKER]];
+        for (actor, keypair) in self.actors.iter().enumerate() {
+            let signs = actor == ATTACKER || case.signs.get(actor).copied().unwrap_or(false);
+            let mut placed = false;
+            for meta in ix
+                .accounts
+                .iter_mut()
+                .filter(|m| m.pubkey == keypair.pubkey())
+            {
+                meta.is_signer = signs;
+                placed = true;
+            }
+            if signs && placed && actor != ATTACKER {
+                signers.push(keypair);
+            }
+        }
+
+        let msg = Message::new(&[ix], Some(&self.actors[ATTACKER].pubkey()));
+        let tx = Transaction::new(&signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+            .map(|_| ())
+            .map_err(|failed| format!("{:?}", failed.err))
+    }
+
+    /// Smallest case derived from `case` that still diverges: values pulled
+    /// towards zero, accounts towards the first pool entry, signatures
+    /// dropped.
+    pub fn shrink(&self, case: &Case) -> Case {
+        let mut best = case.clone();
+        for _ in 0..MAX_SHRINK_ROUNDS {
+            let Some(next) = candidates(&best).find(|c| self.run(c).is_some()) else {
+                break;
+            };
+            best = next;
+        }
+        best
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The `PoolError` a failed swap returned, if it was one
+fn rejection(idl: &Idl, error: &str) -> Option<Rejection> {
+    Rejection::ALL.into_iter().find(|r| {
+        idl.error(r.name())
+            .is_some_and(|e| error.contains(&format!("Custom({})", e.code)))
+    })
+}
+
+// Values below `value` worth trying, smallest first
+fn smaller(value: u64) -> impl Iterator<Item = u64> {
+    let mut values: Vec<u64> = [0, 1, value / 2, value.saturating_sub(1)]
+        .into_iter()
+        .filter(|&v| v < value)
+        .collect();
+    values.dedup();
+    values.into_iter()
+}
+
+// Every case one step simpler than `case`
+fn candidates(case: &Case) -> impl Iterator<Item = Case> + '_ {
+    let params = (0..case.params.len()).flat_map(move |i| {
+        smaller(case.params[i]).map(move |v| {
+            let mut c = case.clone();
+            c.params[i] = v;
+            c
+        })
+    });
+    let args = (0..case.args.len()).flat_map(move |i| {
+        smaller(case.args[i].1).map(move |v| {
+            let mut c = case.clone();
+            c.args[i].1 = v;
+            c
+        })
+    });
+    let accounts = (0..case.accounts.len()).flat_map(move |i| {
+        (0..case.accounts[i].1).map(move |choice| {
+            let mut c = case.clone();
+            c.accounts[i].1 = choice;
+            c
+        })
+    });
+    let signs = (0..case.signs.len())
+        .filter(|&i| case.signs[i])
+        .map(move |i| {
+            let mut c = case.clone();
+            c.signs[i] = false;
+            c
+        });
+    params.chain(args).chain(accounts).chain(signs)
+}
+
+fn check(harness: &Harness, data: &[u8], model: bool) {
+    let case = harness.decode(data);
+    findings::attach_scenario(&case);
+    if model {
+        check_model(harness, &case);
+    }
+    if harness.run(&case).is_none() {
+        return;
+    }
+    let case = harness.shrink(&case);
+    let divergence = harness.run(&case).expect("a shrunk case still diverges");
+    let (secure_error, diff) = match &divergence {
+        Divergence::SecureRejected(e) => (Some(e.as_str()), &[][..]),
+        Divergence::State(lines) => (None, &lines[..]),
+    };
+    record_finding(
+        &case.program,
+        &Finding {
+            case: &case,
+            secure_error,
+            diff,
+        },
+    );
+}
+
+// The secure swap must agree with the model; how far the vulnerable one
+// strays is only logged
+fn check_model(harness: &Harness, case: &Case) {
+    let Some(report) = harness.model_check(case) else {
+        return;
+    };
+    if report.secure != Verdict::Agrees {
+        panic!(
+            "secure swap of {} (min_out {}) from {:?} {}\ncase: {:#?}",
+            report.amount_in, report.min_out, report.before, report.secure, case
+        );
+    }
+    if report.vulnerable != Verdict::Agrees {
+        eprintln!(
+            "vulnerable swap of {} (min_out {}) from {:?} {}",
+            report.amount_in, report.min_out, report.before, report.vulnerable
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    let model = std::env::var_os(MODEL_VAR).is_some();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(TARGET, data, || check(&harness, data, model));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{pseudo_random_input, replay_corpus};
+
+    const ALICE: usize = 0;
+    const MALLORY: usize = 1;
+
+    fn case(
+        program: &str,
+        instruction: &str,
+        params: &[u64],
+        args: &[(&str, u64)],
+        accounts: &[(&str, usize)],
+        signs: [bool; 2],
+    ) -> Case {
+        Case {
+            program: program.to_string(),
+            instruction: instruction.to_string(),
+            params: params.to_vec(),
+            args: args.iter().map(|(n, v)| (n.to_string(), *v)).collect(),
+            accounts: accounts.iter().map(|(n, c)| (n.to_string(), *c)).collect(),
+            signs: signs.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_decode_is_total() {
+        let harness = Harness::new();
+        for seed in 0..100 {
+            let case = harness.decode(&pseudo_random_input(seed, 256));
+            let (fixture, _) = harness.fixture(&case);
+            assert_eq!(case.params.len(), fixture.params);
+            assert_eq!(case.signs.len(), ACTORS.len());
+        }
+    }
+
+    #[test]
+    fn test_unsigned_withdraw_diverges() {
+        let harness = Harness::new();
+        let exploit = case(
+            "signer_authorization",
+            "withdraw",
+            &[LAMPORTS_PER_SOL],
+            &[("amount", LAMPORTS_PER_SOL / 2)],
+            &[
+                ("vault", FIRST_FIXTURE_ACCOUNT),
+                ("authority", ALICE),
+                ("destination", MALLORY),
+            ],
+            [false, true],
+        );
+        assert!(matches!(
+            harness.run(&exploit),
+            Some(Divergence::SecureRejected(_))
+        ));
+
+        // With Alice's signature both versions agree
+        let mut signed = exploit.clone();
+        signed.signs[ALICE] = true;
+        assert_eq!(harness.run(&signed), None);
+    }
+
+    #[test]
+    fn test_duplicate_transfer_shrinks_to_minimal_case() {
+        let harness = Harness::new();
+        let exploit = case(
+            "duplicate_accounts",
+            "transfer",
+            &[1_000, 500],
+            &[("amount", 700)],
+            &[
+                ("authority", MALLORY),
+                ("from_account", FIRST_FIXTURE_ACCOUNT + 1),
+                ("to_account", FIRST_FIXTURE_ACCOUNT + 1),
+            ],
+            [true, true],
+        );
+        assert!(harness.run(&exploit).is_some());
+
+        let shrunk = harness.shrink(&exploit);
+        assert!(harness.run(&shrunk).is_some());
+        assert!(!shrunk.signs[ALICE]);
+        assert!(shrunk.args[0].1 <= 700 && shrunk.params[1] <= 500);
+    }
+
+    fn swap(amount_in: u64, min_out: u64) -> Case {
+        case(
+            "arithmetic_overflow",
+            "swap",
+            &[1_000_000, 1_000_000, 30],
+            &[("amount_in", amount_in), ("min_out", min_out)],
+            &[("user", MALLORY), ("pool", FIRST_FIXTURE_ACCOUNT)],
+            [false, true],
+        )
+    }
+
+    #[test]
+    fn test_model_judges_both_swaps() {
+        let harness = Harness::new();
+        let report = harness.model_check(&swap(1_000, 999)).unwrap();
+        assert_eq!(report.secure, Verdict::Agrees);
+        assert_eq!(report.vulnerable, Verdict::Agrees);
+
+        // The vulnerable swap skips the zero and slippage checks
+        for refused in [swap(0, 0), swap(1_000, 1_000)] {
+            let report = harness.model_check(&refused).unwrap();
+            assert_eq!(report.secure, Verdict::Agrees);
+            assert!(matches!(report.vulnerable, Verdict::Landed { .. }));
+        }
+
+        // Not a swap of the pool, so nothing to judge
+        let mut elsewhere = swap(1_000, 0);
+        elsewhere.accounts[1].1 = ALICE;
+        assert_eq!(harness.model_check(&elsewhere), None);
+    }
+
+    #[test]
+    fn test_random_swaps_match_model() {
+        let harness = Harness::new();
+        let mut judged = 0;
+        for seed in 0..300 {
+            let case = harness.decode(&pseudo_random_input(seed, 256));
+            if harness.model_check(&case).is_some() {
+                check_model(&harness, &case);
+                judged += 1;
+            }
+        }
+        assert!(judged > 0, "no random case swapped the pool");
+    }
+
+    #[test]
+    fn test_candidates_are_strictly_simpler() {
+        let base = case("p", "i", &[3], &[("a", 1)], &[("s", 2)], [true, false]);
+        let count = candidates(&base).count();
+        // params 3 -> 0, 1, 2; arg 1 -> 0; slot 2 -> 0, 1; one signature
+        assert_eq!(count, 3 + 1 + 2 + 1);
+        assert!(candidates(&base).all(|c| c != base));
+    }
+
+    #[test]
+    fn test_random_cases_run() {
+        let harness = Harness::new();
+        for seed in 0..100 {
+            let case = harness.decode(&pseudo_random_input(seed, 256));
+            if harness.run(&case).is_some() {
+                assert!(harness.run(&harness.shrink(&case)).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        // Divergences are the demonstrated bugs; only a panic fails here
+        let harness = Harness::new();
+        let replayed = replay_corpus(TARGET, |data| {
+            harness.run(&harness.decode(data));
+            false
+        });
+        assert!(replayed >= FIXTURES.len(), "a seed per fixture");
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, data, true);
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_differential.rs"));
+}
@@ -0,0 +1,605 @@
+//! Instruction-data mutation fuzzing against the deployed programs
+//!
+//! Each input picks a fixture, a valid instruction and the state it runs
+//! from, and a few mutations of its raw data: a flipped discriminator bit,
+//! another instruction's discriminator, truncation, appended garbage, an
+//! overwritten argument byte. The mutated instruction runs in LiteSVM, so
+//! the program's own deserialization decides what happens to it.
+//!
+//! Properties, checked on every run:
+//!
+//! > A program rejects data it can't use with an error it returns, never
+//! > by panicking or running out of compute.
+//!
+//! > Data that starts with no known discriminator never lands, and the
+//! > handler that runs is the one the data's discriminator names.
+//!
+//! Anchor programs log `Instruction: <Name>` on entering a handler, which
+//! is how the handler that ran is told apart. Pinocchio programs log
+//! nothing, so for them only the first half of the second property is
+//! checked. Any violation crashes the target.
+
+use fuzz_targets::FuzzInput;
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::corrupt;
+use security_tests::idl::Idl;
+use security_tests::scenario::seeded_keypair;
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const TARGET: &str = "ix_mutation";
+
+/// The fixture's actor is derived from this seed, so a crash input replays
+/// exactly
+const SEED: u64 = 1;
+
+/// Most mutations applied to one instruction
+const MAX_MUTATIONS: usize = 4;
+
+/// Most bytes one [`Mutation::Append`] adds
+const MAX_APPEND: usize = 16;
+
+/// Anchor discriminators are 8 bytes; Pinocchio tags are 1
+const ANCHOR_DISCRIMINATOR_LEN: usize = 8;
+
+/// State shared by a fixture's setup.
+pub struct Ctx<'a> {
+    pub svm: &'a mut LiteSVM,
+    pub program_id: Pubkey,
+    pub idl: &'a Idl,
+    pub actor: &'a Keypair,
+}
+
+impl Ctx<'_> {
+    fn pda(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+
+    fn send(&mut self, ix: Instruction) {
+        let msg = Message::new(&[ix], Some(&self.actor.pubkey()));
+        let tx = Transaction::new(&[self.actor], msg, self.svm.latest_blockhash());
+        self.svm
+            .send_transaction(tx)
+            .unwrap_or_else(|e| panic!("Fixture setup failed: {:?}", e.err));
+        self.svm.expire_blockhash();
+    }
+
+    // An account the program owns, as Pinocchio programs expect them
+    fn program_account(&mut self, len: usize) -> Pubkey {
+        let address = Pubkey::new_unique();
+        let account = Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![0; len],
+            owner: self.program_id,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+        address
+    }
+}
+
+/// A valid instruction and the state it runs from.
+pub struct Fixture {
+    pub program: &'static str,
+    pub instruction: &'static str,
+    /// Build the state and return the instruction, signed by the actor.
+    pub setup: fn(&mut Ctx) -> Instruction,
+}
+
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        program: "signer_authorization",
+        instruction: "initialize",
+        setup: |ctx| {
+            let authority = ctx.actor.pubkey();
+            ctx.idl
+                .ix("initialize")
+                .account("authority", authority)
+                .account("vault", ctx.pda(&[b"vault", authority.as_ref()]))
+                .build()
+        },
+    },
+    Fixture {
+        program: "signer_authorization",
+        instruction: "secure_withdraw",
+        setup: |ctx| {
+            let authority = ctx.actor.pubkey();
+            let vault = ctx.pda(&[b"vault", authority.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .account("authority", authority)
+                .account("vault", vault)
+                .build();
+            ctx.send(ix);
+            ctx.svm.airdrop(&vault, LAMPORTS_PER_SOL).unwrap();
+            ctx.idl
+                .ix("secure_withdraw")
+                .arg("amount", LAMPORTS_PER_SOL / 2)
+                .account("vault", vault)
+                .account("authority", authority)
+                .account("destination", authority)
+                .build()
+        },
+    },
+    Fixture {
+        program: "duplicate_accounts",
+        instruction: "initialize",
+        setup: |ctx| {
+            let user = ctx.actor.pubkey();
+            ctx.idl
+                .ix("initialize")
+                .arg("initial_balance", 1_000u64)
+                .account("user", user)
+                .account("user_account", ctx.pda(&[b"balance", user.as_ref()]))
+                .build()
+        },
+    },
+    Fixture {
+        program: "arithmetic_overflow",
+        instruction: "secure_swap",
+        setup: |ctx| {
+            let authority = ctx.actor.pubkey();
+            let pool = ctx.pda(&[b"pool", authority.as_ref()]);
+            let ix = ctx
+                .idl
+                .ix("initialize")
+                .arg("initial_x", 1_000_000u64)
+                .arg("initial_y", 1_000_000u64)
+                .arg("fee_bps", 30u16)
+                .account("authority", authority)
+                .account("pool", pool)
+                .build();
+            ctx.send(ix);
+            ctx.idl
+                .ix("secure_swap")
+                .arg("amount_in", 1_000u64)
+                .arg("min_out", 0u64)
+                .account("user", authority)
+                .account("pool", pool)
+                .build()
+        },
+    },
+    Fixture {
+        program: "account_griefing",
+        instruction: "secure_create_stake",
+        setup: |ctx| {
+            let user = ctx.actor.pubkey();
+            let nonce = 847_291u64;
+            let stake = ctx.pda(&[b"stake", user.as_ref(), &nonce.to_le_bytes()]);
+            ctx.idl
+                .ix("secure_create_stake")
+                .arg("nonce", nonce)
+                .account("user", user)
+                .account("stake_account", stake)
+                .build()
+        },
+    },
+    Fixture {
+        program: "pda_security",
+        instruction: "secure_create_user",
+        setup: |ctx| {
+            let user = ctx.actor.pubkey();
+            let nonce = 12_345u64;
+            let user_account = ctx.pda(&[b"user_v1", user.as_ref(), &nonce.to_le_bytes()]);
+            ctx.idl
+                .ix("secure_create_user")
+                .arg("nonce", nonce)
+                .account("user", user)
+                .account("user_account", user_account)
+                .build()
+        },
+    },
+    Fixture {
+        program: "owner_check",
+        instruction: "initialize_config",
+        setup: |ctx| {
+            let config = ctx.program_account(32);
+            ctx.idl
+                .ix("initialize_config")
+                .arg("admin", ctx.actor.pubkey())
+                .account("config", config)
+                .build()
+        },
+    },
+    Fixture {
+        program: "account_type_mismatch",
+        instruction: "init_user",
+        setup: |ctx| {
+            let account = ctx.program_account(48);
+            ctx.idl
+                .ix("init_user")
+                .arg("pubkey", ctx.actor.pubkey())
+                .account("account", account)
+                .build()
+        },
+    },
+];
+
+/// One change to the instruction data, applied in order. Offsets wrap
+/// around the current length.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Mutation {
+    /// Flip one bit of the discriminator
+    FlipDiscriminator {
+        bit: usize,
+    },
+    /// Put another instruction's discriminator in front of these arguments
+    SwapDiscriminator {
+        instruction: String,
+    },
+    /// Keep only the first `len` bytes
+    Truncate {
+        len: usize,
+    },
+    Append {
+        bytes: Vec<u8>,
+    },
+    /// Overwrite one byte after the discriminator
+    SetByte {
+        offset: usize,
+        value: u8,
+    },
+}
+
+/// One generated input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Case {
+    pub program: String,
+    pub instruction: String,
+    pub mutations: Vec<Mutation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The program panicked or ran out of compute
+    Crash { error: String, logs: Vec<String> },
+    /// Data starting with no known discriminator landed
+    UnknownLanded { data: Vec<u8> },
+    /// A handler other than the one the data's discriminator names ran;
+    /// `expected` is `None` when no instruction has that discriminator
+    WrongHandler {
+        expected: Option<String>,
+        ran: String,
+    },
+}
+
+struct Program {
+    svm: LiteSVM,
+    idl: Idl,
+    ix: Instruction,
+}
+
+/// Every fixture's state and instruction, built once; each case runs from
+/// a copy of its fixture's state.
+pub struct Harness {
+    programs: Vec<Program>,
+    actor: Keypair,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let actor = seeded_keypair(SEED, "alice");
+        let programs = FIXTURES
+            .iter()
+            .map(|fixture| {
+                let (mut svm, _) = common::setup(fixture.program);
+                svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL)
+                    .expect("Airdrop failed");
+                let idl = Idl::load(fixture.program);
+                let ix = (fixture.setup)(&mut Ctx {
+                    svm: &mut svm,
+                    program_id: common::load_program_id(fixture.program),
+                    idl: &idl,
+                    actor: &actor,
+                });
+                Program { svm, idl, ix }
+            })
+            .collect();
+        Self { programs, actor }
+    }
+
+    fn program(&self, case: &Case) -> &Program {
+        let i = FIXTURES
+            .iter()
+            .position(|f| f.program == case.program && f.instruction == case.instruction)
+            .unwrap_or_else(|| panic!("no fixture for {}::{}", case.program, case.instruction));
+        &self.programs[i]
+    }
+
+    /// Read a case out of fuzzer input.
+    pub fn decode(&self, data: &[u8]) -> Case {
+        let mut input = FuzzInput::new(data);
+        let i = input.choose(FIXTURES.len());
+        let idl = &self.programs[i].idl;
+        let count = 1 + input.choose(MAX_MUTATIONS);
+        let mutations = (0..count)
+            .map(|_| match input.choose(5) {
+                0 => Mutation::FlipDiscriminator {
+                    bit: input.u8() as usize,
+                },
+                1 => Mutation::SwapDiscriminator {
+                    instruction: idl.instructions[input.choose(idl.instructions.len())]
+                        .name
+                        .clone(),
+                },
+                2 => Mutation::Truncate {
+                    len: input.u8() as usize,
+                },
+                3 => Mutation::Append {
+                    bytes: (0..1 + input.choose(MAX_APPEND))
+                        .map(|_| input.u8())
+                        .collect(),
+                },
+                _ => Mutation::SetByte {
+                    offset: input.u8() as usize,
+                    value: input.u8(),
+                },
+            })
+            .collect();
+        Case {
+            program: FIXTURES[i].program.to_string(),
+            instruction: FIXTURES[i].instruction.to_string(),
+            mutations,
+        }
+    }
+
+    /// The fixture's instruction data with `case`'s mutations applied.
+    pub fn mutated(&self, case: &Case) -> Vec<u8> {
+        let program = self.program(case);
+        let mut data = program.ix.data.clone();
+        let discriminator_len = program
+            .idl
+            .instruction(&case.instruction)
+            .discriminator
+            .len();
+
+        for mutation in &case.mutations {
+            match mutation {
+                Mutation::FlipDiscriminator { bit } => {
+                    let bits = discriminator_len.min(data.len()) * 8;
+                    if bits > 0 {
+                        data[bit % bits / 8] ^= 1 << (bit % 8);
+                    }
+                }
+                Mutation::SwapDiscriminator { instruction } => {
+                    let other = &program.idl.instruction(instruction).discriminator;
+                    let args = data.get(discriminator_len..).unwrap_or_default().to_vec();
+                    data = [other.as_slice(), &args].concat();
+                }
+                Mutation::Truncate { len } => data.truncate(len % (data.len() + 1)),
+                Mutation::Append { bytes } => data.extend(bytes),
+                Mutation::SetByte { offset, value } => {
+                    if data.len() > discriminator_len {
+                        let args = data.len() - discriminator_len;
+                        data[discriminator_len + offset % args] = *value;
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    /// Run the mutated instruction of `case` and report what it broke.
+    pub fn run(&self, case: &Case) -> Option<Violation> {
+        let program = self.program(case);
+        let data = self.mutated(case);
+        let mut ix = program.ix.clone();
+        ix.data = data.clone();
+
+        let mut svm = program.svm.clone();
+        let msg = Message::new(&[ix], Some(&self.actor.pubkey()));
+        let tx = Transaction::new(&[&self.actor], msg, svm.latest_blockhash());
+        let (landed, logs) = match svm.send_transaction(tx) {
+            Ok(meta) => (true, meta.logs),
+            Err(failed) if corrupt::crashed(&failed) => {
+                return Some(Violation::Crash {
+                    error: format!("{:?}", failed.err),
+                    logs: failed.meta.logs,
+                });
+            }
+            Err(failed) => (false, failed.meta.logs),
+        };
+
+        let expected = program
+            .idl
+            .instructions
+            .iter()
+            .find(|i| data.starts_with(&i.discriminator))
+            .map(|i| i.name.clone());
+        if landed && expected.is_none() {
+            return Some(Violation::UnknownLanded { data });
+        }
+        let anchor = program
+            .idl
+            .instruction(&case.instruction)
+            .discriminator
+            .len()
+            == ANCHOR_DISCRIMINATOR_LEN;
+        let ran = logs
+            .iter()
+            .find_map(|l| l.strip_prefix("Program log: Instruction: "));
+        match ran {
+            Some(ran)
+                if anchor && expected.as_deref().map(pascal_case) != Some(ran.to_string()) =>
+            {
+                Some(Violation::WrongHandler {
+                    expected,
+                    ran: ran.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `secure_withdraw` -> `SecureWithdraw`, the name Anchor logs.
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+fn check(harness: &Harness, case: &Case) {
+    findings::attach_scenario(case);
+    if let Some(violation) = harness.run(case) {
+        panic!(
+            "{}::{} mishandled mutated data {:02x?}\n{:#?}\ncase: {:#?}",
+            case.program,
+            case.instruction,
+            harness.mutated(case),
+            violation,
+            case
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(TARGET, data, || check(&harness, &harness.decode(data)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{pseudo_random_input, replay_corpus};
+
+    fn case(program: &str, instruction: &str, mutations: Vec<Mutation>) -> Case {
+        Case {
+            program: program.to_string(),
+            instruction: instruction.to_string(),
+            mutations,
+        }
+    }
+
+    #[test]
+    fn test_pascal_case_matches_anchor_log_names() {
+        assert_eq!(pascal_case("secure_withdraw"), "SecureWithdraw");
+        assert_eq!(pascal_case("initialize"), "Initialize");
+        assert_eq!(pascal_case("init_user"), "InitUser");
+    }
+
+    #[test]
+    fn test_unmutated_fixtures_land() {
+        let harness = Harness::new();
+        for (fixture, program) in FIXTURES.iter().zip(&harness.programs) {
+            let mut svm = program.svm.clone();
+            let msg = Message::new(
+                std::slice::from_ref(&program.ix),
+                Some(&harness.actor.pubkey()),
+            );
+            let tx = Transaction::new(&[&harness.actor], msg, svm.latest_blockhash());
+            let result = svm.send_transaction(tx);
+            assert!(
+                result.is_ok(),
+                "{}::{}: {:?}",
+                fixture.program,
+                fixture.instruction,
+                result.err().map(|e| e.err)
+            );
+        }
+    }
+
+    #[test]
+    fn test_mutations_apply_in_order() {
+        let harness = Harness::new();
+        let original = &harness.programs[1].ix.data;
+        let case = case(
+            "signer_authorization",
+            "secure_withdraw",
+            vec![
+                Mutation::FlipDiscriminator { bit: 9 },
+                Mutation::Truncate { len: 10 },
+                Mutation::Append {
+                    bytes: vec![0xaa, 0xbb],
+                },
+                Mutation::SetByte {
+                    offset: 1,
+                    value: 7,
+                },
+            ],
+        );
+        let mut expected = original[..10].to_vec();
+        expected[1] ^= 0b10;
+        expected.extend([0xaa, 0xbb]);
+        expected[9] = 7;
+        assert_eq!(harness.mutated(&case), expected);
+    }
+
+    #[test]
+    fn test_swapped_discriminator_runs_the_named_handler() {
+        // secure_withdraw's amount behind vulnerable_withdraw's
+        // discriminator reaches vulnerable_withdraw, whatever it then does
+        let harness = Harness::new();
+        let case = case(
+            "signer_authorization",
+            "secure_withdraw",
+            vec![Mutation::SwapDiscriminator {
+                instruction: "vulnerable_withdraw".to_string(),
+            }],
+        );
+        assert_eq!(harness.run(&case), None);
+    }
+
+    #[test]
+    fn test_truncated_args_fail_cleanly() {
+        let harness = Harness::new();
+        for fixture in FIXTURES {
+            for len in [0, 1, 7, 8, 9] {
+                let case = case(
+                    fixture.program,
+                    fixture.instruction,
+                    vec![Mutation::Truncate { len }],
+                );
+                check(&harness, &case);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_mutations_are_handled() {
+        let harness = Harness::new();
+        for seed in 0..200 {
+            check(&harness, &harness.decode(&pseudo_random_input(seed, 64)));
+        }
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        let harness = Harness::new();
+        let replayed = replay_corpus(TARGET, |data| harness.run(&harness.decode(data)).is_some());
+        assert!(replayed > 0, "no corpus for {}", TARGET);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, &harness.decode(data));
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_ix_mutation.rs"));
+}
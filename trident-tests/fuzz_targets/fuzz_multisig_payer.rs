@@ -0,0 +1,485 @@
+//! Fuzz test for the DAO governance in the multisig-payer program
+//!
+//! Each input decodes to a sequence of `initialize`, `vulnerable_create_proposal`
+//! / `secure_create_proposal`, `vote` and treasury top-ups by three actors,
+//! with proposal ids from a small set so votes land on proposals that exist
+//! and creations collide.
+//!
+//! Invariants, checked after every action:
+//!
+//! > Treasury lamports never decrease without a passed proposal.
+//!
+//! > A proposal's tally only moves by one per landed vote, and never once it
+//! > is executed.
+//!
+//! The program has no `execute` instruction or per-voter vote records yet,
+//! so there is no way to pass a proposal: any treasury decrease is a
+//! violation, and "executed proposals are never executed twice" has nothing
+//! to run against. Add `Action::Execute` and that invariant here when the
+//! instruction lands.
+
+use std::collections::HashMap;
+
+use fuzz_targets::FuzzInput;
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::idl::{ArgValue, Idl};
+use security_tests::scenario::seeded_keypair;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const PROGRAM: &str = "multisig_payer";
+
+/// Actors are derived from this seed, so a crash input replays exactly
+const SEED: u64 = 1;
+
+const ACTORS: [&str; 3] = ["alice", "bob", "carol"];
+
+/// Longest action sequence read from one input
+const MAX_ACTIONS: usize = 16;
+
+/// Proposal ids both creators and voters pick from
+const PROPOSAL_IDS: [u64; 3] = [0, 1, u64::MAX];
+
+/// Titles up to this long are generated; the program accepts 64 bytes
+const MAX_TITLE: usize = 80;
+
+/// One step of a fuzzed sequence. Actor fields index [`ACTORS`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Initialize {
+        actor: usize,
+    },
+    CreateProposal {
+        secure: bool,
+        creator: usize,
+        /// Signs and pays rent on the secure path
+        rent_payer: usize,
+        id: u64,
+        title_len: usize,
+    },
+    Vote {
+        voter: usize,
+        id: u64,
+        approve: bool,
+    },
+    /// Airdrop into the treasury, so a drain has something to take
+    FundTreasury {
+        amount: u64,
+    },
+}
+
+impl Action {
+    fn decode(input: &mut FuzzInput) -> Self {
+        let actor = |input: &mut FuzzInput| input.choose(ACTORS.len());
+        let id = |input: &mut FuzzInput| PROPOSAL_IDS[input.choose(PROPOSAL_IDS.len())];
+        match input.choose(4) {
+            0 => Self::Initialize {
+                actor: actor(input),
+            },
+            1 => Self::CreateProposal {
+                secure: input.bool(),
+                creator: actor(input),
+                rent_payer: actor(input),
+                id: id(input),
+                title_len: input.choose(MAX_TITLE + 1),
+            },
+            2 => Self::Vote {
+                voter: actor(input),
+                id: id(input),
+                approve: input.bool(),
+            },
+            _ => Self::FundTreasury {
+                amount: input.amount(5 * LAMPORTS_PER_SOL),
+            },
+        }
+    }
+}
+
+/// Decode a whole input into at most [`MAX_ACTIONS`] actions.
+pub fn decode(data: &[u8]) -> Vec<Action> {
+    let mut input = FuzzInput::new(data);
+    let mut actions = Vec::new();
+    while !input.is_empty() && actions.len() < MAX_ACTIONS {
+        actions.push(Action::decode(&mut input));
+    }
+    actions
+}
+
+/// The invariant an action broke.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Broken {
+    TreasuryDecreased {
+        before: u64,
+        after: u64,
+    },
+    Tally {
+        id: u64,
+        /// `(yes, no)` counted from the votes that landed
+        expected: (u64, u64),
+        found: (u64, u64),
+    },
+    VotedAfterExecution {
+        id: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the offending action in the sequence
+    pub step: usize,
+    pub action: Action,
+    pub broken: Broken,
+}
+
+/// What a `Proposal` account holds that the invariants look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tally {
+    yes: u64,
+    no: u64,
+    executed: bool,
+}
+
+/// The program deployed and every actor funded; each run starts from a
+/// copy of this.
+pub struct Harness {
+    svm: LiteSVM,
+    program_id: Pubkey,
+    idl: Idl,
+    actors: Vec<Keypair>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let (mut svm, _) = common::setup(PROGRAM);
+        let actors: Vec<Keypair> = ACTORS
+            .iter()
+            .map(|label| seeded_keypair(SEED, label))
+            .collect();
+        for actor in &actors {
+            svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        Self {
+            svm,
+            program_id: common::load_program_id(PROGRAM),
+            idl: Idl::load(PROGRAM),
+            actors,
+        }
+    }
+
+    fn config(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"dao_config"], &self.program_id).0
+    }
+
+    fn treasury(&self) -> Pubkey {
+        let config = self.config();
+        Pubkey::find_program_address(&[b"treasury", config.as_ref()], &self.program_id).0
+    }
+
+    fn proposal(&self, id: u64) -> Pubkey {
+        let config = self.config();
+        Pubkey::find_program_address(
+            &[b"proposal", config.as_ref(), &id.to_le_bytes()],
+            &self.program_id,
+        )
+        .0
+    }
+
+    fn tally(&self, svm: &LiteSVM, id: u64) -> Option<Tally> {
+        let account = svm.get_account(&self.proposal(id))?;
+        let (name, fields) = self.idl.decode_account(&account.data)?;
+        if name != "Proposal" {
+            return None;
+        }
+        let field = |name: &str| fields.iter().find(|(n, _)| n == name).map(|(_, v)| v);
+        let count = |name: &str| match field(name) {
+            Some(ArgValue::UInt(n)) => *n as u64,
+            other => panic!("Proposal.{} decoded as {:?}", name, other),
+        };
+        Some(Tally {
+            yes: count("yes_votes"),
+            no: count("no_votes"),
+            executed: matches!(field("executed"), Some(ArgValue::Bool(true))),
+        })
+    }
+
+    /// Run `actions` from a fresh copy of the base state and return every
+    /// invariant violation. Failed transactions are expected and ignored.
+    pub fn run(&self, actions: &[Action]) -> Vec<Violation> {
+        self.play(actions).1
+    }
+
+    // `run`, also handing back the state the sequence ended in
+    fn play(&self, actions: &[Action]) -> (LiteSVM, Vec<Violation>) {
+        let mut svm = self.svm.clone();
+        let mut violations = Vec::new();
+        let treasury = self.treasury();
+        // Votes that landed on each proposal since it was created
+        let mut counted: HashMap<u64, (u64, u64)> = HashMap::new();
+
+        for (step, action) in actions.iter().enumerate() {
+            let treasury_before = svm.get_balance(&treasury).unwrap_or(0);
+            let mut broken = Vec::new();
+
+            match *action {
+                Action::Initialize { actor } => {
+                    let ix = self
+                        .idl
+                        .ix("initialize")
+                        .account("creator", self.actors[actor].pubkey())
+                        .account("config", self.config())
+                        .account("treasury", treasury)
+                        .build();
+                    let _ = svm.send_transaction(self.transaction(&svm, ix, &[actor]));
+                }
+                Action::CreateProposal {
+                    secure,
+                    creator,
+                    rent_payer,
+                    id,
+                    title_len,
+                } => {
+                    let title = "p".repeat(title_len);
+                    let (ix, signers) = if secure {
+                        let ix = self
+                            .idl
+                            .ix("secure_create_proposal")
+                            .arg("proposal_id", id)
+                            .arg("title", title)
+                            .account("rent_payer", self.actors[rent_payer].pubkey())
+                            .account("creator", self.actors[creator].pubkey())
+                            .account("treasury", treasury)
+                            .account("config", self.config())
+                            .account("proposal", self.proposal(id))
+                            .build();
+                        (ix, vec![rent_payer, creator])
+                    } else {
+                        let ix = self
+                            .idl
+                            .ix("vulnerable_create_proposal")
+                            .arg("proposal_id", id)
+                            .arg("title", title)
+                            .account("treasury", treasury)
+                            .account("config", self.config())
+                            .account("proposal", self.proposal(id))
+                            .account("creator", self.actors[creator].pubkey())
+                            .build();
+                        (ix, vec![creator])
+                    };
+                    let existed = self.tally(&svm, id).is_some();
+                    let landed = svm
+                        .send_transaction(self.transaction(&svm, ix, &signers))
+                        .is_ok();
+                    if landed && !existed {
+                        counted.insert(id, (0, 0));
+                    }
+                }
+                Action::Vote { voter, id, approve } => {
+                    let before = self.tally(&svm, id);
+                    let ix = self
+                        .idl
+                        .ix("vote")
+                        .arg("approve", approve)
+                        .account("voter", self.actors[voter].pubkey())
+                        .account("proposal", self.proposal(id))
+                        .build();
+                    let landed = svm
+                        .send_transaction(self.transaction(&svm, ix, &[voter]))
+                        .is_ok();
+                    if landed {
+                        if before.is_some_and(|t| t.executed) {
+                            broken.push(Broken::VotedAfterExecution { id });
+                        }
+                        let entry = counted.entry(id).or_default();
+                        if approve {
+                            entry.0 += 1;
+                        } else {
+                            entry.1 += 1;
+                        }
+                    }
+                }
+                Action::FundTreasury { amount } => {
+                    let _ = svm.airdrop(&treasury, amount.max(1));
+                }
+            }
+
+            let treasury_after = svm.get_balance(&treasury).unwrap_or(0);
+            if treasury_after < treasury_before {
+                broken.push(Broken::TreasuryDecreased {
+                    before: treasury_before,
+                    after: treasury_after,
+                });
+            }
+            for (&id, &expected) in &counted {
+                let found = self.tally(&svm, id).map_or((0, 0), |t| (t.yes, t.no));
+                if found != expected {
+                    broken.push(Broken::Tally {
+                        id,
+                        expected,
+                        found,
+                    });
+                }
+            }
+
+            violations.extend(broken.into_iter().map(|broken| Violation {
+                step,
+                action: action.clone(),
+                broken,
+            }));
+            svm.expire_blockhash();
+        }
+        (svm, violations)
+    }
+
+    fn transaction(
+        &self,
+        svm: &LiteSVM,
+        ix: solana_instruction::Instruction,
+        signers: &[usize],
+    ) -> Transaction {
+        let mut keypairs: Vec<&Keypair> = Vec::new();
+        for &i in signers {
+            if !keypairs
+                .iter()
+                .any(|k| k.pubkey() == self.actors[i].pubkey())
+            {
+                keypairs.push(&self.actors[i]);
+            }
+        }
+        let msg = Message::new(&[ix], Some(&keypairs[0].pubkey()));
+        Transaction::new(&keypairs, msg, svm.latest_blockhash())
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check(harness: &Harness, data: &[u8]) {
+    let actions = decode(data);
+    findings::attach_decoded(&actions);
+    if let Some(v) = harness.run(&actions).first() {
+        panic!(
+            "governance invariant broken at step {}: {:?}\nsequence: {:#?}",
+            v.step, v.broken, actions
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(PROGRAM, data, || check(&harness, data));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{corpus_dir, pseudo_random_input, replay_corpus};
+
+    const ALICE: usize = 0;
+    const BOB: usize = 1;
+    const CAROL: usize = 2;
+
+    fn create(secure: bool, id: u64) -> Action {
+        Action::CreateProposal {
+            secure,
+            creator: ALICE,
+            rent_payer: BOB,
+            id,
+            title_len: 13,
+        }
+    }
+
+    #[test]
+    fn test_decode_is_bounded_and_total() {
+        assert!(decode(&[]).is_empty());
+        for seed in 0..100 {
+            let actions = decode(&pseudo_random_input(seed, 512));
+            assert!(!actions.is_empty() && actions.len() <= MAX_ACTIONS);
+        }
+    }
+
+    #[test]
+    fn test_votes_are_tallied_once_each() {
+        let harness = Harness::new();
+        let actions = [
+            Action::Initialize { actor: ALICE },
+            create(true, 1),
+            Action::Vote {
+                voter: BOB,
+                id: 1,
+                approve: true,
+            },
+            Action::Vote {
+                voter: CAROL,
+                id: 1,
+                approve: false,
+            },
+        ];
+        let (svm, violations) = harness.play(&actions);
+        assert!(violations.is_empty(), "{:?}", violations);
+        let tally = harness.tally(&svm, 1).expect("proposal 1 exists");
+        assert_eq!((tally.yes, tally.no, tally.executed), (1, 1, false));
+    }
+
+    #[test]
+    fn test_vulnerable_create_leaves_treasury_alone() {
+        let actions = [
+            Action::Initialize { actor: ALICE },
+            Action::FundTreasury {
+                amount: 5 * LAMPORTS_PER_SOL,
+            },
+            create(false, 1),
+            create(false, 0),
+        ];
+        assert!(Harness::new().run(&actions).is_empty());
+    }
+
+    #[test]
+    fn test_random_sequences_hold() {
+        let harness = Harness::new();
+        for seed in 0..200 {
+            check(&harness, &pseudo_random_input(seed, 256));
+        }
+    }
+
+    #[test]
+    fn test_corpus_seed_decodes_to_double_vote() {
+        let seed = std::fs::read(corpus_dir(PROGRAM).join("double_vote")).unwrap();
+        let vote = Action::Vote {
+            voter: ALICE,
+            id: 1,
+            approve: true,
+        };
+        assert_eq!(decode(&seed)[3..], [vote.clone(), vote]);
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        let harness = Harness::new();
+        let replayed = replay_corpus(PROGRAM, |data| {
+            check(&harness, data);
+            false
+        });
+        assert!(replayed > 0, "no corpus for {}", PROGRAM);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, data);
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_multisig_payer.rs"));
+}
@@ -0,0 +1,714 @@
+//! Fuzz test for PDA seed construction across the programs
+//!
+//! Every PDA the programs derive is described by a [`Schema`]: the program,
+//! the domain (what the address holds) and the seeds, built from literals
+//! and identity parts (an owner's key, a nonce or id, a string). Each input
+//! decodes to a list of [`Tuple`]s, a schema plus random values for its
+//! identity parts, and two properties are checked:
+//!
+//! > No two logically distinct (program, domain, identity) tuples derive
+//! > the same address.
+//!
+//! Seeds are concatenated before hashing, so `[b"ab", s]` and `[b"a", "b" + s]`
+//! are the same address. A collision is always a crash.
+//!
+//! > The bump an instruction stores is the canonical one.
+//!
+//! Schemas with an [`Init`] are created through their instruction in LiteSVM
+//! and the `bump` field of the new account is compared with what
+//! `find_program_address` returns. A wrong bump stored by a `vulnerable_*`
+//! instruction is the bug being demonstrated and isn't reported; any other
+//! instruction storing one crashes the target.
+
+use std::collections::HashMap;
+
+use fuzz_targets::FuzzInput;
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::idl::{ArgValue, Idl};
+use security_tests::scenario::seeded_keypair;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const TARGET: &str = "pda_derivation";
+
+/// Owners are derived from this seed, so a crash input replays exactly
+const SEED: u64 = 1;
+
+/// Owners a key part picks from: few enough that tuples share them
+const OWNERS: usize = 8;
+
+/// Most tuples read from one input
+const MAX_TUPLES: usize = 16;
+
+/// Longest seed the runtime accepts
+const MAX_SEED_LEN: usize = 32;
+
+/// Bytes string parts are made of. A small alphabet makes one string the
+/// prefix of another often enough to matter.
+const ALPHABET: &[u8] = b"ab_";
+
+/// Nonces and ids picked more often than a uniform `u64` would: edges, the
+/// byte boundary, and the values the test suites use
+const NONCES: [u64; 7] = [0, 1, 255, 256, 12_345, 847_291, u64::MAX];
+
+/// One seed of a schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Lit(&'static [u8]),
+    /// An owner's key
+    Key,
+    /// A nonce or id, as little-endian bytes
+    U64,
+    /// A string of up to [`MAX_SEED_LEN`] bytes. No program seeds one yet;
+    /// the tests below do.
+    #[allow(dead_code)]
+    Str,
+}
+
+/// Where an [`Init`] argument comes from.
+#[derive(Debug, Clone, Copy)]
+pub enum Arg {
+    /// The identity's nonce or id
+    Nonce,
+    /// The signer's key
+    Signer,
+    Const(u64),
+}
+
+/// The instruction that creates a schema's account and stores its bump.
+#[derive(Debug)]
+pub struct Init {
+    pub instruction: &'static str,
+    /// Account slot of the signer and payer: the first key part's owner, or
+    /// the first owner when the seeds have no key
+    pub signer: &'static str,
+    /// Account slot of the PDA
+    pub account: &'static str,
+    pub args: &'static [(&'static str, Arg)],
+}
+
+/// How a program derives the addresses of one domain.
+#[derive(Debug)]
+pub struct Schema {
+    pub program: &'static str,
+    pub domain: &'static str,
+    pub seeds: &'static [Part],
+    /// `None` for accounts created as a side effect of another domain's
+    /// instruction, or with setup this target doesn't build
+    pub init: Option<Init>,
+}
+
+const fn init(
+    instruction: &'static str,
+    signer: &'static str,
+    account: &'static str,
+    args: &'static [(&'static str, Arg)],
+) -> Option<Init> {
+    Some(Init {
+        instruction,
+        signer,
+        account,
+        args,
+    })
+}
+
+/// Every PDA of the workspace's programs. The AMMs live in their own
+/// workspace and p-escrow takes its bump from the caller, with mints and
+/// token accounts to set up first, so neither is listed.
+pub const SCHEMAS: &[Schema] = &[
+    Schema {
+        program: "account_close",
+        domain: "user",
+        seeds: &[Part::Lit(b"user"), Part::Key],
+        init: init("initialize", "owner", "user_account", &[]),
+    },
+    Schema {
+        program: "account_reloading",
+        domain: "counter",
+        seeds: &[Part::Lit(b"counter"), Part::Key],
+        init: init("initialize", "authority", "counter", &[]),
+    },
+    Schema {
+        program: "arithmetic_overflow",
+        domain: "pool",
+        seeds: &[Part::Lit(b"pool"), Part::Key],
+        init: init(
+            "initialize",
+            "authority",
+            "pool",
+            &[
+                ("initial_x", Arg::Const(1_000_000)),
+                ("initial_y", Arg::Const(1_000_000)),
+                ("fee_bps", Arg::Const(30)),
+            ],
+        ),
+    },
+    Schema {
+        program: "duplicate_accounts",
+        domain: "balance",
+        seeds: &[Part::Lit(b"balance"), Part::Key],
+        init: init(
+            "initialize",
+            "user",
+            "user_account",
+            &[("initial_balance", Arg::Const(1_000))],
+        ),
+    },
+    Schema {
+        program: "signer_authorization",
+        domain: "vault",
+        seeds: &[Part::Lit(b"vault"), Part::Key],
+        init: init("initialize", "authority", "vault", &[]),
+    },
+    Schema {
+        program: "authority_transfer",
+        domain: "config",
+        seeds: &[Part::Lit(b"config")],
+        init: init("initialize", "authority", "config", &[]),
+    },
+    Schema {
+        program: "remaining_accounts",
+        domain: "config",
+        seeds: &[Part::Lit(b"config")],
+        init: init("initialize", "authority", "config", &[]),
+    },
+    Schema {
+        program: "insecure_init",
+        domain: "config",
+        seeds: &[Part::Lit(b"config")],
+        init: init(
+            "vulnerable_initialize",
+            "payer",
+            "config",
+            &[("admin", Arg::Signer)],
+        ),
+    },
+    Schema {
+        program: "insecure_init",
+        domain: "secure_config",
+        seeds: &[Part::Lit(b"secure_config")],
+        init: init(
+            "secure_initialize",
+            "payer",
+            "config",
+            &[("admin", Arg::Signer)],
+        ),
+    },
+    Schema {
+        program: "account_griefing",
+        domain: "stake",
+        seeds: &[Part::Lit(b"stake"), Part::Key],
+        init: init("vulnerable_create_stake", "user", "stake_account", &[]),
+    },
+    Schema {
+        program: "account_griefing",
+        domain: "stake",
+        seeds: &[Part::Lit(b"stake"), Part::Key, Part::U64],
+        init: init(
+            "secure_create_stake",
+            "user",
+            "stake_account",
+            &[("nonce", Arg::Nonce)],
+        ),
+    },
+    Schema {
+        program: "multisig_payer",
+        domain: "dao_config",
+        seeds: &[Part::Lit(b"dao_config")],
+        // Creates the config's treasury too, which the target doesn't derive
+        init: None,
+    },
+    Schema {
+        program: "multisig_payer",
+        domain: "treasury",
+        seeds: &[Part::Lit(b"treasury"), Part::Key],
+        init: None,
+    },
+    Schema {
+        program: "multisig_payer",
+        domain: "proposal",
+        seeds: &[Part::Lit(b"proposal"), Part::Key, Part::U64],
+        init: None,
+    },
+    Schema {
+        program: "pda_security",
+        domain: "weak_user",
+        seeds: &[Part::Key],
+        init: init("vulnerable_create_user", "user", "user_account", &[]),
+    },
+    Schema {
+        program: "pda_security",
+        domain: "user",
+        seeds: &[Part::Lit(b"user_v1"), Part::Key, Part::U64],
+        init: init(
+            "secure_create_user",
+            "user",
+            "user_account",
+            &[("nonce", Arg::Nonce)],
+        ),
+    },
+];
+
+/// The value of one identity part; literals have none.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// Index of the owner
+    Key(usize),
+    U64(u64),
+    Str(Vec<u8>),
+}
+
+/// A schema with values for its identity parts, in seed order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tuple {
+    pub schema: usize,
+    pub identity: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// Two logically distinct tuples derive `address`
+    Collision {
+        address: Pubkey,
+        first: Tuple,
+        second: Tuple,
+    },
+    /// The instruction creating `tuple` stored `stored` (`None` when the
+    /// account has no readable bump) instead of the canonical bump
+    StoredBump {
+        tuple: Tuple,
+        stored: Option<u8>,
+        canonical: u8,
+    },
+}
+
+/// The schemas with their program ids and the owners key parts pick from.
+pub struct Catalogue {
+    schemas: &'static [Schema],
+    program_ids: Vec<Pubkey>,
+    owners: Vec<Keypair>,
+}
+
+impl Catalogue {
+    /// `program_ids` holds the id of each schema's program, in order.
+    pub fn new(schemas: &'static [Schema], program_ids: Vec<Pubkey>) -> Self {
+        assert_eq!(schemas.len(), program_ids.len());
+        let owners = (0..OWNERS)
+            .map(|i| seeded_keypair(SEED, &format!("owner_{}", i)))
+            .collect();
+        Self {
+            schemas,
+            program_ids,
+            owners,
+        }
+    }
+
+    /// Read at most [`MAX_TUPLES`] tuples out of fuzzer input.
+    pub fn decode(&self, data: &[u8]) -> Vec<Tuple> {
+        let mut input = FuzzInput::new(data);
+        let mut tuples = Vec::new();
+        while !input.is_empty() && tuples.len() < MAX_TUPLES {
+            let schema = input.choose(self.schemas.len());
+            let identity = self.schemas[schema]
+                .seeds
+                .iter()
+                .filter_map(|part| match part {
+                    Part::Lit(_) => None,
+                    Part::Key => Some(Value::Key(input.choose(OWNERS))),
+                    Part::U64 if input.bool() => {
+                        Some(Value::U64(NONCES[input.choose(NONCES.len())]))
+                    }
+                    Part::U64 => Some(Value::U64(input.u64())),
+                    Part::Str => {
+                        let len = input.choose(MAX_SEED_LEN + 1);
+                        let s = (0..len)
+                            .map(|_| ALPHABET[input.choose(ALPHABET.len())])
+                            .collect();
+                        Some(Value::Str(s))
+                    }
+                })
+                .collect();
+            tuples.push(Tuple { schema, identity });
+        }
+        tuples
+    }
+
+    fn seeds(&self, tuple: &Tuple) -> Vec<Vec<u8>> {
+        let mut identity = tuple.identity.iter();
+        self.schemas[tuple.schema]
+            .seeds
+            .iter()
+            .map(|part| match part {
+                Part::Lit(bytes) => bytes.to_vec(),
+                _ => match identity.next() {
+                    Some(Value::Key(owner)) => self.owners[*owner].pubkey().to_bytes().to_vec(),
+                    Some(Value::U64(v)) => v.to_le_bytes().to_vec(),
+                    Some(Value::Str(s)) => s.clone(),
+                    None => panic!("{:?}: no value for {:?}", tuple, part),
+                },
+            })
+            .collect()
+    }
+
+    /// The address and canonical bump of `tuple`.
+    pub fn derive(&self, tuple: &Tuple) -> (Pubkey, u8) {
+        let seeds = self.seeds(tuple);
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        Pubkey::find_program_address(&seeds, &self.program_ids[tuple.schema])
+    }
+
+    /// What a tuple means: two tuples name the same account exactly when
+    /// these are equal.
+    fn logical<'a>(&self, tuple: &'a Tuple) -> (&'static str, &'static str, &'a [Value]) {
+        let schema = &self.schemas[tuple.schema];
+        (schema.program, schema.domain, &tuple.identity)
+    }
+
+    /// Every pair of distinct tuples in `tuples` that share an address.
+    pub fn collisions(&self, tuples: &[Tuple]) -> Vec<Violation> {
+        let mut seen: HashMap<Pubkey, &Tuple> = HashMap::new();
+        let mut violations = Vec::new();
+        for tuple in tuples {
+            let (address, _) = self.derive(tuple);
+            match seen.get(&address) {
+                Some(first) if self.logical(first) != self.logical(tuple) => {
+                    violations.push(Violation::Collision {
+                        address,
+                        first: (*first).clone(),
+                        second: tuple.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(address, tuple);
+                }
+            }
+        }
+        violations
+    }
+
+    fn signer(&self, tuple: &Tuple) -> &Keypair {
+        let owner = tuple.identity.iter().find_map(|value| match value {
+            Value::Key(owner) => Some(*owner),
+            _ => None,
+        });
+        &self.owners[owner.unwrap_or(0)]
+    }
+}
+
+/// Every program with an [`Init`] deployed in one LiteSVM with funded
+/// owners; each input runs from a copy of this.
+pub struct Harness {
+    catalogue: Catalogue,
+    svm: LiteSVM,
+    idls: HashMap<&'static str, Idl>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let program_ids = SCHEMAS
+            .iter()
+            .map(|schema| common::load_program_id(schema.program))
+            .collect();
+        let catalogue = Catalogue::new(SCHEMAS, program_ids);
+
+        let mut svm = LiteSVM::new();
+        let mut idls = HashMap::new();
+        for (schema, program_id) in SCHEMAS.iter().zip(&catalogue.program_ids) {
+            if schema.init.is_none() || idls.contains_key(schema.program) {
+                continue;
+            }
+            svm.add_program(*program_id, &common::load_program_bytes(schema.program));
+            idls.insert(schema.program, Idl::load(schema.program));
+        }
+        for owner in &catalogue.owners {
+            svm.airdrop(&owner.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        Self {
+            catalogue,
+            svm,
+            idls,
+        }
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Vec<Tuple> {
+        self.catalogue.decode(data)
+    }
+
+    /// Check both properties on `tuples`. Creations the program rejects,
+    /// like a second one for the same tuple, are skipped.
+    pub fn run(&self, tuples: &[Tuple]) -> Vec<Violation> {
+        let mut violations = self.catalogue.collisions(tuples);
+        let mut svm = self.svm.clone();
+
+        for tuple in tuples {
+            let schema = &SCHEMAS[tuple.schema];
+            let Some(init) = &schema.init else {
+                continue;
+            };
+            let (address, canonical) = self.catalogue.derive(tuple);
+            let signer = self.catalogue.signer(tuple);
+            let idl = &self.idls[schema.program];
+
+            let mut ix = idl
+                .ix(init.instruction)
+                .account(init.signer, signer.pubkey())
+                .account(init.account, address);
+            for (name, arg) in init.args {
+                let value: ArgValue = match *arg {
+                    Arg::Nonce => tuple
+                        .identity
+                        .iter()
+                        .find_map(|value| match value {
+                            Value::U64(v) => Some(ArgValue::from(*v)),
+                            _ => None,
+                        })
+                        .unwrap_or_else(|| panic!("{:?} has no nonce", tuple)),
+                    Arg::Signer => signer.pubkey().into(),
+                    Arg::Const(v) => v.into(),
+                };
+                ix = ix.arg(name, value);
+            }
+
+            let msg = Message::new(&[ix.build()], Some(&signer.pubkey()));
+            let tx = Transaction::new(&[signer], msg, svm.latest_blockhash());
+            let landed = svm.send_transaction(tx).is_ok();
+            svm.expire_blockhash();
+            if !landed {
+                continue;
+            }
+
+            let stored = svm
+                .get_account(&address)
+                .and_then(|account| idl.decode_account(&account.data))
+                .and_then(|(_, fields)| {
+                    fields.into_iter().find_map(|(name, value)| match value {
+                        ArgValue::UInt(bump) if name == "bump" => u8::try_from(bump).ok(),
+                        _ => None,
+                    })
+                });
+            if stored != Some(canonical) {
+                violations.push(Violation::StoredBump {
+                    tuple: tuple.clone(),
+                    stored,
+                    canonical,
+                });
+            }
+        }
+        violations
+    }
+
+    /// Whether `violation` is the bug a `vulnerable_*` instruction shows.
+    fn expected(violation: &Violation) -> bool {
+        match violation {
+            Violation::Collision { .. } => false,
+            Violation::StoredBump { tuple, .. } => SCHEMAS[tuple.schema]
+                .init
+                .as_ref()
+                .is_some_and(|init| init.instruction.starts_with("vulnerable_")),
+        }
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check(harness: &Harness, tuples: &[Tuple]) {
+    findings::attach_decoded(&tuples);
+    let violations: Vec<_> = harness
+        .run(tuples)
+        .into_iter()
+        .filter(|v| !Harness::expected(v))
+        .collect();
+    if let Some(v) = violations.first() {
+        panic!("{:#?}\ntuples: {:#?}", v, tuples);
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(TARGET, data, || check(&harness, &harness.decode(data)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{corpus_dir, pseudo_random_input, replay_corpus};
+
+    /// A prefix domain and a bare string in one program: `b"ab"` + `s` and
+    /// `b"a"` + `"b" + s` hash to the same address.
+    const AMBIGUOUS: &[Schema] = &[
+        Schema {
+            program: "ambiguous",
+            domain: "ab",
+            seeds: &[Part::Lit(b"ab"), Part::Str],
+            init: None,
+        },
+        Schema {
+            program: "ambiguous",
+            domain: "a",
+            seeds: &[Part::Lit(b"a"), Part::Str],
+            init: None,
+        },
+    ];
+
+    fn ambiguous() -> Catalogue {
+        let program_id = Pubkey::new_unique();
+        Catalogue::new(AMBIGUOUS, vec![program_id; AMBIGUOUS.len()])
+    }
+
+    // Program ids only matter for deriving, so the catalogue tests don't
+    // need a build
+    fn catalogue() -> Catalogue {
+        let ids: HashMap<&str, Pubkey> = SCHEMAS
+            .iter()
+            .map(|schema| (schema.program, Pubkey::new_unique()))
+            .collect();
+        Catalogue::new(SCHEMAS, SCHEMAS.iter().map(|s| ids[s.program]).collect())
+    }
+
+    /// Index of the schema `instruction` creates
+    fn schema(instruction: &str) -> usize {
+        SCHEMAS
+            .iter()
+            .position(|s| {
+                s.init
+                    .as_ref()
+                    .is_some_and(|i| i.instruction == instruction)
+            })
+            .unwrap()
+    }
+
+    fn str_tuple(schema: usize, s: &[u8]) -> Tuple {
+        Tuple {
+            schema,
+            identity: vec![Value::Str(s.to_vec())],
+        }
+    }
+
+    #[test]
+    fn test_decode_is_bounded_and_total() {
+        let catalogue = catalogue();
+        assert!(catalogue.decode(&[]).is_empty());
+        for seed in 0..100 {
+            let tuples = catalogue.decode(&pseudo_random_input(seed, 512));
+            assert!(!tuples.is_empty() && tuples.len() <= MAX_TUPLES);
+            for tuple in &tuples {
+                let parts = SCHEMAS[tuple.schema].seeds;
+                assert!(parts.len() - tuple.identity.len() <= 1);
+                catalogue.derive(tuple);
+            }
+        }
+    }
+
+    #[test]
+    fn test_schemas_never_collide() {
+        let catalogue = catalogue();
+        for seed in 0..300 {
+            let tuples = catalogue.decode(&pseudo_random_input(seed, 256));
+            assert_eq!(catalogue.collisions(&tuples), vec![], "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_same_tuple_twice_is_not_a_collision() {
+        let catalogue = catalogue();
+        let tuple = Tuple {
+            schema: schema("secure_create_stake"),
+            identity: vec![Value::Key(3), Value::U64(847_291)],
+        };
+        assert!(catalogue.collisions(&[tuple.clone(), tuple]).is_empty());
+    }
+
+    #[test]
+    fn test_corpus_covers_every_schema() {
+        let catalogue = catalogue();
+        let seed = std::fs::read(corpus_dir(TARGET).join("every_schema_one_owner")).unwrap();
+        let tuples = catalogue.decode(&seed);
+        let covered: Vec<usize> = tuples.iter().map(|t| t.schema).collect();
+        assert_eq!(covered, (0..SCHEMAS.len()).collect::<Vec<_>>());
+        assert!(tuples
+            .iter()
+            .flat_map(|t| &t.identity)
+            .all(|v| matches!(v, Value::Key(2) | Value::U64(847_291))));
+    }
+
+    #[test]
+    fn test_concatenated_seeds_collide() {
+        let catalogue = ambiguous();
+        let tuples = [str_tuple(0, b"_b"), str_tuple(1, b"b_b")];
+        let violations = catalogue.collisions(&tuples);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            &violations[0],
+            Violation::Collision { first, second, .. } if *first == tuples[0] && *second == tuples[1]
+        ));
+    }
+
+    #[test]
+    fn test_fuzzer_finds_concatenation_collisions() {
+        let catalogue = ambiguous();
+        let found = (0..2_000).any(|seed| {
+            let tuples = catalogue.decode(&pseudo_random_input(seed, 64));
+            !catalogue.collisions(&tuples).is_empty()
+        });
+        assert!(found, "no collision between the ambiguous schemas");
+    }
+
+    #[test]
+    fn test_stored_bumps_are_canonical() {
+        let harness = Harness::new();
+        for seed in 0..50 {
+            check(&harness, &harness.decode(&pseudo_random_input(seed, 256)));
+        }
+    }
+
+    #[test]
+    fn test_vulnerable_stake_stores_no_bump() {
+        // vulnerable_create_stake creates the account by hand and never
+        // writes it
+        let harness = Harness::new();
+        let tuple = Tuple {
+            schema: schema("vulnerable_create_stake"),
+            identity: vec![Value::Key(0)],
+        };
+        let violations = harness.run(std::slice::from_ref(&tuple));
+        assert!(matches!(
+            &violations[..],
+            [v @ Violation::StoredBump { stored: None, .. }] if Harness::expected(v)
+        ));
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        let harness = Harness::new();
+        let replayed = replay_corpus(TARGET, |data| {
+            harness
+                .run(&harness.decode(data))
+                .iter()
+                .any(|v| !Harness::expected(v))
+        });
+        assert!(replayed > 0, "no corpus for {}", TARGET);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, &harness.decode(data));
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_pda_derivation.rs"));
+}
@@ -0,0 +1,552 @@
+//! Fuzz test for privilege boundaries between roles
+//!
+//! Each input decodes to a sequence of actions by four roles (admin, user,
+//! attacker, keeper; see `fuzz_targets::roles`), which keep their keypairs
+//! for the whole sequence. It runs in one LiteSVM against insecure_init
+//! and authority_transfer: reinitializing either config, `admin_action`,
+//! and the one-step and two-step authority transfers.
+//!
+//! The keeper deploys both insecure_init configs naming the admin, and the
+//! admin initializes the authority-transfer config. After every step the
+//! target asks:
+//!
+//! > Did a role that doesn't hold a privilege gain an effect only its
+//! > holder may have?
+//!
+//! A privilege moves only when its holder signs the move (or, for
+//! `accept_authority`, the role the holder proposed). `admin_action`
+//! landing for another role, or a config naming another role, is an
+//! escalation. Reinitializing the `vulnerable_initialize` config is the
+//! bug being demonstrated, so escalations of that config are only counted;
+//! any other is a crash.
+
+use fuzz_targets::roles::{Escalation, Grants, Role, Roles};
+use fuzz_targets::FuzzInput;
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::idl::{ArgValue, Idl};
+use serde::{Deserialize, Serialize};
+use solana_instruction::Instruction;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+
+const TARGET: &str = "roles";
+
+const INSECURE_INIT: &str = "insecure_init";
+
+const AUTHORITY_TRANSFER: &str = "authority_transfer";
+
+/// Roles are derived from this seed, so a crash input replays exactly
+const SEED: u64 = 1;
+
+/// Longest action sequence read from one input
+const MAX_ACTIONS: usize = 16;
+
+/// Admin of the `vulnerable_initialize` config
+const CONFIG: &str = "insecure_init::config";
+
+/// Admin of the `secure_initialize` config
+const SECURE_CONFIG: &str = "insecure_init::secure_config";
+
+const AUTHORITY: &str = "authority_transfer::authority";
+
+/// The proposed authority, who may accept it
+const PENDING: &str = "authority_transfer::pending";
+
+const PRIVILEGES: [&str; 4] = [CONFIG, SECURE_CONFIG, AUTHORITY, PENDING];
+
+/// One step of a fuzzed sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// `vulnerable_initialize` / `secure_initialize` naming `admin`
+    Initialize {
+        secure: bool,
+        payer: Role,
+        admin: Role,
+    },
+    /// `admin_action` against either config
+    AdminAction { secure: bool, caller: Role },
+    /// `vulnerable_transfer`
+    Transfer { caller: Role, to: Role },
+    /// `propose_authority`
+    Propose { caller: Role, to: Role },
+    /// `accept_authority`
+    Accept { caller: Role },
+}
+
+impl Action {
+    fn decode(input: &mut FuzzInput) -> Self {
+        match input.choose(5) {
+            0 => Self::Initialize {
+                secure: input.bool(),
+                payer: Role::decode(input),
+                admin: Role::decode(input),
+            },
+            1 => Self::AdminAction {
+                secure: input.bool(),
+                caller: Role::decode(input),
+            },
+            2 => Self::Transfer {
+                caller: Role::decode(input),
+                to: Role::decode(input),
+            },
+            3 => Self::Propose {
+                caller: Role::decode(input),
+                to: Role::decode(input),
+            },
+            _ => Self::Accept {
+                caller: Role::decode(input),
+            },
+        }
+    }
+
+    /// The role that signs and pays for it
+    fn signer(&self) -> Role {
+        match *self {
+            Self::Initialize { payer, .. } => payer,
+            Self::AdminAction { caller, .. }
+            | Self::Transfer { caller, .. }
+            | Self::Propose { caller, .. }
+            | Self::Accept { caller } => caller,
+        }
+    }
+}
+
+/// Decode a whole input into at most [`MAX_ACTIONS`] actions.
+pub fn decode(data: &[u8]) -> Vec<Action> {
+    let mut input = FuzzInput::new(data);
+    let mut actions = Vec::new();
+    while !input.is_empty() && actions.len() < MAX_ACTIONS {
+        actions.push(Action::decode(&mut input));
+    }
+    actions
+}
+
+fn config_privilege(secure: bool) -> &'static str {
+    if secure {
+        SECURE_CONFIG
+    } else {
+        CONFIG
+    }
+}
+
+/// An action that left a privilege's effect with a role it wasn't granted
+/// to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// Index of the offending action in the sequence
+    pub step: usize,
+    pub action: Action,
+    pub escalation: Escalation,
+}
+
+/// Both programs deployed, every role funded and the configs initialized;
+/// each run starts from a copy of this.
+pub struct Harness {
+    svm: LiteSVM,
+    insecure_init: Idl,
+    authority_transfer: Idl,
+    roles: Roles,
+    grants: Grants,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let (mut svm, _) = common::setup(INSECURE_INIT);
+        svm.add_program(
+            common::load_program_id(AUTHORITY_TRANSFER),
+            &common::load_program_bytes(AUTHORITY_TRANSFER),
+        );
+        let roles = Roles::new(SEED);
+        for role in Role::ALL {
+            svm.airdrop(&roles.pubkey(role), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+
+        let mut harness = Self {
+            svm,
+            insecure_init: Idl::load(INSECURE_INIT),
+            authority_transfer: Idl::load(AUTHORITY_TRANSFER),
+            roles,
+            grants: Grants::default(),
+        };
+        for secure in [false, true] {
+            harness.setup(Action::Initialize {
+                secure,
+                payer: Role::Keeper,
+                admin: Role::Admin,
+            });
+        }
+        let initialize = harness
+            .authority_transfer
+            .ix("initialize")
+            .account("authority", harness.roles.pubkey(Role::Admin))
+            .account("config", harness.authority_config())
+            .build();
+        harness.send(initialize, Role::Admin);
+
+        for privilege in [CONFIG, SECURE_CONFIG, AUTHORITY] {
+            harness.grants.grant(privilege, Role::Admin);
+        }
+        harness
+    }
+
+    fn setup(&mut self, action: Action) {
+        let ix = self.instruction(&action);
+        self.send(ix, action.signer());
+    }
+
+    fn send(&mut self, ix: Instruction, signer: Role) {
+        let tx = self.transaction(&self.svm, ix, signer);
+        self.svm
+            .send_transaction(tx)
+            .expect("Setup transaction failed");
+        self.svm.expire_blockhash();
+    }
+
+    fn insecure_config(&self, secure: bool) -> Pubkey {
+        let seed: &[u8] = if secure { b"secure_config" } else { b"config" };
+        Pubkey::find_program_address(&[seed], &self.insecure_init.program_id).0
+    }
+
+    fn authority_config(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"config"], &self.authority_transfer.program_id).0
+    }
+
+    fn instruction(&self, action: &Action) -> Instruction {
+        let key = |role| self.roles.pubkey(role);
+        match *action {
+            Action::Initialize {
+                secure,
+                payer,
+                admin,
+            } => {
+                let name = if secure {
+                    "secure_initialize"
+                } else {
+                    "vulnerable_initialize"
+                };
+                self.insecure_init
+                    .ix(name)
+                    .arg("admin", key(admin))
+                    .account("payer", key(payer))
+                    .account("config", self.insecure_config(secure))
+                    .build()
+            }
+            Action::AdminAction { secure, caller } => self
+                .insecure_init
+                .ix("admin_action")
+                .account("config", self.insecure_config(secure))
+                .account("admin", key(caller))
+                .build(),
+            Action::Transfer { caller, to } | Action::Propose { caller, to } => {
+                let name = if matches!(action, Action::Transfer { .. }) {
+                    "vulnerable_transfer"
+                } else {
+                    "propose_authority"
+                };
+                self.authority_transfer
+                    .ix(name)
+                    .arg("new_authority", key(to))
+                    .account("authority", key(caller))
+                    .account("config", self.authority_config())
+                    .build()
+            }
+            Action::Accept { caller } => self
+                .authority_transfer
+                .ix("accept_authority")
+                .account("new_authority", key(caller))
+                .account("config", self.authority_config())
+                .build(),
+        }
+    }
+
+    // Pubkey field `name` of the account at `address`, if it is set
+    fn stored_key(svm: &LiteSVM, idl: &Idl, address: &Pubkey, name: &str) -> Option<Pubkey> {
+        let account = svm.get_account(address)?;
+        let (_, fields) = idl.decode_account(&account.data)?;
+        fields
+            .into_iter()
+            .find_map(|(field, value)| match (field == name, value) {
+                (true, ArgValue::Pubkey(key)) => Some(key),
+                (true, ArgValue::Option(Some(value))) => match *value {
+                    ArgValue::Pubkey(key) => Some(key),
+                    _ => None,
+                },
+                _ => None,
+            })
+    }
+
+    /// Who holds each of [`PRIVILEGES`] on-chain: `None` when it is unset,
+    /// `Some(None)` for a key no role has.
+    pub fn holders(&self, svm: &LiteSVM) -> [Option<Option<Role>>; 4] {
+        let config = self.authority_config();
+        [
+            Self::stored_key(
+                svm,
+                &self.insecure_init,
+                &self.insecure_config(false),
+                "admin",
+            ),
+            Self::stored_key(
+                svm,
+                &self.insecure_init,
+                &self.insecure_config(true),
+                "admin",
+            ),
+            Self::stored_key(svm, &self.authority_transfer, &config, "authority"),
+            Self::stored_key(svm, &self.authority_transfer, &config, "pending_authority"),
+        ]
+        .map(|key| key.map(|key| self.roles.role_of(&key)))
+    }
+
+    /// Move whatever `action`, which landed, legitimately moves: only the
+    /// holder of a privilege hands it on.
+    fn grant(grants: &mut Grants, action: &Action) {
+        match *action {
+            Action::Initialize {
+                secure,
+                payer,
+                admin,
+            } => {
+                let privilege = config_privilege(secure);
+                if grants.holds(privilege, payer) {
+                    grants.grant(privilege, admin);
+                }
+            }
+            Action::AdminAction { .. } => {}
+            Action::Transfer { caller, to } => {
+                if grants.holds(AUTHORITY, caller) {
+                    grants.grant(AUTHORITY, to);
+                }
+            }
+            Action::Propose { caller, to } => {
+                if grants.holds(AUTHORITY, caller) {
+                    grants.grant(PENDING, to);
+                }
+            }
+            Action::Accept { caller } => {
+                if grants.holds(PENDING, caller) {
+                    grants.grant(AUTHORITY, caller);
+                    grants.revoke(PENDING);
+                }
+            }
+        }
+    }
+
+    /// Whether `violation` is the insecure_init bug being demonstrated
+    pub fn expected(violation: &Violation) -> bool {
+        violation.escalation.privilege == CONFIG
+    }
+
+    /// Run `actions` from a fresh copy of the base state and return every
+    /// escalation. Failed transactions are expected and ignored.
+    pub fn run(&self, actions: &[Action]) -> Vec<Violation> {
+        let mut svm = self.svm.clone();
+        let mut grants = self.grants.clone();
+        let mut violations = Vec::new();
+
+        for (step, action) in actions.iter().enumerate() {
+            let before = self.holders(&svm);
+            let tx = self.transaction(&svm, self.instruction(action), action.signer());
+            let landed = svm.send_transaction(tx).is_ok();
+            svm.expire_blockhash();
+            if !landed {
+                continue;
+            }
+
+            if let Action::AdminAction { secure, caller } = *action {
+                let privilege = config_privilege(secure);
+                if !grants.holds(privilege, caller) {
+                    violations.push(Violation {
+                        step,
+                        action: *action,
+                        escalation: Escalation {
+                            privilege,
+                            granted: grants.holder(privilege),
+                            holder: Some(caller),
+                        },
+                    });
+                }
+            }
+
+            // A privilege taken earlier stays taken; report it once, when
+            // it moves
+            Self::grant(&mut grants, action);
+            let after = self.holders(&svm);
+            for (i, privilege) in PRIVILEGES.into_iter().enumerate() {
+                if after[i] == before[i] {
+                    continue;
+                }
+                if let Some(escalation) = grants.escalation(privilege, after[i]) {
+                    violations.push(Violation {
+                        step,
+                        action: *action,
+                        escalation,
+                    });
+                }
+            }
+        }
+        violations
+    }
+
+    fn transaction(&self, svm: &LiteSVM, ix: Instruction, signer: Role) -> Transaction {
+        let keypair = self.roles.keypair(signer);
+        let msg = Message::new(&[ix], Some(&self.roles.pubkey(signer)));
+        Transaction::new(&[keypair], msg, svm.latest_blockhash())
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check(harness: &Harness, actions: &[Action]) {
+    findings::attach_scenario(&actions);
+    let violations = harness.run(actions);
+    if let Some(v) = violations.iter().find(|v| !Harness::expected(v)) {
+        panic!(
+            "{:?} gained {} (granted to {:?}) at step {}\nsequence: {:#?}",
+            v.escalation.holder, v.escalation.privilege, v.escalation.granted, v.step, actions
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(TARGET, data, || check(&harness, &decode(data)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{pseudo_random_input, replay_corpus};
+
+    #[test]
+    fn test_decode_is_bounded_and_total() {
+        assert!(decode(&[]).is_empty());
+        for seed in 0..100 {
+            let actions = decode(&pseudo_random_input(seed, 512));
+            assert!(!actions.is_empty() && actions.len() <= MAX_ACTIONS);
+        }
+        assert_eq!(
+            decode(&[4, 2]),
+            vec![Action::Accept {
+                caller: Role::Attacker
+            }]
+        );
+    }
+
+    #[test]
+    fn test_grants_follow_only_the_holder() {
+        let mut grants = Grants::default();
+        grants.grant(AUTHORITY, Role::Admin);
+
+        let propose = |caller, to| Action::Propose { caller, to };
+        Harness::grant(&mut grants, &propose(Role::Attacker, Role::Attacker));
+        assert_eq!(grants.holder(PENDING), None);
+        Harness::grant(&mut grants, &propose(Role::Admin, Role::User));
+        Harness::grant(
+            &mut grants,
+            &Action::Accept {
+                caller: Role::Attacker,
+            },
+        );
+        assert!(grants.holds(AUTHORITY, Role::Admin));
+        Harness::grant(&mut grants, &Action::Accept { caller: Role::User });
+        assert!(grants.holds(AUTHORITY, Role::User));
+        assert_eq!(grants.holder(PENDING), None);
+    }
+
+    #[test]
+    fn test_attacker_reinitializing_the_vulnerable_config_is_expected() {
+        let harness = Harness::new();
+        let actions = [
+            Action::Initialize {
+                secure: false,
+                payer: Role::Attacker,
+                admin: Role::Attacker,
+            },
+            Action::AdminAction {
+                secure: false,
+                caller: Role::Attacker,
+            },
+        ];
+        let violations = harness.run(&actions);
+        assert_eq!(violations.len(), 2, "{:#?}", violations);
+        assert!(violations.iter().all(Harness::expected));
+        assert_eq!(violations[0].escalation.holder, Some(Role::Attacker));
+        assert_eq!(violations[1].step, 1);
+    }
+
+    #[test]
+    fn test_secure_privileges_stay_with_their_holders() {
+        let harness = Harness::new();
+        let mut actions = vec![Action::Initialize {
+            secure: true,
+            payer: Role::Attacker,
+            admin: Role::Attacker,
+        }];
+        for caller in [Role::User, Role::Attacker, Role::Keeper] {
+            actions.extend([
+                Action::AdminAction {
+                    secure: true,
+                    caller,
+                },
+                Action::Transfer { caller, to: caller },
+                Action::Propose { caller, to: caller },
+                Action::Accept { caller },
+            ]);
+        }
+        assert_eq!(harness.run(&actions), vec![]);
+    }
+
+    #[test]
+    fn test_two_step_transfer_hands_over_authority() {
+        let harness = Harness::new();
+        let actions = [
+            Action::Propose {
+                caller: Role::Admin,
+                to: Role::User,
+            },
+            Action::Accept {
+                caller: Role::Attacker,
+            },
+            Action::Accept { caller: Role::User },
+            // The old admin no longer holds it
+            Action::Transfer {
+                caller: Role::Admin,
+                to: Role::Attacker,
+            },
+        ];
+        assert_eq!(harness.run(&actions), vec![]);
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        let harness = Harness::new();
+        let replayed = replay_corpus(TARGET, |data| {
+            harness
+                .run(&decode(data))
+                .iter()
+                .any(|v| !Harness::expected(v))
+        });
+        assert!(replayed > 0, "no corpus for {}", TARGET);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, &decode(data));
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/findings_roles.rs"));
+}
@@ -0,0 +1,455 @@
+//! Fuzz test for the Signer Authorization vulnerability
+//!
+//! Unlike `fuzz_arithmetic`, this target runs the deployed program: every
+//! input decodes to a sequence of vault actions by three actors (initialize,
+//! deposit, fund, and both withdraw instructions with random vault,
+//! authority, signer and destination accounts), which is executed in
+//! LiteSVM against `target/deploy/signer_authorization.so`.
+//!
+//! After each landed withdraw the target checks one invariant:
+//!
+//! > A vault's balance only goes down when its stored authority signed.
+//!
+//! The vulnerable instruction breaks it as soon as the fuzzer passes the
+//! authority's key without their signature; those findings are expected and
+//! only counted. A violation through `secure_withdraw` is a crash.
+//!
+//! Amounts come from `FuzzInput::lamports` around the vault's rent-exempt
+//! minimum, so funds and withdrawals land exactly on it and one lamport to
+//! either side. No vault may ever be left holding less than that minimum;
+//! one that is crashes the target whichever instruction left it there.
+
+use fuzz_targets::{rent_collectable, rent_exempt, FuzzInput};
+use litesvm::LiteSVM;
+use security_fuzz::findings;
+use security_tests::common;
+use security_tests::idl::Idl;
+use security_tests::scenario::seeded_keypair;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const PROGRAM: &str = "signer_authorization";
+
+/// Actors are derived from this seed, so a crash input replays exactly
+const SEED: u64 = 1;
+
+const ACTORS: [&str; 3] = ["alice", "bob", "mallory"];
+
+/// Longest action sequence read from one input
+const MAX_ACTIONS: usize = 16;
+
+/// Discriminator, authority and bump
+const VAULT_SPACE: usize = 8 + 32 + 1;
+
+/// One step of a fuzzed sequence. Actor fields index [`ACTORS`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Initialize {
+        actor: usize,
+    },
+    Deposit {
+        actor: usize,
+        amount: u64,
+    },
+    /// Airdrop into `owner`'s vault address, so withdrawals have something
+    /// to take
+    Fund {
+        owner: usize,
+        amount: u64,
+    },
+    Withdraw {
+        secure: bool,
+        /// Whose vault PDA is passed
+        vault: usize,
+        /// Whose key is passed as `authority`
+        authority: usize,
+        /// Pays for and signs the transaction
+        signer: usize,
+        /// Whether `authority` co-signs when it isn't the signer
+        authority_signs: bool,
+        destination: usize,
+        amount: u64,
+    },
+}
+
+impl Action {
+    fn decode(input: &mut FuzzInput) -> Self {
+        let actor = |input: &mut FuzzInput| input.choose(ACTORS.len());
+        let rent = rent_exempt(VAULT_SPACE);
+        match input.choose(4) {
+            0 => Self::Initialize {
+                actor: actor(input),
+            },
+            1 => Self::Deposit {
+                actor: actor(input),
+                amount: input.lamports(rent, 2 * LAMPORTS_PER_SOL),
+            },
+            2 => Self::Fund {
+                owner: actor(input),
+                amount: input.lamports(rent, 5 * LAMPORTS_PER_SOL),
+            },
+            _ => Self::Withdraw {
+                secure: input.bool(),
+                vault: actor(input),
+                authority: actor(input),
+                signer: actor(input),
+                authority_signs: input.bool(),
+                destination: actor(input),
+                amount: input.lamports(rent, 5 * LAMPORTS_PER_SOL),
+            },
+        }
+    }
+}
+
+/// Decode a whole input into at most [`MAX_ACTIONS`] actions.
+pub fn decode(data: &[u8]) -> Vec<Action> {
+    let mut input = FuzzInput::new(data);
+    let mut actions = Vec::new();
+    while !input.is_empty() && actions.len() < MAX_ACTIONS {
+        actions.push(Action::decode(&mut input));
+    }
+    actions
+}
+
+/// A withdraw that lowered a vault's balance without its authority's
+/// signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the offending action in the sequence
+    pub step: usize,
+    pub action: Action,
+    pub vault_before: u64,
+    pub vault_after: u64,
+}
+
+/// The program deployed and every actor funded; each run starts from a
+/// copy of this.
+pub struct Harness {
+    svm: LiteSVM,
+    program_id: Pubkey,
+    idl: Idl,
+    actors: Vec<Keypair>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let (mut svm, _) = common::setup(PROGRAM);
+        let actors: Vec<Keypair> = ACTORS
+            .iter()
+            .map(|label| seeded_keypair(SEED, label))
+            .collect();
+        for actor in &actors {
+            svm.airdrop(&actor.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .expect("Airdrop failed");
+        }
+        Self {
+            svm,
+            program_id: common::load_program_id(PROGRAM),
+            idl: Idl::load(PROGRAM),
+            actors,
+        }
+    }
+
+    fn vault(&self, owner: usize) -> Pubkey {
+        let authority = self.actors[owner].pubkey();
+        Pubkey::find_program_address(&[b"vault", authority.as_ref()], &self.program_id).0
+    }
+
+    // Authority stored in the vault at `vault`, if it holds one
+    fn stored_authority(svm: &LiteSVM, vault: &Pubkey) -> Option<Pubkey> {
+        let account = svm.get_account(vault)?;
+        let key: [u8; 32] = account.data.get(8..40)?.try_into().ok()?;
+        Some(Pubkey::new_from_array(key))
+    }
+
+    /// Run `actions` from a fresh copy of the base state and return every
+    /// invariant violation. Failed transactions are expected and ignored.
+    pub fn run(&self, actions: &[Action]) -> Vec<Violation> {
+        let mut svm = self.svm.clone();
+        let mut violations = Vec::new();
+
+        for (step, action) in actions.iter().enumerate() {
+            match *action {
+                Action::Initialize { actor } => {
+                    let ix = self
+                        .idl
+                        .ix("initialize")
+                        .account("authority", self.actors[actor].pubkey())
+                        .account("vault", self.vault(actor))
+                        .build();
+                    let _ = svm.send_transaction(self.transaction(&svm, ix, actor, &[]));
+                }
+                Action::Deposit { actor, amount } => {
+                    let ix = self
+                        .idl
+                        .ix("deposit")
+                        .arg("amount", amount)
+                        .account("authority", self.actors[actor].pubkey())
+                        .account("vault", self.vault(actor))
+                        .build();
+                    let _ = svm.send_transaction(self.transaction(&svm, ix, actor, &[]));
+                }
+                Action::Fund { owner, amount } => {
+                    let _ = svm.airdrop(&self.vault(owner), amount.max(1));
+                }
+                Action::Withdraw {
+                    secure,
+                    vault,
+                    authority,
+                    signer,
+                    authority_signs,
+                    destination,
+                    amount,
+                } => {
+                    let vault = self.vault(vault);
+                    let authority_key = self.actors[authority].pubkey();
+                    let instruction = if secure {
+                        "secure_withdraw"
+                    } else {
+                        "vulnerable_withdraw"
+                    };
+                    let mut ix = self
+                        .idl
+                        .ix(instruction)
+                        .arg("amount", amount)
+                        .account("vault", vault)
+                        .account("authority", authority_key)
+                        .account("destination", self.actors[destination].pubkey())
+                        .build();
+
+                    // The signature is the fuzzed permission, whatever the
+                    // instruction declares
+                    let authority_signed = authority == signer || authority_signs;
+                    ix.accounts
+                        .iter_mut()
+                        .filter(|meta| meta.pubkey == authority_key)
+                        .for_each(|meta| meta.is_signer = authority_signed);
+                    let co_signers: &[usize] = if authority != signer && authority_signed {
+                        &[authority]
+                    } else {
+                        &[]
+                    };
+
+                    let stored = Self::stored_authority(&svm, &vault);
+                    let before = svm.get_balance(&vault).unwrap_or(0);
+                    let tx = self.transaction(&svm, ix, signer, co_signers);
+                    let signed = tx.message.account_keys[..tx.signatures.len()].to_vec();
+                    if svm.send_transaction(tx).is_err() {
+                        continue;
+                    }
+                    let after = svm.get_balance(&vault).unwrap_or(0);
+
+                    let authorized = stored.is_some_and(|stored| signed.contains(&stored));
+                    if after < before && !authorized {
+                        violations.push(Violation {
+                            step,
+                            action: action.clone(),
+                            vault_before: before,
+                            vault_after: after,
+                        });
+                    }
+                }
+            }
+            let vaults = (0..ACTORS.len()).map(|owner| self.vault(owner));
+            if let Some(account) = rent_collectable(&svm, vaults) {
+                panic!(
+                    "step {} left a vault below its rent-exempt minimum: {:?}\naction: {:?}",
+                    step, account, action
+                );
+            }
+            svm.expire_blockhash();
+        }
+        violations
+    }
+
+    fn transaction(
+        &self,
+        svm: &LiteSVM,
+        ix: solana_instruction::Instruction,
+        payer: usize,
+        co_signers: &[usize],
+    ) -> Transaction {
+        let mut signers = vec![&self.actors[payer]];
+        signers.extend(co_signers.iter().map(|&i| &self.actors[i]));
+        let msg = Message::new(&[ix], Some(&self.actors[payer].pubkey()));
+        Transaction::new(&signers, msg, svm.latest_blockhash())
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check(harness: &Harness, data: &[u8]) {
+    let actions = decode(data);
+    findings::attach_decoded(&actions);
+    let violations = harness.run(&actions);
+    if let Some(v) = violations
+        .iter()
+        .find(|v| matches!(v.action, Action::Withdraw { secure: true, .. }))
+    {
+        panic!(
+            "secure_withdraw drained a vault without its authority's signature \
+             at step {} ({} -> {} lamports)\nsequence: {:#?}",
+            v.step, v.vault_before, v.vault_after, actions
+        );
+    }
+}
+
+fn main() {
+    let harness = Harness::new();
+    loop {
+        honggfuzz::fuzz!(|data: &[u8]| {
+            findings::capture(PROGRAM, data, || check(&harness, data));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzz_targets::{corpus_dir, pseudo_random_input, replay_corpus};
+
+    const ALICE: usize = 0;
+    const MALLORY: usize = 2;
+
+    // Mallory withdraws from Alice's funded vault, passing Alice's key
+    // without her signature
+    fn exploit(secure: bool) -> Vec<Action> {
+        vec![
+            Action::Initialize { actor: ALICE },
+            Action::Fund {
+                owner: ALICE,
+                amount: 5 * LAMPORTS_PER_SOL,
+            },
+            Action::Withdraw {
+                secure,
+                vault: ALICE,
+                authority: ALICE,
+                signer: MALLORY,
+                authority_signs: false,
+                destination: MALLORY,
+                amount: LAMPORTS_PER_SOL,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_decode_is_bounded_and_total() {
+        assert!(decode(&[]).is_empty());
+        for seed in 0..100 {
+            let actions = decode(&pseudo_random_input(seed, 512));
+            assert!(!actions.is_empty() && actions.len() <= MAX_ACTIONS);
+        }
+    }
+
+    #[test]
+    fn test_vulnerable_exploit_sequence_is_flagged() {
+        let violations = Harness::new().run(&exploit(false));
+        assert_eq!(violations.len(), 1, "{:?}", violations);
+        assert_eq!(violations[0].step, 2);
+        assert_eq!(
+            violations[0].vault_before - violations[0].vault_after,
+            LAMPORTS_PER_SOL
+        );
+    }
+
+    #[test]
+    fn test_secure_exploit_sequence_holds() {
+        assert!(Harness::new().run(&exploit(true)).is_empty());
+    }
+
+    #[test]
+    fn test_authority_withdraw_is_not_flagged() {
+        let mut actions = exploit(true);
+        actions[2] = Action::Withdraw {
+            secure: true,
+            vault: ALICE,
+            authority: ALICE,
+            signer: MALLORY,
+            authority_signs: true,
+            destination: MALLORY,
+            amount: LAMPORTS_PER_SOL,
+        };
+        assert!(Harness::new().run(&actions).is_empty());
+    }
+
+    #[test]
+    fn test_withdrawals_at_the_rent_boundary_keep_vaults_exempt() {
+        // The vault holds its minimum plus `rent` after funding, so these
+        // leave it one lamport above, at, and one below the minimum
+        let rent = rent_exempt(VAULT_SPACE);
+        let harness = Harness::new();
+        for amount in [rent - 1, rent, rent + 1] {
+            let actions = [
+                Action::Initialize { actor: ALICE },
+                Action::Fund {
+                    owner: ALICE,
+                    amount: rent,
+                },
+                Action::Withdraw {
+                    secure: true,
+                    vault: ALICE,
+                    authority: ALICE,
+                    signer: ALICE,
+                    authority_signs: false,
+                    destination: ALICE,
+                    amount,
+                },
+            ];
+            assert!(harness.run(&actions).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_random_sequences_hold_for_secure_withdraw() {
+        let harness = Harness::new();
+        for seed in 0..200 {
+            check(&harness, &pseudo_random_input(seed, 256));
+        }
+    }
+
+    #[test]
+    fn test_corpus_seed_decodes_to_exploit() {
+        let seed = std::fs::read(corpus_dir(PROGRAM).join("unsigned_authority_withdraw")).unwrap();
+        let actions = decode(&seed);
+        assert_eq!(actions[0], Action::Initialize { actor: ALICE });
+        assert!(matches!(
+            actions[2],
+            Action::Withdraw {
+                secure: true,
+                vault: ALICE,
+                authority: ALICE,
+                signer: MALLORY,
+                authority_signs: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_corpus_replays() {
+        let harness = Harness::new();
+        let replayed = replay_corpus(PROGRAM, |data| {
+            check(&harness, data);
+            false
+        });
+        assert!(replayed > 0, "no corpus for {}", PROGRAM);
+    }
+
+    fn replay_finding(data: &[u8]) {
+        let harness = Harness::new();
+        check(&harness, data);
+    }
+
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/findings_signer_authorization.rs"
+    ));
+}
@@ -0,0 +1,399 @@
+//! Shared pieces of the fuzz targets
+//!
+//! The fuzzer hands each target a byte string. Targets read the actions they
+//! run out of it with [`FuzzInput`], so the mutations honggfuzz makes map
+//! onto small changes in the action sequence rather than onto noise. It lives
+//! in the harness's `sequence` module, which decodes the same way.
+//!
+//! A sequence that breaks a target's invariant is written as JSON under
+//! `regressions/<target>/` with [`record_counterexample`]. Each target's
+//! tests replay every file there through [`regressions`], so a
+//! counterexample keeps being checked after the fuzzer run that found it.
+//!
+//! Raw inputs live in `corpus/<target>/`, which honggfuzz reads its seeds
+//! from and writes new coverage back into, with a dictionary of magic values
+//! in `dict/<target>.dict`. [`replay_corpus`] runs every stored input as a
+//! plain test and [`minimize`]s the ones that fail; `cargo xtask
+//! fuzz-regress` runs those replays for every target.
+//!
+//! [`amm_model`] is the reference AMM the differential target holds
+//! on-chain swaps to, and [`roles`] gives privilege-checking targets actors
+//! with a role and a key that persists across the sequence.
+//!
+//! Targets that move lamports draw them with [`FuzzInput::lamports`], which
+//! favours the rent-exempt minimum of the receiving account and its
+//! neighbours, and after every step check with [`rent_collectable`] that
+//! no state account was left below that minimum.
+
+pub mod amm_model;
+pub mod roles;
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use litesvm::LiteSVM;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use solana_pubkey::Pubkey;
+use solana_rent::Rent;
+
+pub use security_tests::sequence::{pseudo_random_input, FuzzInput};
+
+/// Directory holding the recorded counterexamples of `target`.
+pub fn regressions_dir(target: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("regressions")
+        .join(target)
+}
+
+/// Directory holding the stored inputs of `target`.
+pub fn corpus_dir(target: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("corpus")
+        .join(target)
+}
+
+/// Dictionary file of `target`, in the AFL format honggfuzz reads with
+/// `--dict`.
+pub fn dict_path(target: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("dict")
+        .join(format!("{}.dict", target))
+}
+
+/// Directory holding the findings of the differential target for `program`.
+pub fn findings_dir(program: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("findings")
+        .join(program)
+}
+
+/// Write `sequence` to `regressions/<target>/<hash>.json` and return the
+/// path. Recording the same sequence twice writes the same file.
+pub fn record_counterexample<T: Serialize>(target: &str, sequence: &T) -> PathBuf {
+    write_json(&regressions_dir(target), sequence)
+}
+
+/// Write `finding` to `findings/<program>/<hash>.json` and return the path.
+/// Unlike regressions these aren't replayed; they are divergences the
+/// vulnerable instructions are expected to have, kept for reading.
+pub fn record_finding<T: Serialize>(program: &str, finding: &T) -> PathBuf {
+    write_json(&findings_dir(program), finding)
+}
+
+// Content-addressed, so the same value always lands in the same file
+fn write_json<T: Serialize>(dir: &Path, value: &T) -> PathBuf {
+    let json = serde_json::to_string_pretty(value).expect("value serializes");
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+
+    let path = dir.join(format!("{:016x}.json", hasher.finish()));
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, json + "\n"))
+        .unwrap_or_else(|e| panic!("Failed to record {}: {}", path.display(), e));
+    path
+}
+
+/// Every recorded counterexample of `target`, sorted by file name.
+pub fn regressions<T: DeserializeOwned>(target: &str) -> Vec<(PathBuf, T)> {
+    let Ok(entries) = std::fs::read_dir(regressions_dir(target)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let sequence = std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .unwrap_or_else(|| panic!("{} is not a valid sequence", path.display()));
+            (path, sequence)
+        })
+        .collect()
+}
+
+/// Every stored input of `target`, sorted by file name. Hidden files are
+/// skipped.
+pub fn corpus(target: &str) -> Vec<(PathBuf, Vec<u8>)> {
+    let Ok(entries) = std::fs::read_dir(corpus_dir(target)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && !p.file_name().unwrap().to_string_lossy().starts_with('.'))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let data = std::fs::read(&path)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+            (path, data)
+        })
+        .collect()
+}
+
+/// Shrink `data` to a smaller input `fails` still returns true for: drop
+/// chunks of halving size, then zero the bytes that are left one by one.
+pub fn minimize(data: &[u8], fails: impl Fn(&[u8]) -> bool) -> Vec<u8> {
+    assert!(fails(data), "minimize: the input doesn't fail");
+    let mut best = data.to_vec();
+
+    let mut chunk = best.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < best.len() {
+            let mut candidate = best.clone();
+            candidate.drain(start..(start + chunk).min(best.len()));
+            if fails(&candidate) {
+                best = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    for i in 0..best.len() {
+        if best[i] != 0 {
+            let mut candidate = best.clone();
+            candidate[i] = 0;
+            if fails(&candidate) {
+                best = candidate;
+            }
+        }
+    }
+    best
+}
+
+/// Run every input in `corpus/<target>/` and return how many there were.
+///
+/// An input fails when `fails` returns true or panics. Each failing input
+/// is minimized and, when that made it smaller, written next to the
+/// original as `<name>.min`; the replay then panics listing them all.
+pub fn replay_corpus(target: &str, fails: impl Fn(&[u8]) -> bool) -> usize {
+    let fails = |data: &[u8]| panic::catch_unwind(AssertUnwindSafe(|| fails(data))).unwrap_or(true);
+    let inputs = corpus(target);
+
+    let mut failures = Vec::new();
+    for (path, data) in &inputs {
+        if !fails(data) {
+            continue;
+        }
+        let minimized = minimize(data, fails);
+        if minimized != *data {
+            let min_path = path.with_file_name(format!(
+                "{}.min",
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .trim_end_matches(".min")
+            ));
+            std::fs::write(&min_path, &minimized)
+                .unwrap_or_else(|e| panic!("Failed to write {}: {}", min_path.display(), e));
+            failures.push(format!(
+                "{} ({} bytes, minimized to {} bytes in {})",
+                path.display(),
+                data.len(),
+                minimized.len(),
+                min_path.display()
+            ));
+        } else {
+            failures.push(format!(
+                "{} ({} bytes, already minimal)",
+                path.display(),
+                data.len()
+            ));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} {} corpus inputs fail:\n  {}",
+        failures.len(),
+        inputs.len(),
+        target,
+        failures.join("\n  ")
+    );
+    inputs.len()
+}
+
+/// Rent-exempt minimum of an account holding `data_len` bytes, under the
+/// rent LiteSVM charges.
+pub fn rent_exempt(data_len: usize) -> u64 {
+    Rent::default().minimum_balance(data_len)
+}
+
+/// A state account whose balance is below the rent-exempt minimum for its
+/// data, so rent collection could shrink or remove it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RentCollectable {
+    pub address: Pubkey,
+    pub lamports: u64,
+    pub minimum: u64,
+}
+
+/// The first of `addresses` that holds data but less than the rent-exempt
+/// minimum for it. Closed accounts and data-less system accounts don't
+/// count.
+pub fn rent_collectable(
+    svm: &LiteSVM,
+    addresses: impl IntoIterator<Item = Pubkey>,
+) -> Option<RentCollectable> {
+    addresses.into_iter().find_map(|address| {
+        let account = svm.get_account(&address)?;
+        let minimum = svm.minimum_balance_for_rent_exemption(account.data.len());
+        (!account.data.is_empty() && account.lamports < minimum).then_some(RentCollectable {
+            address,
+            lamports: account.lamports,
+            minimum,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_reads_in_order_then_zeroes() {
+        let mut input = FuzzInput::new(&[7, 1, 2, 0, 0, 0, 0, 0, 0, 0, 5]);
+        assert_eq!(input.u8(), 7);
+        assert!(input.bool());
+        assert_eq!(input.u64(), 2);
+        assert!(!input.is_empty());
+        assert_eq!(input.choose(3), 2);
+        assert!(input.is_empty());
+        assert_eq!(input.u64(), 0);
+    }
+
+    #[test]
+    fn test_amount_stays_in_range() {
+        for seed in 0..200 {
+            let data = pseudo_random_input(seed, 64);
+            let mut input = FuzzInput::new(&data);
+            while !input.is_empty() {
+                assert!(input.amount(1_000) <= 1_000);
+            }
+        }
+        assert_eq!(FuzzInput::new(&[1]).amount(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_lamports_hit_the_rent_boundary() {
+        let rent = rent_exempt(41);
+        let edges: Vec<u64> = (0..5)
+            .map(|selector| FuzzInput::new(&[selector]).lamports(rent, 1_000))
+            .collect();
+        assert_eq!(edges, [0, rent - 1, rent, rent + 1, u64::MAX]);
+        // Anything else is an amount up to the maximum
+        assert_eq!(FuzzInput::new(&[5, 1]).lamports(rent, 1_000), 1_000);
+    }
+
+    #[test]
+    fn test_rent_collectable_only_counts_state_accounts() {
+        let mut svm = LiteSVM::new();
+        let minimum = rent_exempt(41);
+        let state = |lamports| solana_account::Account {
+            lamports,
+            data: vec![0; 41],
+            owner: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let (exempt, short, empty) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        svm.set_account(exempt, state(minimum)).unwrap();
+        svm.set_account(short, state(minimum - 1)).unwrap();
+        svm.set_account(
+            empty,
+            solana_account::Account {
+                lamports: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            rent_collectable(&svm, [exempt, empty, Pubkey::new_unique()]),
+            None
+        );
+        assert_eq!(
+            rent_collectable(&svm, [exempt, short]),
+            Some(RentCollectable {
+                address: short,
+                lamports: minimum - 1,
+                minimum
+            })
+        );
+    }
+
+    #[test]
+    fn test_minimize_keeps_only_what_fails() {
+        let data = pseudo_random_input(7, 300);
+        let fails = |d: &[u8]| d.iter().filter(|&&b| b >= 0xf0).count() >= 2;
+        assert!(fails(&data));
+        let minimized = minimize(&data, fails);
+        assert_eq!(minimized.len(), 2);
+        assert!(fails(&minimized));
+    }
+
+    // AFL dictionary lines: name="value" with \xNN escapes
+    fn parse_dict(text: &str) -> Vec<(String, Vec<u8>)> {
+        text.lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (name, value) = line.split_once('=').expect("name=\"value\"");
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .expect("quoted value");
+                let bytes = value
+                    .split("\\x")
+                    .skip(1)
+                    .map(|hex| u8::from_str_radix(hex, 16).expect("\\xNN escapes only"))
+                    .collect();
+                (name.to_string(), bytes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_every_corpus_has_a_dictionary_with_correct_rent() {
+        let corpora = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+        let targets: Vec<String> = std::fs::read_dir(corpora)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(!targets.is_empty());
+
+        let rent = solana_rent::Rent::default();
+        for target in targets {
+            assert!(!corpus(&target).is_empty(), "{}: empty corpus", target);
+            let text = std::fs::read_to_string(dict_path(&target))
+                .unwrap_or_else(|_| panic!("{}: no dictionary", target));
+            for (name, bytes) in parse_dict(&text) {
+                assert!(!bytes.is_empty(), "{}: {} is empty", target, name);
+                let (len, offset) = if let Some(len) = name.strip_prefix("rent_") {
+                    (len, 0)
+                } else if let Some(len) = name.strip_prefix("below_rent_") {
+                    (len, -1)
+                } else if let Some(len) = name.strip_prefix("above_rent_") {
+                    (len, 1)
+                } else {
+                    continue;
+                };
+                let expected = rent.minimum_balance(len.parse().unwrap()) as i64 + offset;
+                assert_eq!(bytes, expected.to_le_bytes(), "{}: {}", target, name);
+            }
+        }
+    }
+}
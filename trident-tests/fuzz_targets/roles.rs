@@ -0,0 +1,175 @@
+//! Roles the actors of a fuzzed sequence play
+//!
+//! Targets that check privilege boundaries pick actors by [`Role`] instead
+//! of by index. [`Roles`] derives one keypair per role from the target's
+//! seed and the role's name, so a role signs with the same key at every
+//! step of a sequence and in every replay of it, and [`Roles::role_of`]
+//! turns a key a program stored back into the role that holds it.
+//!
+//! [`Grants`] is the checker's side: which role each privilege belongs to.
+//! A target changes it only when the current holder (or whoever the holder
+//! named) signs the change, so a privilege the chain shows in anyone
+//! else's hands is a non-admin that gained admin-only effects.
+
+use std::collections::BTreeMap;
+
+use security_tests::scenario::seeded_keypair;
+use serde::{Deserialize, Serialize};
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::FuzzInput;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Role {
+    /// Holds every privilege when the sequence starts
+    Admin,
+    /// An ordinary account with no privileges of its own
+    User,
+    Attacker,
+    /// Sets up state on others' behalf, like a deploy script or a crank
+    Keeper,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::User, Role::Attacker, Role::Keeper];
+
+    /// Label its keypair is derived from
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::User => "user",
+            Role::Attacker => "attacker",
+            Role::Keeper => "keeper",
+        }
+    }
+
+    pub fn decode(input: &mut FuzzInput) -> Self {
+        Self::ALL[input.choose(Self::ALL.len())]
+    }
+}
+
+/// One keypair per role
+pub struct Roles {
+    keypairs: Vec<Keypair>,
+}
+
+impl Roles {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            keypairs: Role::ALL
+                .iter()
+                .map(|role| seeded_keypair(seed, role.name()))
+                .collect(),
+        }
+    }
+
+    pub fn keypair(&self, role: Role) -> &Keypair {
+        &self.keypairs[role as usize]
+    }
+
+    pub fn pubkey(&self, role: Role) -> Pubkey {
+        self.keypair(role).pubkey()
+    }
+
+    /// The role whose key is `key`, if any
+    pub fn role_of(&self, key: &Pubkey) -> Option<Role> {
+        Role::ALL
+            .into_iter()
+            .find(|&role| self.pubkey(role) == *key)
+    }
+}
+
+/// Who each privilege legitimately belongs to, by name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Grants {
+    holders: BTreeMap<&'static str, Role>,
+}
+
+impl Grants {
+    pub fn grant(&mut self, privilege: &'static str, role: Role) {
+        self.holders.insert(privilege, role);
+    }
+
+    pub fn revoke(&mut self, privilege: &'static str) {
+        self.holders.remove(privilege);
+    }
+
+    pub fn holder(&self, privilege: &str) -> Option<Role> {
+        self.holders.get(privilege).copied()
+    }
+
+    pub fn holds(&self, privilege: &str, role: Role) -> bool {
+        self.holder(privilege) == Some(role)
+    }
+
+    /// Compare the holder of `privilege` on-chain with the granted one.
+    /// `observed` is `None` when the privilege is unset and `Some(None)`
+    /// when it is held by a key no role has.
+    pub fn escalation(
+        &self,
+        privilege: &'static str,
+        observed: Option<Option<Role>>,
+    ) -> Option<Escalation> {
+        let granted = self.holder(privilege);
+        match observed {
+            None if granted.is_none() => None,
+            Some(Some(role)) if granted == Some(role) => None,
+            _ => Some(Escalation {
+                privilege,
+                granted,
+                holder: observed.flatten(),
+            }),
+        }
+    }
+}
+
+/// A privilege held on-chain by someone it wasn't granted to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Escalation {
+    pub privilege: &'static str,
+    pub granted: Option<Role>,
+    /// The role holding it on-chain; `None` for a key no role has, or an
+    /// unset privilege
+    pub holder: Option<Role>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_keep_their_keys() {
+        let (a, b) = (Roles::new(1), Roles::new(1));
+        for role in Role::ALL {
+            assert_eq!(a.pubkey(role), b.pubkey(role));
+            assert_eq!(a.role_of(&a.pubkey(role)), Some(role));
+        }
+        assert_ne!(a.pubkey(Role::Admin), a.pubkey(Role::Attacker));
+        assert_eq!(a.role_of(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn test_escalation_is_any_holder_but_the_granted_one() {
+        let mut grants = Grants::default();
+        grants.grant("config", Role::Admin);
+        assert_eq!(grants.escalation("config", Some(Some(Role::Admin))), None);
+
+        let taken = grants
+            .escalation("config", Some(Some(Role::Attacker)))
+            .unwrap();
+        assert_eq!(taken.granted, Some(Role::Admin));
+        assert_eq!(taken.holder, Some(Role::Attacker));
+        assert!(grants.escalation("config", Some(None)).is_some());
+
+        // Unset on both sides, or set where nothing was granted
+        assert_eq!(grants.escalation("pending", None), None);
+        assert!(grants
+            .escalation("pending", Some(Some(Role::User)))
+            .is_some());
+        grants.grant("pending", Role::User);
+        grants.revoke("pending");
+        assert!(!grants.holds("pending", Role::User));
+    }
+}
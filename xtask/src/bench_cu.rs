@@ -0,0 +1,375 @@
+//! `cargo xtask bench-cu`: compute units per instruction, over time
+//!
+//! Runs the tests crate's `bench-cu` binary (see `security_tests::bench`),
+//! which calls every benched instruction in LiteSVM and reports the compute
+//! it used and the data bytes of its accounts. The samples are appended to
+//! a JSON history, one run per commit (a rerun on the same commit replaces
+//! it), by default `target/bench-cu/history.json`. Each run prints:
+//! - every instruction, with its change since the previous commit
+//! - each vulnerable instruction next to its secure twin
+//! - each ported piece of secure logic in every framework it's written
+//!   for, with the program's binary size
+//!
+//! and fails when an instruction grew by more than `--max-increase` percent
+//! in compute or data, or when a call landed that should fail or the other
+//! way round. `--no-record` compares without writing the history.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use solsec_registry::CATALOG;
+
+use crate::root;
+
+pub const USAGE: &str =
+    "cargo xtask bench-cu [--history FILE] [--max-increase PCT] [--no-record] [PROGRAM]...";
+
+/// Percent an instruction may grow by unless `--max-increase` says otherwise
+const DEFAULT_MAX_INCREASE: f64 = 10.0;
+
+struct Options {
+    history: PathBuf,
+    max_increase: f64,
+    record: bool,
+    programs: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut options = Options {
+            history: root().join("target/bench-cu/history.json"),
+            max_increase: DEFAULT_MAX_INCREASE,
+            record: true,
+            programs: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .copied()
+                    .ok_or_else(|| format!("{} needs a value", arg))
+            };
+            match arg {
+                "--history" => options.history = PathBuf::from(value()?),
+                "--max-increase" => {
+                    options.max_increase = value()?
+                        .parse()
+                        .ok()
+                        .filter(|&pct: &f64| pct >= 0.0)
+                        .ok_or("--max-increase takes a percentage")?
+                }
+                "--no-record" => options.record = false,
+                flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+                program => options.programs.push(program.to_string()),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// One instruction's measurement, as `bench-cu` prints it
+struct Sample {
+    program: String,
+    instruction: String,
+    compute_units: u64,
+    data_bytes: u64,
+    error: Option<String>,
+    expected_failure: bool,
+    binary_bytes: u64,
+    port: Option<String>,
+}
+
+impl Sample {
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            program: value["program"].as_str()?.to_string(),
+            instruction: value["instruction"].as_str()?.to_string(),
+            compute_units: value["compute_units"].as_u64()?,
+            data_bytes: value["data_bytes"].as_u64()?,
+            error: value["error"].as_str().map(str::to_string),
+            expected_failure: value["expected_failure"].as_bool()?,
+            // Both are missing from runs recorded before ports were benched
+            binary_bytes: value["binary_bytes"].as_u64().unwrap_or(0),
+            port: value["port"].as_str().map(str::to_string),
+        })
+    }
+
+    fn key(&self) -> (String, String) {
+        (self.program.clone(), self.instruction.clone())
+    }
+
+    fn unexpected(&self) -> bool {
+        self.error.is_some() != self.expected_failure
+    }
+}
+
+pub fn bench_cu(args: &[&str]) -> ExitCode {
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\nusage: {}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "bench-cu", "--"])
+        .args(&options.programs)
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match output.status.code() {
+        Some(0) => {}
+        // Unknown program, already reported
+        Some(2) => return ExitCode::FAILURE,
+        _ => {
+            eprintln!("bench-cu failed; the programs must be built (anchor build)");
+            return ExitCode::FAILURE;
+        }
+    }
+    let samples: Vec<Value> = match serde_json::from_slice(&output.stdout) {
+        Ok(samples) => samples,
+        Err(e) => {
+            eprintln!("bench-cu printed malformed samples: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut history = load_history(&options);
+    let commit = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty());
+    history.retain(|run| run["commit"] != commit.as_str());
+    let baseline: BTreeMap<(String, String), Sample> = history
+        .last()
+        .and_then(|run| run["samples"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(Sample::from_json)
+        .map(|s| (s.key(), s))
+        .collect();
+    let since = history
+        .last()
+        .and_then(|run| run["commit"].as_str())
+        .map(str::to_string);
+
+    let current: Vec<Sample> = samples.iter().filter_map(Sample::from_json).collect();
+    let failures = print_samples(&current, &baseline, since.as_deref(), options.max_increase);
+    print_pairs(&current);
+    print_ports(&current);
+
+    if options.record {
+        history.push(json!({
+            "commit": commit,
+            "dirty": dirty,
+            "timestamp": SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            "samples": samples,
+        }));
+        if let Err(e) = save_history(&options, &history) {
+            eprintln!("failed to write {}: {}", options.history.display(), e);
+            return ExitCode::FAILURE;
+        }
+        eprintln!("recorded {} in {}", commit, options.history.display());
+    }
+
+    if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("\n{} failed:", failures.len());
+        for failure in &failures {
+            eprintln!("  {}", failure);
+        }
+        ExitCode::FAILURE
+    }
+}
+
+/// Prints one row per sample and returns what failed.
+fn print_samples(
+    samples: &[Sample],
+    baseline: &BTreeMap<(String, String), Sample>,
+    since: Option<&str>,
+    max_increase: f64,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    match since {
+        Some(commit) => println!("Compute units (change since {})\n", commit),
+        None => println!("Compute units (no earlier run to compare with)\n"),
+    }
+    println!(
+        "{:<22} {:<28} {:>8} {:>8} {:>6} {:>6}  STATUS",
+        "PROGRAM", "INSTRUCTION", "CU", "ΔCU", "BYTES", "ΔBYTES"
+    );
+    for sample in samples {
+        let before = baseline.get(&sample.key());
+        let delta = |now: u64, then: Option<u64>| {
+            then.map_or_else(
+                || "new".to_string(),
+                |then| format!("{:+}", now as i64 - then as i64),
+            )
+        };
+        let status = match (&sample.error, sample.expected_failure) {
+            (None, false) => "ok".to_string(),
+            (Some(_), true) => "fails by design".to_string(),
+            (None, true) => "landed, expected to fail".to_string(),
+            (Some(e), false) => e.clone(),
+        };
+        println!(
+            "{:<22} {:<28} {:>8} {:>8} {:>6} {:>6}  {}",
+            sample.program,
+            sample.instruction,
+            sample.compute_units,
+            delta(sample.compute_units, before.map(|b| b.compute_units)),
+            sample.data_bytes,
+            delta(sample.data_bytes, before.map(|b| b.data_bytes)),
+            status
+        );
+
+        let name = format!("{}/{}", sample.program, sample.instruction);
+        if sample.unexpected() {
+            failures.push(format!("{}: {}", name, status));
+        }
+        let Some(before) = before else {
+            continue;
+        };
+        for (what, now, then) in [
+            ("compute", sample.compute_units, before.compute_units),
+            ("data", sample.data_bytes, before.data_bytes),
+        ] {
+            let increase = percent_increase(now, then);
+            if increase > max_increase {
+                failures.push(format!(
+                    "{}: {} grew {:.1}% ({} -> {}), limit {}%",
+                    name, what, increase, then, now, max_increase
+                ));
+            }
+        }
+    }
+    failures
+}
+
+fn percent_increase(now: u64, then: u64) -> f64 {
+    match (now, then) {
+        (now, then) if now <= then => 0.0,
+        (_, 0) => f64::INFINITY,
+        (now, then) => (now - then) as f64 * 100.0 / then as f64,
+    }
+}
+
+/// Vulnerable instructions next to their secure twins, from the registry.
+fn print_pairs(samples: &[Sample]) {
+    let find = |program: &str, instruction: &str| {
+        samples
+            .iter()
+            .find(|s| s.program == program && s.instruction == instruction && s.error.is_none())
+    };
+    let mut rows = Vec::new();
+    for vulnerability in CATALOG {
+        let pairs = vulnerability
+            .vulnerable
+            .iter()
+            .zip(vulnerability.mitigation.instructions);
+        for (vulnerable, secure) in pairs {
+            if let (Some(v), Some(s)) = (
+                find(vulnerability.program, vulnerable),
+                find(vulnerability.program, secure),
+            ) {
+                rows.push((vulnerability.program, *vulnerable, *secure, v, s));
+            }
+        }
+    }
+    if rows.is_empty() {
+        return;
+    }
+
+    println!("\nSecure vs vulnerable\n");
+    println!(
+        "{:<22} {:<28} {:<28} {:>8} {:>8} {:>8} {:>6}",
+        "PROGRAM", "VULNERABLE", "SECURE", "CU", "CU", "Δ", "RATIO"
+    );
+    for (program, vulnerable, secure, v, s) in rows {
+        let ratio = if v.compute_units == 0 {
+            f64::INFINITY
+        } else {
+            s.compute_units as f64 / v.compute_units as f64
+        };
+        println!(
+            "{:<22} {:<28} {:<28} {:>8} {:>8} {:>+8} {:>5.2}x",
+            program,
+            vulnerable,
+            secure,
+            v.compute_units,
+            s.compute_units,
+            s.compute_units as i64 - v.compute_units as i64,
+            ratio
+        );
+    }
+}
+
+/// Every variant of each port, from the leanest binary up.
+fn print_ports(samples: &[Sample]) {
+    let mut ports: BTreeMap<&str, Vec<&Sample>> = BTreeMap::new();
+    for sample in samples.iter().filter(|s| s.error.is_none()) {
+        if let Some(port) = &sample.port {
+            ports.entry(port).or_default().push(sample);
+        }
+    }
+    // A port benched in one framework only, e.g. with PROGRAM arguments
+    ports.retain(|_, variants| variants.len() > 1);
+    if ports.is_empty() {
+        return;
+    }
+
+    println!("\nAcross frameworks\n");
+    println!(
+        "{:<36} {:<10} {:<22} {:<28} {:>8} {:>8} {:>6}",
+        "LOGIC", "FRAMEWORK", "PROGRAM", "INSTRUCTION", "CU", "BINARY", "BYTES"
+    );
+    for (logic, mut variants) in ports {
+        variants.sort_by_key(|s| s.binary_bytes);
+        for s in variants {
+            let framework = solsec_registry::framework(&s.program).map_or("?", |f| f.name());
+            println!(
+                "{:<36} {:<10} {:<22} {:<28} {:>8} {:>8} {:>6}",
+                logic,
+                framework,
+                s.program,
+                s.instruction,
+                s.compute_units,
+                s.binary_bytes,
+                s.data_bytes
+            );
+        }
+    }
+}
+
+fn load_history(options: &Options) -> Vec<Value> {
+    std::fs::read_to_string(&options.history)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|history| history["runs"].as_array().cloned())
+        .unwrap_or_default()
+}
+
+fn save_history(options: &Options, runs: &[Value]) -> std::io::Result<()> {
+    if let Some(dir) = options.history.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let text = serde_json::to_string_pretty(&json!({ "runs": runs })).unwrap();
+    std::fs::write(&options.history, text + "\n")
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
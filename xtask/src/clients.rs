@@ -0,0 +1,30 @@
+//! `cargo xtask clients [--check] [PROGRAM]...`: the typed client crates
+//! in `clients/`
+//!
+//! Hands off to the tests crate's `gen-clients` binary (see
+//! `security_tests::client`), which generates instruction builders, PDA
+//! finders and account decoders from each program's IDL, and writes the
+//! IDL's Codama node tree as `codama.json` beside them (see
+//! `security_tests::codama`). `--check` fails when a checked-in crate no
+//! longer matches its IDL.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask clients [--check] [--out DIR] [PROGRAM]...";
+
+pub fn clients(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "gen-clients", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,28 @@
+//! `cargo xtask docs [--check] [PROGRAM]...`: the writeups in
+//! `generated-docs/`
+//!
+//! Hands off to the tests crate's `gen-docs` binary (see
+//! `security_tests::docs`), which renders each program's page from its
+//! registry entry, README, demo run and sources. `--check` fails when a
+//! checked-in page no longer matches what the code produces.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask docs [--check] [--out DIR] [PROGRAM]...";
+
+pub fn docs(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "gen-docs", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,28 @@
+//! `cargo xtask export-exploits [--out DIR] [PROGRAM]...`: the exploit
+//! transactions, for other tools
+//!
+//! Hands off to the tests crate's `export-exploits` binary (see
+//! `security_tests::export`), which plays each demo in LiteSVM and writes
+//! its signed transactions, the accounts they start from and the keypairs
+//! that signed them to `target/exports/<program>/`.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask export-exploits [--out DIR] [PROGRAM]...";
+
+pub fn export_exploits(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "export-exploits", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}
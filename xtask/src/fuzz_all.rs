@@ -0,0 +1,441 @@
+//! `cargo xtask fuzz-all`: every fuzz target in one command
+//!
+//! Each target is a shard run by one engine process on one core, for its
+//! time budget. Up to `--jobs` shards run at once, longest budget first, so
+//! the slowest target doesn't start last. A shard reads the committed
+//! corpus and writes what it discovers to a scratch directory under
+//! `target/fuzz-all/`; new inputs are merged back into the corpus once it
+//! ends. The run closes with `target/fuzz-all/report.md`: per target, how
+//! long it ran, how the engine exited, how many inputs it added, and the
+//! findings it recorded under `fuzz/findings/`.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{root, targets, Fuzzer, FUZZERS};
+
+pub const USAGE: &str =
+    "cargo xtask fuzz-all [--jobs N] [--time SECS] [--budget TARGET=SECS]... [TARGET]...";
+
+/// Seconds each target gets unless `--budget` says otherwise
+const DEFAULT_SECONDS: u64 = 60;
+
+struct Options {
+    jobs: usize,
+    seconds: u64,
+    budgets: Vec<(String, u64)>,
+    targets: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut options = Options {
+            jobs: thread::available_parallelism().map_or(1, |n| n.get()),
+            seconds: DEFAULT_SECONDS,
+            budgets: Vec::new(),
+            targets: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .copied()
+                    .ok_or_else(|| format!("{} needs a value", arg))
+            };
+            match arg {
+                "--jobs" => {
+                    options.jobs = value()?
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or("--jobs takes a positive number")?
+                }
+                "--time" => {
+                    options.seconds = value()?.parse().map_err(|_| "--time takes seconds")?
+                }
+                "--budget" => {
+                    let budget = value()?;
+                    let (target, seconds) = budget
+                        .split_once('=')
+                        .and_then(|(t, s)| Some((t, s.parse().ok()?)))
+                        .ok_or_else(|| format!("--budget {}: expected TARGET=SECS", budget))?;
+                    options.budgets.push((target.to_string(), seconds));
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+                target => options.targets.push(target.to_string()),
+            }
+        }
+        Ok(options)
+    }
+
+    fn seconds(&self, target: &str) -> u64 {
+        self.budgets
+            .iter()
+            .rev()
+            .find(|(t, _)| t == target)
+            .map_or(self.seconds, |(_, s)| *s)
+    }
+}
+
+/// One target fuzzed by one engine
+struct Shard {
+    fuzzer: &'static Fuzzer,
+    target: String,
+    seconds: u64,
+}
+
+impl Shard {
+    fn dir(&self) -> PathBuf {
+        root()
+            .join("target/fuzz-all")
+            .join(self.fuzzer.name)
+            .join(&self.target)
+    }
+
+    fn corpus(&self) -> PathBuf {
+        root().join(self.fuzzer.corpus).join(&self.target)
+    }
+}
+
+/// How a shard went
+struct Outcome {
+    target: String,
+    fuzzer: &'static str,
+    seconds: u64,
+    ran: Duration,
+    /// `None` if the engine could not be started
+    success: Option<bool>,
+    merged: usize,
+    findings: Vec<PathBuf>,
+    log: PathBuf,
+}
+
+pub fn fuzz_all(args: &[&str]) -> ExitCode {
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\nusage: {}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut shards: Vec<Shard> = FUZZERS
+        .iter()
+        .flat_map(|fuzzer| {
+            targets(fuzzer)
+                .into_iter()
+                .filter(|t| options.targets.is_empty() || options.targets.contains(t))
+                .map(|target| Shard {
+                    fuzzer,
+                    seconds: options.seconds(&target),
+                    target,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let unknown: Vec<_> = options
+        .targets
+        .iter()
+        .chain(options.budgets.iter().map(|(t, _)| t))
+        .filter(|t| !shards.iter().any(|s| s.target == **t))
+        .collect();
+    if !unknown.is_empty() {
+        eprintln!(
+            "unknown fuzz targets: {}",
+            unknown
+                .iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        return ExitCode::FAILURE;
+    }
+    // Longest first, so no long shard starts when the others are done
+    shards.sort_by(|a, b| b.seconds.cmp(&a.seconds).then(a.target.cmp(&b.target)));
+
+    // Build each engine once up front rather than in every shard at once
+    let engines: BTreeSet<&str> = shards.iter().map(|s| s.fuzzer.name).collect();
+    let mut broken = Vec::new();
+    for fuzzer in FUZZERS.iter().filter(|f| engines.contains(f.name)) {
+        eprintln!("building {} targets", fuzzer.name);
+        let built = (fuzzer.build)()
+            .status()
+            .is_ok_and(|status| status.success());
+        if !built {
+            eprintln!("failed to build the {} targets, skipping them", fuzzer.name);
+            broken.push(fuzzer.name);
+        }
+    }
+
+    let jobs = options.jobs.min(shards.len()).max(1);
+    eprintln!(
+        "fuzzing {} targets, {} at a time: {}",
+        shards.len(),
+        jobs,
+        shards
+            .iter()
+            .map(|s| format!("{} ({}s)", s.target, s.seconds))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let started = Instant::now();
+    let queue = Mutex::new(shards.into_iter());
+    let (done, outcomes) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let done = done.clone();
+            let (queue, broken) = (&queue, &broken);
+            scope.spawn(move || loop {
+                let Some(shard) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let outcome = run(&shard, broken.contains(&shard.fuzzer.name));
+                eprintln!(
+                    "{} finished after {}s: {} new inputs, {} findings",
+                    shard.target,
+                    outcome.ran.as_secs(),
+                    outcome.merged,
+                    outcome.findings.len()
+                );
+                done.send(outcome).unwrap();
+            });
+        }
+    });
+    drop(done);
+
+    let mut outcomes: Vec<Outcome> = outcomes.into_iter().collect();
+    outcomes.sort_by(|a, b| a.target.cmp(&b.target));
+    let report = report(&outcomes, started.elapsed());
+    let path = root().join("target/fuzz-all/report.md");
+    match std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|()| std::fs::write(&path, &report))
+    {
+        Ok(()) => eprintln!("report written to {}", path.display()),
+        Err(e) => eprintln!("failed to write {}: {}", path.display(), e),
+    }
+
+    if outcomes
+        .iter()
+        .all(|o| o.success == Some(true) && o.findings.is_empty())
+    {
+        ExitCode::SUCCESS
+    } else {
+        println!("{}", report);
+        ExitCode::FAILURE
+    }
+}
+
+fn run(shard: &Shard, broken: bool) -> Outcome {
+    let dir = shard.dir();
+    let scratch = dir.join("corpus");
+    let log = dir.join("fuzz.log");
+    let findings_before = findings(&shard.target);
+    let started = Instant::now();
+
+    let success = if broken {
+        None
+    } else {
+        std::fs::create_dir_all(&scratch)
+            .and_then(|()| File::create(&log))
+            .and_then(|out| {
+                (shard.fuzzer.fuzz)(&shard.target, &shard.corpus(), &scratch, shard.seconds)
+                    .stdout(out.try_clone()?)
+                    .stderr(out)
+                    .status()
+            })
+            .map(|status| status.success())
+            .map_err(|e| eprintln!("{}: failed to run the fuzzer: {}", shard.target, e))
+            .ok()
+    };
+
+    Outcome {
+        target: shard.target.clone(),
+        fuzzer: shard.fuzzer.name,
+        seconds: shard.seconds,
+        ran: started.elapsed(),
+        success,
+        merged: merge(&scratch, &shard.corpus()),
+        findings: findings(&shard.target)
+            .difference(&findings_before)
+            .cloned()
+            .collect(),
+        log,
+    }
+}
+
+/// `.json` files under `fuzz/findings/<target>/`
+fn findings(target: &str) -> BTreeSet<PathBuf> {
+    std::fs::read_dir(root().join("fuzz/findings").join(target))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Copy every input of `scratch` whose contents `corpus` doesn't have yet
+/// into it, and return how many there were.
+fn merge(scratch: &Path, corpus: &Path) -> usize {
+    let files = |dir: &Path| -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| p.is_file())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut known: HashSet<Vec<u8>> = files(corpus)
+        .iter()
+        .filter_map(|p| std::fs::read(p).ok())
+        .collect();
+
+    let mut merged = 0;
+    for input in files(scratch) {
+        let Ok(data) = std::fs::read(&input) else {
+            continue;
+        };
+        if known.contains(&data) {
+            continue;
+        }
+        let mut name = input.file_name().unwrap().to_os_string();
+        while corpus.join(&name).exists() {
+            name.push("_");
+        }
+        if std::fs::write(corpus.join(name), &data).is_ok() {
+            known.insert(data);
+            merged += 1;
+        }
+    }
+    merged
+}
+
+fn report(outcomes: &[Outcome], elapsed: Duration) -> String {
+    let mut out = format!(
+        "# Fuzz run\n\n{} targets in {}s\n\n\
+         | Target | Engine | Budget | Ran | Exit | New inputs | Findings |\n\
+         |--------|--------|--------|-----|------|------------|----------|\n",
+        outcomes.len(),
+        elapsed.as_secs()
+    );
+    for o in outcomes {
+        let exit = match o.success {
+            Some(true) => "ok",
+            Some(false) => "failed",
+            None => "not run",
+        };
+        writeln!(
+            out,
+            "| {} | {} | {}s | {}s | {} | {} | {} |",
+            o.target,
+            o.fuzzer,
+            o.seconds,
+            o.ran.as_secs(),
+            exit,
+            o.merged,
+            o.findings.len()
+        )
+        .unwrap();
+    }
+
+    for o in outcomes.iter().filter(|o| !o.findings.is_empty()) {
+        writeln!(out, "\n## {}\n", o.target).unwrap();
+        for finding in &o.findings {
+            let message = std::fs::read(finding)
+                .ok()
+                .and_then(|json| serde_json::from_slice::<serde_json::Value>(&json).ok())
+                .and_then(|json| json["message"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            let first = message.lines().next().unwrap_or("");
+            writeln!(
+                out,
+                "- `{}`: {}",
+                finding.strip_prefix(root()).unwrap_or(finding).display(),
+                first
+            )
+            .unwrap();
+        }
+    }
+
+    let failed: Vec<_> = outcomes
+        .iter()
+        .filter(|o| o.success == Some(false))
+        .collect();
+    if !failed.is_empty() {
+        out.push_str("\n## Engine logs\n\n");
+        for o in failed {
+            writeln!(out, "- {}: `{}`", o.target, o.log.display()).unwrap();
+        }
+    }
+    out
+}
+
+/// `cargo hfuzz build` for the Trident targets
+pub fn hfuzz_build() -> Command {
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .args(["hfuzz", "build"])
+        .current_dir(root().join("trident-tests"));
+    command
+}
+
+/// `cargo hfuzz run fuzz_<target>` on one thread for `seconds`
+pub fn hfuzz_run(target: &str, corpus: &Path, out: &Path, seconds: u64) -> Command {
+    let dict = root()
+        .join("trident-tests/fuzz_targets/dict")
+        .join(format!("{}.dict", target));
+    let mut args = format!(
+        "--input {} --output {} --threads 1 --run_time {}",
+        corpus.display(),
+        out.display(),
+        seconds
+    );
+    if dict.exists() {
+        write!(args, " --dict {}", dict.display()).unwrap();
+    }
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .args(["hfuzz", "run", &format!("fuzz_{}", target)])
+        .env("HFUZZ_RUN_ARGS", args)
+        .current_dir(root().join("trident-tests"))
+        .stdin(Stdio::null());
+    command
+}
+
+/// `cargo +nightly fuzz build` for the targets in `fuzz/`
+pub fn libfuzzer_build() -> Command {
+    let mut command = Command::new("cargo");
+    command
+        .args(["+nightly", "fuzz", "build"])
+        .current_dir(root());
+    command
+}
+
+/// `cargo +nightly fuzz run <target>` for `seconds`, writing new inputs to
+/// `out` (libFuzzer adds to its first corpus directory)
+pub fn libfuzzer_run(target: &str, corpus: &Path, out: &Path, seconds: u64) -> Command {
+    let dict = root().join("fuzz/dict").join(format!("{}.dict", target));
+    let mut command = Command::new("cargo");
+    command
+        .args(["+nightly", "fuzz", "run", target])
+        .arg(out)
+        .arg(corpus)
+        .arg("--")
+        .arg(format!("-max_total_time={}", seconds))
+        .current_dir(root())
+        .stdin(Stdio::null());
+    if dict.exists() {
+        command.arg(format!("-dict={}", dict.display()));
+    }
+    command
+}
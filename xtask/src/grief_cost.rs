@@ -0,0 +1,419 @@
+//! `cargo xtask grief-cost`: what griefing a PDA scheme costs each side
+//!
+//! Prices the account-griefing attack for a seed template and a number of
+//! users. An attacker blocks a PDA by sending it the rent-exempt minimum of
+//! an empty account, so blocking every user costs that much per address,
+//! plus the fees of the transfers. Recovering costs the defender an upgrade
+//! to a create path that tops up, allocates and assigns (see
+//! `scan-prefund`), then one transaction and the rent shortfall per PDA.
+//! That rent is what creating the account costs anyway, so the upgrade and
+//! the outage until it ships are what the attack really takes.
+//!
+//! The template takes the components `scan-prefund` does, plus
+//! `{nonce:u8}` through `{nonce:u64}` for a value the user picks when the
+//! account is created. An attacker has to fund every value of a nonce to be
+//! sure of blocking a user, which is what the table shows: the template as
+//! given next to the same seeds with no nonce and with nonces of each width.
+//! Without `{user}` one PDA is shared by everyone, and one transfer blocks
+//! all of them.
+//!
+//! Rent and fees are mainnet's defaults, so the figures are lamports as of
+//! today's parameters rather than a quote.
+
+use std::process::ExitCode;
+
+use serde_json::json;
+
+use crate::root;
+use crate::scan_prefund::{parse_seeds, Seed};
+
+pub const USAGE: &str = "cargo xtask grief-cost --seeds TEMPLATE --users N --space BYTES \
+                         [--program-bytes BYTES] [--json]";
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Rent's default `lamports_per_byte_year` and `exemption_threshold`
+const LAMPORTS_PER_BYTE_YEAR: u128 = 3_480;
+const EXEMPTION_YEARS: u128 = 2;
+
+/// Bytes the runtime charges rent for on top of an account's data
+const ACCOUNT_OVERHEAD: u128 = 128;
+
+const LAMPORTS_PER_SIGNATURE: u128 = 5_000;
+
+/// Transfers one attacker transaction fits: a legacy transaction is at most
+/// 1232 bytes, one signature, header, payer, blockhash and the system
+/// program take 166, and each transfer adds its target key (32) and a
+/// compiled instruction (17)
+const TRANSFERS_PER_TX: u128 = (1232 - 166) / (32 + 17);
+
+/// Program bytes `solana program deploy` writes per buffer transaction,
+/// give or take
+const WRITE_CHUNK: u128 = 1_000;
+
+/// Program size assumed when `account_griefing.so` isn't built and
+/// `--program-bytes` isn't given
+const DEFAULT_PROGRAM_BYTES: u64 = 250_000;
+
+const NONCE_WIDTHS: [u32; 4] = [8, 16, 32, 64];
+
+struct Options {
+    template: String,
+    /// The template without its nonces
+    base: String,
+    per_user: bool,
+    nonce_bits: u32,
+    users: u64,
+    space: u64,
+    /// Size of the program to upgrade, and whether it was measured
+    program_bytes: (u64, bool),
+    json: bool,
+}
+
+impl Options {
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut template = None;
+        let mut users = None;
+        let mut space = None;
+        let mut program_bytes = None;
+        let mut json = false;
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .copied()
+                    .ok_or_else(|| format!("{} needs a value", arg))
+            };
+            match arg {
+                "--seeds" => template = Some(value()?.to_string()),
+                "--users" => {
+                    users = Some(
+                        value()?
+                            .parse()
+                            .ok()
+                            .filter(|&n: &u64| n > 0)
+                            .ok_or("--users takes a positive count")?,
+                    )
+                }
+                "--space" => space = Some(value()?.parse().map_err(|_| "--space takes bytes")?),
+                "--program-bytes" => {
+                    program_bytes = Some(
+                        value()?
+                            .parse()
+                            .map_err(|_| "--program-bytes takes bytes")?,
+                    )
+                }
+                "--json" => json = true,
+                other => return Err(format!("unknown option {}", other)),
+            }
+        }
+        let template = template.ok_or("--seeds is required")?;
+
+        let mut fixed = Vec::new();
+        let mut nonce_bits = 0;
+        for part in template.split(',') {
+            match part
+                .strip_prefix("{nonce:u")
+                .and_then(|width| width.strip_suffix('}'))
+            {
+                Some(width) => match width.parse() {
+                    Ok(bits) if NONCE_WIDTHS.contains(&bits) => nonce_bits += bits,
+                    _ => return Err(format!("seed {}: nonces are u8 to u64", part)),
+                },
+                None => fixed.push(part),
+            }
+        }
+        // A template of nonces alone has no fixed seeds to parse
+        let seeds = match fixed.is_empty() {
+            true => Vec::new(),
+            false => parse_seeds(&fixed.join(","))?,
+        };
+        if nonce_bits > 128 {
+            return Err("more than 128 bits of nonce".to_string());
+        }
+
+        let program_bytes = match program_bytes {
+            Some(bytes) => (bytes, true),
+            None => std::fs::metadata(root().join("target/deploy/account_griefing.so"))
+                .map_or((DEFAULT_PROGRAM_BYTES, false), |m| (m.len(), true)),
+        };
+        Ok(Options {
+            base: fixed.join(","),
+            per_user: seeds.iter().any(|seed| matches!(seed, Seed::User)),
+            nonce_bits,
+            users: users.ok_or("--users is required")?,
+            space: space.ok_or("--space is required")?,
+            program_bytes,
+            json,
+            template,
+        })
+    }
+}
+
+fn rent_exempt(bytes: u128) -> u128 {
+    (ACCOUNT_OVERHEAD + bytes) * LAMPORTS_PER_BYTE_YEAR * EXEMPTION_YEARS
+}
+
+/// One seed scheme, priced
+struct Row {
+    template: String,
+    given: bool,
+    nonce_bits: u32,
+    /// Addresses the attacker funds to block every user
+    pdas: f64,
+    attacker: f64,
+    defender: f64,
+}
+
+impl Row {
+    fn price(options: &Options, template: String, nonce_bits: u32) -> Self {
+        let users = options.users as f64;
+        let addresses = if options.per_user { users } else { 1.0 };
+        let pdas = addresses * 2f64.powi(nonce_bits as i32);
+
+        let grief = rent_exempt(0) as f64;
+        let fees = (pdas / TRANSFERS_PER_TX as f64).ceil() * LAMPORTS_PER_SIGNATURE as f64;
+        let attacker = pdas * grief + fees;
+
+        let defender = if nonce_bits == 0 {
+            // The program can't create the accounts until it's upgraded,
+            // then each one costs a transaction and what rent is missing
+            let (bytes, _) = options.program_bytes;
+            let writes = (bytes as u128).div_ceil(WRITE_CHUNK) + 1;
+            let shortfall = rent_exempt(options.space as u128).saturating_sub(rent_exempt(0));
+            (writes * LAMPORTS_PER_SIGNATURE) as f64
+                + addresses * (LAMPORTS_PER_SIGNATURE + shortfall) as f64
+        } else {
+            // A blocked user retries with another nonce
+            users * LAMPORTS_PER_SIGNATURE as f64
+        };
+        Row {
+            given: template == options.template,
+            template,
+            nonce_bits,
+            pdas,
+            attacker,
+            defender,
+        }
+    }
+}
+
+fn ratio(ratio: f64) -> String {
+    if ratio >= 1e6 {
+        format!("{:.2e}x", ratio)
+    } else {
+        format!("{:.2}x", ratio)
+    }
+}
+
+pub fn grief_cost(args: &[&str]) -> ExitCode {
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\nusage: {}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut rows = vec![Row::price(&options, options.base.clone(), 0)];
+    for bits in NONCE_WIDTHS {
+        let template = format!("{},{{nonce:u{}}}", options.base, bits);
+        rows.push(Row::price(&options, template, bits));
+    }
+    if !rows.iter().any(|row| row.given) {
+        rows.push(Row::price(
+            &options,
+            options.template.clone(),
+            options.nonce_bits,
+        ));
+    }
+
+    if options.json {
+        let report = json!({
+            "seeds": options.template,
+            "users": options.users,
+            "space": options.space,
+            "program_bytes": options.program_bytes.0,
+            "rent_exempt_lamports": {
+                "empty": rent_exempt(0) as u64,
+                "account": rent_exempt(options.space as u128) as u64,
+            },
+            "schemes": rows.iter().map(|row| json!({
+                "seeds": row.template,
+                "given": row.given,
+                "nonce_bits": row.nonce_bits,
+                "pdas": row.pdas,
+                "attacker_lamports": row.attacker,
+                "defender_lamports": row.defender,
+            })).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return ExitCode::SUCCESS;
+    }
+
+    let sol = |lamports: f64| {
+        let sol = lamports / LAMPORTS_PER_SOL;
+        if sol >= 1e9 {
+            format!("{:.2e}", sol)
+        } else {
+            format!("{:.6}", sol)
+        }
+    };
+    println!(
+        "Griefing {} users, {} bytes per account\n",
+        options.users, options.space
+    );
+    if !options.per_user {
+        println!("The seeds don't include {{user}}: one PDA is shared, and blocking it blocks everyone\n");
+    }
+    println!(
+        "{:<36} {:>12} {:>18} {:>18} {:>16} {:>12}",
+        "SEEDS", "PDAS", "ATTACKER (SOL)", "PER USER (SOL)", "DEFENDER (SOL)", "RATIO"
+    );
+    for row in &rows {
+        let pdas = if row.pdas >= 1e9 {
+            format!("{:.2e}", row.pdas)
+        } else {
+            format!("{}", row.pdas)
+        };
+        println!(
+            "{:<36} {:>12} {:>18} {:>18} {:>16} {:>12}{}",
+            row.template,
+            pdas,
+            sol(row.attacker),
+            sol(row.attacker / options.users as f64),
+            sol(row.defender),
+            ratio(row.attacker / row.defender),
+            if row.given { "  (given)" } else { "" }
+        );
+    }
+
+    let (bytes, measured) = options.program_bytes;
+    println!(
+        "\nAttacker: {} lamports per address (rent-exempt minimum of an empty account), \
+         {} transfers per transaction at {} lamports each.",
+        rent_exempt(0),
+        TRANSFERS_PER_TX,
+        LAMPORTS_PER_SIGNATURE
+    );
+    println!(
+        "Defender without a nonce: upgrade a {}-byte program ({}) to top up, allocate \
+         and assign, then one transaction and {} lamports of rent shortfall per PDA.",
+        bytes,
+        if measured {
+            "measured"
+        } else {
+            "assumed; pass --program-bytes"
+        },
+        rent_exempt(options.space as u128).saturating_sub(rent_exempt(0))
+    );
+    println!(
+        "Defender with a nonce: a blocked user retries with another, for one fee. The \
+         attacker's cost doubles with every bit of nonce."
+    );
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(seeds: &str) -> Options {
+        let args = [
+            "--seeds",
+            seeds,
+            "--users",
+            "100",
+            "--space",
+            "165",
+            "--program-bytes",
+            "250000",
+        ];
+        Options::parse(&args).unwrap()
+    }
+
+    #[test]
+    fn test_rent_matches_mainnet_defaults() {
+        assert_eq!(rent_exempt(0), 890_880);
+        // A token account
+        assert_eq!(rent_exempt(165), 2_039_280);
+        assert_eq!(TRANSFERS_PER_TX, 21);
+    }
+
+    #[test]
+    fn test_prices_one_address_per_user() {
+        let options = options("vault,{user}");
+        let row = Row::price(&options, options.base.clone(), 0);
+        assert!(row.given);
+        assert_eq!(row.pdas, 100.0);
+        // 100 empty accounts' rent, in five transactions of up to 21
+        assert_eq!(row.attacker, (100 * 890_880 + 5 * 5_000) as f64);
+        // 251 deploy writes, then a fee and the shortfall to 165 bytes each
+        let shortfall = 2_039_280 - 890_880;
+        assert_eq!(
+            row.defender,
+            (251 * 5_000 + 100 * (5_000 + shortfall)) as f64
+        );
+
+        let row = Row::price(&options, "vault,{user},{nonce:u8}".to_string(), 8);
+        assert!(!row.given);
+        assert_eq!(row.pdas, 25_600.0);
+        assert_eq!(
+            row.attacker,
+            (25_600 * 890_880_u64 + 25_600_u64.div_ceil(21) * 5_000) as f64
+        );
+        // A blocked user just retries
+        assert_eq!(row.defender, (100 * 5_000) as f64);
+    }
+
+    #[test]
+    fn test_prices_a_shared_address_once() {
+        let options = options("config");
+        assert!(!options.per_user);
+        let row = Row::price(&options, options.base.clone(), 0);
+        assert_eq!(row.pdas, 1.0);
+        assert_eq!(row.attacker, (890_880 + 5_000) as f64);
+    }
+
+    #[test]
+    fn test_parses_nonces_out_of_the_template() {
+        let options = options("vault,{user},{nonce:u16},{nonce:u8}");
+        assert_eq!(options.base, "vault,{user}");
+        assert_eq!(options.nonce_bits, 24);
+        assert!(options.per_user);
+        assert_eq!((options.users, options.space), (100, 165));
+        assert_eq!(options.program_bytes, (250_000, true));
+        assert!(!options.json);
+    }
+
+    #[test]
+    fn test_rejects_bad_options() {
+        for (args, error) in [
+            (
+                &["--seeds", "vault", "--users", "0", "--space", "0"][..],
+                "--users takes a positive count",
+            ),
+            (
+                &["--seeds", "{nonce:u7}", "--users", "1", "--space", "0"],
+                "seed {nonce:u7}: nonces are u8 to u64",
+            ),
+            (
+                &["--seeds", "vault", "--users", "1", "--space", "many"],
+                "--space takes bytes",
+            ),
+            (&["--seeds", "vault", "--users", "1"], "--space is required"),
+            (&["--users", "1", "--space", "0"], "--seeds is required"),
+            (&["--seeds"], "--seeds needs a value"),
+            (&["--verbose"], "unknown option --verbose"),
+        ] {
+            assert_eq!(Options::parse(args).err().as_deref(), Some(error));
+        }
+
+        let wide = "{nonce:u64},{nonce:u64},{nonce:u8}";
+        let args = ["--seeds", wide, "--users", "1", "--space", "0"];
+        assert_eq!(
+            Options::parse(&args).err().as_deref(),
+            Some("more than 128 bits of nonce")
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! `cargo xtask idl-diff [PROGRAM]...`: what the fix changes in the interface
+//!
+//! For each vulnerable instruction and its secure twin in the registry,
+//! lines up their accounts and arguments and prints what differs:
+//! - an account added or removed, or its type changed (`UncheckedAccount`
+//!   to `Signer`)
+//! - an `#[account(...)]` constraint added, removed or changed
+//! - an argument added, removed or retyped
+//!
+//! Anchor programs are read from source with `solsec_lint::interface`,
+//! since the generated IDL leaves the constraints out. Pinocchio programs
+//! check everything in the handler, so theirs come from the hand-written
+//! IDL in `tests/idl/`, which only has the signer and writable flags. A pair
+//! with no difference has its fix in the handler body. `--all` prints the
+//! unchanged rows too, and `--json` prints every pair as JSON.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+use solsec_lint::interface::{self, Instruction, InstructionAccount};
+use solsec_registry::{Framework, Vulnerability, CATALOG};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask idl-diff [--all] [--json] [PROGRAM]...";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Same,
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    fn name(&self) -> &'static str {
+        match self {
+            Change::Same => "same",
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+}
+
+/// One line of a pair's diff
+struct Row {
+    /// `account` or `arg`
+    kind: &'static str,
+    name: String,
+    /// `account`, `arg`, `type` or the constraint's key
+    what: String,
+    change: Change,
+    vulnerable: String,
+    secure: String,
+}
+
+pub fn idl_diff(args: &[&str]) -> ExitCode {
+    let mut all = false;
+    let mut json = false;
+    let mut programs = Vec::new();
+    for &arg in args {
+        match arg {
+            "--all" => all = true,
+            "--json" => json = true,
+            flag if flag.starts_with("--") => {
+                eprintln!("unknown option {}\nusage: {}", flag, USAGE);
+                return ExitCode::FAILURE;
+            }
+            program => programs.push(program),
+        }
+    }
+    let selected: Vec<&Vulnerability> = if programs.is_empty() {
+        CATALOG.iter().collect()
+    } else {
+        let mut selected = Vec::new();
+        for program in programs {
+            match solsec_registry::find(program) {
+                Some(vulnerability) => selected.push(vulnerability),
+                None => {
+                    eprintln!("unknown program {}; see cargo xtask list", program);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        selected
+    };
+
+    let mut report = Vec::new();
+    for vulnerability in selected {
+        let mut loaded = BTreeMap::new();
+        let pairs = vulnerability
+            .vulnerable
+            .iter()
+            .zip(vulnerability.mitigation.instructions);
+        for (vulnerable, secure) in pairs {
+            let (v, s) = match (
+                find(vulnerability, vulnerable, &mut loaded),
+                find(vulnerability, secure, &mut loaded),
+            ) {
+                (Ok(v), Ok(s)) => (v, s),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("{}: {}", vulnerability.program, e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let rows = diff(&v, &s);
+            if json {
+                report.push(pair_json(vulnerability, vulnerable, secure, &rows));
+            } else {
+                print_pair(vulnerability, vulnerable, secure, &rows, all);
+            }
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    }
+    ExitCode::SUCCESS
+}
+
+/// `name` (or `crate::name`) from the program it belongs to, read once.
+fn find(
+    vulnerability: &Vulnerability,
+    name: &str,
+    loaded: &mut BTreeMap<PathBuf, Vec<Instruction>>,
+) -> Result<Instruction, String> {
+    let dir = root().join("programs").join(vulnerability.program);
+    let (path, name) = match (vulnerability.framework, name.split_once("::")) {
+        (Framework::Anchor, Some((krate, name))) => {
+            (dir.join(krate.replace('_', "-")).join("src"), name)
+        }
+        (Framework::Anchor, None) => (dir.join("src"), name),
+        (Framework::Pinocchio | Framework::Native, _) => (
+            root()
+                .join("tests/idl")
+                .join(format!("{}.json", vulnerability.program.replace('-', "_"))),
+            name,
+        ),
+    };
+    if !loaded.contains_key(&path) {
+        let instructions = match vulnerability.framework {
+            Framework::Anchor => interface::instructions(&path)?,
+            Framework::Pinocchio | Framework::Native => from_idl(&path)?,
+        };
+        loaded.insert(path.clone(), instructions);
+    }
+    loaded[&path]
+        .iter()
+        .find(|ix| ix.name == name)
+        .cloned()
+        .ok_or_else(|| format!("no instruction {} in {}", name, path.display()))
+}
+
+/// The instructions of a hand-written IDL, with `signer` and `mut` as
+/// their only constraints.
+fn from_idl(path: &PathBuf) -> Result<Vec<Instruction>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let idl: Value = serde_json::from_str(&text)
+        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+    let flag = |account: &Value, key: &str| account[key].as_bool().unwrap_or(false);
+    Ok(idl["instructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|ix| Instruction {
+            name: ix["name"].as_str().unwrap_or_default().to_string(),
+            args: ix["args"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|arg| {
+                    (
+                        arg["name"].as_str().unwrap_or_default().to_string(),
+                        idl_type(&arg["type"]),
+                    )
+                })
+                .collect(),
+            accounts: ix["accounts"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|account| InstructionAccount {
+                    name: account["name"].as_str().unwrap_or_default().to_string(),
+                    ty: "AccountInfo".to_string(),
+                    constraints: [("writable", "mut"), ("signer", "signer")]
+                        .into_iter()
+                        .filter(|(key, _)| flag(account, key))
+                        .map(|(_, constraint)| constraint.to_string())
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+// An IDL type as it would be written in Rust
+fn idl_type(ty: &Value) -> String {
+    if let Some(name) = ty.as_str() {
+        return match name {
+            "pubkey" | "publicKey" => "Pubkey".to_string(),
+            "string" => "String".to_string(),
+            other => other.to_string(),
+        };
+    }
+    if let Some(inner) = ty.get("vec") {
+        return format!("Vec<{}>", idl_type(inner));
+    }
+    if let Some(inner) = ty.get("option") {
+        return format!("Option<{}>", idl_type(inner));
+    }
+    if let Some([inner, len]) = ty.get("array").and_then(Value::as_array).map(Vec::as_slice) {
+        return format!("[{}; {}]", idl_type(inner), len);
+    }
+    match ty.pointer("/defined/name").or_else(|| ty.get("defined")) {
+        Some(Value::String(name)) => name.clone(),
+        _ => ty.to_string(),
+    }
+}
+
+// What a constraint is matched on between the sides: `seeds = ...` is the
+// same constraint with a new value, but two `constraint = ...` are only the
+// same if the check is, whatever error it raises
+fn key(constraint: &str) -> String {
+    let head = constraint
+        .split_once('=')
+        .map_or(constraint, |(head, _)| head)
+        .trim();
+    match head {
+        "constraint" | "has_one" => constraint
+            .split_once(" @ ")
+            .map_or(constraint, |(check, _)| check)
+            .to_string(),
+        _ => head.to_string(),
+    }
+}
+
+fn diff(vulnerable: &Instruction, secure: &Instruction) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let change = |v: Option<&String>, s: Option<&String>| match (v, s) {
+        (Some(v), Some(s)) if v == s => Change::Same,
+        (Some(_), Some(_)) => Change::Changed,
+        (Some(_), None) => Change::Removed,
+        _ => Change::Added,
+    };
+
+    // Every vulnerable instruction takes the program's cluster record, to
+    // refuse mainnet-beta; it has nothing to do with the fix
+    let mut names: Vec<&str> = vulnerable
+        .accounts
+        .iter()
+        .map(|a| a.name.as_str())
+        .filter(|name| *name != "cluster")
+        .collect();
+    for account in &secure.accounts {
+        if !names.contains(&account.name.as_str()) {
+            names.push(&account.name);
+        }
+    }
+    for name in names {
+        let v = vulnerable.accounts.iter().find(|a| a.name == name);
+        let s = secure.accounts.iter().find(|a| a.name == name);
+        let (Some(v), Some(s)) = (v, s) else {
+            let describe = |a: Option<&InstructionAccount>| {
+                a.map(|a| {
+                    std::iter::once(a.ty.clone())
+                        .chain(a.constraints.iter().cloned())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                })
+            };
+            let (dv, ds) = (describe(v), describe(s));
+            rows.push(Row {
+                kind: "account",
+                name: name.to_string(),
+                what: "account".to_string(),
+                change: change(dv.as_ref(), ds.as_ref()),
+                vulnerable: dv.unwrap_or_default(),
+                secure: ds.unwrap_or_default(),
+            });
+            continue;
+        };
+
+        rows.push(Row {
+            kind: "account",
+            name: name.to_string(),
+            what: "type".to_string(),
+            change: change(Some(&v.ty), Some(&s.ty)),
+            vulnerable: v.ty.clone(),
+            secure: s.ty.clone(),
+        });
+        let mut keys: Vec<String> = v.constraints.iter().map(|c| key(c)).collect();
+        for constraint in &s.constraints {
+            if !keys.contains(&key(constraint)) {
+                keys.push(key(constraint));
+            }
+        }
+        for k in keys {
+            let find = |a: &InstructionAccount| a.constraints.iter().find(|c| key(c) == k).cloned();
+            let (cv, cs) = (find(v), find(s));
+            rows.push(Row {
+                kind: "account",
+                name: name.to_string(),
+                what: k
+                    .split_once(' ')
+                    .map_or(k.as_str(), |(head, _)| head)
+                    .to_string(),
+                change: change(cv.as_ref(), cs.as_ref()),
+                vulnerable: cv.unwrap_or_default(),
+                secure: cs.unwrap_or_default(),
+            });
+        }
+    }
+
+    let mut args: Vec<&str> = vulnerable.args.iter().map(|(n, _)| n.as_str()).collect();
+    for (name, _) in &secure.args {
+        if !args.contains(&name.as_str()) {
+            args.push(name);
+        }
+    }
+    for name in args {
+        let ty = |ix: &Instruction| {
+            ix.args
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, t)| t.clone())
+        };
+        let (tv, ts) = (ty(vulnerable), ty(secure));
+        rows.push(Row {
+            kind: "arg",
+            name: name.to_string(),
+            what: "arg".to_string(),
+            change: change(tv.as_ref(), ts.as_ref()),
+            vulnerable: tv.unwrap_or_default(),
+            secure: ts.unwrap_or_default(),
+        });
+    }
+    rows
+}
+
+fn print_pair(
+    vulnerability: &Vulnerability,
+    vulnerable: &str,
+    secure: &str,
+    rows: &[Row],
+    all: bool,
+) {
+    println!("{}: {} -> {}", vulnerability.program, vulnerable, secure);
+    let shown: Vec<&Row> = rows
+        .iter()
+        .filter(|row| all || row.change != Change::Same)
+        .collect();
+    if shown.is_empty() {
+        println!(
+            "  same accounts, constraints and arguments: the fix is in the handler ({})\n",
+            vulnerability.sources.join(", ")
+        );
+        return;
+    }
+
+    let width = |header: &str, cell: fn(&Row) -> &str| {
+        shown
+            .iter()
+            .map(|row| cell(row).len())
+            .chain([header.len()])
+            .max()
+            .unwrap()
+    };
+    let name = width("NAME", |row| &row.name);
+    let what = width("WHAT", |row| &row.what);
+    let before = width("VULNERABLE", |row| &row.vulnerable);
+    println!(
+        "  {:<7} {:<name$} {:<what$} {:<7} {:<before$}  SECURE",
+        "KIND", "NAME", "WHAT", "CHANGE", "VULNERABLE"
+    );
+    for row in shown {
+        let line = format!(
+            "  {:<7} {:<name$} {:<what$} {:<7} {:<before$}  {}",
+            row.kind,
+            row.name,
+            row.what,
+            row.change.name(),
+            row.vulnerable,
+            row.secure
+        );
+        println!("{}", line.trim_end());
+    }
+    println!();
+}
+
+fn pair_json(vulnerability: &Vulnerability, vulnerable: &str, secure: &str, rows: &[Row]) -> Value {
+    json!({
+        "program": vulnerability.program,
+        "vulnerable": vulnerable,
+        "secure": secure,
+        "rows": rows.iter().map(|row| json!({
+            "kind": row.kind,
+            "name": row.name,
+            "what": row.what,
+            "change": row.change.name(),
+            "vulnerable": row.vulnerable,
+            "secure": row.secure,
+        })).collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,53 @@
+//! `cargo xtask lint [--all]`: static checks for Anchor anti-patterns
+//!
+//! Runs `solsec_lint` over the Anchor programs. The vulnerable modules are
+//! supposed to be flagged, so by default only the findings anywhere else
+//! are printed, and any of them fail the task; `--all` prints every
+//! finding.
+
+use std::process::ExitCode;
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask lint [--all]";
+
+pub fn lint(all: bool) -> ExitCode {
+    let findings = match solsec_lint::lint_workspace(&root()) {
+        Ok(findings) => findings,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let misplaced = findings.misplaced();
+
+    if all {
+        for finding in &findings.findings {
+            println!("{}", finding);
+        }
+        for module in findings.unflagged_modules() {
+            println!("{}: nothing flagged", module.display());
+        }
+    } else {
+        for finding in &misplaced {
+            println!("{}", finding);
+        }
+    }
+
+    eprintln!(
+        "{} findings, {} in the {} vulnerable modules, {} elsewhere",
+        findings.findings.len(),
+        findings.findings.len() - misplaced.len(),
+        findings.vulnerable_modules.len(),
+        misplaced.len()
+    );
+    if misplaced.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!(
+            "fix them, or mark a reviewed false positive with \
+             `// solsec-lint: allow(<lint>) <reason>` above it"
+        );
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,95 @@
+//! `cargo xtask list`: the vulnerability catalog
+//!
+//! A table for people, or with `--json` an array with one object per
+//! program, for scripts that pick targets or link to sources. Each object
+//! carries the entry's CVSS v3.1 vector and score and its ids in
+//! sealevel-attacks, SWC and CWE, so audit tooling can match its findings
+//! to the reproducer here.
+
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+use solsec_registry::{Vulnerability, CATALOG};
+
+pub const USAGE: &str = "cargo xtask list [--json]";
+
+fn to_json(v: &Vulnerability) -> Value {
+    json!({
+        "title": v.title,
+        "program": v.program,
+        "suite": v.suite,
+        "path": format!("programs/{}", v.program),
+        "class": v.class.name(),
+        "severity": v.severity.name(),
+        "score": {
+            "vector": v.score.vector(),
+            "base": v.score.base_score(),
+            "rating": v.score.rating().map(|r| r.name()),
+        },
+        "weaknesses": v.weaknesses.iter().map(|w| json!({
+            "taxonomy": w.taxonomy(),
+            "id": w.id(),
+            "url": w.url(),
+        })).collect::<Vec<_>>(),
+        "framework": v.framework.name(),
+        "vulnerable": v.vulnerable,
+        "secure": v.mitigation.instructions,
+        "sources": v.sources,
+        "readme": v.readme(),
+        "mitigation": v.mitigation.summary,
+    })
+}
+
+pub fn list(as_json: bool) -> ExitCode {
+    if as_json {
+        let all: Vec<Value> = CATALOG.iter().map(to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&all).unwrap());
+        return ExitCode::SUCCESS;
+    }
+
+    let rows: Vec<[String; 7]> = CATALOG
+        .iter()
+        .map(|v| {
+            [
+                v.program.to_string(),
+                v.class.name().to_string(),
+                v.severity.name().to_string(),
+                format!("{:.1}", v.score.base_score()),
+                v.vulnerable.join(", "),
+                v.mitigation.instructions.join(", "),
+                v.sources.join(", "),
+            ]
+        })
+        .collect();
+    let header = [
+        "PROGRAM",
+        "CLASS",
+        "SEVERITY",
+        "SCORE",
+        "VULNERABLE",
+        "SECURE",
+        "SOURCES",
+    ];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([header[i].len()])
+                .max()
+                .unwrap()
+        })
+        .collect();
+    let print = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print(&header);
+    for row in &rows {
+        print(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,29 @@
+//! `cargo xtask live-demo [--cluster local|devnet|URL] [PROGRAM]...`: the
+//! exploit demos on a real validator
+//!
+//! Hands off to the tests crate's `live-demo` binary (see
+//! `security_tests::live`), which starts a `solana-test-validator` for the
+//! run or deploys to the named cluster, plays each demo there and in
+//! LiteSVM, and fails where the two disagree.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask live-demo [--cluster local|devnet|URL] [--keypair FILE] \
+                         [--port PORT] [--seed N] [--close-programs] [PROGRAM]...";
+
+pub fn live_demo(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "live-demo", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}
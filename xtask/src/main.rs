@@ -0,0 +1,255 @@
+//! Repository tasks, run as `cargo xtask <task>` from anywhere in the tree
+//!
+//! - `fuzz-regress [TARGET]` replays the stored corpus of every fuzz target
+//!   (or just `TARGET`) as plain deterministic tests: the honggfuzz targets
+//!   in `trident-tests/` and the libFuzzer targets in `fuzz/`. Failing
+//!   honggfuzz inputs are minimized next to the original as `<name>.min`.
+//!   The tests generated from `fuzz/findings/` run along with them.
+//! - `fuzz-all [--jobs N] [--time SECS] [--budget TARGET=SECS]... [TARGET]...`
+//!   fuzzes every target (or the ones named) in parallel, one core each,
+//!   merges what they discover into the corpora and writes a findings
+//!   report to `target/fuzz-all/report.md`. See [`fuzz_all`].
+//! - `list [--json]` prints every vulnerability program with its class,
+//!   severity, instructions and sources, from `solsec_registry`.
+//! - `run-exploit [--format text|json] <PROGRAM>...` deploys the program in
+//!   LiteSVM, plays its scripted exploit against the vulnerable and the
+//!   secure instruction, and narrates both: balances, program logs and
+//!   errors. `--format json` prints the results, error codes, balance
+//!   deltas and compute units for scripts instead.
+//! - `tutorial [--no-pause] <PROGRAM>` is `run-exploit` for a classroom:
+//!   it prints the vulnerable code, then waits for Enter before each
+//!   transaction of the attack, and again for the secure code. See
+//!   [`tutorial`].
+//! - `export-exploits [--out DIR] [PROGRAM]...` writes each demo's signed
+//!   transactions in wire format, with the accounts they start from, to
+//!   `target/exports/` for replay in other tools. See [`export_exploits`].
+//! - `idl-diff [--all] [PROGRAM]...` lines up each vulnerable instruction
+//!   with its secure twin and prints the accounts, constraints and
+//!   arguments the fix changes. See [`idl_diff`].
+//! - `docs [--check] [PROGRAM]...` renders a writeup per program into
+//!   `generated-docs/` from the registry, the README, the demo's captured
+//!   logs and the mitigation diff; `--check` fails if one is stale. See
+//!   [`docs`].
+//! - `clients [--check] [PROGRAM]...` generates a Rust client crate per
+//!   program with an IDL into `clients/`: instruction builders, PDA finders
+//!   and account decoders, with the IDL's Codama node tree beside it;
+//!   `--check` fails if one is stale. See [`clients`].
+//! - `report [--no-run] [PROGRAM]...` runs the exploit suites and writes a
+//!   Markdown and a JSON report per vulnerability to
+//!   `target/exploit-report/`. See [`report`].
+//! - `lint [--all]` flags unconstrained accounts, unsigned authorities,
+//!   `init_if_needed` and unchecked arithmetic in the Anchor programs, and
+//!   fails if any turn up outside the vulnerable modules. See [`lint`].
+//! - `scan-prefund --program ID --seeds TEMPLATE --user PUBKEY...` derives
+//!   each user's PDA and asks an RPC node which are pre-funded but
+//!   unallocated, i.e. griefed, with the lamports stranded and how the
+//!   program can still create them. See [`scan_prefund`].
+//! - `grief-cost --seeds TEMPLATE --users N --space BYTES` prices griefing
+//!   every user's PDA for the attacker and recovering for the defender,
+//!   with and without a nonce in the seeds. See [`grief_cost`].
+//! - `bench-cu [--history FILE] [--max-increase PCT] [PROGRAM]...` calls
+//!   every benched instruction in LiteSVM, appends its compute units and
+//!   account data to a history and fails on growth since the last commit.
+//!   See [`bench_cu`].
+//! - `live-demo [--cluster local|devnet|URL] [PROGRAM]...` plays the
+//!   exploit demos on a `solana-test-validator` it starts and stops (or on
+//!   devnet, deploying and funding from a keypair) next to LiteSVM, and
+//!   fails where a result or account differs. See [`live_demo`].
+//! - `verify-deploy [--cluster local|devnet|URL] NAME...` checks that each
+//!   program's deployed bytecode is its local `.so`, padding aside, and
+//!   `--demo` shows an impostor at a "secure" address it catches. See
+//!   [`verify_deploy`].
+
+mod bench_cu;
+mod clients;
+mod docs;
+mod export_exploits;
+mod fuzz_all;
+mod grief_cost;
+mod idl_diff;
+mod lint;
+mod list;
+mod live_demo;
+mod report;
+mod run_exploit;
+mod scan_prefund;
+mod tutorial;
+mod verify_deploy;
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "usage: cargo xtask fuzz-regress [TARGET]";
+
+/// Where a fuzzer keeps its targets and how their replays are selected
+struct Fuzzer {
+    name: &'static str,
+    /// Workspace manifest, relative to the repository root
+    manifest: &'static str,
+    /// Directory with one corpus directory per target
+    corpus: &'static str,
+    /// `cargo test` arguments replaying `target`, or every target for `None`
+    test_args: fn(Option<&str>) -> Vec<String>,
+    /// Builds every target for the engine
+    build: fn() -> Command,
+    /// Fuzzes `target` from `corpus` for some seconds, writing new inputs
+    /// to `out`
+    fuzz: fn(target: &str, corpus: &Path, out: &Path, seconds: u64) -> Command,
+}
+
+const FUZZERS: &[Fuzzer] = &[
+    Fuzzer {
+        name: "honggfuzz",
+        manifest: "trident-tests/Cargo.toml",
+        corpus: "trident-tests/fuzz_targets/corpus",
+        test_args: |target| {
+            let mut args = Vec::new();
+            if let Some(target) = target {
+                args.extend(["--bin".to_string(), format!("fuzz_{}", target)]);
+            }
+            // Recorded counterexamples and findings are part of the stored
+            // inputs too
+            args.extend(
+                ["--", "corpus_replays", "recorded_regressions", "finding_"].map(String::from),
+            );
+            args
+        },
+        build: fuzz_all::hfuzz_build,
+        fuzz: fuzz_all::hfuzz_run,
+    },
+    Fuzzer {
+        name: "libFuzzer",
+        manifest: "fuzz/Cargo.toml",
+        corpus: "fuzz/corpus",
+        test_args: |target| {
+            let (corpus, findings) = match target {
+                Some(target) => (
+                    format!("{}_corpus_replays", target),
+                    format!("{}_findings", target),
+                ),
+                None => ("corpus_replays".to_string(), "_findings".to_string()),
+            };
+            vec!["--".to_string(), corpus, findings]
+        },
+        build: fuzz_all::libfuzzer_build,
+        fuzz: fuzz_all::libfuzzer_run,
+    },
+];
+
+fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the repository root")
+        .to_path_buf()
+}
+
+fn targets(fuzzer: &Fuzzer) -> Vec<String> {
+    let mut targets: Vec<String> = std::fs::read_dir(root().join(fuzzer.corpus))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    targets.sort();
+    targets
+}
+
+fn fuzz_regress(target: Option<&str>) -> ExitCode {
+    let selected: Vec<&Fuzzer> = match target {
+        Some(target) => FUZZERS
+            .iter()
+            .filter(|f| targets(f).iter().any(|t| t == target))
+            .collect(),
+        None => FUZZERS.iter().collect(),
+    };
+    if selected.is_empty() {
+        eprintln!(
+            "unknown fuzz target {}; targets with a corpus:",
+            target.unwrap()
+        );
+        for fuzzer in FUZZERS {
+            eprintln!("  {}: {}", fuzzer.name, targets(fuzzer).join(", "));
+        }
+        return ExitCode::FAILURE;
+    }
+
+    let mut failed = Vec::new();
+    for fuzzer in selected {
+        eprintln!(
+            "replaying {} corpus: {}",
+            fuzzer.name,
+            target.map_or_else(|| targets(fuzzer).join(", "), str::to_string)
+        );
+        let status = Command::new(env!("CARGO"))
+            .args(["test", "--no-fail-fast", "--manifest-path"])
+            .arg(root().join(fuzzer.manifest))
+            .args((fuzzer.test_args)(target))
+            .status()
+            .unwrap_or_else(|e| panic!("Failed to run cargo test: {}", e));
+        if !status.success() {
+            failed.push(fuzzer.name);
+        }
+    }
+
+    if failed.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("corpus replay failed for {}", failed.join(", "));
+        if failed.contains(&"libFuzzer") {
+            eprintln!(
+                "shrink a failing libFuzzer input with: cargo +nightly fuzz tmin <target> <file>"
+            );
+        }
+        ExitCode::FAILURE
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["fuzz-regress"] => fuzz_regress(None),
+        ["fuzz-regress", target] => fuzz_regress(Some(target)),
+        ["fuzz-all", ref rest @ ..] => fuzz_all::fuzz_all(rest),
+        ["list"] => list::list(false),
+        ["list", "--json"] => list::list(true),
+        ["run-exploit", ref rest @ ..] => run_exploit::run_exploit(rest),
+        ["tutorial", ref rest @ ..] => tutorial::tutorial(rest),
+        ["export-exploits", ref rest @ ..] => export_exploits::export_exploits(rest),
+        ["idl-diff", ref rest @ ..] => idl_diff::idl_diff(rest),
+        ["docs", ref rest @ ..] => docs::docs(rest),
+        ["clients", ref rest @ ..] => clients::clients(rest),
+        ["report", ref rest @ ..] => report::report(rest),
+        ["lint"] => lint::lint(false),
+        ["lint", "--all"] => lint::lint(true),
+        ["scan-prefund", ref rest @ ..] => scan_prefund::scan_prefund(rest),
+        ["grief-cost", ref rest @ ..] => grief_cost::grief_cost(rest),
+        ["bench-cu", ref rest @ ..] => bench_cu::bench_cu(rest),
+        ["live-demo", ref rest @ ..] => live_demo::live_demo(rest),
+        ["verify-deploy", ref rest @ ..] => verify_deploy::verify_deploy(rest),
+        _ => {
+            eprintln!(
+                "{}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}\n       {}",
+                USAGE,
+                fuzz_all::USAGE,
+                list::USAGE,
+                run_exploit::USAGE,
+                tutorial::USAGE,
+                export_exploits::USAGE,
+                idl_diff::USAGE,
+                docs::USAGE,
+                clients::USAGE,
+                report::USAGE,
+                lint::USAGE,
+                scan_prefund::USAGE,
+                grief_cost::USAGE,
+                bench_cu::USAGE,
+                live_demo::USAGE,
+                verify_deploy::USAGE
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
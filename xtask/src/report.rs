@@ -0,0 +1,448 @@
+//! `cargo xtask report`: an exploit report per vulnerability
+//!
+//! Runs the test suite of every catalogued program (or the ones named) and
+//! writes `target/exploit-report/<program>.md` and `<program>.json` from the
+//! catalog entry and what the suite left behind:
+//!
+//! - the result of each test;
+//! - the golden snapshot of each exploit transaction under
+//!   `tests/golden/<suite>/`: its outcome, compute units and logs;
+//! - the secure-vs-vulnerable compute unit comparisons the suite recorded;
+//! - a diff of each vulnerable source file against its secure counterpart.
+//!
+//! `index.md` links the reports. With `--no-run` the suites are not run and
+//! the reports come from the snapshots and comparisons already on disk.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+
+use serde_json::{json, Value};
+use solsec_registry::{Vulnerability, CATALOG};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask report [--no-run] [PROGRAM]...";
+
+/// One line of `cargo test` output
+struct TestResult {
+    name: String,
+    /// `ok`, `FAILED` or `ignored`
+    outcome: String,
+}
+
+/// A `tests/golden/<suite>/<case>.snap` file
+struct Snapshot {
+    case: String,
+    status: String,
+    compute_units: u64,
+    logs: Vec<String>,
+}
+
+/// A row of the compute unit summary
+struct CuRow {
+    name: String,
+    vulnerable: u64,
+    secure: u64,
+}
+
+struct SourceDiff {
+    vulnerable: &'static str,
+    secure: &'static str,
+    diff: String,
+}
+
+struct Report {
+    vulnerability: &'static Vulnerability,
+    /// `None` with `--no-run`
+    tests: Option<Vec<TestResult>>,
+    snapshots: Vec<Snapshot>,
+    compute_units: Vec<CuRow>,
+    diffs: Vec<SourceDiff>,
+}
+
+impl Report {
+    fn passed(&self) -> bool {
+        self.tests
+            .as_ref()
+            .is_none_or(|tests| tests.iter().all(|t| t.outcome != "FAILED"))
+    }
+}
+
+pub fn report(args: &[&str]) -> ExitCode {
+    let run = !args.contains(&"--no-run");
+    let names: Vec<&str> = args.iter().copied().filter(|a| *a != "--no-run").collect();
+    if let Some(unknown) = names
+        .iter()
+        .find(|n| CATALOG.iter().all(|v| v.program != **n))
+    {
+        eprintln!("unknown program {}; see cargo xtask list", unknown);
+        return ExitCode::FAILURE;
+    }
+    let selected = CATALOG
+        .iter()
+        .filter(|v| names.is_empty() || names.contains(&v.program));
+
+    let dir = root().join("target/exploit-report");
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("failed to create {}: {}", dir.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    let mut reports = Vec::new();
+    for vulnerability in selected {
+        let tests = run.then(|| run_suite(vulnerability.suite));
+        let report = Report {
+            vulnerability,
+            tests,
+            snapshots: snapshots(vulnerability.suite),
+            compute_units: compute_units(vulnerability.suite),
+            diffs: diffs(vulnerability),
+        };
+        let written = std::fs::write(
+            dir.join(format!("{}.md", vulnerability.program)),
+            markdown(&report),
+        )
+        .and_then(|()| {
+            std::fs::write(
+                dir.join(format!("{}.json", vulnerability.program)),
+                serde_json::to_string_pretty(&to_json(&report)).unwrap(),
+            )
+        });
+        if let Err(e) = written {
+            eprintln!("failed to write {} report: {}", vulnerability.program, e);
+            return ExitCode::FAILURE;
+        }
+        reports.push(report);
+    }
+
+    if let Err(e) = std::fs::write(dir.join("index.md"), index(&reports)) {
+        eprintln!("failed to write index: {}", e);
+        return ExitCode::FAILURE;
+    }
+    eprintln!("reports written to {}", dir.display());
+
+    let failed: Vec<&str> = reports
+        .iter()
+        .filter(|r| !r.passed())
+        .map(|r| r.vulnerability.suite)
+        .collect();
+    if failed.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("failing suites: {}", failed.join(", "));
+        ExitCode::FAILURE
+    }
+}
+
+fn run_suite(suite: &str) -> Vec<TestResult> {
+    eprintln!("running suite {}", suite);
+    let output = Command::new(env!("CARGO"))
+        .args(["test", "--no-fail-fast", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--test", suite])
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run cargo test: {}", e));
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, outcome) = line.strip_prefix("test ")?.split_once(" ... ")?;
+            Some(TestResult {
+                name: name.rsplit("::").next().unwrap_or(name).to_string(),
+                outcome: outcome.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn snapshots(suite: &str) -> Vec<Snapshot> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(root().join("tests/golden").join(suite))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "snap"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| parse_snapshot(path))
+        .collect()
+}
+
+// The format `security_tests::golden::TxSnapshot::render` writes
+fn parse_snapshot(path: &Path) -> Option<Snapshot> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let mut lines = text.lines();
+    let status = lines.next()?.strip_prefix("status: ")?.to_string();
+    let compute_units = lines
+        .next()?
+        .strip_prefix("compute_units: ")?
+        .parse()
+        .ok()?;
+    Some(Snapshot {
+        case: path.file_stem()?.to_string_lossy().into_owned(),
+        status,
+        compute_units,
+        logs: lines
+            .skip(1)
+            .map(|l| l.strip_prefix("  ").unwrap_or(l).to_string())
+            .collect(),
+    })
+}
+
+// Rows `security_tests::compute::assert_cu_overhead` leaves under the tests
+// crate's target dir, one file per comparison named `<suite>__<case>`
+fn compute_units(suite: &str) -> Vec<CuRow> {
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root().join("tests/target"));
+    let prefix = format!("{}__", suite);
+    let mut rows: Vec<CuRow> = std::fs::read_dir(target.join("compute-units/rows"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+                .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+                .filter_map(|row| parse_cu_row(&row))
+                .collect()
+        })
+        .unwrap_or_default();
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    rows
+}
+
+// `| name | vulnerable | secure | overhead | ratio |`
+fn parse_cu_row(row: &str) -> Option<CuRow> {
+    let cells: Vec<&str> = row
+        .trim()
+        .trim_matches('|')
+        .split('|')
+        .map(str::trim)
+        .collect();
+    match cells[..] {
+        [name, vulnerable, secure, ..] => Some(CuRow {
+            name: name.to_string(),
+            vulnerable: vulnerable.parse().ok()?,
+            secure: secure.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+fn diffs(vulnerability: &Vulnerability) -> Vec<SourceDiff> {
+    let sources = vulnerability.sources;
+    if sources.len() < 2 {
+        return Vec::new();
+    }
+    let (vulnerable, secure) = sources.split_at(sources.len() / 2);
+    vulnerable
+        .iter()
+        .zip(secure)
+        .map(|(&vulnerable, &secure)| {
+            // Exits 1 when the files differ, which they always should
+            let output = Command::new("git")
+                .args(["diff", "--no-index", "--no-color", "--", vulnerable, secure])
+                .current_dir(root())
+                .output()
+                .unwrap_or_else(|e| panic!("Failed to run git diff: {}", e));
+            SourceDiff {
+                vulnerable,
+                secure,
+                diff: String::from_utf8_lossy(&output.stdout).into_owned(),
+            }
+        })
+        .collect()
+}
+
+fn markdown(report: &Report) -> String {
+    let v = report.vulnerability;
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", v.title);
+    let _ = writeln!(
+        out,
+        "- Program: [`programs/{}`](../../programs/{}) ({})",
+        v.program,
+        v.program,
+        v.framework.name()
+    );
+    let _ = writeln!(out, "- Class: {}", v.class.name());
+    let _ = writeln!(out, "- Severity: {}", v.severity.name());
+    let _ = writeln!(out, "- Vulnerable: {}", code_list(v.vulnerable));
+    let _ = writeln!(out, "- Secure: {}", code_list(v.mitigation.instructions));
+    let _ = writeln!(out, "- Mitigation: {}", v.mitigation.summary);
+    let _ = writeln!(
+        out,
+        "- Description: [{}](../../{})\n",
+        v.readme(),
+        v.readme()
+    );
+
+    let _ = writeln!(out, "## Tests\n");
+    match &report.tests {
+        None => {
+            let _ = writeln!(out, "Not run (`--no-run`).\n");
+        }
+        Some(tests) if tests.is_empty() => {
+            let _ = writeln!(out, "The suite `{}` did not run any tests.\n", v.suite);
+        }
+        Some(tests) => {
+            let _ = writeln!(out, "| Test | Shows | Result |\n|------|-------|--------|");
+            for test in tests {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} |",
+                    test.name,
+                    shows(v, &test.name),
+                    test.outcome
+                );
+            }
+            out.push('\n');
+        }
+    }
+
+    let _ = writeln!(out, "## Exploit transactions\n");
+    if report.snapshots.is_empty() {
+        let _ = writeln!(
+            out,
+            "No golden snapshots under `tests/golden/{}/`.\n",
+            v.suite
+        );
+    }
+    for snapshot in &report.snapshots {
+        let _ = writeln!(out, "### `{}`\n", snapshot.case);
+        let _ = writeln!(out, "- Status: `{}`", snapshot.status);
+        let _ = writeln!(out, "- Compute units: {}\n", snapshot.compute_units);
+        let _ = writeln!(out, "```text");
+        for log in &snapshot.logs {
+            let _ = writeln!(out, "{}", log);
+        }
+        let _ = writeln!(out, "```\n");
+    }
+
+    if !report.compute_units.is_empty() {
+        let _ = writeln!(out, "## Compute units\n");
+        let _ = writeln!(
+            out,
+            "| Instruction | Vulnerable CU | Secure CU | Overhead |\n\
+             |-------------|---------------|-----------|----------|"
+        );
+        for row in &report.compute_units {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {:+} |",
+                row.name,
+                row.vulnerable,
+                row.secure,
+                row.secure as i64 - row.vulnerable as i64
+            );
+        }
+        out.push('\n');
+    }
+
+    let _ = writeln!(out, "## Mitigation diff\n");
+    if report.diffs.is_empty() {
+        let _ = writeln!(out, "Both versions live in {}.\n", code_list(v.sources));
+    }
+    for diff in &report.diffs {
+        let _ = writeln!(out, "`{}` -> `{}`\n", diff.vulnerable, diff.secure);
+        let _ = writeln!(out, "```diff\n{}```\n", diff.diff);
+    }
+    out
+}
+
+// What the registry says a test demonstrates
+fn shows(v: &Vulnerability, test: &str) -> &'static str {
+    let coverage = v.coverage;
+    [
+        ("exploit", coverage.exploit),
+        ("rejects", coverage.rejects),
+        ("accepts", coverage.accepts),
+    ]
+    .into_iter()
+    .find(|(_, tests)| tests.contains(&test))
+    .map_or("", |(kind, _)| kind)
+}
+
+fn code_list(items: &[&str]) -> String {
+    items
+        .iter()
+        .map(|i| format!("`{}`", i))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn to_json(report: &Report) -> Value {
+    let v = report.vulnerability;
+    json!({
+        "title": v.title,
+        "program": v.program,
+        "suite": v.suite,
+        "class": v.class.name(),
+        "severity": v.severity.name(),
+        "framework": v.framework.name(),
+        "vulnerable": v.vulnerable,
+        "secure": v.mitigation.instructions,
+        "mitigation": v.mitigation.summary,
+        "readme": v.readme(),
+        "tests": report.tests.as_ref().map(|tests| {
+            tests
+                .iter()
+                .map(|t| json!({ "name": t.name, "shows": shows(v, &t.name), "outcome": t.outcome }))
+                .collect::<Vec<_>>()
+        }),
+        "transactions": report
+            .snapshots
+            .iter()
+            .map(|s| json!({
+                "case": s.case,
+                "status": s.status,
+                "compute_units": s.compute_units,
+                "logs": s.logs,
+            }))
+            .collect::<Vec<_>>(),
+        "compute_units": report
+            .compute_units
+            .iter()
+            .map(|r| json!({ "name": r.name, "vulnerable": r.vulnerable, "secure": r.secure }))
+            .collect::<Vec<_>>(),
+        "mitigation_diff": report
+            .diffs
+            .iter()
+            .map(|d| json!({ "vulnerable": d.vulnerable, "secure": d.secure, "diff": d.diff }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn index(reports: &[Report]) -> String {
+    let mut out = String::from(
+        "# Exploit reports\n\n\
+         | Vulnerability | Severity | Tests | Snapshots | Report |\n\
+         |---------------|----------|-------|-----------|--------|\n",
+    );
+    for report in reports {
+        let v = report.vulnerability;
+        let tests = match &report.tests {
+            None => "not run".to_string(),
+            Some(tests) => format!(
+                "{}/{} passed",
+                tests.iter().filter(|t| t.outcome == "ok").count(),
+                tests.len()
+            ),
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | [md]({p}.md), [json]({p}.json) |",
+            v.title,
+            v.severity.name(),
+            tests,
+            report.snapshots.len(),
+            p = v.program
+        );
+    }
+    out
+}
@@ -0,0 +1,27 @@
+//! `cargo xtask run-exploit [--format text|json] <PROGRAM>...`: a narrated
+//! exploit demo
+//!
+//! The scripts need LiteSVM and the test helpers, so this hands off to the
+//! tests crate's `run-exploit` binary (see `security_tests::demo`) rather
+//! than linking them in.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask run-exploit [--format text|json] <PROGRAM>... | --all";
+
+pub fn run_exploit(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "run-exploit", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}
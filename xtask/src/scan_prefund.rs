@@ -0,0 +1,530 @@
+//! `cargo xtask scan-prefund`: PDAs griefed on a live cluster
+//!
+//! The account-griefing lesson on real protocols: derive a program's PDA
+//! for each user from a seed template, fetch them all over RPC and report
+//! the ones that hold lamports but were never allocated. A program that
+//! creates the account with `system_program::create_account` can never
+//! create those; they stay blocked until it switches to a path that tops
+//! up, allocates and assigns instead.
+//!
+//! A seed template is a comma-separated list of components:
+//! - `{user}`: the user's public key
+//! - `u8:N`, `u16:N`, `u32:N`, `u64:N`: a little-endian integer
+//! - `0x<HEX>`: raw bytes, `0x` alone for an empty seed
+//! - `pubkey:<BASE58>`: a fixed public key
+//! - anything else: its UTF-8 bytes
+//!
+//! so account-griefing's stake PDA is `stake,{user}`. Users come from
+//! `--user` or from `--users FILE`, one public key per line (`#` starts a
+//! comment). The task fails if any PDA is griefed, so it can run from a
+//! monitor.
+
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+
+pub const USAGE: &str = "cargo xtask scan-prefund --program ID --seeds TEMPLATE \
+                         (--user PUBKEY | --users FILE)... [--space BYTES] [--rpc URL] [--json]";
+
+const DEFAULT_RPC: &str = "http://127.0.0.1:8899";
+
+/// `getMultipleAccounts` takes at most this many keys
+const BATCH: usize = 100;
+
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+pub(crate) enum Seed {
+    User,
+    Bytes(Vec<u8>),
+}
+
+struct Options {
+    program: Pubkey,
+    seeds: Vec<Seed>,
+    users: Vec<Pubkey>,
+    /// Bytes the program allocates, to price the top-up
+    space: Option<u64>,
+    rpc: String,
+    json: bool,
+}
+
+impl Options {
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut program = None;
+        let mut seeds = None;
+        let mut options = Options {
+            program: Pubkey::default(),
+            seeds: Vec::new(),
+            users: Vec::new(),
+            space: None,
+            rpc: DEFAULT_RPC.to_string(),
+            json: false,
+        };
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .copied()
+                    .ok_or_else(|| format!("{} needs a value", arg))
+            };
+            match arg {
+                "--program" => program = Some(pubkey(value()?)?),
+                "--seeds" => seeds = Some(parse_seeds(value()?)?),
+                "--user" => options.users.push(pubkey(value()?)?),
+                "--users" => {
+                    let file = value()?;
+                    let text = std::fs::read_to_string(file)
+                        .map_err(|e| format!("failed to read {}: {}", file, e))?;
+                    for line in text.lines() {
+                        let line = line.split('#').next().unwrap().trim();
+                        if !line.is_empty() {
+                            options.users.push(pubkey(line)?);
+                        }
+                    }
+                }
+                "--space" => {
+                    options.space = Some(value()?.parse().map_err(|_| "--space takes bytes")?)
+                }
+                "--rpc" => options.rpc = value()?.to_string(),
+                "--json" => options.json = true,
+                other => return Err(format!("unknown option {}", other)),
+            }
+        }
+        options.program = program.ok_or("--program is required")?;
+        options.seeds = seeds.ok_or("--seeds is required")?;
+        if options.users.is_empty() {
+            return Err("no users given; pass --user or --users".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn pubkey(s: &str) -> Result<Pubkey, String> {
+    s.parse()
+        .map_err(|_| format!("{} is not a base58 public key", s))
+}
+
+pub(crate) fn parse_seeds(template: &str) -> Result<Vec<Seed>, String> {
+    let seeds = template
+        .split(',')
+        .map(|part| {
+            match part {
+                "{user}" => return Ok(Seed::User),
+                "" => return Err("empty seed in the template; write 0x for one".to_string()),
+                _ => {}
+            }
+            let bytes = match part.split_once(':') {
+                Some(("u8", n)) => n.parse::<u8>().map(|n| n.to_le_bytes().to_vec()).ok(),
+                Some(("u16", n)) => n.parse::<u16>().map(|n| n.to_le_bytes().to_vec()).ok(),
+                Some(("u32", n)) => n.parse::<u32>().map(|n| n.to_le_bytes().to_vec()).ok(),
+                Some(("u64", n)) => n.parse::<u64>().map(|n| n.to_le_bytes().to_vec()).ok(),
+                Some(("pubkey", key)) => pubkey(key).map(|k| k.to_bytes().to_vec()).ok(),
+                _ => match part.strip_prefix("0x") {
+                    Some(hex) => decode_hex(hex),
+                    None => Some(part.as_bytes().to_vec()),
+                },
+            };
+            match bytes {
+                Some(bytes) if bytes.len() <= 32 => Ok(Seed::Bytes(bytes)),
+                Some(_) => Err(format!("seed {} is longer than 32 bytes", part)),
+                None => Err(format!("seed {}: malformed value", part)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if seeds.len() > 15 {
+        return Err("a PDA takes at most 16 seeds, including the bump".to_string());
+    }
+    Ok(seeds)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    // `from_str_radix` would take a sign
+    if hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// What the cluster holds at a user's PDA
+enum State {
+    /// Never funded: the program can create it
+    Free,
+    /// Lamports on an unallocated system account: `create_account` fails
+    Griefed { lamports: u64 },
+    /// Created by the program
+    Initialized,
+    /// Allocated, or assigned to another program
+    Foreign { owner: String, data_len: usize },
+}
+
+impl State {
+    fn name(&self) -> &'static str {
+        match self {
+            State::Free => "free",
+            State::Griefed { .. } => "griefed",
+            State::Initialized => "initialized",
+            State::Foreign { .. } => "foreign",
+        }
+    }
+}
+
+struct Target {
+    user: Pubkey,
+    address: Pubkey,
+    bump: u8,
+    state: State,
+}
+
+pub fn scan_prefund(args: &[&str]) -> ExitCode {
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\nusage: {}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match scan(&options) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the scan and returns how many PDAs are griefed.
+fn scan(options: &Options) -> Result<usize, String> {
+    let mut targets: Vec<Target> = options
+        .users
+        .iter()
+        .map(|user| {
+            let seeds: Vec<&[u8]> = options
+                .seeds
+                .iter()
+                .map(|seed| match seed {
+                    Seed::User => user.as_ref(),
+                    Seed::Bytes(bytes) => bytes.as_slice(),
+                })
+                .collect();
+            let (address, bump) = Pubkey::find_program_address(&seeds, &options.program);
+            Target {
+                user: *user,
+                address,
+                bump,
+                state: State::Free,
+            }
+        })
+        .collect();
+
+    for batch in targets.chunks_mut(BATCH) {
+        let keys: Vec<String> = batch.iter().map(|t| t.address.to_string()).collect();
+        let result = rpc(
+            &options.rpc,
+            "getMultipleAccounts",
+            json!([keys, { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        let accounts = result["value"]
+            .as_array()
+            .filter(|a| a.len() == batch.len())
+            .ok_or("getMultipleAccounts: unexpected response")?;
+        for (target, account) in batch.iter_mut().zip(accounts) {
+            target.state = classify(account, &options.program)?;
+        }
+    }
+
+    let rent = match options.space {
+        Some(space) => Some(
+            rpc(
+                &options.rpc,
+                "getMinimumBalanceForRentExemption",
+                json!([space]),
+            )?
+            .as_u64()
+            .ok_or("getMinimumBalanceForRentExemption: unexpected response")?,
+        ),
+        None => None,
+    };
+    let top_up = |lamports: u64| rent.map(|rent| rent.saturating_sub(lamports));
+
+    let griefed: Vec<(&Target, u64)> = targets
+        .iter()
+        .filter_map(|t| match t.state {
+            State::Griefed { lamports } => Some((t, lamports)),
+            _ => None,
+        })
+        .collect();
+    let stranded: u64 = griefed.iter().map(|(_, lamports)| lamports).sum();
+
+    if options.json {
+        let rows: Vec<Value> = targets
+            .iter()
+            .map(|t| {
+                let mut row = json!({
+                    "user": t.user.to_string(),
+                    "address": t.address.to_string(),
+                    "bump": t.bump,
+                    "state": t.state.name(),
+                });
+                match &t.state {
+                    State::Griefed { lamports } => {
+                        row["lamports"] = json!(lamports);
+                        row["top_up"] = json!(top_up(*lamports));
+                    }
+                    State::Foreign { owner, data_len } => {
+                        row["owner"] = json!(owner);
+                        row["data_len"] = json!(data_len);
+                    }
+                    State::Free | State::Initialized => {}
+                }
+                row
+            })
+            .collect();
+        let report = json!({
+            "program": options.program.to_string(),
+            "rpc": options.rpc,
+            "scanned": targets.len(),
+            "griefed": griefed.len(),
+            "stranded_lamports": stranded,
+            "targets": rows,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return Ok(griefed.len());
+    }
+
+    println!("{:<44}  {:<44}  STATE", "USER", "PDA");
+    for t in &targets {
+        let detail = match &t.state {
+            State::Griefed { lamports } => format!("{} lamports stranded", lamports),
+            State::Foreign { owner, data_len } => {
+                format!("owned by {}, {} bytes", owner, data_len)
+            }
+            State::Free | State::Initialized => String::new(),
+        };
+        let row = format!(
+            "{:<44}  {:<44}  {:<11}  {}",
+            t.user.to_string(),
+            t.address.to_string(),
+            t.state.name(),
+            detail
+        );
+        println!("{}", row.trim_end());
+    }
+    println!(
+        "\n{} of {} PDAs griefed, {} lamports ({:.9} SOL) stranded",
+        griefed.len(),
+        targets.len(),
+        stranded,
+        stranded as f64 / 1e9
+    );
+    if !griefed.is_empty() {
+        println!(
+            "\nOnly {} can sign for these addresses, so nobody else can move the \
+             lamports. It recovers each one by creating the account without \
+             `create_account`: transfer any rent shortfall, then `allocate` and \
+             `assign` signed with the PDA seeds and bump, as Anchor's `init` \
+             does. The stranded lamports become part of the account's rent.",
+            options.program
+        );
+        for (t, lamports) in &griefed {
+            match top_up(*lamports) {
+                Some(0) => println!("  {}: allocate + assign (bump {})", t.address, t.bump),
+                Some(top_up) => println!(
+                    "  {}: transfer {} lamports, then allocate + assign (bump {})",
+                    t.address, top_up, t.bump
+                ),
+                None => println!(
+                    "  {}: top up to rent, then allocate + assign (bump {})",
+                    t.address, t.bump
+                ),
+            }
+        }
+    }
+    Ok(griefed.len())
+}
+
+fn classify(account: &Value, program: &Pubkey) -> Result<State, String> {
+    if account.is_null() {
+        return Ok(State::Free);
+    }
+    let lamports = account["lamports"]
+        .as_u64()
+        .ok_or("account without lamports")?;
+    let owner = account["owner"]
+        .as_str()
+        .ok_or("account without owner")?
+        .to_string();
+    let data_len = base64_len(account["data"][0].as_str().unwrap_or_default());
+
+    Ok(if owner == program.to_string() {
+        State::Initialized
+    } else if owner == SYSTEM_PROGRAM && data_len == 0 {
+        if lamports == 0 {
+            State::Free
+        } else {
+            State::Griefed { lamports }
+        }
+    } else {
+        State::Foreign { owner, data_len }
+    })
+}
+
+/// Decoded length of a base64 string
+fn base64_len(encoded: &str) -> usize {
+    let padding = encoded.bytes().rev().take_while(|&b| b == b'=').count();
+    (encoded.len() / 4 * 3).saturating_sub(padding)
+}
+
+fn rpc(url: &str, method: &str, params: Value) -> Result<Value, String> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let mut response: Value = ureq::post(url)
+        .send_json(request)
+        .map_err(|e| format!("{} {}: {}", url, method, e))?
+        .into_json()
+        .map_err(|e| format!("{} {}: {}", url, method, e))?;
+    if let Some(error) = response.get("error") {
+        return Err(format!("{} {}: {}", url, method, error));
+    }
+    Ok(response["result"].take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each seed's bytes, `None` for `{user}`
+    fn seeds(template: &str) -> Result<Vec<Option<Vec<u8>>>, String> {
+        Ok(parse_seeds(template)?
+            .into_iter()
+            .map(|seed| match seed {
+                Seed::User => None,
+                Seed::Bytes(bytes) => Some(bytes),
+            })
+            .collect())
+    }
+
+    fn account(owner: &str, lamports: u64, data: &str) -> Value {
+        json!({ "lamports": lamports, "owner": owner, "data": [data, "base64"] })
+    }
+
+    #[test]
+    fn test_parses_mixed_seed_kinds() {
+        let key = Pubkey::new_from_array([7; 32]);
+        let template = format!(
+            "stake,{{user}},u8:7,u16:258,u32:1,u64:2,0x0aFF,pubkey:{}",
+            key
+        );
+        assert_eq!(
+            seeds(&template).unwrap(),
+            [
+                Some(b"stake".to_vec()),
+                None,
+                Some(vec![7]),
+                Some(vec![2, 1]),
+                Some(vec![1, 0, 0, 0]),
+                Some(vec![2, 0, 0, 0, 0, 0, 0, 0]),
+                Some(vec![0x0a, 0xff]),
+                Some(vec![7; 32]),
+            ]
+        );
+        // A component that isn't a known kind is its UTF-8 bytes
+        assert_eq!(seeds("u128:1").unwrap(), [Some(b"u128:1".to_vec())]);
+        assert_eq!(seeds("0x").unwrap(), [Some(vec![])]);
+    }
+
+    #[test]
+    fn test_rejects_malformed_seeds() {
+        for (template, error) in [
+            ("", "empty seed in the template; write 0x for one"),
+            (
+                "stake,,{user}",
+                "empty seed in the template; write 0x for one",
+            ),
+            ("u8:256", "seed u8:256: malformed value"),
+            ("u16:-1", "seed u16:-1: malformed value"),
+            ("0xabc", "seed 0xabc: malformed value"),
+            ("0xzz", "seed 0xzz: malformed value"),
+            ("pubkey:nope", "seed pubkey:nope: malformed value"),
+        ] {
+            assert_eq!(seeds(template).err().as_deref(), Some(error));
+        }
+
+        let long = "a".repeat(33);
+        assert_eq!(
+            seeds(&long).err(),
+            Some(format!("seed {} is longer than 32 bytes", long))
+        );
+        assert!(seeds(&"a".repeat(32)).is_ok());
+
+        assert!(seeds(&["x"; 15].join(",")).is_ok());
+        assert_eq!(
+            seeds(&["x"; 16].join(",")).err().as_deref(),
+            Some("a PDA takes at most 16 seeds, including the bump")
+        );
+    }
+
+    #[test]
+    fn test_decodes_hex() {
+        assert_eq!(decode_hex(""), Some(vec![]));
+        assert_eq!(decode_hex("00ff10"), Some(vec![0, 255, 16]));
+        assert_eq!(decode_hex("AbCd"), Some(vec![0xab, 0xcd]));
+        assert_eq!(decode_hex("0"), None);
+        assert_eq!(decode_hex("0g"), None);
+        // A sign, which `from_str_radix` alone would accept
+        assert_eq!(decode_hex("+1"), None);
+        assert_eq!(decode_hex("éé"), None);
+    }
+
+    #[test]
+    fn test_base64_len_counts_padding() {
+        assert_eq!(base64_len(""), 0);
+        assert_eq!(base64_len("AA=="), 1);
+        assert_eq!(base64_len("AAA="), 2);
+        assert_eq!(base64_len("AAAA"), 3);
+        assert_eq!(base64_len("AAAAAA=="), 4);
+        // A token account's 165 bytes
+        assert_eq!(base64_len(&"A".repeat(220)), 165);
+        assert_eq!(base64_len("=="), 0);
+    }
+
+    #[test]
+    fn test_classifies_accounts() {
+        let program = Pubkey::new_from_array([9; 32]);
+        let state = |account: &Value| classify(account, &program).unwrap().name();
+
+        assert_eq!(state(&Value::Null), "free");
+        assert_eq!(state(&account(SYSTEM_PROGRAM, 0, "")), "free");
+        assert!(matches!(
+            classify(&account(SYSTEM_PROGRAM, 890_880, ""), &program),
+            Ok(State::Griefed { lamports: 890_880 })
+        ));
+        assert_eq!(
+            state(&account(&program.to_string(), 2_039_280, "AAAA")),
+            "initialized"
+        );
+        // Allocated without the program, or assigned elsewhere
+        assert!(matches!(
+            classify(&account(SYSTEM_PROGRAM, 890_880, "AAAA"), &program),
+            Ok(State::Foreign { data_len: 3, .. })
+        ));
+        let other = Pubkey::new_from_array([1; 32]).to_string();
+        assert_eq!(state(&account(&other, 890_880, "")), "foreign");
+
+        assert_eq!(
+            classify(&json!({ "owner": SYSTEM_PROGRAM }), &program)
+                .err()
+                .as_deref(),
+            Some("account without lamports")
+        );
+        assert_eq!(
+            classify(&json!({ "lamports": 1 }), &program)
+                .err()
+                .as_deref(),
+            Some("account without owner")
+        );
+    }
+}
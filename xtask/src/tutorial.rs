@@ -0,0 +1,28 @@
+//! `cargo xtask tutorial [--no-pause] <PROGRAM>`: an exploit demo, one step
+//! at a time
+//!
+//! Hands off to the tests crate's `tutorial` binary (see
+//! `security_tests::tutorial`), which shows the vulnerable code, pauses,
+//! sends the attack one transaction at a time with the state diff of each,
+//! then does the same with the secure code.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str = "cargo xtask tutorial [--no-pause] <PROGRAM>";
+
+pub fn tutorial(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "tutorial", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,31 @@
+//! `cargo xtask verify-deploy [--cluster local|devnet|URL] NAME...`: is the
+//! program deployed at each address the one built here?
+//!
+//! Hands off to the tests crate's `verify-deploy` binary (see
+//! `security_tests::verify`), which hashes the deployed ELF with and without
+//! its trailing zero padding, compares it with the local `.so` and shows who
+//! can still upgrade it. `--demo` plays an impostor deployed at the secure
+//! AMM's address on a `solana-test-validator`.
+
+use std::process::{Command, ExitCode};
+
+use crate::root;
+
+pub const USAGE: &str =
+    "cargo xtask verify-deploy [--cluster local|devnet|URL] [--format text|json] \
+                         NAME... | --program ID --so FILE | --demo";
+
+pub fn verify_deploy(args: &[&str]) -> ExitCode {
+    let status = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(root().join("tests/Cargo.toml"))
+        .args(["--bin", "verify-deploy", "--"])
+        .args(args)
+        .status()
+        .unwrap_or_else(|e| panic!("Failed to run cargo run: {}", e));
+    match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    }
+}